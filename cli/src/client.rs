@@ -0,0 +1,776 @@
+//! Typed client for the Raceway HTTP API.
+//!
+//! Every CLI subcommand goes through [`RacewayApiClient`], and integration
+//! tests and downstream tools can use the same typed surface instead of
+//! building URLs and parsing JSON themselves. Each method unwraps the
+//! server's `ApiResponse` envelope, so a server-side error comes back as an
+//! `Err` carrying the server's message.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use raceway_core::bundle::{BundleImport, TraceBundle};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Client for one Raceway server
+#[derive(Debug, Clone)]
+pub struct RacewayApiClient {
+    http: Client,
+    base_url: String,
+}
+
+impl RacewayApiClient {
+    /// A client for the server at `base_url`, e.g. `http://localhost:8080`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(Client::new(), base_url)
+    }
+
+    /// A client that sends requests through an existing reqwest client
+    pub fn with_http_client(http: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Server status (`GET /status`)
+    pub async fn status(&self) -> Result<StatusData> {
+        self.get("/status", "Status").await
+    }
+
+    /// One page of traces, most recent first
+    /// One page of traces matching `filter`
+    pub async fn list_traces(
+        &self,
+        page: usize,
+        page_size: usize,
+        filter: &TraceListFilter,
+    ) -> Result<TracesListData> {
+        let mut path = format!("/api/traces?page={}&page_size={}", page, page_size);
+        if let Some(tag) = &filter.tag {
+            path.push_str(&format!("&tag={}", urlencoding::encode(tag)));
+        }
+        for (name, bound) in [("since", filter.since), ("until", filter.until)] {
+            if let Some(bound) = bound {
+                path.push_str(&format!(
+                    "&{}={}",
+                    name,
+                    urlencoding::encode(&bound.to_rfc3339())
+                ));
+            }
+        }
+        self.get(&path, "Trace list").await
+    }
+
+    /// Events and full analysis of one trace
+    pub async fn get_trace(&self, trace_id: &str) -> Result<FullTraceAnalysis> {
+        self.get(&format!("/api/traces/{}", trace_id), "Trace")
+            .await
+    }
+
+    /// Full analysis of one trace, returning only its first `event_limit` events
+    pub async fn get_trace_summary(
+        &self,
+        trace_id: &str,
+        event_limit: usize,
+    ) -> Result<FullTraceAnalysis> {
+        self.get(
+            &format!("/api/traces/{}?event_limit={}", trace_id, event_limit),
+            "Trace",
+        )
+        .await
+    }
+
+    /// The trace endpoint's response body exactly as the server sent it
+    pub async fn get_trace_raw(&self, trace_id: &str) -> Result<String> {
+        let url = self.url(&format!("/api/traces/{}", trace_id));
+        self.http
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to request trace")?
+            .text()
+            .await
+            .context("Failed to read response body")
+    }
+
+    pub async fn services(&self) -> Result<ServicesListData> {
+        self.get("/api/services", "Services").await
+    }
+
+    /// One page of the traces a service took part in
+    pub async fn service_traces(
+        &self,
+        service: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<ServiceTracesData> {
+        self.get(
+            &format!(
+                "/api/services/{}/traces?page={}&page_size={}",
+                urlencoding::encode(service),
+                page,
+                page_size
+            ),
+            "Service traces",
+        )
+        .await
+    }
+
+    /// Counts and event latency percentiles for one service
+    pub async fn service_overview(&self, service: &str) -> Result<ServiceOverviewData> {
+        self.get(
+            &format!("/api/services/{}/overview", urlencoding::encode(service)),
+            "Service overview",
+        )
+        .await
+    }
+
+    pub async fn service_dependencies(&self, service: &str) -> Result<ServiceDependenciesData> {
+        self.get(
+            &format!(
+                "/api/services/{}/dependencies",
+                urlencoding::encode(service)
+            ),
+            "Service dependency",
+        )
+        .await
+    }
+
+    /// Health of every service over the last `window_minutes`
+    pub async fn services_health(&self, window_minutes: u64) -> Result<Vec<ServiceHealthEntry>> {
+        self.get(
+            &format!(
+                "/api/services/health?time_window_minutes={}",
+                window_minutes
+            ),
+            "Health",
+        )
+        .await
+    }
+
+    /// Races across all traces, grouped by variable
+    pub async fn global_races(&self) -> Result<GlobalRacesData> {
+        self.get("/api/distributed/global-races", "Global races")
+            .await
+    }
+
+    pub async fn hotspots(&self) -> Result<HotspotsData> {
+        self.get("/api/distributed/hotspots", "Hotspots").await
+    }
+
+    /// Locks acquired in conflicting orders across all traces
+    pub async fn deadlocks(&self) -> Result<DeadlocksData> {
+        self.get("/api/distributed/deadlocks", "Deadlocks").await
+    }
+
+    /// Acquisitions, contending threads and hold times per lock
+    pub async fn lock_stats(&self) -> Result<LockStatsData> {
+        self.get("/api/distributed/locks", "Lock stats").await
+    }
+
+    pub async fn distributed_edges(&self) -> Result<DistributedEdgesData> {
+        self.get("/api/distributed/edges", "Edges").await
+    }
+
+    /// System performance metrics, listing up to `limit` slowest traces, with
+    /// per-minute throughput for the last `buckets` minutes when given
+    pub async fn performance_metrics(
+        &self,
+        limit: usize,
+        buckets: Option<u64>,
+    ) -> Result<PerformanceMetrics> {
+        let mut path = format!("/api/performance/metrics?limit={}", limit);
+        if let Some(buckets) = buckets {
+            path.push_str(&format!("&buckets={}", buckets));
+        }
+        self.get(&path, "Performance").await
+    }
+
+    /// Run storage maintenance (`POST /api/maintenance`)
+    pub async fn run_maintenance(&self) -> Result<MaintenanceData> {
+        self.post("/api/maintenance", None::<&()>, "Maintenance")
+            .await
+    }
+
+    /// Events through which `from` causally leads to `to`, in order
+    pub async fn causal_path(&self, trace_id: &str, from: &str, to: &str) -> Result<Vec<Event>> {
+        self.get(
+            &format!("/api/traces/{}/path?from={}&to={}", trace_id, from, to),
+            "Causal path",
+        )
+        .await
+    }
+
+    pub async fn export_bundle(&self, trace_id: &str) -> Result<TraceBundle> {
+        self.get(&format!("/api/traces/{}/bundle", trace_id), "Bundle")
+            .await
+    }
+
+    pub async fn import_bundle(&self, bundle: &TraceBundle) -> Result<BundleImport> {
+        self.post("/api/bundles", Some(bundle), "Bundle import")
+            .await
+    }
+
+    /// Submit a batch of events for ingestion
+    pub async fn submit_events(&self, events: &[raceway_core::Event]) -> Result<()> {
+        self.send(
+            self.http
+                .post(self.url("/events"))
+                .json(&serde_json::json!({ "events": events })),
+            "POST",
+            "/events",
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, what: &str) -> Result<T> {
+        let response = self
+            .send(self.http.get(self.url(path)), "GET", path)
+            .await?;
+        unwrap_data(response.json().await?, what)
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: Option<&B>,
+        what: &str,
+    ) -> Result<T> {
+        let mut request = self.http.post(self.url(path));
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = self.send(request, "POST", path).await?;
+        unwrap_data(response.json().await?, what)
+    }
+
+    /// Send a request, turning transport failures and non-2xx statuses into errors
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        method: &str,
+        path: &str,
+    ) -> Result<reqwest::Response> {
+        let url = self.url(path);
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to {} {}", method, url))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Request to {} failed: {} {}", url, status, text));
+        }
+        Ok(response)
+    }
+}
+
+fn unwrap_data<T>(response: ApiResponse<T>, what: &str) -> Result<T> {
+    if !response.success {
+        return Err(anyhow!(response
+            .error
+            .unwrap_or_else(|| "Unknown error".into())));
+    }
+    response
+        .data
+        .ok_or_else(|| anyhow!("{} response missing data", what))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+}
+
+/// Narrows a trace listing; unset fields don't filter
+#[derive(Debug, Clone, Default)]
+pub struct TraceListFilter {
+    /// Only traces with an event tagged `key:value`
+    pub tag: Option<String>,
+    /// Only traces whose events overlap `since..=until`
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TracesListData {
+    pub total_traces: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub total_pages: usize,
+    pub traces: Vec<TraceMetadata>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TraceMetadata {
+    pub trace_id: String,
+    pub event_count: usize,
+    pub first_timestamp: String,
+    pub last_timestamp: String,
+    pub service_count: usize,
+    pub services: Vec<String>,
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FullTraceAnalysis {
+    pub trace_id: String,
+    /// Events in the whole trace, even when `events` holds only a page
+    #[serde(default)]
+    pub total_events: usize,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub audit_trails: HashMap<String, Vec<VariableAccess>>,
+    pub analysis: TraceAnalysisSummary,
+    #[serde(default)]
+    pub critical_path: Option<CriticalPath>,
+    #[serde(default)]
+    pub anomalies: Vec<DetectedAnomaly>,
+    #[serde(default)]
+    pub dependencies: Option<TraceDependencies>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct Event {
+    pub id: String,
+    #[serde(default)]
+    pub timestamp: String,
+    #[serde(default)]
+    pub trace_id: String,
+    #[serde(default)]
+    pub metadata: EventMetadata,
+    #[serde(default)]
+    pub kind: Value,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct EventMetadata {
+    #[serde(default)]
+    pub thread_id: String,
+    #[serde(default)]
+    pub service_name: String,
+    #[serde(default)]
+    pub duration_ns: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct TraceAnalysisSummary {
+    #[serde(default)]
+    pub concurrent_events: usize,
+    #[serde(default)]
+    pub potential_races: usize,
+    #[serde(default)]
+    pub anomalies: Vec<String>,
+    #[serde(default)]
+    pub race_details: Vec<RaceDetail>,
+    /// Pairs stopped at the server's `race_detection.max_pairs_per_trace`
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct RaceDetail {
+    pub variable: String,
+    pub event1_thread: String,
+    pub event2_thread: String,
+    pub event1_location: String,
+    pub event2_location: String,
+    pub severity: String,
+    pub description: String,
+    #[serde(default)]
+    pub recommendation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct CriticalPath {
+    #[serde(default)]
+    pub path_events: usize,
+    #[serde(default)]
+    pub total_duration_ms: f64,
+    #[serde(default)]
+    pub trace_total_duration_ms: f64,
+    #[serde(default)]
+    pub percentage_of_total: f64,
+    #[serde(default)]
+    pub path: Vec<PathEvent>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct PathEvent {
+    pub id: String,
+    pub kind: String,
+    pub location: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub duration_ms: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct DetectedAnomaly {
+    pub event_id: String,
+    pub event_kind: String,
+    pub severity: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct TraceDependencies {
+    pub services: Vec<ServiceInfo>,
+    pub dependencies: Vec<ServiceDependency>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct ServiceInfo {
+    pub name: String,
+    #[serde(default)]
+    pub event_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct ServiceDependency {
+    pub from: String,
+    pub to: String,
+    pub call_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServicesListData {
+    pub total_services: usize,
+    pub services: Vec<ServiceListItem>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceListItem {
+    pub name: String,
+    pub event_count: usize,
+    pub trace_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceOverviewData {
+    pub name: String,
+    pub event_count: usize,
+    pub trace_count: usize,
+    pub avg_events_per_trace: f64,
+    pub latency: ServiceLatencyPercentiles,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceLatencyPercentiles {
+    pub samples: usize,
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceTracesData {
+    pub service_name: String,
+    pub total_traces: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub total_pages: usize,
+    pub traces: Vec<TraceMetadata>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceDependenciesData {
+    pub service_name: String,
+    pub calls_to: Vec<ServiceDependencyInfo>,
+    pub called_by: Vec<ServiceDependencyInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceDependencyInfo {
+    pub to: String,
+    pub total_calls: usize,
+    pub trace_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GlobalRacesData {
+    pub total_races: usize,
+    pub races: Vec<GlobalRace>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GlobalRace {
+    pub variable: String,
+    pub trace_count: usize,
+    pub access_count: usize,
+    pub access_types: Vec<String>,
+    pub thread_count: usize,
+    pub severity: String,
+    pub trace_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HotspotsData {
+    pub top_variables: Vec<VariableHotspot>,
+    pub top_service_calls: Vec<ServiceCallHotspot>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VariableHotspot {
+    pub variable: String,
+    pub access_count: usize,
+    pub trace_count: usize,
+    pub services: Vec<String>,
+    #[serde(default)]
+    pub accesses_by_service: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceCallHotspot {
+    pub from_service: String,
+    pub to_service: String,
+    pub call_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeadlocksData {
+    pub total_violations: usize,
+    pub violations: Vec<LockOrderViolation>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LockOrderViolation {
+    pub lock_a: String,
+    pub lock_b: String,
+    pub a_then_b: LockOrderObservation,
+    pub b_then_a: Vec<LockOrderObservation>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LockOrderObservation {
+    pub held: String,
+    pub acquired: String,
+    pub thread_id: String,
+    pub trace_id: String,
+    pub event_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LockStatsData {
+    pub total_locks: usize,
+    pub locks: Vec<LockStats>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LockStats {
+    pub lock_id: String,
+    pub acquisitions: usize,
+    pub thread_count: usize,
+    pub trace_count: usize,
+    pub total_hold_ms: f64,
+    pub mean_hold_ms: f64,
+    pub max_hold_ms: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceHealthEntry {
+    pub name: String,
+    pub status: String,
+    pub trace_count: usize,
+    pub last_activity: String,
+    pub avg_events_per_trace: f64,
+    pub minutes_since_last_activity: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PerformanceMetrics {
+    pub trace_latency: TraceLatencyMetrics,
+    pub event_performance: EventPerformanceMetrics,
+    pub service_latency: Vec<ServiceLatency>,
+    pub throughput: ThroughputMetrics,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TraceLatencyMetrics {
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub slowest_traces: Vec<SlowTrace>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SlowTrace {
+    pub trace_id: String,
+    pub duration_ms: f64,
+    pub services: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EventPerformanceMetrics {
+    pub by_type: Vec<EventTypePerformance>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EventTypePerformance {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub count: usize,
+    pub avg_duration_ms: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceLatency {
+    pub service: String,
+    pub avg_duration_ms: f64,
+    pub event_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ThroughputMetrics {
+    pub events_per_second: f64,
+    pub traces_per_second: f64,
+    pub time_range_seconds: f64,
+    /// Per-minute history, present when requested with `buckets`
+    #[serde(default)]
+    pub buckets: Vec<ThroughputBucket>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ThroughputBucket {
+    pub minute: String,
+    pub events: usize,
+    pub traces: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DistributedEdgesData {
+    pub total_edges: usize,
+    pub edges: Vec<DistributedEdge>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DistributedEdge {
+    pub from_service: String,
+    pub to_service: String,
+    pub link_type: String,
+    pub call_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MaintenanceData {
+    pub steps: Vec<String>,
+    pub rows_removed: usize,
+    pub reclaimed_bytes: Option<i64>,
+    pub trace_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct VariableAccess {
+    pub event_id: String,
+    pub timestamp: String,
+    pub thread_id: String,
+    pub service_name: String,
+    pub access_type: String,
+    #[serde(default)]
+    pub location: String,
+}
+
+/// Subset of the `/status` payload.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StatusData {
+    #[serde(default)]
+    pub version: String,
+    pub events_captured: usize,
+    pub traces_active: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Query;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::json;
+
+    async fn spawn_mock_server() -> String {
+        let app = Router::new()
+            .route(
+                "/api/traces",
+                get(|Query(params): Query<HashMap<String, String>>| async move {
+                    Json(json!({
+                        "success": true,
+                        "data": {
+                            "total_traces": 1,
+                            "page": params["page"].parse::<usize>().unwrap(),
+                            "page_size": params["page_size"].parse::<usize>().unwrap(),
+                            "total_pages": 1,
+                            "traces": [{
+                                "trace_id": "3f2b6c1e-0000-4000-8000-000000000001",
+                                "event_count": 12,
+                                "first_timestamp": "2024-01-01T12:00:00Z",
+                                "last_timestamp": "2024-01-01T12:00:05Z",
+                                "service_count": 2,
+                                "services": ["api", "ledger"]
+                            }]
+                        },
+                        "error": null
+                    }))
+                }),
+            )
+            .route(
+                "/api/traces/:trace_id",
+                get(|| async {
+                    Json(json!({
+                        "success": false,
+                        "data": null,
+                        "error": "Trace not found"
+                    }))
+                }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}/", addr)
+    }
+
+    #[tokio::test]
+    async fn list_traces_sends_paging_and_parses_response() {
+        let api = RacewayApiClient::new(spawn_mock_server().await);
+
+        let traces = api
+            .list_traces(3, 50, &TraceListFilter::default())
+            .await
+            .unwrap();
+
+        assert_eq!(traces.page, 3);
+        assert_eq!(traces.page_size, 50);
+        assert_eq!(traces.traces.len(), 1);
+        assert_eq!(traces.traces[0].services, vec!["api", "ledger"]);
+        assert!(!traces.traces[0].truncated);
+    }
+
+    #[tokio::test]
+    async fn server_error_is_returned_as_err() {
+        let api = RacewayApiClient::new(spawn_mock_server().await);
+
+        let error = api.get_trace("missing").await.unwrap_err();
+
+        assert_eq!(error.to_string(), "Trace not found");
+    }
+}
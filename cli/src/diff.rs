@@ -0,0 +1,280 @@
+//! Comparison of two traces for `raceway diff`.
+//!
+//! Event ids differ between runs, so events are matched by kind and location:
+//! a key with more events in one trace than the other shows up as the surplus
+//! on that side. Races are matched by variable and the pair of locations.
+
+use crate::client::{FullTraceAnalysis, RaceDetail};
+use crate::export::kind_and_location;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// Events with one kind and location present more often in one trace
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventDelta {
+    pub kind: String,
+    pub location: String,
+    pub count: usize,
+}
+
+/// What changed from a baseline trace to a candidate trace
+#[derive(Debug, Serialize)]
+pub struct TraceDiff {
+    pub baseline: String,
+    pub candidate: String,
+    pub baseline_events: usize,
+    pub candidate_events: usize,
+    pub only_in_baseline: Vec<EventDelta>,
+    pub only_in_candidate: Vec<EventDelta>,
+    /// Races in the candidate with no matching race in the baseline
+    pub new_races: Vec<RaceDetail>,
+    pub baseline_critical_path_ms: Option<f64>,
+    pub candidate_critical_path_ms: Option<f64>,
+    /// Candidate minus baseline, when both traces have a critical path
+    pub critical_path_delta_ms: Option<f64>,
+}
+
+pub fn diff_traces(baseline: &FullTraceAnalysis, candidate: &FullTraceAnalysis) -> TraceDiff {
+    let (only_in_baseline, only_in_candidate) = diff_events(baseline, candidate);
+
+    let baseline_races: HashSet<_> = baseline
+        .analysis
+        .race_details
+        .iter()
+        .map(race_key)
+        .collect();
+    let new_races = candidate
+        .analysis
+        .race_details
+        .iter()
+        .filter(|race| !baseline_races.contains(&race_key(race)))
+        .cloned()
+        .collect();
+
+    let baseline_critical_path_ms = baseline
+        .critical_path
+        .as_ref()
+        .map(|cp| cp.total_duration_ms);
+    let candidate_critical_path_ms = candidate
+        .critical_path
+        .as_ref()
+        .map(|cp| cp.total_duration_ms);
+
+    TraceDiff {
+        baseline: baseline.trace_id.clone(),
+        candidate: candidate.trace_id.clone(),
+        baseline_events: baseline.events.len(),
+        candidate_events: candidate.events.len(),
+        only_in_baseline,
+        only_in_candidate,
+        new_races,
+        baseline_critical_path_ms,
+        candidate_critical_path_ms,
+        critical_path_delta_ms: baseline_critical_path_ms
+            .zip(candidate_critical_path_ms)
+            .map(|(before, after)| after - before),
+    }
+}
+
+/// Events keyed by kind and location that one trace has more of than the
+/// other, as (only in baseline, only in candidate)
+fn diff_events(
+    baseline: &FullTraceAnalysis,
+    candidate: &FullTraceAnalysis,
+) -> (Vec<EventDelta>, Vec<EventDelta>) {
+    let baseline_counts = event_counts(baseline);
+    let candidate_counts = event_counts(candidate);
+
+    let surplus = |ours: &BTreeMap<(String, String), usize>,
+                   theirs: &BTreeMap<(String, String), usize>| {
+        ours.iter()
+            .filter_map(|((kind, location), &count)| {
+                let other = theirs
+                    .get(&(kind.clone(), location.clone()))
+                    .copied()
+                    .unwrap_or(0);
+                (count > other).then(|| EventDelta {
+                    kind: kind.clone(),
+                    location: location.clone(),
+                    count: count - other,
+                })
+            })
+            .collect()
+    };
+
+    (
+        surplus(&baseline_counts, &candidate_counts),
+        surplus(&candidate_counts, &baseline_counts),
+    )
+}
+
+fn event_counts(trace: &FullTraceAnalysis) -> BTreeMap<(String, String), usize> {
+    let mut counts = BTreeMap::new();
+    for event in &trace.events {
+        *counts.entry(kind_and_location(&event.kind)).or_default() += 1;
+    }
+    counts
+}
+
+/// A race's variable and its two locations, in either order
+fn race_key(race: &RaceDetail) -> (String, String, String) {
+    let (first, second) = if race.event1_location <= race.event2_location {
+        (&race.event1_location, &race.event2_location)
+    } else {
+        (&race.event2_location, &race.event1_location)
+    };
+    (race.variable.clone(), first.clone(), second.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn trace(
+        trace_id: &str,
+        kinds: &[serde_json::Value],
+        races: serde_json::Value,
+    ) -> FullTraceAnalysis {
+        let events: Vec<_> = kinds
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| json!({ "id": format!("{}-{}", trace_id, i), "kind": kind }))
+            .collect();
+        serde_json::from_value(json!({
+            "trace_id": trace_id,
+            "total_events": events.len(),
+            "events": events,
+            "analysis": { "race_details": races },
+        }))
+        .unwrap()
+    }
+
+    fn call(file: &str, line: u64) -> serde_json::Value {
+        json!({ "FunctionCall": { "function_name": "f", "file": file, "line": line } })
+    }
+
+    fn write(location: &str) -> serde_json::Value {
+        json!({ "StateChange": { "variable": "balance", "location": location, "access_type": "Write" } })
+    }
+
+    fn race(location1: &str, location2: &str) -> serde_json::Value {
+        json!({
+            "variable": "balance",
+            "event1_thread": "t1",
+            "event2_thread": "t2",
+            "event1_location": location1,
+            "event2_location": location2,
+            "severity": "CRITICAL",
+            "description": "Write-Write race"
+        })
+    }
+
+    #[test]
+    fn events_are_matched_by_kind_and_location() {
+        let baseline = trace(
+            "base",
+            &[
+                call("bank.rs", 10),
+                write("bank.rs:12"),
+                write("bank.rs:20"),
+            ],
+            json!([]),
+        );
+        let candidate = trace(
+            "cand",
+            &[
+                call("bank.rs", 10),
+                write("bank.rs:12"),
+                call("audit.rs", 3),
+            ],
+            json!([]),
+        );
+
+        let diff = diff_traces(&baseline, &candidate);
+        assert_eq!(
+            diff.only_in_baseline,
+            vec![EventDelta {
+                kind: "StateChange".into(),
+                location: "bank.rs:20".into(),
+                count: 1,
+            }]
+        );
+        assert_eq!(
+            diff.only_in_candidate,
+            vec![EventDelta {
+                kind: "FunctionCall".into(),
+                location: "audit.rs:3".into(),
+                count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn repeated_events_report_the_surplus() {
+        let baseline = trace("base", &[write("bank.rs:12")], json!([]));
+        let candidate = trace(
+            "cand",
+            &[
+                write("bank.rs:12"),
+                write("bank.rs:12"),
+                write("bank.rs:12"),
+            ],
+            json!([]),
+        );
+
+        let diff = diff_traces(&baseline, &candidate);
+        assert!(diff.only_in_baseline.is_empty());
+        assert_eq!(diff.only_in_candidate[0].count, 2);
+    }
+
+    #[test]
+    fn identical_traces_have_no_differences() {
+        let events = [call("bank.rs", 10), write("bank.rs:12")];
+        let races = json!([race("bank.rs:12", "bank.rs:30")]);
+        let diff = diff_traces(
+            &trace("base", &events, races.clone()),
+            &trace("cand", &events, races),
+        );
+
+        assert!(diff.only_in_baseline.is_empty());
+        assert!(diff.only_in_candidate.is_empty());
+        assert!(diff.new_races.is_empty());
+    }
+
+    #[test]
+    fn only_races_missing_from_the_baseline_are_new() {
+        let baseline = trace("base", &[], json!([race("bank.rs:12", "bank.rs:30")]));
+        let candidate = trace(
+            "cand",
+            &[],
+            json!([
+                race("bank.rs:30", "bank.rs:12"),
+                race("bank.rs:12", "bank.rs:45")
+            ]),
+        );
+
+        let diff = diff_traces(&baseline, &candidate);
+        assert_eq!(diff.new_races.len(), 1);
+        assert_eq!(diff.new_races[0].event2_location, "bank.rs:45");
+    }
+
+    #[test]
+    fn critical_path_delta_needs_both_paths() {
+        let mut baseline = trace("base", &[], json!([]));
+        let mut candidate = trace("cand", &[], json!([]));
+        assert_eq!(
+            diff_traces(&baseline, &candidate).critical_path_delta_ms,
+            None
+        );
+
+        baseline.critical_path =
+            serde_json::from_value(json!({ "total_duration_ms": 120.0 })).unwrap();
+        candidate.critical_path =
+            serde_json::from_value(json!({ "total_duration_ms": 150.5 })).unwrap();
+        assert_eq!(
+            diff_traces(&baseline, &candidate).critical_path_delta_ms,
+            Some(30.5)
+        );
+    }
+}
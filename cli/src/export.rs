@@ -0,0 +1,368 @@
+//! Rendering of a trace's events for `raceway export`.
+//!
+//! `json` keeps the `/api/traces/:id` response as the server sent it. The
+//! line-oriented formats are meant for log pipelines: `jsonl` writes each
+//! event as a standalone JSON object on its own line, and `csv` writes one
+//! row per event with a header. `dot` draws the causal graph for Graphviz.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Supported output formats for `raceway export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Jsonl,
+    Csv,
+    Dot,
+}
+
+const CSV_HEADER: &str = "id,timestamp,service,kind,location";
+
+/// Render a raw `/api/traces/:id` response body in `format`
+pub fn render_trace(response: &str, format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Json => Ok(response.to_string()),
+        ExportFormat::Jsonl => to_jsonl(&trace_events(response)?),
+        ExportFormat::Csv => Ok(to_csv(&trace_events(response)?)),
+        ExportFormat::Dot => to_dot(response),
+    }
+}
+
+fn trace_events(response: &str) -> Result<Vec<Value>> {
+    let mut body: Value =
+        serde_json::from_str(response).context("Failed to parse trace response")?;
+    match body.pointer_mut("/data/events").map(Value::take) {
+        Some(Value::Array(events)) => Ok(events),
+        _ => Err(anyhow!("Trace response missing events")),
+    }
+}
+
+fn to_jsonl(events: &[Value]) -> Result<String> {
+    let mut out = String::new();
+    for event in events {
+        out.push_str(&serde_json::to_string(event)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn to_csv(events: &[Value]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for event in events {
+        let (kind, location) = kind_and_location(&event["kind"]);
+        let row = [
+            event["id"].as_str().unwrap_or_default(),
+            event["timestamp"].as_str().unwrap_or_default(),
+            event["metadata"]["service_name"]
+                .as_str()
+                .unwrap_or_default(),
+            &kind,
+            &location,
+        ];
+        let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Graphviz digraph of a trace: one node per event labeled with its kind and
+/// location, solid edges from parent to child, dashed edges from an upstream
+/// service's span to the first event of each span it called, and race-involved
+/// events filled red.
+fn to_dot(response: &str) -> Result<String> {
+    let body: Value = serde_json::from_str(response).context("Failed to parse trace response")?;
+    let data = &body["data"];
+    let events = data["events"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Trace response missing events"))?;
+    let ids: HashSet<&str> = events.iter().filter_map(|e| e["id"].as_str()).collect();
+
+    // `is_race` is set on the later access of each racing pair in a trail
+    let mut racing: HashSet<&str> = HashSet::new();
+    if let Some(trails) = data["audit_trails"].as_object() {
+        for accesses in trails.values().filter_map(Value::as_array) {
+            for (i, access) in accesses.iter().enumerate() {
+                if access["is_race"].as_bool() != Some(true) {
+                    continue;
+                }
+                racing.extend(access["event_id"].as_str());
+                if i > 0 {
+                    racing.extend(accesses[i - 1]["event_id"].as_str());
+                }
+            }
+        }
+    }
+
+    let mut out = String::from("digraph trace {\n");
+    if let Some(trace_id) = data["trace_id"].as_str() {
+        out.push_str(&format!("  label={};\n", dot_string(trace_id)));
+    }
+    out.push_str("  node [shape=box, fontname=\"monospace\"];\n");
+
+    for event in events {
+        let Some(id) = event["id"].as_str() else {
+            continue;
+        };
+        let (kind, location) = kind_and_location(&event["kind"]);
+        let label = if location.is_empty() {
+            kind
+        } else {
+            format!("{}\n{}", kind, location)
+        };
+        let style = if racing.contains(id) {
+            ", style=filled, fillcolor=\"#f8d0d0\", color=red"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "  {} [label={}{}];\n",
+            dot_string(id),
+            dot_string(&label),
+            style
+        ));
+    }
+
+    for event in events {
+        if let (Some(id), Some(parent)) = (event["id"].as_str(), event["parent_id"].as_str()) {
+            if ids.contains(parent) {
+                out.push_str(&format!(
+                    "  {} -> {};\n",
+                    dot_string(parent),
+                    dot_string(id)
+                ));
+            }
+        }
+    }
+
+    // The caller is the upstream span's last event before the downstream span
+    // starts, or its first event when clock skew leaves none earlier
+    let mut ordered: Vec<&Value> = events.iter().collect();
+    ordered.sort_by_key(|event| {
+        event["timestamp"]
+            .as_str()
+            .and_then(|ts| ts.parse::<DateTime<Utc>>().ok())
+    });
+    let mut first_in_span: HashMap<&str, &str> = HashMap::new();
+    for event in &ordered {
+        if let (Some(id), Some(span)) = (
+            event["id"].as_str(),
+            event["metadata"]["distributed_span_id"].as_str(),
+        ) {
+            first_in_span.entry(span).or_insert(id);
+        }
+    }
+    let mut latest_in_span: HashMap<&str, &str> = HashMap::new();
+    let mut linked: HashSet<(&str, &str)> = HashSet::new();
+    for event in ordered {
+        let Some(id) = event["id"].as_str() else {
+            continue;
+        };
+        let metadata = &event["metadata"];
+        if let (Some(upstream), Some(span)) = (
+            metadata["upstream_span_id"].as_str(),
+            metadata["distributed_span_id"].as_str(),
+        ) {
+            let caller = latest_in_span
+                .get(upstream)
+                .or_else(|| first_in_span.get(upstream));
+            if let Some(caller) = caller {
+                if linked.insert((upstream, span)) {
+                    out.push_str(&format!(
+                        "  {} -> {} [style=dashed];\n",
+                        dot_string(caller),
+                        dot_string(id)
+                    ));
+                }
+            }
+        }
+        if let Some(span) = metadata["distributed_span_id"].as_str() {
+            latest_in_span.insert(span, id);
+        }
+    }
+
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// Double-quoted DOT string with quotes and backslashes escaped and line
+/// breaks kept as `\n`
+fn dot_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+/// Variant name of an externally tagged `EventKind` and where it happened
+pub(crate) fn kind_and_location(kind: &Value) -> (String, String) {
+    let Some((name, payload)) = kind.as_object().and_then(|map| map.iter().next()) else {
+        return (kind.as_str().unwrap_or_default().to_string(), String::new());
+    };
+
+    let location = match name.as_str() {
+        "FunctionCall" => match (payload["file"].as_str(), payload["line"].as_u64()) {
+            (Some(file), Some(line)) => format!("{}:{}", file, line),
+            (Some(file), None) => file.to_string(),
+            _ => String::new(),
+        },
+        "HttpRequest" => payload["url"].as_str().unwrap_or_default().to_string(),
+        _ => payload["location"].as_str().unwrap_or_default().to_string(),
+    };
+    (name.clone(), location)
+}
+
+/// Quote a field when it contains a delimiter, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn small_trace() -> String {
+        json!({
+            "success": true,
+            "data": {
+                "trace_id": "trace-1",
+                "events": [
+                    {
+                        "id": "e1",
+                        "timestamp": "2024-01-01T12:00:00Z",
+                        "metadata": { "service_name": "api" },
+                        "kind": { "FunctionCall": {
+                            "function_name": "transfer",
+                            "file": "bank.rs",
+                            "line": 10
+                        } }
+                    },
+                    {
+                        "id": "e2",
+                        "timestamp": "2024-01-01T12:00:01Z",
+                        "metadata": { "service_name": "api" },
+                        "kind": { "StateChange": {
+                            "variable": "balance",
+                            "location": "bank.rs:12, in transfer",
+                            "access_type": "Write"
+                        } }
+                    }
+                ]
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn jsonl_writes_one_event_per_line() {
+        let out = render_trace(&small_trace(), ExportFormat::Jsonl).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let second: Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["id"], "e2");
+        assert_eq!(second["kind"]["StateChange"]["variable"], "balance");
+    }
+
+    #[test]
+    fn csv_writes_header_and_quotes_fields() {
+        let out = render_trace(&small_trace(), ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                CSV_HEADER,
+                "e1,2024-01-01T12:00:00Z,api,FunctionCall,bank.rs:10",
+                "e2,2024-01-01T12:00:01Z,api,StateChange,\"bank.rs:12, in transfer\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn json_is_passed_through_unchanged() {
+        let trace = small_trace();
+        assert_eq!(render_trace(&trace, ExportFormat::Json).unwrap(), trace);
+    }
+
+    #[test]
+    fn line_formats_reject_a_response_without_events() {
+        let err = render_trace(r#"{"success":false}"#, ExportFormat::Jsonl).unwrap_err();
+        assert!(err.to_string().contains("missing events"));
+    }
+
+    #[test]
+    fn dot_draws_causal_and_distributed_edges() {
+        let trace = json!({
+            "success": true,
+            "data": {
+                "trace_id": "trace-1",
+                "events": [
+                    {
+                        "id": "e1",
+                        "parent_id": null,
+                        "timestamp": "2024-01-01T12:00:00Z",
+                        "metadata": { "service_name": "api", "distributed_span_id": "span-api" },
+                        "kind": { "FunctionCall": { "file": "bank.rs", "line": 10 } }
+                    },
+                    {
+                        "id": "e2",
+                        "parent_id": "e1",
+                        "timestamp": "2024-01-01T12:00:01Z",
+                        "metadata": { "service_name": "api", "distributed_span_id": "span-api" },
+                        "kind": { "StateChange": {
+                            "variable": "balance",
+                            "location": "bank.rs:12, \"transfer\"",
+                            "access_type": "Write"
+                        } }
+                    },
+                    {
+                        "id": "e3",
+                        "parent_id": null,
+                        "timestamp": "2024-01-01T12:00:02Z",
+                        "metadata": {
+                            "service_name": "ledger",
+                            "distributed_span_id": "span-ledger",
+                            "upstream_span_id": "span-api"
+                        },
+                        "kind": { "HttpRequest": { "method": "POST", "url": "/ledger" } }
+                    }
+                ],
+                "audit_trails": {
+                    "balance": [
+                        { "event_id": "e0", "is_race": false },
+                        { "event_id": "e2", "is_race": true }
+                    ]
+                }
+            }
+        })
+        .to_string();
+
+        let out = render_trace(&trace, ExportFormat::Dot).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.first(), Some(&"digraph trace {"));
+        assert_eq!(lines.last(), Some(&"}"));
+        assert!(lines[1..lines.len() - 1]
+            .iter()
+            .all(|line| line.starts_with("  ") && line.ends_with(';')));
+        assert_eq!(out.matches('{').count(), out.matches('}').count());
+
+        assert!(out.contains(r#"  "e1" [label="FunctionCall\nbank.rs:10"];"#));
+        assert!(out.contains(
+            r##"  "e2" [label="StateChange\nbank.rs:12, \"transfer\"", style=filled, fillcolor="#f8d0d0", color=red];"##
+        ));
+        assert!(out.contains(r#"  "e3" [label="HttpRequest\n/ledger"];"#));
+        assert!(out.contains(r#"  "e1" -> "e2";"#));
+        assert!(out.contains(r#"  "e2" -> "e3" [style=dashed];"#));
+        assert_eq!(out.matches("->").count(), 2);
+    }
+}
@@ -0,0 +1,140 @@
+//! `raceway trace --follow` - keep one trace's summary on screen while it grows.
+//!
+//! Polls `/api/traces/:id`, redraws the summary in place each cycle and calls
+//! out what changed since the previous poll, e.g. "+2 races since last poll".
+
+use std::time::Duration;
+
+use anyhow::Result;
+use raceway::client::{FullTraceAnalysis, RacewayApiClient};
+
+use crate::render_trace_summary;
+
+/// The counts compared between polls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraceCounts {
+    pub events: usize,
+    pub races: usize,
+    pub concurrent: usize,
+    pub anomalies: usize,
+}
+
+impl TraceCounts {
+    /// Each count with its singular and plural label, in display order
+    fn labelled(&self) -> [(usize, &'static str, &'static str); 4] {
+        [
+            (self.races, "race", "races"),
+            (self.anomalies, "anomaly", "anomalies"),
+            (self.events, "event", "events"),
+            (self.concurrent, "concurrent event", "concurrent events"),
+        ]
+    }
+}
+
+impl From<&FullTraceAnalysis> for TraceCounts {
+    fn from(data: &FullTraceAnalysis) -> Self {
+        Self {
+            events: data.total_events,
+            races: data.analysis.potential_races,
+            concurrent: data.analysis.concurrent_events,
+            anomalies: data.anomalies.len(),
+        }
+    }
+}
+
+pub async fn run_follow(
+    api: &RacewayApiClient,
+    trace_id: &str,
+    interval: u64,
+    show_events: usize,
+    fields: &[String],
+) -> Result<()> {
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut previous: Option<TraceCounts> = None;
+
+    loop {
+        let data = tokio::select! {
+            data = api.get_trace_summary(trace_id, show_events) => data?,
+            _ = &mut ctrl_c => return Ok(()),
+        };
+        let counts = TraceCounts::from(&data);
+
+        let changes = match previous {
+            None => String::new(),
+            Some(previous) => match describe_deltas(&previous, &counts) {
+                Some(deltas) => format!("\x1B[1;33m📈 {}\x1B[0m\n", deltas),
+                None => "No changes since last poll\n".to_string(),
+            },
+        };
+        // Clear the screen and move the cursor home so the view refreshes in place
+        print!(
+            "\x1B[2J\x1B[H🔁 Following every {}s, Ctrl-C to stop | {}\n{}\n{}",
+            interval.max(1),
+            chrono::Local::now().format("%H:%M:%S"),
+            changes,
+            render_trace_summary(&data, show_events, fields)
+        );
+        previous = Some(counts);
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(interval.max(1))) => {}
+            _ = &mut ctrl_c => return Ok(()),
+        }
+    }
+}
+
+/// "+2 races, +14 events since last poll", or `None` when nothing changed
+pub fn describe_deltas(previous: &TraceCounts, current: &TraceCounts) -> Option<String> {
+    let deltas: Vec<String> = previous
+        .labelled()
+        .into_iter()
+        .zip(current.labelled())
+        .filter(|((before, ..), (after, ..))| before != after)
+        .map(|((before, ..), (after, singular, plural))| {
+            let change = after as i64 - before as i64;
+            let label = if change.abs() == 1 { singular } else { plural };
+            format!("{:+} {}", change, label)
+        })
+        .collect();
+
+    (!deltas.is_empty()).then(|| format!("{} since last poll", deltas.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deltas_list_changed_counts_with_sign() {
+        let previous = TraceCounts {
+            events: 10,
+            races: 1,
+            concurrent: 4,
+            anomalies: 2,
+        };
+        let current = TraceCounts {
+            events: 24,
+            races: 3,
+            concurrent: 4,
+            anomalies: 1,
+        };
+
+        assert_eq!(
+            describe_deltas(&previous, &current).as_deref(),
+            Some("+2 races, -1 anomaly, +14 events since last poll")
+        );
+    }
+
+    #[test]
+    fn unchanged_counts_have_no_deltas() {
+        let counts = TraceCounts {
+            events: 10,
+            races: 1,
+            concurrent: 4,
+            anomalies: 0,
+        };
+
+        assert_eq!(describe_deltas(&counts, &counts), None);
+    }
+}
@@ -0,0 +1,456 @@
+//! Conversion of external trace exports (Jaeger and OTLP JSON) into Raceway events.
+//!
+//! Each span becomes one event. Span relationships are preserved two ways:
+//! the child event's `parent_id` points at its parent span's event, and spans
+//! carry `distributed_span_id` / `upstream_span_id` so cross-service calls
+//! produce distributed edges on ingestion.
+//!
+//! IDs are derived deterministically: the trace ID is the (zero-padded) hex
+//! trace ID read as a UUID, and each event ID is a v5 UUID of the span ID
+//! namespaced by the trace, so importing the same file twice yields the same IDs.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use raceway_core::event::{Event, EventKind, EventMetadata};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Supported input formats for `raceway import`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    Jaeger,
+    Otlp,
+}
+
+/// Parse an exported trace file into Raceway events
+pub fn parse_events(input: &str, format: ImportFormat) -> Result<Vec<Event>> {
+    let spans = match format {
+        ImportFormat::Jaeger => parse_jaeger(input)?,
+        ImportFormat::Otlp => parse_otlp(input)?,
+    };
+    spans_to_events(spans)
+}
+
+/// Format-neutral span, the common shape both exports are reduced to
+#[derive(Debug, Clone)]
+struct ImportedSpan {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    service: String,
+    start: DateTime<Utc>,
+    duration_ns: u64,
+    attributes: HashMap<String, String>,
+}
+
+fn spans_to_events(spans: Vec<ImportedSpan>) -> Result<Vec<Event>> {
+    let service_by_span: HashMap<(String, String), String> = spans
+        .iter()
+        .map(|span| {
+            (
+                (span.trace_id.clone(), span.span_id.clone()),
+                span.service.clone(),
+            )
+        })
+        .collect();
+
+    let mut events = Vec::with_capacity(spans.len());
+    for span in spans {
+        let trace_id = trace_uuid(&span.trace_id)?;
+        let parent_service = span
+            .parent_span_id
+            .as_ref()
+            .and_then(|parent| service_by_span.get(&(span.trace_id.clone(), parent.clone())));
+
+        // Only cross-service parents become distributed edges; same-service
+        // nesting is already captured by parent_id
+        let upstream_span_id = match parent_service {
+            Some(service) if *service != span.service => span.parent_span_id.clone(),
+            _ => None,
+        };
+
+        let thread_id = span
+            .attributes
+            .get("thread.id")
+            .or_else(|| span.attributes.get("thread.name"))
+            .cloned()
+            .unwrap_or_else(|| format!("span-{}", span.span_id));
+
+        events.push(Event {
+            id: event_uuid(trace_id, &span.span_id),
+            trace_id,
+            parent_id: span
+                .parent_span_id
+                .as_deref()
+                .map(|parent| event_uuid(trace_id, parent)),
+            timestamp: span.start,
+            kind: span_kind(&span),
+            metadata: EventMetadata {
+                thread_id,
+                process_id: 0,
+                service_name: span.service.clone(),
+                environment: span
+                    .attributes
+                    .get("deployment.environment")
+                    .cloned()
+                    .unwrap_or_else(|| "imported".to_string()),
+                tags: span.attributes.clone(),
+                duration_ns: Some(span.duration_ns),
+                instance_id: span.attributes.get("service.instance.id").cloned(),
+                distributed_span_id: Some(span.span_id.clone()),
+                upstream_span_id,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
+            },
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        });
+    }
+
+    events.sort_by_key(|event| event.timestamp);
+    Ok(events)
+}
+
+/// Map well-known span attributes onto the closest Raceway event kind
+fn span_kind(span: &ImportedSpan) -> EventKind {
+    let attr = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| span.attributes.get(*key).cloned())
+    };
+
+    if let Some(query) = attr(&["db.statement", "db.query.text"]) {
+        return EventKind::DatabaseQuery {
+            query,
+            database: attr(&["db.name", "db.system"]).unwrap_or_default(),
+            duration_ms: span.duration_ns / 1_000_000,
+        };
+    }
+
+    if let Some(method) = attr(&["http.method", "http.request.method"]) {
+        return EventKind::HttpRequest {
+            method,
+            url: attr(&["http.url", "url.full", "http.target", "url.path"])
+                .unwrap_or_else(|| span.name.clone()),
+            headers: HashMap::new(),
+            body: None,
+        };
+    }
+
+    EventKind::FunctionCall {
+        function_name: span.name.clone(),
+        module: span.service.clone(),
+        args: serde_json::to_value(&span.attributes).unwrap_or(Value::Null),
+        file: attr(&["code.filepath", "code.file.path"]).unwrap_or_default(),
+        line: attr(&["code.lineno", "code.line.number"])
+            .and_then(|line| line.parse().ok())
+            .unwrap_or(0),
+    }
+}
+
+/// 64- or 128-bit hex trace IDs, zero-padded into a UUID
+fn trace_uuid(hex: &str) -> Result<Uuid> {
+    let value = u128::from_str_radix(hex, 16)
+        .with_context(|| format!("Invalid trace ID '{}': expected hex", hex))?;
+    Ok(Uuid::from_u128(value))
+}
+
+fn event_uuid(trace_id: Uuid, span_id: &str) -> Uuid {
+    Uuid::new_v5(&trace_id, span_id.to_ascii_lowercase().as_bytes())
+}
+
+// ─── Jaeger ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JaegerTrace {
+    #[serde(rename = "traceID")]
+    trace_id: String,
+    spans: Vec<JaegerSpan>,
+    #[serde(default)]
+    processes: HashMap<String, JaegerProcess>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JaegerSpan {
+    #[serde(rename = "traceID")]
+    trace_id: Option<String>,
+    #[serde(rename = "spanID")]
+    span_id: String,
+    operation_name: String,
+    #[serde(default)]
+    references: Vec<JaegerReference>,
+    /// Microseconds since the Unix epoch
+    start_time: i64,
+    /// Microseconds
+    #[serde(default)]
+    duration: u64,
+    #[serde(default)]
+    tags: Vec<JaegerTag>,
+    #[serde(rename = "processID")]
+    process_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JaegerReference {
+    ref_type: String,
+    #[serde(rename = "spanID")]
+    span_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JaegerProcess {
+    service_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JaegerTag {
+    key: String,
+    value: Value,
+}
+
+/// Accepts the Jaeger API/UI export (`{"data": [trace, ...]}`), a bare array
+/// of traces, or a single trace object
+fn parse_jaeger(input: &str) -> Result<Vec<ImportedSpan>> {
+    let root: Value = serde_json::from_str(input).context("Invalid Jaeger JSON")?;
+    let traces_value = match root {
+        Value::Object(mut object) if object.contains_key("data") => {
+            object.remove("data").unwrap_or_default()
+        }
+        Value::Array(traces) => Value::Array(traces),
+        other => Value::Array(vec![other]),
+    };
+    let traces: Vec<JaegerTrace> =
+        serde_json::from_value(traces_value).context("Unexpected Jaeger trace layout")?;
+
+    let mut spans = Vec::new();
+    for trace in traces {
+        for span in trace.spans {
+            let service = span
+                .process_id
+                .as_ref()
+                .and_then(|id| trace.processes.get(id))
+                .map(|process| process.service_name.clone())
+                .unwrap_or_else(|| "unknown".to_string());
+
+            let parent_span_id = span
+                .references
+                .iter()
+                .find(|reference| reference.ref_type == "CHILD_OF")
+                .or_else(|| span.references.first())
+                .map(|reference| reference.span_id.clone());
+
+            let start = Utc
+                .timestamp_micros(span.start_time)
+                .single()
+                .ok_or_else(|| anyhow!("Span {} has an invalid startTime", span.span_id))?;
+
+            spans.push(ImportedSpan {
+                trace_id: span.trace_id.unwrap_or_else(|| trace.trace_id.clone()),
+                span_id: span.span_id,
+                parent_span_id,
+                name: span.operation_name,
+                service,
+                start,
+                duration_ns: span.duration.saturating_mul(1_000),
+                attributes: span
+                    .tags
+                    .into_iter()
+                    .map(|tag| (tag.key, value_to_string(&tag.value)))
+                    .collect(),
+            });
+        }
+    }
+    Ok(spans)
+}
+
+// ─── OTLP ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OtlpExport {
+    #[serde(default)]
+    resource_spans: Vec<OtlpResourceSpans>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OtlpResourceSpans {
+    #[serde(default)]
+    resource: OtlpResource,
+    #[serde(default, alias = "instrumentationLibrarySpans")]
+    scope_spans: Vec<OtlpScopeSpans>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OtlpResource {
+    #[serde(default)]
+    attributes: Vec<OtlpAttribute>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OtlpScopeSpans {
+    #[serde(default)]
+    spans: Vec<OtlpSpan>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OtlpSpan {
+    trace_id: String,
+    span_id: String,
+    #[serde(default)]
+    parent_span_id: Option<String>,
+    name: String,
+    start_time_unix_nano: OtlpNanos,
+    end_time_unix_nano: OtlpNanos,
+    #[serde(default)]
+    attributes: Vec<OtlpAttribute>,
+}
+
+/// OTLP/JSON encodes 64-bit integers as strings, but some exporters emit numbers
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OtlpNanos {
+    Text(String),
+    Number(u64),
+}
+
+impl OtlpNanos {
+    fn get(&self) -> Result<u64> {
+        match self {
+            OtlpNanos::Text(text) => text
+                .parse()
+                .with_context(|| format!("Invalid nanosecond timestamp '{}'", text)),
+            OtlpNanos::Number(value) => Ok(*value),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OtlpAttribute {
+    key: String,
+    #[serde(default)]
+    value: Value,
+}
+
+impl OtlpAttribute {
+    /// Unwrap the `AnyValue` envelope (`{"stringValue": "..."}`, `{"intValue": "3"}`, ...)
+    fn value_string(&self) -> String {
+        match &self.value {
+            Value::Object(object) => object
+                .values()
+                .next()
+                .map(value_to_string)
+                .unwrap_or_default(),
+            other => value_to_string(other),
+        }
+    }
+}
+
+fn parse_otlp(input: &str) -> Result<Vec<ImportedSpan>> {
+    let export: OtlpExport = serde_json::from_str(input).context("Invalid OTLP JSON")?;
+
+    let mut spans = Vec::new();
+    for resource_spans in export.resource_spans {
+        let service = resource_spans
+            .resource
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == "service.name")
+            .map(OtlpAttribute::value_string)
+            .unwrap_or_else(|| "unknown".to_string());
+
+        for span in resource_spans
+            .scope_spans
+            .into_iter()
+            .flat_map(|scope| scope.spans)
+        {
+            let start_ns = span.start_time_unix_nano.get()?;
+            let end_ns = span.end_time_unix_nano.get()?;
+
+            spans.push(ImportedSpan {
+                trace_id: span.trace_id,
+                span_id: span.span_id,
+                parent_span_id: span.parent_span_id.filter(|parent| !parent.is_empty()),
+                name: span.name,
+                service: service.clone(),
+                start: Utc.timestamp_nanos(start_ns as i64),
+                duration_ns: end_ns.saturating_sub(start_ns),
+                attributes: span
+                    .attributes
+                    .iter()
+                    .map(|attribute| (attribute.key.clone(), attribute.value_string()))
+                    .collect(),
+            });
+        }
+    }
+    Ok(spans)
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_otlp_links_parent_and_cross_service_edges() {
+        let input = r#"{
+          "resourceSpans": [
+            {
+              "resource": { "attributes": [{ "key": "service.name", "value": { "stringValue": "frontend" } }] },
+              "scopeSpans": [{ "spans": [{
+                "traceId": "5b8efff798038103d269b633813fc60c",
+                "spanId": "eee19b7ec3c1b174",
+                "name": "GET /checkout",
+                "startTimeUnixNano": "1704110400000000000",
+                "endTimeUnixNano": "1704110400050000000",
+                "attributes": [{ "key": "http.method", "value": { "stringValue": "GET" } }]
+              }]}]
+            },
+            {
+              "resource": { "attributes": [{ "key": "service.name", "value": { "stringValue": "payments" } }] },
+              "scopeSpans": [{ "spans": [{
+                "traceId": "5b8efff798038103d269b633813fc60c",
+                "spanId": "eee19b7ec3c1b175",
+                "parentSpanId": "eee19b7ec3c1b174",
+                "name": "charge",
+                "startTimeUnixNano": "1704110400010000000",
+                "endTimeUnixNano": "1704110400030000000"
+              }]}]
+            }
+          ]
+        }"#;
+
+        let events = parse_events(input, ImportFormat::Otlp).unwrap();
+        assert_eq!(events.len(), 2);
+
+        let (root, child) = (&events[0], &events[1]);
+        assert_eq!(
+            root.trace_id,
+            Uuid::parse_str("5b8efff7-9803-8103-d269-b633813fc60c").unwrap()
+        );
+        assert!(matches!(root.kind, EventKind::HttpRequest { .. }));
+        assert_eq!(root.parent_id, None);
+
+        assert_eq!(child.parent_id, Some(root.id));
+        assert_eq!(child.metadata.service_name, "payments");
+        assert_eq!(child.metadata.duration_ns, Some(20_000_000));
+        assert_eq!(
+            child.metadata.upstream_span_id.as_deref(),
+            Some("eee19b7ec3c1b174")
+        );
+    }
+}
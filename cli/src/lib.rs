@@ -1,2 +1,9 @@
+pub mod client;
+pub mod diff;
+pub mod export;
+pub mod import;
+pub mod offline;
+#[cfg(feature = "otel")]
+pub mod otel;
 pub mod server;
 pub mod tui;
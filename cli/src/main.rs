@@ -1,15 +1,26 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write as _;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
-use reqwest::Client;
-use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use raceway::{server, tui};
+use raceway::client::{FullTraceAnalysis, RacewayApiClient, TraceListFilter};
+use raceway::diff;
+use raceway::export::{self, ExportFormat};
+use raceway::import::ImportFormat;
+use raceway::{import, offline, server, tui};
+use raceway_core::bundle::TraceBundle;
 use raceway_core::Config;
 
+use crate::template::TemplateTarget;
+
+mod follow;
+mod template;
+mod top;
+mod watch;
+
 const DEFAULT_PAGE_SIZE: usize = 20;
 
 #[derive(Parser)]
@@ -39,6 +50,9 @@ enum Commands {
         /// Override server URL from config
         #[arg(short, long)]
         server: Option<String>,
+        /// Explore an exported trace bundle (or a directory of them) without a server
+        #[arg(long, conflicts_with = "server")]
+        file: Option<std::path::PathBuf>,
     },
 
     /// List traces (mirrors Web UI trace list)
@@ -49,9 +63,21 @@ enum Commands {
         /// Page size
         #[arg(long, default_value_t = DEFAULT_PAGE_SIZE)]
         page_size: usize,
+        /// Only traces with an event carrying this tag (key:value)
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only traces with events at or after this time (RFC 3339)
+        #[arg(long)]
+        since: Option<DateTime<Utc>>,
+        /// Only traces with events at or before this time (RFC 3339)
+        #[arg(long)]
+        until: Option<DateTime<Utc>>,
         /// Output raw JSON response
         #[arg(long)]
         json: bool,
+        /// Render with a template: a built-in name (slack, logfmt), a template file, or inline Handlebars
+        #[arg(long, conflicts_with = "json")]
+        template: Option<String>,
         /// Override server URL from config
         #[arg(long)]
         server: Option<String>,
@@ -64,9 +90,53 @@ enum Commands {
         /// Output raw JSON response
         #[arg(long)]
         json: bool,
+        /// Render with a template: a built-in name (slack, logfmt), a template file, or inline Handlebars
+        #[arg(long, conflicts_with = "json")]
+        template: Option<String>,
         /// Show the first N events (default 0 = summary only)
         #[arg(long, default_value_t = 0)]
         events: usize,
+        /// Payload keys to summarize for Custom/FunctionCall events (comma-separated, dotted paths allowed)
+        #[arg(long, value_delimiter = ',')]
+        fields: Vec<String>,
+        /// Keep polling and redraw the summary, highlighting what changed since the last poll
+        #[arg(long, conflicts_with_all = ["json", "template"])]
+        follow: bool,
+        /// Poll interval in seconds for --follow
+        #[arg(long, default_value_t = 2, requires = "follow")]
+        interval: u64,
+        /// Override server URL from config
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Show the chain of events through which one event causally leads to another
+    Path {
+        #[arg(long = "trace")]
+        trace_id: String,
+        /// Event the chain starts at
+        #[arg(long)]
+        from: String,
+        /// Event the chain ends at
+        #[arg(long)]
+        to: String,
+        #[arg(long)]
+        json: bool,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Compare two traces: events unique to either side, new races and critical-path change
+    Diff {
+        /// Trace to compare against
+        #[arg(long)]
+        baseline: String,
+        /// Trace checked for changes
+        #[arg(long)]
+        candidate: String,
+        /// Output the diff as JSON
+        #[arg(long)]
+        json: bool,
         /// Override server URL from config
         #[arg(long)]
         server: Option<String>,
@@ -92,6 +162,9 @@ enum Commands {
     Races {
         #[arg(long)]
         json: bool,
+        /// Render with a template: a built-in name (slack, logfmt), a template file, or inline Handlebars
+        #[arg(long, conflicts_with = "json")]
+        template: Option<String>,
         #[arg(long)]
         server: Option<String>,
     },
@@ -104,6 +177,46 @@ enum Commands {
         server: Option<String>,
     },
 
+    /// Show potential deadlocks from conflicting lock acquisition orders
+    Deadlocks {
+        #[arg(long)]
+        json: bool,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Show per-lock contention: acquisitions, contending threads and hold times
+    Locks {
+        #[arg(long)]
+        json: bool,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Live contention dashboard (hotspots, busiest services, races, ingest rate)
+    Top {
+        /// Refresh interval in seconds
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Exit after N refreshes (default: run until interrupted)
+        #[arg(long)]
+        count: Option<usize>,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Print new traces as they arrive, and traces that pick up race conditions
+    Watch {
+        /// Poll interval in seconds
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Exit with an error the first time a race is detected
+        #[arg(long)]
+        on_race: bool,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
     /// Show service health status
     Health {
         /// Time window in minutes
@@ -120,6 +233,9 @@ enum Commands {
         /// Number of slow traces to include
         #[arg(long, default_value_t = 50)]
         limit: usize,
+        /// Also show per-minute event and trace counts for the last N minutes
+        #[arg(long)]
+        buckets: Option<u64>,
         #[arg(long)]
         json: bool,
         #[arg(long)]
@@ -142,15 +258,62 @@ enum Commands {
         server: Option<String>,
     },
 
-    /// Export trace data to a file
+    /// Export trace data to a file, or to an OpenTelemetry collector
     Export {
-        #[arg(short, long)]
+        #[arg(short, long, alias = "trace")]
         trace_id: String,
+        #[arg(short, long, required_unless_present = "otlp")]
+        output: Option<String>,
+        /// Send the trace as OTLP spans to this OTLP/HTTP collector endpoint
+        /// instead of writing a file (requires the `otel` feature)
+        #[arg(long, conflicts_with_all = ["output", "bundle"])]
+        otlp: Option<String>,
+        /// Write a self-contained bundle (events, edges, baselines) for `import-bundle`
+        #[arg(long)]
+        bundle: bool,
+        /// Output format: the raw API response (json), one event per line (jsonl),
+        /// id/timestamp/service/kind/location rows (csv), or a Graphviz causal graph (dot)
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json, conflicts_with = "bundle")]
+        format: ExportFormat,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Load a bundle written by `export --bundle` into a server and re-run its analysis
+    ImportBundle {
+        /// Path to the bundle file
         #[arg(short, long)]
-        output: String,
+        input: std::path::PathBuf,
+        #[arg(long)]
+        json: bool,
         #[arg(long)]
         server: Option<String>,
     },
+
+    /// Import a Jaeger or OTLP JSON export and send its spans to the server as events
+    Import {
+        /// Path to the exported JSON file
+        #[arg(short, long)]
+        input: std::path::PathBuf,
+        /// Format of the input file
+        #[arg(short, long, value_enum)]
+        format: ImportFormat,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Run storage maintenance on the server's backend (Postgres VACUUM ANALYZE + REINDEX,
+    /// in-memory compaction)
+    Vacuum {
+        #[arg(long)]
+        json: bool,
+        #[arg(long)]
+        server: Option<String>,
+    },
+
+    /// Print the JSON Schema for the event wire format
+    #[cfg(feature = "schema")]
+    Schema,
 }
 
 #[derive(Subcommand)]
@@ -179,307 +342,6 @@ enum ServiceCommand {
     },
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct ApiResponse<T> {
-    success: bool,
-    data: Option<T>,
-    error: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct TracesListData {
-    total_traces: usize,
-    page: usize,
-    page_size: usize,
-    total_pages: usize,
-    traces: Vec<TraceMetadata>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct TraceMetadata {
-    trace_id: String,
-    event_count: usize,
-    first_timestamp: String,
-    last_timestamp: String,
-    service_count: usize,
-    services: Vec<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct FullTraceAnalysis {
-    trace_id: String,
-    #[serde(default)]
-    events: Vec<Event>,
-    #[serde(default)]
-    audit_trails: HashMap<String, Vec<VariableAccess>>,
-    analysis: TraceAnalysisSummary,
-    #[serde(default)]
-    critical_path: Option<CriticalPath>,
-    #[serde(default)]
-    anomalies: Vec<DetectedAnomaly>,
-    #[serde(default)]
-    dependencies: Option<TraceDependencies>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct Event {
-    id: String,
-    #[serde(default)]
-    timestamp: String,
-    #[serde(default)]
-    trace_id: String,
-    #[serde(default)]
-    metadata: EventMetadata,
-    #[serde(default)]
-    kind: Value,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct EventMetadata {
-    #[serde(default)]
-    thread_id: String,
-    #[serde(default)]
-    service_name: String,
-    #[serde(default)]
-    duration_ns: Option<u64>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct TraceAnalysisSummary {
-    #[serde(default)]
-    concurrent_events: usize,
-    #[serde(default)]
-    potential_races: usize,
-    #[serde(default)]
-    anomalies: Vec<String>,
-    #[serde(default)]
-    race_details: Vec<RaceDetail>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct RaceDetail {
-    variable: String,
-    event1_thread: String,
-    event2_thread: String,
-    event1_location: String,
-    event2_location: String,
-    severity: String,
-    description: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct CriticalPath {
-    #[serde(default)]
-    path_events: usize,
-    #[serde(default)]
-    total_duration_ms: f64,
-    #[serde(default)]
-    trace_total_duration_ms: f64,
-    #[serde(default)]
-    percentage_of_total: f64,
-    #[serde(default)]
-    path: Vec<PathEvent>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct PathEvent {
-    id: String,
-    kind: String,
-    location: String,
-    timestamp: String,
-    #[serde(default)]
-    duration_ms: f64,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct DetectedAnomaly {
-    event_id: String,
-    event_kind: String,
-    severity: String,
-    description: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct TraceDependencies {
-    services: Vec<ServiceInfo>,
-    dependencies: Vec<ServiceDependency>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct ServiceInfo {
-    name: String,
-    #[serde(default)]
-    event_count: usize,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct ServiceDependency {
-    from: String,
-    to: String,
-    call_count: usize,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServicesListData {
-    total_services: usize,
-    services: Vec<ServiceListItem>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServiceListItem {
-    name: String,
-    event_count: usize,
-    trace_count: usize,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServiceTracesData {
-    service_name: String,
-    total_traces: usize,
-    page: usize,
-    page_size: usize,
-    total_pages: usize,
-    traces: Vec<TraceMetadata>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServiceDependenciesData {
-    service_name: String,
-    calls_to: Vec<ServiceDependencyInfo>,
-    called_by: Vec<ServiceDependencyInfo>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServiceDependencyInfo {
-    to: String,
-    total_calls: usize,
-    trace_count: usize,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct GlobalRacesData {
-    total_races: usize,
-    races: Vec<GlobalRace>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct GlobalRace {
-    variable: String,
-    trace_count: usize,
-    access_count: usize,
-    access_types: Vec<String>,
-    thread_count: usize,
-    severity: String,
-    trace_ids: Vec<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct HotspotsData {
-    top_variables: Vec<VariableHotspot>,
-    top_service_calls: Vec<ServiceCallHotspot>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct VariableHotspot {
-    variable: String,
-    access_count: usize,
-    trace_count: usize,
-    services: Vec<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServiceCallHotspot {
-    from_service: String,
-    to_service: String,
-    call_count: usize,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServiceHealthEntry {
-    name: String,
-    status: String,
-    trace_count: usize,
-    last_activity: String,
-    avg_events_per_trace: f64,
-    minutes_since_last_activity: f64,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct PerformanceMetrics {
-    trace_latency: TraceLatencyMetrics,
-    event_performance: EventPerformanceMetrics,
-    service_latency: Vec<ServiceLatency>,
-    throughput: ThroughputMetrics,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct TraceLatencyMetrics {
-    avg_ms: f64,
-    p50_ms: f64,
-    p95_ms: f64,
-    p99_ms: f64,
-    slowest_traces: Vec<SlowTrace>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct SlowTrace {
-    trace_id: String,
-    duration_ms: f64,
-    services: Vec<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct EventPerformanceMetrics {
-    by_type: Vec<EventTypePerformance>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct EventTypePerformance {
-    #[serde(rename = "type")]
-    type_name: String,
-    count: usize,
-    avg_duration_ms: f64,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ServiceLatency {
-    service: String,
-    avg_duration_ms: f64,
-    event_count: usize,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ThroughputMetrics {
-    events_per_second: f64,
-    traces_per_second: f64,
-    time_range_seconds: f64,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct DistributedEdgesData {
-    total_edges: usize,
-    edges: Vec<DistributedEdge>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct DistributedEdge {
-    from_service: String,
-    to_service: String,
-    link_type: String,
-    call_count: usize,
-}
-
-#[derive(Debug, Deserialize, Serialize, Default)]
-struct VariableAccess {
-    event_id: String,
-    timestamp: String,
-    thread_id: String,
-    service_name: String,
-    access_type: String,
-    #[serde(default)]
-    location: String,
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -513,7 +375,16 @@ async fn main() -> Result<()> {
             );
             server::start_server(config).await?;
         }
-        Commands::Tui { server } => {
+        Commands::Tui {
+            file: Some(file), ..
+        } => {
+            println!(
+                "🎨 Launching Raceway TUI offline (loading {})...",
+                file.display()
+            );
+            offline::launch_offline_tui(&file).await?;
+        }
+        Commands::Tui { server, file: None } => {
             let server_url = server.unwrap_or(default_server);
             println!("🎨 Launching Raceway TUI (connecting to {})...", server_url);
             tui::launch_tui(&server_url).await?;
@@ -521,34 +392,68 @@ async fn main() -> Result<()> {
         Commands::Traces {
             page,
             page_size,
+            tag,
+            since,
+            until,
             json,
+            template,
             server,
         } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_traces(&client, &server_url, page, page_size, json).await?;
+            let api = RacewayApiClient::new(&server_url);
+            let filter = TraceListFilter { tag, since, until };
+            handle_traces(&api, page, page_size, &filter, json, template.as_deref()).await?;
         }
         Commands::Trace {
             trace_id,
             json,
+            template,
             events,
+            fields,
+            follow,
+            interval,
             server,
         } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_trace(&client, &server_url, &trace_id, json, events).await?;
+            let api = RacewayApiClient::new(&server_url);
+            if follow {
+                follow::run_follow(&api, &trace_id, interval, events, &fields).await?;
+            } else {
+                handle_trace(&api, &trace_id, json, template.as_deref(), events, &fields).await?;
+            }
+        }
+        Commands::Path {
+            trace_id,
+            from,
+            to,
+            json,
+            server,
+        } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            handle_path(&api, &trace_id, &from, &to, json).await?;
+        }
+        Commands::Diff {
+            baseline,
+            candidate,
+            json,
+            server,
+        } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            handle_diff(&api, &baseline, &candidate, json).await?;
         }
         Commands::Services { json, server } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_services(&client, &server_url, json).await?;
+            let api = RacewayApiClient::new(&server_url);
+            handle_services(&api, json).await?;
         }
         Commands::Service { server, action } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
+            let api = RacewayApiClient::new(&server_url);
             match action {
                 ServiceCommand::Overview { name, json } => {
-                    handle_service_overview(&client, &server_url, &name, json).await?;
+                    handle_service_overview(&api, &name, json).await?;
                 }
                 ServiceCommand::Traces {
                     name,
@@ -556,23 +461,54 @@ async fn main() -> Result<()> {
                     page_size,
                     json,
                 } => {
-                    handle_service_traces(&client, &server_url, &name, page, page_size, json)
-                        .await?;
+                    handle_service_traces(&api, &name, page, page_size, json).await?;
                 }
                 ServiceCommand::Dependencies { name, json } => {
-                    handle_service_dependencies(&client, &server_url, &name, json).await?;
+                    handle_service_dependencies(&api, &name, json).await?;
                 }
             }
         }
-        Commands::Races { json, server } => {
+        Commands::Races {
+            json,
+            template,
+            server,
+        } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_global_races(&client, &server_url, json).await?;
+            let api = RacewayApiClient::new(&server_url);
+            handle_global_races(&api, json, template.as_deref()).await?;
         }
         Commands::Hotspots { json, server } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_hotspots(&client, &server_url, json).await?;
+            let api = RacewayApiClient::new(&server_url);
+            handle_hotspots(&api, json).await?;
+        }
+        Commands::Deadlocks { json, server } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            handle_deadlocks(&api, json).await?;
+        }
+        Commands::Locks { json, server } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            handle_locks(&api, json).await?;
+        }
+        Commands::Top {
+            interval,
+            count,
+            server,
+        } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            top::run_top(&api, interval, count).await?;
+        }
+        Commands::Watch {
+            interval,
+            on_race,
+            server,
+        } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            watch::run_watch(&api, interval, on_race).await?;
         }
         Commands::Health {
             window,
@@ -580,36 +516,74 @@ async fn main() -> Result<()> {
             server,
         } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_health(&client, &server_url, window, json).await?;
+            let api = RacewayApiClient::new(&server_url);
+            handle_health(&api, window, json).await?;
         }
         Commands::Performance {
             limit,
+            buckets,
             json,
             server,
         } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_performance(&client, &server_url, limit, json).await?;
+            let api = RacewayApiClient::new(&server_url);
+            handle_performance(&api, limit, buckets, json).await?;
         }
         Commands::Edges { json, server } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
-            handle_edges(&client, &server_url, json).await?;
+            let api = RacewayApiClient::new(&server_url);
+            handle_edges(&api, json).await?;
         }
         Commands::Analyze { trace_id, server } => {
             let server_url = server.unwrap_or(default_server);
-            let client = Client::new();
+            let api = RacewayApiClient::new(&server_url);
             // Preserve legacy behaviour by printing JSON
-            handle_trace(&client, &server_url, &trace_id, true, 0).await?;
+            handle_trace(&api, &trace_id, true, None, 0, &[]).await?;
         }
         Commands::Export {
             trace_id,
             output,
+            otlp,
+            bundle,
+            format,
             server,
         } => {
             let server_url = server.unwrap_or(default_server);
-            export_trace(&trace_id, &output, &server_url).await?;
+            let api = RacewayApiClient::new(&server_url);
+            match (otlp, output) {
+                (Some(endpoint), _) => export_otlp(&api, &trace_id, &endpoint).await?,
+                (None, Some(output)) if bundle => export_bundle(&api, &trace_id, &output).await?,
+                (None, Some(output)) => export_trace(&api, &trace_id, &output, format).await?,
+                (None, None) => unreachable!("clap requires --output without --otlp"),
+            }
+        }
+        Commands::ImportBundle {
+            input,
+            json,
+            server,
+        } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            import_bundle(&api, &input, json).await?;
+        }
+        Commands::Import {
+            input,
+            format,
+            server,
+        } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            import_events(&api, &input, format).await?;
+        }
+        Commands::Vacuum { json, server } => {
+            let server_url = server.unwrap_or(default_server);
+            let api = RacewayApiClient::new(&server_url);
+            handle_vacuum(&api, json).await?;
+        }
+        #[cfg(feature = "schema")]
+        Commands::Schema => {
+            let schema = raceway_core::event::wire_schema();
+            println!("{}", serde_json::to_string_pretty(&schema)?);
         }
     }
 
@@ -617,32 +591,25 @@ async fn main() -> Result<()> {
 }
 
 async fn handle_traces(
-    client: &Client,
-    server: &str,
+    api: &RacewayApiClient,
     page: usize,
     page_size: usize,
+    filter: &TraceListFilter,
     json: bool,
+    template: Option<&str>,
 ) -> Result<()> {
-    let url = format!(
-        "{}/api/traces?page={}&page_size={}",
-        server, page, page_size
-    );
-    let response: ApiResponse<TracesListData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Trace list response missing data"))?;
+    let data = api.list_traces(page, page_size, filter).await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
         return Ok(());
     }
 
+    if let Some(template) = template {
+        template::print_rendered(&template::render(TemplateTarget::Traces, template, &data)?);
+        return Ok(());
+    }
+
     println!(
         "📋 Traces (page {} of {}, total {} traces)",
         data.page, data.total_pages, data.total_traces
@@ -654,7 +621,7 @@ async fn handle_traces(
 
     for trace in data.traces {
         println!(
-            "{:<38} {:>8} {:>8} {:<24} {}",
+            "{:<38} {:>8} {:>8} {:<24} {}{}",
             trace.trace_id,
             trace.event_count,
             trace.service_count,
@@ -663,7 +630,8 @@ async fn handle_traces(
                 "-".into()
             } else {
                 trace.services.join(",")
-            }
+            },
+            if trace.truncated { " (truncated)" } else { "" }
         );
     }
 
@@ -671,47 +639,66 @@ async fn handle_traces(
 }
 
 async fn handle_trace(
-    client: &Client,
-    server: &str,
+    api: &RacewayApiClient,
     trace_id: &str,
     json: bool,
+    template: Option<&str>,
     show_events: usize,
+    fields: &[String],
 ) -> Result<()> {
-    let url = format!("{}/api/traces/{}", server, trace_id);
-    let response: ApiResponse<FullTraceAnalysis> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Trace response missing data"))?;
+    // JSON and template output keep every event; the summary only needs the first few
+    let data = if json || template.is_some() {
+        api.get_trace(trace_id).await?
+    } else {
+        api.get_trace_summary(trace_id, show_events).await?
+    };
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
         return Ok(());
     }
 
-    println!("🔍 Trace {}", data.trace_id);
-    println!(
+    if let Some(template) = template {
+        template::print_rendered(&template::render(TemplateTarget::Trace, template, &data)?);
+        return Ok(());
+    }
+
+    print!("{}", render_trace_summary(&data, show_events, fields));
+    Ok(())
+}
+
+/// Plain-text trace summary printed by `raceway trace`: counts, critical path,
+/// the first few races and anomalies, and optionally the first `show_events` events
+fn render_trace_summary(data: &FullTraceAnalysis, show_events: usize, fields: &[String]) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "🔍 Trace {}", data.trace_id);
+    let _ = writeln!(
+        out,
         "• Events: {}  • Potential races: {}  • Concurrent events: {}  • Anomalies: {}",
-        data.events.len(),
+        data.total_events,
         data.analysis.potential_races,
         data.analysis.concurrent_events,
         data.anomalies.len()
     );
+    if data.analysis.truncated {
+        let _ = writeln!(
+            out,
+            "• Race results are partial: the server stopped at race_detection.max_pairs_per_trace"
+        );
+    }
 
     if let Some(cp) = &data.critical_path {
-        println!(
+        let _ = writeln!(
+            out,
             "• Critical path: {:.2} ms ({:.1}% of {:.2} ms total)",
             cp.total_duration_ms, cp.percentage_of_total, cp.trace_total_duration_ms
         );
     }
 
     if let Some(deps) = &data.dependencies {
-        println!(
+        let _ = writeln!(
+            out,
             "• Services: {}  • Cross-service calls: {}",
             deps.services.len(),
             deps.dependencies.len()
@@ -719,9 +706,10 @@ async fn handle_trace(
     }
 
     if !data.analysis.race_details.is_empty() {
-        println!("\n⚠️  Race conditions:");
+        let _ = writeln!(out, "\n⚠️  Race conditions:");
         for detail in data.analysis.race_details.iter().take(5) {
-            println!(
+            let _ = writeln!(
+                out,
                 "  [{}] {} ({} ↔ {}, {} ↔ {})",
                 detail.severity,
                 detail.variable,
@@ -730,9 +718,13 @@ async fn handle_trace(
                 detail.event1_location,
                 detail.event2_location
             );
+            if let Some(recommendation) = &detail.recommendation {
+                let _ = writeln!(out, "      💡 {}", recommendation);
+            }
         }
         if data.analysis.race_details.len() > 5 {
-            println!(
+            let _ = writeln!(
+                out,
                 "  … {} more (use --json for full details)",
                 data.analysis.race_details.len() - 5
             );
@@ -740,15 +732,17 @@ async fn handle_trace(
     }
 
     if !data.anomalies.is_empty() {
-        println!("\n📈 Performance anomalies:");
+        let _ = writeln!(out, "\n📈 Performance anomalies:");
         for anomaly in data.anomalies.iter().take(5) {
-            println!(
+            let _ = writeln!(
+                out,
                 "  [{}] {} — {}",
                 anomaly.severity, anomaly.event_kind, anomaly.description
             );
         }
         if data.anomalies.len() > 5 {
-            println!(
+            let _ = writeln!(
+                out,
                 "  … {} more (use --json for full details)",
                 data.anomalies.len() - 5
             );
@@ -756,17 +750,11 @@ async fn handle_trace(
     }
 
     if show_events > 0 {
-        println!("\n🪵 Events (showing first {}):", show_events);
+        let _ = writeln!(out, "\n🪵 Events (showing first {}):", show_events);
         for event in data.events.iter().take(show_events) {
-            let kind = if let Some(obj) = event.kind.as_object() {
-                obj.keys()
-                    .next()
-                    .cloned()
-                    .unwrap_or_else(|| "Unknown".to_string())
-            } else {
-                "Unknown".to_string()
-            };
-            println!(
+            let kind = event_kind_summary(&event.kind, fields);
+            let _ = writeln!(
+                out,
                 "  {}  {:<12} {:<16} {}",
                 event.timestamp,
                 short_id(&event.id),
@@ -775,28 +763,19 @@ async fn handle_trace(
             );
         }
         if data.events.len() > show_events {
-            println!(
+            let _ = writeln!(
+                out,
                 "  … {} more events (use --json to dump everything)",
                 data.events.len() - show_events
             );
         }
     }
 
-    Ok(())
+    out
 }
 
-async fn handle_services(client: &Client, server: &str, json: bool) -> Result<()> {
-    let url = format!("{}/api/services", server);
-    let response: ApiResponse<ServicesListData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Services response missing data"))?;
+async fn handle_services(api: &RacewayApiClient, json: bool) -> Result<()> {
+    let data = api.services().await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
@@ -815,21 +794,11 @@ async fn handle_services(client: &Client, server: &str, json: bool) -> Result<()
     Ok(())
 }
 
-async fn handle_service_overview(
-    client: &Client,
-    server: &str,
-    name: &str,
-    json: bool,
-) -> Result<()> {
-    let services = fetch_services(client, server).await?;
-    let service = services
-        .services
-        .iter()
-        .find(|svc| svc.name == name)
-        .ok_or_else(|| anyhow!("Service '{}' not found", name))?;
+async fn handle_service_overview(api: &RacewayApiClient, name: &str, json: bool) -> Result<()> {
+    let service = api.service_overview(name).await?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(service)?);
+        println!("{}", serde_json::to_string_pretty(&service)?);
         return Ok(());
     }
 
@@ -838,40 +807,29 @@ async fn handle_service_overview(
     println!("• Traces: {}", service.trace_count);
     println!(
         "• Avg events per trace: {:.1}",
-        if service.trace_count > 0 {
-            service.event_count as f64 / service.trace_count as f64
-        } else {
-            0.0
-        }
+        service.avg_events_per_trace
     );
 
+    let latency = &service.latency;
+    match (latency.p50_ms, latency.p95_ms, latency.p99_ms) {
+        (Some(p50), Some(p95), Some(p99)) => println!(
+            "• Latency: p50 {:.2}ms, p95 {:.2}ms, p99 {:.2}ms ({} events)",
+            p50, p95, p99, latency.samples
+        ),
+        _ => println!("• Latency: no events report a duration"),
+    }
+
     Ok(())
 }
 
 async fn handle_service_traces(
-    client: &Client,
-    server: &str,
+    api: &RacewayApiClient,
     name: &str,
     page: usize,
     page_size: usize,
     json: bool,
 ) -> Result<()> {
-    let url = format!(
-        "{}/api/services/{}/traces?page={}&page_size={}",
-        server,
-        urlencoding::encode(name),
-        page,
-        page_size
-    );
-    let response: ApiResponse<ServiceTracesData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Service traces response missing data"))?;
+    let data = api.service_traces(name, page, page_size).await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
@@ -882,10 +840,7 @@ async fn handle_service_traces(
         "🧵 Traces for {} (page {} of {}, total {})",
         data.service_name, data.page, data.total_pages, data.total_traces
     );
-    println!(
-        "{:<12} {:>8} {:>8} LAST SEEN",
-        "TRACE", "EVENTS", "SVCS"
-    );
+    println!("{:<12} {:>8} {:>8} LAST SEEN", "TRACE", "EVENTS", "SVCS");
     for trace in data.traces {
         println!(
             "{:<12} {:>8} {:>8} {}",
@@ -899,26 +854,8 @@ async fn handle_service_traces(
     Ok(())
 }
 
-async fn handle_service_dependencies(
-    client: &Client,
-    server: &str,
-    name: &str,
-    json: bool,
-) -> Result<()> {
-    let url = format!(
-        "{}/api/services/{}/dependencies",
-        server,
-        urlencoding::encode(name)
-    );
-    let response: ApiResponse<ServiceDependenciesData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Service dependency response missing data"))?;
+async fn handle_service_dependencies(api: &RacewayApiClient, name: &str, json: bool) -> Result<()> {
+    let data = api.service_dependencies(name).await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
@@ -953,23 +890,23 @@ async fn handle_service_dependencies(
     Ok(())
 }
 
-async fn handle_global_races(client: &Client, server: &str, json: bool) -> Result<()> {
-    let url = format!("{}/api/distributed/global-races", server);
-    let response: ApiResponse<GlobalRacesData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Global races response missing data"))?;
+async fn handle_global_races(
+    api: &RacewayApiClient,
+    json: bool,
+    template: Option<&str>,
+) -> Result<()> {
+    let data = api.global_races().await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
         return Ok(());
     }
 
+    if let Some(template) = template {
+        template::print_rendered(&template::render(TemplateTarget::Races, template, &data)?);
+        return Ok(());
+    }
+
     println!("🌐 Global race conditions ({} total)", data.total_races);
     if data.races.is_empty() {
         println!("No concurrent access issues detected.");
@@ -990,17 +927,8 @@ async fn handle_global_races(client: &Client, server: &str, json: bool) -> Resul
     Ok(())
 }
 
-async fn handle_hotspots(client: &Client, server: &str, json: bool) -> Result<()> {
-    let url = format!("{}/api/distributed/hotspots", server);
-    let response: ApiResponse<HotspotsData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Hotspots response missing data"))?;
+async fn handle_hotspots(api: &RacewayApiClient, json: bool) -> Result<()> {
+    let data = api.hotspots().await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
@@ -1017,7 +945,13 @@ async fn handle_hotspots(client: &Client, server: &str, json: bool) -> Result<()
                 var.variable,
                 var.access_count,
                 var.trace_count,
-                if var.services.is_empty() {
+                if !var.accesses_by_service.is_empty() {
+                    var.accesses_by_service
+                        .iter()
+                        .map(|(service, count)| format!("{}×{}", service, count))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                } else if var.services.is_empty() {
                     "-".into()
                 } else {
                     var.services.join(",")
@@ -1041,20 +975,180 @@ async fn handle_hotspots(client: &Client, server: &str, json: bool) -> Result<()
     Ok(())
 }
 
-async fn handle_health(client: &Client, server: &str, window: u64, json: bool) -> Result<()> {
-    let url = format!(
-        "{}/api/services/health?time_window_minutes={}",
-        server, window
+async fn handle_path(
+    api: &RacewayApiClient,
+    trace_id: &str,
+    from: &str,
+    to: &str,
+    json: bool,
+) -> Result<()> {
+    let events = api.causal_path(trace_id, from, to).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&events)?);
+        return Ok(());
+    }
+
+    println!(
+        "🔗 {} leads to {} through {} events:",
+        short_id(from),
+        short_id(to),
+        events.len()
+    );
+    for (i, event) in events.iter().enumerate() {
+        println!(
+            "  {} {}  {:<12} {:<16} [{}] {}",
+            if i == 0 { " " } else { "↓" },
+            event.timestamp,
+            short_id(&event.id),
+            event.metadata.service_name,
+            event.metadata.thread_id,
+            event_kind_summary(&event.kind, &[])
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_diff(
+    api: &RacewayApiClient,
+    baseline: &str,
+    candidate: &str,
+    json: bool,
+) -> Result<()> {
+    let baseline = api.get_trace(baseline).await?;
+    let candidate = api.get_trace(candidate).await?;
+    let diff = diff::diff_traces(&baseline, &candidate);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    println!(
+        "🔀 {} ({} events) → {} ({} events)",
+        short_id(&diff.baseline),
+        diff.baseline_events,
+        short_id(&diff.candidate),
+        diff.candidate_events
     );
-    let response: ApiResponse<Vec<ServiceHealthEntry>> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
+
+    match (
+        diff.baseline_critical_path_ms,
+        diff.candidate_critical_path_ms,
+        diff.critical_path_delta_ms,
+    ) {
+        (Some(before), Some(after), Some(delta)) => println!(
+            "• Critical path: {:.2} ms → {:.2} ms ({:+.2} ms)",
+            before, after, delta
+        ),
+        _ => println!("• Critical path: not available for both traces"),
+    }
+
+    for (label, deltas) in [
+        ("➖ Only in baseline", &diff.only_in_baseline),
+        ("➕ Only in candidate", &diff.only_in_candidate),
+    ] {
+        if deltas.is_empty() {
+            continue;
+        }
+        println!("\n{}:", label);
+        for delta in deltas.iter().take(10) {
+            println!(
+                "  {:>4}× {:<14} {}",
+                delta.count, delta.kind, delta.location
+            );
+        }
+        if deltas.len() > 10 {
+            println!(
+                "  … {} more (use --json for full details)",
+                deltas.len() - 10
+            );
+        }
     }
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Health response missing data"))?;
+
+    if diff.new_races.is_empty() {
+        println!("\n✅ No new races in the candidate");
+    } else {
+        println!("\n⚠️  New races in the candidate:");
+        for detail in &diff.new_races {
+            println!(
+                "  [{}] {} ({} ↔ {})",
+                detail.severity, detail.variable, detail.event1_location, detail.event2_location
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_deadlocks(api: &RacewayApiClient, json: bool) -> Result<()> {
+    let data = api.deadlocks().await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+        return Ok(());
+    }
+
+    if data.violations.is_empty() {
+        println!("✅ No conflicting lock orders found");
+        return Ok(());
+    }
+
+    println!("🔒 {} potential deadlocks:", data.total_violations);
+    for violation in &data.violations {
+        println!("\n  {} ⇄ {}", violation.lock_a, violation.lock_b);
+        for observation in std::iter::once(&violation.a_then_b).chain(&violation.b_then_a) {
+            println!(
+                "    {} → {}  thread {} (trace {}, event {})",
+                observation.held,
+                observation.acquired,
+                observation.thread_id,
+                observation.trace_id,
+                observation.event_id
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_locks(api: &RacewayApiClient, json: bool) -> Result<()> {
+    let data = api.lock_stats().await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+        return Ok(());
+    }
+
+    if data.locks.is_empty() {
+        println!("✅ No lock events captured");
+        return Ok(());
+    }
+
+    println!("🔐 Lock contention ({} locks)", data.total_locks);
+    println!(
+        "{:<32} {:>9} {:>8} {:>7} {:>12} {:>11} {:>11}",
+        "LOCK", "ACQUIRES", "THREADS", "TRACES", "TOTAL HOLD", "MEAN HOLD", "MAX HOLD"
+    );
+    for lock in &data.locks {
+        println!(
+            "{:<32} {:>9} {:>8} {:>7} {:>10.1}ms {:>9.1}ms {:>9.1}ms",
+            lock.lock_id,
+            lock.acquisitions,
+            lock.thread_count,
+            lock.trace_count,
+            lock.total_hold_ms,
+            lock.mean_hold_ms,
+            lock.max_hold_ms
+        );
+    }
+
+    Ok(())
+}
+
+async fn handle_health(api: &RacewayApiClient, window: u64, json: bool) -> Result<()> {
+    let data = api.services_health(window).await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
@@ -1080,17 +1174,13 @@ async fn handle_health(client: &Client, server: &str, window: u64, json: bool) -
     Ok(())
 }
 
-async fn handle_performance(client: &Client, server: &str, limit: usize, json: bool) -> Result<()> {
-    let url = format!("{}/api/performance/metrics?limit={}", server, limit);
-    let response: ApiResponse<PerformanceMetrics> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Performance response missing data"))?;
+async fn handle_performance(
+    api: &RacewayApiClient,
+    limit: usize,
+    buckets: Option<u64>,
+    json: bool,
+) -> Result<()> {
+    let data = api.performance_metrics(limit, buckets).await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
@@ -1112,6 +1202,18 @@ async fn handle_performance(client: &Client, server: &str, limit: usize, json: b
         data.throughput.time_range_seconds
     );
 
+    if !data.throughput.buckets.is_empty() {
+        let counts: Vec<usize> = data.throughput.buckets.iter().map(|b| b.events).collect();
+        println!("\n📊 Events per minute: {}", sparkline(&counts));
+        println!("{:<8} {:>8} {:>8}", "MINUTE", "EVENTS", "TRACES");
+        // The sparkline already shows quiet minutes
+        for bucket in data.throughput.buckets.iter().filter(|b| b.events > 0) {
+            // "2024-01-15T10:29:00Z" -> "10:29"
+            let minute = bucket.minute.get(11..16).unwrap_or(&bucket.minute);
+            println!("{:<8} {:>8} {:>8}", minute, bucket.events, bucket.traces);
+        }
+    }
+
     if !data.trace_latency.slowest_traces.is_empty() {
         println!("\n🐢 Slowest traces:");
         for trace in data.trace_latency.slowest_traces.iter().take(10) {
@@ -1142,17 +1244,18 @@ async fn handle_performance(client: &Client, server: &str, limit: usize, json: b
     Ok(())
 }
 
-async fn handle_edges(client: &Client, server: &str, json: bool) -> Result<()> {
-    let url = format!("{}/api/distributed/edges", server);
-    let response: ApiResponse<DistributedEdgesData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
-    }
-    let data = response
-        .data
-        .ok_or_else(|| anyhow!("Edges response missing data"))?;
+/// One block character per value, scaled to the largest value
+fn sparkline(values: &[usize]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|&value| BLOCKS[value * (BLOCKS.len() - 1) / max])
+        .collect()
+}
+
+async fn handle_edges(api: &RacewayApiClient, json: bool) -> Result<()> {
+    let data = api.distributed_edges().await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&data)?);
@@ -1170,50 +1273,124 @@ async fn handle_edges(client: &Client, server: &str, json: bool) -> Result<()> {
     Ok(())
 }
 
-async fn export_trace(trace_id: &str, output: &str, server: &str) -> Result<()> {
-    let client = Client::new();
-    let response = client
-        .get(format!("{}/api/traces/{}", server, trace_id))
-        .send()
-        .await
-        .context("Failed to request trace")?;
-
-    let trace_data = response
-        .text()
-        .await
-        .context("Failed to read response body")?;
+async fn handle_vacuum(api: &RacewayApiClient, json: bool) -> Result<()> {
+    let data = api.run_maintenance().await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+        return Ok(());
+    }
+
+    println!("🧹 Storage maintenance complete");
+    for step in &data.steps {
+        println!("  ✓ {}", step);
+    }
+    println!("Stale rows removed: {}", data.rows_removed);
+    if let Some(bytes) = data.reclaimed_bytes {
+        println!("Space reclaimed:    {} bytes", bytes);
+    }
+    println!("Traces kept:        {}", data.trace_count);
+    Ok(())
+}
+
+async fn export_trace(
+    api: &RacewayApiClient,
+    trace_id: &str,
+    output: &str,
+    format: ExportFormat,
+) -> Result<()> {
+    let trace_data = export::render_trace(&api.get_trace_raw(trace_id).await?, format)?;
     std::fs::write(output, trace_data).with_context(|| format!("Failed to write {}", output))?;
 
     println!("✅ Trace exported to {}", output);
     Ok(())
 }
 
-async fn get_json<T: DeserializeOwned>(client: &Client, url: &str) -> Result<ApiResponse<T>> {
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .with_context(|| format!("Failed to GET {}", url))?;
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(anyhow!("Request to {} failed: {} {}", url, status, text));
+#[cfg(feature = "otel")]
+async fn export_otlp(api: &RacewayApiClient, trace_id: &str, endpoint: &str) -> Result<()> {
+    let spans = raceway::otel::export_trace_otlp(api, trace_id, endpoint).await?;
+    println!("✅ Exported {} spans to {}", spans, endpoint);
+    Ok(())
+}
+
+#[cfg(not(feature = "otel"))]
+async fn export_otlp(_api: &RacewayApiClient, _trace_id: &str, _endpoint: &str) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "OTLP export requires building raceway with `--features otel`"
+    ))
+}
+
+async fn export_bundle(api: &RacewayApiClient, trace_id: &str, output: &str) -> Result<()> {
+    let bundle = api.export_bundle(trace_id).await?;
+
+    std::fs::write(output, serde_json::to_vec_pretty(&bundle)?)
+        .with_context(|| format!("Failed to write {}", output))?;
+
+    println!(
+        "✅ Bundle exported to {} ({} events, {} distributed edges, {} baselines)",
+        output,
+        bundle.events.len(),
+        bundle.distributed_edges.len(),
+        bundle.baselines.len()
+    );
+    Ok(())
+}
+
+async fn import_bundle(api: &RacewayApiClient, input: &std::path::Path, json: bool) -> Result<()> {
+    let contents = std::fs::read_to_string(input)
+        .with_context(|| format!("Failed to read {}", input.display()))?;
+    let bundle: TraceBundle = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse bundle {}", input.display()))?;
+
+    let data = api.import_bundle(&bundle).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+        return Ok(());
+    }
+
+    println!("📦 Imported trace {}", data.trace_id);
+    println!("Events:            {}", data.events_imported);
+    println!("Distributed spans: {}", data.spans_imported);
+    println!("Distributed edges: {}", data.edges_imported);
+    println!("Baselines:         {}", data.baselines_imported);
+    if data.reproduced {
+        println!("✅ Races and critical path match the exported analysis");
+    } else {
+        println!("⚠️  Analysis differs from the exported annotations");
     }
-    let parsed = response.json::<ApiResponse<T>>().await?;
-    Ok(parsed)
+    Ok(())
 }
 
-async fn fetch_services(client: &Client, server: &str) -> Result<ServicesListData> {
-    let url = format!("{}/api/services", server);
-    let response: ApiResponse<ServicesListData> = get_json(client, &url).await?;
-    if !response.success {
-        return Err(anyhow!(response
-            .error
-            .unwrap_or_else(|| "Unknown error".into())));
+const IMPORT_BATCH_SIZE: usize = 500;
+
+async fn import_events(
+    api: &RacewayApiClient,
+    input: &std::path::Path,
+    format: ImportFormat,
+) -> Result<()> {
+    let contents = std::fs::read_to_string(input)
+        .with_context(|| format!("Failed to read {}", input.display()))?;
+    let events = import::parse_events(&contents, format)
+        .with_context(|| format!("Failed to parse {}", input.display()))?;
+
+    let trace_count = events
+        .iter()
+        .map(|event| event.trace_id)
+        .collect::<HashSet<_>>()
+        .len();
+
+    for batch in events.chunks(IMPORT_BATCH_SIZE) {
+        api.submit_events(batch).await?;
     }
-    response
-        .data
-        .ok_or_else(|| anyhow!("Services response missing data"))
+
+    println!(
+        "✅ Imported {} events across {} traces from {}",
+        events.len(),
+        trace_count,
+        input.display()
+    );
+    Ok(())
 }
 
 fn short_id(id: &str) -> String {
@@ -1223,3 +1400,111 @@ fn short_id(id: &str) -> String {
         id[..8].to_string()
     }
 }
+
+/// Maximum number of payload keys shown when `--fields` is not given
+const DEFAULT_SUMMARY_FIELDS: usize = 3;
+const MAX_SUMMARY_VALUE_LEN: usize = 32;
+
+/// One-line description of an event kind, e.g. `Custom(order.placed) order_id=42 total=19.99`
+///
+/// `Custom` (`data`) and `FunctionCall` (`args`) payloads are summarized using
+/// `fields` (dotted paths) when given, otherwise their first few scalar keys.
+fn event_kind_summary(kind: &Value, fields: &[String]) -> String {
+    let Some((name, body)) = kind.as_object().and_then(|obj| obj.iter().next()) else {
+        return "Unknown".to_string();
+    };
+
+    let (label, payload) = match name.as_str() {
+        "Custom" => (
+            format!(
+                "Custom({})",
+                body.get("name").and_then(Value::as_str).unwrap_or("?")
+            ),
+            body.get("data"),
+        ),
+        "FunctionCall" => (
+            format!(
+                "FunctionCall({})",
+                body.get("function_name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("?")
+            ),
+            body.get("args"),
+        ),
+        _ => (name.clone(), body.get("data").or_else(|| body.get("args"))),
+    };
+
+    let Some(payload) = payload.filter(|payload| payload.is_object()) else {
+        return label;
+    };
+
+    let pairs: Vec<(String, &Value)> = if fields.is_empty() {
+        payload
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(_, value)| !value.is_object() && !value.is_array())
+            .take(DEFAULT_SUMMARY_FIELDS)
+            .map(|(key, value)| (key.clone(), value))
+            .collect()
+    } else {
+        fields
+            .iter()
+            .filter_map(|field| {
+                field
+                    .split('.')
+                    .try_fold(payload, |value, key| value.get(key))
+                    .map(|value| (field.clone(), value))
+            })
+            .collect()
+    };
+
+    let mut summary = label;
+    for (key, value) in pairs {
+        let mut rendered = match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        if rendered.chars().count() > MAX_SUMMARY_VALUE_LEN {
+            rendered = rendered
+                .chars()
+                .take(MAX_SUMMARY_VALUE_LEN)
+                .collect::<String>()
+                + "…";
+        }
+        summary.push_str(&format!(" {}={}", key, rendered));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn custom_event_summary_includes_selected_fields() {
+        let kind = json!({
+            "Custom": {
+                "name": "order.placed",
+                "data": {
+                    "order_id": 42,
+                    "customer": { "tier": "gold" },
+                    "note": "leave at door"
+                }
+            }
+        });
+        let fields = vec!["order_id".to_string(), "customer.tier".to_string()];
+
+        assert_eq!(
+            event_kind_summary(&kind, &fields),
+            "Custom(order.placed) order_id=42 customer.tier=gold"
+        );
+    }
+
+    #[test]
+    fn sparkline_scales_to_largest_value() {
+        assert_eq!(sparkline(&[0, 7, 14]), "▁▄█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+    }
+}
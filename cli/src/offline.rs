@@ -0,0 +1,96 @@
+//! Offline mode: explore exported trace bundles without a running server.
+//!
+//! Bundles are imported into an in-process engine on the memory backend, and
+//! the engine's router is served on an ephemeral loopback port so the TUI can
+//! drive it exactly as it would a remote server.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use raceway_core::bundle::TraceBundle;
+use raceway_core::{Config, RacewayEngine};
+
+use crate::server::{build_router, init_engine};
+use crate::tui;
+
+/// Read one bundle file, or every `.json` bundle in a directory (in name order)
+pub fn load_bundles(path: &Path) -> Result<Vec<TraceBundle>> {
+    let files: Vec<PathBuf> = if path.is_dir() {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory {}", path.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    if files.is_empty() {
+        return Err(anyhow!("No .json bundles found in {}", path.display()));
+    }
+
+    files
+        .iter()
+        .map(|file| {
+            let contents = std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse bundle {}", file.display()))
+        })
+        .collect()
+}
+
+/// Configuration for an offline engine: memory storage, no auth
+pub fn offline_config() -> Config {
+    let mut config = Config::default();
+    config.storage.backend = "memory".into();
+    config.server.auth_enabled = false;
+    config.server.rate_limit_enabled = false;
+    config.server.cors_enabled = false;
+    config.server.verbose = false;
+    config
+}
+
+/// Start an in-process engine seeded with the bundles at `path`
+pub async fn init_offline_engine(config: &Config, path: &Path) -> Result<Arc<RacewayEngine>> {
+    let bundles = load_bundles(path)?;
+    let engine = init_engine(config).await?;
+
+    for bundle in bundles {
+        let trace_id = bundle.trace_id;
+        engine
+            .analysis()
+            .import_bundle(bundle)
+            .await
+            .with_context(|| format!("Failed to import trace {}", trace_id))?;
+    }
+
+    Ok(engine)
+}
+
+/// Load the bundles at `path` and run the TUI against them
+pub async fn launch_offline_tui(path: &Path) -> Result<()> {
+    let config = offline_config();
+    let engine = init_offline_engine(&config, path).await?;
+    let router = build_router(&config, engine);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let server_url = format!("http://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        {
+            tracing::error!("Offline server stopped: {}", e);
+        }
+    });
+
+    // The TUI blocks on its HTTP calls; keep it off the runtime serving them
+    tokio::task::spawn_blocking(move || tui::launch_tui_blocking(&server_url)).await?
+}
@@ -0,0 +1,328 @@
+//! Export of a trace to an OpenTelemetry collector over OTLP/HTTP (JSON encoding).
+//!
+//! Each event becomes one span, grouped into one resource per service. IDs are
+//! derived from Raceway's UUIDs: the OTLP trace ID is the trace UUID's 32 hex
+//! digits (so `raceway import --format otlp` maps it back to the same trace),
+//! and the span ID is the first 16 hex digits of the event UUID.
+//!
+//! - Events without a parent in the trace become root spans.
+//! - `parent_id` becomes the span's `parentSpanId`.
+//! - Each distributed edge becomes a link from the first event of the
+//!   downstream span to the first event of the upstream span.
+
+use anyhow::{anyhow, Context, Result};
+use raceway_core::bundle::TraceBundle;
+use raceway_core::event::{Event, EventKind};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::client::RacewayApiClient;
+
+/// Instrumentation scope reported on every exported span
+const SCOPE_NAME: &str = "raceway";
+/// `SPAN_KIND_INTERNAL`
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// Fetch a trace from the server and send it to the OTLP/HTTP `endpoint`.
+/// Returns the number of spans exported.
+pub async fn export_trace_otlp(
+    api: &RacewayApiClient,
+    trace_id: &str,
+    endpoint: &str,
+) -> Result<usize> {
+    let bundle = api.export_bundle(trace_id).await?;
+    let request = build_otlp_request(&bundle);
+
+    let url = traces_url(endpoint);
+    let response = reqwest::Client::new()
+        .post(&url)
+        .json(&request)
+        .send()
+        .await
+        .with_context(|| format!("Failed to POST {}", url))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(anyhow!(
+            "OTLP export to {} failed: {} {}",
+            url,
+            status,
+            text
+        ));
+    }
+
+    Ok(bundle.events.len())
+}
+
+/// The collector's trace path, unless the endpoint already names it
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+/// An `ExportTraceServiceRequest` in OTLP's JSON encoding
+pub fn build_otlp_request(bundle: &TraceBundle) -> Value {
+    let events: HashSet<Uuid> = bundle.events.iter().map(|event| event.id).collect();
+
+    // Earliest event of each W3C span, which links are attached to and point at
+    let mut span_heads: HashMap<&str, &Event> = HashMap::new();
+    for event in &bundle.events {
+        if let Some(span_id) = event.metadata.distributed_span_id.as_deref() {
+            let head = span_heads.entry(span_id).or_insert(event);
+            if event.timestamp < head.timestamp {
+                *head = event;
+            }
+        }
+    }
+    let mut links: HashMap<Uuid, Vec<Value>> = HashMap::new();
+    for edge in &bundle.distributed_edges {
+        let (Some(upstream), Some(downstream)) = (
+            span_heads.get(edge.from_span.as_str()),
+            span_heads.get(edge.to_span.as_str()),
+        ) else {
+            continue;
+        };
+        links.entry(downstream.id).or_default().push(json!({
+            "traceId": otlp_trace_id(upstream.trace_id),
+            "spanId": otlp_span_id(upstream.id),
+            "attributes": [attribute("raceway.link_type", format!("{:?}", edge.link_type))],
+        }));
+    }
+
+    let mut spans_by_service: BTreeMap<&str, Vec<Value>> = BTreeMap::new();
+    for event in &bundle.events {
+        let parent_span_id = event
+            .parent_id
+            .filter(|parent| events.contains(parent))
+            .map(otlp_span_id)
+            .unwrap_or_default();
+        let start_ns = event.timestamp.timestamp_nanos_opt().unwrap_or(0);
+        let end_ns = start_ns + event.metadata.duration_ns.unwrap_or(0) as i64;
+
+        spans_by_service
+            .entry(&event.metadata.service_name)
+            .or_default()
+            .push(json!({
+                "traceId": otlp_trace_id(event.trace_id),
+                "spanId": otlp_span_id(event.id),
+                "parentSpanId": parent_span_id,
+                "name": span_name(&event.kind),
+                "kind": SPAN_KIND_INTERNAL,
+                "startTimeUnixNano": start_ns.to_string(),
+                "endTimeUnixNano": end_ns.to_string(),
+                "attributes": span_attributes(event),
+                "links": links.remove(&event.id).unwrap_or_default(),
+            }));
+    }
+
+    let resource_spans: Vec<Value> = spans_by_service
+        .into_iter()
+        .map(|(service, spans)| {
+            json!({
+                "resource": { "attributes": [attribute("service.name", service)] },
+                "scopeSpans": [{
+                    "scope": { "name": SCOPE_NAME },
+                    "spans": spans,
+                }],
+            })
+        })
+        .collect();
+
+    json!({ "resourceSpans": resource_spans })
+}
+
+fn otlp_trace_id(trace_id: Uuid) -> String {
+    trace_id.simple().to_string()
+}
+
+fn otlp_span_id(event_id: Uuid) -> String {
+    event_id.simple().to_string()[..16].to_string()
+}
+
+fn attribute(key: &str, value: impl Into<String>) -> Value {
+    json!({ "key": key, "value": { "stringValue": value.into() } })
+}
+
+fn span_name(kind: &EventKind) -> String {
+    match kind {
+        EventKind::FunctionCall { function_name, .. } => function_name.clone(),
+        EventKind::StateChange {
+            variable,
+            access_type,
+            ..
+        } => format!("{:?} {}", access_type, variable),
+        EventKind::LockAcquire { lock_id, .. } => format!("acquire {}", lock_id),
+        EventKind::LockRelease { lock_id, .. } => format!("release {}", lock_id),
+        EventKind::HttpRequest { method, url, .. } => format!("{} {}", method, url),
+        EventKind::HttpResponse { status, .. } => format!("HTTP {}", status),
+        EventKind::DatabaseQuery { database, .. } => format!("query {}", database),
+        EventKind::Error { error_type, .. } => error_type.clone(),
+        other => kind_name(other),
+    }
+}
+
+fn span_attributes(event: &Event) -> Vec<Value> {
+    let mut attributes = vec![
+        attribute("raceway.event_id", event.id.to_string()),
+        attribute("raceway.kind", kind_name(&event.kind)),
+        attribute("thread.id", event.metadata.thread_id.as_str()),
+    ];
+    match &event.kind {
+        EventKind::StateChange { location, .. }
+        | EventKind::LockAcquire { location, .. }
+        | EventKind::LockRelease { location, .. } => {
+            attributes.push(attribute("code.location", location.as_str()));
+        }
+        EventKind::DatabaseQuery { query, .. } => {
+            attributes.push(attribute("db.statement", query.as_str()));
+        }
+        _ => {}
+    }
+    attributes
+}
+
+/// Variant name of an externally tagged `EventKind`
+fn kind_name(kind: &EventKind) -> String {
+    match serde_json::to_value(kind) {
+        Ok(Value::Object(map)) => map.keys().next().cloned().unwrap_or_default(),
+        Ok(Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use raceway_core::bundle::BundleAnnotations;
+    use raceway_core::event::EventMetadata;
+
+    fn event(trace_id: Uuid, parent_id: Option<Uuid>, name: &str, offset_ms: i64) -> Event {
+        Event {
+            id: Uuid::new_v4(),
+            trace_id,
+            parent_id,
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+                + chrono::Duration::milliseconds(offset_ms),
+            kind: EventKind::FunctionCall {
+                function_name: name.into(),
+                module: "app".into(),
+                args: Value::Null,
+                file: "app.rs".into(),
+                line: 1,
+            },
+            metadata: EventMetadata {
+                thread_id: "main".into(),
+                process_id: 1,
+                service_name: "api".into(),
+                environment: "test".into(),
+                tags: HashMap::new(),
+                duration_ns: Some(2_000_000),
+                instance_id: None,
+                distributed_span_id: None,
+                upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
+            },
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
+    fn bundle(events: Vec<Event>) -> TraceBundle {
+        TraceBundle {
+            format_version: 1,
+            trace_id: events[0].trace_id,
+            exported_at: Utc::now(),
+            events,
+            distributed_spans: Vec::new(),
+            distributed_edges: Vec::new(),
+            baselines: HashMap::new(),
+            annotations: BundleAnnotations::default(),
+        }
+    }
+
+    #[test]
+    fn parent_id_becomes_parent_span() {
+        let trace_id = Uuid::new_v4();
+        let root = event(trace_id, None, "handle_request", 0);
+        let child = event(trace_id, Some(root.id), "load_account", 1);
+        let request = build_otlp_request(&bundle(vec![root.clone(), child.clone()]));
+
+        let spans = request["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(
+            request["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "api"
+        );
+
+        let root_span = spans
+            .iter()
+            .find(|s| s["name"] == "handle_request")
+            .unwrap();
+        let child_span = spans.iter().find(|s| s["name"] == "load_account").unwrap();
+        assert_eq!(root_span["parentSpanId"], "");
+        assert_eq!(child_span["parentSpanId"], root_span["spanId"]);
+        assert_eq!(root_span["traceId"], trace_id.simple().to_string());
+        assert_eq!(child_span["traceId"], root_span["traceId"]);
+
+        let start: i64 = child_span["startTimeUnixNano"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let end: i64 = child_span["endTimeUnixNano"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(end - start, 2_000_000);
+    }
+
+    #[test]
+    fn distributed_edges_become_links() {
+        use raceway_core::event::{DistributedEdge, EdgeLinkType};
+
+        let trace_id = Uuid::new_v4();
+        let mut upstream = event(trace_id, None, "call_ledger", 0);
+        upstream.metadata.distributed_span_id = Some("span-a".into());
+        let mut downstream = event(trace_id, None, "record", 1);
+        downstream.metadata.service_name = "ledger".into();
+        downstream.metadata.distributed_span_id = Some("span-b".into());
+
+        let mut bundle = bundle(vec![upstream.clone(), downstream]);
+        bundle.distributed_edges.push(DistributedEdge {
+            from_span: "span-a".into(),
+            to_span: "span-b".into(),
+            link_type: EdgeLinkType::HttpCall,
+            metadata: Value::Null,
+        });
+        let request = build_otlp_request(&bundle);
+
+        // Resources are ordered by service name: api, then ledger
+        let ledger = &request["resourceSpans"][1]["scopeSpans"][0]["spans"][0];
+        assert_eq!(ledger["name"], "record");
+        assert_eq!(ledger["links"][0]["spanId"], otlp_span_id(upstream.id));
+        assert_eq!(ledger["parentSpanId"], "");
+    }
+
+    #[test]
+    fn traces_url_appends_the_otlp_path_once() {
+        assert_eq!(
+            traces_url("http://collector:4318/"),
+            "http://collector:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("http://collector:4318/v1/traces"),
+            "http://collector:4318/v1/traces"
+        );
+    }
+}
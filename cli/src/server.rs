@@ -1,28 +1,54 @@
 use anyhow::Result;
 use axum::{
     body::Body,
-    extract::{ConnectInfo, Path, Query, State},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, FromRequest, Path, Query, State,
+    },
     http::{HeaderMap, HeaderValue, Method, Request, StatusCode},
     middleware::{self, Next},
-    response::{IntoResponse, Json, Response},
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
     routing::{get, post},
     Router,
 };
-use chrono::Local;
-use governor::{clock::DefaultClock, state::keyed::DefaultKeyedStateStore, Quota, RateLimiter};
+use chrono::{DateTime, Local, Utc};
+use governor::{
+    clock::{Clock, DefaultClock},
+    middleware::StateInformationMiddleware,
+    state::keyed::DefaultKeyedStateStore,
+    Quota, RateLimiter,
+};
+use prometheus::{
+    Encoder, Gauge, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder,
+};
 use raceway_core::analysis::{WarmupPhase, WarmupStatus};
+use raceway_core::anomaly_feed::AnomalyAlert;
+use raceway_core::bundle::TraceBundle;
 use raceway_core::cache::QueryCache;
-use raceway_core::engine::EngineConfig;
-use raceway_core::graph::{Anomaly, ServiceDependencies, VariableAccess};
-use raceway_core::storage::{TraceAnalysisData, TraceSummary};
-use raceway_core::{create_storage_backend, Config, Event, RacewayEngine};
+use raceway_core::config::EnvironmentConfig;
+use raceway_core::engine::{EngineConfig, JobStatus, RaceAlert};
+use raceway_core::graph::{
+    Anomaly, DoubleCheckedLocking, LockImbalance, ServiceDependencies, UnreleasedLock,
+    VariableAccess,
+};
+use raceway_core::health::{event_coverage, health_score, HealthInputs, TraceHealth};
+use raceway_core::storage::{MetricsScope, TimeWindow, TraceAnalysisData, TraceSummary};
+use raceway_core::timing::{timing_breakdown, TimingBreakdown};
+use raceway_core::{
+    create_storage_backend, Backpressure, Config, Event, RacewayEngine, StorageBackend,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::net::SocketAddr;
 use std::num::NonZeroU32;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::broadcast;
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::decompression::RequestDecompressionLayer;
 use tower_http::services::{ServeDir, ServeFile};
 use uuid::Uuid;
 #[derive(Clone)]
@@ -32,6 +58,100 @@ struct AppState {
     auth: AuthConfig,
     ui_auth: UIAuthConfig,
     perf_metrics_cache: Arc<QueryCache<serde_json::Value>>,
+    environments: EnvironmentConfig,
+    service_groups: Arc<HashMap<String, HashMap<String, String>>>,
+    storage_latency_threshold: Duration,
+    metrics: ServerMetrics,
+}
+
+/// Operational metrics served at `/metrics` in the Prometheus text format
+#[derive(Clone)]
+struct ServerMetrics {
+    registry: Registry,
+    events_ingested: IntCounter,
+    traces_active: IntGauge,
+    races_detected: IntCounter,
+    anomalies_detected: IntCounter,
+    ingest_latency: Histogram,
+    storage_ping_latency: Gauge,
+    /// Races and anomalies last reported per trace, so analyzing a trace
+    /// again only counts what is new
+    reported: Arc<Mutex<HashMap<Uuid, (usize, usize)>>>,
+}
+
+impl ServerMetrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        let register = |metric: Box<dyn prometheus::core::Collector>| {
+            registry.register(metric).expect("metric names are unique");
+        };
+
+        let events_ingested = IntCounter::new(
+            "raceway_events_ingested_total",
+            "Events accepted for ingestion",
+        )
+        .unwrap();
+        let traces_active = IntGauge::new(
+            "raceway_traces_active",
+            "Traces currently held in the causal graph",
+        )
+        .unwrap();
+        let races_detected = IntCounter::new(
+            "raceway_races_detected_total",
+            "Potential races reported by trace analyses",
+        )
+        .unwrap();
+        let anomalies_detected = IntCounter::new(
+            "raceway_anomalies_detected_total",
+            "Anomalies reported by trace analyses",
+        )
+        .unwrap();
+        let ingest_latency = Histogram::with_opts(HistogramOpts::new(
+            "raceway_ingest_request_duration_seconds",
+            "Time to handle a POST /events request",
+        ))
+        .unwrap();
+        let storage_ping_latency = Gauge::new(
+            "raceway_storage_ping_seconds",
+            "Latency of the most recent successful storage ping",
+        )
+        .unwrap();
+
+        register(Box::new(events_ingested.clone()));
+        register(Box::new(traces_active.clone()));
+        register(Box::new(races_detected.clone()));
+        register(Box::new(anomalies_detected.clone()));
+        register(Box::new(ingest_latency.clone()));
+        register(Box::new(storage_ping_latency.clone()));
+
+        Self {
+            registry,
+            events_ingested,
+            traces_active,
+            races_detected,
+            anomalies_detected,
+            ingest_latency,
+            storage_ping_latency,
+            reported: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Count the races and anomalies one analysis of `trace_id` found beyond
+    /// those already reported for it
+    fn record_analysis(&self, trace_id: Uuid, races: Option<usize>, anomalies: Option<usize>) {
+        let mut reported = self.reported.lock().unwrap();
+        let (reported_races, reported_anomalies) = reported.entry(trace_id).or_default();
+        if let Some(races) = races {
+            self.races_detected
+                .inc_by(races.saturating_sub(*reported_races) as u64);
+            *reported_races = (*reported_races).max(races);
+        }
+        if let Some(anomalies) = anomalies {
+            self.anomalies_detected
+                .inc_by(anomalies.saturating_sub(*reported_anomalies) as u64);
+            *reported_anomalies = (*reported_anomalies).max(anomalies);
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -39,9 +159,46 @@ struct AuthConfig {
     enabled: bool,
     valid_keys: Arc<HashSet<String>>,
     rate_limiter: Option<Arc<KeyedRateLimiter>>,
+    rate_limit_rpm: u32,
+    /// Serve `/metrics` without an API key or rate limit
+    metrics_public: bool,
+}
+
+type KeyedRateLimiter =
+    RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock, StateInformationMiddleware>;
+
+/// Error half of the handler results: a status plus an error envelope
+type ApiError = (StatusCode, Json<ApiResponse<String>>);
+
+/// Result of charging one request against a client's rate limit
+enum RateLimitDecision {
+    Unlimited,
+    Allowed { limit: u32, remaining: u32 },
+    Limited { limit: u32, retry_after: Duration },
 }
 
-type KeyedRateLimiter = RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>;
+impl RateLimitDecision {
+    /// `X-RateLimit-*` and (when limited) `Retry-After` headers for this decision
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let (limit, remaining) = match self {
+            RateLimitDecision::Unlimited => return headers,
+            RateLimitDecision::Allowed { limit, remaining } => (*limit, *remaining),
+            RateLimitDecision::Limited { limit, retry_after } => {
+                // Round up so a client that waits exactly this long is let through
+                let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                headers.insert(
+                    axum::http::header::RETRY_AFTER,
+                    HeaderValue::from(seconds.max(1)),
+                );
+                (*limit, 0)
+            }
+        };
+        headers.insert("x-ratelimit-limit", HeaderValue::from(limit));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(remaining));
+        headers
+    }
+}
 
 impl AuthConfig {
     fn from_server_config(cfg: &raceway_core::config::ServerConfig) -> Self {
@@ -50,7 +207,9 @@ impl AuthConfig {
         let rate_limiter = if cfg.rate_limit_enabled {
             if let Some(rpm) = NonZeroU32::new(cfg.rate_limit_rpm) {
                 let quota = Quota::per_minute(rpm);
-                Some(Arc::new(RateLimiter::keyed(quota)))
+                Some(Arc::new(
+                    RateLimiter::keyed(quota).with_middleware::<StateInformationMiddleware>(),
+                ))
             } else {
                 None
             }
@@ -62,6 +221,8 @@ impl AuthConfig {
             enabled: cfg.auth_enabled,
             valid_keys: Arc::new(valid_keys),
             rate_limiter,
+            rate_limit_rpm: cfg.rate_limit_rpm,
+            metrics_public: cfg.metrics_public,
         }
     }
 
@@ -76,12 +237,21 @@ impl AuthConfig {
         }
     }
 
-    fn check_rate_limit(&self, key: &str) -> bool {
-        if let Some(limiter) = &self.rate_limiter {
-            let key_owned = key.to_string();
-            limiter.check_key(&key_owned).is_ok()
-        } else {
-            true
+    fn check_rate_limit(&self, key: &str) -> RateLimitDecision {
+        let Some(limiter) = &self.rate_limiter else {
+            return RateLimitDecision::Unlimited;
+        };
+
+        let limit = self.rate_limit_rpm;
+        match limiter.check_key(&key.to_string()) {
+            Ok(snapshot) => RateLimitDecision::Allowed {
+                limit,
+                remaining: snapshot.remaining_burst_capacity(),
+            },
+            Err(not_until) => RateLimitDecision::Limited {
+                limit,
+                retry_after: not_until.wait_time_from(DefaultClock::default().now()),
+            },
         }
     }
 }
@@ -144,9 +314,11 @@ impl UIAuthConfig {
     }
 }
 
+/// Events are kept as raw JSON so each one is deserialized on its own, and a
+/// malformed event doesn't reject the rest of the batch
 #[derive(Debug, Deserialize)]
 struct EventBatch {
-    events: Vec<Event>,
+    events: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -182,7 +354,17 @@ struct ServerStatus {
     uptime_seconds: u64,
     events_captured: usize,
     traces_active: usize,
+    /// Events rejected since startup because the capture buffer was full
+    events_dropped: u64,
     warmup: WarmupSummary,
+    diagnostics: StatusDiagnostics,
+}
+
+/// Invariant checks on the causal graph; all empty when it is healthy
+#[derive(Debug, Serialize)]
+struct StatusDiagnostics {
+    /// Event IDs of each cycle in the causal graph (see `CausalGraph::find_cycles`)
+    cycles: Vec<Vec<Uuid>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -232,22 +414,20 @@ impl From<WarmupStatus> for WarmupSummary {
 }
 
 pub async fn start_server(config: Config) -> Result<()> {
-    let log_level = match config.logging.level.to_lowercase().as_str() {
-        "trace" => tracing::Level::TRACE,
-        "debug" => tracing::Level::DEBUG,
-        "info" => tracing::Level::INFO,
-        "warn" => tracing::Level::WARN,
-        "error" => tracing::Level::ERROR,
-        _ => tracing::Level::INFO,
-    };
-
     tracing_subscriber::fmt()
         .with_target(config.logging.include_modules)
-        .with_max_level(log_level)
+        .with_max_level(config.logging.max_level())
         .compact()
         .init();
 
     let engine = init_engine(&config).await?;
+    if let Some(hours) = config
+        .storage
+        .maintenance_interval_hours
+        .filter(|hours| *hours > 0)
+    {
+        spawn_maintenance_task(Arc::clone(&engine), Duration::from_secs(hours * 3600));
+    }
     let app = build_router(&config, Arc::clone(&engine));
 
     let addr = format!("{}:{}", config.server.host, config.server.port);
@@ -262,23 +442,106 @@ pub async fn start_server(config: Config) -> Result<()> {
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
+    .with_graceful_shutdown(shutdown_signal())
     .await?;
+
+    // No new requests are accepted past this point; flush what's still in memory
+    println!("\n🛑 Shutting down, flushing buffered events...");
+    let report = engine.shutdown().await?;
+    println!(
+        "✅ Flushed {} buffered events and {} baselines",
+        report.drained_events, report.persisted_baselines
+    );
     Ok(())
 }
 
+/// Run storage maintenance every `every` (`[storage] maintenance_interval_hours`)
+fn spawn_maintenance_task(engine: Arc<RacewayEngine>, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        // The first tick completes immediately; don't vacuum at startup
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match engine.storage().maintenance().await {
+                Ok(report) => tracing::info!(
+                    "Storage maintenance removed {} stale rows ({} traces kept)",
+                    report.rows_removed,
+                    report.trace_count
+                ),
+                Err(e) => tracing::warn!("Storage maintenance failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Resolves on Ctrl+C or (on Unix) SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
 pub async fn init_engine(config: &Config) -> Result<Arc<RacewayEngine>> {
     let storage = create_storage_backend(&config.storage).await?;
+    init_engine_with_storage(config, storage).await
+}
 
+/// Start an engine on an already constructed storage backend
+pub async fn init_engine_with_storage(
+    config: &Config,
+    storage: Arc<dyn StorageBackend>,
+) -> Result<Arc<RacewayEngine>> {
     let engine_config = EngineConfig {
         buffer_size: config.engine.buffer_size,
         batch_size: config.engine.batch_size,
         flush_interval_ms: config.engine.flush_interval_ms,
         enable_anomaly_detection: config.anomaly_detection.enabled,
         enable_race_detection: config.race_detection.enabled,
+        max_graph_traces: config.engine.max_graph_traces,
+        retention_interval_ms: config.engine.retention_interval_ms,
     };
 
     let engine = Arc::new(RacewayEngine::new(engine_config, storage, config.clone()).await?);
     engine.start().await?;
+
+    if config.warmup.enabled {
+        let analysis = engine.analysis();
+        // Unready from here on, not only once the replay task gets scheduled
+        analysis.begin_warmup(0).await;
+        let concurrency = config.warmup.concurrency;
+        tokio::spawn(async move {
+            match analysis.warmup(concurrency).await {
+                Ok(()) => tracing::info!("✅ Warmup replay completed"),
+                Err(e) => tracing::error!("Warmup replay failed: {}", e),
+            }
+        });
+    }
+
     Ok(engine)
 }
 
@@ -295,6 +558,12 @@ pub fn build_router(config: &Config, engine: Arc<RacewayEngine>) -> Router {
         auth,
         ui_auth,
         perf_metrics_cache,
+        environments: config.environments.clone(),
+        service_groups: Arc::new(config.service_groups.clone()),
+        storage_latency_threshold: Duration::from_millis(
+            config.storage.health_latency_threshold_ms,
+        ),
+        metrics: ServerMetrics::new(),
     };
     let auth_state = state.clone();
     let ui_auth_state = state.clone();
@@ -310,12 +579,25 @@ pub fn build_router(config: &Config, engine: Arc<RacewayEngine>) -> Router {
         .route("/auth/check", get(ui_check_handler))
         .with_state(state.clone());
 
+    // Kubernetes probe routes (no authentication required)
+    let probe_routes = Router::new()
+        .route("/livez", get(livez_handler))
+        .route("/readyz", get(readyz_handler))
+        .with_state(state.clone());
+
     // API routes (protected by API key authentication)
     let api_routes = Router::new()
         .route("/health", get(health_handler))
         .route("/status", get(status_handler))
-        .route("/events", post(ingest_events_handler))
+        .route("/metrics", get(metrics_handler))
+        // `Content-Encoding: gzip` bodies are inflated before the handler sees
+        // them; any other encoding is rejected with 415
+        .route(
+            "/events",
+            post(ingest_events_handler).layer(RequestDecompressionLayer::new()),
+        )
         .route("/api/traces", get(list_traces_handler))
+        .route("/api/traces/analyze-batch", post(analyze_batch_handler))
         .route(
             "/api/traces/:trace_id",
             get(get_full_trace_analysis_handler),
@@ -336,7 +618,35 @@ pub fn build_router(config: &Config, engine: Arc<RacewayEngine>) -> Router {
             "/api/traces/:trace_id/dependencies",
             get(get_dependencies_handler),
         )
-        .route("/api/analyze/global", get(analyze_global_handler))
+        .route(
+            "/api/traces/:trace_id/lineage",
+            get(get_trace_lineage_handler),
+        )
+        .route(
+            "/api/traces/:trace_id/events",
+            get(get_trace_events_handler),
+        )
+        .route(
+            "/api/traces/:trace_id/stream",
+            get(stream_trace_events_handler),
+        )
+        .route("/api/traces/:trace_id/explain", get(explain_pair_handler))
+        .route("/api/traces/:trace_id/path", get(causal_path_handler))
+        .route(
+            "/api/traces/:trace_id/happens-before",
+            get(happens_before_handler),
+        )
+        .route("/api/traces/:trace_id/bundle", get(export_bundle_handler))
+        .route("/api/traces/:trace_id/tags", post(add_trace_tags_handler))
+        .route("/api/bundles", post(import_bundle_handler))
+        .route(
+            "/api/analyze/global",
+            get(analyze_global_handler).post(start_global_analysis_handler),
+        )
+        .route(
+            "/api/analyze/global/:job_id",
+            get(get_global_analysis_job_handler),
+        )
         .route("/api/services", get(list_services_handler))
         .route("/api/services/health", get(get_service_health_handler))
         .route(
@@ -347,6 +657,10 @@ pub fn build_router(config: &Config, engine: Arc<RacewayEngine>) -> Router {
             "/api/services/:service_name/dependencies",
             get(get_service_dependencies_handler),
         )
+        .route(
+            "/api/services/:service_name/overview",
+            get(get_service_overview_handler),
+        )
         .route(
             "/api/performance/metrics",
             get(get_performance_metrics_handler),
@@ -360,6 +674,12 @@ pub fn build_router(config: &Config, engine: Arc<RacewayEngine>) -> Router {
             "/api/distributed/hotspots",
             get(get_system_hotspots_handler),
         )
+        .route("/api/distributed/deadlocks", get(get_deadlocks_handler))
+        .route("/api/distributed/locks", get(get_lock_stats_handler))
+        .route("/api/topology", get(get_topology_handler))
+        .route("/api/anomalies", get(get_anomaly_alerts_handler))
+        .route("/api/ws/races", get(race_alerts_ws_handler))
+        .route("/api/maintenance", post(run_maintenance_handler))
         .layer(middleware::from_fn_with_state(auth_state, auth_middleware))
         .with_state(state.clone());
 
@@ -371,6 +691,7 @@ pub fn build_router(config: &Config, engine: Arc<RacewayEngine>) -> Router {
 
     let router = Router::new()
         .merge(auth_routes)
+        .merge(probe_routes)
         .merge(api_routes)
         .merge(ui_routes);
 
@@ -386,6 +707,10 @@ async fn auth_middleware(
     req: Request<Body>,
     next: Next,
 ) -> Result<Response, (StatusCode, Json<ApiResponse<String>>)> {
+    if state.auth.metrics_public && req.uri().path() == "/metrics" {
+        return Ok(next.run(req).await);
+    }
+
     let headers = req.headers().clone();
     let auth_key = extract_api_key(&headers);
 
@@ -397,14 +722,19 @@ async fn auth_middleware(
     }
 
     let limiter_key = extract_client_identifier(&req, &headers, auth_key.as_deref());
-    if !state.auth.check_rate_limit(&limiter_key) {
-        return Err((
+    let decision = state.auth.check_rate_limit(&limiter_key);
+    if let RateLimitDecision::Limited { .. } = decision {
+        return Ok((
             StatusCode::TOO_MANY_REQUESTS,
+            decision.headers(),
             Json(ApiResponse::error("Too Many Requests".to_string())),
-        ));
+        )
+            .into_response());
     }
 
-    Ok(next.run(req).await)
+    let mut response = next.run(req).await;
+    response.headers_mut().extend(decision.headers());
+    Ok(response)
 }
 
 fn extract_api_key(headers: &HeaderMap) -> Option<String> {
@@ -772,17 +1102,29 @@ async fn root_handler(State(state): State<AppState>) -> impl IntoResponse {
             <div class="endpoint"><span class="method get">GET</span> /health</div>
             <div class="endpoint-desc">Health check endpoint</div>
 
+            <div class="endpoint"><span class="method get">GET</span> /livez</div>
+            <div class="endpoint-desc">Liveness probe (200 while the process is up)</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /readyz</div>
+            <div class="endpoint-desc">Readiness probe (200 once warmup completed and storage is reachable)</div>
+
             <div class="endpoint"><span class="method get">GET</span> /status</div>
             <div class="endpoint-desc">Server statistics (version, uptime, event/trace counts)</div>
 
+            <div class="endpoint"><span class="method get">GET</span> /metrics</div>
+            <div class="endpoint-desc">Operational metrics in the Prometheus text format</div>
+
             <div class="endpoint"><span class="method post">POST</span> /events</div>
             <div class="endpoint-desc">Ingest trace events from SDKs</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/traces</div>
-            <div class="endpoint-desc">List all traces (paginated)</div>
+            <div class="endpoint-desc">List all traces (paginated, or after=cursor for incremental sync)</div>
+
+            <div class="endpoint"><span class="method post">POST</span> /api/traces/analyze-batch</div>
+            <div class="endpoint-desc">Race, concurrency and anomaly counts for several traces (body: trace_ids)</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/traces/:id</div>
-            <div class="endpoint-desc">Get complete trace analysis</div>
+            <div class="endpoint-desc">Get complete trace analysis (page events with event_offset/event_limit, filter with event_kind)</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/critical-path</div>
             <div class="endpoint-desc">Get critical path for a trace</div>
@@ -790,13 +1132,45 @@ async fn root_handler(State(state): State<AppState>) -> impl IntoResponse {
             <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/anomalies</div>
             <div class="endpoint-desc">Get anomalies and race conditions for a trace</div>
 
+            <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/bundle</div>
+            <div class="endpoint-desc">Export a self-contained bundle (events, edges, baselines) for offline analysis</div>
+
+            <div class="endpoint"><span class="method post">POST</span> /api/bundles</div>
+            <div class="endpoint-desc">Import a trace bundle and re-run its analysis</div>
+
+            <div class="endpoint"><span class="method post">POST</span> /api/traces/:id/tags</div>
+            <div class="endpoint-desc">Label a trace (body: tags); find it again with /api/traces?trace_tag=key:value</div>
+
             <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/audit-trail/:variable</div>
             <div class="endpoint-desc">Get access history for a variable</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/dependencies</div>
-            <div class="endpoint-desc">Get service dependency graph for a trace</div>
+            <div class="endpoint-desc">Get service dependency graph for a trace (query: by_instance=true splits service instances)</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/events</div>
+            <div class="endpoint-desc">List a trace's events in stable order (query: after, limit)</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/stream</div>
+            <div class="endpoint-desc">Server-Sent Events stream of a trace's events as they are captured</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/ws/races</div>
+            <div class="endpoint-desc">WebSocket pushing an alert for each new race as events are ingested</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/lineage</div>
+            <div class="endpoint-desc">Get all retry attempts of the operation a trace belongs to</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/explain</div>
+            <div class="endpoint-desc">Explain the race verdict for a pair of events (query: a, b)</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/path</div>
+            <div class="endpoint-desc">Chain of events through which one event causally leads to another (query: from, to)</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/traces/:id/happens-before</div>
+            <div class="endpoint-desc">Whether event a is ordered before event b, after it, or concurrent (query: a, b)</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/analyze/global</div>
+            <div class="endpoint"><span class="method post">POST</span> /api/analyze/global</div>
+            <div class="endpoint"><span class="method get">GET</span> /api/analyze/global/:job_id</div>
             <div class="endpoint-desc">Global analysis across all traces</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/services</div>
@@ -812,7 +1186,7 @@ async fn root_handler(State(state): State<AppState>) -> impl IntoResponse {
             <div class="endpoint-desc">Get dependency graph for a service</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/performance/metrics</div>
-            <div class="endpoint-desc">Performance metrics (supports ?limit param)</div>
+            <div class="endpoint-desc">Performance metrics (supports ?limit, ?window_minutes, ?service, ?buckets params)</div>
 
             <div class="endpoint"><span class="method get">GET</span> /api/distributed/edges</div>
             <div class="endpoint-desc">Distributed tracing edges across services</div>
@@ -822,6 +1196,21 @@ async fn root_handler(State(state): State<AppState>) -> impl IntoResponse {
 
             <div class="endpoint"><span class="method get">GET</span> /api/distributed/hotspots</div>
             <div class="endpoint-desc">System hotspots (top variables and service calls)</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/distributed/deadlocks</div>
+            <div class="endpoint-desc">Potential deadlocks: locks acquired in conflicting orders across all traces</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/distributed/locks</div>
+            <div class="endpoint-desc">Lock contention: acquisitions, contending threads and hold times per lock</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/topology</div>
+            <div class="endpoint-desc">Service map: all services, cross-service edges and which edges carry races</div>
+
+            <div class="endpoint"><span class="method get">GET</span> /api/anomalies</div>
+            <div class="endpoint-desc">Deduplicated anomaly alerts across traces, one per operation per cooldown window</div>
+
+            <div class="endpoint"><span class="method post">POST</span> /api/maintenance</div>
+            <div class="endpoint-desc">Run storage maintenance (vacuum, reindex, compaction)</div>
         </div>
     </div>
 </body>
@@ -831,25 +1220,136 @@ async fn root_handler(State(state): State<AppState>) -> impl IntoResponse {
     (StatusCode::OK, [("content-type", "text/html")], html)
 }
 
+#[derive(Debug, Serialize)]
+struct HealthStatus {
+    #[serde(flatten)]
+    warmup: WarmupSummary,
+    storage_healthy: bool,
+    storage_latency_ms: Option<f64>,
+    storage_error: Option<String>,
+}
+
 async fn health_handler(State(state): State<AppState>) -> impl IntoResponse {
     let warmup_status = state.engine.analysis().warmup_status().await;
-    if matches!(warmup_status.phase, WarmupPhase::Failed) {
-        let response = ApiResponse::<WarmupSummary> {
+    let warmup_error = matches!(warmup_status.phase, WarmupPhase::Failed).then(|| {
+        warmup_status
+            .last_error
+            .clone()
+            .unwrap_or_else(|| "warmup failed".to_string())
+    });
+
+    let threshold = state.storage_latency_threshold;
+    let (storage_latency_ms, storage_error) = match state.engine.storage().ping().await {
+        Ok(latency) => {
+            let latency_ms = latency.as_secs_f64() * 1000.0;
+            let slow = (latency > threshold).then(|| {
+                format!(
+                    "storage ping took {:.1}ms (threshold {}ms)",
+                    latency_ms,
+                    threshold.as_millis()
+                )
+            });
+            (Some(latency_ms), slow)
+        }
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    let error = warmup_error.or_else(|| storage_error.clone());
+    let status = HealthStatus {
+        warmup: warmup_status.into(),
+        storage_healthy: storage_error.is_none(),
+        storage_latency_ms,
+        storage_error,
+    };
+
+    match error {
+        None => (StatusCode::OK, Json(ApiResponse::success(status))),
+        Some(error) => {
+            let response = ApiResponse {
+                success: false,
+                data: Some(status),
+                error: Some(error),
+            };
+            (StatusCode::SERVICE_UNAVAILABLE, Json(response))
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ReadinessStatus {
+    ready: bool,
+    storage_reachable: bool,
+    storage_error: Option<String>,
+    warmup: WarmupSummary,
+}
+
+async fn livez_handler() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success("alive".to_string())),
+    )
+}
+
+async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let metrics = &state.metrics;
+    let active_traces = state.engine.analysis().active_trace_count().await;
+    metrics.traces_active.set(active_traces as i64);
+    match state.engine.storage().ping().await {
+        Ok(latency) => metrics.storage_ping_latency.set(latency.as_secs_f64()),
+        Err(e) => tracing::warn!("Storage ping failed during metrics scrape: {}", e),
+    }
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    match encoder.encode(&metrics.registry.gather(), &mut buffer) {
+        Ok(()) => (
+            StatusCode::OK,
+            [(
+                axum::http::header::CONTENT_TYPE,
+                encoder.format_type().to_string(),
+            )],
+            buffer,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<String>::error(format!(
+                "Failed to encode metrics: {}",
+                e
+            ))),
+        )
+            .into_response(),
+    }
+}
+
+async fn readyz_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let storage_error = state
+        .engine
+        .storage()
+        .ping()
+        .await
+        .err()
+        .map(|e| e.to_string());
+    let warmup: WarmupSummary = state.engine.analysis().warmup_status().await.into();
+
+    let storage_reachable = storage_error.is_none();
+    let ready = storage_reachable && warmup.ready;
+    let status = ReadinessStatus {
+        ready,
+        storage_reachable,
+        storage_error,
+        warmup,
+    };
+
+    if ready {
+        (StatusCode::OK, Json(ApiResponse::success(status)))
+    } else {
+        let response = ApiResponse {
             success: false,
-            data: None,
-            error: Some(
-                warmup_status
-                    .last_error
-                    .clone()
-                    .unwrap_or_else(|| "warmup failed".to_string()),
-            ),
+            data: Some(status),
+            error: Some("not ready".to_string()),
         };
         (StatusCode::SERVICE_UNAVAILABLE, Json(response))
-    } else {
-        (
-            StatusCode::OK,
-            Json(ApiResponse::success(warmup_status.into())),
-        )
     }
 }
 
@@ -873,106 +1373,719 @@ async fn status_handler(State(state): State<AppState>) -> impl IntoResponse {
         uptime_seconds: 0,
         events_captured: event_count,
         traces_active: trace_count,
+        events_dropped: state.engine.capture().dropped_events(),
         warmup: state.engine.analysis().warmup_status().await.into(),
+        diagnostics: StatusDiagnostics {
+            cycles: state.engine.analysis().find_cycles().await,
+        },
     };
 
     Json(ApiResponse::success(status))
 }
 
-async fn ingest_events_handler(
-    State(state): State<AppState>,
-    Json(batch): Json<EventBatch>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+async fn ingest_events_handler(State(state): State<AppState>, request: Request<Body>) -> Response {
+    let _timer = state.metrics.ingest_latency.start_timer();
+
+    if is_ndjson(request.headers(), axum::http::header::CONTENT_TYPE) {
+        if is_ndjson(request.headers(), axum::http::header::ACCEPT) {
+            return ingest_ndjson_with_results(&state, request.into_body());
+        }
+        return ingest_ndjson(&state, request.into_body()).await;
+    }
+
+    match Json::<EventBatch>::from_request(request, &state).await {
+        Ok(Json(batch)) => ingest_batch(&state, batch),
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+fn is_ndjson(headers: &HeaderMap, header: axum::http::header::HeaderName) -> bool {
+    headers
+        .get(header)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/x-ndjson"))
+        .unwrap_or(false)
+}
+
+/// Events of a batch that were not ingested, reported when any was malformed
+#[derive(Debug, Serialize)]
+struct BatchIngestReport {
+    ingested: usize,
+    failed: Vec<BatchEventError>,
+}
+
+/// One rejected event; `index` is its position in the `events` array
+#[derive(Debug, Serialize)]
+struct BatchEventError {
+    index: usize,
+    id: Option<String>,
+    error: String,
+}
+
+/// Seconds a client is asked to wait before retrying events rejected because
+/// the capture buffer was full
+const BACKPRESSURE_RETRY_AFTER_SECS: u64 = 1;
+
+fn backpressure_response<T: Serialize>(response: ApiResponse<T>) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(
+            axum::http::header::RETRY_AFTER,
+            BACKPRESSURE_RETRY_AFTER_SECS.to_string(),
+        )],
+        Json(response),
+    )
+        .into_response()
+}
+
+fn ingest_batch(state: &AppState, batch: EventBatch) -> Response {
     let mut success_count = 0;
     let mut error_count = 0;
-
-    for event in batch.events {
+    let mut malformed_count = 0;
+    let mut backpressure_count = 0;
+    let mut failed = Vec::new();
+
+    for (index, value) in batch.events.into_iter().enumerate() {
+        let id = value
+            .get("id")
+            .and_then(|id| id.as_str())
+            .map(str::to_string);
+        let event = match serde_json::from_value::<Event>(value) {
+            Ok(event) => event,
+            Err(e) => {
+                malformed_count += 1;
+                failed.push(BatchEventError {
+                    index,
+                    id,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
         match state.engine.capture().capture(event) {
-            Ok(_) => success_count += 1,
-            Err(_) => error_count += 1,
+            Ok(_) => {
+                state.metrics.events_ingested.inc();
+                success_count += 1;
+            }
+            Err(e) => {
+                if e.is::<Backpressure>() {
+                    backpressure_count += 1;
+                } else {
+                    error_count += 1;
+                }
+                failed.push(BatchEventError {
+                    index,
+                    id,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    // The buffer filled up mid-batch: tell the client which events to resend
+    if backpressure_count > 0 {
+        return backpressure_response(ApiResponse {
+            success: false,
+            data: Some(BatchIngestReport {
+                ingested: success_count,
+                failed,
+            }),
+            error: Some(format!(
+                "Capture buffer full; {} events not accepted, retry them later",
+                backpressure_count
+            )),
+        });
+    }
+
+    if malformed_count > 0 {
+        let report = BatchIngestReport {
+            ingested: success_count,
+            failed,
+        };
+        // Nothing made it in and at least one event was invalid: the batch is the client's fault
+        if success_count == 0 {
+            let response = ApiResponse {
+                success: false,
+                data: Some(report),
+                error: Some(format!("{} malformed events", malformed_count)),
+            };
+            return (StatusCode::BAD_REQUEST, Json(response)).into_response();
         }
+        return (StatusCode::MULTI_STATUS, Json(ApiResponse::success(report))).into_response();
     }
 
     if error_count == 0 {
-        Ok((
+        (
             StatusCode::OK,
             Json(ApiResponse::success(format!(
                 "Ingested {} events",
                 success_count
             ))),
-        ))
+        )
+            .into_response()
     } else {
-        Ok((
+        (
             StatusCode::PARTIAL_CONTENT,
             Json(ApiResponse::success(format!(
                 "Ingested {} events, {} errors",
                 success_count, error_count
             ))),
-        ))
+        )
+            .into_response()
     }
 }
 
-async fn list_traces_handler(
-    State(state): State<AppState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let page: usize = params.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
-    let page_size: usize = params
-        .get("page_size")
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(20);
-    let min_events: Option<usize> = params.get("min_events").and_then(|p| p.parse().ok());
+#[derive(Debug, Default, Serialize)]
+struct NdjsonIngestSummary {
+    ingested: usize,
+    capture_errors: usize,
+    malformed_lines: usize,
+    /// Lines rejected because the capture buffer was full
+    backpressured: usize,
+    truncated: bool,
+}
+
+impl NdjsonIngestSummary {
+    fn record(&mut self, result: &NdjsonLineResult) {
+        match result.status {
+            NdjsonLineStatus::Ingested => self.ingested += 1,
+            NdjsonLineStatus::CaptureError => self.capture_errors += 1,
+            NdjsonLineStatus::Malformed => self.malformed_lines += 1,
+            NdjsonLineStatus::Backpressure => self.backpressured += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum NdjsonLineStatus {
+    Ingested,
+    CaptureError,
+    Malformed,
+    Backpressure,
+}
+
+/// Outcome of one non-empty NDJSON input line; `line` is 1-based
+#[derive(Debug, Serialize)]
+struct NdjsonLineResult {
+    line: usize,
+    id: Option<String>,
+    status: NdjsonLineStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Ingest a line-delimited body one event at a time as it streams in, so a
+/// dropped connection still persists every complete line received before it.
+async fn ingest_ndjson(state: &AppState, body: Body) -> Response {
+    let mut summary = NdjsonIngestSummary::default();
+
+    let truncated = read_ndjson_lines(body, |line_number, line| {
+        if let Some(result) = ingest_ndjson_line(state, line_number, line) {
+            summary.record(&result);
+        }
+    })
+    .await;
+    summary.truncated = truncated;
 
     if state.verbose {
         println!(
-            "[{}] 📋 list_traces_handler -> page: {}, page_size: {}, min_events: {:?}",
+            "[{}] 📥 ndjson ingest -> {:?}",
             Local::now().format("%H:%M:%S.%3f"),
-            page,
-            page_size,
-            min_events
+            summary
         );
     }
 
-    #[derive(Serialize)]
-    struct TraceMetadata {
-        trace_id: String,
-        event_count: usize,
-        first_timestamp: String,
-        last_timestamp: String,
-        service_count: usize,
-        services: Vec<String>,
+    if summary.backpressured > 0 {
+        return backpressure_response(ApiResponse::success(summary));
     }
 
-    #[derive(Serialize)]
-    struct TracesListResponse {
-        total_traces: usize,
-        page: usize,
-        page_size: usize,
-        total_pages: usize,
+    let complete =
+        summary.capture_errors == 0 && summary.malformed_lines == 0 && !summary.truncated;
+    let status = if complete {
+        StatusCode::OK
+    } else {
+        StatusCode::PARTIAL_CONTENT
+    };
+
+    (status, Json(ApiResponse::success(summary))).into_response()
+}
+
+/// Like `ingest_ndjson`, but streams back one `NdjsonLineResult` line per input
+/// event as soon as it is processed, so bulk importers can reconcile exactly
+/// which events landed. The status is always 200 since it is sent first.
+fn ingest_ndjson_with_results(state: &AppState, body: Body) -> Response {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let state = state.clone();
+
+    tokio::spawn(async move {
+        read_ndjson_lines(body, |line_number, line| {
+            if let Some(result) = ingest_ndjson_line(&state, line_number, line) {
+                let mut json = serde_json::to_string(&result).unwrap_or_default();
+                json.push('\n');
+                // A client that stopped reading results still gets its events ingested
+                let _ = sender.send(json);
+            }
+        })
+        .await;
+    });
+
+    let results = futures::stream::unfold(receiver, |mut receiver| async move {
+        let line = receiver.recv().await?;
+        Some((Ok::<_, std::convert::Infallible>(line), receiver))
+    });
+
+    (
+        [(axum::http::header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(results),
+    )
+        .into_response()
+}
+
+/// Longest NDJSON line buffered while waiting for its newline, matching axum's
+/// default limit on a whole JSON batch. A streamed body bypasses that limit,
+/// and gzip can inflate a small request without newlines into gigabytes.
+const MAX_NDJSON_LINE_BYTES: usize = 2 * 1024 * 1024;
+
+/// One line handed out by `read_ndjson_lines`
+enum NdjsonLine<'a> {
+    Complete(&'a [u8]),
+    /// Longer than `MAX_NDJSON_LINE_BYTES`; its bytes were discarded
+    Oversized,
+}
+
+/// Feed each line of a streamed body to `on_line` (with its 1-based line
+/// number) as soon as it is complete. Returns true if the body broke off early.
+async fn read_ndjson_lines(mut body: Body, mut on_line: impl FnMut(usize, NdjsonLine)) -> bool {
+    use axum::body::HttpBody;
+    use std::pin::Pin;
+
+    let mut buffer: Vec<u8> = Vec::new();
+    // Bytes of `buffer` already searched for a newline
+    let mut scanned = 0;
+    // Dropping the rest of an oversized line up to its newline
+    let mut discarding = false;
+    let mut line_number = 0;
+    let mut truncated = false;
+
+    while let Some(frame) = std::future::poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+        let chunk = match frame {
+            Ok(frame) => match frame.into_data() {
+                Ok(chunk) => chunk,
+                Err(_) => continue,
+            },
+            Err(_) => {
+                truncated = true;
+                break;
+            }
+        };
+
+        buffer.extend_from_slice(&chunk);
+        let mut start = 0;
+        while let Some(offset) = buffer[scanned..].iter().position(|b| *b == b'\n') {
+            let end = scanned + offset + 1;
+            line_number += 1;
+            if discarding || end - start > MAX_NDJSON_LINE_BYTES {
+                on_line(line_number, NdjsonLine::Oversized);
+                discarding = false;
+            } else {
+                on_line(line_number, NdjsonLine::Complete(&buffer[start..end]));
+            }
+            start = end;
+            scanned = end;
+        }
+        buffer.drain(..start);
+        scanned = buffer.len();
+
+        if discarding || buffer.len() > MAX_NDJSON_LINE_BYTES {
+            discarding = true;
+            buffer.clear();
+            scanned = 0;
+        }
+    }
+
+    // Whatever is left has no trailing newline: either a final complete event
+    // or the cut-off remains of one
+    if discarding {
+        on_line(line_number + 1, NdjsonLine::Oversized);
+    } else if !buffer.is_empty() {
+        on_line(line_number + 1, NdjsonLine::Complete(&buffer));
+    }
+
+    truncated
+}
+
+/// Parse and capture one NDJSON line. Blank lines are skipped.
+fn ingest_ndjson_line(
+    state: &AppState,
+    line_number: usize,
+    line: NdjsonLine,
+) -> Option<NdjsonLineResult> {
+    let line = match line {
+        NdjsonLine::Complete(line) => line.trim_ascii(),
+        NdjsonLine::Oversized => {
+            return Some(NdjsonLineResult {
+                line: line_number,
+                id: None,
+                status: NdjsonLineStatus::Malformed,
+                error: Some(format!("Line exceeds {} bytes", MAX_NDJSON_LINE_BYTES)),
+            });
+        }
+    };
+    if line.is_empty() {
+        return None;
+    }
+
+    let result = match serde_json::from_slice::<Event>(line) {
+        Ok(event) => {
+            let id = Some(event.id.to_string());
+            match state.engine.capture().capture(event) {
+                Ok(_) => {
+                    state.metrics.events_ingested.inc();
+                    (id, NdjsonLineStatus::Ingested, None)
+                }
+                Err(e) if e.is::<Backpressure>() => {
+                    (id, NdjsonLineStatus::Backpressure, Some(e.to_string()))
+                }
+                Err(e) => (id, NdjsonLineStatus::CaptureError, Some(e.to_string())),
+            }
+        }
+        Err(e) => {
+            // Still report the event's id when the line is valid JSON with one
+            let id = serde_json::from_slice::<serde_json::Value>(line)
+                .ok()
+                .and_then(|value| value.get("id")?.as_str().map(str::to_string));
+            (id, NdjsonLineStatus::Malformed, Some(e.to_string()))
+        }
+    };
+
+    let (id, status, error) = result;
+    Some(NdjsonLineResult {
+        line: line_number,
+        id,
+        status,
+        error,
+    })
+}
+
+async fn list_traces_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, (StatusCode, Json<ApiResponse<String>>)> {
+    let page: usize = params.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+    let page_size: usize = params
+        .get("page_size")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(20);
+    let min_events: Option<usize> = params.get("min_events").and_then(|p| p.parse().ok());
+    // `sort=health` pages through the analysis service's health index, worst score
+    // first. Only traces that changed since the last request are rescored.
+    let sort_by_health = params.get("sort").map(|s| s == "health").unwrap_or(false);
+    let environment = requested_environment(&state, &params);
+    // `tag=key:value` keeps traces with at least one event carrying that tag,
+    // `trace_tag=key:value` traces labelled with it via POST /api/traces/:id/tags
+    let tags = TagFilters {
+        event: parse_tag_filter(params.get("tag"))?,
+        trace: parse_tag_filter(params.get("trace_tag"))?,
+    };
+    // `since`/`until` keep traces whose events overlap the window
+    let window = parse_time_window(&params)?;
+
+    if state.verbose {
+        println!(
+            "[{}] 📋 list_traces_handler -> page: {}, page_size: {}, min_events: {:?}, tags: {:?}, window: {:?}",
+            Local::now().format("%H:%M:%S.%3f"),
+            page,
+            page_size,
+            min_events,
+            tags,
+            window
+        );
+    }
+
+    #[derive(Serialize)]
+    struct TraceMetadata {
+        trace_id: String,
+        event_count: usize,
+        first_timestamp: String,
+        last_timestamp: String,
+        service_count: usize,
+        services: Vec<String>,
+        /// Events were rejected after the trace hit `capture.max_events_per_trace`
+        truncated: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        health_score: Option<f64>,
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        trace_tags: HashMap<String, String>,
+    }
+
+    #[derive(Serialize)]
+    struct TracesListResponse {
+        total_traces: usize,
+        page: usize,
+        page_size: usize,
+        total_pages: usize,
         traces: Vec<TraceMetadata>,
     }
 
-    // Use storage trait method for paginated trace summaries
-    match state
-        .engine
-        .storage()
-        .get_trace_summaries(page, page_size, min_events)
+    #[derive(Serialize)]
+    struct TracesCursorResponse {
+        total_traces: usize,
+        page_size: usize,
+        next_cursor: Option<String>,
+        has_more: bool,
+        traces: Vec<TraceMetadata>,
+    }
+
+    let capture = state.engine.capture();
+    let to_metadata =
+        |summary: TraceSummary,
+         health_score: Option<f64>,
+         trace_tags: &mut HashMap<Uuid, HashMap<String, String>>| TraceMetadata {
+            trace_tags: trace_tags.remove(&summary.trace_id).unwrap_or_default(),
+            trace_id: summary.trace_id.to_string(),
+            event_count: summary.event_count as usize,
+            first_timestamp: summary.first_timestamp.to_rfc3339(),
+            last_timestamp: summary.last_timestamp.to_rfc3339(),
+            service_count: summary.service_count,
+            services: summary.services,
+            truncated: capture.is_truncated(summary.trace_id),
+            health_score,
+        };
+
+    // `after=<trace_id>` switches to cursor mode for incremental sync: traces in
+    // stable creation order (first_timestamp, then trace_id) strictly after the
+    // cursor. An empty `after=` starts from the beginning. With `sort=health`
+    // the cursor follows the health ranking instead.
+    if let Some(cursor) = params.get("after").filter(|_| !sort_by_health) {
+        let cursor = parse_cursor(cursor)?;
+        let storage_error = |e: anyhow::Error| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!(
+                    "Failed to fetch trace summaries: {}",
+                    e
+                ))),
+            )
+        };
+
+        let trace_count = state
+            .engine
+            .storage()
+            .count_traces()
+            .await
+            .map_err(storage_error)?;
+        let (mut summaries, total_traces) = fetch_trace_summaries(
+            &state,
+            environment.as_deref(),
+            tags,
+            window,
+            1,
+            trace_count.max(1),
+            min_events,
+        )
         .await
+        .map_err(storage_error)?;
+        summaries.sort_by(|a, b| {
+            a.first_timestamp
+                .cmp(&b.first_timestamp)
+                .then(a.trace_id.cmp(&b.trace_id))
+        });
+
+        let (summaries, has_more) =
+            page_after_cursor(summaries, cursor, page_size, |summary| summary.trace_id)?;
+
+        let next_cursor = summaries
+            .last()
+            .map(|summary| summary.trace_id)
+            .or(cursor)
+            .map(|id| id.to_string());
+        let mut trace_tags = fetch_trace_tags(
+            &state,
+            summaries.iter().map(|summary| summary.trace_id).collect(),
+        )
+        .await;
+        let response = TracesCursorResponse {
+            total_traces,
+            page_size,
+            next_cursor,
+            has_more,
+            traces: summaries
+                .into_iter()
+                .map(|summary| to_metadata(summary, None, &mut trace_tags))
+                .collect(),
+        };
+
+        return Ok((StatusCode::OK, Json(ApiResponse::success(response))).into_response());
+    }
+
+    if sort_by_health {
+        let storage_error = |e: anyhow::Error| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!(
+                    "Failed to fetch trace summaries: {}",
+                    e
+                ))),
+            )
+        };
+        let cursor = params
+            .get("after")
+            .map(|cursor| parse_cursor(cursor))
+            .transpose()?;
+
+        // Filters are applied to storage summaries, then the ranking is restricted
+        // to the matches; without filters only the page's traces are summarized
+        let filtered = environment.is_some()
+            || tags.event.is_some()
+            || tags.trace.is_some()
+            || !window.is_unbounded()
+            || min_events.is_some();
+        let mut summaries: HashMap<Uuid, TraceSummary> = HashMap::new();
+        if filtered {
+            let trace_count = state
+                .engine
+                .storage()
+                .count_traces()
+                .await
+                .map_err(storage_error)?;
+            let (matching, _) = fetch_trace_summaries(
+                &state,
+                environment.as_deref(),
+                tags,
+                window,
+                1,
+                trace_count.max(1),
+                min_events,
+            )
+            .await
+            .map_err(storage_error)?;
+            summaries.extend(
+                matching
+                    .into_iter()
+                    .map(|summary| (summary.trace_id, summary)),
+            );
+        }
+        let matching: HashSet<Uuid> = summaries.keys().copied().collect();
+
+        let offset = match cursor {
+            Some(_) => 0,
+            None => page.saturating_sub(1) * page_size,
+        };
+        let ranked = state
+            .engine
+            .analysis()
+            .health_page(
+                offset,
+                cursor.flatten(),
+                page_size,
+                filtered.then_some(&matching),
+            )
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(format!(
+                        "Failed to rank traces by health: {}",
+                        e
+                    ))),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::error(format!(
+                        "Unknown cursor: {}",
+                        cursor.flatten().unwrap_or_default()
+                    ))),
+                )
+            })?;
+
+        if !filtered {
+            for (trace_id, _) in &ranked.traces {
+                let events = state
+                    .engine
+                    .storage()
+                    .get_trace_events(*trace_id)
+                    .await
+                    .map_err(storage_error)?;
+                summaries.extend(
+                    TraceSummary::from_events(*trace_id, &events)
+                        .map(|summary| (*trace_id, summary)),
+                );
+            }
+        }
+
+        let mut trace_tags = fetch_trace_tags(
+            &state,
+            ranked
+                .traces
+                .iter()
+                .map(|(trace_id, _)| *trace_id)
+                .collect(),
+        )
+        .await;
+        let next_cursor = ranked
+            .traces
+            .last()
+            .map(|(trace_id, _)| *trace_id)
+            .or(cursor.flatten())
+            .map(|id| id.to_string());
+        let traces = ranked
+            .traces
+            .into_iter()
+            .filter_map(|(trace_id, score)| {
+                let summary = summaries.remove(&trace_id)?;
+                Some(to_metadata(summary, Some(score), &mut trace_tags))
+            })
+            .collect();
+
+        if cursor.is_some() {
+            let response = TracesCursorResponse {
+                total_traces: ranked.total,
+                page_size,
+                next_cursor,
+                has_more: ranked.has_more,
+                traces,
+            };
+            return Ok((StatusCode::OK, Json(ApiResponse::success(response))).into_response());
+        }
+
+        let response = TracesListResponse {
+            total_traces: ranked.total,
+            page,
+            page_size,
+            total_pages: ranked.total.div_ceil(page_size),
+            traces,
+        };
+
+        return Ok((StatusCode::OK, Json(ApiResponse::success(response))).into_response());
+    }
+
+    // Use storage trait method for paginated trace summaries
+    match fetch_trace_summaries(
+        &state,
+        environment.as_deref(),
+        tags,
+        window,
+        page,
+        page_size,
+        min_events,
+    )
+    .await
     {
         Ok((summaries, total_traces)) => {
             let total_pages = total_traces.div_ceil(page_size);
 
             // Build trace metadata with service information from distributed_spans
+            let mut trace_tags = fetch_trace_tags(
+                &state,
+                summaries.iter().map(|summary| summary.trace_id).collect(),
+            )
+            .await;
             let traces: Vec<TraceMetadata> = summaries
                 .into_iter()
-                .map(|summary| TraceMetadata {
-                    trace_id: summary.trace_id.to_string(),
-                    event_count: summary.event_count as usize,
-                    first_timestamp: summary.first_timestamp.to_rfc3339(),
-                    last_timestamp: summary.last_timestamp.to_rfc3339(),
-                    service_count: summary.service_count,
-                    services: summary.services,
-                })
+                .map(|summary| to_metadata(summary, None, &mut trace_tags))
                 .collect();
 
             let response = TracesListResponse {
@@ -983,7 +2096,7 @@ async fn list_traces_handler(
                 traces,
             };
 
-            Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+            Ok((StatusCode::OK, Json(ApiResponse::success(response))).into_response())
         }
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -995,167 +2108,1154 @@ async fn list_traces_handler(
     }
 }
 
-async fn analyze_global_handler(
+/// Environment a query is scoped to: `?environment=`, else the configured default.
+/// `?environment=all` covers every environment even when a default is set.
+fn requested_environment(state: &AppState, params: &HashMap<String, String>) -> Option<String> {
+    match params.get("environment").map(String::as_str) {
+        Some("all") | Some("") => None,
+        Some(environment) => Some(environment.to_string()),
+        None => state.environments.default.clone(),
+    }
+}
+
+/// `key:value` tag filters of a trace listing
+#[derive(Debug, Clone, Copy)]
+struct TagFilters<'a> {
+    /// Some event of the trace carries this tag
+    event: Option<(&'a str, &'a str)>,
+    /// The trace itself is labelled with this tag
+    trace: Option<(&'a str, &'a str)>,
+}
+
+/// Paginated trace summaries overlapping `window`, restricted to traces from
+/// `environment` and to traces matching the tag filters when given
+async fn fetch_trace_summaries(
+    state: &AppState,
+    environment: Option<&str>,
+    tags: TagFilters<'_>,
+    window: TimeWindow,
+    page: usize,
+    page_size: usize,
+    min_events: Option<usize>,
+) -> Result<(Vec<TraceSummary>, usize)> {
+    let storage = state.engine.storage();
+
+    // Tag matches aren't known to the summary queries, so a tag filter reads
+    // every summary and paginates after filtering
+    let filtered = tags.event.is_some() || tags.trace.is_some();
+    let (storage_page, storage_page_size) = if filtered {
+        (1, storage.count_traces().await?.max(1))
+    } else {
+        (page, page_size)
+    };
+    let (summaries, total_traces) = match environment {
+        Some(environment) => {
+            storage
+                .get_trace_summaries_by_environment(
+                    environment,
+                    storage_page,
+                    storage_page_size,
+                    min_events,
+                    window,
+                )
+                .await?
+        }
+        None => {
+            storage
+                .get_trace_summaries(storage_page, storage_page_size, min_events, window)
+                .await?
+        }
+    };
+
+    if !filtered {
+        return Ok((summaries, total_traces));
+    }
+    let mut summaries = summaries;
+    if let Some((key, value)) = tags.event {
+        let tagged = storage.get_trace_ids_by_tag(key, value).await?;
+        summaries.retain(|summary| tagged.contains(&summary.trace_id));
+    }
+    if let Some((key, value)) = tags.trace {
+        let tagged = storage.get_trace_ids_by_trace_tag(key, value).await?;
+        summaries.retain(|summary| tagged.contains(&summary.trace_id));
+    }
+    let total_traces = summaries.len();
+    let summaries = summaries
+        .into_iter()
+        .skip(page.saturating_sub(1) * page_size)
+        .take(page_size)
+        .collect();
+
+    Ok((summaries, total_traces))
+}
+
+/// Trace tags of the listed traces; a trace whose tags can't be read is shown untagged
+async fn fetch_trace_tags(
+    state: &AppState,
+    trace_ids: Vec<Uuid>,
+) -> HashMap<Uuid, HashMap<String, String>> {
+    let storage = state.engine.storage();
+    let mut trace_tags = HashMap::new();
+    for trace_id in trace_ids {
+        if let Ok(tags) = storage.get_trace_tags(trace_id).await {
+            if !tags.is_empty() {
+                trace_tags.insert(trace_id, tags);
+            }
+        }
+    }
+    trace_tags
+}
+
+#[derive(Deserialize)]
+struct TraceTagsRequest {
+    tags: HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct TraceTagsResponse {
+    trace_id: String,
+    tags: HashMap<String, String>,
+}
+
+/// Label a trace so it can be found again with `GET /api/traces?trace_tag=key:value`.
+/// Keys the trace already has are overwritten; the response holds all of its tags.
+async fn add_trace_tags_handler(
     State(state): State<AppState>,
+    Path(trace_id): Path<String>,
+    Json(request): Json<TraceTagsRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    #[derive(Serialize)]
-    struct RaceDetail {
-        severity: String,
-        variable: String,
-        trace1_id: String,
-        trace2_id: String,
-        event1_thread: String,
-        event2_thread: String,
-        event1_location: String,
-        event2_location: String,
-        event1_timestamp: String,
-        event2_timestamp: String,
-        description: String,
-    }
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
 
-    #[derive(Serialize)]
-    struct GlobalAnalysis {
-        total_traces: usize,
-        total_events: usize,
-        concurrent_events: usize,
-        potential_races: usize,
-        anomalies: Vec<String>,
-        race_details: Vec<RaceDetail>,
+    if request.tags.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("tags must not be empty".to_string())),
+        ));
+    }
+    // `trace_tag=key:value` splits on the first colon, so keys can't contain one
+    if let Some(key) = request
+        .tags
+        .keys()
+        .find(|key| key.is_empty() || key.contains(':'))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(format!(
+                "Invalid trace tag key '{}' (must be non-empty and contain no ':')",
+                key
+            ))),
+        ));
     }
 
-    match state
-        .engine
-        .analysis()
-        .find_global_concurrent_events()
+    let storage_error = |e: anyhow::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to tag trace: {}", e))),
+        )
+    };
+    let storage = state.engine.storage();
+    let events = storage
+        .get_trace_events(trace_uuid)
         .await
-    {
-        Ok(concurrent) => {
-            let mut anomalies = Vec::new();
-            let mut race_details = Vec::new();
-            const MAX_RACE_DETAILS: usize = 100;
+        .map_err(storage_error)?;
+    if events.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Trace {} not found", trace_id))),
+        ));
+    }
 
-            for (event1, event2) in &concurrent {
-                if race_details.len() >= MAX_RACE_DETAILS {
-                    break;
-                }
+    storage
+        .add_trace_tags(trace_uuid, request.tags)
+        .await
+        .map_err(storage_error)?;
+    let tags = storage
+        .get_trace_tags(trace_uuid)
+        .await
+        .map_err(storage_error)?;
 
-                if let (
-                    raceway_core::event::EventKind::StateChange {
-                        variable: var1,
-                        old_value: _old1,
-                        new_value: new1,
-                        location: loc1,
-                        access_type: access1,
-                    },
-                    raceway_core::event::EventKind::StateChange {
-                        variable: var2,
-                        old_value: _old2,
-                        new_value: new2,
-                        location: loc2,
-                        access_type: access2,
-                    },
-                ) = (&event1.kind, &event2.kind)
-                {
-                    if var1 != var2 {
-                        continue;
-                    }
+    Ok((
+        StatusCode::OK,
+        Json(ApiResponse::success(TraceTagsResponse {
+            trace_id: trace_uuid.to_string(),
+            tags,
+        })),
+    ))
+}
 
-                    use raceway_core::event::AccessType;
-
-                    let is_write1 = matches!(
-                        access1,
-                        AccessType::Write | AccessType::AtomicWrite | AccessType::AtomicRMW
-                    );
-                    let is_write2 = matches!(
-                        access2,
-                        AccessType::Write | AccessType::AtomicWrite | AccessType::AtomicRMW
-                    );
-
-                    let severity_desc = match (is_write1, is_write2) {
-                        (true, true) => (
-                            "CRITICAL",
-                            format!(
-                                "Cross-trace write-write race on {}. Trace {} (thread {}) wrote {:?}, Trace {} (thread {}) wrote {:?}",
-                                var1,
-                                &event1.trace_id.to_string()[..8],
-                                event1.metadata.thread_id,
-                                new1,
-                                &event2.trace_id.to_string()[..8],
-                                event2.metadata.thread_id,
-                                new2
-                            ),
-                        ),
-                        (true, false) | (false, true) => (
-                            "WARNING",
-                            format!(
-                                "Cross-trace read-write race on {}. One thread read while another wrote across different traces.",
-                                var1
-                            ),
-                        ),
-                        (false, false) => (
-                            "INFO",
-                            format!(
-                                "Concurrent reads on {} across traces. Generally safe but indicates potential race.",
-                                var1
-                            ),
-                        ),
-                    };
+/// Restrict race pairs to the requested environment. With environment isolation
+/// on, pairs spanning two environments are dropped unless `cross_environment=true`.
+fn scope_race_pairs(
+    state: &AppState,
+    params: &HashMap<String, String>,
+    pairs: &[(Event, Event)],
+) -> Vec<(Event, Event)> {
+    let environment = requested_environment(state, params);
+    let allow_cross_environment = !state.environments.isolate
+        || params
+            .get("cross_environment")
+            .is_some_and(|value| value == "true");
+
+    pairs
+        .iter()
+        .filter(|(event1, event2)| {
+            let (env1, env2) = (&event1.metadata.environment, &event2.metadata.environment);
+            (allow_cross_environment || env1 == env2)
+                && environment
+                    .as_deref()
+                    .is_none_or(|environment| env1 == environment || env2 == environment)
+        })
+        .cloned()
+        .collect()
+}
 
-                    race_details.push(RaceDetail {
-                        severity: severity_desc.0.to_string(),
-                        variable: var1.clone(),
-                        trace1_id: event1.trace_id.to_string(),
-                        trace2_id: event2.trace_id.to_string(),
-                        event1_thread: event1.metadata.thread_id.clone(),
-                        event2_thread: event2.metadata.thread_id.clone(),
-                        event1_location: loc1.clone(),
-                        event2_location: loc2.clone(),
-                        event1_timestamp: event1.timestamp.to_string(),
-                        event2_timestamp: event2.timestamp.to_string(),
-                        description: severity_desc.1,
-                    });
-                }
-            }
+/// Parse an `after=` cursor; empty means "from the beginning"
+/// Parse the `?since=`/`?until=` RFC 3339 bounds of a trace listing
+fn parse_time_window(
+    params: &HashMap<String, String>,
+) -> Result<TimeWindow, (StatusCode, Json<ApiResponse<String>>)> {
+    let bound = |name: &str| {
+        params
+            .get(name)
+            .map(|value| {
+                DateTime::parse_from_rfc3339(value)
+                    .map(|timestamp| timestamp.with_timezone(&Utc))
+                    .map_err(|_| {
+                        (
+                            StatusCode::BAD_REQUEST,
+                            Json(ApiResponse::error(format!(
+                                "Invalid {} '{}' (expected an RFC 3339 timestamp)",
+                                name, value
+                            ))),
+                        )
+                    })
+            })
+            .transpose()
+    };
 
-            if !race_details.is_empty() {
-                anomalies.push(format!(
-                    "🌐 GLOBAL: Found {} pairs of concurrent events across all traces",
-                    concurrent.len()
-                ));
-                if concurrent.len() > race_details.len() {
-                    anomalies.push(format!(
-                        "   Showing first {} race pairs ({} total)",
-                        race_details.len(),
-                        concurrent.len()
-                    ));
+    let window = TimeWindow {
+        since: bound("since")?,
+        until: bound("until")?,
+    };
+    if let (Some(since), Some(until)) = (window.since, window.until) {
+        if since > until {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "since must not be after until".to_string(),
+                )),
+            ));
+        }
+    }
+    Ok(window)
+}
+
+/// Parse a `?tag=key:value` filter; the value may itself contain colons
+fn parse_tag_filter(tag: Option<&String>) -> Result<Option<(&str, &str)>, ApiError> {
+    let Some(tag) = tag else {
+        return Ok(None);
+    };
+    match tag.split_once(':') {
+        Some((key, value)) if !key.is_empty() => Ok(Some((key, value))),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(format!(
+                "Invalid tag filter '{}' (expected key:value)",
+                tag
+            ))),
+        )),
+    }
+}
+
+fn parse_cursor(cursor: &str) -> Result<Option<Uuid>, (StatusCode, Json<ApiResponse<String>>)> {
+    if cursor.is_empty() {
+        return Ok(None);
+    }
+    Uuid::parse_str(cursor).map(Some).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid cursor".to_string())),
+        )
+    })
+}
+
+/// Up to `limit` items strictly after the item with id `cursor` (all items when
+/// `cursor` is None), plus whether more remain
+fn page_after_cursor<T>(
+    items: Vec<T>,
+    cursor: Option<Uuid>,
+    limit: usize,
+    id: impl Fn(&T) -> Uuid,
+) -> Result<(Vec<T>, bool), ApiError> {
+    let start = match cursor {
+        None => 0,
+        Some(cursor) => {
+            items
+                .iter()
+                .position(|item| id(item) == cursor)
+                .ok_or_else(|| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiResponse::error(format!("Unknown cursor: {}", cursor))),
+                    )
+                })?
+                + 1
+        }
+    };
+
+    let has_more = items.len() > start + limit;
+    let page = items.into_iter().skip(start).take(limit).collect();
+    Ok((page, has_more))
+}
+
+/// Collect the distinct variables that appear in a set of racing StateChange pairs.
+fn race_variables(pairs: &[(Event, Event)]) -> HashSet<String> {
+    use raceway_core::event::EventKind;
+
+    pairs
+        .iter()
+        .flat_map(|(event1, event2)| [event1, event2])
+        .filter_map(|event| match &event.kind {
+            EventKind::StateChange { variable, .. } => Some(variable.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct GlobalRaceDetail {
+    severity: String,
+    variable: String,
+    trace1_id: String,
+    trace2_id: String,
+    event1_thread: String,
+    event2_thread: String,
+    event1_location: String,
+    event2_location: String,
+    event1_timestamp: String,
+    event2_timestamp: String,
+    description: String,
+    recommendation: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GlobalAnalysis {
+    total_traces: usize,
+    total_events: usize,
+    concurrent_events: usize,
+    potential_races: usize,
+    anomalies: Vec<String>,
+    race_details: Vec<GlobalRaceDetail>,
+}
+
+async fn analyze_global_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    match state
+        .engine
+        .analysis()
+        .find_global_concurrent_events()
+        .await
+    {
+        Ok(concurrent) => {
+            let concurrent = scope_race_pairs(&state, &params, &concurrent);
+            let analysis = build_global_analysis(&state, &concurrent).await;
+            Ok((StatusCode::OK, Json(ApiResponse::success(analysis))))
+        }
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Global analysis failed: {}", e))),
+        )),
+    }
+}
+
+/// Build the global race report from cross-trace concurrent pairs
+async fn build_global_analysis(state: &AppState, concurrent: &[(Event, Event)]) -> GlobalAnalysis {
+    let mut anomalies = Vec::new();
+    let mut race_details = Vec::new();
+    const MAX_RACE_DETAILS: usize = 100;
+
+    for (event1, event2) in concurrent {
+        if race_details.len() >= MAX_RACE_DETAILS {
+            break;
+        }
+
+        if let (
+            raceway_core::event::EventKind::StateChange {
+                variable: var1,
+                old_value: _old1,
+                new_value: new1,
+                location: loc1,
+                access_type: access1,
+            },
+            raceway_core::event::EventKind::StateChange {
+                variable: var2,
+                old_value: _old2,
+                new_value: new2,
+                location: loc2,
+                access_type: access2,
+            },
+        ) = (&event1.kind, &event2.kind)
+        {
+            if var1 != var2 {
+                continue;
+            }
+
+            let is_write1 = access1.is_write();
+            let is_write2 = access2.is_write();
+
+            let severity_desc = match (is_write1, is_write2) {
+                (true, true) => (
+                    "CRITICAL",
+                    format!(
+                        "Cross-trace write-write race on {}. Trace {} (thread {}) wrote {:?}, Trace {} (thread {}) wrote {:?}",
+                        var1,
+                        &event1.trace_id.to_string()[..8],
+                        event1.metadata.thread_id,
+                        new1,
+                        &event2.trace_id.to_string()[..8],
+                        event2.metadata.thread_id,
+                        new2
+                    ),
+                ),
+                (true, false) | (false, true) => (
+                    "WARNING",
+                    format!(
+                        "Cross-trace read-write race on {}. One thread read while another wrote across different traces.",
+                        var1
+                    ),
+                ),
+                (false, false) => (
+                    "INFO",
+                    format!(
+                        "Concurrent reads on {} across traces. Generally safe but indicates potential race.",
+                        var1
+                    ),
+                ),
+            };
+
+            race_details.push(GlobalRaceDetail {
+                severity: severity_desc.0.to_string(),
+                variable: var1.clone(),
+                trace1_id: event1.trace_id.to_string(),
+                trace2_id: event2.trace_id.to_string(),
+                event1_thread: event1.metadata.thread_id.clone(),
+                event2_thread: event2.metadata.thread_id.clone(),
+                event1_location: loc1.clone(),
+                event2_location: loc2.clone(),
+                event1_timestamp: event1.timestamp.to_string(),
+                event2_timestamp: event2.timestamp.to_string(),
+                description: severity_desc.1,
+                // From the lock usage of the two racing traces
+                recommendation: state
+                    .engine
+                    .analysis()
+                    .get_lock_recommendations(
+                        [var1],
+                        &HashSet::from([event1.trace_id, event2.trace_id]),
+                    )
+                    .await
+                    .remove(var1),
+            });
+        }
+    }
+
+    if !race_details.is_empty() {
+        anomalies.push(format!(
+            "🌐 GLOBAL: Found {} pairs of concurrent events across all traces",
+            concurrent.len()
+        ));
+        if concurrent.len() > race_details.len() {
+            anomalies.push(format!(
+                "   Showing first {} race pairs ({} total)",
+                race_details.len(),
+                concurrent.len()
+            ));
+        }
+    }
+
+    // Fetch aggregate counts without loading entire datasets
+    let total_events = state.engine.storage().count_events().await.unwrap_or(0);
+    let total_traces = state.engine.storage().count_traces().await.unwrap_or(0);
+
+    GlobalAnalysis {
+        total_traces,
+        total_events,
+        concurrent_events: concurrent.len(),
+        potential_races: concurrent.len(),
+        anomalies,
+        race_details,
+    }
+}
+
+#[derive(Serialize)]
+struct GlobalAnalysisJobStarted {
+    job_id: String,
+}
+
+#[derive(Serialize)]
+struct GlobalAnalysisJobStatus {
+    job_id: String,
+    status: JobStatus,
+    processed: usize,
+    total: usize,
+    progress_percent: f64,
+    started_at: String,
+    finished_at: Option<String>,
+    error: Option<String>,
+    result: Option<GlobalAnalysis>,
+}
+
+/// Start a background global analysis; poll `GET /api/analyze/global/:job_id` for progress
+async fn start_global_analysis_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let job_id = state.engine.start_global_analysis();
+
+    if state.verbose {
+        println!(
+            "[{}] 🌐 start_global_analysis_handler -> job {}",
+            Local::now().format("%H:%M:%S.%3f"),
+            job_id
+        );
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(ApiResponse::success(GlobalAnalysisJobStarted {
+            job_id: job_id.to_string(),
+        })),
+    )
+}
+
+async fn get_global_analysis_job_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let job_uuid = Uuid::parse_str(&job_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid job ID format".to_string())),
+        )
+    })?;
+
+    let job = state.engine.global_analysis_job(job_uuid).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Job {} not found", job_id))),
+        )
+    })?;
+
+    let result = match &job.result {
+        Some(pairs) => {
+            let pairs = scope_race_pairs(&state, &params, pairs);
+            Some(build_global_analysis(&state, &pairs).await)
+        }
+        None => None,
+    };
+
+    let progress_percent = match job.status {
+        JobStatus::Completed => 100.0,
+        _ if job.total == 0 => 0.0,
+        _ => job.processed as f64 / job.total as f64 * 100.0,
+    };
+
+    let status = GlobalAnalysisJobStatus {
+        job_id: job.id.to_string(),
+        status: job.status,
+        processed: job.processed,
+        total: job.total,
+        progress_percent,
+        started_at: job.started_at.to_rfc3339(),
+        finished_at: job.finished_at.map(|t| t.to_rfc3339()),
+        error: job.error,
+        result,
+    };
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(status))))
+}
+
+async fn get_audit_trail_handler(
+    State(state): State<AppState>,
+    Path((trace_id, variable)): Path<(String, String)>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
+
+    // Use storage backend directly - no graph reconstruction
+    match state
+        .engine
+        .analysis()
+        .get_audit_trail(trace_uuid, &variable)
+        .await
+    {
+        Ok(audit_trail) => Ok((StatusCode::OK, Json(ApiResponse::success(audit_trail)))),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!(
+                "Audit trail generation failed: {}",
+                e
+            ))),
+        )),
+    }
+}
+
+async fn get_full_trace_analysis_handler(
+    State(state): State<AppState>,
+    Path(trace_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
+
+    // Use storage backend directly - preserves all accumulated baselines and caches
+    let analysis_data = state
+        .engine
+        .analysis()
+        .get_trace_analysis_data(trace_uuid)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(format!("Failed to fetch trace: {}", e))),
+            )
+        })?;
+
+    let concurrent = state
+        .engine
+        .analysis()
+        .find_concurrent_events(trace_uuid)
+        .await
+        .unwrap_or_default();
+    // Pairs stopped at `race_detection.max_pairs_per_trace`
+    let truncated = state
+        .engine
+        .analysis()
+        .concurrent_events_truncated(trace_uuid)
+        .await
+        .unwrap_or(false);
+
+    let races = state
+        .engine
+        .analysis()
+        .find_races_with_severity(trace_uuid)
+        .await
+        .unwrap_or_default();
+
+    let double_checked_locking = state
+        .engine
+        .analysis()
+        .find_double_checked_locking(trace_uuid)
+        .await
+        .unwrap_or_default();
+
+    let unreleased_locks = state
+        .engine
+        .analysis()
+        .find_unreleased_locks(trace_uuid)
+        .await
+        .unwrap_or_default();
+
+    #[derive(Serialize)]
+    struct RaceDetail {
+        severity: String,
+        variable: String,
+        event1_thread: String,
+        event2_thread: String,
+        event1_location: String,
+        event2_location: String,
+        description: String,
+        recommendation: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct RaceAnalysis {
+        concurrent_events: usize,
+        potential_races: usize,
+        anomalies: Vec<String>,
+        race_details: Vec<RaceDetail>,
+        double_checked_locking: Vec<DoubleCheckedLocking>,
+        unreleased_locks: Vec<UnreleasedLock>,
+        /// Concurrent pairs stopped at `race_detection.max_pairs_per_trace`,
+        /// so the counts and details above are partial
+        truncated: bool,
+    }
+
+    #[derive(Serialize)]
+    struct FullTraceAnalysis {
+        trace_id: String,
+        total_events: usize,
+        events: Vec<serde_json::Value>,
+        audit_trails: HashMap<String, Vec<VariableAccess>>,
+        analysis: RaceAnalysis,
+        critical_path: Option<serde_json::Value>,
+        anomalies: Vec<serde_json::Value>,
+        dependencies: Option<ServiceDependencies>,
+        health: TraceHealth,
+        timing_breakdown: TimingBreakdown,
+    }
+
+    let mut anomalies = Vec::new();
+    let mut race_details = Vec::new();
+
+    // From the lock usage of this trace and the traces merged into it
+    let merged_traces: HashSet<Uuid> = analysis_data
+        .events
+        .iter()
+        .map(|event| event.trace_id)
+        .collect();
+    let recommendations = state
+        .engine
+        .analysis()
+        .get_lock_recommendations(&race_variables(&concurrent), &merged_traces)
+        .await;
+
+    for race in &races {
+        use raceway_core::event::EventKind;
+
+        let (event1, event2) = (&race.event1, &race.event2);
+        if let (
+            EventKind::StateChange {
+                variable: var1,
+                old_value: old1,
+                new_value: new1,
+                location: loc1,
+                access_type: access1,
+            },
+            EventKind::StateChange {
+                variable: var2,
+                old_value: old2,
+                new_value: new2,
+                location: loc2,
+                access_type: access2,
+            },
+        ) = (&event1.kind, &event2.kind)
+        {
+            if var1 != var2 {
+                continue;
+            }
+
+            let is_write1 = access1.is_write();
+            let is_write2 = access2.is_write();
+
+            let severity = race.severity.label();
+            let description = if is_write1 && is_write2 {
+                format!(
+                    "Write-Write race on {}. Both threads modified the same variable without synchronization{}.",
+                    var1,
+                    if race.values_diverge {
+                        " and stored different values"
+                    } else {
+                        ""
+                    }
+                )
+            } else if is_write1 || is_write2 {
+                format!(
+                    "Read-Write race on {}. One thread read while {} concurrent writer(s) wrote.",
+                    var1, race.concurrent_writers
+                )
+            } else {
+                format!(
+                    "Concurrent reads on {}. Generally safe but indicates potential race.",
+                    var1
+                )
+            };
+
+            let event1_id_short = &event1.id.to_string()[..8];
+            let event2_id_short = &event2.id.to_string()[..8];
+
+            race_details.push(RaceDetail {
+                severity: severity.to_string(),
+                variable: var1.clone(),
+                event1_thread: event1.metadata.thread_id.clone(),
+                event2_thread: event2.metadata.thread_id.clone(),
+                event1_location: loc1.clone(),
+                event2_location: loc2.clone(),
+                description: description.clone(),
+                recommendation: recommendations.get(var1).cloned(),
+            });
+
+            anomalies.push(format!("🚨 {} RACE on {}", severity, var1));
+            anomalies.push(format!(
+                "   Event 1: {} @ {} [{}]",
+                event1_id_short, loc1, event1.metadata.thread_id
+            ));
+            anomalies.push(format!(
+                "           @ {}",
+                event1.timestamp.format("%H:%M:%S%.3f")
+            ));
+            if is_write1 {
+                anomalies.push(format!("           WRITE: {:?} → {:?}", old1, new1));
+            } else {
+                anomalies.push(format!("           READ: {:?}", new1));
+            }
+            anomalies.push(format!(
+                "   Event 2: {} @ {} [{}]",
+                event2_id_short, loc2, event2.metadata.thread_id
+            ));
+            anomalies.push(format!(
+                "           @ {}",
+                event2.timestamp.format("%H:%M:%S%.3f")
+            ));
+            if is_write2 {
+                anomalies.push(format!("           WRITE: {:?} → {:?}", old2, new2));
+            } else {
+                anomalies.push(format!("           READ: {:?}", new2));
+            }
+            if let Some(recommendation) = recommendations.get(var1) {
+                anomalies.push(format!("   💡 {}", recommendation));
+            }
+            anomalies.push(String::new());
+        }
+    }
+
+    for finding in &double_checked_locking {
+        anomalies.push(format!(
+            "⚠️  WARNING double-checked locking on {}",
+            finding.variable
+        ));
+        anomalies.push(format!("   {}", finding.description));
+        anomalies.push(String::new());
+    }
+
+    for lock in &unreleased_locks {
+        let kind = match lock.imbalance {
+            LockImbalance::UnreleasedAcquire => "UNRELEASED",
+            LockImbalance::UnmatchedRelease => "UNMATCHED RELEASE of",
+        };
+        anomalies.push(format!(
+            "🔒 {} lock {} on thread {}: {} acquires, {} releases",
+            kind, lock.lock_id, lock.thread_id, lock.acquires, lock.releases
+        ));
+        if let Some(location) = &lock.location {
+            anomalies.push(format!("   at {}", location));
+        }
+        anomalies.push(String::new());
+    }
+
+    if anomalies.is_empty() {
+        anomalies.push("No potential races detected for this trace".to_string());
+    }
+
+    let TraceAnalysisData {
+        events,
+        audit_trails,
+        critical_path,
+        anomalies: detected_anomalies,
+        dependencies,
+    } = analysis_data;
+
+    // `event_kind`, `event_offset` and `event_limit` only page the returned
+    // events; everything else above is computed over the whole trace
+    let event_kind = params.get("event_kind");
+    let event_offset: usize = params
+        .get("event_offset")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(0);
+    let event_limit: usize = params
+        .get("event_limit")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(usize::MAX);
+
+    let events_json: Vec<serde_json::Value> = events
+        .iter()
+        .filter(|e| match event_kind {
+            // EventKind is externally tagged, so the variant name is its only key
+            Some(kind) => serde_json::to_value(&e.kind)
+                .map(|value| value.get(kind).is_some())
+                .unwrap_or(false),
+            None => true,
+        })
+        .skip(event_offset)
+        .take(event_limit)
+        .map(|e| serde_json::to_value(e).unwrap_or(serde_json::json!({})))
+        .collect();
+
+    let health = health_score(&HealthInputs {
+        races: races.iter().map(|race| race.severity).collect(),
+        anomalies: detected_anomalies.iter().map(|a| a.severity).collect(),
+        critical_path_percentage: critical_path.as_ref().map(|cp| cp.percentage_of_total),
+        coverage: event_coverage(&events),
+    });
+    let timing_breakdown = timing_breakdown(&events);
+
+    let critical_path_json = critical_path.map(|cp| {
+        #[derive(Serialize)]
+        struct PathEvent {
+            id: String,
+            kind: String,
+            location: String,
+            timestamp: String,
+            duration_ms: f64,
+        }
+
+        let path_events: Vec<PathEvent> = cp
+            .path
+            .iter()
+            .map(|event| {
+                let kind = match &event.kind {
+                    raceway_core::event::EventKind::FunctionCall { function_name, .. } => {
+                        format!("FunctionCall({})", function_name)
+                    }
+                    raceway_core::event::EventKind::AsyncSpawn { .. } => "AsyncSpawn".to_string(),
+                    raceway_core::event::EventKind::StateChange { variable, .. } => {
+                        format!("StateChange({})", variable)
+                    }
+                    raceway_core::event::EventKind::HttpRequest { method, url, .. } => {
+                        format!("HttpRequest({} {})", method, url)
+                    }
+                    raceway_core::event::EventKind::DatabaseQuery { .. } => {
+                        "DatabaseQuery".to_string()
+                    }
+                    _ => "Other".to_string(),
+                };
+
+                let location = match &event.kind {
+                    raceway_core::event::EventKind::FunctionCall { file, line, .. } => {
+                        format!("{}:{}", file, line)
+                    }
+                    raceway_core::event::EventKind::StateChange { location, .. } => {
+                        location.clone()
+                    }
+                    raceway_core::event::EventKind::HttpRequest { url, .. } => url.clone(),
+                    _ => "unknown".to_string(),
+                };
+
+                PathEvent {
+                    id: event.id.to_string(),
+                    kind,
+                    location,
+                    timestamp: event.timestamp.to_rfc3339(),
+                    duration_ms: event.metadata.duration_ns.unwrap_or(0) as f64 / 1_000_000.0,
                 }
+            })
+            .collect();
+
+        serde_json::json!({
+            "trace_id": trace_id.clone(),
+            "path_events": cp.path.len(),
+            "total_duration_ms": cp.total_duration_ms,
+            "trace_total_duration_ms": cp.trace_total_duration_ms,
+            "percentage_of_total": cp.percentage_of_total,
+            "path": path_events,
+        })
+    });
+
+    state.metrics.record_analysis(
+        trace_uuid,
+        Some(concurrent.len()),
+        Some(detected_anomalies.len()),
+    );
+
+    let anomalies_json: Vec<serde_json::Value> = detected_anomalies
+        .iter()
+        .map(|a| serde_json::to_value(a).unwrap_or(serde_json::json!({})))
+        .collect();
+
+    let response = FullTraceAnalysis {
+        trace_id: trace_id.clone(),
+        total_events: events.len(),
+        events: events_json,
+        audit_trails,
+        analysis: RaceAnalysis {
+            concurrent_events: concurrent.len(),
+            potential_races: concurrent.len(),
+            anomalies,
+            race_details,
+            double_checked_locking,
+            unreleased_locks,
+            truncated,
+        },
+        critical_path: critical_path_json,
+        anomalies: anomalies_json,
+        dependencies,
+        health,
+        timing_breakdown,
+    };
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+}
+
+async fn get_critical_path_handler(
+    State(state): State<AppState>,
+    Path(trace_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
+
+    // Use storage backend directly
+    match state.engine.analysis().get_critical_path(trace_uuid).await {
+        Ok(critical_path) => {
+            #[derive(Serialize)]
+            struct PathEvent {
+                id: String,
+                kind: String,
+                location: String,
+                timestamp: String,
+                duration_ms: f64,
+            }
+
+            #[derive(Serialize)]
+            struct CriticalPathResponse {
+                trace_id: String,
+                path_events: usize,
+                total_duration_ms: f64,
+                trace_total_duration_ms: f64,
+                percentage_of_total: f64,
+                path: Vec<PathEvent>,
             }
 
-            // Fetch aggregate counts without loading entire datasets
-            let total_events = state.engine.storage().count_events().await.unwrap_or(0);
-            let total_traces = state.engine.storage().count_traces().await.unwrap_or(0);
+            let path = critical_path
+                .path
+                .iter()
+                .map(|event| {
+                    let kind = match &event.kind {
+                        raceway_core::event::EventKind::FunctionCall { function_name, .. } => {
+                            format!("FunctionCall({})", function_name)
+                        }
+                        raceway_core::event::EventKind::StateChange { variable, .. } => {
+                            format!("StateChange({})", variable)
+                        }
+                        raceway_core::event::EventKind::AsyncSpawn { .. } => {
+                            "AsyncSpawn".to_string()
+                        }
+                        raceway_core::event::EventKind::HttpRequest { method, url, .. } => {
+                            format!("HttpRequest({} {})", method, url)
+                        }
+                        raceway_core::event::EventKind::DatabaseQuery { .. } => {
+                            "DatabaseQuery".to_string()
+                        }
+                        _ => "Other".to_string(),
+                    };
+
+                    let location = match &event.kind {
+                        raceway_core::event::EventKind::FunctionCall { file, line, .. } => {
+                            format!("{}:{}", file, line)
+                        }
+                        raceway_core::event::EventKind::StateChange { location, .. } => {
+                            location.clone()
+                        }
+                        raceway_core::event::EventKind::HttpRequest { url, .. } => url.clone(),
+                        _ => "unknown".to_string(),
+                    };
+
+                    PathEvent {
+                        id: event.id.to_string(),
+                        kind,
+                        location,
+                        timestamp: event.timestamp.to_rfc3339(),
+                        duration_ms: event.metadata.duration_ns.unwrap_or(0) as f64 / 1_000_000.0,
+                    }
+                })
+                .collect();
+
+            let response = CriticalPathResponse {
+                trace_id,
+                path_events: critical_path.path.len(),
+                total_duration_ms: critical_path.total_duration_ms,
+                trace_total_duration_ms: critical_path.trace_total_duration_ms,
+                percentage_of_total: critical_path.percentage_of_total,
+                path,
+            };
+
+            Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+        }
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!(
+                "Critical path analysis failed: {}",
+                e
+            ))),
+        )),
+    }
+}
+
+async fn get_anomalies_handler(
+    State(state): State<AppState>,
+    Path(trace_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
+
+    // Update baselines first to ensure fresh metrics for anomaly detection
+    let _ = state.engine.analysis().update_baselines(trace_uuid).await;
+
+    // Detect anomalies with updated baselines
+    let anomalies = state
+        .engine
+        .analysis()
+        .detect_anomalies(trace_uuid)
+        .await
+        .unwrap_or_default();
+    state
+        .metrics
+        .record_analysis(trace_uuid, None, Some(anomalies.len()));
+
+    #[derive(Serialize)]
+    struct AnomaliesResponse {
+        trace_id: String,
+        anomaly_count: usize,
+        anomalies: Vec<Anomaly>,
+    }
+
+    let response = AnomaliesResponse {
+        trace_id,
+        anomaly_count: anomalies.len(),
+        anomalies,
+    };
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+}
+
+/// Anomalies seen so far across all traces, with repeats of the same
+/// operation folded into one alert per cooldown window
+async fn get_anomaly_alerts_handler(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    #[derive(Serialize)]
+    struct AnomalyAlertsResponse {
+        total_alerts: usize,
+        cooldown_seconds: u64,
+        alerts: Vec<AnomalyAlert>,
+    }
+
+    let analysis = state.engine.analysis();
+    let alerts = analysis.get_anomaly_alerts();
 
-            let analysis = GlobalAnalysis {
-                total_traces,
-                total_events,
-                concurrent_events: concurrent.len(),
-                potential_races: concurrent.len(),
-                anomalies,
-                race_details,
-            };
+    let response = AnomalyAlertsResponse {
+        total_alerts: alerts.len(),
+        cooldown_seconds: analysis.anomaly_cooldown().as_secs(),
+        alerts,
+    };
 
-            Ok((StatusCode::OK, Json(ApiResponse::success(analysis))))
-        }
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error(format!("Global analysis failed: {}", e))),
-        )),
-    }
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
 }
 
-async fn get_audit_trail_handler(
+async fn get_dependencies_handler(
     State(state): State<AppState>,
-    Path((trace_id, variable)): Path<(String, String)>,
+    Path(trace_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
     let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
         (
@@ -1164,27 +3264,32 @@ async fn get_audit_trail_handler(
         )
     })?;
 
-    // Use storage backend directly - no graph reconstruction
+    let by_instance = params
+        .get("by_instance")
+        .is_some_and(|value| value == "true");
+
+    // Use storage backend directly
     match state
         .engine
         .analysis()
-        .get_audit_trail(trace_uuid, &variable)
+        .get_service_dependencies(trace_uuid, by_instance)
         .await
     {
-        Ok(audit_trail) => Ok((StatusCode::OK, Json(ApiResponse::success(audit_trail)))),
+        Ok(deps) => Ok((StatusCode::OK, Json(ApiResponse::success(deps)))),
         Err(e) => Err((
             StatusCode::NOT_FOUND,
             Json(ApiResponse::error(format!(
-                "Audit trail generation failed: {}",
+                "Dependency analysis failed: {}",
                 e
             ))),
         )),
     }
 }
 
-async fn get_full_trace_analysis_handler(
+async fn get_trace_events_handler(
     State(state): State<AppState>,
     Path(trace_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
     let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
         (
@@ -1192,266 +3297,203 @@ async fn get_full_trace_analysis_handler(
             Json(ApiResponse::error("Invalid trace ID format".to_string())),
         )
     })?;
+    let cursor = parse_cursor(params.get("after").map(String::as_str).unwrap_or(""))?;
+    let limit: usize = params
+        .get("limit")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(1000);
 
-    // Use storage backend directly - preserves all accumulated baselines and caches
-    let analysis_data = state
+    // Storage returns events in stable order: timestamp, causal depth, id
+    let events = state
         .engine
-        .analysis()
-        .get_trace_analysis_data(trace_uuid)
+        .storage()
+        .get_trace_events(trace_uuid)
         .await
         .map_err(|e| {
             (
-                StatusCode::NOT_FOUND,
-                Json(ApiResponse::error(format!("Failed to fetch trace: {}", e))),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Failed to fetch events: {}", e))),
             )
         })?;
-
-    let concurrent = state
-        .engine
-        .analysis()
-        .find_concurrent_events(trace_uuid)
-        .await
-        .unwrap_or_default();
-
-    #[derive(Serialize)]
-    struct RaceDetail {
-        severity: String,
-        variable: String,
-        event1_thread: String,
-        event2_thread: String,
-        event1_location: String,
-        event2_location: String,
-        description: String,
-    }
-
-    #[derive(Serialize)]
-    struct RaceAnalysis {
-        concurrent_events: usize,
-        potential_races: usize,
-        anomalies: Vec<String>,
-        race_details: Vec<RaceDetail>,
-    }
+    let (events, has_more) = page_after_cursor(events, cursor, limit, |event| event.id)?;
 
     #[derive(Serialize)]
-    struct FullTraceAnalysis {
+    struct TraceEventsResponse {
         trace_id: String,
-        events: Vec<serde_json::Value>,
-        audit_trails: HashMap<String, Vec<VariableAccess>>,
-        analysis: RaceAnalysis,
-        critical_path: Option<serde_json::Value>,
-        anomalies: Vec<serde_json::Value>,
-        dependencies: Option<ServiceDependencies>,
+        next_cursor: Option<String>,
+        has_more: bool,
+        events: Vec<Event>,
     }
 
-    let mut anomalies = Vec::new();
-    let mut race_details = Vec::new();
-
-    for (event1, event2) in &concurrent {
-        use raceway_core::event::{AccessType, EventKind};
+    let response = TraceEventsResponse {
+        trace_id,
+        next_cursor: events
+            .last()
+            .map(|event| event.id)
+            .or(cursor)
+            .map(|id| id.to_string()),
+        has_more,
+        events,
+    };
 
-        if let (
-            EventKind::StateChange {
-                variable: var1,
-                old_value: old1,
-                new_value: new1,
-                location: loc1,
-                access_type: access1,
-            },
-            EventKind::StateChange {
-                variable: var2,
-                old_value: old2,
-                new_value: new2,
-                location: loc2,
-                access_type: access2,
-            },
-        ) = (&event1.kind, &event2.kind)
-        {
-            if var1 != var2 {
-                continue;
-            }
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+}
 
-            let is_write1 = matches!(
-                access1,
-                AccessType::Write | AccessType::AtomicWrite | AccessType::AtomicRMW
-            );
-            let is_write2 = matches!(
-                access2,
-                AccessType::Write | AccessType::AtomicWrite | AccessType::AtomicRMW
-            );
+/// Stream a trace's events as Server-Sent Events while they are captured.
+/// Each event is sent as an `event` message carrying the event JSON; if the
+/// client falls behind, a `lagged` message reports how many were skipped.
+/// The stream ends when the client disconnects.
+async fn stream_trace_events_handler(
+    State(state): State<AppState>,
+    Path(trace_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
 
-            let (severity, description) = if is_write1 && is_write2 {
-                (
-                    "CRITICAL",
-                    format!(
-                        "Write-Write race on {}. Both threads modified the same variable without synchronization.",
-                        var1
-                    ),
-                )
-            } else if is_write1 || is_write2 {
-                (
-                    "WARNING",
-                    format!(
-                        "Read-Write race on {}. One thread read while another wrote.",
-                        var1
-                    ),
-                )
-            } else {
-                (
-                    "INFO",
-                    format!(
-                        "Concurrent reads on {}. Generally safe but indicates potential race.",
-                        var1
-                    ),
-                )
+    use tokio::sync::broadcast::error::RecvError;
+
+    let receiver = state.engine.capture().subscribe();
+    let stream = futures::stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            let message = match receiver.recv().await {
+                Ok(event) if event.trace_id == trace_uuid => SseEvent::default()
+                    .event("event")
+                    .id(event.id.to_string())
+                    .json_data(&event),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => Ok(SseEvent::default()
+                    .event("lagged")
+                    .data(skipped.to_string())),
+                Err(RecvError::Closed) => return None,
             };
-
-            let event1_id_short = &event1.id.to_string()[..8];
-            let event2_id_short = &event2.id.to_string()[..8];
-
-            race_details.push(RaceDetail {
-                severity: severity.to_string(),
-                variable: var1.clone(),
-                event1_thread: event1.metadata.thread_id.clone(),
-                event2_thread: event2.metadata.thread_id.clone(),
-                event1_location: loc1.clone(),
-                event2_location: loc2.clone(),
-                description: description.clone(),
-            });
-
-            anomalies.push(format!("🚨 {} RACE on {}", severity, var1));
-            anomalies.push(format!(
-                "   Event 1: {} @ {} [{}]",
-                event1_id_short, loc1, event1.metadata.thread_id
-            ));
-            anomalies.push(format!(
-                "           @ {}",
-                event1.timestamp.format("%H:%M:%S%.3f")
-            ));
-            if is_write1 {
-                anomalies.push(format!("           WRITE: {:?} → {:?}", old1, new1));
-            } else {
-                anomalies.push(format!("           READ: {:?}", new1));
-            }
-            anomalies.push(format!(
-                "   Event 2: {} @ {} [{}]",
-                event2_id_short, loc2, event2.metadata.thread_id
-            ));
-            anomalies.push(format!(
-                "           @ {}",
-                event2.timestamp.format("%H:%M:%S%.3f")
-            ));
-            if is_write2 {
-                anomalies.push(format!("           WRITE: {:?} → {:?}", old2, new2));
-            } else {
-                anomalies.push(format!("           READ: {:?}", new2));
-            }
-            anomalies.push(String::new());
+            return Some((message, receiver));
         }
-    }
-
-    if anomalies.is_empty() {
-        anomalies.push("No potential races detected for this trace".to_string());
-    }
-
-    let TraceAnalysisData {
-        events,
-        audit_trails,
-        critical_path,
-        anomalies: detected_anomalies,
-        dependencies,
-    } = analysis_data;
+    });
 
-    let events_json: Vec<serde_json::Value> = events
-        .iter()
-        .map(|e| serde_json::to_value(e).unwrap_or(serde_json::json!({})))
-        .collect();
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
 
-    let critical_path_json = critical_path.map(|cp| {
-        #[derive(Serialize)]
-        struct PathEvent {
-            id: String,
-            kind: String,
-            location: String,
-            timestamp: String,
-            duration_ms: f64,
-        }
+/// Push live race alerts over a WebSocket. Every race that ingest creates in
+/// any trace is sent once as a JSON text message (`trace_id`, `variable`,
+/// `severity`, `event1`, `event2`); if the client falls behind, a
+/// `{"lagged": n}` message reports how many alerts were skipped. Messages from
+/// the client are ignored, and the push ends when it closes the socket.
+async fn race_alerts_ws_handler(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    // Subscribe before the handshake so races ingested meanwhile aren't missed
+    let alerts = state.engine.subscribe_race_alerts();
+    ws.on_upgrade(move |socket| push_race_alerts(socket, alerts))
+}
 
-        let path_events: Vec<PathEvent> = cp
-            .path
-            .iter()
-            .map(|event| {
-                let kind = match &event.kind {
-                    raceway_core::event::EventKind::FunctionCall { function_name, .. } => {
-                        format!("FunctionCall({})", function_name)
-                    }
-                    raceway_core::event::EventKind::AsyncSpawn { .. } => "AsyncSpawn".to_string(),
-                    raceway_core::event::EventKind::StateChange { variable, .. } => {
-                        format!("StateChange({})", variable)
-                    }
-                    raceway_core::event::EventKind::HttpRequest { method, url, .. } => {
-                        format!("HttpRequest({} {})", method, url)
-                    }
-                    raceway_core::event::EventKind::DatabaseQuery { .. } => {
-                        "DatabaseQuery".to_string()
-                    }
-                    _ => "Other".to_string(),
-                };
+async fn push_race_alerts(mut socket: WebSocket, mut alerts: broadcast::Receiver<RaceAlert>) {
+    use tokio::sync::broadcast::error::RecvError;
 
-                let location = match &event.kind {
-                    raceway_core::event::EventKind::FunctionCall { file, line, .. } => {
-                        format!("{}:{}", file, line)
-                    }
-                    raceway_core::event::EventKind::StateChange { location, .. } => {
-                        location.clone()
+    loop {
+        let message = tokio::select! {
+            alert = alerts.recv() => match alert {
+                Ok(alert) => match serde_json::to_string(&alert) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        tracing::warn!("Failed to serialize race alert: {}", e);
+                        continue;
                     }
-                    raceway_core::event::EventKind::HttpRequest { url, .. } => url.clone(),
-                    _ => "unknown".to_string(),
-                };
-
-                PathEvent {
-                    id: event.id.to_string(),
-                    kind,
-                    location,
-                    timestamp: event.timestamp.to_rfc3339(),
-                    duration_ms: event.metadata.duration_ns.unwrap_or(0) as f64 / 1_000_000.0,
+                },
+                Err(RecvError::Lagged(skipped)) => {
+                    serde_json::json!({ "lagged": skipped }).to_string()
                 }
-            })
-            .collect();
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        if socket.send(Message::Text(message)).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn get_trace_lineage_handler(
+    State(state): State<AppState>,
+    Path(trace_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
 
-        serde_json::json!({
-            "trace_id": trace_id.clone(),
-            "path_events": cp.path.len(),
-            "total_duration_ms": cp.total_duration_ms,
-            "trace_total_duration_ms": cp.trace_total_duration_ms,
-            "percentage_of_total": cp.percentage_of_total,
-            "path": path_events,
-        })
-    });
+    let analysis = state.engine.analysis();
+    let lineage = analysis.get_trace_lineage(trace_uuid).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Lineage lookup failed: {}", e))),
+        )
+    })?;
 
-    let anomalies_json: Vec<serde_json::Value> = detected_anomalies
-        .iter()
-        .map(|a| serde_json::to_value(a).unwrap_or(serde_json::json!({})))
-        .collect();
+    #[derive(Serialize)]
+    struct AttemptSummary {
+        trace_id: String,
+        attempt: u32,
+        retry_of: Option<String>,
+        event_count: usize,
+        potential_races: usize,
+    }
 
-    let response = FullTraceAnalysis {
-        trace_id: trace_id.clone(),
-        events: events_json,
-        audit_trails,
-        analysis: RaceAnalysis {
-            concurrent_events: concurrent.len(),
-            potential_races: concurrent.len(),
-            anomalies,
-            race_details,
-        },
-        critical_path: critical_path_json,
-        anomalies: anomalies_json,
-        dependencies,
+    #[derive(Serialize)]
+    struct LineageResponse {
+        trace_id: String,
+        original_trace_id: String,
+        attempt_count: usize,
+        attempts: Vec<AttemptSummary>,
+    }
+
+    // Per-attempt race counts answer "it passed on retry but raced on attempt 1"
+    let mut attempts = Vec::with_capacity(lineage.len());
+    for attempt in lineage {
+        let event_count = state
+            .engine
+            .storage()
+            .get_trace_events(attempt.trace_id)
+            .await
+            .map(|events| events.len())
+            .unwrap_or(0);
+        let potential_races = analysis
+            .find_concurrent_events(attempt.trace_id)
+            .await
+            .map(|pairs| pairs.len())
+            .unwrap_or(0);
+
+        attempts.push(AttemptSummary {
+            trace_id: attempt.trace_id.to_string(),
+            attempt: attempt.attempt,
+            retry_of: attempt.retry_of.map(|id| id.to_string()),
+            event_count,
+            potential_races,
+        });
+    }
+
+    let response = LineageResponse {
+        trace_id,
+        original_trace_id: attempts[0].trace_id.clone(),
+        attempt_count: attempts.len(),
+        attempts,
     };
 
     Ok((StatusCode::OK, Json(ApiResponse::success(response))))
 }
 
-async fn get_critical_path_handler(
+/// Export a trace with its distributed spans/edges and baselines so another
+/// server can reproduce its analysis
+async fn export_bundle_handler(
     State(state): State<AppState>,
     Path(trace_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
@@ -1462,96 +3504,122 @@ async fn get_critical_path_handler(
         )
     })?;
 
-    // Use storage backend directly
-    match state.engine.analysis().get_critical_path(trace_uuid).await {
-        Ok(critical_path) => {
-            #[derive(Serialize)]
-            struct PathEvent {
-                id: String,
-                kind: String,
-                location: String,
-                timestamp: String,
-                duration_ms: f64,
-            }
-
-            #[derive(Serialize)]
-            struct CriticalPathResponse {
-                trace_id: String,
-                path_events: usize,
-                total_duration_ms: f64,
-                trace_total_duration_ms: f64,
-                percentage_of_total: f64,
-                path: Vec<PathEvent>,
-            }
+    match state.engine.analysis().export_bundle(trace_uuid).await {
+        Ok(bundle) => Ok((StatusCode::OK, Json(ApiResponse::success(bundle)))),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Bundle export failed: {}", e))),
+        )),
+    }
+}
 
-            let path = critical_path
-                .path
-                .iter()
-                .map(|event| {
-                    let kind = match &event.kind {
-                        raceway_core::event::EventKind::FunctionCall { function_name, .. } => {
-                            format!("FunctionCall({})", function_name)
-                        }
-                        raceway_core::event::EventKind::StateChange { variable, .. } => {
-                            format!("StateChange({})", variable)
-                        }
-                        raceway_core::event::EventKind::AsyncSpawn { .. } => {
-                            "AsyncSpawn".to_string()
-                        }
-                        raceway_core::event::EventKind::HttpRequest { method, url, .. } => {
-                            format!("HttpRequest({} {})", method, url)
-                        }
-                        raceway_core::event::EventKind::DatabaseQuery { .. } => {
-                            "DatabaseQuery".to_string()
-                        }
-                        _ => "Other".to_string(),
-                    };
+async fn import_bundle_handler(
+    State(state): State<AppState>,
+    Json(bundle): Json<TraceBundle>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    match state.engine.analysis().import_bundle(bundle).await {
+        Ok(import) => Ok((StatusCode::OK, Json(ApiResponse::success(import)))),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(format!("Bundle import failed: {}", e))),
+        )),
+    }
+}
 
-                    let location = match &event.kind {
-                        raceway_core::event::EventKind::FunctionCall { file, line, .. } => {
-                            format!("{}:{}", file, line)
-                        }
-                        raceway_core::event::EventKind::StateChange { location, .. } => {
-                            location.clone()
-                        }
-                        raceway_core::event::EventKind::HttpRequest { url, .. } => url.clone(),
-                        _ => "unknown".to_string(),
-                    };
+#[derive(Deserialize)]
+struct AnalyzeBatchRequest {
+    trace_ids: Vec<String>,
+}
 
-                    PathEvent {
-                        id: event.id.to_string(),
-                        kind,
-                        location,
-                        timestamp: event.timestamp.to_rfc3339(),
-                        duration_ms: event.metadata.duration_ns.unwrap_or(0) as f64 / 1_000_000.0,
-                    }
-                })
-                .collect();
+#[derive(Serialize)]
+struct TraceBatchSummary {
+    potential_races: usize,
+    concurrent_events: usize,
+    anomaly_count: usize,
+}
 
-            let response = CriticalPathResponse {
-                trace_id,
-                path_events: critical_path.path.len(),
-                total_duration_ms: critical_path.total_duration_ms,
-                trace_total_duration_ms: critical_path.trace_total_duration_ms,
-                percentage_of_total: critical_path.percentage_of_total,
-                path,
-            };
+/// Summarize several traces in one round trip, keyed by trace ID
+async fn analyze_batch_handler(
+    State(state): State<AppState>,
+    Json(request): Json<AnalyzeBatchRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    const MAX_BATCH_TRACES: usize = 100;
 
-            Ok((StatusCode::OK, Json(ApiResponse::success(response))))
-        }
-        Err(e) => Err((
-            StatusCode::NOT_FOUND,
+    if request.trace_ids.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "trace_ids must not be empty".to_string(),
+            )),
+        ));
+    }
+    if request.trace_ids.len() > MAX_BATCH_TRACES {
+        return Err((
+            StatusCode::BAD_REQUEST,
             Json(ApiResponse::error(format!(
-                "Critical path analysis failed: {}",
-                e
+                "At most {} trace IDs can be analyzed per batch",
+                MAX_BATCH_TRACES
             ))),
-        )),
+        ));
+    }
+
+    let trace_uuids = request
+        .trace_ids
+        .iter()
+        .map(|id| {
+            Uuid::parse_str(id).map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::error(format!(
+                        "Invalid trace ID format: {}",
+                        id
+                    ))),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let analysis = state.engine.analysis();
+    let mut summaries = BTreeMap::new();
+    for trace_uuid in trace_uuids {
+        let analysis_error = |e: anyhow::Error| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!(
+                    "Failed to analyze trace {}: {}",
+                    trace_uuid, e
+                ))),
+            )
+        };
+        let concurrent = analysis
+            .find_concurrent_events(trace_uuid)
+            .await
+            .map_err(analysis_error)?;
+        let anomalies = analysis
+            .detect_anomalies(trace_uuid)
+            .await
+            .map_err(analysis_error)?;
+
+        state
+            .metrics
+            .record_analysis(trace_uuid, Some(concurrent.len()), Some(anomalies.len()));
+        summaries.insert(
+            trace_uuid.to_string(),
+            TraceBatchSummary {
+                potential_races: concurrent.len(),
+                concurrent_events: concurrent.len(),
+                anomaly_count: anomalies.len(),
+            },
+        );
     }
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(summaries))))
 }
 
-async fn get_anomalies_handler(
+async fn explain_pair_handler(
     State(state): State<AppState>,
     Path(trace_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
     let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
         (
@@ -1560,36 +3628,96 @@ async fn get_anomalies_handler(
         )
     })?;
 
-    // Update baselines first to ensure fresh metrics for anomaly detection
-    let _ = state.engine.analysis().update_baselines(trace_uuid).await;
+    let event_param = |name: &str| {
+        let value = params.get(name).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!(
+                    "Missing query parameter '{}'",
+                    name
+                ))),
+            )
+        })?;
+        Uuid::parse_str(value).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!(
+                    "Invalid event ID format for '{}'",
+                    name
+                ))),
+            )
+        })
+    };
+    let event_a = event_param("a")?;
+    let event_b = event_param("b")?;
 
-    // Detect anomalies with updated baselines
-    let anomalies = state
+    match state
         .engine
         .analysis()
-        .detect_anomalies(trace_uuid)
+        .explain_pair(trace_uuid, event_a, event_b)
         .await
-        .unwrap_or_default();
-
-    #[derive(Serialize)]
-    struct AnomaliesResponse {
-        trace_id: String,
-        anomaly_count: usize,
-        anomalies: Vec<Anomaly>,
+    {
+        Ok(explanation) => Ok((StatusCode::OK, Json(ApiResponse::success(explanation)))),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Explanation failed: {}", e))),
+        )),
     }
+}
 
-    let response = AnomaliesResponse {
-        trace_id,
-        anomaly_count: anomalies.len(),
-        anomalies,
+async fn causal_path_handler(
+    State(state): State<AppState>,
+    Path(trace_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Invalid trace ID format".to_string())),
+        )
+    })?;
+
+    let event_param = |name: &str| {
+        let value = params.get(name).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!(
+                    "Missing query parameter '{}'",
+                    name
+                ))),
+            )
+        })?;
+        Uuid::parse_str(value).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!(
+                    "Invalid event ID format for '{}'",
+                    name
+                ))),
+            )
+        })
     };
+    let from = event_param("from")?;
+    let to = event_param("to")?;
 
-    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+    match state
+        .engine
+        .analysis()
+        .find_causal_path(trace_uuid, from, to)
+        .await
+    {
+        Ok(events) => Ok((StatusCode::OK, Json(ApiResponse::success(events)))),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(e.to_string())),
+        )),
+    }
 }
 
-async fn get_dependencies_handler(
+async fn happens_before_handler(
     State(state): State<AppState>,
     Path(trace_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
     let trace_uuid = Uuid::parse_str(&trace_id).map_err(|_| {
         (
@@ -1598,27 +3726,47 @@ async fn get_dependencies_handler(
         )
     })?;
 
-    // Use storage backend directly
+    let event_param = |name: &str| {
+        let value = params.get(name).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!(
+                    "Missing query parameter '{}'",
+                    name
+                ))),
+            )
+        })?;
+        Uuid::parse_str(value).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(format!(
+                    "Invalid event ID format for '{}'",
+                    name
+                ))),
+            )
+        })
+    };
+    let event_a = event_param("a")?;
+    let event_b = event_param("b")?;
+
     match state
         .engine
         .analysis()
-        .get_service_dependencies(trace_uuid)
+        .event_ordering(trace_uuid, event_a, event_b)
         .await
     {
-        Ok(deps) => Ok((StatusCode::OK, Json(ApiResponse::success(deps)))),
-        Err(e) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error(format!(
-                "Dependency analysis failed: {}",
-                e
-            ))),
+        Ok(ordering) => Ok((StatusCode::OK, Json(ApiResponse::success(ordering)))),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(e.to_string())),
         )),
     }
 }
 
 async fn list_services_handler(
     State(state): State<AppState>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, (StatusCode, Json<ApiResponse<String>>)> {
     #[derive(Serialize)]
     struct ServiceInfo {
         name: String,
@@ -1648,6 +3796,48 @@ async fn list_services_handler(
             )
         })?;
 
+    // `group_by=<dimension>` rolls member services up into their groups
+    if let Some(dimension) = params.get("group_by") {
+        #[derive(Serialize)]
+        struct ServiceGroupInfo {
+            name: String,
+            services: Vec<String>,
+            event_count: usize,
+            trace_count: usize,
+        }
+
+        #[derive(Serialize)]
+        struct ServiceGroupsResponse {
+            group_by: String,
+            total_groups: usize,
+            groups: Vec<ServiceGroupInfo>,
+        }
+
+        let groups = resolve_service_groups(&state, dimension).await?;
+        let mut by_group: BTreeMap<String, ServiceGroupInfo> = BTreeMap::new();
+        for (service, event_count, trace_count) in services_data {
+            let name = group_of(&groups, &service);
+            let group = by_group
+                .entry(name.clone())
+                .or_insert_with(|| ServiceGroupInfo {
+                    name,
+                    services: Vec::new(),
+                    event_count: 0,
+                    trace_count: 0,
+                });
+            group.services.push(service);
+            group.event_count += event_count;
+            group.trace_count += trace_count;
+        }
+
+        let response = ServiceGroupsResponse {
+            group_by: dimension.clone(),
+            total_groups: by_group.len(),
+            groups: by_group.into_values().collect(),
+        };
+        return Ok((StatusCode::OK, Json(ApiResponse::success(response))).into_response());
+    }
+
     let services: Vec<ServiceInfo> = services_data
         .into_iter()
         .map(|(name, event_count, trace_count)| ServiceInfo {
@@ -1662,7 +3852,79 @@ async fn list_services_handler(
         services,
     };
 
-    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))).into_response())
+}
+
+/// Group assigned to services that have neither a config mapping nor a tag
+const UNGROUPED: &str = "ungrouped";
+
+/// Service -> group for `?group_by=<dimension>`. The `[service_groups.<dimension>]`
+/// config mapping wins over the services' `<dimension>` metadata tag.
+async fn resolve_service_groups(
+    state: &AppState,
+    dimension: &str,
+) -> Result<HashMap<String, String>, (StatusCode, Json<ApiResponse<String>>)> {
+    let mut groups = state
+        .engine
+        .storage()
+        .get_service_tag_values(dimension)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!(
+                    "Failed to resolve service groups: {}",
+                    e
+                ))),
+            )
+        })?;
+    if let Some(mapping) = state.service_groups.get(dimension) {
+        groups.extend(mapping.clone());
+    }
+    Ok(groups)
+}
+
+fn group_of(groups: &HashMap<String, String>, service: &str) -> String {
+    groups
+        .get(service)
+        .cloned()
+        .unwrap_or_else(|| UNGROUPED.to_string())
+}
+
+/// Roll `{from_service, to_service, [link_type], call_count}` rows up to group level,
+/// summing call counts. Calls within one group are dropped, like same-service calls.
+fn group_service_calls(
+    calls: Vec<serde_json::Value>,
+    groups: &HashMap<String, String>,
+) -> Vec<serde_json::Value> {
+    let mut totals: HashMap<(String, String, Option<String>), u64> = HashMap::new();
+    for call in calls {
+        let from = group_of(groups, call["from_service"].as_str().unwrap_or_default());
+        let to = group_of(groups, call["to_service"].as_str().unwrap_or_default());
+        if from == to {
+            continue;
+        }
+        let link_type = call["link_type"].as_str().map(str::to_string);
+        *totals.entry((from, to, link_type)).or_insert(0) +=
+            call["call_count"].as_u64().unwrap_or(0);
+    }
+
+    let mut grouped: Vec<_> = totals.into_iter().collect();
+    grouped.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    grouped
+        .into_iter()
+        .map(|((from, to, link_type), call_count)| {
+            let mut call = serde_json::json!({
+                "from_service": from,
+                "to_service": to,
+                "call_count": call_count,
+            });
+            if let Some(link_type) = link_type {
+                call["link_type"] = link_type.into();
+            }
+            call
+        })
+        .collect()
 }
 
 async fn get_service_dependencies_handler(
@@ -1726,6 +3988,101 @@ async fn get_service_dependencies_handler(
     Ok((StatusCode::OK, Json(ApiResponse::success(response))))
 }
 
+/// Latency percentiles over the events that report `duration_ns`; all `None`
+/// when none do
+#[derive(Serialize)]
+struct ServiceLatency {
+    samples: usize,
+    p50_ms: Option<f64>,
+    p95_ms: Option<f64>,
+    p99_ms: Option<f64>,
+}
+
+impl ServiceLatency {
+    fn from_durations_ns(durations_ns: Vec<u64>) -> Self {
+        let mut durations_ms: Vec<f64> = durations_ns
+            .into_iter()
+            .map(|ns| ns as f64 / 1_000_000.0)
+            .collect();
+        durations_ms.sort_by(f64::total_cmp);
+
+        let percentile = |p: f64| {
+            let last = durations_ms.len().checked_sub(1)?;
+            // Linear interpolation between the closest ranks
+            let rank = last as f64 * p;
+            let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+            let fraction = rank - lower as f64;
+            Some(durations_ms[lower] + (durations_ms[upper] - durations_ms[lower]) * fraction)
+        };
+
+        Self {
+            samples: durations_ms.len(),
+            p50_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            p99_ms: percentile(0.99),
+        }
+    }
+}
+
+async fn get_service_overview_handler(
+    State(state): State<AppState>,
+    Path(service_name): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    #[derive(Serialize)]
+    struct ServiceOverviewResponse {
+        name: String,
+        event_count: usize,
+        trace_count: usize,
+        avg_events_per_trace: f64,
+        latency: ServiceLatency,
+    }
+
+    let storage_error = |e: anyhow::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!(
+                "Failed to fetch service overview: {}",
+                e
+            ))),
+        )
+    };
+
+    let storage = state.engine.storage();
+    let (_, event_count, trace_count) = storage
+        .get_all_services()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .find(|(name, _, _)| *name == service_name)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(format!(
+                    "Service '{}' not found",
+                    service_name
+                ))),
+            )
+        })?;
+    let durations = storage
+        .get_service_event_durations(&service_name)
+        .await
+        .map_err(storage_error)?;
+
+    let response = ServiceOverviewResponse {
+        name: service_name,
+        event_count,
+        trace_count,
+        avg_events_per_trace: if trace_count > 0 {
+            event_count as f64 / trace_count as f64
+        } else {
+            0.0
+        },
+        latency: ServiceLatency::from_durations_ns(durations),
+    };
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+}
+
 async fn get_service_health_handler(
     State(state): State<AppState>,
     Query(params): Query<std::collections::HashMap<String, String>>,
@@ -1804,6 +4161,9 @@ async fn get_service_traces_handler(
     Ok((StatusCode::OK, Json(ApiResponse::success(response))))
 }
 
+/// Most per-minute throughput buckets one request can ask for (one day)
+const MAX_THROUGHPUT_BUCKETS: u64 = 24 * 60;
+
 async fn get_performance_metrics_handler(
     State(state): State<AppState>,
     Query(params): Query<std::collections::HashMap<String, String>>,
@@ -1813,36 +4173,75 @@ async fn get_performance_metrics_handler(
         .and_then(|s| s.parse::<usize>().ok())
         .unwrap_or(50);
 
-    // Use cache to avoid expensive queries
-    let metrics = state
-        .perf_metrics_cache
-        .get_or_fetch(|| {
-            let storage = state.engine.storage().clone();
-            async move {
-                storage
-                    .get_performance_metrics(limit)
-                    .await
-                    .map(|metrics| serde_json::to_value(&metrics).unwrap_or(serde_json::Value::Null))
-            }
-        })
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::error(format!(
-                    "Failed to fetch performance metrics: {}",
-                    e
-                ))),
+    let scope = MetricsScope {
+        window_minutes: params
+            .get("window_minutes")
+            .and_then(|s| s.parse::<u64>().ok()),
+        service: params.get("service").cloned(),
+    };
+
+    let fetch = || {
+        let storage = state.engine.storage().clone();
+        let scope = scope.clone();
+        async move {
+            storage
+                .get_performance_metrics(limit, &scope)
+                .await
+                .map(|metrics| serde_json::to_value(&metrics).unwrap_or(serde_json::Value::Null))
+        }
+    };
+
+    // Use cache to avoid expensive queries; the cache holds a single value, so only
+    // unscoped requests go through it
+    let mut metrics = if scope.is_unscoped() {
+        state.perf_metrics_cache.get_or_fetch(fetch).await
+    } else {
+        fetch().await
+    }
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!(
+                "Failed to fetch performance metrics: {}",
+                e
+            ))),
+        )
+    })?;
+
+    // Per-minute history is opt-in and never cached, so the newest bucket stays current
+    let buckets = params
+        .get("buckets")
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&buckets| buckets > 0);
+    if let Some(buckets) = buckets {
+        let history = state
+            .engine
+            .storage()
+            .get_throughput_buckets(
+                buckets.min(MAX_THROUGHPUT_BUCKETS),
+                scope.service.as_deref(),
             )
-        })?;
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error(format!(
+                        "Failed to fetch throughput history: {}",
+                        e
+                    ))),
+                )
+            })?;
+        metrics["throughput"]["buckets"] = serde_json::to_value(history).unwrap_or_default();
+    }
 
     Ok((StatusCode::OK, Json(ApiResponse::success(metrics))))
 }
 
 async fn get_distributed_edges_handler(
     State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let edges = state
+    let mut edges = state
         .engine
         .storage()
         .get_all_distributed_edges()
@@ -1857,21 +4256,32 @@ async fn get_distributed_edges_handler(
             )
         })?;
 
-    let response = serde_json::json!({
+    let group_by = params.get("group_by");
+    if let Some(dimension) = group_by {
+        let groups = resolve_service_groups(&state, dimension).await?;
+        edges = group_service_calls(edges, &groups);
+    }
+
+    let mut response = serde_json::json!({
         "total_edges": edges.len(),
         "edges": edges,
     });
+    if let Some(dimension) = group_by {
+        response["group_by"] = dimension.clone().into();
+    }
 
     Ok((StatusCode::OK, Json(ApiResponse::success(response))))
 }
 
 async fn get_global_races_handler(
     State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let environment = requested_environment(&state, &params);
     let races = state
         .engine
         .storage()
-        .get_global_race_candidates()
+        .get_global_race_candidates(environment.as_deref())
         .await
         .map_err(|e| {
             (
@@ -1883,21 +4293,65 @@ async fn get_global_races_handler(
             )
         })?;
 
+    let Some(variable) = params.get("variable") else {
+        let response = serde_json::json!({
+            "total_races": races.len(),
+            "races": races,
+        });
+        return Ok((StatusCode::OK, Json(ApiResponse::success(response))));
+    };
+
+    // Scoped to one variable: also list the concurrent access pairs behind it
+    let races: Vec<serde_json::Value> = races
+        .into_iter()
+        .filter(|race| race["variable"] == variable.as_str())
+        .collect();
+    let concurrent_pairs = state
+        .engine
+        .analysis()
+        .get_cross_trace_races(variable)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!(
+                    "Failed to find races on {}: {}",
+                    variable, e
+                ))),
+            )
+        })?;
+
     let response = serde_json::json!({
         "total_races": races.len(),
         "races": races,
+        "variable": variable,
+        "concurrent_pairs": concurrent_pairs,
     });
 
     Ok((StatusCode::OK, Json(ApiResponse::success(response))))
 }
 
+/// Variables listed in `/api/distributed/hotspots`
+const HOTSPOT_LIMIT: usize = 10;
+
 async fn get_system_hotspots_handler(
     State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
-    let (top_variables, top_service_calls) = state
+    let environment = requested_environment(&state, &params);
+    let top_variables = state
+        .engine
+        .analysis()
+        .variable_hotspots(HOTSPOT_LIMIT, environment.as_deref())
+        .await;
+    let mut top_variables: Vec<serde_json::Value> = top_variables
+        .into_iter()
+        .map(|hotspot| serde_json::to_value(hotspot).unwrap_or_default())
+        .collect();
+    let mut top_service_calls = state
         .engine
         .storage()
-        .get_system_hotspots()
+        .get_top_service_calls(environment.as_deref())
         .await
         .map_err(|e| {
             (
@@ -1909,10 +4363,240 @@ async fn get_system_hotspots_handler(
             )
         })?;
 
-    let response = serde_json::json!({
+    let group_by = params.get("group_by");
+    if let Some(dimension) = group_by {
+        let groups = resolve_service_groups(&state, dimension).await?;
+        for variable in &mut top_variables {
+            let member_groups: BTreeSet<String> = variable["services"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|service| service.as_str())
+                .map(|service| group_of(&groups, service))
+                .collect();
+            variable["services"] = serde_json::json!(member_groups);
+
+            let mut accesses_by_group: BTreeMap<String, u64> = BTreeMap::new();
+            for (service, count) in variable["accesses_by_service"]
+                .as_object()
+                .into_iter()
+                .flatten()
+            {
+                *accesses_by_group
+                    .entry(group_of(&groups, service))
+                    .or_default() += count.as_u64().unwrap_or(0);
+            }
+            variable["accesses_by_service"] = serde_json::json!(accesses_by_group);
+        }
+        top_service_calls = group_service_calls(top_service_calls, &groups);
+    }
+
+    let mut response = serde_json::json!({
         "top_variables": top_variables,
         "top_service_calls": top_service_calls,
     });
+    if let Some(dimension) = group_by {
+        response["group_by"] = dimension.clone().into();
+    }
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+}
+
+/// Potential deadlocks: cycles in the order locks are acquired across all traces
+async fn get_deadlocks_handler(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let violations = state.engine.analysis().detect_lock_order_inversions().await;
+
+    let response = serde_json::json!({
+        "total_violations": violations.len(),
+        "violations": violations,
+    });
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+}
+
+/// Lock contention: acquisitions, contending threads and hold times per lock
+async fn get_lock_stats_handler(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let locks = state.engine.analysis().lock_contention_stats().await;
+
+    let response = serde_json::json!({
+        "total_locks": locks.len(),
+        "locks": locks,
+    });
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(response))))
+}
+
+/// System-wide service map: every service, every cross-service edge, and which
+/// of them carry races found by global analysis
+async fn get_topology_handler(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    #[derive(Serialize)]
+    struct TopologyService {
+        name: String,
+        event_count: usize,
+        trace_count: usize,
+        outbound_calls: usize,
+        inbound_calls: usize,
+        race_count: usize,
+    }
+
+    #[derive(Serialize)]
+    struct TopologyEdge {
+        from_service: String,
+        to_service: String,
+        link_type: String,
+        call_count: usize,
+        race_count: usize,
+        has_race: bool,
+    }
+
+    #[derive(Serialize)]
+    struct TopologyResponse {
+        total_services: usize,
+        total_edges: usize,
+        total_calls: usize,
+        racing_edges: usize,
+        services: Vec<TopologyService>,
+        edges: Vec<TopologyEdge>,
+    }
+
+    let storage = state.engine.storage();
+    let services = storage.get_all_services().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!(
+                "Failed to fetch services: {}",
+                e
+            ))),
+        )
+    })?;
+    let edges = storage.get_all_distributed_edges().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!(
+                "Failed to fetch distributed edges: {}",
+                e
+            ))),
+        )
+    })?;
+    let concurrent = state
+        .engine
+        .analysis()
+        .find_global_concurrent_events()
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Global analysis failed: {}", e))),
+            )
+        })?;
+    let concurrent = scope_race_pairs(&state, &params, &concurrent);
+
+    // Races per service, and per unordered service pair for cross-service races
+    let mut service_races: HashMap<String, usize> = HashMap::new();
+    let mut pair_races: HashMap<(String, String), usize> = HashMap::new();
+    for (event1, event2) in &concurrent {
+        let (service1, service2) = (&event1.metadata.service_name, &event2.metadata.service_name);
+        *service_races.entry(service1.clone()).or_insert(0) += 1;
+        if service1 != service2 {
+            *service_races.entry(service2.clone()).or_insert(0) += 1;
+            let pair = if service1 < service2 {
+                (service1.clone(), service2.clone())
+            } else {
+                (service2.clone(), service1.clone())
+            };
+            *pair_races.entry(pair).or_insert(0) += 1;
+        }
+    }
+
+    let new_node = |name: &str, event_count: usize, trace_count: usize| TopologyService {
+        name: name.to_string(),
+        event_count,
+        trace_count,
+        outbound_calls: 0,
+        inbound_calls: 0,
+        race_count: service_races.get(name).copied().unwrap_or(0),
+    };
+    let mut nodes: BTreeMap<String, TopologyService> = services
+        .into_iter()
+        .map(|(name, event_count, trace_count)| {
+            let service = new_node(&name, event_count, trace_count);
+            (name, service)
+        })
+        .collect();
+
+    let mut topology_edges = Vec::with_capacity(edges.len());
+    for edge in &edges {
+        let from = edge["from_service"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let to = edge["to_service"].as_str().unwrap_or_default().to_string();
+        let call_count = edge["call_count"].as_u64().unwrap_or(0) as usize;
+        nodes
+            .entry(from.clone())
+            .or_insert_with(|| new_node(&from, 0, 0))
+            .outbound_calls += call_count;
+        nodes
+            .entry(to.clone())
+            .or_insert_with(|| new_node(&to, 0, 0))
+            .inbound_calls += call_count;
+
+        let pair = if from < to {
+            (from.clone(), to.clone())
+        } else {
+            (to.clone(), from.clone())
+        };
+        let race_count = pair_races.get(&pair).copied().unwrap_or(0);
+        topology_edges.push(TopologyEdge {
+            from_service: from,
+            to_service: to,
+            link_type: edge["link_type"].as_str().unwrap_or("Custom").to_string(),
+            call_count,
+            race_count,
+            has_race: race_count > 0,
+        });
+    }
+
+    let response = TopologyResponse {
+        total_services: nodes.len(),
+        total_edges: topology_edges.len(),
+        total_calls: topology_edges.iter().map(|edge| edge.call_count).sum(),
+        racing_edges: topology_edges.iter().filter(|edge| edge.has_race).count(),
+        services: nodes.into_values().collect(),
+        edges: topology_edges,
+    };
 
     Ok((StatusCode::OK, Json(ApiResponse::success(response))))
 }
+
+async fn run_maintenance_handler(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<String>>)> {
+    let report = state.engine.storage().maintenance().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!(
+                "Storage maintenance failed: {}",
+                e
+            ))),
+        )
+    })?;
+
+    if state.verbose {
+        println!(
+            "[{}] 🧹 run_maintenance_handler -> removed {} stale rows, {} traces",
+            Local::now().format("%H:%M:%S.%3f"),
+            report.rows_removed,
+            report.trace_count
+        );
+    }
+
+    Ok((StatusCode::OK, Json(ApiResponse::success(report))))
+}
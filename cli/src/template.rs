@@ -0,0 +1,156 @@
+//! `--template` rendering for CLI responses.
+//!
+//! A template is rendered once against the same struct `--json` would print,
+//! so any field visible in the JSON output can be interpolated. The value of
+//! `--template` is resolved as a built-in name for the command, then as a path
+//! to a template file, and otherwise used as the template text itself.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use handlebars::Handlebars;
+use serde::Serialize;
+
+/// Commands that accept `--template`, each with its own built-in templates
+#[derive(Debug, Clone, Copy)]
+pub enum TemplateTarget {
+    Traces,
+    Trace,
+    Races,
+}
+
+impl TemplateTarget {
+    fn builtins(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            TemplateTarget::Traces => &[
+                (
+                    "slack",
+                    "{{#each traces}}:mag: `{{trace_id}}` {{event_count}} events across {{service_count}} services (last seen {{last_timestamp}})\n{{/each}}",
+                ),
+                (
+                    "logfmt",
+                    "{{#each traces}}trace_id={{trace_id}} events={{event_count}} services={{service_count}} last_seen={{last_timestamp}}\n{{/each}}",
+                ),
+            ],
+            TemplateTarget::Trace => &[
+                (
+                    "slack",
+                    ":mag: Trace `{{trace_id}}`: {{len events}} events, {{analysis.potential_races}} potential races, {{len anomalies}} anomalies\n{{#each analysis.race_details}}• [{{severity}}] `{{variable}}` {{event1_location}} ↔ {{event2_location}}\n{{/each}}",
+                ),
+                (
+                    "logfmt",
+                    "trace_id={{trace_id}} events={{len events}} potential_races={{analysis.potential_races}} anomalies={{len anomalies}}\n",
+                ),
+            ],
+            TemplateTarget::Races => &[
+                (
+                    "slack",
+                    "{{#each races}}:rotating_light: [{{severity}}] `{{variable}}` raced in {{trace_count}} traces across {{thread_count}} threads\n{{/each}}",
+                ),
+                (
+                    "logfmt",
+                    "{{#each races}}variable={{variable}} severity={{severity}} traces={{trace_count}} accesses={{access_count}} threads={{thread_count}}\n{{/each}}",
+                ),
+            ],
+        }
+    }
+
+    /// Names of the built-in templates, for `--help` and error messages
+    pub fn builtin_names(self) -> Vec<&'static str> {
+        self.builtins().iter().map(|(name, _)| *name).collect()
+    }
+
+    fn resolve(self, template: &str) -> Result<String> {
+        if let Some((_, source)) = self.builtins().iter().find(|(name, _)| *name == template) {
+            return Ok(source.to_string());
+        }
+        let path = Path::new(template);
+        if path.is_file() {
+            return std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read template {}", path.display()));
+        }
+        Ok(template.to_string())
+    }
+}
+
+/// Render `data` through the template named or given by `template`
+pub fn render<T: Serialize>(target: TemplateTarget, template: &str, data: &T) -> Result<String> {
+    let source = target.resolve(template)?;
+
+    let mut handlebars = Handlebars::new();
+    // Output goes to terminals and log pipelines, not HTML
+    handlebars.register_escape_fn(handlebars::no_escape);
+
+    handlebars.render_template(&source, data).map_err(|e| {
+        anyhow!(
+            "Failed to render template: {} (built-in templates: {})",
+            e,
+            target.builtin_names().join(", ")
+        )
+    })
+}
+
+/// Print rendered output, ending with exactly one trailing newline
+pub fn print_rendered(rendered: &str) {
+    println!("{}", rendered.trim_end_matches('\n'));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raceway::client::{GlobalRace, GlobalRacesData, TraceMetadata, TracesListData};
+
+    fn traces() -> TracesListData {
+        TracesListData {
+            total_traces: 1,
+            page: 1,
+            page_size: 20,
+            total_pages: 1,
+            traces: vec![TraceMetadata {
+                trace_id: "3f2b6c1e-0000-4000-8000-000000000001".into(),
+                event_count: 12,
+                first_timestamp: "2024-01-01T12:00:00Z".into(),
+                last_timestamp: "2024-01-01T12:00:05Z".into(),
+                service_count: 2,
+                services: vec!["api".into(), "ledger".into()],
+                truncated: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn custom_template_interpolates_response_fields() {
+        let rendered = render(
+            TemplateTarget::Traces,
+            "{{#each traces}}{{trace_id}} <{{event_count}}> {{#each services}}{{this}};{{/each}}\n{{/each}}total={{total_traces}}",
+            &traces(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            "3f2b6c1e-0000-4000-8000-000000000001 <12> api;ledger;\ntotal=1"
+        );
+    }
+
+    #[test]
+    fn builtin_template_is_selected_by_name() {
+        let races = GlobalRacesData {
+            total_races: 1,
+            races: vec![GlobalRace {
+                variable: "alice.balance".into(),
+                trace_count: 2,
+                access_count: 4,
+                access_types: vec!["Write".into()],
+                thread_count: 2,
+                severity: "CRITICAL".into(),
+                trace_ids: Vec::new(),
+            }],
+        };
+
+        assert_eq!(
+            render(TemplateTarget::Races, "logfmt", &races).unwrap(),
+            "variable=alice.balance severity=CRITICAL traces=2 accesses=4 threads=2\n"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! `raceway top` - a lightweight, periodically refreshing contention dashboard.
+//!
+//! Unlike the full ratatui TUI this just reprints a plain-text snapshot in place,
+//! so it works over SSH, in CI logs and when piped to other tools.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use raceway::client::{GlobalRacesData, HotspotsData, RacewayApiClient, StatusData};
+
+const TOP_ROWS: usize = 5;
+
+struct TopSnapshot {
+    status: StatusData,
+    hotspots: HotspotsData,
+    races: GlobalRacesData,
+    ingest_rate: Option<f64>,
+}
+
+/// Tracks the previous refresh so the ingest rate can be derived between polls.
+#[derive(Default)]
+pub struct TopState {
+    last_sample: Option<(Instant, usize)>,
+}
+
+pub async fn run_top(api: &RacewayApiClient, interval: u64, count: Option<usize>) -> Result<()> {
+    let mut state = TopState::default();
+    let mut refreshes = 0usize;
+
+    loop {
+        let frame = refresh_once(api, &mut state).await?;
+        // Clear the screen and move the cursor home so the view refreshes in place
+        print!("\x1B[2J\x1B[H{}", frame);
+        refreshes += 1;
+
+        if count.is_some_and(|limit| refreshes >= limit) {
+            return Ok(());
+        }
+
+        tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+    }
+}
+
+/// Fetch one snapshot from the server and render it to a string.
+pub async fn refresh_once(api: &RacewayApiClient, state: &mut TopState) -> Result<String> {
+    let status = api.status().await?;
+    let hotspots = api.hotspots().await?;
+    let races = api.global_races().await?;
+
+    let now = Instant::now();
+    let ingest_rate = state.last_sample.and_then(|(at, events)| {
+        let elapsed = now.duration_since(at).as_secs_f64();
+        (elapsed > 0.0).then(|| status.events_captured.saturating_sub(events) as f64 / elapsed)
+    });
+    state.last_sample = Some((now, status.events_captured));
+
+    Ok(render(&TopSnapshot {
+        status,
+        hotspots,
+        races,
+        ingest_rate,
+    }))
+}
+
+fn render(snapshot: &TopSnapshot) -> String {
+    let mut out = String::new();
+
+    let rate = snapshot
+        .ingest_rate
+        .map(|r| format!("{:.1} events/s", r))
+        .unwrap_or_else(|| "-".to_string());
+    let _ = writeln!(
+        out,
+        "🏁 raceway top - {} | events: {} | traces: {} | ingest: {}",
+        chrono::Local::now().format("%H:%M:%S"),
+        snapshot.status.events_captured,
+        snapshot.status.traces_active,
+        rate
+    );
+
+    let _ = writeln!(out, "\n🔥 Top hotspot variables:");
+    if snapshot.hotspots.top_variables.is_empty() {
+        let _ = writeln!(out, "  none");
+    }
+    for var in snapshot.hotspots.top_variables.iter().take(TOP_ROWS) {
+        let _ = writeln!(
+            out,
+            "  {:<24} {:>6} accesses {:>4} traces",
+            var.variable, var.access_count, var.trace_count
+        );
+    }
+
+    // Busiest services: total calls in or out, from the service-call hotspots
+    let mut service_calls: HashMap<&str, usize> = HashMap::new();
+    for call in &snapshot.hotspots.top_service_calls {
+        *service_calls.entry(call.from_service.as_str()).or_insert(0) += call.call_count;
+        *service_calls.entry(call.to_service.as_str()).or_insert(0) += call.call_count;
+    }
+    let mut busiest: Vec<_> = service_calls.into_iter().collect();
+    busiest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let _ = writeln!(out, "\n📞 Busiest services:");
+    if busiest.is_empty() {
+        let _ = writeln!(out, "  none");
+    }
+    for (service, calls) in busiest.iter().take(TOP_ROWS) {
+        let _ = writeln!(out, "  {:<24} {:>6} calls", service, calls);
+    }
+
+    let _ = writeln!(
+        out,
+        "\n🚨 Recent races ({} total):",
+        snapshot.races.total_races
+    );
+    if snapshot.races.races.is_empty() {
+        let _ = writeln!(out, "  none");
+    }
+    for race in snapshot.races.races.iter().take(TOP_ROWS) {
+        let _ = writeln!(
+            out,
+            "  {:<24} {:<10} {:>4} traces {:>4} threads",
+            race.variable, race.severity, race.trace_count, race.thread_count
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use serde_json::json;
+
+    async fn spawn_mock_server() -> String {
+        let app = Router::new()
+            .route(
+                "/status",
+                get(|| async {
+                    Json(json!({
+                        "success": true,
+                        "data": {
+                            "version": "0.1.0",
+                            "uptime_seconds": 0,
+                            "events_captured": 42,
+                            "traces_active": 3
+                        },
+                        "error": null
+                    }))
+                }),
+            )
+            .route(
+                "/api/distributed/hotspots",
+                get(|| async {
+                    Json(json!({
+                        "success": true,
+                        "data": {
+                            "top_variables": [{
+                                "variable": "alice.balance",
+                                "access_count": 12,
+                                "trace_count": 3,
+                                "services": ["api"]
+                            }],
+                            "top_service_calls": [{
+                                "from_service": "api",
+                                "to_service": "ledger",
+                                "call_count": 7
+                            }]
+                        },
+                        "error": null
+                    }))
+                }),
+            )
+            .route(
+                "/api/distributed/global-races",
+                get(|| async {
+                    Json(json!({
+                        "success": true,
+                        "data": {
+                            "total_races": 1,
+                            "races": [{
+                                "variable": "alice.balance",
+                                "trace_count": 2,
+                                "access_count": 4,
+                                "access_types": ["Write"],
+                                "thread_count": 2,
+                                "severity": "CRITICAL",
+                                "trace_ids": []
+                            }]
+                        },
+                        "error": null
+                    }))
+                }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn refresh_once_prints_all_sections() {
+        let server = spawn_mock_server().await;
+        let api = RacewayApiClient::new(server);
+        let mut state = TopState::default();
+
+        let frame = refresh_once(&api, &mut state).await.unwrap();
+
+        assert!(frame.contains("events: 42"));
+        assert!(frame.contains("ingest: -"));
+        assert!(frame.contains("🔥 Top hotspot variables:"));
+        assert!(frame.contains("alice.balance"));
+        assert!(frame.contains("📞 Busiest services:"));
+        assert!(frame.contains("ledger"));
+        assert!(frame.contains("🚨 Recent races (1 total):"));
+        assert!(frame.contains("CRITICAL"));
+    }
+}
@@ -41,12 +41,16 @@ pub fn render_audit_trail_view(
         let mut lines = Vec::new();
 
         let race_count = trail.accesses.iter().filter(|a| a.is_race).count();
+        let lost_update_count = trail.accesses.iter().filter(|a| a.is_lost_update).count();
 
         lines.push(format!("Variable: {}", trail.variable));
         lines.push(format!("Total Accesses: {}", trail.accesses.len()));
         if race_count > 0 {
             lines.push(format!("⚠️  Race Conditions: {}", race_count));
         }
+        if lost_update_count > 0 {
+            lines.push(format!("💥 Lost Updates: {}", lost_update_count));
+        }
         lines.push(String::new());
         lines.push("Timeline:".to_string());
         lines.push(String::new());
@@ -71,7 +75,13 @@ pub fn render_audit_trail_view(
             };
 
             // Race indicator
-            let race_marker = if access.is_race { " ⚠️ RACE" } else { "" };
+            let race_marker = if access.is_lost_update {
+                " 💥 LOST UPDATE"
+            } else if access.is_race {
+                " ⚠️ RACE"
+            } else {
+                ""
+            };
 
             lines.push(format!(
                 "{} {} [{}] @ {}{}",
@@ -94,6 +104,9 @@ pub fn render_audit_trail_view(
             } else {
                 lines.push(format!("   Value: {}", access.new_value));
             }
+            if access.is_lost_update {
+                lines.push("   💥 Started from the same value as a concurrent write".to_string());
+            }
 
             // Causal link info
             if idx > 0 {
@@ -217,7 +217,7 @@ fn render_hotspots(f: &mut Frame, area: Rect, global_analysis: Option<&GlobalAna
 
             // Sort by count and take top entries
             let mut sorted_vars: Vec<_> = variable_counts.into_iter().collect();
-            sorted_vars.sort_by(|a, b| b.1.cmp(&a.1));
+            sorted_vars.sort_by_key(|v| std::cmp::Reverse(v.1));
 
             sorted_vars
                 .into_iter()
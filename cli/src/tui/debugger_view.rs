@@ -8,6 +8,7 @@ use ratatui::{
 use std::collections::HashSet;
 
 /// Render the time-travel debugger view with playback controls
+#[allow(clippy::too_many_arguments)]
 pub fn render_debugger_view(
     f: &mut Frame,
     area: Rect,
@@ -311,7 +312,7 @@ fn render_state_snapshot(
             // Show HTTPResponse details
             else if kind_name == "HTTPResponse" {
                 if let Some(status) = kind_data.get("status_code").and_then(|s| s.as_i64()) {
-                    let status_color = if status >= 200 && status < 300 {
+                    let status_color = if (200..300).contains(&status) {
                         Color::Green
                     } else if status >= 400 {
                         Color::Red
@@ -46,7 +46,7 @@ pub fn render_dependencies_view(
 
         // Sort services by event count (desc)
         let mut sorted_services = deps.services.clone();
-        sorted_services.sort_by(|a, b| b.event_count.cmp(&a.event_count));
+        sorted_services.sort_by_key(|s| std::cmp::Reverse(s.event_count));
 
         lines.push("Services:".to_string());
         for service in &sorted_services {
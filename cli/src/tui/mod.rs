@@ -21,6 +21,7 @@ use ratatui::{
     backend::{Backend, CrosstermBackend},
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame, Terminal,
 };
@@ -73,6 +74,7 @@ struct App {
     global_analysis_data: Option<GlobalAnalysisData>,
     selected_variable: Option<String>,
     audit_trails: HashMap<String, Vec<VariableAccess>>, // All audit trails from full response
+    loaded_trace_json: Option<String>, // Raw full analysis response of the loaded trace
 
     // Debugger state
     debugger_playing: bool,
@@ -82,9 +84,12 @@ struct App {
     // Event filtering
     event_filter_mode: Option<EventFilterMode>,
     event_filter_value: String,
+    search_input: Option<String>, // Query being typed while the `/` prompt is open
 
     // Request tracking to prevent race conditions
     pending_trace_fetch: Option<usize>, // Track which trace we're currently fetching
+    pending_global_job: Option<String>, // Global analysis job we're polling
+    global_job_last_poll: Instant,
 }
 
 impl App {
@@ -160,6 +165,7 @@ impl App {
             // View mode - default to Events view
             view_mode: ViewMode::Events,
             audit_trails: HashMap::new(),
+            loaded_trace_json: None,
             critical_path_data: None,
             anomalies_data: None,
             dependencies_data: None,
@@ -176,9 +182,12 @@ impl App {
             // Event filtering
             event_filter_mode: None,
             event_filter_value: String::new(),
+            search_input: None,
 
             // Request tracking
             pending_trace_fetch: None,
+            pending_global_job: None,
+            global_job_last_poll: Instant::now(),
         }
     }
 
@@ -188,13 +197,13 @@ impl App {
                 self.traces_scroll = self.traces_scroll.saturating_sub(1);
             }
             Panel::Events => {
-                if self.selected_event > 0 {
-                    self.selected_event -= 1;
-                    self.details_scroll = 0;
-                    if self.selected_event < self.event_data.len() {
-                        let event = &self.event_data[self.selected_event];
-                        self.event_detail = format!("{:#}", event);
-                    }
+                let prev = self
+                    .visible_events()
+                    .into_iter()
+                    .rev()
+                    .find(|&i| i < self.selected_event);
+                if let Some(prev) = prev {
+                    self.select_event(prev);
                 }
             }
             Panel::Details => {
@@ -212,13 +221,12 @@ impl App {
                 self.traces_scroll = self.traces_scroll.saturating_add(1);
             }
             Panel::Events => {
-                if self.selected_event < self.events.len().saturating_sub(1) {
-                    self.selected_event += 1;
-                    self.details_scroll = 0;
-                    if self.selected_event < self.event_data.len() {
-                        let event = &self.event_data[self.selected_event];
-                        self.event_detail = format!("{:#}", event);
-                    }
+                let next = self
+                    .visible_events()
+                    .into_iter()
+                    .find(|&i| i > self.selected_event);
+                if let Some(next) = next {
+                    self.select_event(next);
                 }
             }
             Panel::Details => {
@@ -338,12 +346,24 @@ impl App {
                                     } else {
                                         String::new()
                                     };
+                                    let mut tags: Vec<String> = meta
+                                        .trace_tags
+                                        .iter()
+                                        .map(|(key, value)| format!("{}={}", key, value))
+                                        .collect();
+                                    tags.sort();
+                                    let tag_badge = if tags.is_empty() {
+                                        String::new()
+                                    } else {
+                                        format!(" [🏷 {}]", tags.join(", "))
+                                    };
                                     format!(
-                                        "🔍 Trace {}: {}... ({} events){}",
+                                        "🔍 Trace {}: {}... ({} events){}{}",
                                         i + 1,
                                         &meta.trace_id[..8],
                                         meta.event_count,
-                                        service_badge
+                                        service_badge,
+                                        tag_badge
                                     )
                                 })
                                 .collect();
@@ -439,6 +459,7 @@ impl App {
             self.critical_path_data = cached.critical_path_data.clone();
             self.dependencies_data = cached.dependencies_data.clone();
             self.distributed_analysis_data = cached.distributed_analysis_data.clone();
+            self.loaded_trace_json = Some(cached.raw_response.clone());
 
             // Update event detail for current selection
             if self.selected_event < self.event_data.len() {
@@ -453,6 +474,7 @@ impl App {
         // Mark that we're fetching this trace
         let fetching_trace_index = self.loaded_trace;
         self.pending_trace_fetch = Some(fetching_trace_index);
+        self.loaded_trace_json = None;
 
         let trace_id = &self.trace_ids[self.loaded_trace];
 
@@ -475,7 +497,8 @@ impl App {
                 return;
             }
 
-            if let Ok(full_resp) = response.json::<FullTraceAnalysisResponse>() {
+            let body = response.text().unwrap_or_default();
+            if let Ok(full_resp) = serde_json::from_str::<FullTraceAnalysisResponse>(&body) {
                 if let Some(full_data) = full_resp.data {
                     // 1. Store event data
                     self.event_data = full_data.events.clone();
@@ -626,8 +649,10 @@ impl App {
                             critical_path_data: self.critical_path_data.clone(),
                             dependencies_data: self.dependencies_data.clone(),
                             distributed_analysis_data: self.distributed_analysis_data.clone(),
+                            raw_response: body.clone(),
                         },
                     );
+                    self.loaded_trace_json = Some(body);
                 } else {
                     // JSON parsing succeeded but data is None
                     self.anomalies =
@@ -649,139 +674,188 @@ impl App {
         self.pending_trace_fetch = None;
     }
 
+    /// Kick off a background global analysis job on the server. Results are
+    /// picked up by `poll_global_analysis` so the UI never blocks on it.
     fn fetch_global_analysis(&mut self) {
         // Mark that we've run global analysis for this trace count
         self.last_global_analysis_trace_count = self.trace_ids.len();
 
         let global_url = format!("{}/api/analyze/global", self.server_url);
-        if let Ok(response) = self.client.get(&global_url).send() {
-            if let Ok(global_resp) = response.json::<GlobalAnalysisResponse>() {
-                if let Some(global) = global_resp.data {
-                    // Store global analysis data for dashboard
-                    self.global_analysis_data = Some(global.clone());
-
-                    if global.potential_races > 0 {
-                        let current_trace_id = &self.trace_ids[self.selected_trace];
-                        let current_trace_short = &current_trace_id[..8];
-                        let mut current_trace_involved = false;
-                        let mut events_in_this_trace = HashSet::new();
-
-                        // Parse trace IDs and event IDs from anomaly messages
-                        // Track the most recently seen event ID to associate it with a trace
-                        let mut current_event_id: Option<String> = None;
-
-                        for anomaly in &global.anomalies {
-                            // Extract trace IDs from messages like "Trace bbbbbbbb" or "Trace aaaaaaaa"
-                            if anomaly.contains("Trace ") {
-                                for (i, trace_id) in self.trace_ids.iter().enumerate() {
-                                    let trace_short = &trace_id[..8];
-                                    if anomaly.contains(trace_short) {
-                                        self.traces_with_races.insert(i);
-                                        if trace_short == current_trace_short {
-                                            current_trace_involved = true;
-
-                                            // If we just saw an event ID, associate it with this trace
-                                            if let Some(ref event_id) = current_event_id {
-                                                events_in_this_trace.insert(event_id.clone());
-                                            }
-                                        }
-                                    }
-                                }
-                                // Clear the current event after processing trace line
-                                current_event_id = None;
-                            }
+        if let Ok(response) = self.client.post(&global_url).send() {
+            if let Ok(started) = response.json::<GlobalAnalysisJobStartResponse>() {
+                if let Some(job) = started.data {
+                    self.pending_global_job = Some(job.job_id);
+                    self.global_job_last_poll = Instant::now();
+                    self.status_message = "⏳ Running global analysis...".to_string();
+                }
+            }
+        }
+    }
 
-                            // Extract event IDs from messages like "Event 1: 11111111" or "Event 2: 22222222"
-                            if anomaly.contains("Event ") && anomaly.contains(": ") {
-                                if let Some(colon_pos) = anomaly.find(": ") {
-                                    let after_colon = &anomaly[colon_pos + 2..];
-                                    if let Some(space_pos) = after_colon.find(' ') {
-                                        let event_id = &after_colon[..space_pos];
-                                        if event_id.len() == 8 {
-                                            self.events_in_races.insert(event_id.to_string());
-                                            current_event_id = Some(event_id.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
+    fn poll_global_analysis(&mut self) {
+        let Some(job_id) = self.pending_global_job.clone() else {
+            return;
+        };
+        if self.global_job_last_poll.elapsed() < std::time::Duration::from_millis(500) {
+            return;
+        }
+        self.global_job_last_poll = Instant::now();
 
-                        // Update anomalies with clearer messaging
-                        if current_trace_involved {
-                            // Remove any existing cross-trace messaging to regenerate it fresh
-                            if let Some(separator_pos) = self
-                                .anomalies
-                                .iter()
-                                .position(|a| a.contains("─────────────────────────────────"))
-                            {
-                                self.anomalies.truncate(separator_pos.saturating_sub(1));
-                            }
+        let job_url = format!("{}/api/analyze/global/{}", self.server_url, job_id);
+        let Ok(response) = self.client.get(&job_url).send() else {
+            return;
+        };
+        let Some(job) = response
+            .json::<GlobalAnalysisJobResponse>()
+            .ok()
+            .and_then(|resp| resp.data)
+        else {
+            // Unknown job (e.g. server restarted) - stop polling
+            self.pending_global_job = None;
+            return;
+        };
 
-                            if !self.current_trace_has_races {
-                                // No per-trace races, but involved in cross-trace races
-                                self.current_trace_has_races = true;
-                                self.traces_with_races.insert(self.selected_trace);
+        match job.status.as_str() {
+            "Running" => {
+                self.status_message =
+                    format!("⏳ Running global analysis... {:.0}%", job.progress_percent);
+            }
+            "Completed" => {
+                self.pending_global_job = None;
+                self.status_message = "✅ Global analysis complete".to_string();
+                if let Some(global) = job.result {
+                    self.apply_global_analysis(global);
+                }
+            }
+            _ => {
+                self.pending_global_job = None;
+                self.status_message = format!(
+                    "❌ Global analysis failed: {}",
+                    job.error.unwrap_or_else(|| "unknown error".to_string())
+                );
+            }
+        }
+    }
 
-                                // Replace the "no races" message with a better one
-                                if self
-                                    .anomalies
-                                    .iter()
-                                    .any(|a| a.contains("✅ No race conditions"))
-                                {
-                                    self.anomalies = vec![
-                                        format!("⚠️  This trace is involved in CROSS-TRACE races"),
-                                        "".to_string(),
-                                        format!("✅ No races within this trace alone"),
-                                        format!(
-                                            "🌐 But {} event(s) race with other traces",
-                                            events_in_this_trace.len()
-                                        ),
-                                        "".to_string(),
-                                    ];
-                                }
-                            } else {
-                                // Has per-trace races, update the event count line
-                                if self.anomalies.iter().any(|a| a.contains("🌐 But")) {
-                                    if let Some(pos) =
-                                        self.anomalies.iter().position(|a| a.contains("🌐 But"))
-                                    {
-                                        self.anomalies[pos] = format!(
-                                            "🌐 But {} event(s) race with other traces",
-                                            events_in_this_trace.len()
-                                        );
-                                    }
+    fn apply_global_analysis(&mut self, global: GlobalAnalysisData) {
+        // Store global analysis data for dashboard
+        self.global_analysis_data = Some(global.clone());
+
+        if global.potential_races > 0 {
+            let current_trace_id = &self.trace_ids[self.selected_trace];
+            let current_trace_short = &current_trace_id[..8];
+            let mut current_trace_involved = false;
+            let mut events_in_this_trace = HashSet::new();
+
+            // Parse trace IDs and event IDs from anomaly messages
+            // Track the most recently seen event ID to associate it with a trace
+            let mut current_event_id: Option<String> = None;
+
+            for anomaly in &global.anomalies {
+                // Extract trace IDs from messages like "Trace bbbbbbbb" or "Trace aaaaaaaa"
+                if anomaly.contains("Trace ") {
+                    for (i, trace_id) in self.trace_ids.iter().enumerate() {
+                        let trace_short = &trace_id[..8];
+                        if anomaly.contains(trace_short) {
+                            self.traces_with_races.insert(i);
+                            if trace_short == current_trace_short {
+                                current_trace_involved = true;
+
+                                // If we just saw an event ID, associate it with this trace
+                                if let Some(ref event_id) = current_event_id {
+                                    events_in_this_trace.insert(event_id.clone());
                                 }
                             }
+                        }
+                    }
+                    // Clear the current event after processing trace line
+                    current_event_id = None;
+                }
 
-                            // Add separator
-                            self.anomalies.push("".to_string());
-                            self.anomalies
-                                .push("─────────────────────────────────".to_string());
-                            self.anomalies
-                                .push("🌐 CROSS-TRACE RACE DETAILS".to_string());
-                            self.anomalies
-                                .push("─────────────────────────────────".to_string());
-                            self.anomalies.push("".to_string());
-
-                            // Add global race info
-                            for anomaly in &global.anomalies {
-                                // Only show races involving current trace
-                                if anomaly.contains(current_trace_short)
-                                    || !anomaly.contains("Event ")
-                                {
-                                    self.anomalies.push(anomaly.clone());
-                                }
+                // Extract event IDs from messages like "Event 1: 11111111" or "Event 2: 22222222"
+                if anomaly.contains("Event ") && anomaly.contains(": ") {
+                    if let Some(colon_pos) = anomaly.find(": ") {
+                        let after_colon = &anomaly[colon_pos + 2..];
+                        if let Some(space_pos) = after_colon.find(' ') {
+                            let event_id = &after_colon[..space_pos];
+                            if event_id.len() == 8 {
+                                self.events_in_races.insert(event_id.to_string());
+                                current_event_id = Some(event_id.to_string());
                             }
+                        }
+                    }
+                }
+            }
+
+            // Update anomalies with clearer messaging
+            if current_trace_involved {
+                // Remove any existing cross-trace messaging to regenerate it fresh
+                if let Some(separator_pos) = self
+                    .anomalies
+                    .iter()
+                    .position(|a| a.contains("─────────────────────────────────"))
+                {
+                    self.anomalies.truncate(separator_pos.saturating_sub(1));
+                }
 
-                            self.anomalies.push("".to_string());
-                            self.anomalies
-                                .push("💡 Events marked in red are involved in races".to_string());
-                            self.anomalies.push(
-                                "   Switch traces to see other race participants".to_string(),
+                if !self.current_trace_has_races {
+                    // No per-trace races, but involved in cross-trace races
+                    self.current_trace_has_races = true;
+                    self.traces_with_races.insert(self.selected_trace);
+
+                    // Replace the "no races" message with a better one
+                    if self
+                        .anomalies
+                        .iter()
+                        .any(|a| a.contains("✅ No race conditions"))
+                    {
+                        self.anomalies = vec![
+                            format!("⚠️  This trace is involved in CROSS-TRACE races"),
+                            "".to_string(),
+                            format!("✅ No races within this trace alone"),
+                            format!(
+                                "🌐 But {} event(s) race with other traces",
+                                events_in_this_trace.len()
+                            ),
+                            "".to_string(),
+                        ];
+                    }
+                } else {
+                    // Has per-trace races, update the event count line
+                    if self.anomalies.iter().any(|a| a.contains("🌐 But")) {
+                        if let Some(pos) = self.anomalies.iter().position(|a| a.contains("🌐 But"))
+                        {
+                            self.anomalies[pos] = format!(
+                                "🌐 But {} event(s) race with other traces",
+                                events_in_this_trace.len()
                             );
                         }
                     }
                 }
+
+                // Add separator
+                self.anomalies.push("".to_string());
+                self.anomalies
+                    .push("─────────────────────────────────".to_string());
+                self.anomalies
+                    .push("🌐 CROSS-TRACE RACE DETAILS".to_string());
+                self.anomalies
+                    .push("─────────────────────────────────".to_string());
+                self.anomalies.push("".to_string());
+
+                // Add global race info
+                for anomaly in &global.anomalies {
+                    // Only show races involving current trace
+                    if anomaly.contains(current_trace_short) || !anomaly.contains("Event ") {
+                        self.anomalies.push(anomaly.clone());
+                    }
+                }
+
+                self.anomalies.push("".to_string());
+                self.anomalies
+                    .push("💡 Events marked in red are involved in races".to_string());
+                self.anomalies
+                    .push("   Switch traces to see other race participants".to_string());
             }
         }
     }
@@ -851,11 +925,140 @@ impl App {
         if matches!(self.event_filter_mode, Some(EventFilterMode::ByType))
             && self.event_filter_value == event_type
         {
-            self.event_filter_mode = None;
-            self.event_filter_value.clear();
+            self.clear_event_filter();
         } else {
             self.event_filter_mode = Some(EventFilterMode::ByType);
             self.event_filter_value = event_type.to_string();
+            self.keep_selected_event_visible();
+        }
+    }
+
+    fn clear_event_filter(&mut self) {
+        self.event_filter_mode = None;
+        self.event_filter_value.clear();
+    }
+
+    // Write the loaded trace's full analysis, as `raceway export` would, to
+    // raceway-<shortid>-<timestamp>.json in the working directory
+    fn export_loaded_trace(&mut self) {
+        let (Some(trace_id), Some(json)) = (
+            self.trace_ids.get(self.loaded_trace),
+            self.loaded_trace_json.as_ref(),
+        ) else {
+            self.status_message = "⚠️  No trace loaded to export yet".to_string();
+            return;
+        };
+
+        let short_id = trace_id.get(..8).unwrap_or(trace_id);
+        let path = format!(
+            "raceway-{}-{}.json",
+            short_id,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        self.status_message = match std::fs::write(&path, json) {
+            Ok(()) => format!("💾 Exported trace to {}", path),
+            Err(e) => format!("❌ Failed to export trace to {}: {}", path, e),
+        };
+    }
+
+    // Open the `/` search prompt, starting from the current search if any
+    fn start_search(&mut self) {
+        let query = if matches!(self.event_filter_mode, Some(EventFilterMode::ByKeyword)) {
+            self.event_filter_value.clone()
+        } else {
+            String::new()
+        };
+        self.search_input = Some(query);
+    }
+
+    // Keys typed while the search prompt is open; the filter follows the query live
+    fn handle_search_key(&mut self, code: KeyCode) {
+        let Some(query) = self.search_input.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => {
+                self.search_input = None;
+                self.clear_event_filter();
+                return;
+            }
+            KeyCode::Enter => {
+                self.search_input = None;
+                return;
+            }
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => query.push(c),
+            _ => return,
+        }
+
+        if query.is_empty() {
+            self.clear_event_filter();
+        } else {
+            self.event_filter_mode = Some(EventFilterMode::ByKeyword);
+            self.event_filter_value = query.clone();
+            self.keep_selected_event_visible();
+        }
+    }
+
+    fn event_matches_filter(&self, index: usize) -> bool {
+        let (Some(filter_mode), Some(event)) = (self.event_filter_mode, self.event_data.get(index))
+        else {
+            return true; // No filter active, or a loading/placeholder line
+        };
+        match filter_mode {
+            EventFilterMode::ByType => event
+                .get("kind")
+                .and_then(|k| k.as_object())
+                .and_then(|obj| obj.keys().next())
+                .map(|kind| kind == &self.event_filter_value)
+                .unwrap_or(false),
+            EventFilterMode::ByService => event
+                .get("metadata")
+                .and_then(|m| m.get("service_name"))
+                .and_then(|s| s.as_str())
+                .map(|service| service == self.event_filter_value)
+                .unwrap_or(false),
+            EventFilterMode::ByKeyword => {
+                let query = self.event_filter_value.to_ascii_lowercase();
+                event_search_fields(event)
+                    .iter()
+                    .any(|field| field.to_ascii_lowercase().contains(&query))
+            }
+        }
+    }
+
+    // Indices of the events listed in the Events view
+    fn visible_events(&self) -> Vec<usize> {
+        if !matches!(self.view_mode, ViewMode::Events) {
+            return (0..self.events.len()).collect();
+        }
+        (0..self.events.len())
+            .filter(|&i| self.event_matches_filter(i))
+            .collect()
+    }
+
+    fn select_event(&mut self, index: usize) {
+        self.selected_event = index;
+        self.details_scroll = 0;
+        if let Some(event) = self.event_data.get(index) {
+            self.event_detail = format!("{:#}", event);
+        }
+    }
+
+    // After the filter narrows, move the selection to the nearest listed event
+    fn keep_selected_event_visible(&mut self) {
+        let visible = self.visible_events();
+        if visible.contains(&self.selected_event) {
+            return;
+        }
+        let nearest = visible
+            .iter()
+            .find(|&&i| i > self.selected_event)
+            .or(visible.last());
+        if let Some(&index) = nearest {
+            self.select_event(index);
         }
     }
 
@@ -938,7 +1141,7 @@ pub async fn launch_tui(server: &str) -> Result<()> {
     launch_tui_blocking(server)
 }
 
-fn launch_tui_blocking(server: &str) -> Result<()> {
+pub(crate) fn launch_tui_blocking(server: &str) -> Result<()> {
     // Print before entering TUI mode
     println!("Starting Raceway TUI...");
     println!("Connecting to: {}", server);
@@ -992,6 +1195,9 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
         // Try to load pending trace after debounce period
         app.try_load_pending_trace();
 
+        // Pick up background global analysis results
+        app.poll_global_analysis();
+
         // Handle debugger playback
         if app.debugger_playing && matches!(app.view_mode, ViewMode::Debugger) {
             let interval_ms = (1000.0 / app.debugger_speed) as u64;
@@ -1024,6 +1230,8 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
                             }
                             _ => {}
                         }
+                    } else if app.search_input.is_some() {
+                        app.handle_search_key(key.code);
                     } else {
                         match key.code {
                             KeyCode::Char('q') => return Ok(()),
@@ -1108,9 +1316,13 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
                             KeyCode::Char('4') if matches!(app.view_mode, ViewMode::Events) => {
                                 app.toggle_event_type_filter("HTTPResponse");
                             }
-                            KeyCode::Char('c') if matches!(app.view_mode, ViewMode::Events) => {
-                                app.event_filter_mode = None;
-                                app.event_filter_value.clear();
+                            KeyCode::Char('c') | KeyCode::Esc
+                                if matches!(app.view_mode, ViewMode::Events) =>
+                            {
+                                app.clear_event_filter();
+                            }
+                            KeyCode::Char('/') if matches!(app.view_mode, ViewMode::Events) => {
+                                app.start_search();
                             }
 
                             // Global actions
@@ -1127,6 +1339,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
                             KeyCode::Tab | KeyCode::Char('v') => {
                                 app.cycle_view_mode();
                             }
+                            KeyCode::Char('e') => app.export_loaded_trace(),
                             _ => {}
                         }
                     }
@@ -1146,6 +1359,56 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
     }
 }
 
+/// Text the `/` search matches against: the event kind, service and location
+fn event_search_fields(event: &serde_json::Value) -> Vec<String> {
+    let mut fields = Vec::new();
+    if let Some((kind, body)) = event
+        .get("kind")
+        .and_then(|k| k.as_object())
+        .and_then(|obj| obj.iter().next())
+    {
+        fields.push(kind.clone());
+        if let Some(location) = body.get("location").and_then(|l| l.as_str()) {
+            fields.push(location.to_string());
+        }
+        if let Some(file) = body.get("file").and_then(|f| f.as_str()) {
+            let line = body.get("line").and_then(|l| l.as_u64()).unwrap_or(0);
+            fields.push(format!("{}:{}", file, line));
+        }
+    }
+    if let Some(service) = event
+        .get("metadata")
+        .and_then(|m| m.get("service_name"))
+        .and_then(|s| s.as_str())
+    {
+        fields.push(service.to_string());
+    }
+    fields
+}
+
+/// `text` with every (ASCII case-insensitive) occurrence of `query` highlighted
+fn highlight_matches(text: &str, query: &str) -> Line<'static> {
+    let haystack = text.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+    let highlight = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let mut spans = Vec::new();
+    let mut rest = 0;
+    for (start, _) in haystack.match_indices(&needle) {
+        spans.push(Span::raw(text[rest..start].to_string()));
+        spans.push(Span::styled(
+            text[start..start + needle.len()].to_string(),
+            highlight,
+        ));
+        rest = start + needle.len();
+    }
+    spans.push(Span::raw(text[rest..].to_string()));
+    Line::from(spans)
+}
+
 fn render_help_modal(f: &mut Frame) {
     let area = f.size();
 
@@ -1174,6 +1437,8 @@ fn render_help_modal(f: &mut Frame) {
         "┌─ PANEL-SPECIFIC KEYS (when focused) ────────────────┐",
         "│  w / s          Traces panel - select trace         │",
         "│  j / k / ↑↓     Events panel - select event         │",
+        "│  /              Search events (kind/service/loc)    │",
+        "│  Esc            Clear event filter or search        │",
         "│  u / d          Details panel - scroll content      │",
         "│  p / n          Anomalies panel - scroll list       │",
         "└──────────────────────────────────────────────────────┘",
@@ -1188,6 +1453,7 @@ fn render_help_modal(f: &mut Frame) {
         "│  a              Toggle auto-refresh on/off          │",
         "│                 (Auto-refresh: every 20 seconds)    │",
         "│  Tab / v        Cycle view mode (Events/Path/Anom)  │",
+        "│  e              Export trace to raceway-<id>-*.json │",
         "└──────────────────────────────────────────────────────┘",
         "",
         "┌─ GENERAL ────────────────────────────────────────────┐",
@@ -1331,41 +1597,7 @@ fn ui(f: &mut Frame, app: &App) {
                 .events
                 .iter()
                 .enumerate()
-                .filter(|(i, _event)| {
-                    // Apply filter if active
-                    if let Some(filter_mode) = app.event_filter_mode {
-                        if *i < app.event_data.len() {
-                            match filter_mode {
-                                EventFilterMode::ByType => {
-                                    // Check if event kind matches filter value
-                                    app.event_data[*i]
-                                        .get("kind")
-                                        .and_then(|k| k.as_object())
-                                        .and_then(|obj| obj.keys().next())
-                                        .map(|kind| kind == &app.event_filter_value)
-                                        .unwrap_or(false)
-                                }
-                                EventFilterMode::ByService => {
-                                    // Check if service name matches filter value
-                                    app.event_data[*i]
-                                        .get("metadata")
-                                        .and_then(|m| m.get("service_name"))
-                                        .and_then(|s| s.as_str())
-                                        .map(|service| service == app.event_filter_value)
-                                        .unwrap_or(false)
-                                }
-                                EventFilterMode::ByKeyword => {
-                                    // Search in event display text (simple contains)
-                                    false // Not implemented yet
-                                }
-                            }
-                        } else {
-                            true // Show loading/placeholder events
-                        }
-                    } else {
-                        true // No filter active, show all events
-                    }
-                })
+                .filter(|(i, _event)| app.event_matches_filter(*i))
                 .map(|(i, event)| {
                     // Check if this event is involved in a race
                     let event_in_race = if i < app.event_data.len() {
@@ -1416,11 +1648,18 @@ fn ui(f: &mut Frame, app: &App) {
 
                     // Prepend service badge to event display
                     let display_text = format!("{}{}", service_badge, event);
-                    ListItem::new(display_text).style(style)
+                    if matches!(app.event_filter_mode, Some(EventFilterMode::ByKeyword)) {
+                        ListItem::new(highlight_matches(&display_text, &app.event_filter_value))
+                            .style(style)
+                    } else {
+                        ListItem::new(display_text).style(style)
+                    }
                 })
                 .collect();
 
-            let filter_indicator = if let Some(filter_mode) = app.event_filter_mode {
+            let filter_indicator = if let Some(query) = &app.search_input {
+                format!(" 🔍 /{}▏", query)
+            } else if let Some(filter_mode) = app.event_filter_mode {
                 match filter_mode {
                     EventFilterMode::ByType => format!(" 🔍 Type={}", app.event_filter_value),
                     EventFilterMode::ByService => format!(" 🔍 Service={}", app.event_filter_value),
@@ -1431,9 +1670,15 @@ fn ui(f: &mut Frame, app: &App) {
             };
 
             let events_title = if events_focused {
-                format!("⚡ Event Timeline [1-4:Filter c:Clear] ●{}", filter_indicator)
+                format!(
+                    "⚡ Event Timeline [1-4:Filter /:Search c:Clear] ●{}",
+                    filter_indicator
+                )
             } else {
-                format!("⚡ Event Timeline [1-4:Filter c:Clear]{}", filter_indicator)
+                format!(
+                    "⚡ Event Timeline [1-4:Filter /:Search c:Clear]{}",
+                    filter_indicator
+                )
             };
             let events_block = Block::default()
                 .borders(Borders::ALL)
@@ -36,6 +36,8 @@ pub struct TraceMetadata {
     pub last_timestamp: String,
     pub service_count: usize,
     pub services: Vec<String>,
+    #[serde(default)]
+    pub trace_tags: std::collections::HashMap<String, String>,
 }
 
 #[derive(Deserialize)]
@@ -80,9 +82,26 @@ pub struct RaceDetail {
 }
 
 #[derive(Deserialize)]
-pub struct GlobalAnalysisResponse {
-    pub success: bool,
-    pub data: Option<GlobalAnalysisData>,
+pub struct GlobalAnalysisJobStartResponse {
+    pub data: Option<GlobalAnalysisJobStart>,
+}
+
+#[derive(Deserialize)]
+pub struct GlobalAnalysisJobStart {
+    pub job_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct GlobalAnalysisJobResponse {
+    pub data: Option<GlobalAnalysisJob>,
+}
+
+#[derive(Deserialize)]
+pub struct GlobalAnalysisJob {
+    pub status: String,
+    pub progress_percent: f64,
+    pub error: Option<String>,
+    pub result: Option<GlobalAnalysisData>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -172,6 +191,7 @@ pub struct CachedTraceData {
     pub critical_path_data: Option<CriticalPathData>,
     pub dependencies_data: Option<DependenciesData>,
     pub distributed_analysis_data: Option<DistributedTraceAnalysisData>,
+    pub raw_response: String, // `/api/traces/:id` body, as exported with `e`
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -257,6 +277,8 @@ pub struct VariableAccess {
     pub location: String,
     pub has_causal_link_to_previous: bool,
     pub is_race: bool,
+    #[serde(default)]
+    pub is_lost_update: bool,
 }
 
 // Full trace analysis response (single endpoint with ALL data)
@@ -0,0 +1,267 @@
+//! `raceway watch` - tail new traces while a workload runs.
+//!
+//! Polls the most recent page of `/api/traces` and prints traces that were not
+//! there on earlier polls. Traces whose event count changed are re-analyzed so
+//! a one-line summary can be printed when they pick up race conditions.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use raceway::client::{RacewayApiClient, TraceListFilter, TraceMetadata};
+
+/// Traces fetched per poll; more new traces than this between polls are missed
+const WATCH_PAGE_SIZE: usize = 100;
+
+struct SeenTrace {
+    event_count: usize,
+    /// Race count at the last check; `None` for traces that existed before the
+    /// watch started and have not been analyzed yet
+    races: Option<usize>,
+}
+
+/// Traces seen on previous polls. The first poll only records a baseline.
+#[derive(Default)]
+pub struct WatchState {
+    seen: HashMap<String, SeenTrace>,
+    primed: bool,
+}
+
+/// What changed since the previous poll
+#[derive(Debug, Default)]
+pub struct WatchUpdate {
+    pub lines: Vec<String>,
+    pub race_detected: bool,
+}
+
+pub async fn run_watch(api: &RacewayApiClient, interval: u64, on_race: bool) -> Result<()> {
+    let mut state = WatchState::default();
+
+    poll_once(api, &mut state).await?;
+    println!(
+        "👀 Watching {} for new traces ({} already present, Ctrl-C to stop)",
+        api.base_url(),
+        state.seen.len()
+    );
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+
+        let update = poll_once(api, &mut state).await?;
+        for line in &update.lines {
+            println!("{}", line);
+        }
+        if on_race && update.race_detected {
+            return Err(anyhow!("Race condition detected"));
+        }
+    }
+}
+
+/// Poll the server once and diff the trace list against earlier polls.
+pub async fn poll_once(api: &RacewayApiClient, state: &mut WatchState) -> Result<WatchUpdate> {
+    let listing = api
+        .list_traces(1, WATCH_PAGE_SIZE, &TraceListFilter::default())
+        .await?;
+    let mut update = WatchUpdate::default();
+
+    if !state.primed {
+        for trace in listing.traces {
+            state.seen.insert(
+                trace.trace_id,
+                SeenTrace {
+                    event_count: trace.event_count,
+                    races: None,
+                },
+            );
+        }
+        state.primed = true;
+        return Ok(update);
+    }
+
+    // Oldest first, so output reads in arrival order
+    for trace in listing.traces.iter().rev() {
+        let previous_races = match state.seen.get(&trace.trace_id) {
+            None => {
+                update.lines.push(format_new_trace(trace));
+                Some(0)
+            }
+            Some(seen) if seen.event_count == trace.event_count => continue,
+            Some(seen) => seen.races,
+        };
+
+        let summary = api.get_trace_summary(&trace.trace_id, 0).await?;
+        let races = summary.analysis.potential_races;
+        if previous_races.is_some_and(|previous| races > previous) {
+            let mut variables: Vec<&str> = summary
+                .analysis
+                .race_details
+                .iter()
+                .map(|race| race.variable.as_str())
+                .collect();
+            variables.sort();
+            variables.dedup();
+            update.lines.push(format!(
+                "🚨 {} now has {} race condition(s) on {}",
+                trace.trace_id,
+                races,
+                variables.join(", ")
+            ));
+            update.race_detected = true;
+        }
+
+        state.seen.insert(
+            trace.trace_id.clone(),
+            SeenTrace {
+                event_count: trace.event_count,
+                races: Some(races),
+            },
+        );
+    }
+
+    Ok(update)
+}
+
+fn format_new_trace(trace: &TraceMetadata) -> String {
+    format!(
+        "🆕 {} | {} events | {}",
+        trace.trace_id,
+        trace.event_count,
+        trace.services.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Path, State};
+    use axum::{routing::get, Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    /// Trace id -> (event count, race variables), served newest first
+    type MockTraces = Arc<Mutex<Vec<(String, usize, Vec<&'static str>)>>>;
+
+    async fn spawn_mock_server(traces: MockTraces) -> String {
+        let app = Router::new()
+            .route(
+                "/api/traces",
+                get(|State(traces): State<MockTraces>| async move {
+                    let traces = traces.lock().unwrap();
+                    let rows: Vec<Value> = traces
+                        .iter()
+                        .map(|(id, events, _)| {
+                            json!({
+                                "trace_id": id,
+                                "event_count": events,
+                                "first_timestamp": "2024-01-01T00:00:00Z",
+                                "last_timestamp": "2024-01-01T00:00:01Z",
+                                "service_count": 1,
+                                "services": ["api"]
+                            })
+                        })
+                        .collect();
+                    Json(json!({
+                        "success": true,
+                        "data": {
+                            "total_traces": rows.len(),
+                            "page": 1,
+                            "page_size": WATCH_PAGE_SIZE,
+                            "total_pages": 1,
+                            "traces": rows
+                        },
+                        "error": null
+                    }))
+                }),
+            )
+            .route(
+                "/api/traces/:id",
+                get(
+                    |State(traces): State<MockTraces>, Path(id): Path<String>| async move {
+                        let traces = traces.lock().unwrap();
+                        let races = traces
+                            .iter()
+                            .find(|(trace_id, _, _)| *trace_id == id)
+                            .map(|(_, _, races)| races.clone())
+                            .unwrap_or_default();
+                        let details: Vec<Value> = races
+                            .iter()
+                            .map(|variable| {
+                                json!({
+                                    "variable": variable,
+                                    "event1_thread": "t1",
+                                    "event2_thread": "t2",
+                                    "event1_location": "a.rs:1",
+                                    "event2_location": "a.rs:2",
+                                    "severity": "CRITICAL",
+                                    "description": "write-write"
+                                })
+                            })
+                            .collect();
+                        Json(json!({
+                            "success": true,
+                            "data": {
+                                "trace_id": id,
+                                "analysis": {
+                                    "potential_races": races.len(),
+                                    "race_details": details
+                                }
+                            },
+                            "error": null
+                        }))
+                    },
+                ),
+            )
+            .with_state(traces);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn poll_reports_new_traces_and_races() {
+        let traces: MockTraces = Arc::new(Mutex::new(vec![("existing".into(), 3, vec![])]));
+        let api = RacewayApiClient::new(spawn_mock_server(traces.clone()).await);
+        let mut state = WatchState::default();
+
+        let baseline = poll_once(&api, &mut state).await.unwrap();
+        assert!(baseline.lines.is_empty());
+
+        traces
+            .lock()
+            .unwrap()
+            .insert(0, ("fresh".into(), 2, vec![]));
+        let update = poll_once(&api, &mut state).await.unwrap();
+        assert_eq!(update.lines.len(), 1);
+        assert!(update.lines[0].starts_with("🆕 fresh | 2 events"));
+        assert!(!update.race_detected);
+
+        // Nothing changed, nothing printed
+        let update = poll_once(&api, &mut state).await.unwrap();
+        assert!(update.lines.is_empty());
+
+        traces.lock().unwrap()[0] = ("fresh".into(), 4, vec!["balance"]);
+        let update = poll_once(&api, &mut state).await.unwrap();
+        assert_eq!(
+            update.lines,
+            vec!["🚨 fresh now has 1 race condition(s) on balance"]
+        );
+        assert!(update.race_detected);
+    }
+
+    #[tokio::test]
+    async fn races_already_present_at_start_are_not_reported() {
+        let traces: MockTraces = Arc::new(Mutex::new(vec![("old".into(), 3, vec!["x"])]));
+        let api = RacewayApiClient::new(spawn_mock_server(traces.clone()).await);
+        let mut state = WatchState::default();
+        poll_once(&api, &mut state).await.unwrap();
+
+        traces.lock().unwrap()[0].1 = 5;
+        let update = poll_once(&api, &mut state).await.unwrap();
+        assert!(update.lines.is_empty());
+        assert!(!update.race_detected);
+    }
+}
@@ -0,0 +1,28 @@
+-- Distributed tracing: spans and the edges linking them across services
+
+CREATE TABLE IF NOT EXISTS distributed_spans (
+    trace_id CHAR(36) NOT NULL,
+    span_id VARCHAR(255) PRIMARY KEY,
+    service VARCHAR(255) NOT NULL,
+    instance VARCHAR(255) NOT NULL,
+    first_event DATETIME(6) NOT NULL,
+    last_event DATETIME(6),
+    span_data JSON NOT NULL,
+    created_at DATETIME(6) DEFAULT CURRENT_TIMESTAMP(6),
+
+    INDEX idx_distributed_spans_trace_id (trace_id),
+    INDEX idx_distributed_spans_service (service),
+    INDEX idx_distributed_spans_first_event (first_event)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS distributed_edges (
+    from_span VARCHAR(255) NOT NULL,
+    to_span VARCHAR(255) NOT NULL,
+    link_type VARCHAR(50) NOT NULL,
+    edge_data JSON NOT NULL,
+    created_at DATETIME(6) DEFAULT CURRENT_TIMESTAMP(6),
+
+    PRIMARY KEY (from_span, to_span),
+    INDEX idx_distributed_edges_from_span (from_span),
+    INDEX idx_distributed_edges_to_span (to_span)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,10 @@
+-- Environment Index for Raceway PostgreSQL Database
+-- Supports per-environment scoping of trace, race and hotspot queries
+
+-- =============================================================================
+-- Expression Index on metadata->>'environment'
+-- =============================================================================
+-- Speeds up: WHERE metadata->>'environment' = ?
+-- Used by: get_trace_summaries_by_environment(), ?environment= race/hotspot filters
+CREATE INDEX IF NOT EXISTS idx_events_environment
+ON events((metadata->>'environment'), trace_id);
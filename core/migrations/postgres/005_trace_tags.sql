@@ -0,0 +1,19 @@
+-- Trace Tags for Raceway PostgreSQL Database
+-- Labels attached to whole traces (POST /api/traces/:id/tags)
+
+-- =============================================================================
+-- Trace Tags Table
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS trace_tags (
+    trace_id UUID NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    PRIMARY KEY (trace_id, key)
+);
+
+-- Speeds up: GET /api/traces?trace_tag=key:value
+CREATE INDEX IF NOT EXISTS idx_trace_tags_key_value ON trace_tags(key, value);
+
+COMMENT ON TABLE trace_tags IS 'User-assigned labels on traces, one value per key';
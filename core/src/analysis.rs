@@ -1,11 +1,21 @@
+use crate::anomaly_feed::{AnomalyAlert, AnomalyFeed};
+use crate::bundle::{BundleAnnotations, BundleImport, TraceBundle, BUNDLE_FORMAT_VERSION};
 use crate::config::Config;
 use crate::event::{DistributedEdge, DistributedSpan, EdgeLinkType, Event};
-use crate::graph::{Anomaly, AuditTrail, CausalGraph, CriticalPath, ServiceDependencies, TreeNode};
+use crate::graph::{
+    Anomaly, AuditTrail, CausalGraph, CriticalPath, DoubleCheckedLocking, EventOrdering,
+    GraphStats, LineageAttempt, LockOrderViolation, LockStats, RaceExplanation, ScoredRace,
+    ServiceDependencies, TreeNode, UnreleasedLock, VariableHotspot,
+};
+use crate::health::{
+    event_coverage, health_score, HealthIndex, HealthInputs, HealthPage, TraceHealth,
+};
 use crate::storage::{CrossTraceRace, StorageBackend, TraceAnalysisData};
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use chrono::{DateTime, Utc};
-use std::collections::HashSet;
-use std::sync::Arc;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
@@ -15,14 +25,24 @@ pub struct AnalysisService {
     storage: Arc<dyn StorageBackend>,
     graph: Arc<RwLock<CausalGraph>>,
     warmup: Arc<RwLock<WarmupStatus>>,
+    anomaly_feed: Arc<Mutex<AnomalyFeed>>,
+    /// Races already logged per trace with `race_detection.log_on_detect`
+    logged_races: Mutex<HashMap<Uuid, HashSet<(Uuid, Uuid)>>>,
+    /// Health scores ranked for `sort=health`, rescored when the graph reports
+    /// their traces (or traces merged into them) changed
+    health_index: Mutex<HealthIndex>,
+    /// Serializes `refresh_health_index`, so a slow refresh can't store scores
+    /// over a newer one's
+    health_refresh: tokio::sync::Mutex<()>,
     config: Config,
 }
 
 impl AnalysisService {
     /// Create a new AnalysisService with the given storage backend and config
     pub async fn new(storage: Arc<dyn StorageBackend>, config: Config) -> Result<Self> {
-        let graph = Arc::new(RwLock::new(CausalGraph::new()));
+        let graph = Arc::new(RwLock::new(build_graph(&config)));
         let warmup = Arc::new(RwLock::new(WarmupStatus::new()));
+        let anomaly_feed = Arc::new(Mutex::new(build_anomaly_feed(&config)));
 
         // Load existing baselines from storage
         let baseline_operations = storage.get_all_baseline_operations().await?;
@@ -43,6 +63,10 @@ impl AnalysisService {
             storage,
             graph,
             warmup,
+            anomaly_feed,
+            logged_races: Mutex::new(HashMap::new()),
+            health_index: Mutex::new(HealthIndex::default()),
+            health_refresh: tokio::sync::Mutex::new(()),
             config,
         })
     }
@@ -65,6 +89,7 @@ impl AnalysisService {
 
         // Persist to storage first
         self.storage.add_event(event.clone()).await?;
+        let mut linked_traces = HashSet::new();
 
         // Handle distributed tracing metadata if enabled
         if self.config.distributed_tracing.enabled {
@@ -113,6 +138,7 @@ impl AnalysisService {
                         }),
                     };
 
+                    linked_traces.extend(self.upstream_traces(std::slice::from_ref(&edge)).await?);
                     self.storage.add_distributed_edge(edge).await?;
                 }
             }
@@ -121,6 +147,7 @@ impl AnalysisService {
         // Then update in-memory graph
         let graph = self.graph.write().await;
         graph.add_event(event)?;
+        graph.mark_traces_changed(linked_traces);
 
         Ok(())
     }
@@ -133,6 +160,7 @@ impl AnalysisService {
 
         // First, persist all events to storage in a single batch
         let event_count = self.storage.add_events_batch(events.clone()).await?;
+        let mut linked_traces = HashSet::new();
 
         // Handle distributed tracing if enabled
         if self.config.distributed_tracing.enabled {
@@ -190,20 +218,51 @@ impl AnalysisService {
             }
 
             // Batch add all edges
+            linked_traces = self.upstream_traces(&edges_to_add).await?;
             for edge in edges_to_add {
                 self.storage.add_distributed_edge(edge).await?;
             }
         }
 
-        // Update in-memory graph with all events
+        // Update in-memory graph; children whose parent is in a later batch wait for it
         let graph = self.graph.write().await;
-        for event in events {
-            graph.add_event(event)?;
-        }
+        graph.add_events_reconciling(events)?;
+        graph.mark_traces_changed(linked_traces);
 
         Ok(event_count)
     }
 
+    /// Add buffered events whose parent never arrived as trace roots
+    pub async fn expire_orphans(&self) -> Result<usize> {
+        let graph = self.graph.write().await;
+        graph.expire_orphans()
+    }
+
+    /// Evict all but the `max_traces` most recently active traces from the graph,
+    /// and from storage if the backend only keeps them in memory
+    pub async fn retain_recent(&self, max_traces: usize) -> Result<usize> {
+        // Held until storage lets go too, so no query reloads a trace halfway evicted
+        let graph = self.graph.write().await;
+        let evicted = graph.retain_recent(max_traces);
+        if evicted.is_empty() {
+            return Ok(0);
+        }
+
+        self.logged_races
+            .lock()
+            .unwrap()
+            .retain(|trace_id, _| graph.has_trace(*trace_id));
+        {
+            let mut index = self.health_index.lock().unwrap();
+            for trace_id in &evicted {
+                index.remove(*trace_id);
+            }
+        }
+        let trace_ids: Vec<Uuid> = evicted.into_iter().collect();
+        self.storage.evict_traces(&trace_ids).await?;
+        Ok(trace_ids.len())
+    }
+
     /// Update baselines after processing a trace
     pub async fn update_baselines(&self, trace_id: Uuid) -> Result<()> {
         self.ensure_trace_loaded(trace_id).await?;
@@ -221,17 +280,180 @@ impl AnalysisService {
         Ok(())
     }
 
+    /// Persist the in-memory baselines to storage, returning how many were saved
+    pub async fn persist_baselines(&self) -> Result<usize> {
+        let baselines = self.graph.read().await.get_all_baselines();
+        let count = baselines.len();
+        if count > 0 {
+            self.storage.save_baselines_batch(baselines).await?;
+        }
+        Ok(count)
+    }
+
     /// Get the current warm-up status
     pub async fn warmup_status(&self) -> WarmupStatus {
         self.warmup.read().await.clone()
     }
 
+    /// Mark the start of a warm-up replay over `total_traces` traces
+    pub async fn begin_warmup(&self, total_traces: usize) {
+        let mut warmup = self.warmup.write().await;
+        *warmup = WarmupStatus {
+            phase: WarmupPhase::Replaying,
+            total_traces,
+            started_at: Some(Utc::now()),
+            ..WarmupStatus::new()
+        };
+    }
+
+    /// Record that a trace has been replayed during warm-up
+    pub async fn record_warmup_progress(&self, trace_id: Uuid) {
+        let mut warmup = self.warmup.write().await;
+        warmup.processed_traces += 1;
+        warmup.last_trace = Some(trace_id);
+    }
+
+    /// Mark the warm-up replay as completed
+    pub async fn complete_warmup(&self) {
+        let mut warmup = self.warmup.write().await;
+        warmup.phase = WarmupPhase::Completed;
+        warmup.completed_at = Some(Utc::now());
+    }
+
+    /// Mark the warm-up replay as failed
+    pub async fn fail_warmup(&self, error: String) {
+        let mut warmup = self.warmup.write().await;
+        warmup.phase = WarmupPhase::Failed;
+        warmup.last_error = Some(error);
+    }
+
+    /// Replay every stored trace into the graph, `concurrency` traces at a time.
+    /// Storage reads overlap, but each trace is ingested as one batch under the
+    /// graph lock, so events within a trace keep their order. Stored distributed
+    /// edges are applied once all traces are in.
+    pub async fn warmup(&self, concurrency: usize) -> Result<()> {
+        let trace_ids = match self.storage.get_all_trace_ids().await {
+            Ok(trace_ids) => trace_ids,
+            Err(e) => {
+                self.fail_warmup(e.to_string()).await;
+                return Err(e);
+            }
+        };
+        self.begin_warmup(trace_ids.len()).await;
+
+        let mut replays = stream::iter(trace_ids)
+            .map(|trace_id| async move {
+                self.ensure_trace_loaded(trace_id)
+                    .await
+                    .map(|()| trace_id)
+                    .map_err(|e| anyhow!("replaying trace {}: {}", trace_id, e))
+            })
+            .buffer_unordered(concurrency.max(1));
+        while let Some(replayed) = replays.next().await {
+            match replayed {
+                Ok(trace_id) => self.record_warmup_progress(trace_id).await,
+                Err(e) => {
+                    self.fail_warmup(e.to_string()).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        // An edge whose other span was in a trace replayed later could not be
+        // resolved mid-replay; every event is in the graph now
+        if self.config.distributed_tracing.enabled {
+            match self.storage.list_distributed_edges().await {
+                Ok(edges) => self.graph.read().await.add_distributed_edges(edges),
+                Err(e) => {
+                    let e = anyhow!("loading distributed edges: {}", e);
+                    self.fail_warmup(e.to_string()).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        self.complete_warmup().await;
+        Ok(())
+    }
+
+    /// Size of the in-memory causal graph
+    pub async fn graph_stats(&self) -> GraphStats {
+        self.graph.read().await.stats()
+    }
+
+    /// Number of traces in the in-memory causal graph
+    pub async fn active_trace_count(&self) -> usize {
+        self.graph.read().await.trace_count()
+    }
+
     /// Detect anomalies in a trace
     pub async fn detect_anomalies(&self, trace_id: Uuid) -> Result<Vec<Anomaly>> {
         self.ensure_trace_loaded(trace_id).await?;
 
         let graph = self.graph.read().await;
-        graph.detect_anomalies(trace_id)
+        let anomalies = trace_anomalies(&graph, trace_id)?;
+        self.record_anomalies(trace_id, &anomalies);
+        Ok(anomalies)
+    }
+
+    /// Deduplicated anomaly alerts across all traces, most recent first
+    pub fn get_anomaly_alerts(&self) -> Vec<AnomalyAlert> {
+        self.anomaly_feed.lock().unwrap().alerts()
+    }
+
+    /// Window within which repeats of the same anomaly are folded together
+    pub fn anomaly_cooldown(&self) -> std::time::Duration {
+        self.anomaly_feed.lock().unwrap().cooldown()
+    }
+
+    fn record_anomalies(&self, trace_id: Uuid, anomalies: &[Anomaly]) {
+        self.anomaly_feed
+            .lock()
+            .unwrap()
+            .record(trace_id, anomalies);
+    }
+
+    /// With `race_detection.log_on_detect`, warn about each race the first
+    /// time analysis finds it in the trace
+    fn log_new_races<'a>(
+        &self,
+        trace_id: Uuid,
+        pairs: impl IntoIterator<Item = (&'a Event, &'a Event)>,
+    ) {
+        if !self.config.race_detection.log_on_detect {
+            return;
+        }
+
+        let mut logged = self.logged_races.lock().unwrap();
+        let logged = logged.entry(trace_id).or_default();
+        for (event1, event2) in pairs {
+            let (
+                crate::event::EventKind::StateChange {
+                    variable,
+                    location: location1,
+                    ..
+                },
+                crate::event::EventKind::StateChange {
+                    location: location2,
+                    ..
+                },
+            ) = (&event1.kind, &event2.kind)
+            else {
+                continue;
+            };
+            if !logged.insert((event1.id.min(event2.id), event1.id.max(event2.id))) {
+                continue;
+            }
+            tracing::warn!(
+                trace_id = %trace_id,
+                variable = %variable,
+                event1 = %event1.id,
+                location1 = %location1,
+                event2 = %event2.id,
+                location2 = %location2,
+                "Race detected"
+            );
+        }
     }
 
     /// Get critical path for a trace
@@ -242,12 +464,212 @@ impl AnalysisService {
         graph.get_critical_path(trace_id)
     }
 
+    /// All attempts of the retried operation a trace belongs to, ordered by attempt
+    pub async fn get_trace_lineage(&self, trace_id: Uuid) -> Result<Vec<LineageAttempt>> {
+        self.ensure_trace_loaded(trace_id).await?;
+
+        let graph = self.graph.read().await;
+        Ok(graph.get_trace_lineage(trace_id))
+    }
+
     /// Find concurrent events within a trace
     pub async fn find_concurrent_events(&self, trace_id: Uuid) -> Result<Vec<(Event, Event)>> {
         self.ensure_trace_loaded(trace_id).await?;
 
         let graph = self.graph.read().await;
-        graph.find_concurrent_events(trace_id)
+        let pairs = graph.find_concurrent_events(trace_id)?;
+        self.log_new_races(trace_id, pairs.iter().map(|(a, b)| (a, b)));
+        Ok(pairs)
+    }
+
+    /// Whether `race_detection.max_pairs_per_trace` cut a trace's concurrent
+    /// pairs short, so its race results are partial
+    pub async fn concurrent_events_truncated(&self, trace_id: Uuid) -> Result<bool> {
+        self.ensure_trace_loaded(trace_id).await?;
+
+        let graph = self.graph.read().await;
+        Ok(graph.find_concurrent_events_with_truncation(trace_id)?.1)
+    }
+
+    /// Find races in a trace, each with a severity
+    pub async fn find_races_with_severity(&self, trace_id: Uuid) -> Result<Vec<ScoredRace>> {
+        self.ensure_trace_loaded(trace_id).await?;
+
+        let graph = self.graph.read().await;
+        let races = graph.find_races_with_severity(trace_id)?;
+        self.log_new_races(
+            trace_id,
+            races.iter().map(|race| (&race.event1, &race.event2)),
+        );
+        Ok(races)
+    }
+
+    /// Find locks whose acquires and releases don't balance within a trace
+    pub async fn find_unreleased_locks(&self, trace_id: Uuid) -> Result<Vec<UnreleasedLock>> {
+        self.ensure_trace_loaded(trace_id).await?;
+
+        let graph = self.graph.read().await;
+        graph.find_unreleased_locks(trace_id)
+    }
+
+    /// Find double-checked locking on non-atomic variables within a trace
+    pub async fn find_double_checked_locking(
+        &self,
+        trace_id: Uuid,
+    ) -> Result<Vec<DoubleCheckedLocking>> {
+        self.ensure_trace_loaded(trace_id).await?;
+
+        let graph = self.graph.read().await;
+        graph.find_double_checked_locking(trace_id)
+    }
+
+    /// Causal order of two events. `trace_id` is loaded first; an event from
+    /// another trace is looked up in storage and its trace loaded too.
+    pub async fn event_ordering(&self, trace_id: Uuid, a: Uuid, b: Uuid) -> Result<EventOrdering> {
+        self.ensure_trace_loaded(trace_id).await?;
+        for id in [a, b] {
+            let event = self
+                .storage
+                .get_event(id)
+                .await?
+                .ok_or_else(|| anyhow!("Event {} not found", id))?;
+            if event.trace_id != trace_id {
+                self.ensure_trace_loaded(event.trace_id).await?;
+            }
+        }
+
+        let graph = self.graph.read().await;
+        graph.event_ordering(a, b)
+    }
+
+    /// Explain the race verdict for one pair of events in a trace
+    pub async fn explain_pair(&self, trace_id: Uuid, a: Uuid, b: Uuid) -> Result<RaceExplanation> {
+        self.ensure_trace_loaded(trace_id).await?;
+
+        let graph = self.graph.read().await;
+        graph.explain_pair(trace_id, a, b)
+    }
+
+    /// Events through which `from` causally leads to `to`, both included,
+    /// in order. Errors when either event is not in the trace or no path exists.
+    pub async fn find_causal_path(
+        &self,
+        trace_id: Uuid,
+        from: Uuid,
+        to: Uuid,
+    ) -> Result<Vec<Event>> {
+        self.ensure_trace_loaded(trace_id).await?;
+
+        let graph = self.graph.read().await;
+        for event_id in [from, to] {
+            if graph.get_event(event_id).map(|event| event.trace_id) != Some(trace_id) {
+                bail!("Event {} not found in trace {}", event_id, trace_id);
+            }
+        }
+        graph.find_causal_path(from, to)
+    }
+
+    /// Bundle a trace with everything needed to reproduce its analysis elsewhere
+    pub async fn export_bundle(&self, trace_id: Uuid) -> Result<TraceBundle> {
+        let events = self.get_merged_trace_events(trace_id).await?;
+        if events.is_empty() {
+            return Err(anyhow!("Trace {} not found", trace_id));
+        }
+        self.ensure_trace_loaded_from_events(trace_id, &events)
+            .await?;
+
+        let trace_ids: HashSet<Uuid> = events.iter().map(|event| event.trace_id).collect();
+        let mut distributed_spans = Vec::new();
+        let mut distributed_edges = Vec::new();
+        let mut seen_edges = HashSet::new();
+        for tid in trace_ids {
+            distributed_spans.extend(self.storage.get_distributed_spans(tid).await?);
+            for edge in self.storage.get_distributed_edges(tid).await? {
+                if seen_edges.insert((edge.from_span.clone(), edge.to_span.clone())) {
+                    distributed_edges.push(edge);
+                }
+            }
+        }
+
+        let graph = self.graph.read().await;
+        let races = graph.find_concurrent_events(trace_id)?;
+        let critical_path = graph.get_critical_path(trace_id).ok();
+
+        Ok(TraceBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            trace_id,
+            exported_at: Utc::now(),
+            baselines: graph.get_baselines_for(&events),
+            annotations: BundleAnnotations::from_results(&races, critical_path.as_ref()),
+            events,
+            distributed_spans,
+            distributed_edges,
+        })
+    }
+
+    /// Load a bundle from `export_bundle`, then re-run its analysis and check
+    /// it against the exporter's annotations
+    pub async fn import_bundle(&self, bundle: TraceBundle) -> Result<BundleImport> {
+        if bundle.format_version > BUNDLE_FORMAT_VERSION {
+            bail!(
+                "Unsupported bundle format version {} (this server reads up to {})",
+                bundle.format_version,
+                BUNDLE_FORMAT_VERSION
+            );
+        }
+        if !self
+            .storage
+            .get_trace_events(bundle.trace_id)
+            .await?
+            .is_empty()
+        {
+            bail!("Trace {} already exists on this server", bundle.trace_id);
+        }
+
+        let TraceBundle {
+            trace_id,
+            events,
+            distributed_spans,
+            distributed_edges,
+            baselines,
+            annotations,
+            ..
+        } = bundle;
+
+        let events_imported = self.storage.add_events_batch(events.clone()).await?;
+        let spans_imported = distributed_spans.len();
+        for span in distributed_spans {
+            self.storage.save_distributed_span(span).await?;
+        }
+        let edges_imported = distributed_edges.len();
+        for edge in distributed_edges.iter().cloned() {
+            self.storage.add_distributed_edge(edge).await?;
+        }
+        let baselines_imported = baselines.len();
+
+        let reproduced = {
+            let graph = self.graph.write().await;
+            for (operation, stats) in &baselines {
+                graph.set_baseline(operation, stats.clone());
+            }
+            // Parents outside the bundle stand in as roots, as they did on export
+            graph.ingest_events(events)?;
+            graph.add_distributed_edges(distributed_edges);
+
+            let races = graph.find_concurrent_events(trace_id)?;
+            let critical_path = graph.get_critical_path(trace_id).ok();
+            BundleAnnotations::from_results(&races, critical_path.as_ref()) == annotations
+        };
+        self.storage.save_baselines_batch(baselines).await?;
+
+        Ok(BundleImport {
+            trace_id,
+            events_imported,
+            spans_imported,
+            edges_imported,
+            baselines_imported,
+            reproduced,
+        })
     }
 
     /// Find concurrent events across all traces
@@ -256,10 +678,54 @@ impl AnalysisService {
         graph.find_global_concurrent_events()
     }
 
+    /// Find concurrent events across all traces on a blocking thread,
+    /// reporting `(done, total)` progress as the comparison runs
+    pub async fn find_global_concurrent_events_with_progress<F>(
+        &self,
+        progress: F,
+    ) -> Result<Vec<(Event, Event)>>
+    where
+        F: Fn(usize, usize) + Send + 'static,
+    {
+        let graph = Arc::clone(&self.graph);
+        tokio::task::spawn_blocking(move || {
+            let graph = graph.blocking_read();
+            graph.find_global_concurrent_events_with_progress(progress)
+        })
+        .await?
+    }
+
+    /// Find concurrent accesses of one variable across all traces
+    pub async fn find_global_concurrent_events_for(&self, variable: &str) -> Vec<(Event, Event)> {
+        let graph = self.graph.read().await;
+        graph.find_global_concurrent_events_for(variable)
+    }
+
+    /// Find locks acquired in conflicting orders across all traces
+    pub async fn detect_lock_order_inversions(&self) -> Vec<LockOrderViolation> {
+        let graph = self.graph.read().await;
+        graph.detect_lock_order_inversions()
+    }
+
+    /// Per-lock acquisition counts and hold times across all traces
+    pub async fn lock_contention_stats(&self) -> Vec<LockStats> {
+        let graph = self.graph.read().await;
+        graph.lock_contention_stats()
+    }
+
+    /// The `top_n` most accessed variables across all traces
+    pub async fn variable_hotspots(
+        &self,
+        top_n: usize,
+        environment: Option<&str>,
+    ) -> Vec<VariableHotspot> {
+        let graph = self.graph.read().await;
+        graph.variable_hotspots(top_n, environment)
+    }
+
     /// Get cross-trace races for a specific variable
     pub async fn get_cross_trace_races(&self, variable: &str) -> Result<Vec<CrossTraceRace>> {
-        let graph = self.graph.read().await;
-        let concurrent_pairs = graph.find_global_concurrent_events()?;
+        let concurrent_pairs = self.find_global_concurrent_events_for(variable).await;
 
         let mut races = Vec::new();
         for (event1, event2) in concurrent_pairs {
@@ -302,12 +768,37 @@ impl AnalysisService {
         Ok(races)
     }
 
-    /// Get service dependencies for a trace
-    pub async fn get_service_dependencies(&self, trace_id: Uuid) -> Result<ServiceDependencies> {
+    /// Get lock-usage recommendations for the given variables from their accesses
+    /// in `trace_ids` (variables whose lock usage there yields no suggestion are omitted)
+    pub async fn get_lock_recommendations<'a, I>(
+        &self,
+        variables: I,
+        trace_ids: &HashSet<Uuid>,
+    ) -> HashMap<String, String>
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        let graph = self.graph.read().await;
+        variables
+            .into_iter()
+            .filter_map(|variable| {
+                graph
+                    .lock_recommendation(variable, trace_ids)
+                    .map(|recommendation| (variable.clone(), recommendation))
+            })
+            .collect()
+    }
+
+    /// Get service dependencies for a trace, optionally split per service instance
+    pub async fn get_service_dependencies(
+        &self,
+        trace_id: Uuid,
+        by_instance: bool,
+    ) -> Result<ServiceDependencies> {
         self.ensure_trace_loaded(trace_id).await?;
 
         let graph = self.graph.read().await;
-        graph.get_service_dependencies(trace_id)
+        graph.get_service_dependencies(trace_id, by_instance)
     }
 
     /// Get audit trail for a variable in a trace
@@ -338,7 +829,7 @@ impl AnalysisService {
     /// This fetches events from the primary trace and all related traces connected via distributed edges
     /// Uses BFS to recursively follow all edges through arbitrary-length service chains
     async fn get_merged_trace_events(&self, trace_id: Uuid) -> Result<Vec<Event>> {
-        use std::collections::{HashMap, HashSet, VecDeque};
+        use std::collections::VecDeque;
 
         // Start with events from the primary trace
         let mut all_events = self.storage.get_trace_events(trace_id).await?;
@@ -455,7 +946,7 @@ impl AnalysisService {
         }
 
         // Sort all events by timestamp to create a unified timeline
-        all_events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        crate::event::sort_events_chronologically(&mut all_events);
 
         tracing::info!(
             "Merged trace {}: {} total events from {} spans across {} traces",
@@ -486,9 +977,10 @@ impl AnalysisService {
 
         // Get analysis results from graph
         let graph = self.graph.read().await;
-        let anomalies = graph.detect_anomalies(trace_id)?;
+        let anomalies = trace_anomalies(&graph, trace_id)?;
+        self.record_anomalies(trace_id, &anomalies);
         let critical_path = graph.get_critical_path(trace_id).ok();
-        let dependencies = graph.get_service_dependencies(trace_id).ok();
+        let dependencies = graph.get_service_dependencies(trace_id, false).ok();
 
         // Get audit trails in a single pass
         let audit_trails = graph.get_audit_trails_bulk(trace_id, &variables)?;
@@ -502,12 +994,125 @@ impl AnalysisService {
         })
     }
 
+    /// Compute the health score for a trace from its races, anomalies,
+    /// critical path and causality coverage.
+    pub async fn get_trace_health(&self, trace_id: Uuid) -> Result<TraceHealth> {
+        Ok(self.compute_trace_health(trace_id).await?.0)
+    }
+
+    /// Up to `limit` traces in the graph ranked by health score, worst first,
+    /// skipping `offset` of them and starting after the trace `after` when
+    /// given. `only` restricts the ranking to those traces. None if `after`
+    /// isn't a ranked trace.
+    ///
+    /// Scores are kept between calls and only traces that changed since the
+    /// last call (or are merged with one that did) are rescored, so anomaly
+    /// penalties reflect the baselines at the time each score was computed.
+    pub async fn health_page(
+        &self,
+        offset: usize,
+        after: Option<Uuid>,
+        limit: usize,
+        only: Option<&HashSet<Uuid>>,
+    ) -> Result<Option<HealthPage>> {
+        self.refresh_health_index().await?;
+        Ok(self
+            .health_index
+            .lock()
+            .unwrap()
+            .page(offset, after, limit, only))
+    }
+
+    /// Rescore the traces the graph reports changed, and the traces whose
+    /// scores merged their events
+    async fn refresh_health_index(&self) -> Result<()> {
+        let _refresh = self.health_refresh.lock().await;
+        let changed = self.graph.read().await.take_changed_traces();
+        let pending: Vec<Uuid> = {
+            let mut index = self.health_index.lock().unwrap();
+            index.invalidate(changed);
+            index.take_pending().into_iter().collect()
+        };
+
+        for (done, trace_id) in pending.iter().enumerate() {
+            match self.compute_trace_health(*trace_id).await {
+                Ok((health, sources)) => {
+                    // Under the graph lock, so a trace evicted meanwhile isn't ranked again
+                    let graph = self.graph.read().await;
+                    if graph.has_trace(*trace_id) {
+                        self.health_index
+                            .lock()
+                            .unwrap()
+                            .insert(*trace_id, health.score, sources);
+                    }
+                }
+                Err(err) => {
+                    self.health_index
+                        .lock()
+                        .unwrap()
+                        .invalidate(pending[done..].iter().copied());
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A trace's health report, and the other traces whose events it merged
+    async fn compute_trace_health(&self, trace_id: Uuid) -> Result<(TraceHealth, HashSet<Uuid>)> {
+        let events = self.get_merged_trace_events(trace_id).await?;
+
+        self.ensure_trace_loaded_from_events(trace_id, &events)
+            .await?;
+
+        let graph = self.graph.read().await;
+        let races = graph.find_races_with_severity(trace_id)?;
+        self.log_new_races(
+            trace_id,
+            races.iter().map(|race| (&race.event1, &race.event2)),
+        );
+        let anomalies = trace_anomalies(&graph, trace_id)?;
+        self.record_anomalies(trace_id, &anomalies);
+        let critical_path = graph.get_critical_path(trace_id).ok();
+
+        let health = health_score(&HealthInputs {
+            races: races.iter().map(|race| race.severity).collect(),
+            anomalies: anomalies.iter().map(|anomaly| anomaly.severity).collect(),
+            critical_path_percentage: critical_path.map(|cp| cp.percentage_of_total),
+            coverage: event_coverage(&events),
+        });
+        let sources = events
+            .iter()
+            .map(|event| event.trace_id)
+            .filter(|source| *source != trace_id)
+            .collect();
+        Ok((health, sources))
+    }
+
+    /// Traces owning the upstream end of `edges`. Their distributed traces now
+    /// reach further, which the graph can't see until it loads the edges.
+    async fn upstream_traces(&self, edges: &[DistributedEdge]) -> Result<HashSet<Uuid>> {
+        let mut trace_ids = HashSet::new();
+        for edge in edges {
+            if let Some(span) = self.storage.get_distributed_span(&edge.from_span).await? {
+                trace_ids.insert(span.trace_id);
+            }
+        }
+        Ok(trace_ids)
+    }
+
     /// Check if graph has cycles
     pub async fn has_cycles(&self) -> Result<bool> {
         let graph = self.graph.read().await;
         Ok(graph.has_cycles())
     }
 
+    /// Event IDs of every cycle in the graph (see `CausalGraph::find_cycles`)
+    pub async fn find_cycles(&self) -> Vec<Vec<Uuid>> {
+        let graph = self.graph.read().await;
+        graph.find_cycles()
+    }
+
     /// Clear all data (for testing)
     pub async fn clear(&self) -> Result<()> {
         // Clear storage
@@ -515,7 +1120,10 @@ impl AnalysisService {
 
         // Clear graph
         let mut graph = self.graph.write().await;
-        *graph = CausalGraph::new();
+        *graph = build_graph(&self.config);
+        self.anomaly_feed.lock().unwrap().clear();
+        self.logged_races.lock().unwrap().clear();
+        *self.health_index.lock().unwrap() = HealthIndex::default();
 
         // Reset warmup status (callers may choose to trigger a manual warmup afterwards)
         *self.warmup.write().await = WarmupStatus::new();
@@ -554,13 +1162,15 @@ impl AnalysisService {
         {
             let graph = self.graph.write().await;
             let mut pending = missing_events;
-            pending.retain(|event| !graph.contains_event(event.id));
+            // Orphans are persisted already but still waiting for their parent
+            pending.retain(|event| !graph.contains_event(event.id) && !graph.is_orphan(event.id));
 
             if pending.is_empty() {
                 return Ok(());
             }
 
             graph.ingest_events(pending)?;
+            graph.release_orphans()?;
         }
 
         // Load distributed edges if distributed tracing is enabled
@@ -585,6 +1195,55 @@ impl AnalysisService {
     }
 }
 
+/// Build an empty causal graph configured from `race_detection`
+fn build_graph(config: &Config) -> CausalGraph {
+    let race_detection = &config.race_detection;
+    let mut graph = CausalGraph::new()
+        .with_safe_patterns(race_detection.safe_patterns.clone())
+        .with_lock_families(race_detection.lock_families.clone())
+        .with_race_policy(race_detection.policy.clone());
+    if race_detection.require_temporal_overlap {
+        graph = graph.with_temporal_overlap(race_detection.max_gap_ms);
+    }
+    if race_detection.max_clock_components > 0 {
+        graph = graph.with_clock_component_limit(race_detection.max_clock_components);
+    }
+    if race_detection.max_pairs_per_trace > 0 {
+        graph = graph.with_max_pairs_per_trace(race_detection.max_pairs_per_trace);
+    }
+    if let Some(max_events) = config.anomaly_detection.max_events_scanned {
+        graph = graph.with_anomaly_scan_limit(max_events);
+    }
+    if config.anomaly_detection.method == "percentile" {
+        graph = graph.with_percentile_anomalies(config.anomaly_detection.percentile_multiplier);
+    }
+    let anomaly = &config.anomaly_detection;
+    graph = graph.with_min_baseline_samples(
+        anomaly.min_baseline_samples,
+        anomaly.min_baseline_samples_by_kind.clone(),
+    );
+    if config.environments.isolate {
+        graph = graph.with_environment_isolation();
+    }
+    graph.with_orphan_buffer(
+        config.engine.orphan_buffer_size,
+        std::time::Duration::from_millis(config.engine.orphan_timeout_ms),
+    )
+}
+
+fn build_anomaly_feed(config: &Config) -> AnomalyFeed {
+    AnomalyFeed::new(std::time::Duration::from_secs(
+        config.anomaly_detection.cooldown_seconds,
+    ))
+}
+
+/// Duration anomalies followed by abnormally long lock holds in a trace
+fn trace_anomalies(graph: &CausalGraph, trace_id: Uuid) -> Result<Vec<Anomaly>> {
+    let mut anomalies = graph.detect_anomalies(trace_id)?;
+    anomalies.extend(graph.detect_lock_hold_anomalies(trace_id)?);
+    Ok(anomalies)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WarmupPhase {
     Idle,
@@ -627,3 +1286,296 @@ impl WarmupStatus {
         matches!(self.phase, WarmupPhase::Completed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StorageConfig;
+    use crate::event::{EventKind, EventMetadata};
+    use crate::storage::MemoryBackend;
+    use chrono::TimeZone;
+
+    /// A root call followed by a chain of `children` calls alternating threads
+    fn trace_events(trace_id: Uuid, children: usize) -> Vec<Event> {
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut events: Vec<Event> = Vec::new();
+        for i in 0..=children {
+            events.push(Event {
+                id: Uuid::new_v4(),
+                trace_id,
+                parent_id: events.last().map(|parent| parent.id),
+                timestamp: base + chrono::Duration::milliseconds(i as i64),
+                kind: EventKind::FunctionCall {
+                    function_name: format!("step_{}", i),
+                    module: "warmup".to_string(),
+                    args: serde_json::Value::Null,
+                    file: "warmup.rs".to_string(),
+                    line: i as u32,
+                },
+                metadata: EventMetadata {
+                    thread_id: format!("thread-{}", i % 2),
+                    process_id: 1,
+                    service_name: "api".to_string(),
+                    environment: "test".to_string(),
+                    tags: HashMap::new(),
+                    duration_ns: Some(1_000),
+                    instance_id: None,
+                    distributed_span_id: None,
+                    upstream_span_id: None,
+                    monotonic_ns: None,
+                    retry_of: None,
+                    attempt: None,
+                },
+                causality_vector: Vec::new(),
+                lock_set: Vec::new(),
+            });
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn concurrent_warmup_matches_serial_replay() -> Result<()> {
+        let storage: Arc<dyn StorageBackend> =
+            Arc::new(MemoryBackend::new(&StorageConfig::default())?);
+        for i in 0..20 {
+            storage
+                .add_events_batch(trace_events(Uuid::new_v4(), 1 + i % 5))
+                .await?;
+        }
+
+        let serial = AnalysisService::new(Arc::clone(&storage), Config::default()).await?;
+        serial.warmup(1).await?;
+        let concurrent = AnalysisService::new(Arc::clone(&storage), Config::default()).await?;
+        concurrent.warmup(8).await?;
+
+        let status = concurrent.warmup_status().await;
+        assert_eq!(status.phase, WarmupPhase::Completed);
+        assert_eq!(status.total_traces, 20);
+        assert_eq!(status.processed_traces, 20);
+
+        let (serial_stats, concurrent_stats) =
+            (serial.graph_stats().await, concurrent.graph_stats().await);
+        assert_eq!(concurrent_stats.total_traces, 20);
+        assert_eq!(concurrent_stats.total_events, serial_stats.total_events);
+        assert_eq!(concurrent_stats.total_edges, serial_stats.total_edges);
+        assert!(!concurrent_stats.has_cycles);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn distributed_edges_survive_restart() -> Result<()> {
+        let storage: Arc<dyn StorageBackend> =
+            Arc::new(MemoryBackend::new(&StorageConfig::default())?);
+        let mut config = Config::default();
+        config.distributed_tracing.enabled = true;
+
+        // The checkout service calls inventory, which records its own trace
+        let mut upstream = trace_events(Uuid::new_v4(), 0).remove(0);
+        upstream.metadata.service_name = "checkout".to_string();
+        upstream.metadata.distributed_span_id = Some("span-checkout".to_string());
+        let mut downstream = trace_events(Uuid::new_v4(), 0).remove(0);
+        downstream.metadata.service_name = "inventory".to_string();
+        downstream.metadata.distributed_span_id = Some("span-inventory".to_string());
+        downstream.metadata.upstream_span_id = Some("span-checkout".to_string());
+
+        let before = AnalysisService::new(Arc::clone(&storage), config.clone()).await?;
+        before.add_events_batch(vec![downstream.clone()]).await?;
+        before.add_events_batch(vec![upstream.clone()]).await?;
+        drop(before);
+
+        let after = AnalysisService::new(Arc::clone(&storage), config).await?;
+        after.warmup(8).await?;
+
+        let graph = after.graph.read().await;
+        assert!(graph.is_ancestor(upstream.id, downstream.id));
+        assert!(!graph.is_ancestor(downstream.id, upstream.id));
+
+        Ok(())
+    }
+
+    /// `trace_id`'s score as ranked for `sort=health`
+    async fn ranked_score(analysis: &AnalysisService, trace_id: Uuid) -> Result<f64> {
+        let page = analysis.health_page(0, None, 100, None).await?.unwrap();
+        page.traces
+            .into_iter()
+            .find(|(ranked, _)| *ranked == trace_id)
+            .map(|(_, score)| score)
+            .ok_or_else(|| anyhow!("trace {} is not ranked", trace_id))
+    }
+
+    #[tokio::test]
+    async fn trace_health_is_recomputed_after_ingest() -> Result<()> {
+        let storage: Arc<dyn StorageBackend> =
+            Arc::new(MemoryBackend::new(&StorageConfig::default())?);
+        let analysis = AnalysisService::new(storage, Config::default()).await?;
+        let root = trace_events(Uuid::new_v4(), 0).remove(0);
+        let trace_id = root.trace_id;
+        analysis.add_events_batch(vec![root.clone()]).await?;
+
+        let clean = ranked_score(&analysis, trace_id).await?;
+        assert_eq!(ranked_score(&analysis, trace_id).await?, clean);
+
+        // Two threads write the same variable under the root: a write/write race
+        let writes = ["worker-1", "worker-2"].map(|thread| {
+            let mut write = trace_events(trace_id, 0).remove(0);
+            write.parent_id = Some(root.id);
+            write.metadata.thread_id = thread.to_string();
+            write.kind = EventKind::StateChange {
+                variable: "balance".to_string(),
+                old_value: None,
+                new_value: serde_json::json!(thread),
+                location: "balance.rs:12".to_string(),
+                access_type: crate::event::AccessType::Write,
+            };
+            write
+        });
+        analysis.add_events_batch(writes.to_vec()).await?;
+
+        let racy = ranked_score(&analysis, trace_id).await?;
+        assert!(racy < clean);
+        assert_eq!(racy, analysis.get_trace_health(trace_id).await?.score);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn trace_health_is_recomputed_when_a_downstream_trace_links_to_it() -> Result<()> {
+        let mut config = Config::default();
+        config.distributed_tracing.enabled = true;
+        let storage: Arc<dyn StorageBackend> =
+            Arc::new(MemoryBackend::new(&StorageConfig::default())?);
+        let analysis = AnalysisService::new(storage, config).await?;
+
+        let mut upstream = trace_events(Uuid::new_v4(), 0).remove(0);
+        upstream.metadata.distributed_span_id = Some("span-checkout".to_string());
+        upstream.causality_vector = vec![("checkout".to_string(), 1)];
+        analysis.add_events_batch(vec![upstream.clone()]).await?;
+        let clean = ranked_score(&analysis, upstream.trace_id).await?;
+
+        // Another service's trace joins the distributed trace without causality
+        // vectors, lowering the merged trace's coverage
+        let mut downstream = trace_events(Uuid::new_v4(), 2);
+        downstream[0].metadata.distributed_span_id = Some("span-inventory".to_string());
+        downstream[0].metadata.upstream_span_id = Some("span-checkout".to_string());
+        analysis.add_events_batch(downstream).await?;
+
+        assert!(ranked_score(&analysis, upstream.trace_id).await? < clean);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retain_recent_deletes_evicted_traces_from_memory_storage() -> Result<()> {
+        let storage: Arc<dyn StorageBackend> =
+            Arc::new(MemoryBackend::new(&StorageConfig::default())?);
+        let analysis = AnalysisService::new(Arc::clone(&storage), Config::default()).await?;
+
+        let mut trace_ids = Vec::new();
+        for minutes in 0..3 {
+            let mut events = trace_events(Uuid::new_v4(), 1);
+            for event in &mut events {
+                event.timestamp += chrono::Duration::minutes(minutes);
+            }
+            trace_ids.push(events[0].trace_id);
+            analysis.add_events_batch(events).await?;
+        }
+        assert_eq!(storage.count_events().await?, 6);
+
+        assert_eq!(analysis.retain_recent(2).await?, 1);
+
+        assert_eq!(storage.count_events().await?, 4);
+        assert_eq!(storage.count_traces().await?, 2);
+        assert!(storage.get_trace_events(trace_ids[0]).await?.is_empty());
+        assert_eq!(storage.get_trace_events(trace_ids[2]).await?.len(), 2);
+
+        Ok(())
+    }
+
+    /// Log output of the current thread, filtered at `logging.level`
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Analyze a trace whose two workers write `balance` concurrently three
+    /// times over, returning what was logged
+    async fn race_logs(config: Config) -> Result<(String, Vec<Event>)> {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(config.logging.max_level())
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut events = trace_events(Uuid::new_v4(), 0);
+        for (thread, location) in [("worker-1", "balance.rs:12"), ("worker-2", "balance.rs:45")] {
+            let mut write = trace_events(events[0].trace_id, 0).remove(0);
+            write.parent_id = Some(events[0].id);
+            write.metadata.thread_id = thread.to_string();
+            write.kind = EventKind::StateChange {
+                variable: "balance".to_string(),
+                old_value: Some(serde_json::json!(10)),
+                new_value: serde_json::json!(thread),
+                location: location.to_string(),
+                access_type: crate::event::AccessType::Write,
+            };
+            events.push(write);
+        }
+
+        let storage: Arc<dyn StorageBackend> =
+            Arc::new(MemoryBackend::new(&StorageConfig::default())?);
+        let analysis = AnalysisService::new(storage, config).await?;
+        analysis.add_events_batch(events.clone()).await?;
+
+        let trace_id = events[0].trace_id;
+        assert_eq!(analysis.find_concurrent_events(trace_id).await?.len(), 1);
+        assert_eq!(analysis.find_races_with_severity(trace_id).await?.len(), 1);
+        analysis.get_trace_health(trace_id).await?;
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone())?;
+        Ok((logs, events))
+    }
+
+    #[tokio::test]
+    async fn log_on_detect_logs_each_race_once() -> Result<()> {
+        let mut config = Config::default();
+        config.race_detection.log_on_detect = true;
+        let (logs, events) = race_logs(config.clone()).await?;
+
+        let lines: Vec<&str> = logs
+            .lines()
+            .filter(|line| line.contains("Race detected"))
+            .collect();
+        assert_eq!(lines.len(), 1, "{}", logs);
+        assert!(lines[0].contains("WARN"));
+        for field in [
+            format!("trace_id={}", events[0].trace_id),
+            "variable=balance".to_string(),
+            format!("{}", events[1].id),
+            format!("{}", events[2].id),
+            "balance.rs:12".to_string(),
+            "balance.rs:45".to_string(),
+        ] {
+            assert!(lines[0].contains(&field), "missing {}: {}", field, logs);
+        }
+
+        // Below the configured level, or with the flag off, nothing is logged
+        config.logging.level = "error".to_string();
+        assert!(race_logs(config).await?.0.is_empty());
+        assert!(race_logs(Config::default()).await?.0.is_empty());
+
+        Ok(())
+    }
+}
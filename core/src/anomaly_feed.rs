@@ -0,0 +1,260 @@
+//! Anomaly feed: one alert per repeating anomaly instead of one per trace.
+//!
+//! Detection runs per trace, so a slow query on a hot path produces the same
+//! anomaly for every request. The feed groups anomalies by
+//! `(service, event kind, location)` and folds every occurrence whose
+//! timestamp falls within `cooldown` of the group's first occurrence into a
+//! single alert with an occurrence count. An occurrence outside the window
+//! opens a new alert, so a problem that persists is re-reported once per
+//! window.
+//!
+//! Occurrences are keyed by event id, so re-running detection on the same
+//! trace never inflates the count.
+
+use crate::graph::{Anomaly, AnomalySeverity};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use uuid::Uuid;
+
+/// Alerts kept before the oldest are dropped
+const MAX_ALERTS: usize = 1_000;
+
+/// A deduplicated anomaly: every occurrence of one operation within a window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyAlert {
+    pub service_name: String,
+    pub event_kind: String,
+    pub location: String,
+    /// Worst severity seen in the window
+    pub severity: AnomalySeverity,
+    pub occurrence_count: usize,
+    pub trace_count: usize,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// The first occurrence, for its description and durations
+    pub sample: Anomaly,
+}
+
+struct FeedEntry {
+    alert: AnomalyAlert,
+    event_ids: HashSet<String>,
+    trace_ids: HashSet<Uuid>,
+}
+
+impl FeedEntry {
+    fn matches(&self, anomaly: &Anomaly) -> bool {
+        self.alert.service_name == anomaly.service_name
+            && self.alert.event_kind == anomaly.event_kind
+            && self.alert.location == anomaly.location
+    }
+}
+
+pub struct AnomalyFeed {
+    cooldown: Duration,
+    entries: VecDeque<FeedEntry>,
+}
+
+impl AnomalyFeed {
+    /// A feed that folds repeats within `cooldown`; a zero cooldown reports
+    /// every occurrence as its own alert
+    pub fn new(cooldown: std::time::Duration) -> Self {
+        Self {
+            cooldown: Duration::from_std(cooldown).unwrap_or(Duration::MAX),
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn cooldown(&self) -> std::time::Duration {
+        self.cooldown.to_std().unwrap_or_default()
+    }
+
+    /// Fold the anomalies detected in one trace into the feed
+    pub fn record(&mut self, trace_id: Uuid, anomalies: &[Anomaly]) {
+        for anomaly in anomalies {
+            let seen = self
+                .entries
+                .iter()
+                .any(|entry| entry.matches(anomaly) && entry.event_ids.contains(&anomaly.event_id));
+            if seen {
+                continue;
+            }
+
+            let cooldown = self.cooldown;
+            let window = self.entries.iter_mut().rev().find(|entry| {
+                entry.matches(anomaly)
+                    && (anomaly.timestamp - entry.alert.first_seen).abs() < cooldown
+            });
+
+            match window {
+                Some(entry) => {
+                    entry.event_ids.insert(anomaly.event_id.clone());
+                    entry.trace_ids.insert(trace_id);
+                    let alert = &mut entry.alert;
+                    alert.occurrence_count += 1;
+                    alert.trace_count = entry.trace_ids.len();
+                    alert.severity = alert.severity.max(anomaly.severity);
+                    alert.first_seen = alert.first_seen.min(anomaly.timestamp);
+                    alert.last_seen = alert.last_seen.max(anomaly.timestamp);
+                }
+                None => {
+                    if self.entries.len() >= MAX_ALERTS {
+                        self.entries.pop_front();
+                    }
+                    self.entries.push_back(FeedEntry {
+                        alert: AnomalyAlert {
+                            service_name: anomaly.service_name.clone(),
+                            event_kind: anomaly.event_kind.clone(),
+                            location: anomaly.location.clone(),
+                            severity: anomaly.severity,
+                            occurrence_count: 1,
+                            trace_count: 1,
+                            first_seen: anomaly.timestamp,
+                            last_seen: anomaly.timestamp,
+                            sample: anomaly.clone(),
+                        },
+                        event_ids: HashSet::from([anomaly.event_id.clone()]),
+                        trace_ids: HashSet::from([trace_id]),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Alerts, most recently opened first
+    pub fn alerts(&self) -> Vec<AnomalyAlert> {
+        self.entries
+            .iter()
+            .rev()
+            .map(|entry| entry.alert.clone())
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, EventKind, EventMetadata};
+    use crate::graph::CausalGraph;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn query_trace(graph: &CausalGraph, timestamp: DateTime<Utc>, duration_ms: u64) -> Uuid {
+        let trace_id = Uuid::new_v4();
+        graph
+            .add_event(Event {
+                id: Uuid::new_v4(),
+                trace_id,
+                parent_id: None,
+                timestamp,
+                kind: EventKind::DatabaseQuery {
+                    query: "SELECT * FROM orders WHERE id = $1".into(),
+                    database: "orders".into(),
+                    duration_ms,
+                },
+                metadata: EventMetadata {
+                    thread_id: "main".into(),
+                    process_id: 1,
+                    service_name: "checkout".into(),
+                    environment: "test".into(),
+                    tags: HashMap::new(),
+                    duration_ns: Some(duration_ms * 1_000_000),
+                    instance_id: None,
+                    distributed_span_id: None,
+                    upstream_span_id: None,
+                    monotonic_ns: None,
+                    retry_of: None,
+                    attempt: None,
+                },
+                causality_vector: Vec::new(),
+                lock_set: Vec::new(),
+            })
+            .unwrap();
+        trace_id
+    }
+
+    fn anomaly_at(timestamp: DateTime<Utc>) -> Anomaly {
+        Anomaly {
+            event_id: Uuid::new_v4().to_string(),
+            event_kind: "DatabaseQuery".into(),
+            severity: AnomalySeverity::Warning,
+            actual_duration_ms: 100.0,
+            expected_duration_ms: 10.0,
+            std_dev_from_mean: 4.0,
+            description: "DatabaseQuery took 100.00ms".into(),
+            location: "orders".into(),
+            timestamp,
+            service_name: "checkout".into(),
+        }
+    }
+
+    #[test]
+    fn test_repeating_anomaly_is_reported_once_with_count() {
+        let graph = CausalGraph::new();
+        let mut feed = AnomalyFeed::new(std::time::Duration::from_secs(300));
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        // Build a ~11ms baseline for the query
+        for i in 0..20 {
+            let trace_id =
+                query_trace(&graph, base + Duration::seconds(i), 10 + (i as u64 % 2) * 2);
+            feed.record(trace_id, &graph.detect_anomalies(trace_id).unwrap());
+        }
+        assert!(feed.alerts().is_empty());
+
+        // The same query turns slow in every request for a minute
+        let mut detected = 0;
+        for i in 0..6 {
+            let trace_id = query_trace(&graph, base + Duration::seconds(60 + i * 10), 100);
+            let anomalies = graph.detect_anomalies(trace_id).unwrap();
+            assert_eq!(anomalies.len(), 1, "slow trace {} not flagged", i);
+            detected += anomalies.len();
+            feed.record(trace_id, &anomalies);
+        }
+
+        let alerts = feed.alerts();
+        assert_eq!(alerts.len(), 1);
+        let alert = &alerts[0];
+        assert_eq!(alert.service_name, "checkout");
+        assert_eq!(alert.event_kind, "DatabaseQuery");
+        assert_eq!(alert.location, "orders");
+        assert_eq!(alert.occurrence_count, detected);
+        assert_eq!(alert.trace_count, 6);
+        assert_eq!(alert.first_seen, base + Duration::seconds(60));
+        assert_eq!(alert.last_seen, base + Duration::seconds(110));
+    }
+
+    #[test]
+    fn test_recording_same_trace_twice_does_not_inflate_count() {
+        let mut feed = AnomalyFeed::new(std::time::Duration::from_secs(300));
+        let trace_id = Uuid::new_v4();
+        let anomalies = vec![anomaly_at(Utc::now())];
+
+        feed.record(trace_id, &anomalies);
+        feed.record(trace_id, &anomalies);
+
+        let alerts = feed.alerts();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].occurrence_count, 1);
+    }
+
+    #[test]
+    fn test_occurrence_after_cooldown_opens_new_alert() {
+        let mut feed = AnomalyFeed::new(std::time::Duration::from_secs(10));
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        feed.record(Uuid::new_v4(), &[anomaly_at(base)]);
+        feed.record(Uuid::new_v4(), &[anomaly_at(base + Duration::seconds(5))]);
+        feed.record(Uuid::new_v4(), &[anomaly_at(base + Duration::seconds(30))]);
+
+        let alerts = feed.alerts();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].occurrence_count, 1);
+        assert_eq!(alerts[0].first_seen, base + Duration::seconds(30));
+        assert_eq!(alerts[1].occurrence_count, 2);
+    }
+}
@@ -0,0 +1,74 @@
+//! Trace bundles: everything needed to reproduce a trace's analysis offline.
+//!
+//! A bundle carries the trace's events exactly as they were ingested (before
+//! the graph assigned clocks and lock sets), the distributed spans and edges
+//! that merge it with other services' traces, and the baselines for its
+//! operations. Importing a bundle into a fresh server replays the same inputs
+//! through the same graph, so races and critical path come out identical.
+//! The exporter's own results travel along as annotations so an import can
+//! check that it reproduced them.
+
+use crate::event::{DistributedEdge, DistributedSpan, Event};
+use crate::graph::CriticalPath;
+use crate::storage::DurationStats;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Bumped whenever a field is added that an older importer would drop
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceBundle {
+    pub format_version: u32,
+    pub trace_id: Uuid,
+    pub exported_at: DateTime<Utc>,
+    /// Events of the trace and every trace merged into it, as ingested
+    pub events: Vec<Event>,
+    pub distributed_spans: Vec<DistributedSpan>,
+    pub distributed_edges: Vec<DistributedEdge>,
+    /// Baselines for the operations in the trace, keyed as on the exporting server
+    pub baselines: HashMap<String, DurationStats>,
+    pub annotations: BundleAnnotations,
+}
+
+/// Analysis results on the exporting server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BundleAnnotations {
+    /// Racing event pairs, each ordered and the list sorted
+    pub races: Vec<(Uuid, Uuid)>,
+    /// Event ids along the critical path, in order
+    pub critical_path: Vec<Uuid>,
+    pub critical_path_duration_ms: Option<f64>,
+}
+
+impl BundleAnnotations {
+    pub fn from_results(races: &[(Event, Event)], critical_path: Option<&CriticalPath>) -> Self {
+        let mut races: Vec<(Uuid, Uuid)> = races
+            .iter()
+            .map(|(a, b)| (a.id.min(b.id), a.id.max(b.id)))
+            .collect();
+        races.sort();
+        races.dedup();
+
+        Self {
+            races,
+            critical_path: critical_path
+                .map(|cp| cp.path.iter().map(|event| event.id).collect())
+                .unwrap_or_default(),
+            critical_path_duration_ms: critical_path.map(|cp| cp.total_duration_ms),
+        }
+    }
+}
+
+/// What importing a bundle loaded, and whether the analysis matched the export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleImport {
+    pub trace_id: Uuid,
+    pub events_imported: usize,
+    pub spans_imported: usize,
+    pub edges_imported: usize,
+    pub baselines_imported: usize,
+    pub reproduced: bool,
+}
@@ -1,24 +1,160 @@
-use crate::event::Event;
-use anyhow::Result;
-use crossbeam::channel::{bounded, Receiver, Sender};
+use crate::config::CaptureConfig;
+use crate::event::{Event, EventKind};
+use anyhow::{anyhow, Result};
+use crossbeam::channel::{bounded, Receiver, SendTimeoutError, Sender, TrySendError};
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Captured events buffered per subscriber before a slow one starts lagging
+const SUBSCRIBER_BUFFER: usize = 1024;
+
+/// The capture buffer was full, so the event was rejected rather than queued.
+/// Callers should retry later instead of treating the event as lost.
+#[derive(Debug, thiserror::Error)]
+#[error("Capture buffer is full ({capacity} events); retry later")]
+pub struct Backpressure {
+    pub capacity: usize,
+}
 
 /// High-performance event capture system with lock-free queues
 pub struct EventCapture {
     sender: Sender<Event>,
     receiver: Receiver<Event>,
+    capacity: usize,
+    /// Events rejected because the buffer was full
+    dropped: AtomicU64,
+    limits: CaptureConfig,
+    /// Events accepted per trace, tracked only with `max_events_per_trace`
+    trace_event_counts: DashMap<Uuid, usize>,
+    /// Events rejected per trace after it hit `max_events_per_trace`
+    truncated_traces: DashMap<Uuid, usize>,
+    /// Fan-out of every accepted event to live subscribers
+    subscribers: broadcast::Sender<Event>,
 }
 
 impl EventCapture {
     pub fn new(buffer_size: usize) -> Self {
         let (sender, receiver) = bounded(buffer_size);
-        Self { sender, receiver }
+        let (subscribers, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        Self {
+            sender,
+            receiver,
+            capacity: buffer_size,
+            dropped: AtomicU64::new(0),
+            limits: CaptureConfig::default(),
+            trace_event_counts: DashMap::new(),
+            truncated_traces: DashMap::new(),
+            subscribers,
+        }
     }
 
-    /// Capture an event (non-blocking)
+    /// Drop or truncate HTTP bodies and database queries as events are captured,
+    /// and cap the number of events accepted per trace
+    pub fn with_limits(mut self, limits: CaptureConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Capture an event (non-blocking). Fails with [`Backpressure`] when the
+    /// buffer is full.
     pub fn capture(&self, event: Event) -> Result<()> {
-        self.sender
-            .try_send(event)
-            .map_err(|e| anyhow::anyhow!("Failed to capture event: {}", e))
+        self.capture_inner(event, None)
+    }
+
+    /// Capture an event, waiting up to `timeout` for room in a full buffer
+    /// before failing with [`Backpressure`]. Blocks the calling thread.
+    pub fn capture_with_timeout(&self, event: Event, timeout: Duration) -> Result<()> {
+        self.capture_inner(event, Some(timeout))
+    }
+
+    fn capture_inner(&self, mut event: Event, timeout: Option<Duration>) -> Result<()> {
+        apply_capture_limits(&mut event, &self.limits);
+
+        let Some(max_events) = self.limits.max_events_per_trace else {
+            return self.send(event, timeout);
+        };
+
+        // Reserve the event's slot under the entry so concurrent captures for the
+        // trace can't overshoot, but let go of it before a send that may block
+        let trace_id = event.trace_id;
+        {
+            let mut accepted = self.trace_event_counts.entry(trace_id).or_insert(0);
+            if *accepted >= max_events {
+                drop(accepted);
+                *self.truncated_traces.entry(trace_id).or_insert(0) += 1;
+                return Err(anyhow!(
+                    "Trace {} is truncated: it reached capture.max_events_per_trace ({})",
+                    trace_id,
+                    max_events
+                ));
+            }
+            *accepted += 1;
+        }
+
+        let sent = self.send(event, timeout);
+        if sent.is_err() {
+            // Not accepted (e.g. `Backpressure`), so give the slot back
+            if let Some(mut accepted) = self.trace_event_counts.get_mut(&trace_id) {
+                *accepted = accepted.saturating_sub(1);
+            }
+        }
+        sent
+    }
+
+    fn send(&self, event: Event, timeout: Option<Duration>) -> Result<()> {
+        // Only pay for the clone when someone is listening
+        let published = (self.subscribers.receiver_count() > 0).then(|| event.clone());
+        let full = match timeout {
+            None => match self.sender.try_send(event) {
+                Ok(()) => false,
+                Err(TrySendError::Full(_)) => true,
+                Err(e) => return Err(anyhow!("Failed to capture event: {}", e)),
+            },
+            Some(timeout) => match self.sender.send_timeout(event, timeout) {
+                Ok(()) => false,
+                Err(SendTimeoutError::Timeout(_)) => true,
+                Err(e) => return Err(anyhow!("Failed to capture event: {}", e)),
+            },
+        };
+        if full {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return Err(Backpressure {
+                capacity: self.capacity,
+            }
+            .into());
+        }
+        if let Some(event) = published {
+            // Subscribers may have gone away since the check
+            let _ = self.subscribers.send(event);
+        }
+        Ok(())
+    }
+
+    /// Receive every event accepted from now on, across all traces. A
+    /// subscriber that falls more than 1024 events behind skips the oldest.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.subscribers.subscribe()
+    }
+
+    /// Whether the trace hit `max_events_per_trace` and had events rejected
+    pub fn is_truncated(&self, trace_id: Uuid) -> bool {
+        self.truncated_traces.contains_key(&trace_id)
+    }
+
+    /// Events rejected for a trace after it reached `max_events_per_trace`
+    pub fn rejected_events(&self, trace_id: Uuid) -> usize {
+        self.truncated_traces
+            .get(&trace_id)
+            .map(|rejected| *rejected)
+            .unwrap_or(0)
+    }
+
+    /// Events rejected with [`Backpressure`] since startup
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
     }
 
     /// Get a sender handle for multi-threaded capture
@@ -41,6 +177,56 @@ impl EventCapture {
     }
 }
 
+/// Apply `limits` to the payloads an event carries
+pub fn apply_capture_limits(event: &mut Event, limits: &CaptureConfig) {
+    match &mut event.kind {
+        EventKind::HttpRequest { body, .. } | EventKind::HttpResponse { body, .. } => {
+            if !limits.capture_bodies {
+                *body = None;
+            } else if let (Some(value), Some(max_bytes)) = (body.as_mut(), limits.max_body_bytes) {
+                truncate_body(value, max_bytes);
+            }
+        }
+        EventKind::DatabaseQuery { query, .. } => {
+            if let Some(max_bytes) = limits.max_body_bytes {
+                truncate_text(query, max_bytes);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Truncate a body to `max_bytes` of its text. Non-string bodies are measured
+/// by their serialized JSON and become a truncated string when over the limit.
+fn truncate_body(body: &mut serde_json::Value, max_bytes: usize) {
+    if let serde_json::Value::String(text) = body {
+        truncate_text(text, max_bytes);
+        return;
+    }
+
+    let mut text = body.to_string();
+    if text.len() > max_bytes {
+        truncate_text(&mut text, max_bytes);
+        *body = serde_json::Value::String(text);
+    }
+}
+
+/// Keep at most `max_bytes` of `text` (backing off to a char boundary) and
+/// append a marker with the number of bytes dropped
+fn truncate_text(text: &mut String, max_bytes: usize) {
+    if text.len() <= max_bytes {
+        return;
+    }
+
+    let mut cut = max_bytes;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let dropped = text.len() - cut;
+    text.truncate(cut);
+    text.push_str(&format!("...[truncated {} bytes]", dropped));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,6 +235,123 @@ mod tests {
     use std::collections::HashMap;
     use uuid::Uuid;
 
+    fn custom_event(trace_id: Uuid) -> Event {
+        Event::new(
+            EventKind::Custom {
+                name: "recurse".to_string(),
+                data: serde_json::json!({}),
+            },
+            EventMetadata {
+                thread_id: "main".to_string(),
+                process_id: 1234,
+                service_name: "test".to_string(),
+                environment: "dev".to_string(),
+                tags: HashMap::new(),
+                duration_ns: None,
+                instance_id: None,
+                distributed_span_id: None,
+                upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
+            },
+            trace_id,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_events_past_trace_cap_are_rejected() {
+        let capture = EventCapture::new(1000).with_limits(CaptureConfig {
+            max_events_per_trace: Some(3),
+            ..CaptureConfig::default()
+        });
+        let runaway = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        for _ in 0..3 {
+            assert!(capture.capture(custom_event(runaway)).is_ok());
+        }
+        assert!(!capture.is_truncated(runaway));
+
+        assert!(capture.capture(custom_event(runaway)).is_err());
+        assert!(capture.capture(custom_event(runaway)).is_err());
+        assert!(capture.capture(custom_event(other)).is_ok());
+
+        assert!(capture.is_truncated(runaway));
+        assert_eq!(capture.rejected_events(runaway), 2);
+        assert!(!capture.is_truncated(other));
+        assert_eq!(capture.drain().len(), 4);
+    }
+
+    #[test]
+    fn test_full_buffer_reports_backpressure() {
+        let capture = EventCapture::new(2);
+        let trace_id = Uuid::new_v4();
+        capture.capture(custom_event(trace_id)).unwrap();
+        capture.capture(custom_event(trace_id)).unwrap();
+
+        let err = capture.capture(custom_event(trace_id)).unwrap_err();
+        assert_eq!(err.downcast_ref::<Backpressure>().unwrap().capacity, 2);
+        let err = capture
+            .capture_with_timeout(custom_event(trace_id), Duration::from_millis(10))
+            .unwrap_err();
+        assert!(err.is::<Backpressure>());
+        assert_eq!(capture.dropped_events(), 2);
+
+        // Nothing already buffered was lost, and there is room again once drained
+        assert_eq!(capture.drain().len(), 2);
+        capture
+            .capture_with_timeout(custom_event(trace_id), Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(capture.dropped_events(), 2);
+    }
+
+    #[test]
+    fn test_blocked_send_releases_the_trace_while_waiting() {
+        let capture = EventCapture::new(1).with_limits(CaptureConfig {
+            max_events_per_trace: Some(2),
+            ..CaptureConfig::default()
+        });
+        let trace_id = Uuid::new_v4();
+        capture.capture(custom_event(trace_id)).unwrap();
+
+        std::thread::scope(|scope| {
+            let blocked = scope.spawn(|| {
+                capture.capture_with_timeout(custom_event(trace_id), Duration::from_secs(1))
+            });
+            std::thread::sleep(Duration::from_millis(50));
+
+            // The blocked send holds the trace's last slot, not its entry
+            let started = std::time::Instant::now();
+            assert!(capture.capture(custom_event(trace_id)).is_err());
+            assert!(started.elapsed() < Duration::from_millis(500));
+            assert_eq!(capture.rejected_events(trace_id), 1);
+
+            assert!(blocked.join().unwrap().unwrap_err().is::<Backpressure>());
+        });
+
+        // The timed-out event gave its slot back
+        assert_eq!(capture.drain().len(), 1);
+        capture.capture(custom_event(trace_id)).unwrap();
+    }
+
+    #[test]
+    fn test_subscribers_receive_events_captured_after_subscribing() {
+        let capture = EventCapture::new(1000);
+        let trace_id = Uuid::new_v4();
+        capture.capture(custom_event(trace_id)).unwrap();
+
+        let mut subscriber = capture.subscribe();
+        let event = custom_event(trace_id);
+        let event_id = event.id;
+        capture.capture(event).unwrap();
+
+        assert_eq!(subscriber.try_recv().unwrap().id, event_id);
+        assert!(subscriber.try_recv().is_err());
+        assert_eq!(capture.drain().len(), 2);
+    }
+
     #[test]
     fn test_event_capture() {
         let capture = EventCapture::new(1000);
@@ -64,6 +367,9 @@ mod tests {
             instance_id: None,
             distributed_span_id: None,
             upstream_span_id: None,
+            monotonic_ns: None,
+            retry_of: None,
+            attempt: None,
         };
 
         let event = Event::new(
@@ -80,4 +386,80 @@ mod tests {
         let events = capture.drain();
         assert_eq!(events.len(), 1);
     }
+
+    fn http_response(body: serde_json::Value) -> Event {
+        let metadata = EventMetadata {
+            thread_id: "main".to_string(),
+            process_id: 1234,
+            service_name: "test".to_string(),
+            environment: "dev".to_string(),
+            tags: HashMap::new(),
+            duration_ns: None,
+            instance_id: None,
+            distributed_span_id: None,
+            upstream_span_id: None,
+            monotonic_ns: None,
+            retry_of: None,
+            attempt: None,
+        };
+
+        Event::new(
+            EventKind::HttpResponse {
+                status: 200,
+                headers: HashMap::new(),
+                body: Some(body),
+                duration_ms: 5,
+            },
+            metadata,
+            Uuid::new_v4(),
+            None,
+        )
+    }
+
+    fn captured_body(capture: &EventCapture) -> Option<serde_json::Value> {
+        match capture.drain().pop().unwrap().kind {
+            EventKind::HttpResponse { body, .. } => body,
+            _ => panic!("expected an HttpResponse"),
+        }
+    }
+
+    #[test]
+    fn test_body_truncated_at_limit() {
+        let capture = EventCapture::new(10).with_limits(CaptureConfig {
+            capture_bodies: true,
+            max_body_bytes: Some(10),
+            max_events_per_trace: None,
+        });
+
+        // Exactly at the limit is kept whole
+        capture
+            .capture(http_response(serde_json::json!("0123456789")))
+            .unwrap();
+        assert_eq!(
+            captured_body(&capture),
+            Some(serde_json::json!("0123456789"))
+        );
+
+        capture
+            .capture(http_response(serde_json::json!("0123456789abcdef")))
+            .unwrap();
+        assert_eq!(
+            captured_body(&capture),
+            Some(serde_json::json!("0123456789...[truncated 6 bytes]"))
+        );
+    }
+
+    #[test]
+    fn test_disabled_body_capture_omits_body() {
+        let capture = EventCapture::new(10).with_limits(CaptureConfig {
+            capture_bodies: false,
+            max_body_bytes: None,
+            max_events_per_trace: None,
+        });
+
+        capture
+            .capture(http_response(serde_json::json!({"card": "4111"})))
+            .unwrap();
+        assert_eq!(captured_body(&capture), None);
+    }
 }
@@ -1,6 +1,8 @@
+use crate::event::AccessType;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 /// Main configuration structure for Raceway.
@@ -16,6 +18,12 @@ pub struct Config {
     #[serde(default)]
     pub engine: EngineConfig,
 
+    #[serde(default)]
+    pub warmup: WarmupConfig,
+
+    #[serde(default)]
+    pub capture: CaptureConfig,
+
     #[serde(default)]
     pub race_detection: RaceDetectionConfig,
 
@@ -25,6 +33,15 @@ pub struct Config {
     #[serde(default)]
     pub distributed_tracing: DistributedTracingConfig,
 
+    #[serde(default)]
+    pub environments: EnvironmentConfig,
+
+    /// Service -> group mappings per grouping dimension, used by `?group_by=<dimension>`.
+    /// `[service_groups.team]` with `checkout = "payments"` puts `checkout` in the
+    /// `payments` team. Unmapped services fall back to their `<dimension>` metadata tag.
+    #[serde(default)]
+    pub service_groups: HashMap<String, HashMap<String, String>>,
+
     #[serde(default)]
     pub logging: LoggingConfig,
 
@@ -67,23 +84,61 @@ impl Config {
     /// Validate the configuration for obvious misconfiguration.
     pub fn validate(&self) -> Result<()> {
         match self.storage.backend.as_str() {
-            "memory" | "postgres" | "supabase" => {}
+            "memory" | "postgres" | "supabase" | "mysql" | "redis" => {}
             other => anyhow::bail!("Invalid storage backend: {}", other),
         }
 
+        if self.storage.backend == "redis" && self.storage.redis.url.is_none() {
+            anyhow::bail!("Redis backend requires url");
+        }
+
         if matches!(self.storage.backend.as_str(), "postgres" | "supabase")
             && self.storage.postgres.connection_string.is_none() {
                 anyhow::bail!("PostgreSQL/Supabase backend requires connection_string");
             }
 
+        if self.storage.backend == "mysql" && self.storage.mysql.connection_string.is_none() {
+            anyhow::bail!("MySQL backend requires connection_string");
+        }
+
         if self.server.port == 0 {
             anyhow::bail!("Server port cannot be 0");
         }
 
+        if self.engine.buffer_size == 0 {
+            anyhow::bail!("engine.buffer_size must be greater than 0");
+        }
+
+        if self.warmup.concurrency == 0 {
+            anyhow::bail!("warmup.concurrency must be greater than 0");
+        }
+
         if self.server.rate_limit_enabled && self.server.rate_limit_rpm == 0 {
             anyhow::bail!("rate_limit_rpm must be greater than 0 when rate limiting is enabled");
         }
 
+        match self.anomaly_detection.method.as_str() {
+            "sigma" => {}
+            "percentile" if self.anomaly_detection.percentile_multiplier > 0.0 => {}
+            "percentile" => anyhow::bail!("percentile_multiplier must be greater than 0"),
+            other => anyhow::bail!("Invalid anomaly detection method: {}", other),
+        }
+
+        if self.anomaly_detection.min_baseline_samples == 0 {
+            anyhow::bail!("anomaly_detection.min_baseline_samples must be greater than 0");
+        }
+        if let Some(kind) = self
+            .anomaly_detection
+            .min_baseline_samples_by_kind
+            .iter()
+            .find_map(|(kind, &samples)| (samples == 0).then_some(kind))
+        {
+            anyhow::bail!(
+                "anomaly_detection.min_baseline_samples_by_kind.\"{}\" must be greater than 0",
+                kind
+            );
+        }
+
         match self.logging.level.to_lowercase().as_str() {
             "trace" | "debug" | "info" | "warn" | "error" => {}
             other => anyhow::bail!("Invalid log level: {}", other),
@@ -136,6 +191,10 @@ pub struct ServerConfig {
     /// API endpoints are not affected - they use api_keys for authentication.
     #[serde(default)]
     pub ui_password: Option<String>,
+
+    /// Serve `/metrics` without an API key so a Prometheus scraper needs no credentials
+    #[serde(default)]
+    pub metrics_public: bool,
 }
 
 impl Default for ServerConfig {
@@ -151,6 +210,7 @@ impl Default for ServerConfig {
             auth_enabled: false,
             api_keys: Vec::new(),
             ui_password: None,
+            metrics_public: false,
         }
     }
 }
@@ -163,6 +223,26 @@ pub struct StorageConfig {
 
     #[serde(default)]
     pub postgres: PostgresConfig,
+
+    #[serde(default)]
+    pub mysql: MySqlConfig,
+
+    #[serde(default)]
+    pub redis: RedisConfig,
+
+    /// Run `StorageBackend::maintenance()` (vacuum/compaction) every N hours
+    /// while the server is up. Disabled when unset.
+    #[serde(default)]
+    pub maintenance_interval_hours: Option<u64>,
+
+    /// `/health` reports the backend unhealthy (503) when a ping takes longer
+    #[serde(default = "default_health_latency_threshold_ms")]
+    pub health_latency_threshold_ms: u64,
+
+    /// With the memory backend, load this snapshot file on start (if it
+    /// exists) and write everything back to it on graceful shutdown
+    #[serde(default)]
+    pub memory_snapshot_path: Option<PathBuf>,
 }
 
 impl Default for StorageConfig {
@@ -170,6 +250,11 @@ impl Default for StorageConfig {
         Self {
             backend: default_storage_backend(),
             postgres: PostgresConfig::default(),
+            mysql: MySqlConfig::default(),
+            redis: RedisConfig::default(),
+            maintenance_interval_hours: None,
+            health_latency_threshold_ms: default_health_latency_threshold_ms(),
+            memory_snapshot_path: None,
         }
     }
 }
@@ -204,9 +289,60 @@ impl Default for PostgresConfig {
     }
 }
 
+/// MySQL settings (requires the `mysql` feature).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MySqlConfig {
+    pub connection_string: Option<String>,
+
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+
+    #[serde(default = "default_min_connections")]
+    pub min_connections: u32,
+
+    #[serde(default = "default_connection_timeout")]
+    pub connection_timeout_seconds: u32,
+
+    #[serde(default = "default_true")]
+    pub auto_migrate: bool,
+}
+
+impl Default for MySqlConfig {
+    fn default() -> Self {
+        Self {
+            connection_string: None,
+            max_connections: default_max_connections(),
+            min_connections: default_min_connections(),
+            connection_timeout_seconds: default_connection_timeout(),
+            auto_migrate: true,
+        }
+    }
+}
+
+/// Redis settings (requires the `redis` feature).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RedisConfig {
+    pub url: Option<String>,
+
+    /// Namespace for every key, so several deployments can share one Redis
+    #[serde(default = "default_redis_key_prefix")]
+    pub key_prefix: String,
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            key_prefix: default_redis_key_prefix(),
+        }
+    }
+}
+
 /// Engine tuning configuration.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct EngineConfig {
+    /// Events held in the capture buffer awaiting processing; captures beyond
+    /// this are rejected with backpressure (HTTP 429) rather than dropped
     #[serde(default = "default_buffer_size")]
     pub buffer_size: usize,
 
@@ -215,6 +351,24 @@ pub struct EngineConfig {
 
     #[serde(default = "default_flush_interval")]
     pub flush_interval_ms: u64,
+
+    /// Most events held back waiting for a parent that hasn't arrived yet
+    #[serde(default = "default_orphan_buffer_size")]
+    pub orphan_buffer_size: usize,
+
+    /// How long an event waits for its parent before it is added as a root
+    #[serde(default = "default_orphan_timeout")]
+    pub orphan_timeout_ms: u64,
+
+    /// Most traces kept in the in-memory causal graph; the least recently
+    /// active are evicted and reloaded from storage when next queried.
+    /// Unset keeps every trace.
+    #[serde(default)]
+    pub max_graph_traces: Option<usize>,
+
+    /// How often the engine enforces `max_graph_traces`
+    #[serde(default = "default_retention_interval")]
+    pub retention_interval_ms: u64,
 }
 
 impl Default for EngineConfig {
@@ -223,6 +377,61 @@ impl Default for EngineConfig {
             buffer_size: default_buffer_size(),
             batch_size: default_batch_size(),
             flush_interval_ms: default_flush_interval(),
+            orphan_buffer_size: default_orphan_buffer_size(),
+            orphan_timeout_ms: default_orphan_timeout(),
+            max_graph_traces: None,
+            retention_interval_ms: default_retention_interval(),
+        }
+    }
+}
+
+/// Startup replay of stored traces into the causal graph.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WarmupConfig {
+    /// Replay every stored trace when the server starts instead of loading
+    /// each one on first query. `/readyz` is unavailable until it finishes.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Traces fetched from storage and replayed at the same time
+    #[serde(default = "default_warmup_concurrency")]
+    pub concurrency: usize,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            concurrency: default_warmup_concurrency(),
+        }
+    }
+}
+
+/// Limits on the payloads kept from captured HTTP and database events,
+/// applied at ingest before anything is stored.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CaptureConfig {
+    /// Keep HTTP request/response bodies; when false they are dropped entirely
+    #[serde(default = "default_true")]
+    pub capture_bodies: bool,
+
+    /// Truncate HTTP bodies and database queries longer than this many bytes,
+    /// appending a `...[truncated N bytes]` marker. Unset keeps them whole.
+    #[serde(default)]
+    pub max_body_bytes: Option<usize>,
+
+    /// Stop accepting events for a trace once it has this many, and mark it
+    /// truncated. Guards against runaway recursion or fan-out. Unset is unlimited.
+    #[serde(default)]
+    pub max_events_per_trace: Option<usize>,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            capture_bodies: true,
+            max_body_bytes: None,
+            max_events_per_trace: None,
         }
     }
 }
@@ -232,24 +441,255 @@ impl Default for EngineConfig {
 pub struct RaceDetectionConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
+
+    /// Access pairs on specific variables that are intentionally racy and
+    /// should not be reported. The variables are still tracked.
+    #[serde(default)]
+    pub safe_patterns: Vec<SafePattern>,
+
+    /// Groups of lock ids that act as one logical lock for some variables,
+    /// e.g. striped locks `balance-lock-0` .. `balance-lock-15`
+    #[serde(default)]
+    pub lock_families: Vec<LockFamily>,
+
+    /// Also require the two accesses' `[timestamp, timestamp + duration]`
+    /// intervals to overlap (or be within `max_gap_ms`) before flagging a race.
+    /// Reduces false positives when timestamps are coarse and vector clocks empty.
+    #[serde(default = "default_false")]
+    pub require_temporal_overlap: bool,
+
+    /// Largest gap between two access intervals still treated as overlapping
+    #[serde(default)]
+    pub max_gap_ms: u64,
+
+    /// Most components kept in an event's causality vector (0 = unbounded).
+    /// Beyond the cap the smallest (oldest) clocks are pruned, which can only
+    /// hide happens-before relations, never invent them.
+    #[serde(default = "default_max_clock_components")]
+    pub max_clock_components: usize,
+
+    /// Most concurrent pairs collected per trace (0 = unbounded). Pairs are
+    /// kept in timestamp order and analysis is marked `truncated` past the cap,
+    /// bounding the cost of traces that touch one variable thousands of times.
+    #[serde(default)]
+    pub max_pairs_per_trace: usize,
+
+    /// Which access-type pairs are considered safe before any happens-before
+    /// or lock analysis runs
+    #[serde(default)]
+    pub policy: RaceDetectionPolicy,
+
+    /// Log a structured warning the first time analysis finds each race in a
+    /// trace, for log-based alerting
+    #[serde(default = "default_false")]
+    pub log_on_detect: bool,
 }
 
 impl Default for RaceDetectionConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            safe_patterns: Vec::new(),
+            lock_families: Vec::new(),
+            require_temporal_overlap: false,
+            max_gap_ms: 0,
+            max_clock_components: default_max_clock_components(),
+            max_pairs_per_trace: 0,
+            policy: RaceDetectionPolicy::default(),
+            log_on_detect: false,
+        }
     }
 }
 
+/// Access-type pairs on the same variable that never count as a race.
+///
+/// Pairs are unordered. The defaults follow the C++11/Rust memory model:
+/// concurrent reads are safe and atomics never data-race with each other, while
+/// pairs involving a plain write, or mixing plain and atomic accesses, are
+/// checked. Turning an atomic toggle off also flags atomic pairs, for catching
+/// logical races such as check-then-act on an atomic.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RaceDetectionPolicy {
+    /// Two plain `Read`s
+    #[serde(default = "default_true")]
+    pub read_read_safe: bool,
+
+    /// A plain `Read` against a plain `Write`, for reads known to be safe by
+    /// other means (e.g. lock-protected where no lock event is recorded)
+    #[serde(default)]
+    pub read_write_safe: bool,
+
+    /// Two `AtomicRead`s
+    #[serde(default = "default_true")]
+    pub atomic_read_read_safe: bool,
+
+    /// Two `AtomicRMW`s (e.g. concurrent `fetch_add`s on a counter)
+    #[serde(default = "default_true")]
+    pub atomic_rmw_rmw_safe: bool,
+
+    /// An `AtomicRead` against an `AtomicWrite` or `AtomicRMW`
+    #[serde(default = "default_true")]
+    pub atomic_read_write_safe: bool,
+
+    /// An `AtomicWrite` against an `AtomicWrite` or `AtomicRMW`
+    #[serde(default = "default_true")]
+    pub atomic_write_write_safe: bool,
+}
+
+impl Default for RaceDetectionPolicy {
+    fn default() -> Self {
+        Self {
+            read_read_safe: true,
+            read_write_safe: false,
+            atomic_read_read_safe: true,
+            atomic_rmw_rmw_safe: true,
+            atomic_read_write_safe: true,
+            atomic_write_write_safe: true,
+        }
+    }
+}
+
+impl RaceDetectionPolicy {
+    pub fn is_safe(&self, access1: AccessType, access2: AccessType) -> bool {
+        use AccessType::*;
+
+        match (access1, access2) {
+            (Read, Read) => self.read_read_safe,
+            (AtomicRead, AtomicRead) => self.atomic_read_read_safe,
+            (AtomicRMW, AtomicRMW) => self.atomic_rmw_rmw_safe,
+            (AtomicRead, AtomicWrite | AtomicRMW) | (AtomicWrite | AtomicRMW, AtomicRead) => {
+                self.atomic_read_write_safe
+            }
+            (AtomicWrite, AtomicWrite | AtomicRMW) | (AtomicRMW, AtomicWrite) => {
+                self.atomic_write_write_safe
+            }
+            // A plain access racing an atomic one is still a data race
+            (Read | Write, AtomicRead | AtomicWrite | AtomicRMW)
+            | (AtomicRead | AtomicWrite | AtomicRMW, Read | Write) => false,
+            (Read, Write) | (Write, Read) => self.read_write_safe,
+            (Write, Write) => false,
+        }
+    }
+}
+
+/// A declared-safe access pair on variables matching `variable_glob`.
+///
+/// The pair is unordered: `{ access_a = "Read", access_b = "Write" }` also
+/// covers a Write followed by a Read.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SafePattern {
+    pub variable_glob: String,
+    pub access_a: AccessType,
+    pub access_b: AccessType,
+}
+
+impl SafePattern {
+    pub fn matches(&self, variable: &str, access1: AccessType, access2: AccessType) -> bool {
+        let accesses_match = (self.access_a == access1 && self.access_b == access2)
+            || (self.access_a == access2 && self.access_b == access1);
+        accesses_match && glob_match(self.variable_glob.as_bytes(), variable.as_bytes())
+    }
+}
+
+/// A family of lock ids (e.g. lock stripes) treated as one logical lock for the
+/// variables matching `variable_glob`: two accesses to such a variable that each
+/// hold any member of the family are mutually exclusive.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LockFamily {
+    pub lock_glob: String,
+    #[serde(default = "default_family_variable_glob")]
+    pub variable_glob: String,
+}
+
+fn default_family_variable_glob() -> String {
+    "*".to_string()
+}
+
+impl LockFamily {
+    pub fn covers_variable(&self, variable: &str) -> bool {
+        glob_match(self.variable_glob.as_bytes(), variable.as_bytes())
+    }
+
+    pub fn contains_lock(&self, lock_id: &str) -> bool {
+        glob_match(self.lock_glob.as_bytes(), lock_id.as_bytes())
+    }
+}
+
+/// Glob matcher: `*` matches any run of characters, `?` matches exactly one.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    while p < pattern.len() && pattern[p] == b'*' {
+        p += 1;
+    }
+    p == pattern.len()
+}
+
 /// Controls whether anomaly detection is enabled.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AnomalyDetectionConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
+
+    /// Sample large traces down to about this many events when scanning for
+    /// anomalies (the slowest event of each kind is always scanned)
+    #[serde(default)]
+    pub max_events_scanned: Option<usize>,
+
+    /// Report a repeating anomaly (same service, event kind and location)
+    /// once per this many seconds with an occurrence count; 0 disables dedup
+    #[serde(default = "default_anomaly_cooldown")]
+    pub cooldown_seconds: u64,
+
+    /// How a slow event is recognized: `sigma` flags durations more than 1.5
+    /// standard deviations from the baseline mean, `percentile` flags durations
+    /// above the baseline p95 times `percentile_multiplier`
+    #[serde(default = "default_anomaly_method")]
+    pub method: String,
+
+    /// With `method = "percentile"`, how far above p95 a duration must be
+    #[serde(default = "default_percentile_multiplier")]
+    pub percentile_multiplier: f64,
+
+    /// Samples an event kind's baseline needs before its durations are flagged
+    #[serde(default = "default_min_baseline_samples")]
+    pub min_baseline_samples: usize,
+
+    /// Per-kind overrides of `min_baseline_samples`, keyed by the exact event
+    /// kind (`HttpResponse(200)`, `FunctionCall(checkout)`) or its variant
+    /// (`DatabaseQuery`, `HttpResponse`); an exact kind wins over its variant
+    #[serde(default)]
+    pub min_baseline_samples_by_kind: HashMap<String, usize>,
 }
 
 impl Default for AnomalyDetectionConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            max_events_scanned: None,
+            cooldown_seconds: default_anomaly_cooldown(),
+            method: default_anomaly_method(),
+            percentile_multiplier: default_percentile_multiplier(),
+            min_baseline_samples: default_min_baseline_samples(),
+            min_baseline_samples_by_kind: HashMap::new(),
+        }
     }
 }
 
@@ -272,6 +712,19 @@ pub struct DistributedTracingConfig {
     pub enabled: bool,
 }
 
+/// Isolation between deployment environments (`EventMetadata::environment`).
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct EnvironmentConfig {
+    /// Key anomaly baselines per environment and drop cross-environment races
+    /// unless a query asks for them with `cross_environment=true`
+    #[serde(default = "default_false")]
+    pub isolate: bool,
+
+    /// Environment that trace, race and hotspot queries are scoped to when no
+    /// `?environment=` is given (`?environment=all` lifts it)
+    #[serde(default)]
+    pub default: Option<String>,
+}
 
 /// Logging configuration.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -283,6 +736,20 @@ pub struct LoggingConfig {
     pub include_modules: bool,
 }
 
+impl LoggingConfig {
+    /// Most verbose level emitted; unrecognized levels fall back to info
+    pub fn max_level(&self) -> tracing::Level {
+        match self.level.to_lowercase().as_str() {
+            "trace" => tracing::Level::TRACE,
+            "debug" => tracing::Level::DEBUG,
+            "info" => tracing::Level::INFO,
+            "warn" => tracing::Level::WARN,
+            "error" => tracing::Level::ERROR,
+            _ => tracing::Level::INFO,
+        }
+    }
+}
+
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
@@ -329,6 +796,14 @@ fn default_storage_backend() -> String {
     "memory".to_string()
 }
 
+fn default_health_latency_threshold_ms() -> u64 {
+    1000
+}
+
+fn default_redis_key_prefix() -> String {
+    "raceway".to_string()
+}
+
 fn default_max_connections() -> u32 {
     10
 }
@@ -345,6 +820,10 @@ fn default_buffer_size() -> usize {
     10_000
 }
 
+fn default_max_clock_components() -> usize {
+    256
+}
+
 fn default_batch_size() -> usize {
     100
 }
@@ -353,6 +832,38 @@ fn default_flush_interval() -> u64 {
     100
 }
 
+fn default_warmup_concurrency() -> usize {
+    8
+}
+
+fn default_orphan_buffer_size() -> usize {
+    10_000
+}
+
+fn default_orphan_timeout() -> u64 {
+    5_000
+}
+
+fn default_retention_interval() -> u64 {
+    60_000
+}
+
+fn default_anomaly_cooldown() -> u64 {
+    300
+}
+
+fn default_anomaly_method() -> String {
+    "sigma".to_string()
+}
+
+fn default_percentile_multiplier() -> f64 {
+    1.5
+}
+
+fn default_min_baseline_samples() -> usize {
+    crate::graph::DEFAULT_MIN_BASELINE_SAMPLES
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -394,6 +905,71 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_validate_redis_requires_url() {
+        let mut config = Config::default();
+        config.storage.backend = "redis".to_string();
+        assert!(config.validate().is_err());
+
+        config.storage.redis.url = Some("redis://localhost:6379".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_mysql_requires_connection_string() {
+        let mut config = Config::default();
+        config.storage.backend = "mysql".to_string();
+        assert!(config.validate().is_err());
+
+        config.storage.mysql.connection_string = Some("mysql://localhost/raceway".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_anomaly_method() {
+        let mut config = Config::default();
+        config.anomaly_detection.method = "percentile".to_string();
+        assert!(config.validate().is_ok());
+
+        config.anomaly_detection.percentile_multiplier = 0.0;
+        assert!(config.validate().is_err());
+
+        config.anomaly_detection.method = "mad".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_min_baseline_samples() {
+        let config: Config = toml::from_str(
+            r#"
+            [anomaly_detection]
+            min_baseline_samples = 20
+
+            [anomaly_detection.min_baseline_samples_by_kind]
+            DatabaseQuery = 50
+            "HttpResponse(200)" = 10
+            "#,
+        )
+        .unwrap();
+        let anomaly = &config.anomaly_detection;
+        assert_eq!(anomaly.min_baseline_samples, 20);
+        let by_kind = &anomaly.min_baseline_samples_by_kind;
+        assert_eq!(by_kind["DatabaseQuery"], 50);
+        assert_eq!(by_kind["HttpResponse(200)"], 10);
+        assert!(config.validate().is_ok());
+
+        assert_eq!(Config::default().anomaly_detection.min_baseline_samples, 5);
+
+        let mut config = Config::default();
+        let by_kind = &mut config.anomaly_detection.min_baseline_samples_by_kind;
+        by_kind.insert("DatabaseQuery".to_string(), 0);
+        assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        config.anomaly_detection.min_baseline_samples = 0;
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_parse_toml() {
         let toml_str = r#"
@@ -421,4 +997,92 @@ mod tests {
         );
         assert_eq!(config.logging.level, "debug");
     }
+
+    #[test]
+    fn test_parse_safe_patterns() {
+        let toml_str = r#"
+            [[race_detection.safe_patterns]]
+            variable_glob = "counter.*"
+            access_a = "Write"
+            access_b = "Write"
+        "#;
+
+        let config = Config::from_str(toml_str).unwrap();
+        let pattern = &config.race_detection.safe_patterns[0];
+        assert!(pattern.matches("counter.hits", AccessType::Write, AccessType::Write));
+        assert!(!pattern.matches("counter.hits", AccessType::Read, AccessType::Write));
+        assert!(!pattern.matches("balance", AccessType::Write, AccessType::Write));
+    }
+
+    #[test]
+    fn test_parse_race_detection_policy() {
+        let toml_str = r#"
+            [race_detection.policy]
+            read_read_safe = false
+        "#;
+
+        let config = Config::from_str(toml_str).unwrap();
+        let policy = &config.race_detection.policy;
+        assert!(!policy.is_safe(AccessType::Read, AccessType::Read));
+        // Unset toggles keep their defaults
+        assert!(policy.is_safe(AccessType::AtomicRead, AccessType::AtomicRead));
+        assert!(policy.is_safe(AccessType::AtomicRMW, AccessType::AtomicRMW));
+    }
+
+    const ATOMIC_ACCESSES: [AccessType; 3] = [
+        AccessType::AtomicRead,
+        AccessType::AtomicWrite,
+        AccessType::AtomicRMW,
+    ];
+
+    #[test]
+    fn test_default_policy_treats_atomic_pairs_as_safe() {
+        let policy = RaceDetectionPolicy::default();
+        for access1 in ATOMIC_ACCESSES {
+            for access2 in ATOMIC_ACCESSES {
+                assert!(
+                    policy.is_safe(access1, access2),
+                    "{:?}/{:?} should be safe",
+                    access1,
+                    access2
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_policy_flags_atomic_mixed_with_plain_access() {
+        let policy = RaceDetectionPolicy::default();
+        for atomic in ATOMIC_ACCESSES {
+            for plain in [AccessType::Read, AccessType::Write] {
+                assert!(
+                    !policy.is_safe(atomic, plain),
+                    "{:?}/{:?} should be flagged",
+                    atomic,
+                    plain
+                );
+                assert!(
+                    !policy.is_safe(plain, atomic),
+                    "{:?}/{:?} should be flagged",
+                    plain,
+                    atomic
+                );
+            }
+        }
+        assert!(!policy.is_safe(AccessType::Read, AccessType::Write));
+        assert!(!policy.is_safe(AccessType::Write, AccessType::Write));
+    }
+
+    #[test]
+    fn test_atomic_write_write_toggle_covers_write_and_rmw_pairs() {
+        let policy = RaceDetectionPolicy {
+            atomic_write_write_safe: false,
+            ..RaceDetectionPolicy::default()
+        };
+        assert!(!policy.is_safe(AccessType::AtomicWrite, AccessType::AtomicWrite));
+        assert!(!policy.is_safe(AccessType::AtomicWrite, AccessType::AtomicRMW));
+        assert!(!policy.is_safe(AccessType::AtomicRMW, AccessType::AtomicWrite));
+        assert!(policy.is_safe(AccessType::AtomicRMW, AccessType::AtomicRMW));
+        assert!(policy.is_safe(AccessType::AtomicRead, AccessType::AtomicWrite));
+    }
 }
@@ -1,12 +1,29 @@
 use crate::analysis::AnalysisService;
 use crate::capture::EventCapture;
 use crate::config::Config;
+use crate::event::{Event, EventKind};
+use crate::graph::RaceSeverity;
 use crate::storage::StorageBackend;
 use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio::task;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{broadcast, Notify, RwLock};
+use tokio::task::{self, JoinHandle};
+use uuid::Uuid;
+
+/// How long finished global analysis jobs are kept for polling
+const FINISHED_JOB_RETENTION_MINUTES: i64 = 60;
+/// Race alerts buffered per subscriber before a slow one starts lagging
+const RACE_ALERT_BUFFER: usize = 256;
+/// How long a trace touched by ingest waits for more events before it is
+/// checked for new races, so a burst of events costs one scan
+const RACE_ALERT_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(250);
+/// Alerted pairs of a trace are forgotten once it goes this long without being checked
+const RACE_ALERT_RETENTION: std::time::Duration = std::time::Duration::from_secs(600);
 
 /// Main engine that coordinates event capture and graph building
 pub struct RacewayEngine {
@@ -15,6 +32,50 @@ pub struct RacewayEngine {
     analysis: Arc<AnalysisService>,
     config: EngineConfig,
     running: Arc<RwLock<bool>>,
+    wake: Arc<Notify>,
+    processor: Mutex<Option<JoinHandle<()>>>,
+    global_jobs: Arc<DashMap<Uuid, GlobalAnalysisJob>>,
+    race_alerts: broadcast::Sender<RaceAlert>,
+}
+
+/// What a graceful shutdown flushed before exiting
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    /// Events still buffered in the capture queue that were persisted on shutdown
+    pub drained_events: usize,
+    pub persisted_baselines: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A background global (cross-trace) race analysis
+#[derive(Debug, Clone)]
+pub struct GlobalAnalysisJob {
+    pub id: Uuid,
+    pub status: JobStatus,
+    /// State changes compared so far / total state changes
+    pub processed: usize,
+    pub total: usize,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub result: Option<Arc<Vec<(Event, Event)>>>,
+}
+
+/// A race that ingest just created: two concurrent accesses to `variable`
+/// in `trace_id` that no earlier alert reported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaceAlert {
+    pub trace_id: Uuid,
+    pub variable: String,
+    pub severity: RaceSeverity,
+    pub event1: Uuid,
+    pub event2: Uuid,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +85,9 @@ pub struct EngineConfig {
     pub flush_interval_ms: u64,
     pub enable_anomaly_detection: bool,
     pub enable_race_detection: bool,
+    /// Most traces kept in the causal graph (None = unbounded)
+    pub max_graph_traces: Option<usize>,
+    pub retention_interval_ms: u64,
 }
 
 impl Default for EngineConfig {
@@ -34,6 +98,8 @@ impl Default for EngineConfig {
             flush_interval_ms: 100,
             enable_anomaly_detection: true,
             enable_race_detection: true,
+            max_graph_traces: None,
+            retention_interval_ms: 60_000,
         }
     }
 }
@@ -44,10 +110,13 @@ impl RacewayEngine {
         storage: Arc<dyn StorageBackend>,
         full_config: Config,
     ) -> Result<Self> {
-        let capture = Arc::new(EventCapture::new(config.buffer_size));
+        let capture = Arc::new(
+            EventCapture::new(config.buffer_size).with_limits(full_config.capture.clone()),
+        );
 
         // Create AnalysisService with the storage backend and full config
         let analysis = Arc::new(AnalysisService::new(Arc::clone(&storage), full_config).await?);
+        let (race_alerts, _) = broadcast::channel(RACE_ALERT_BUFFER);
 
         Ok(Self {
             capture,
@@ -55,6 +124,10 @@ impl RacewayEngine {
             analysis,
             config,
             running: Arc::new(RwLock::new(false)),
+            wake: Arc::new(Notify::new()),
+            processor: Mutex::new(None),
+            global_jobs: Arc::new(DashMap::new()),
+            race_alerts,
         })
     }
 
@@ -72,10 +145,13 @@ impl RacewayEngine {
         let analysis = Arc::clone(&self.analysis);
         let config = self.config.clone();
         let running = Arc::clone(&self.running);
+        let wake = Arc::clone(&self.wake);
+        let alerts = RaceAlerter::new(self.race_alerts.clone());
 
-        task::spawn(async move {
-            Self::process_events(capture, analysis, config, running).await;
+        let handle = task::spawn(async move {
+            Self::process_events(capture, analysis, config, running, wake, alerts).await;
         });
+        *self.processor.lock().unwrap() = Some(handle);
 
         Ok(())
     }
@@ -84,6 +160,37 @@ impl RacewayEngine {
     pub async fn stop(&self) {
         let mut running = self.running.write().await;
         *running = false;
+        drop(running);
+        // notify_one stores a permit, so a loop that is mid-batch still wakes immediately
+        self.wake.notify_one();
+    }
+
+    /// Stop processing and flush everything still in memory: waits for the
+    /// in-flight batch, persists every event left in the capture buffer, then
+    /// saves the current baselines. Call after the server stops accepting requests.
+    pub async fn shutdown(&self) -> Result<ShutdownReport> {
+        self.stop().await;
+
+        let processor = self.processor.lock().unwrap().take();
+        if let Some(handle) = processor {
+            if let Err(e) = handle.await {
+                tracing::warn!("Event processor exited abnormally: {}", e);
+            }
+        }
+
+        let pending = self.capture.drain();
+        let drained_events = pending.len();
+        for batch in pending.chunks(self.config.batch_size.max(1)) {
+            self.analysis.add_events_batch(batch.to_vec()).await?;
+        }
+
+        let persisted_baselines = self.analysis.persist_baselines().await?;
+        self.storage.close().await?;
+
+        Ok(ShutdownReport {
+            drained_events,
+            persisted_baselines,
+        })
     }
 
     /// Process events and build the causal graph
@@ -92,8 +199,12 @@ impl RacewayEngine {
         analysis: Arc<AnalysisService>,
         config: EngineConfig,
         running: Arc<RwLock<bool>>,
+        wake: Arc<Notify>,
+        mut alerts: RaceAlerter,
     ) {
         let receiver = capture.get_receiver();
+        let retention_interval = std::time::Duration::from_millis(config.retention_interval_ms);
+        let mut last_retention = std::time::Instant::now();
 
         loop {
             {
@@ -112,6 +223,10 @@ impl RacewayEngine {
                 }
             }
 
+            if config.enable_race_detection {
+                alerts.touch(batch.iter().map(|event| event.trace_id));
+            }
+
             // Add events to analysis service using batch operation (much faster!)
             if !batch.is_empty() {
                 if let Err(e) = analysis.add_events_batch(batch).await {
@@ -119,8 +234,30 @@ impl RacewayEngine {
                 }
             }
 
-            // Sleep briefly to avoid spinning
-            tokio::time::sleep(tokio::time::Duration::from_millis(config.flush_interval_ms)).await;
+            alerts.check_due(&analysis).await;
+
+            if let Err(e) = analysis.expire_orphans().await {
+                eprintln!("Failed to expire orphaned events: {}", e);
+            }
+
+            if let Some(max_traces) = config.max_graph_traces {
+                if last_retention.elapsed() >= retention_interval {
+                    match analysis.retain_recent(max_traces).await {
+                        Ok(0) => {}
+                        Ok(evicted) => {
+                            tracing::debug!("Evicted {} traces from the causal graph", evicted);
+                        }
+                        Err(e) => eprintln!("Failed to evict old traces: {}", e),
+                    }
+                    last_retention = std::time::Instant::now();
+                }
+            }
+
+            // Sleep briefly to avoid spinning (stop() wakes us early)
+            tokio::select! {
+                _ = tokio::time::sleep(tokio::time::Duration::from_millis(config.flush_interval_ms)) => {}
+                _ = wake.notified() => {}
+            }
         }
     }
 
@@ -138,6 +275,153 @@ impl RacewayEngine {
     pub fn analysis(&self) -> Arc<AnalysisService> {
         Arc::clone(&self.analysis)
     }
+
+    /// Receive an alert for every new race ingest creates from now on, across
+    /// all traces. Traces are only checked while someone is subscribed, so
+    /// races that formed with nobody listening are reported the next time
+    /// their trace receives events.
+    pub fn subscribe_race_alerts(&self) -> broadcast::Receiver<RaceAlert> {
+        self.race_alerts.subscribe()
+    }
+
+    /// Start a global race analysis in the background and return its job id.
+    /// Poll progress and results with `global_analysis_job`.
+    pub fn start_global_analysis(&self) -> Uuid {
+        let cutoff = Utc::now() - Duration::minutes(FINISHED_JOB_RETENTION_MINUTES);
+        self.global_jobs
+            .retain(|_, job| job.finished_at.is_none_or(|finished| finished > cutoff));
+
+        let id = Uuid::new_v4();
+        self.global_jobs.insert(
+            id,
+            GlobalAnalysisJob {
+                id,
+                status: JobStatus::Running,
+                processed: 0,
+                total: 0,
+                started_at: Utc::now(),
+                finished_at: None,
+                error: None,
+                result: None,
+            },
+        );
+
+        let analysis = Arc::clone(&self.analysis);
+        let jobs = Arc::clone(&self.global_jobs);
+        task::spawn(async move {
+            let progress_jobs = Arc::clone(&jobs);
+            let outcome = analysis
+                .find_global_concurrent_events_with_progress(move |processed, total| {
+                    if let Some(mut job) = progress_jobs.get_mut(&id) {
+                        job.processed = processed;
+                        job.total = total;
+                    }
+                })
+                .await;
+
+            if let Some(mut job) = jobs.get_mut(&id) {
+                job.finished_at = Some(Utc::now());
+                match outcome {
+                    Ok(pairs) => {
+                        job.status = JobStatus::Completed;
+                        job.processed = job.total;
+                        job.result = Some(Arc::new(pairs));
+                    }
+                    Err(e) => {
+                        job.status = JobStatus::Failed;
+                        job.error = Some(e.to_string());
+                    }
+                }
+            }
+        });
+
+        id
+    }
+
+    /// Current state of a global analysis job, if it exists
+    pub fn global_analysis_job(&self, id: Uuid) -> Option<GlobalAnalysisJob> {
+        self.global_jobs.get(&id).map(|job| job.clone())
+    }
+}
+
+/// Debounces race checks of the traces ingest touches and remembers which
+/// pairs were already alerted, so each race is pushed once
+struct RaceAlerter {
+    sender: broadcast::Sender<RaceAlert>,
+    /// When each touched trace is due for a check
+    due: HashMap<Uuid, Instant>,
+    /// Pairs already alerted per trace, with when the trace was last checked
+    alerted: HashMap<Uuid, (HashSet<(Uuid, Uuid)>, Instant)>,
+}
+
+impl RaceAlerter {
+    fn new(sender: broadcast::Sender<RaceAlert>) -> Self {
+        Self {
+            sender,
+            due: HashMap::new(),
+            alerted: HashMap::new(),
+        }
+    }
+
+    /// Schedule a check of each trace. A trace already waiting keeps its
+    /// deadline, so a steady stream of events can't postpone it forever.
+    fn touch(&mut self, trace_ids: impl IntoIterator<Item = Uuid>) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let deadline = Instant::now() + RACE_ALERT_DEBOUNCE;
+        for trace_id in trace_ids {
+            self.due.entry(trace_id).or_insert(deadline);
+        }
+    }
+
+    /// Check every trace whose debounce ran out and alert its new races
+    async fn check_due(&mut self, analysis: &AnalysisService) {
+        let now = Instant::now();
+        let ready: Vec<Uuid> = self
+            .due
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(trace_id, _)| *trace_id)
+            .collect();
+
+        for trace_id in ready {
+            self.due.remove(&trace_id);
+            let races = match analysis.find_races_with_severity(trace_id).await {
+                Ok(races) => races,
+                Err(e) => {
+                    tracing::warn!("Race alert check failed for trace {}: {}", trace_id, e);
+                    continue;
+                }
+            };
+
+            let (alerted, checked_at) = self
+                .alerted
+                .entry(trace_id)
+                .or_insert_with(|| (HashSet::new(), now));
+            *checked_at = now;
+            for race in races {
+                let EventKind::StateChange { variable, .. } = &race.event1.kind else {
+                    continue;
+                };
+                let (event1, event2) = (race.event1.id, race.event2.id);
+                if !alerted.insert((event1.min(event2), event1.max(event2))) {
+                    continue;
+                }
+                // Subscribers may have gone away since the trace was touched
+                let _ = self.sender.send(RaceAlert {
+                    trace_id,
+                    variable: variable.clone(),
+                    severity: race.severity,
+                    event1,
+                    event2,
+                });
+            }
+        }
+
+        self.alerted
+            .retain(|_, (_, checked_at)| now.duration_since(*checked_at) < RACE_ALERT_RETENTION);
+    }
 }
 
 #[cfg(test)]
@@ -159,4 +443,90 @@ mod tests {
         assert!(engine.start().await.is_ok());
         engine.stop().await;
     }
+
+    #[tokio::test]
+    async fn test_shutdown_flushes_buffered_events() {
+        use crate::event::{EventKind, EventMetadata};
+
+        // A long flush interval keeps events sitting in the capture buffer
+        let engine_config = EngineConfig {
+            batch_size: 10,
+            flush_interval_ms: 60_000,
+            ..EngineConfig::default()
+        };
+        let storage = Arc::new(MemoryBackend::new(&StorageConfig::default()).unwrap());
+        let engine = RacewayEngine::new(engine_config, storage.clone(), Config::default())
+            .await
+            .unwrap();
+        engine.start().await.unwrap();
+
+        let trace_id = Uuid::new_v4();
+        for i in 0..50 {
+            let metadata = EventMetadata {
+                thread_id: "main".to_string(),
+                process_id: 1,
+                service_name: "test".to_string(),
+                environment: "test".to_string(),
+                tags: std::collections::HashMap::new(),
+                duration_ns: Some(1_000),
+                instance_id: None,
+                distributed_span_id: None,
+                upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
+            };
+            let event = Event::new(
+                EventKind::Custom {
+                    name: format!("event-{}", i),
+                    data: serde_json::json!({}),
+                },
+                metadata,
+                trace_id,
+                None,
+            );
+            engine.capture().capture(event).unwrap();
+        }
+
+        let report = tokio::time::timeout(tokio::time::Duration::from_secs(5), engine.shutdown())
+            .await
+            .expect("shutdown should not wait for the flush interval")
+            .unwrap();
+
+        assert!(report.drained_events > 0);
+        assert!(engine.capture().drain().is_empty());
+        assert_eq!(storage.get_trace_events(trace_id).await.unwrap().len(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_global_analysis_job_completes() {
+        let storage = Arc::new(MemoryBackend::new(&StorageConfig::default()).unwrap());
+        let engine = RacewayEngine::new(EngineConfig::default(), storage, Config::default())
+            .await
+            .unwrap();
+
+        let job_id = engine.start_global_analysis();
+        assert!(engine.global_analysis_job(job_id).is_some());
+
+        for _ in 0..50 {
+            let job = engine.global_analysis_job(job_id).unwrap();
+            if job.status != JobStatus::Running {
+                assert_eq!(job.status, JobStatus::Completed);
+                assert!(job.result.is_some());
+                return;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        }
+        panic!("global analysis job did not finish");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_global_analysis_job() {
+        let storage = Arc::new(MemoryBackend::new(&StorageConfig::default()).unwrap());
+        let engine = RacewayEngine::new(EngineConfig::default(), storage, Config::default())
+            .await
+            .unwrap();
+
+        assert!(engine.global_analysis_job(Uuid::new_v4()).is_none());
+    }
 }
@@ -16,6 +16,7 @@ pub struct DistributedSpan {
 
 /// Represents an edge between spans in distributed trace (Phase 2)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DistributedEdge {
     pub from_span: String, // Upstream span ID
     pub to_span: String,   // Downstream span ID
@@ -24,6 +25,7 @@ pub struct DistributedEdge {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum EdgeLinkType {
     HttpCall,      // HTTP request/response
     GrpcCall,      // gRPC call
@@ -34,6 +36,7 @@ pub enum EdgeLinkType {
 
 /// Type of memory access
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AccessType {
     Read,
     Write,
@@ -42,8 +45,20 @@ pub enum AccessType {
     AtomicRMW,   // Atomic read-modify-write (CAS, fetch_add, etc.)
 }
 
+impl AccessType {
+    /// Whether the access stores to the variable: plain and atomic writes, and
+    /// read-modify-writes
+    pub fn is_write(self) -> bool {
+        matches!(
+            self,
+            AccessType::Write | AccessType::AtomicWrite | AccessType::AtomicRMW
+        )
+    }
+}
+
 /// Memory ordering for atomic operations and fences
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum MemoryOrdering {
     Relaxed,
     Acquire,
@@ -54,6 +69,7 @@ pub enum MemoryOrdering {
 
 /// Represents a single captured event in the system
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Event {
     pub id: Uuid,
     pub trace_id: Uuid,
@@ -70,6 +86,7 @@ pub struct Event {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum EventKind {
     FunctionCall {
         function_name: String,
@@ -139,6 +156,7 @@ pub enum EventKind {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EventMetadata {
     pub thread_id: String,
     pub process_id: u32,
@@ -154,6 +172,63 @@ pub struct EventMetadata {
     pub distributed_span_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub upstream_span_id: Option<String>,
+
+    /// High-resolution monotonic offset (nanoseconds since a per-process epoch).
+    /// Only comparable between events from the same process/instance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monotonic_ns: Option<u64>,
+
+    /// Retry lineage: the trace this one retries, and which attempt it is
+    /// (the original trace is attempt 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempt: Option<u32>,
+}
+
+/// Sort events chronologically.
+///
+/// Wall-clock timestamps give the overall order. Within a single process/instance,
+/// events that carry a monotonic offset are reordered by it, so sub-millisecond ties
+/// and wall-clock adjustments don't scramble in-process ordering. Cross-service
+/// ordering is still decided by wall-clock time.
+pub fn sort_events_chronologically(events: &mut [Event]) {
+    events.sort_by_key(|event| event.timestamp);
+
+    // Slots occupied by each process's monotonic-stamped events, in wall-clock order
+    let mut per_process: HashMap<(String, Option<String>, u32), Vec<usize>> = HashMap::new();
+    for (idx, event) in events.iter().enumerate() {
+        if event.metadata.monotonic_ns.is_some() {
+            let key = (
+                event.metadata.service_name.clone(),
+                event.metadata.instance_id.clone(),
+                event.metadata.process_id,
+            );
+            per_process.entry(key).or_default().push(idx);
+        }
+    }
+
+    // Reassign each process's events to its slots in monotonic order
+    for slots in per_process.into_values() {
+        if slots.len() < 2 {
+            continue;
+        }
+        let mut group: Vec<Event> = slots.iter().map(|&idx| events[idx].clone()).collect();
+        group.sort_by_key(|event| event.metadata.monotonic_ns);
+        for (idx, event) in slots.into_iter().zip(group) {
+            events[idx] = event;
+        }
+    }
+}
+
+/// JSON Schema for the `Event` wire format, with `DistributedEdge` included in
+/// the definitions. Non-Rust SDKs validate against this.
+#[cfg(feature = "schema")]
+pub fn wire_schema() -> serde_json::Value {
+    let mut generator = schemars::gen::SchemaSettings::draft07().into_generator();
+    generator.subschema_for::<DistributedEdge>();
+    let schema = generator.into_root_schema_for::<Event>();
+    serde_json::to_value(schema).expect("JSON Schema always serializes")
 }
 
 impl Event {
@@ -246,6 +321,9 @@ mod tests {
             instance_id: None,
             distributed_span_id: None,
             upstream_span_id: None,
+            monotonic_ns: None,
+            retry_of: None,
+            attempt: None,
         };
 
         let event = Event::new(
@@ -264,4 +342,35 @@ mod tests {
         assert_eq!(event.trace_id, trace_id);
         assert!(event.parent_id.is_none());
     }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_wire_schema_describes_state_change() {
+        let schema = wire_schema();
+        let definitions = &schema["definitions"];
+
+        let state_change = definitions["EventKind"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find_map(|variant| variant["properties"].get("StateChange"))
+            .expect("StateChange variant present");
+
+        for field in [
+            "variable",
+            "old_value",
+            "new_value",
+            "location",
+            "access_type",
+        ] {
+            assert!(
+                state_change["properties"].get(field).is_some(),
+                "StateChange missing field {}",
+                field
+            );
+        }
+        assert!(definitions.get("EventMetadata").is_some());
+        assert!(definitions.get("AccessType").is_some());
+        assert!(definitions.get("DistributedEdge").is_some());
+    }
 }
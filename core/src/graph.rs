@@ -1,13 +1,14 @@
-use crate::event::{AccessType, Event, EventKind};
+use crate::config::{LockFamily, RaceDetectionPolicy, SafePattern};
+use crate::event::{sort_events_chronologically, AccessType, Event, EventKind, EventMetadata};
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use lru::LruCache;
-use petgraph::algo::is_cyclic_directed;
+use petgraph::algo::{has_path_connecting, is_cyclic_directed, tarjan_scc};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::Mutex;
 use uuid::Uuid;
@@ -75,9 +76,11 @@ pub struct Anomaly {
     pub description: String,
     pub location: String,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub service_name: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AnomalySeverity {
     Minor,    // 2-3 std dev
     Warning,  // 3-5 std dev
@@ -126,27 +129,275 @@ pub struct VariableAccess {
     pub location: String,
     pub has_causal_link_to_previous: bool,
     pub is_race: bool,
+    /// A write computed from the same old value as a concurrent write on
+    /// another thread, but storing a different result: one of them is lost
+    #[serde(default)]
+    pub is_lost_update: bool,
+}
+
+/// One attempt of a retried operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineageAttempt {
+    pub trace_id: Uuid,
+    pub attempt: u32,
+    pub retry_of: Option<Uuid>,
+}
+
+/// Step-by-step reasoning behind the race verdict for one pair of events,
+/// mirroring the checks in `find_concurrent_events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaceExplanation {
+    pub trace_id: Uuid,
+    pub event_a: Uuid,
+    pub event_b: Uuid,
+    pub variable_a: Option<String>,
+    pub variable_b: Option<String>,
+    pub same_variable: bool,
+    pub safe_access_pattern: bool,
+    pub different_thread: bool,
+    pub a_happens_before_b: bool,
+    pub b_happens_before_a: bool,
+    pub concurrent: bool,
+    pub vector_clocks: Vec<ClockComponent>,
+    pub locks_a: Vec<String>,
+    pub locks_b: Vec<String>,
+    pub shared_locks: Vec<String>,
+    pub overlaps_in_time: bool,
+    pub race: bool,
+    pub reason: String,
+}
+
+/// Causal order between two events; `concurrent` when neither happens before the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventOrdering {
+    pub a_before_b: bool,
+    pub b_before_a: bool,
+    pub concurrent: bool,
+}
+
+/// One vector-clock component as seen by each side of an explained pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockComponent {
+    pub component: String,
+    pub a: Option<u64>,
+    pub b: Option<u64>,
+}
+
+/// A double-checked locking sequence on a non-atomic variable: an unlocked
+/// read, then a lock acquire and a re-read of the same variable under it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoubleCheckedLocking {
+    pub variable: String,
+    pub thread_id: String,
+    pub lock_id: String,
+    pub unlocked_read: Uuid,
+    pub locked_read: Uuid,
+    pub location: String,
+    pub severity: AnomalySeverity,
+    pub description: String,
+}
+
+/// A lock whose acquires and releases on one thread don't balance within a trace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnreleasedLock {
+    #[serde(default)]
+    pub imbalance: LockImbalance,
+    pub thread_id: String,
+    pub lock_id: String,
+    pub acquires: usize,
+    pub releases: usize,
+    /// First acquire no release matched
+    pub orphaned_acquire: Option<Uuid>,
+    pub orphaned_at: Option<DateTime<Utc>>,
+    /// First release with no open acquire to match
+    #[serde(default)]
+    pub unmatched_release: Option<Uuid>,
+    /// Where the orphaned acquire or unmatched release was tracked
+    pub location: Option<String>,
+    /// Spans where the thread's lock set still lists the lock after it left
+    /// an orphaned section; the lock does not count as protection there
+    #[serde(default)]
+    pub stale_windows: Vec<StaleLockWindow>,
+}
+
+/// Which side of a lock's acquire/release pairing is missing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockImbalance {
+    /// The thread acquired the lock and never released it
+    #[default]
+    UnreleasedAcquire,
+    /// The thread released the lock without holding it, e.g. a release tracked
+    /// twice or under the wrong lock id
+    UnmatchedRelease,
+}
+
+/// Where an orphaned lock shows in its thread's lock set without being held
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleLockWindow {
+    /// The orphaned section has ended: the thread released a lock it held
+    /// around the acquire, or the section outlasted the longest balanced hold
+    /// of the lock in the trace
+    pub from: DateTime<Utc>,
+    /// The thread took the lock again, making its lock set accurate once
+    /// more; `None` if it never did in this trace
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl StaleLockWindow {
+    fn covers(&self, at: DateTime<Utc>) -> bool {
+        self.from <= at && self.until.is_none_or(|until| at < until)
+    }
+}
+
+/// One observed lock ordering: `acquired` was taken while `held` was held
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockOrderObservation {
+    pub held: String,
+    pub acquired: String,
+    pub thread_id: String,
+    pub trace_id: Uuid,
+    /// The `LockAcquire` event for `acquired`
+    pub event_id: Uuid,
+}
+
+/// Two locks taken in conflicting orders, a potential deadlock. `b_then_a`
+/// is a single observation for a classic inversion, or the chain of orderings
+/// leading from `lock_b` back to `lock_a` through other locks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockOrderViolation {
+    pub lock_a: String,
+    pub lock_b: String,
+    pub a_then_b: LockOrderObservation,
+    pub b_then_a: Vec<LockOrderObservation>,
+}
+
+/// Severity of a race between two concurrent accesses to the same variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RaceSeverity {
+    Info,     // Read/read
+    Warning,  // Read/write
+    Critical, // Write/write
+}
+
+impl RaceSeverity {
+    pub fn classify(first: AccessType, second: AccessType) -> Self {
+        match (first.is_write(), second.is_write()) {
+            (true, true) => RaceSeverity::Critical,
+            (true, false) | (false, true) => RaceSeverity::Warning,
+            (false, false) => RaceSeverity::Info,
+        }
+    }
+
+    /// Upper-case label used in analysis responses (`CRITICAL`, `WARNING`, `INFO`)
+    pub fn label(self) -> &'static str {
+        match self {
+            RaceSeverity::Critical => "CRITICAL",
+            RaceSeverity::Warning => "WARNING",
+            RaceSeverity::Info => "INFO",
+        }
+    }
+}
+
+/// A race between two concurrent accesses to the same variable, with its severity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoredRace {
+    pub event1: Event,
+    pub event2: Event,
+    pub severity: RaceSeverity,
+    /// Threads writing the variable in any concurrent pair of the trace
+    pub concurrent_writers: usize,
+    /// Both accesses are writes and stored different values
+    pub values_diverge: bool,
+}
+
+/// How often, and for how long, one lock was held across all traces
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockStats {
+    pub lock_id: String,
+    pub acquisitions: usize,
+    /// Distinct threads that acquired the lock
+    pub thread_count: usize,
+    pub trace_count: usize,
+    /// Hold times span each acquire to its matching release; acquires that were
+    /// never released don't count towards them
+    pub total_hold_ms: f64,
+    pub mean_hold_ms: f64,
+    pub max_hold_ms: f64,
+}
+
+/// How often one variable was accessed across all traces, and from which services
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableHotspot {
+    pub variable: String,
+    pub access_count: usize,
+    pub trace_count: usize,
+    pub services: Vec<String>,
+    /// Accesses per service, summing to `access_count`
+    pub accesses_by_service: BTreeMap<String, usize>,
 }
 
+/// `Custom` event name for user-declared synchronization. Its `data` names the
+/// other side of the edge: `{"synchronizes_with": <event_id>}` orders that event
+/// before this one, `{"edge_to": <event_id>}` orders this event before that one.
+pub const SYNC_EVENT_NAME: &str = "raceway.sync";
+
 const ANALYSIS_CACHE_CAPACITY: usize = 256;
 const ANOMALY_CACHE_CAPACITY: usize = 256;
+const ORPHAN_BUFFER_CAPACITY: usize = 10_000;
+const ORPHAN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// How long an orphaned section lasts when the trace never holds its lock to a release
+const ORPHANED_SECTION_FALLBACK_MS: i64 = 1_000;
+/// Samples a baseline needs before durations are judged against it
+pub const DEFAULT_MIN_BASELINE_SAMPLES: usize = 5;
+
+/// A trace's cached `find_concurrent_events` result, with the per-variable
+/// accesses and stale locks it was computed from so a new state change can
+/// be folded in without rescanning the trace
+struct ConcurrentPairsCache {
+    pairs: Vec<(Event, Event)>,
+    /// `pairs` stopped at `max_pairs_per_trace`
+    truncated: bool,
+    accesses: HashMap<String, Vec<Event>>,
+    stale_locks: Vec<UnreleasedLock>,
+}
 
 /// The causal graph maintains relationships between all captured events
 pub struct CausalGraph {
     graph: Mutex<DiGraph<Uuid, CausalEdge>>,
     nodes: DashMap<Uuid, (NodeIndex, CausalNode)>,
     trace_roots: DashMap<Uuid, Vec<Uuid>>, // trace_id -> root event IDs
-    analysis_cache: Mutex<LruCache<Uuid, Vec<(Event, Event)>>>, // bounded cache of concurrent pairs
-    anomaly_cache: Mutex<LruCache<Uuid, Vec<Anomaly>>>, // bounded cache of anomalies
-    vector_clocks: DashMap<Uuid, u64>, // trace_id -> logical clock value (fixes async migration)
+    analysis_cache: Mutex<LruCache<Uuid, ConcurrentPairsCache>>, // bounded cache of concurrent pairs
+    anomaly_cache: Mutex<LruCache<Uuid, Vec<Anomaly>>>,          // bounded cache of anomalies
+    changed_traces: Mutex<HashSet<Uuid>>, // traces changed since `take_changed_traces`
+    vector_clocks: DashMap<Uuid, u64>,    // trace_id -> logical clock value (fixes async migration)
     lock_sets: DashMap<String, HashSet<String>>, // thread_id -> currently held locks
     baseline_metrics: DashMap<String, BaselineMetrics>, // event_kind -> metrics
     baseline_durations: DashMap<String, Vec<f64>>, // event_kind -> all observed durations
     baselines_updated: DashMap<Uuid, bool>, // track which traces have been added to baselines
     variable_index: DashMap<String, Vec<Uuid>>, // variable_name -> event IDs accessing it (for fast race detection)
+    trace_lineage: DashMap<Uuid, (Uuid, u32)>,  // retried trace_id -> (retry_of, attempt)
+    sync_edges: DashMap<Uuid, Vec<Uuid>>,       // event_id -> declared sync predecessors
     /// External edges connecting events across services via distributed tracing
     /// Maps from downstream event_id to upstream event_ids
     distributed_edges: DashMap<Uuid, Vec<Uuid>>, // downstream_event_id -> upstream_event_ids
+    /// Applied distributed edges keyed by (from_span, to_span), so replays are no-ops
+    applied_distributed_edges: DashMap<(String, String), (Uuid, Uuid)>, // -> (upstream, downstream)
+    safe_patterns: Vec<SafePattern>,            // user-declared intentionally racy access pairs
+    lock_families: Vec<LockFamily>,             // lock ids that act as one logical lock
+    race_policy: RaceDetectionPolicy,           // access-type pairs that never race
+    temporal_overlap_gap: Option<chrono::Duration>, // if set, races also need overlapping intervals
+    anomaly_scan_limit: Option<usize>,          // if set, sample events scanned for anomalies
+    anomaly_p95_multiplier: Option<f64>,        // if set, anomalies are durations above p95 × this
+    min_baseline_samples: usize,                // baseline samples needed before flagging
+    min_baseline_samples_by_kind: HashMap<String, usize>, // per event kind overrides of the above
+    environment_isolation: bool,                // if set, baselines are keyed per environment
+    max_clock_components: Option<usize>,        // if set, caps causality vector length
+    max_pairs_per_trace: Option<usize>,         // if set, caps concurrent pairs collected per trace
+    /// Events waiting for a parent that hasn't arrived yet, keyed by the missing parent id
+    orphans: Mutex<HashMap<Uuid, Vec<(Event, std::time::Instant)>>>,
+    orphan_capacity: usize,
+    orphan_timeout: std::time::Duration,
 }
 
 impl CausalGraph {
@@ -163,22 +414,117 @@ impl CausalGraph {
                 NonZeroUsize::new(ANOMALY_CACHE_CAPACITY)
                     .expect("anomaly cache capacity must be > 0"),
             )),
+            changed_traces: Mutex::new(HashSet::new()),
             vector_clocks: DashMap::new(),
             lock_sets: DashMap::new(),
             baseline_metrics: DashMap::new(),
             baseline_durations: DashMap::new(),
             baselines_updated: DashMap::new(),
             variable_index: DashMap::new(),
+            trace_lineage: DashMap::new(),
+            sync_edges: DashMap::new(),
             distributed_edges: DashMap::new(),
+            applied_distributed_edges: DashMap::new(),
+            safe_patterns: Vec::new(),
+            lock_families: Vec::new(),
+            race_policy: RaceDetectionPolicy::default(),
+            temporal_overlap_gap: None,
+            anomaly_scan_limit: None,
+            anomaly_p95_multiplier: None,
+            min_baseline_samples: DEFAULT_MIN_BASELINE_SAMPLES,
+            min_baseline_samples_by_kind: HashMap::new(),
+            environment_isolation: false,
+            max_clock_components: None,
+            max_pairs_per_trace: None,
+            orphans: Mutex::new(HashMap::new()),
+            orphan_capacity: ORPHAN_BUFFER_CAPACITY,
+            orphan_timeout: ORPHAN_TIMEOUT,
         }
     }
 
+    /// Suppress races matching these declared-safe (variable, access pair) patterns
+    pub fn with_safe_patterns(mut self, safe_patterns: Vec<SafePattern>) -> Self {
+        self.safe_patterns = safe_patterns;
+        self
+    }
+
+    /// Treat any two members of a lock family as the same lock for the family's variables
+    pub fn with_lock_families(mut self, lock_families: Vec<LockFamily>) -> Self {
+        self.lock_families = lock_families;
+        self
+    }
+
+    /// Override which access-type pairs are skipped as safe
+    pub fn with_race_policy(mut self, race_policy: RaceDetectionPolicy) -> Self {
+        self.race_policy = race_policy;
+        self
+    }
+
+    /// Only report races whose access intervals overlap or are at most `max_gap_ms` apart
+    pub fn with_temporal_overlap(mut self, max_gap_ms: u64) -> Self {
+        self.temporal_overlap_gap = Some(chrono::Duration::milliseconds(max_gap_ms as i64));
+        self
+    }
+
+    /// Flag durations above the baseline p95 times `multiplier` instead of
+    /// using standard deviations, which over-fire on heavy-tailed latencies
+    pub fn with_percentile_anomalies(mut self, multiplier: f64) -> Self {
+        self.anomaly_p95_multiplier = Some(multiplier);
+        self
+    }
+
+    /// Require `min_samples` baseline samples before flagging an event kind's
+    /// durations; `by_kind` overrides it per kind (see `min_baseline_samples_for`)
+    pub fn with_min_baseline_samples(
+        mut self,
+        min_samples: usize,
+        by_kind: HashMap<String, usize>,
+    ) -> Self {
+        self.min_baseline_samples = min_samples;
+        self.min_baseline_samples_by_kind = by_kind;
+        self
+    }
+
+    /// Scan roughly `max_events` timed events per trace for anomalies (see `anomaly_scan_sample`)
+    pub fn with_anomaly_scan_limit(mut self, max_events: usize) -> Self {
+        self.anomaly_scan_limit = Some(max_events.max(1));
+        self
+    }
+
+    /// Keep a separate anomaly baseline per `metadata.environment`
+    pub fn with_environment_isolation(mut self) -> Self {
+        self.environment_isolation = true;
+        self
+    }
+
+    /// Keep at most `max_components` entries in each causality vector (see `prune_causality_vector`)
+    pub fn with_clock_component_limit(mut self, max_components: usize) -> Self {
+        self.max_clock_components = Some(max_components.max(1));
+        self
+    }
+
+    /// Stop collecting a trace's concurrent pairs after the first `max_pairs`
+    /// (see `find_concurrent_events_with_truncation`)
+    pub fn with_max_pairs_per_trace(mut self, max_pairs: usize) -> Self {
+        self.max_pairs_per_trace = Some(max_pairs.max(1));
+        self
+    }
+
+    /// Hold at most `capacity` events whose parent hasn't arrived, each for at most
+    /// `timeout` (see `add_events_reconciling`)
+    pub fn with_orphan_buffer(mut self, capacity: usize, timeout: std::time::Duration) -> Self {
+        self.orphan_capacity = capacity;
+        self.orphan_timeout = timeout;
+        self
+    }
+
     /// Add an event to the graph
     pub fn add_event(&self, mut event: Event) -> Result<()> {
         // Update vector clock for this trace (not thread, to handle async task migration)
         let trace_id = event.trace_id;
         let mut clock_value = self.vector_clocks.entry(trace_id).or_insert(0);
-        *clock_value += 1;
+        // Saturate rather than wrap: a wrapped clock would order new events before old ones
+        *clock_value = clock_value.saturating_add(1);
         let current_clock = *clock_value;
         drop(clock_value);
 
@@ -210,20 +556,18 @@ impl CausalGraph {
         }
 
         // Determine the component key for this event's clock
-        // Use distributed span info if available, otherwise use trace_id
-        let component = if let Some(instance) = &event.metadata.instance_id {
-            // Distributed trace: use "service#instance" format
-            format!("{}#{}", event.metadata.service_name, instance)
-        } else {
-            // Single-service trace: use trace_id as component
-            trace_id.to_string()
-        };
+        // Distributed trace: "service#instance"; single-service trace: the trace_id
+        let component = instance_component(&event.metadata).unwrap_or_else(|| trace_id.to_string());
 
         // Update or add this component's entry in the causality vector
         if let Some(existing) = causality_vector.iter_mut().find(|(c, _)| c == &component) {
             existing.1 = current_clock;
         } else {
-            causality_vector.push((component, current_clock));
+            causality_vector.push((component.clone(), current_clock));
+        }
+
+        if let Some(max_components) = self.max_clock_components {
+            prune_causality_vector(&mut causality_vector, &component, max_components);
         }
 
         event.causality_vector = causality_vector;
@@ -265,22 +609,31 @@ impl CausalGraph {
         };
 
         // Link to parent if exists
-        if let Some(parent_id) = event.parent_id {
-            if let Some(parent_entry) = self.nodes.get(&parent_id) {
-                let parent_idx = parent_entry.value().0;
-                let edge = self.infer_edge_type(&event);
-                graph.add_edge(parent_idx, node_index, edge);
-            }
+        let parent_idx = event
+            .parent_id
+            .and_then(|parent_id| self.nodes.get(&parent_id))
+            .map(|parent_entry| parent_entry.value().0);
+        let mut closes_cycle = false;
+        if let Some(parent_idx) = parent_idx {
+            let edge = self.infer_edge_type(&event);
+            graph.add_edge(parent_idx, node_index, edge);
+            // Cheap while the invariant holds: a new node has nothing downstream yet
+            closes_cycle = has_path_connecting(&*graph, node_index, parent_idx, None);
         } else {
-            // This is a root event
+            // This is a root event (or its parent never arrived, so it stands in for one)
             self.trace_roots
                 .entry(event.trace_id)
                 .or_default()
                 .push(event.id);
         }
 
-        drop(graph); // Release lock before inserting into nodes
+        // Insert under the graph lock so eviction never sees a node without its entry
         self.nodes.insert(event.id, (node_index, causal_node));
+        drop(graph);
+
+        if closes_cycle {
+            self.warn_cycle_through(&event);
+        }
 
         // Update variable index for fast race detection
         if let EventKind::StateChange { variable, .. } = &event.kind {
@@ -290,17 +643,119 @@ impl CausalGraph {
                 .push(event.id);
         }
 
-        // Invalidate per-trace caches so subsequent queries see fresh data
-        self.invalidate_trace_caches(trace_id);
+        // Index retry lineage (the original trace is attempt 1, so retries default to 2)
+        if let Some(retry_of) = event.metadata.retry_of.filter(|id| *id != trace_id) {
+            self.trace_lineage
+                .insert(trace_id, (retry_of, event.metadata.attempt.unwrap_or(2)));
+        }
+
+        self.record_sync_edge(&event);
+
+        // Bring per-trace caches up to date so subsequent queries see fresh data
+        self.update_trace_caches(&event);
 
         Ok(())
     }
 
+    /// Turn a `raceway.sync` custom event into a happens-before edge
+    fn record_sync_edge(&self, event: &Event) {
+        let EventKind::Custom { name, data } = &event.kind else {
+            return;
+        };
+        if name != SYNC_EVENT_NAME {
+            return;
+        }
+
+        let other = |key: &str| {
+            data.get(key)
+                .and_then(|value| value.as_str())
+                .and_then(|id| Uuid::parse_str(id).ok())
+        };
+        let (before, after, other_id) = if let Some(other_id) = other("synchronizes_with") {
+            (other_id, event.id, other_id)
+        } else if let Some(other_id) = other("edge_to") {
+            (event.id, other_id, other_id)
+        } else {
+            tracing::warn!(
+                "Ignoring {} event {} without a valid synchronizes_with/edge_to event id",
+                SYNC_EVENT_NAME,
+                event.id
+            );
+            return;
+        };
+
+        let mut predecessors = self.sync_edges.entry(after).or_default();
+        if !predecessors.contains(&before) {
+            predecessors.push(before);
+        }
+        drop(predecessors);
+
+        // The other side may live in another trace whose cached races are now stale
+        let other_trace = self
+            .nodes
+            .get(&other_id)
+            .map(|entry| entry.value().1.event.trace_id);
+        if let Some(other_trace) = other_trace {
+            self.invalidate_trace_caches(other_trace);
+        }
+    }
+
+    /// All attempts of the operation `trace_id` belongs to, ordered by attempt.
+    /// Chains (`retry_of` pointing at an earlier retry) resolve to the original trace.
+    pub fn get_trace_lineage(&self, trace_id: Uuid) -> Vec<LineageAttempt> {
+        let origin = self.lineage_origin(trace_id);
+
+        // Snapshot the links first so resolving origins doesn't re-lock shards mid-iteration
+        let links: Vec<(Uuid, (Uuid, u32))> = self
+            .trace_lineage
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+
+        let mut attempts: Vec<LineageAttempt> = links
+            .into_iter()
+            .filter(|(retried, _)| self.lineage_origin(*retried) == origin)
+            .map(|(retried, (retry_of, attempt))| LineageAttempt {
+                trace_id: retried,
+                attempt,
+                retry_of: Some(retry_of),
+            })
+            .collect();
+        attempts.push(LineageAttempt {
+            trace_id: origin,
+            attempt: 1,
+            retry_of: None,
+        });
+
+        attempts.sort_by_key(|attempt| (attempt.attempt, attempt.trace_id));
+        attempts
+    }
+
+    /// Follow `retry_of` links back to the first attempt (guarding against cycles)
+    fn lineage_origin(&self, trace_id: Uuid) -> Uuid {
+        let mut current = trace_id;
+        let mut seen = HashSet::new();
+        while let Some(link) = self.trace_lineage.get(&current) {
+            if !seen.insert(current) {
+                break;
+            }
+            current = link.value().0;
+        }
+        current
+    }
+
     /// Returns true if the graph already contains the specified event
     pub fn contains_event(&self, event_id: Uuid) -> bool {
         self.nodes.contains_key(&event_id)
     }
 
+    /// The event with the given id, if the graph holds it
+    pub fn get_event(&self, event_id: Uuid) -> Option<Event> {
+        self.nodes
+            .get(&event_id)
+            .map(|entry| entry.value().1.event.clone())
+    }
+
     /// Returns true if the graph has seen the given trace
     pub fn has_trace(&self, trace_id: Uuid) -> bool {
         self.trace_roots.contains_key(&trace_id)
@@ -314,7 +769,7 @@ impl CausalGraph {
             return Ok(());
         }
 
-        events.sort_by_key(|event| event.timestamp);
+        sort_events_chronologically(&mut events);
         let mut pending = events;
 
         while !pending.is_empty() {
@@ -347,6 +802,145 @@ impl CausalGraph {
         Ok(())
     }
 
+    /// Add live events whose parents may arrive in a later batch (e.g. a child in
+    /// one `/events` POST and its parent in the next). Children of unknown parents
+    /// wait in a bounded orphan buffer and are linked once the parent is added;
+    /// `expire_orphans` gives up on them and adds them as roots.
+    pub fn add_events_reconciling(&self, events: Vec<Event>) -> Result<()> {
+        let mut events: Vec<Event> = events
+            .into_iter()
+            .filter(|event| !self.contains_event(event.id))
+            .collect();
+        sort_events_chronologically(&mut events);
+
+        // An event waits if its parent is neither in the graph nor arriving now,
+        // or if its parent is itself waiting
+        let batch_ids: HashSet<Uuid> = events.iter().map(|event| event.id).collect();
+        let mut waiting: HashSet<Uuid> = HashSet::new();
+        loop {
+            let before = waiting.len();
+            for event in &events {
+                if let Some(parent_id) = event.parent_id {
+                    if !self.contains_event(parent_id)
+                        && (!batch_ids.contains(&parent_id) || waiting.contains(&parent_id))
+                    {
+                        waiting.insert(event.id);
+                    }
+                }
+            }
+            if waiting.len() == before {
+                break;
+            }
+        }
+
+        let (orphans, ready): (Vec<Event>, Vec<Event>) = events
+            .into_iter()
+            .partition(|event| waiting.contains(&event.id));
+        self.ingest_events(ready)?;
+
+        if !orphans.is_empty() {
+            let now = std::time::Instant::now();
+            let mut buffer = self.orphans.lock().unwrap();
+            for event in orphans {
+                if let Some(parent_id) = event.parent_id {
+                    buffer.entry(parent_id).or_default().push((event, now));
+                }
+            }
+        }
+
+        self.release_orphans()?;
+        self.expire_orphans().map(|_| ())
+    }
+
+    /// Link every buffered orphan whose parent is now in the graph
+    pub fn release_orphans(&self) -> Result<()> {
+        loop {
+            let released: Vec<Event> = {
+                let mut buffer = self.orphans.lock().unwrap();
+                let ready: Vec<Uuid> = buffer
+                    .keys()
+                    .filter(|parent_id| self.contains_event(**parent_id))
+                    .copied()
+                    .collect();
+                ready
+                    .iter()
+                    .filter_map(|parent_id| buffer.remove(parent_id))
+                    .flatten()
+                    .map(|(event, _)| event)
+                    .filter(|event| !self.contains_event(event.id))
+                    .collect()
+            };
+            if released.is_empty() {
+                return Ok(());
+            }
+            self.ingest_events(released)?;
+        }
+    }
+
+    /// Give up on orphans that waited longer than the timeout, or that no longer
+    /// fit in the buffer (oldest first), and add them as roots. Returns how many.
+    pub fn expire_orphans(&self) -> Result<usize> {
+        let expired: Vec<Event> = {
+            let mut buffer = self.orphans.lock().unwrap();
+            if buffer.is_empty() {
+                return Ok(0);
+            }
+
+            let mut waiting: Vec<(Uuid, std::time::Instant)> = buffer
+                .values()
+                .flatten()
+                .map(|(event, arrived)| (event.id, *arrived))
+                .collect();
+            waiting.sort_by_key(|(_, arrived)| *arrived);
+            let overflow = waiting.len().saturating_sub(self.orphan_capacity);
+            let expired_ids: HashSet<Uuid> = waiting
+                .iter()
+                .enumerate()
+                .filter(|(index, (_, arrived))| {
+                    *index < overflow || arrived.elapsed() >= self.orphan_timeout
+                })
+                .map(|(_, (id, _))| *id)
+                .collect();
+            if expired_ids.is_empty() {
+                return Ok(0);
+            }
+
+            let mut expired = Vec::new();
+            buffer.retain(|_, children| {
+                children.retain(|(event, _)| {
+                    if expired_ids.contains(&event.id) {
+                        expired.push(event.clone());
+                        false
+                    } else {
+                        true
+                    }
+                });
+                !children.is_empty()
+            });
+            expired
+        };
+
+        let count = expired.len();
+        tracing::warn!(
+            "Adding {} event(s) whose parent never arrived as trace roots",
+            count
+        );
+        self.ingest_events(expired)?;
+        // Orphans of the expired events can now link to them
+        self.release_orphans()?;
+        Ok(count)
+    }
+
+    /// Returns true if the event is waiting in the orphan buffer for its parent
+    pub fn is_orphan(&self, event_id: Uuid) -> bool {
+        self.orphans
+            .lock()
+            .unwrap()
+            .values()
+            .flatten()
+            .any(|(event, _)| event.id == event_id)
+    }
+
     /// Construct a new causal graph by replaying the provided events.
     pub fn from_events(events: Vec<Event>) -> Result<Self> {
         let graph = Self::new();
@@ -368,19 +962,38 @@ impl CausalGraph {
     }
 
     /// Add distributed edges from DistributedEdge records
-    /// This connects events across services based on span relationships
+    /// This connects events across services based on span relationships.
+    /// Idempotent: re-applying an edge (warmup replay, SDK retry) adds nothing.
     pub fn add_distributed_edges(&self, dist_edges: Vec<crate::event::DistributedEdge>) {
         for edge in dist_edges {
+            let key = (edge.from_span.clone(), edge.to_span.clone());
+            if self.applied_distributed_edges.contains_key(&key) {
+                tracing::debug!(
+                    "Skipping already applied distributed edge: {} -> {}",
+                    edge.from_span,
+                    edge.to_span
+                );
+                continue;
+            }
+
             // Find the upstream and downstream events by their span IDs
             let upstream_event_id = self.find_event_by_span(&edge.from_span);
             let downstream_event_id = self.find_event_by_span(&edge.to_span);
 
             if let (Some(up_id), Some(down_id)) = (upstream_event_id, downstream_event_id) {
+                self.applied_distributed_edges.insert(key, (up_id, down_id));
+                for event_id in [up_id, down_id] {
+                    if let Some(entry) = self.nodes.get(&event_id) {
+                        let trace_id = entry.value().1.event.trace_id;
+                        self.changed_traces.lock().unwrap().insert(trace_id);
+                    }
+                }
+
                 // Add to distributed_edges map
-                self.distributed_edges
-                    .entry(down_id)
-                    .or_default()
-                    .push(up_id);
+                let mut upstreams = self.distributed_edges.entry(down_id).or_default();
+                if !upstreams.contains(&up_id) {
+                    upstreams.push(up_id);
+                }
 
                 tracing::debug!(
                     "Added distributed edge: {} (span {}) -> {} (span {})",
@@ -457,86 +1070,347 @@ impl CausalGraph {
         }
     }
 
+    /// Races in a trace, each scored by `race_severity`
+    pub fn find_races_with_severity(&self, trace_id: Uuid) -> Result<Vec<ScoredRace>> {
+        let pairs = self.find_concurrent_events(trace_id)?;
+
+        let mut writers: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for event in pairs.iter().flat_map(|(event1, event2)| [event1, event2]) {
+            if let EventKind::StateChange {
+                variable,
+                access_type,
+                ..
+            } = &event.kind
+            {
+                if access_type.is_write() {
+                    writers
+                        .entry(variable)
+                        .or_default()
+                        .insert(&event.metadata.thread_id);
+                }
+            }
+        }
+
+        let races = pairs
+            .iter()
+            .filter_map(|(event1, event2)| match (&event1.kind, &event2.kind) {
+                (
+                    EventKind::StateChange {
+                        variable: var1,
+                        new_value: value1,
+                        access_type: access1,
+                        ..
+                    },
+                    EventKind::StateChange {
+                        variable: var2,
+                        new_value: value2,
+                        access_type: access2,
+                        ..
+                    },
+                ) if var1 == var2 => {
+                    let values_diverge =
+                        access1.is_write() && access2.is_write() && value1 != value2;
+                    let concurrent_writers = writers.get(var1.as_str()).map_or(0, HashSet::len);
+                    Some(ScoredRace {
+                        event1: event1.clone(),
+                        event2: event2.clone(),
+                        severity: race_severity(
+                            *access1,
+                            *access2,
+                            values_diverge,
+                            concurrent_writers,
+                        ),
+                        concurrent_writers,
+                        values_diverge,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        Ok(races)
+    }
+
     /// Find all concurrent events (potential race conditions)
     /// Uses variable index for O(m * k²) complexity instead of O(n²)
     /// where m = number of variables, k = avg accesses per variable
     pub fn find_concurrent_events(&self, trace_id: Uuid) -> Result<Vec<(Event, Event)>> {
+        Ok(self.find_concurrent_events_with_truncation(trace_id)?.0)
+    }
+
+    /// `find_concurrent_events`, plus whether the pairs stopped at
+    /// `max_pairs_per_trace`. Pairs are collected in order of their earlier
+    /// event, then their later one, both chronological, so a capped result is
+    /// always the same first pairs of the trace.
+    pub fn find_concurrent_events_with_truncation(
+        &self,
+        trace_id: Uuid,
+    ) -> Result<(Vec<(Event, Event)>, bool)> {
         // Check cache first
         if let Some(cached) = self.get_cached_concurrent(trace_id) {
             return Ok(cached);
         }
 
         let mut concurrent_pairs = Vec::new();
+        let mut truncated = false;
 
-        let events = self.get_causal_order(trace_id)?;
+        let mut events = self.get_causal_order(trace_id)?;
+        sort_events_chronologically(&mut events);
+        let stale_locks = unreleased_locks(&events);
         let mut per_variable: HashMap<String, Vec<Event>> = HashMap::new();
+        // Every state change as (variable, index in its accesses), chronologically
+        let mut accesses_in_order = Vec::new();
 
         for event in events.into_iter() {
             if let EventKind::StateChange { variable, .. } = &event.kind {
-                per_variable
-                    .entry(variable.clone())
-                    .or_default()
-                    .push(event);
+                let accesses = per_variable.entry(variable.clone()).or_default();
+                accesses_in_order.push((variable.clone(), accesses.len()));
+                accesses.push(event);
             }
         }
 
-        for trace_events in per_variable.values_mut() {
-            trace_events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-
-            for i in 0..trace_events.len() {
-                for j in (i + 1)..trace_events.len() {
-                    if let (
-                        EventKind::StateChange {
-                            access_type: access1,
-                            ..
-                        },
-                        EventKind::StateChange {
-                            access_type: access2,
-                            ..
-                        },
-                    ) = (&trace_events[i].kind, &trace_events[j].kind)
-                    {
-                        // Skip safe access patterns
-                        if self.is_safe_access_pattern(*access1, *access2) {
-                            continue;
-                        }
-
-                        // Different threads
-                        if trace_events[i].metadata.thread_id != trace_events[j].metadata.thread_id
-                        {
-                            // Use vector clocks for happens-before check
-                            if !self.happens_before_vc(&trace_events[i], &trace_events[j])
-                                && !self.happens_before_vc(&trace_events[j], &trace_events[i])
-                            {
-                                // Check if accesses were protected by the same lock
-                                if !self.protected_by_same_lock(&trace_events[i], &trace_events[j])
-                                {
-                                    concurrent_pairs
-                                        .push((trace_events[i].clone(), trace_events[j].clone()));
-                                }
-                            }
-                        }
-                    }
+        // Each variable's accesses are already chronological, as `events` was
+        'accesses: for (variable, i) in &accesses_in_order {
+            let trace_events = &per_variable[variable];
+            for later in &trace_events[i + 1..] {
+                if !self.is_concurrent_access(variable, &trace_events[*i], later, &stale_locks) {
+                    continue;
                 }
+                if self
+                    .max_pairs_per_trace
+                    .is_some_and(|max_pairs| concurrent_pairs.len() >= max_pairs)
+                {
+                    truncated = true;
+                    break 'accesses;
+                }
+                concurrent_pairs.push((trace_events[*i].clone(), later.clone()));
             }
         }
 
         // Store in cache unless we saw no relevant state changes (trace may still be ingesting)
         if !per_variable.is_empty() {
-            self.cache_concurrent(trace_id, concurrent_pairs.clone());
+            self.cache_concurrent(
+                trace_id,
+                ConcurrentPairsCache {
+                    pairs: concurrent_pairs.clone(),
+                    truncated,
+                    accesses: per_variable,
+                    stale_locks,
+                },
+            );
         }
 
-        Ok(concurrent_pairs)
+        Ok((concurrent_pairs, truncated))
     }
 
-    fn get_cached_concurrent(&self, trace_id: Uuid) -> Option<Vec<(Event, Event)>> {
-        let mut cache = self.analysis_cache.lock().unwrap();
-        cache.get(&trace_id).cloned()
-    }
+    /// Whether two accesses to `variable`, `earlier` sorting first, race: an
+    /// unsafe access pair on different threads, unordered by their clocks, with
+    /// no common lock and (if required) overlapping in time
+    fn is_concurrent_access(
+        &self,
+        variable: &str,
+        earlier: &Event,
+        later: &Event,
+        stale_locks: &[UnreleasedLock],
+    ) -> bool {
+        let (
+            EventKind::StateChange {
+                access_type: access1,
+                ..
+            },
+            EventKind::StateChange {
+                access_type: access2,
+                ..
+            },
+        ) = (&earlier.kind, &later.kind)
+        else {
+            return false;
+        };
 
-    fn cache_concurrent(&self, trace_id: Uuid, pairs: Vec<(Event, Event)>) {
-        let mut cache = self.analysis_cache.lock().unwrap();
-        cache.put(trace_id, pairs);
+        // Skip safe access patterns
+        if self.is_safe_access_pattern(*access1, *access2)
+            || self.is_declared_safe(variable, *access1, *access2)
+        {
+            return false;
+        }
+
+        // Different threads, unordered by vector clocks, not protected by the
+        // same lock
+        earlier.metadata.thread_id != later.metadata.thread_id
+            && !self.happens_before_vc(earlier, later)
+            && !self.happens_before_vc(later, earlier)
+            && !self.protected_by_same_lock(earlier, later, stale_locks)
+            && self.overlaps_in_time(earlier, later)
+    }
+
+    /// Whether `a` happens before `b`, `b` before `a`, or neither. Events of
+    /// different traces have unrelated vector clocks, so they're only ordered
+    /// when a path of parent, distributed or synchronization edges links them.
+    pub fn event_ordering(&self, a: Uuid, b: Uuid) -> Result<EventOrdering> {
+        let lookup = |id: Uuid| {
+            self.get_event(id)
+                .ok_or_else(|| anyhow!("Event {} not found", id))
+        };
+        let event_a = lookup(a)?;
+        let event_b = lookup(b)?;
+
+        let (a_before_b, b_before_a) = if event_a.trace_id == event_b.trace_id {
+            (
+                self.happens_before_vc(&event_a, &event_b),
+                self.happens_before_vc(&event_b, &event_a),
+            )
+        } else {
+            (self.is_ancestor(a, b), self.is_ancestor(b, a))
+        };
+        Ok(EventOrdering {
+            a_before_b,
+            b_before_a,
+            concurrent: !a_before_b && !b_before_a,
+        })
+    }
+
+    /// Explain why two events of a trace are or aren't reported as a race.
+    /// Runs the same checks as `find_concurrent_events`, in the same order,
+    /// and records each intermediate result instead of stopping at the first.
+    pub fn explain_pair(&self, trace_id: Uuid, a: Uuid, b: Uuid) -> Result<RaceExplanation> {
+        let lookup = |id: Uuid| -> Result<Event> {
+            let event = self
+                .nodes
+                .get(&id)
+                .map(|entry| entry.value().1.event.clone())
+                .ok_or_else(|| anyhow!("Event {} not found", id))?;
+            if event.trace_id != trace_id {
+                return Err(anyhow!(
+                    "Event {} does not belong to trace {}",
+                    id,
+                    trace_id
+                ));
+            }
+            Ok(event)
+        };
+        let event_a = lookup(a)?;
+        let event_b = lookup(b)?;
+
+        let access = |event: &Event| match &event.kind {
+            EventKind::StateChange {
+                variable,
+                access_type,
+                ..
+            } => Some((variable.clone(), *access_type)),
+            _ => None,
+        };
+        let access_a = access(&event_a);
+        let access_b = access(&event_b);
+
+        let same_variable = matches!(
+            (&access_a, &access_b),
+            (Some((var_a, _)), Some((var_b, _))) if var_a == var_b
+        );
+        let safe_access_pattern = match (&access_a, &access_b) {
+            (Some((variable, access1)), Some((_, access2))) if same_variable => {
+                self.is_safe_access_pattern(*access1, *access2)
+                    || self.is_declared_safe(variable, *access1, *access2)
+            }
+            _ => false,
+        };
+        let different_thread = event_a.metadata.thread_id != event_b.metadata.thread_id;
+
+        let a_happens_before_b = self.happens_before_vc(&event_a, &event_b);
+        let b_happens_before_a = self.happens_before_vc(&event_b, &event_a);
+        let concurrent = !a_happens_before_b && !b_happens_before_a;
+
+        let mut components: BTreeSet<&str> = BTreeSet::new();
+        components.extend(event_a.causality_vector.iter().map(|(c, _)| c.as_str()));
+        components.extend(event_b.causality_vector.iter().map(|(c, _)| c.as_str()));
+        let clock_of = |event: &Event, component: &str| {
+            event
+                .causality_vector
+                .iter()
+                .find(|(c, _)| c == component)
+                .map(|(_, clock)| *clock)
+        };
+        let vector_clocks = components
+            .into_iter()
+            .map(|component| ClockComponent {
+                component: component.to_string(),
+                a: clock_of(&event_a, component),
+                b: clock_of(&event_b, component),
+            })
+            .collect();
+
+        let stale_locks = self.find_unreleased_locks(trace_id)?;
+        let held_a = held_locks(&event_a, &stale_locks);
+        let held_b = held_locks(&event_b, &stale_locks);
+        let mut shared_locks: Vec<String> = held_a
+            .iter()
+            .filter(|lock| held_b.contains(*lock))
+            .map(|lock| lock.to_string())
+            .collect();
+        shared_locks.sort();
+        // Different members of one lock family count as the family itself
+        if shared_locks.is_empty() && same_variable {
+            if let Some(family) = self.shared_lock_family(&event_a, &held_a, &held_b) {
+                shared_locks.push(family.lock_glob.clone());
+            }
+        }
+        let overlaps_in_time = self.overlaps_in_time(&event_a, &event_b);
+
+        let reason = if !same_variable {
+            "Events do not access the same variable".to_string()
+        } else if safe_access_pattern {
+            "Access types form a safe pattern".to_string()
+        } else if !different_thread {
+            "Both events ran on the same thread".to_string()
+        } else if a_happens_before_b {
+            "Event a happens-before event b".to_string()
+        } else if b_happens_before_a {
+            "Event b happens-before event a".to_string()
+        } else if !shared_locks.is_empty() {
+            format!("Both accesses held lock {}", shared_locks.join(", "))
+        } else if !overlaps_in_time {
+            "Accesses are too far apart in time".to_string()
+        } else {
+            "No shared lock and concurrent vector clocks".to_string()
+        };
+        let race = same_variable
+            && !safe_access_pattern
+            && different_thread
+            && concurrent
+            && shared_locks.is_empty()
+            && overlaps_in_time;
+
+        Ok(RaceExplanation {
+            trace_id,
+            event_a: a,
+            event_b: b,
+            variable_a: access_a.map(|(variable, _)| variable),
+            variable_b: access_b.map(|(variable, _)| variable),
+            same_variable,
+            safe_access_pattern,
+            different_thread,
+            a_happens_before_b,
+            b_happens_before_a,
+            concurrent,
+            vector_clocks,
+            locks_a: event_a.lock_set.clone(),
+            locks_b: event_b.lock_set.clone(),
+            shared_locks,
+            overlaps_in_time,
+            race,
+            reason,
+        })
+    }
+
+    fn get_cached_concurrent(&self, trace_id: Uuid) -> Option<(Vec<(Event, Event)>, bool)> {
+        let mut cache = self.analysis_cache.lock().unwrap();
+        cache
+            .get(&trace_id)
+            .map(|cached| (cached.pairs.clone(), cached.truncated))
+    }
+
+    fn cache_concurrent(&self, trace_id: Uuid, cached: ConcurrentPairsCache) {
+        let mut cache = self.analysis_cache.lock().unwrap();
+        cache.put(trace_id, cached);
     }
 
     fn get_cached_anomalies(&self, trace_id: Uuid) -> Option<Vec<Anomaly>> {
@@ -549,7 +1423,90 @@ impl CausalGraph {
         cache.put(trace_id, anomalies);
     }
 
+    /// Fold a newly added event into its trace's caches. A state change is
+    /// compared only against earlier accesses to the same variable and its
+    /// races appended to the cached pairs; an event that can change existing
+    /// pairs (a lock event, a sync edge, a parent in another trace, the late
+    /// parent of a stand-in root) drops them so the next query recomputes.
+    fn update_trace_caches(&self, event: &Event) {
+        let trace_id = event.trace_id;
+        self.changed_traces.lock().unwrap().insert(trace_id);
+        self.anomaly_cache.lock().unwrap().pop(&trace_id);
+        self.baselines_updated.remove(&trace_id);
+
+        let parent_in_other_trace = event.parent_id.is_some_and(|parent_id| {
+            self.nodes
+                .get(&parent_id)
+                .is_some_and(|entry| entry.value().1.event.trace_id != trace_id)
+        });
+        // A late parent gives a stand-in root new ancestors
+        let adopts_stand_in_root = self.trace_roots.get(&trace_id).is_some_and(|roots| {
+            roots.iter().any(|root_id| {
+                self.nodes
+                    .get(root_id)
+                    .is_some_and(|entry| entry.value().1.event.parent_id == Some(event.id))
+            })
+        });
+
+        let mut cache = self.analysis_cache.lock().unwrap();
+        let Some(cached) = cache.peek_mut(&trace_id) else {
+            return;
+        };
+
+        match &event.kind {
+            _ if parent_in_other_trace || adopts_stand_in_root => {
+                cache.pop(&trace_id);
+            }
+            EventKind::StateChange { variable, .. } => {
+                let ConcurrentPairsCache {
+                    pairs,
+                    truncated,
+                    accesses,
+                    stale_locks,
+                } = cached;
+                let accesses = accesses.entry(variable.clone()).or_default();
+                accesses.push(event.clone());
+                sort_events_chronologically(accesses);
+
+                let position = accesses
+                    .iter()
+                    .position(|access| access.id == event.id)
+                    .expect("event was just added");
+                let mut new_pairs = Vec::new();
+                for (i, other) in accesses.iter().enumerate() {
+                    let (earlier, later) = match i.cmp(&position) {
+                        std::cmp::Ordering::Less => (other, &accesses[position]),
+                        std::cmp::Ordering::Greater => (&accesses[position], other),
+                        std::cmp::Ordering::Equal => continue,
+                    };
+                    if self.is_concurrent_access(variable, earlier, later, stale_locks) {
+                        new_pairs.push((earlier.clone(), later.clone()));
+                    }
+                }
+
+                // Which pairs make the cut depends on where the new ones sort,
+                // so a capped trace recomputes rather than appending
+                let over_cap = self
+                    .max_pairs_per_trace
+                    .is_some_and(|max_pairs| pairs.len() + new_pairs.len() > max_pairs);
+                if !new_pairs.is_empty() && (*truncated || over_cap) {
+                    cache.pop(&trace_id);
+                } else {
+                    pairs.extend(new_pairs);
+                }
+            }
+            EventKind::LockAcquire { .. } | EventKind::LockRelease { .. } => {
+                cache.pop(&trace_id);
+            }
+            EventKind::Custom { name, .. } if name == SYNC_EVENT_NAME => {
+                cache.pop(&trace_id);
+            }
+            _ => {}
+        }
+    }
+
     fn invalidate_trace_caches(&self, trace_id: Uuid) {
+        self.changed_traces.lock().unwrap().insert(trace_id);
         self.analysis_cache.lock().unwrap().pop(&trace_id);
         self.anomaly_cache.lock().unwrap().pop(&trace_id);
         self.baselines_updated.remove(&trace_id);
@@ -559,6 +1516,11 @@ impl CausalGraph {
     /// This is more precise than graph paths as it captures all causal relationships
     /// Vector clocks use trace IDs (not thread IDs) to handle async task migration
     fn happens_before_vc(&self, event1: &Event, event2: &Event) -> bool {
+        // User-declared synchronization orders events the clocks can't see
+        if !self.sync_edges.is_empty() && self.is_ancestor(event1.id, event2.id) {
+            return true;
+        }
+
         // event1 -> event2 if for all traces in VC1:
         // VC1[trace] <= VC2[trace]
         // AND there exists at least one trace where VC1[trace] < VC2[trace]
@@ -620,7 +1582,7 @@ impl CausalGraph {
     }
 
     /// Check if ancestor_id is an ancestor of descendant_id in the parent chain
-    fn is_ancestor(&self, ancestor_id: Uuid, descendant_id: Uuid) -> bool {
+    pub fn is_ancestor(&self, ancestor_id: Uuid, descendant_id: Uuid) -> bool {
         if ancestor_id == descendant_id {
             return false; // An event is not its own ancestor
         }
@@ -657,43 +1619,494 @@ impl CausalGraph {
                     to_visit.push(upstream_id);
                 }
             }
+
+            // Check user-declared synchronization edges
+            if let Some(predecessors) = self.sync_edges.get(&current_id) {
+                for &predecessor_id in predecessors.value() {
+                    if predecessor_id == ancestor_id {
+                        return true;
+                    }
+                    to_visit.push(predecessor_id);
+                }
+            }
         }
 
         false
     }
 
     /// Determine if two access types form a safe (non-racing) pattern
-    /// This implements the rules from C++11/Rust memory model
+    /// The default policy implements the rules from C++11/Rust memory model
     fn is_safe_access_pattern(&self, access1: AccessType, access2: AccessType) -> bool {
-        use AccessType::*;
+        self.race_policy.is_safe(access1, access2)
+    }
+
+    fn protected_by_same_lock(
+        &self,
+        event1: &Event,
+        event2: &Event,
+        stale_locks: &[UnreleasedLock],
+    ) -> bool {
+        // Use the lock sets that were captured when the events occurred
+        let set1 = held_locks(event1, stale_locks);
+        let set2 = held_locks(event2, stale_locks);
 
-        match (access1, access2) {
-            // Read-Read is always safe (even non-atomic)
-            (Read, Read) => true,
+        // If they share any locks, they're protected from racing
+        // This is correct because if both events held the same lock,
+        // they must have executed in some serial order
+        !set1.is_disjoint(&set2) || self.shared_lock_family(event1, &set1, &set2).is_some()
+    }
 
-            // Atomic reads can race with each other safely
-            (AtomicRead, AtomicRead) => true,
+    /// A declared lock family covering the accessed variable with a member held
+    /// on each side, e.g. two different stripes of a striped lock
+    fn shared_lock_family(
+        &self,
+        event: &Event,
+        held1: &HashSet<&String>,
+        held2: &HashSet<&String>,
+    ) -> Option<&LockFamily> {
+        let EventKind::StateChange { variable, .. } = &event.kind else {
+            return None;
+        };
+        self.lock_families.iter().find(|family| {
+            family.covers_variable(variable)
+                && held1.iter().any(|lock| family.contains_lock(lock))
+                && held2.iter().any(|lock| family.contains_lock(lock))
+        })
+    }
 
-            // Atomic RMW operations synchronize with each other
-            // (they have implicit ordering semantics)
-            (AtomicRMW, AtomicRMW) => false, // Still need to check - they can race
+    /// Check the user-declared safe patterns from `race_detection.safe_patterns`
+    fn is_declared_safe(&self, variable: &str, access1: AccessType, access2: AccessType) -> bool {
+        self.safe_patterns
+            .iter()
+            .any(|pattern| pattern.matches(variable, access1, access2))
+    }
 
-            // All other combinations need further checking
-            _ => false,
+    /// With `require_temporal_overlap`, check that the accesses' `[timestamp, timestamp + duration]`
+    /// intervals overlap or are within the configured gap. Always true when the mode is off.
+    fn overlaps_in_time(&self, event1: &Event, event2: &Event) -> bool {
+        let Some(max_gap) = self.temporal_overlap_gap else {
+            return true;
+        };
+
+        let end = |event: &Event| {
+            let duration_ns = event.metadata.duration_ns.unwrap_or(0).min(i64::MAX as u64);
+            event.timestamp + chrono::Duration::nanoseconds(duration_ns as i64)
+        };
+
+        // Gap is positive when the intervals are disjoint, negative when they overlap
+        let gap = (event2.timestamp - end(event1)).max(event1.timestamp - end(event2));
+        gap <= max_gap
+    }
+
+    /// Suggest how a variable should be synchronized, based on its observed lock usage.
+    /// If no access ever held a lock, recommends guarding it with a mutex; otherwise
+    /// recommends using the most common lock consistently. Only accesses from
+    /// `trace_ids` count: the race's trace and any traces merged into it.
+    pub fn lock_recommendation(&self, variable: &str, trace_ids: &HashSet<Uuid>) -> Option<String> {
+        let event_ids = self.variable_index.get(variable)?.value().clone();
+
+        let mut total = 0usize;
+        let mut unlocked = 0usize;
+        let mut threads = HashSet::new();
+        let mut services = HashSet::new();
+        let mut lock_counts: HashMap<String, usize> = HashMap::new();
+
+        for event_id in event_ids {
+            let Some(entry) = self.nodes.get(&event_id) else {
+                continue;
+            };
+            let event = &entry.value().1.event;
+            if !trace_ids.contains(&event.trace_id) {
+                continue;
+            }
+            total += 1;
+            threads.insert(event.metadata.thread_id.clone());
+            services.insert(event.metadata.service_name.clone());
+            if event.lock_set.is_empty() {
+                unlocked += 1;
+            }
+            for lock_id in event.lock_set.iter().collect::<HashSet<_>>() {
+                *lock_counts.entry(lock_id.clone()).or_insert(0) += 1;
+            }
+        }
+
+        if total == 0 {
+            return None;
+        }
+
+        // Most frequently held lock; ties broken by name for stable output
+        let best_lock = lock_counts
+            .into_iter()
+            .max_by(|(a_id, a_count), (b_id, b_count)| {
+                a_count.cmp(b_count).then_with(|| b_id.cmp(a_id))
+            });
+
+        match best_lock {
+            None => Some(format!(
+                "guard `{}` with a mutex; it is accessed by {} across {} with no common lock",
+                variable,
+                pluralize(threads.len(), "thread"),
+                pluralize(services.len(), "service")
+            )),
+            Some((lock_id, held)) => {
+                let missing = total - held;
+                if missing == 0 {
+                    return None;
+                }
+                let verb = if missing == unlocked {
+                    "were unlocked"
+                } else {
+                    "did not hold it"
+                };
+                Some(format!(
+                    "use lock `{}` consistently — {} of {} accesses {}",
+                    lock_id, missing, total, verb
+                ))
+            }
         }
     }
 
-    /// Check if two events were protected by the same lock
-    /// Uses the historical lock sets captured at the time of each event
-    fn protected_by_same_lock(&self, event1: &Event, event2: &Event) -> bool {
-        // Use the lock sets that were captured when the events occurred
-        let set1: HashSet<_> = event1.lock_set.iter().collect();
-        let set2: HashSet<_> = event2.lock_set.iter().collect();
+    /// Locks acquired and never released (or released more often than acquired)
+    /// per thread in a trace. An orphaned acquire usually means the thread
+    /// returned or failed inside its critical section.
+    pub fn find_unreleased_locks(&self, trace_id: Uuid) -> Result<Vec<UnreleasedLock>> {
+        let mut events = self.get_causal_order(trace_id)?;
+        sort_events_chronologically(&mut events);
+        Ok(unreleased_locks(&events))
+    }
 
-        // If they share any locks, they're protected from racing
-        // This is correct because if both events held the same lock,
-        // they must have executed in some serial order
-        !set1.is_disjoint(&set2)
+    /// Find double-checked locking on non-atomic variables: on one thread, a
+    /// read with no lock held, immediately followed by a lock acquire and a
+    /// re-read of the same variable before that lock is released. The outer
+    /// read races with the locked write it is meant to skip.
+    pub fn find_double_checked_locking(&self, trace_id: Uuid) -> Result<Vec<DoubleCheckedLocking>> {
+        let mut events = self.get_causal_order(trace_id)?;
+        sort_events_chronologically(&mut events);
+
+        let mut by_thread: HashMap<&str, Vec<&Event>> = HashMap::new();
+        for event in &events {
+            by_thread
+                .entry(event.metadata.thread_id.as_str())
+                .or_default()
+                .push(event);
+        }
+
+        let mut findings = Vec::new();
+        for (thread_id, thread_events) in by_thread {
+            for (idx, outer) in thread_events.iter().enumerate() {
+                let EventKind::StateChange {
+                    variable,
+                    location,
+                    access_type: AccessType::Read,
+                    ..
+                } = &outer.kind
+                else {
+                    continue;
+                };
+                if !outer.lock_set.is_empty() {
+                    continue;
+                }
+                let Some(EventKind::LockAcquire { lock_id, .. }) =
+                    thread_events.get(idx + 1).map(|event| &event.kind)
+                else {
+                    continue;
+                };
+
+                let locked_read = thread_events[idx + 2..]
+                    .iter()
+                    .take_while(|event| {
+                        !matches!(&event.kind, EventKind::LockRelease { lock_id: released, .. } if released == lock_id)
+                    })
+                    .find(|event| {
+                        matches!(
+                            &event.kind,
+                            EventKind::StateChange { variable: inner, access_type, .. }
+                                if inner == variable
+                                    && matches!(access_type, AccessType::Read | AccessType::AtomicRead)
+                        ) && event.lock_set.contains(lock_id)
+                    });
+
+                if let Some(inner) = locked_read {
+                    findings.push(DoubleCheckedLocking {
+                        variable: variable.clone(),
+                        thread_id: thread_id.to_string(),
+                        lock_id: lock_id.clone(),
+                        unlocked_read: outer.id,
+                        locked_read: inner.id,
+                        location: location.clone(),
+                        severity: AnomalySeverity::Warning,
+                        description: format!(
+                            "`{}` is checked without a lock at {}, then re-checked under `{}`; the unlocked read races with writers unless `{}` is atomic",
+                            variable, location, lock_id, variable
+                        ),
+                    });
+                }
+            }
+        }
+
+        findings.sort_by_key(|finding| (finding.variable.clone(), finding.unlocked_read));
+        Ok(findings)
+    }
+
+    /// Build a lock-ordering graph across all traces, with an edge A→B whenever
+    /// a thread acquires B while holding A, and report each cycle as a potential
+    /// deadlock. Every cycle is reported once, from its lexicographically
+    /// smallest edge.
+    pub fn detect_lock_order_inversions(&self) -> Vec<LockOrderViolation> {
+        // Earliest observation of each ordering
+        let mut orderings: BTreeMap<(String, String), (DateTime<Utc>, LockOrderObservation)> =
+            BTreeMap::new();
+        for entry in self.nodes.iter() {
+            let event = &entry.value().1.event;
+            let EventKind::LockAcquire { lock_id, .. } = &event.kind else {
+                continue;
+            };
+            for held in event.lock_set.iter().filter(|held| *held != lock_id) {
+                let key = (held.clone(), lock_id.clone());
+                if orderings
+                    .get(&key)
+                    .is_some_and(|(seen_at, _)| *seen_at <= event.timestamp)
+                {
+                    continue;
+                }
+                orderings.insert(
+                    key,
+                    (
+                        event.timestamp,
+                        LockOrderObservation {
+                            held: held.clone(),
+                            acquired: lock_id.clone(),
+                            thread_id: event.metadata.thread_id.clone(),
+                            trace_id: event.trace_id,
+                            event_id: event.id,
+                        },
+                    ),
+                );
+            }
+        }
+
+        let mut successors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (held, acquired) in orderings.keys() {
+            successors.entry(held).or_default().push(acquired);
+        }
+
+        let mut reported: HashSet<Vec<(String, String)>> = HashSet::new();
+        let mut violations = Vec::new();
+        for ((lock_a, lock_b), (_, a_then_b)) in &orderings {
+            let Some(path) = shortest_lock_path(&successors, lock_b, lock_a) else {
+                continue;
+            };
+
+            let mut cycle: Vec<(String, String)> = path
+                .windows(2)
+                .map(|hop| (hop[0].to_string(), hop[1].to_string()))
+                .collect();
+            cycle.push((lock_a.clone(), lock_b.clone()));
+            cycle.sort();
+            if !reported.insert(cycle) {
+                continue;
+            }
+
+            violations.push(LockOrderViolation {
+                lock_a: lock_a.clone(),
+                lock_b: lock_b.clone(),
+                a_then_b: a_then_b.clone(),
+                b_then_a: path
+                    .windows(2)
+                    .map(|hop| {
+                        orderings[&(hop[0].to_string(), hop[1].to_string())]
+                            .1
+                            .clone()
+                    })
+                    .collect(),
+            });
+        }
+
+        violations
+    }
+
+    /// Per-lock acquisition counts and hold times across all traces, most total
+    /// hold time first. A release pairs with the most recent open acquire of the
+    /// same lock on the same thread and trace.
+    pub fn lock_contention_stats(&self) -> Vec<LockStats> {
+        let lock_events = self.all_lock_events();
+
+        #[derive(Default)]
+        struct Accumulator<'a> {
+            acquisitions: usize,
+            threads: HashSet<&'a str>,
+            traces: HashSet<Uuid>,
+            holds_ms: Vec<f64>,
+        }
+
+        let mut locks: BTreeMap<&str, Accumulator> = BTreeMap::new();
+        for event in &lock_events {
+            if let EventKind::LockAcquire { lock_id, .. } = &event.kind {
+                let stats = locks.entry(lock_id).or_default();
+                stats.acquisitions += 1;
+                stats.threads.insert(&event.metadata.thread_id);
+                stats.traces.insert(event.trace_id);
+            }
+        }
+        for hold in lock_holds(&lock_events) {
+            if let Some(lock) = locks.get_mut(hold.lock_id) {
+                lock.holds_ms.push(hold.held_ms);
+            }
+        }
+
+        let mut stats: Vec<LockStats> = locks
+            .into_iter()
+            .map(|(lock_id, lock)| {
+                let total_hold_ms: f64 = lock.holds_ms.iter().sum();
+                LockStats {
+                    lock_id: lock_id.to_string(),
+                    acquisitions: lock.acquisitions,
+                    thread_count: lock.threads.len(),
+                    trace_count: lock.traces.len(),
+                    total_hold_ms,
+                    mean_hold_ms: if lock.holds_ms.is_empty() {
+                        0.0
+                    } else {
+                        total_hold_ms / lock.holds_ms.len() as f64
+                    },
+                    max_hold_ms: lock.holds_ms.iter().copied().fold(0.0, f64::max),
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| b.total_hold_ms.total_cmp(&a.total_hold_ms));
+        stats
+    }
+
+    /// Holds in `trace_id` that lasted abnormally long for their lock. Each hold
+    /// is compared against every other completed hold of the same `lock_id`
+    /// across all traces, with the same sigma test (and minimum of 5 samples) as
+    /// duration anomalies. Reported with `event_kind` set to the lock id.
+    pub fn detect_lock_hold_anomalies(&self, trace_id: Uuid) -> Result<Vec<Anomaly>> {
+        if !self.has_trace(trace_id) {
+            return Err(anyhow!("Trace not found: {}", trace_id));
+        }
+
+        let lock_events = self.all_lock_events();
+        let holds = lock_holds(&lock_events);
+
+        // (count, sum, sum of squares) of every hold per lock
+        let mut totals: HashMap<&str, (usize, f64, f64)> = HashMap::new();
+        for hold in &holds {
+            let total = totals.entry(hold.lock_id).or_default();
+            total.0 += 1;
+            total.1 += hold.held_ms;
+            total.2 += hold.held_ms * hold.held_ms;
+        }
+
+        let mut anomalies = Vec::new();
+        for hold in holds
+            .iter()
+            .filter(|hold| hold.acquire.trace_id == trace_id)
+        {
+            // Baseline of the lock's other holds, so an outlier can't mask itself
+            let (count, sum, sum_sq) = totals[hold.lock_id];
+            let count = count - 1;
+            if count < 5 {
+                continue;
+            }
+            let mean = (sum - hold.held_ms) / count as f64;
+            let variance =
+                ((sum_sq - hold.held_ms * hold.held_ms) / count as f64 - mean * mean).max(0.0);
+            let std_dev = variance.sqrt();
+
+            let (std_dev_from_mean, is_anomaly) = sigma_from_mean(hold.held_ms, mean, std_dev);
+            if !is_anomaly || hold.held_ms <= mean {
+                continue;
+            }
+
+            let acquire = hold.acquire;
+            anomalies.push(Anomaly {
+                event_id: acquire.id.to_string(),
+                event_kind: hold.lock_id.to_string(),
+                severity: sigma_severity(std_dev_from_mean),
+                actual_duration_ms: hold.held_ms,
+                expected_duration_ms: mean,
+                std_dev_from_mean,
+                description: format!(
+                    "Lock {} held for {:.2}ms (expected {:.2}ms ± {:.2}ms, {:.1}σ from mean)",
+                    hold.lock_id, hold.held_ms, mean, std_dev, std_dev_from_mean
+                ),
+                location: self.get_event_location(acquire),
+                timestamp: acquire.timestamp,
+                service_name: acquire.metadata.service_name.clone(),
+            });
+        }
+
+        Ok(anomalies)
+    }
+
+    /// Every lock acquire and release in the graph, in chronological order
+    fn all_lock_events(&self) -> Vec<Event> {
+        let mut lock_events: Vec<Event> = self
+            .nodes
+            .iter()
+            .map(|entry| entry.value().1.event.clone())
+            .filter(|event| {
+                matches!(
+                    event.kind,
+                    EventKind::LockAcquire { .. } | EventKind::LockRelease { .. }
+                )
+            })
+            .collect();
+        sort_events_chronologically(&mut lock_events);
+        lock_events
+    }
+
+    /// The `top_n` most accessed variables across all traces, ranked by access
+    /// count and then by how many traces touched them. Only the events listed in
+    /// `variable_index` are read, so this never scans the whole graph. With
+    /// `environment`, only accesses from that environment count.
+    pub fn variable_hotspots(
+        &self,
+        top_n: usize,
+        environment: Option<&str>,
+    ) -> Vec<VariableHotspot> {
+        let mut hotspots: Vec<VariableHotspot> = self
+            .variable_index
+            .iter()
+            .filter_map(|entry| {
+                let mut traces = HashSet::new();
+                let mut accesses_by_service: BTreeMap<String, usize> = BTreeMap::new();
+                for event_id in entry.value() {
+                    let Some(node) = self.nodes.get(event_id) else {
+                        continue;
+                    };
+                    let event = &node.value().1.event;
+                    if environment
+                        .is_some_and(|environment| event.metadata.environment != environment)
+                    {
+                        continue;
+                    }
+                    traces.insert(event.trace_id);
+                    *accesses_by_service
+                        .entry(event.metadata.service_name.clone())
+                        .or_default() += 1;
+                }
+
+                let access_count: usize = accesses_by_service.values().sum();
+                (access_count > 0).then(|| VariableHotspot {
+                    variable: entry.key().clone(),
+                    access_count,
+                    trace_count: traces.len(),
+                    services: accesses_by_service.keys().cloned().collect(),
+                    accesses_by_service,
+                })
+            })
+            .collect();
+
+        hotspots.sort_by(|a, b| {
+            b.access_count
+                .cmp(&a.access_count)
+                .then(b.trace_count.cmp(&a.trace_count))
+                .then_with(|| a.variable.cmp(&b.variable))
+        });
+        hotspots.truncate(top_n);
+        hotspots
     }
 
     /// Find the causal path between two events
@@ -737,6 +2150,39 @@ impl CausalGraph {
         is_cyclic_directed(&*graph)
     }
 
+    /// The events of every cycle in the graph, one list per strongly connected
+    /// component that contains one. Empty for a well-formed causal graph.
+    pub fn find_cycles(&self) -> Vec<Vec<Uuid>> {
+        let graph = self.graph.lock().unwrap();
+        tarjan_scc(&*graph)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || graph.contains_edge(component[0], component[0])
+            })
+            .map(|component| component.into_iter().map(|idx| graph[idx]).collect())
+            .collect()
+    }
+
+    fn warn_cycle_through(&self, event: &Event) {
+        for cycle in self.find_cycles() {
+            if !cycle.contains(&event.id) {
+                continue;
+            }
+            let kinds: Vec<String> = cycle
+                .iter()
+                .filter_map(|id| self.nodes.get(id))
+                .map(|entry| self.event_kind_name(&entry.value().1.event.kind))
+                .collect();
+            tracing::warn!(
+                "Causal graph cycle in trace {} through event {}: {:?} ({})",
+                event.trace_id,
+                event.id,
+                cycle,
+                kinds.join(" -> ")
+            );
+        }
+    }
+
     /// Get statistics about the graph
     pub fn stats(&self) -> GraphStats {
         let graph = self.graph.lock().unwrap();
@@ -748,26 +2194,138 @@ impl CausalGraph {
         }
     }
 
+    /// Number of traces in the graph
+    pub fn trace_count(&self) -> usize {
+        self.trace_roots.len()
+    }
+
+    /// Traces whose events or cross-trace links changed since the last call.
+    /// Results kept outside the graph per trace (such as health scores) are
+    /// stale for these.
+    pub fn take_changed_traces(&self) -> HashSet<Uuid> {
+        std::mem::take(&mut *self.changed_traces.lock().unwrap())
+    }
+
+    /// Report traces as changed from outside the graph, e.g. when storage links
+    /// them to a distributed trace the graph hasn't loaded yet
+    pub fn mark_traces_changed(&self, trace_ids: impl IntoIterator<Item = Uuid>) {
+        self.changed_traces.lock().unwrap().extend(trace_ids);
+    }
+
     /// Get all trace IDs
     pub fn get_all_trace_ids(&self) -> Vec<Uuid> {
         self.trace_roots.iter().map(|entry| *entry.key()).collect()
     }
 
-    /// Build a hierarchical tree view of events for a trace
-    pub fn get_trace_tree(&self, trace_id: Uuid) -> Result<Vec<TreeNode>> {
-        let root_ids = self
-            .trace_roots
-            .get(&trace_id)
-            .ok_or_else(|| anyhow!("Trace not found: {}", trace_id))?;
-
-        let mut trees = Vec::new();
-        for root_id in root_ids.value() {
-            if let Some(tree) = self.build_tree_node(*root_id) {
-                trees.push(tree);
-            }
+    /// Keep the `max_traces` traces with the most recent events and evict the
+    /// rest. Returns the evicted traces; they are reloaded from storage on
+    /// demand like any other trace the graph hasn't seen.
+    pub fn retain_recent(&self, max_traces: usize) -> HashSet<Uuid> {
+        let mut last_seen: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+        for entry in self.nodes.iter() {
+            let event = &entry.value().1.event;
+            let latest = last_seen.entry(event.trace_id).or_insert(event.timestamp);
+            *latest = (*latest).max(event.timestamp);
+        }
+        if last_seen.len() <= max_traces {
+            return HashSet::new();
         }
 
-        Ok(trees)
+        let mut traces: Vec<(Uuid, DateTime<Utc>)> = last_seen.into_iter().collect();
+        traces.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let evicted: HashSet<Uuid> = traces
+            .into_iter()
+            .skip(max_traces)
+            .map(|(trace_id, _)| trace_id)
+            .collect();
+
+        self.evict_traces(&evicted);
+        evicted
+    }
+
+    /// Remove every event of `trace_ids` and everything indexed by them
+    fn evict_traces(&self, trace_ids: &HashSet<Uuid>) {
+        if trace_ids.is_empty() {
+            return;
+        }
+
+        // Held throughout, so no event is added against a stale node index
+        let mut graph = self.graph.lock().unwrap();
+
+        let evicted_events: HashSet<Uuid> = self
+            .nodes
+            .iter()
+            .filter(|entry| trace_ids.contains(&entry.value().1.event.trace_id))
+            .map(|entry| *entry.key())
+            .collect();
+        self.nodes
+            .retain(|event_id, _| !evicted_events.contains(event_id));
+
+        // DiGraph::remove_node moves the last node into the freed slot, so
+        // rebuild the graph and remap the surviving indices instead
+        let mut rebuilt = DiGraph::with_capacity(self.nodes.len(), graph.edge_count());
+        let mut remapped: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        for old_index in graph.node_indices() {
+            let event_id = graph[old_index];
+            if !evicted_events.contains(&event_id) {
+                remapped.insert(old_index, rebuilt.add_node(event_id));
+            }
+        }
+        for edge in graph.raw_edges() {
+            if let (Some(&from), Some(&to)) =
+                (remapped.get(&edge.source()), remapped.get(&edge.target()))
+            {
+                rebuilt.add_edge(from, to, edge.weight.clone());
+            }
+        }
+        for mut entry in self.nodes.iter_mut() {
+            if let Some(&new_index) = remapped.get(&entry.value().0) {
+                entry.value_mut().0 = new_index;
+            }
+        }
+        *graph = rebuilt;
+
+        for trace_id in trace_ids {
+            self.trace_roots.remove(trace_id);
+            self.vector_clocks.remove(trace_id);
+            self.trace_lineage.remove(trace_id);
+            self.invalidate_trace_caches(*trace_id);
+        }
+        self.changed_traces
+            .lock()
+            .unwrap()
+            .retain(|trace_id| !trace_ids.contains(trace_id));
+
+        let keep = |event_id: &Uuid| !evicted_events.contains(event_id);
+        self.variable_index.retain(|_, event_ids| {
+            event_ids.retain(keep);
+            !event_ids.is_empty()
+        });
+        for edges in [&self.sync_edges, &self.distributed_edges] {
+            edges.retain(|event_id, linked| {
+                linked.retain(keep);
+                keep(event_id) && !linked.is_empty()
+            });
+        }
+        self.applied_distributed_edges
+            .retain(|_, (upstream, downstream)| keep(&*upstream) && keep(&*downstream));
+    }
+
+    /// Build a hierarchical tree view of events for a trace
+    pub fn get_trace_tree(&self, trace_id: Uuid) -> Result<Vec<TreeNode>> {
+        let root_ids = self
+            .trace_roots
+            .get(&trace_id)
+            .ok_or_else(|| anyhow!("Trace not found: {}", trace_id))?;
+
+        let mut trees = Vec::new();
+        for root_id in root_ids.value() {
+            if let Some(tree) = self.build_tree_node(*root_id) {
+                trees.push(tree);
+            }
+        }
+
+        Ok(trees)
     }
 
     fn build_tree_node(&self, event_id: Uuid) -> Option<TreeNode> {
@@ -1053,9 +2611,8 @@ impl CausalGraph {
         for event in &events {
             if let Some(duration_ns) = event.metadata.duration_ns {
                 let duration_ms = duration_ns as f64 / 1_000_000.0;
-                let kind = self.event_kind_name(&event.kind);
                 new_durations_by_kind
-                    .entry(kind)
+                    .entry(self.baseline_key(event))
                     .or_default()
                     .push(duration_ms);
             }
@@ -1159,6 +2716,23 @@ impl CausalGraph {
             .collect()
     }
 
+    /// Baselines for the operations of timed events among `events`
+    pub fn get_baselines_for(
+        &self,
+        events: &[Event],
+    ) -> HashMap<String, crate::storage::DurationStats> {
+        let keys: HashSet<String> = events
+            .iter()
+            .filter(|event| event.metadata.duration_ns.is_some())
+            .map(|event| self.baseline_key(event))
+            .collect();
+
+        self.get_all_baselines()
+            .into_iter()
+            .filter(|(operation, _)| keys.contains(operation))
+            .collect()
+    }
+
     /// Detect anomalies in a trace based on baseline metrics
     pub fn detect_anomalies(&self, trace_id: Uuid) -> Result<Vec<Anomaly>> {
         // Check cache first - if we've already analyzed this trace, return cached results
@@ -1173,9 +2747,10 @@ impl CausalGraph {
         let mut has_sufficient_baseline = false;
         for event in &events {
             if event.metadata.duration_ns.is_some() {
-                let kind = self.event_kind_name(&event.kind);
-                if let Some(baseline) = self.baseline_metrics.get(&kind) {
-                    if baseline.value().count >= 5 {
+                if let Some(baseline) = self.baseline_metrics.get(&self.baseline_key(event)) {
+                    let min_samples =
+                        self.min_baseline_samples_for(&self.event_kind_name(&event.kind));
+                    if baseline.value().count >= min_samples {
                         has_sufficient_baseline = true;
                         break;
                     }
@@ -1193,52 +2768,57 @@ impl CausalGraph {
         // This prevents the anomalous values from skewing the baseline
         let mut anomalies = Vec::new();
 
-        for event in &events {
+        for event in self.anomaly_scan_sample(&events) {
             if let Some(duration_ns) = event.metadata.duration_ns {
                 let duration_ms = duration_ns as f64 / 1_000_000.0;
                 let kind = self.event_kind_name(&event.kind);
 
-                if let Some(baseline) = self.baseline_metrics.get(&kind) {
+                if let Some(baseline) = self.baseline_metrics.get(&self.baseline_key(event)) {
                     let baseline = baseline.value();
 
                     // Skip if we don't have enough samples
-                    if baseline.count < 5 {
+                    if baseline.count < self.min_baseline_samples_for(&kind) {
                         continue;
                     }
 
                     // Calculate how many standard deviations away from mean
-                    let (std_dev_from_mean, is_anomaly) = if baseline.std_dev > 0.0 {
-                        let sigma =
-                            (duration_ms - baseline.mean_duration_ms).abs() / baseline.std_dev;
-                        (sigma, sigma > 1.5)
-                    } else {
-                        // When std_dev is 0 (all baseline values are identical),
-                        // flag as anomaly if the value differs by more than 20% from the mean
-                        let percent_diff = ((duration_ms - baseline.mean_duration_ms).abs()
-                            / baseline.mean_duration_ms)
-                            * 100.0;
-                        (percent_diff / 10.0, percent_diff > 20.0) // Scale percent to pseudo-sigma
+                    let (std_dev_from_mean, is_anomaly) =
+                        sigma_from_mean(duration_ms, baseline.mean_duration_ms, baseline.std_dev);
+
+                    // Baselines loaded from storage carry no p95 and fall back to sigma
+                    let flagged = match self.anomaly_p95_multiplier {
+                        Some(multiplier) if baseline.p95 > 0.0 => {
+                            percentile_severity(duration_ms, baseline.p95, multiplier).map(
+                                |severity| {
+                                    let description = format!(
+                                        "{} took {:.2}ms ({:.1}× the p95 of {:.2}ms)",
+                                        kind,
+                                        duration_ms,
+                                        duration_ms / baseline.p95,
+                                        baseline.p95
+                                    );
+                                    (severity, description)
+                                },
+                            )
+                        }
+                        _ if is_anomaly => {
+                            let severity = sigma_severity(std_dev_from_mean);
+
+                            let description = format!(
+                                "{} took {:.2}ms (expected {:.2}ms ± {:.2}ms, {:.1}σ from mean)",
+                                kind,
+                                duration_ms,
+                                baseline.mean_duration_ms,
+                                baseline.std_dev,
+                                std_dev_from_mean
+                            );
+                            Some((severity, description))
+                        }
+                        _ => None,
                     };
 
                     // Only flag if significantly different
-                    if is_anomaly {
-                        let severity = if std_dev_from_mean > 5.0 {
-                            AnomalySeverity::Critical
-                        } else if std_dev_from_mean > 3.0 {
-                            AnomalySeverity::Warning
-                        } else {
-                            AnomalySeverity::Minor
-                        };
-
-                        let description = format!(
-                            "{} took {:.2}ms (expected {:.2}ms ± {:.2}ms, {:.1}σ from mean)",
-                            kind,
-                            duration_ms,
-                            baseline.mean_duration_ms,
-                            baseline.std_dev,
-                            std_dev_from_mean
-                        );
-
+                    if let Some((severity, description)) = flagged {
                         anomalies.push(Anomaly {
                             event_id: event.id.to_string(),
                             event_kind: kind.clone(),
@@ -1249,6 +2829,7 @@ impl CausalGraph {
                             description,
                             location: self.get_event_location(event),
                             timestamp: event.timestamp,
+                            service_name: event.metadata.service_name.clone(),
                         });
                     }
                 }
@@ -1266,23 +2847,150 @@ impl CausalGraph {
         Ok(anomalies)
     }
 
+    /// Events to scan for anomalies. Without a scan limit (or under it) this is
+    /// every event; otherwise a 1-in-K stride over timed events, plus the slowest
+    /// event of each kind so the worst outliers are never skipped
+    fn anomaly_scan_sample<'a>(&self, events: &'a [Event]) -> Vec<&'a Event> {
+        let timed: Vec<&Event> = events
+            .iter()
+            .filter(|event| event.metadata.duration_ns.is_some())
+            .collect();
+
+        let Some(limit) = self.anomaly_scan_limit.filter(|limit| timed.len() > *limit) else {
+            return timed;
+        };
+
+        let stride = timed.len().div_ceil(limit);
+        let mut selected: BTreeSet<usize> = (0..timed.len()).step_by(stride).collect();
+
+        let mut slowest_by_kind: HashMap<String, usize> = HashMap::new();
+        for (index, event) in timed.iter().enumerate() {
+            let slowest = slowest_by_kind
+                .entry(self.baseline_key(event))
+                .or_insert(index);
+            if event.metadata.duration_ns > timed[*slowest].metadata.duration_ns {
+                *slowest = index;
+            }
+        }
+        selected.extend(slowest_by_kind.into_values());
+
+        selected.into_iter().map(|index| timed[index]).collect()
+    }
+
+    /// Baseline bucket for an event: its kind name, suffixed with `@<environment>`
+    /// when environments are isolated so dev timings never skew prod baselines
+    /// Baseline samples needed for an event kind such as `HttpResponse(200)`:
+    /// an override for that exact kind, else one for its variant (`HttpResponse`),
+    /// else the global minimum
+    fn min_baseline_samples_for(&self, kind: &str) -> usize {
+        let variant = kind.split_once('(').map_or(kind, |(variant, _)| variant);
+        self.min_baseline_samples_by_kind
+            .get(kind)
+            .or_else(|| self.min_baseline_samples_by_kind.get(variant))
+            .copied()
+            .unwrap_or(self.min_baseline_samples)
+    }
+
+    fn baseline_key(&self, event: &Event) -> String {
+        let kind = self.event_kind_name(&event.kind);
+        if self.environment_isolation {
+            format!("{}@{}", kind, event.metadata.environment)
+        } else {
+            kind
+        }
+    }
+
     /// Find concurrent events across ALL traces (global race detection)
     pub fn find_global_concurrent_events(&self) -> Result<Vec<(Event, Event)>> {
+        self.find_global_concurrent_events_with_progress(|_, _| {})
+    }
+
+    /// Same as `find_global_concurrent_events`, calling `progress(done, total)`
+    /// after each outer comparison row so long runs can report how far along they are
+    pub fn find_global_concurrent_events_with_progress<F>(
+        &self,
+        progress: F,
+    ) -> Result<Vec<(Event, Event)>>
+    where
+        F: Fn(usize, usize),
+    {
         let mut all_state_changes = Vec::new();
+        let mut lock_events = Vec::new();
 
         // Collect all StateChange events from all traces
         for node_entry in self.nodes.iter() {
             let (_node_idx, causal_node) = node_entry.value();
-            if matches!(causal_node.event.kind, EventKind::StateChange { .. }) {
-                all_state_changes.push(causal_node.event.clone());
+            match causal_node.event.kind {
+                EventKind::StateChange { .. } => all_state_changes.push(causal_node.event.clone()),
+                EventKind::LockAcquire { .. } | EventKind::LockRelease { .. } => {
+                    lock_events.push(causal_node.event.clone())
+                }
+                _ => {}
             }
         }
 
+        Ok(self.global_concurrent_pairs(all_state_changes, lock_events, progress))
+    }
+
+    /// Same as `find_global_concurrent_events`, restricted to accesses of
+    /// `variable`. Candidates come from the variable index, so only that
+    /// variable's accesses are compared with each other.
+    pub fn find_global_concurrent_events_for(&self, variable: &str) -> Vec<(Event, Event)> {
+        let state_changes: Vec<Event> = self
+            .variable_index
+            .get(variable)
+            .map(|ids| ids.value().clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|id| self.nodes.get(&id).map(|node| node.value().1.event.clone()))
+            .collect();
+        if state_changes.len() < 2 {
+            return Vec::new();
+        }
+
+        // Lock balances only matter on the threads that touched the variable
+        let threads: HashSet<&str> = state_changes
+            .iter()
+            .map(|event| event.metadata.thread_id.as_str())
+            .collect();
+        let lock_events: Vec<Event> = self
+            .nodes
+            .iter()
+            .filter(|entry| {
+                let event = &entry.value().1.event;
+                matches!(
+                    event.kind,
+                    EventKind::LockAcquire { .. } | EventKind::LockRelease { .. }
+                ) && threads.contains(event.metadata.thread_id.as_str())
+            })
+            .map(|entry| entry.value().1.event.clone())
+            .collect();
+
+        self.global_concurrent_pairs(state_changes, lock_events, |_, _| {})
+    }
+
+    /// Pairs of `all_state_changes` on the same variable that no happens-before
+    /// relation, shared lock or declared pattern makes safe
+    fn global_concurrent_pairs<F>(
+        &self,
+        all_state_changes: Vec<Event>,
+        mut lock_events: Vec<Event>,
+        progress: F,
+    ) -> Vec<(Event, Event)>
+    where
+        F: Fn(usize, usize),
+    {
+        // Lock sets are tracked per thread across traces, so balance them the same way
+        sort_events_chronologically(&mut lock_events);
+        let stale_locks = unreleased_locks(&lock_events);
+
         let mut concurrent_pairs = Vec::new();
 
         // Compare all state changes across traces
-        for i in 0..all_state_changes.len() {
-            for j in (i + 1)..all_state_changes.len() {
+        let total = all_state_changes.len();
+        progress(0, total);
+        for i in 0..total {
+            for j in (i + 1)..total {
                 let event1 = &all_state_changes[i];
                 let event2 = &all_state_changes[j];
 
@@ -1302,7 +3010,9 @@ impl CausalGraph {
                     // Same variable access
                     if var1 == var2 {
                         // Skip safe access patterns
-                        if self.is_safe_access_pattern(*access1, *access2) {
+                        if self.is_safe_access_pattern(*access1, *access2)
+                            || self.is_declared_safe(var1, *access1, *access2)
+                        {
                             continue;
                         }
 
@@ -1315,7 +3025,9 @@ impl CausalGraph {
                                 && !self.happens_before_vc(event2, event1)
                             {
                                 // Check if accesses were protected by the same lock
-                                if !self.protected_by_same_lock(event1, event2) {
+                                if !self.protected_by_same_lock(event1, event2, &stale_locks)
+                                    && self.overlaps_in_time(event1, event2)
+                                {
                                     concurrent_pairs.push((event1.clone(), event2.clone()));
                                 }
                             }
@@ -1323,36 +3035,47 @@ impl CausalGraph {
                     }
                 }
             }
+            progress(i + 1, total);
         }
 
-        Ok(concurrent_pairs)
+        concurrent_pairs
     }
 
     /// Extract service dependencies from a trace
-    /// Returns a map of services and their call relationships
-    pub fn get_service_dependencies(&self, trace_id: Uuid) -> Result<ServiceDependencies> {
+    /// Returns a map of services and their call relationships. With `by_instance`
+    /// each instance is its own service, keyed `service#instance` like its clock
+    /// component; events without an instance id keep the bare service name.
+    pub fn get_service_dependencies(
+        &self,
+        trace_id: Uuid,
+        by_instance: bool,
+    ) -> Result<ServiceDependencies> {
         let events = self.get_causal_order(trace_id)?;
+        let service_key = |event: &Event| {
+            by_instance
+                .then(|| instance_component(&event.metadata))
+                .flatten()
+                .unwrap_or_else(|| event.metadata.service_name.clone())
+        };
 
         let mut service_event_counts: HashMap<String, usize> = HashMap::new();
         let mut dependencies: HashMap<(String, String), usize> = HashMap::new();
 
         // Count events per service
         for event in &events {
-            let service = &event.metadata.service_name;
-            *service_event_counts.entry(service.clone()).or_insert(0) += 1;
+            *service_event_counts.entry(service_key(event)).or_insert(0) += 1;
         }
 
         // Build dependency graph by analyzing parent-child relationships
         for event in &events {
             if let Some(parent_id) = event.parent_id {
                 if let Some(parent_entry) = self.nodes.get(&parent_id) {
-                    let parent_event = &parent_entry.value().1.event;
-                    let parent_service = &parent_event.metadata.service_name;
-                    let child_service = &event.metadata.service_name;
+                    let parent_service = service_key(&parent_entry.value().1.event);
+                    let child_service = service_key(event);
 
                     // Only count cross-service calls
                     if parent_service != child_service {
-                        let key = (parent_service.clone(), child_service.clone());
+                        let key = (parent_service, child_service);
                         *dependencies.entry(key).or_insert(0) += 1;
                     }
                 }
@@ -1384,7 +3107,9 @@ impl CausalGraph {
     /// Get audit trail for a specific variable in a trace
     /// Shows all accesses to that variable in chronological order with causal links
     pub fn get_audit_trail(&self, trace_id: Uuid, variable: &str) -> Result<AuditTrail> {
-        let events = self.get_causal_order(trace_id)?;
+        let mut events = self.get_causal_order(trace_id)?;
+        sort_events_chronologically(&mut events);
+        let stale_locks = unreleased_locks(&events);
 
         // Filter to only StateChange events for this variable
         let mut variable_events: Vec<Event> = events
@@ -1398,10 +3123,10 @@ impl CausalGraph {
             })
             .collect();
 
-        // Sort by timestamp
-        variable_events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        // Sort chronologically (monotonic offsets break same-process ties)
+        sort_events_chronologically(&mut variable_events);
 
-        let accesses = self.build_variable_accesses(&variable_events);
+        let accesses = self.build_variable_accesses(&variable_events, &stale_locks);
 
         Ok(AuditTrail {
             trace_id: trace_id.to_string(),
@@ -1420,7 +3145,9 @@ impl CausalGraph {
             return Ok(HashMap::new());
         }
 
-        let events = self.get_causal_order(trace_id)?;
+        let mut events = self.get_causal_order(trace_id)?;
+        sort_events_chronologically(&mut events);
+        let stale_locks = unreleased_locks(&events);
         let mut grouped: HashMap<String, Vec<Event>> = HashMap::new();
 
         for event in events.into_iter() {
@@ -1436,16 +3163,21 @@ impl CausalGraph {
 
         let mut trails = HashMap::with_capacity(grouped.len());
         for (variable, mut variable_events) in grouped {
-            variable_events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-            let accesses = self.build_variable_accesses(&variable_events);
+            sort_events_chronologically(&mut variable_events);
+            let accesses = self.build_variable_accesses(&variable_events, &stale_locks);
             trails.insert(variable, accesses);
         }
 
         Ok(trails)
     }
 
-    fn build_variable_accesses(&self, variable_events: &[Event]) -> Vec<VariableAccess> {
+    fn build_variable_accesses(
+        &self,
+        variable_events: &[Event],
+        stale_locks: &[UnreleasedLock],
+    ) -> Vec<VariableAccess> {
         let mut accesses = Vec::new();
+        let mut access_events = Vec::new();
 
         for (i, event) in variable_events.iter().enumerate() {
             if let EventKind::StateChange {
@@ -1474,7 +3206,7 @@ impl CausalGraph {
                     // 4. At least one write
                     event.metadata.thread_id != prev_event.metadata.thread_id
                         && !has_causal_link_to_previous
-                        && !self.protected_by_same_lock(prev_event, event)
+                        && !self.protected_by_same_lock(prev_event, event, stale_locks)
                         && (*access_type == AccessType::Write
                             || matches!(
                                 prev_event.kind,
@@ -1506,7 +3238,34 @@ impl CausalGraph {
                     location: location.clone(),
                     has_causal_link_to_previous,
                     is_race,
+                    is_lost_update: false,
                 });
+                access_events.push(event);
+            }
+        }
+
+        // Read-modify-write from a stale value: two concurrent writes that
+        // started from the same old value but stored different results
+        for i in 0..accesses.len() {
+            for j in (i + 1)..accesses.len() {
+                let (a, b) = (&accesses[i], &accesses[j]);
+                let lost = a.access_type == "Write"
+                    && b.access_type == "Write"
+                    && a.thread_id != b.thread_id
+                    && a.old_value.is_some()
+                    && a.old_value == b.old_value
+                    && a.new_value != b.new_value
+                    && !self.happens_before_vc(access_events[i], access_events[j])
+                    && !self.happens_before_vc(access_events[j], access_events[i])
+                    && !self.protected_by_same_lock(
+                        access_events[i],
+                        access_events[j],
+                        stale_locks,
+                    );
+                if lost {
+                    accesses[i].is_lost_update = true;
+                    accesses[j].is_lost_update = true;
+                }
             }
         }
 
@@ -1514,6 +3273,130 @@ impl CausalGraph {
     }
 }
 
+/// Drop the components with the smallest clocks until at most `max_components`
+/// remain, always keeping `own_component`. Survivors keep their original order.
+///
+/// Pruning loses precision in one direction only: `happens_before_vc` needs every
+/// component of the earlier event to be present in the later one, so a pruned
+/// component can turn an ordered pair into a reported race, never the reverse.
+/// `service#instance`, the causality-vector component of an event that carries an instance id
+fn instance_component(metadata: &EventMetadata) -> Option<String> {
+    metadata
+        .instance_id
+        .as_ref()
+        .map(|instance| format!("{}#{}", metadata.service_name, instance))
+}
+
+fn prune_causality_vector(
+    causality_vector: &mut Vec<(String, u64)>,
+    own_component: &str,
+    max_components: usize,
+) {
+    if causality_vector.len() <= max_components {
+        return;
+    }
+
+    let mut by_recency: Vec<usize> = (0..causality_vector.len()).collect();
+    by_recency.sort_by_key(|&i| {
+        let (component, clock) = &causality_vector[i];
+        (component != own_component, std::cmp::Reverse(*clock))
+    });
+    let keep: HashSet<usize> = by_recency.into_iter().take(max_components).collect();
+
+    let mut index = 0;
+    causality_vector.retain(|_| {
+        index += 1;
+        keep.contains(&(index - 1))
+    });
+}
+
+/// Severity of a duration above `p95 × multiplier`, or None when it is within it.
+/// Minor up to 2× the threshold, Warning up to 3×, Critical beyond.
+fn percentile_severity(duration_ms: f64, p95: f64, multiplier: f64) -> Option<AnomalySeverity> {
+    let threshold = p95 * multiplier;
+    if duration_ms <= threshold {
+        return None;
+    }
+
+    let excess = duration_ms / threshold;
+    Some(if excess > 3.0 {
+        AnomalySeverity::Critical
+    } else if excess > 2.0 {
+        AnomalySeverity::Warning
+    } else {
+        AnomalySeverity::Minor
+    })
+}
+
+/// Standard deviations above which a value is anomalous against its baseline
+const ANOMALY_SIGMA_THRESHOLD: f64 = 1.5;
+
+/// How far `value` is from `mean`, in standard deviations, and whether that is
+/// far enough to be an anomaly. When `std_dev` is 0 (all baseline values are
+/// identical), a value more than 20% away from the mean is flagged instead,
+/// reported as a pseudo-sigma of one per 10%.
+fn sigma_from_mean(value: f64, mean: f64, std_dev: f64) -> (f64, bool) {
+    if std_dev > 0.0 {
+        let sigma = (value - mean).abs() / std_dev;
+        (sigma, sigma > ANOMALY_SIGMA_THRESHOLD)
+    } else {
+        let percent_diff = ((value - mean).abs() / mean) * 100.0;
+        (percent_diff / 10.0, percent_diff > 20.0)
+    }
+}
+
+/// Severity of a sigma-based anomaly: Critical beyond 5σ, Warning beyond 3σ
+fn sigma_severity(std_dev_from_mean: f64) -> AnomalySeverity {
+    if std_dev_from_mean > 5.0 {
+        AnomalySeverity::Critical
+    } else if std_dev_from_mean > 3.0 {
+        AnomalySeverity::Warning
+    } else {
+        AnomalySeverity::Minor
+    }
+}
+
+/// A completed lock hold: the acquire and how long it lasted until its release
+struct LockHold<'a> {
+    acquire: &'a Event,
+    lock_id: &'a str,
+    held_ms: f64,
+}
+
+/// Pair each release in chronologically sorted `events` with the most recent
+/// unreleased acquire of the same lock by the same thread in the same trace.
+fn lock_holds(events: &[Event]) -> Vec<LockHold<'_>> {
+    let mut holds = Vec::new();
+    let mut open: HashMap<(Uuid, &str, &str), Vec<&Event>> = HashMap::new();
+    for event in events {
+        let thread_id = event.metadata.thread_id.as_str();
+        match &event.kind {
+            EventKind::LockAcquire { lock_id, .. } => {
+                open.entry((event.trace_id, thread_id, lock_id))
+                    .or_default()
+                    .push(event);
+            }
+            EventKind::LockRelease { lock_id, .. } => {
+                let acquire = open
+                    .get_mut(&(event.trace_id, thread_id, lock_id.as_str()))
+                    .and_then(|acquires| acquires.pop());
+                if let Some(acquire) = acquire {
+                    let held_us = (event.timestamp - acquire.timestamp)
+                        .num_microseconds()
+                        .unwrap_or(0);
+                    holds.push(LockHold {
+                        acquire,
+                        lock_id,
+                        held_ms: held_us.max(0) as f64 / 1000.0,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    holds
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[derive(Default)]
 pub struct GraphStats {
@@ -1530,14 +3413,236 @@ impl Default for CausalGraph {
     }
 }
 
+/// Locks the event held when it occurred, minus any its thread only appears
+/// to hold because it left a critical section without releasing the lock: the
+/// stale entry would otherwise hide every race until the thread retakes it
+fn held_locks<'a>(event: &'a Event, stale_locks: &[UnreleasedLock]) -> HashSet<&'a String> {
+    event
+        .lock_set
+        .iter()
+        .filter(|lock_id| {
+            !stale_locks.iter().any(|stale| {
+                stale.thread_id == event.metadata.thread_id
+                    && &stale.lock_id == *lock_id
+                    && stale
+                        .stale_windows
+                        .iter()
+                        .any(|window| window.covers(event.timestamp))
+            })
+        })
+        .collect()
+}
+
+/// Severity of one race, starting from its access-type mix (write/write over
+/// read/write over read/read). Two writes that stored the same value drop to
+/// Warning unless a third thread also writes; a read racing two or more
+/// concurrent writers rises to Critical.
+pub fn race_severity(
+    access1: AccessType,
+    access2: AccessType,
+    values_diverge: bool,
+    concurrent_writers: usize,
+) -> RaceSeverity {
+    match RaceSeverity::classify(access1, access2) {
+        RaceSeverity::Critical if !values_diverge && concurrent_writers <= 2 => {
+            RaceSeverity::Warning
+        }
+        RaceSeverity::Warning if concurrent_writers >= 2 => RaceSeverity::Critical,
+        severity => severity,
+    }
+}
+
+/// Per `(thread, lock)` acquire/release imbalances among `events`, which must be
+/// in chronological order. A release pairs with the most recent open acquire of
+/// the same lock by the same thread in the same trace, as in `lock_holds`; acquires
+/// left open and releases with nothing to pair are reported separately.
+fn unreleased_locks(events: &[Event]) -> Vec<UnreleasedLock> {
+    #[derive(Default)]
+    struct Balance {
+        acquires: usize,
+        releases: usize,
+        /// Indices into `events` of acquires no release has matched yet
+        open: Vec<usize>,
+        /// Indices into `events` of releases that found no open acquire
+        unmatched: Vec<usize>,
+    }
+
+    let mut balances: BTreeMap<(&str, &str, Uuid), Balance> = BTreeMap::new();
+    // Longest balanced hold per lock, bounding how long an orphaned section lasts
+    let mut longest_holds: HashMap<&str, chrono::Duration> = HashMap::new();
+    for (index, event) in events.iter().enumerate() {
+        let thread_id = event.metadata.thread_id.as_str();
+        match &event.kind {
+            EventKind::LockAcquire { lock_id, .. } => {
+                let balance = balances
+                    .entry((thread_id, lock_id.as_str(), event.trace_id))
+                    .or_default();
+                balance.acquires += 1;
+                balance.open.push(index);
+            }
+            EventKind::LockRelease { lock_id, .. } => {
+                let balance = balances
+                    .entry((thread_id, lock_id.as_str(), event.trace_id))
+                    .or_default();
+                balance.releases += 1;
+                match balance.open.pop() {
+                    Some(acquire) => {
+                        let held = event.timestamp - events[acquire].timestamp;
+                        let longest = longest_holds.entry(lock_id.as_str()).or_insert(held);
+                        *longest = (*longest).max(held);
+                    }
+                    None => balance.unmatched.push(index),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut imbalances = Vec::new();
+    for ((thread_id, lock_id, _), balance) in balances {
+        let report = |imbalance, event: &Event| UnreleasedLock {
+            imbalance,
+            thread_id: thread_id.to_string(),
+            lock_id: lock_id.to_string(),
+            acquires: balance.acquires,
+            releases: balance.releases,
+            orphaned_acquire: None,
+            orphaned_at: None,
+            unmatched_release: None,
+            location: match &event.kind {
+                EventKind::LockAcquire { location, .. }
+                | EventKind::LockRelease { location, .. } => Some(location.clone()),
+                _ => None,
+            },
+            stale_windows: Vec::new(),
+        };
+
+        if let Some(&first) = balance.open.first() {
+            let acquire = &events[first];
+            let longest_section = longest_holds
+                .get(lock_id)
+                .copied()
+                .unwrap_or_else(|| chrono::Duration::milliseconds(ORPHANED_SECTION_FALLBACK_MS));
+            imbalances.push(UnreleasedLock {
+                orphaned_acquire: Some(acquire.id),
+                orphaned_at: Some(acquire.timestamp),
+                stale_windows: balance
+                    .open
+                    .iter()
+                    .filter_map(|&index| stale_window(events, index, longest_section))
+                    .collect(),
+                ..report(LockImbalance::UnreleasedAcquire, acquire)
+            });
+        }
+        if let Some(&first) = balance.unmatched.first() {
+            let release = &events[first];
+            imbalances.push(UnreleasedLock {
+                unmatched_release: Some(release.id),
+                ..report(LockImbalance::UnmatchedRelease, release)
+            });
+        }
+    }
+    imbalances
+}
+
+/// Where the orphaned acquire `events[orphan]` stops protecting. The thread
+/// may still be inside that section (its release just hasn't arrived), so the
+/// lock keeps counting until the section has clearly ended: the thread released
+/// a lock it already held around the acquire, or `longest_section` has passed
+/// since it. From the thread's next acquire of the lock on, its lock set is
+/// accurate again.
+fn stale_window(
+    events: &[Event],
+    orphan: usize,
+    longest_section: chrono::Duration,
+) -> Option<StaleLockWindow> {
+    let acquire = &events[orphan];
+    let EventKind::LockAcquire { lock_id, .. } = &acquire.kind else {
+        return None;
+    };
+
+    let mut from = acquire.timestamp + longest_section;
+    for event in events[orphan + 1..]
+        .iter()
+        .filter(|event| event.metadata.thread_id == acquire.metadata.thread_id)
+    {
+        match &event.kind {
+            EventKind::LockAcquire { lock_id: retaken, .. } if retaken == lock_id => {
+                return (from < event.timestamp).then_some(StaleLockWindow {
+                    from,
+                    until: Some(event.timestamp),
+                });
+            }
+            EventKind::LockRelease {
+                lock_id: released, ..
+            } if released != lock_id && acquire.lock_set.contains(released) => {
+                from = from.min(event.timestamp);
+            }
+            _ => {}
+        }
+    }
+    Some(StaleLockWindow { from, until: None })
+}
+
+/// Shortest chain of lock orderings from `from` to `to` (breadth-first), as
+/// the locks along it including both ends
+fn shortest_lock_path<'a>(
+    successors: &BTreeMap<&'a str, Vec<&'a str>>,
+    from: &'a str,
+    to: &'a str,
+) -> Option<Vec<&'a str>> {
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = std::collections::VecDeque::from([from]);
+    while let Some(lock) = queue.pop_front() {
+        if lock == to {
+            let mut path = vec![to];
+            while let Some(prev) = previous.get(path.last().unwrap()) {
+                path.push(prev);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in successors.get(lock).into_iter().flatten() {
+            if *next != from && !previous.contains_key(next) {
+                previous.insert(next, lock);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+fn pluralize(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::EventMetadata;
     use crate::storage::DurationStats;
     use chrono::{Duration as ChronoDuration, TimeZone, Utc};
     use std::collections::HashMap;
 
+    #[test]
+    fn test_classify_race_severity() {
+        assert_eq!(
+            RaceSeverity::classify(AccessType::Write, AccessType::AtomicRMW),
+            RaceSeverity::Critical
+        );
+        assert_eq!(
+            RaceSeverity::classify(AccessType::Read, AccessType::Write),
+            RaceSeverity::Warning
+        );
+        assert_eq!(
+            RaceSeverity::classify(AccessType::Read, AccessType::AtomicRead),
+            RaceSeverity::Info
+        );
+    }
+
     #[test]
     fn test_add_event() {
         let graph = CausalGraph::new();
@@ -1553,6 +3658,9 @@ mod tests {
             instance_id: None,
             distributed_span_id: None,
             upstream_span_id: None,
+            monotonic_ns: None,
+            retry_of: None,
+            attempt: None,
         };
 
         let event = Event::new(
@@ -1611,47 +3719,196 @@ mod tests {
         assert_eq!(races.len(), 1);
     }
 
+    /// Event `i` of a synthetic trace: four threads, each a short parent
+    /// chain hanging off the root, touching 21 variables (coprime with the
+    /// thread count, so every variable is shared). Lock events only appear
+    /// in the first half.
+    fn synthetic_event(trace_id: Uuid, root_id: Uuid, ids: &[Uuid], i: usize) -> Event {
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let thread = format!("worker-{}", i % 4);
+        let parent_id = if i < 4 || i % 40 < 4 {
+            root_id
+        } else {
+            ids[i - 4]
+        };
+        let kind = match i {
+            _ if i < 500 && i.is_multiple_of(50) => EventKind::LockAcquire {
+                lock_id: format!("lock-{}", i % 3),
+                lock_type: "Mutex".into(),
+                location: "tests.rs:1".into(),
+            },
+            _ if i < 500 && i % 50 == 25 => EventKind::LockRelease {
+                lock_id: format!("lock-{}", (i - 25) % 3),
+                lock_type: "Mutex".into(),
+                location: "tests.rs:2".into(),
+            },
+            _ if i % 10 == 9 => EventKind::FunctionCall {
+                function_name: format!("step_{}", i),
+                module: "tests".into(),
+                args: serde_json::json!({}),
+                file: "tests.rs".into(),
+                line: 3,
+            },
+            _ => EventKind::StateChange {
+                variable: format!("var-{}", i % 21),
+                old_value: None,
+                new_value: serde_json::json!(i),
+                location: "tests.rs:4".into(),
+                access_type: if i.is_multiple_of(3) {
+                    AccessType::Read
+                } else {
+                    AccessType::Write
+                },
+            },
+        };
+
+        Event {
+            id: ids[i],
+            trace_id,
+            parent_id: Some(parent_id),
+            timestamp: base + ChronoDuration::milliseconds(i as i64),
+            kind,
+            metadata: metadata(&thread, 5),
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
     #[test]
-    fn lock_protected_events_do_not_race() {
+    fn incremental_concurrent_pairs_match_full_recompute() {
         let graph = CausalGraph::new();
         let trace_id = Uuid::new_v4();
         let root_id = Uuid::new_v4();
+        let ids: Vec<Uuid> = (0..1000).map(|_| Uuid::new_v4()).collect();
         let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        let add_range = |range: std::ops::Range<usize>| {
+            for i in range {
+                graph
+                    .add_event(synthetic_event(trace_id, root_id, &ids, i))
+                    .unwrap();
+            }
+        };
+        let pair_ids = |pairs: Vec<(Event, Event)>| {
+            let mut ids: Vec<(Uuid, Uuid)> = pairs
+                .into_iter()
+                .map(|(a, b)| (a.id.min(b.id), a.id.max(b.id)))
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        // Warm the cache, then keep ingesting state changes against it
+        add_range(0..500);
+        graph.find_concurrent_events(trace_id).unwrap();
+        add_range(500..1000);
+        assert!(
+            graph.analysis_cache.lock().unwrap().contains(&trace_id),
+            "state changes should extend the cached pairs, not drop them"
+        );
+        let incremental = pair_ids(graph.find_concurrent_events(trace_id).unwrap());
 
+        graph.invalidate_trace_caches(trace_id);
+        let full = pair_ids(graph.find_concurrent_events(trace_id).unwrap());
+
+        assert!(!full.is_empty());
+        assert_eq!(incremental, full);
+    }
+
+    #[test]
+    fn max_pairs_per_trace_keeps_the_earliest_pairs() {
+        let graph = CausalGraph::new().with_max_pairs_per_trace(10);
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
         graph
             .add_event(make_root(root_id, trace_id, base, "root"))
             .unwrap();
 
-        // Both threads acquire the same lock before writing.
-        for (thread, offset_ms) in [("worker-a", 1), ("worker-b", 1)] {
+        // Ingest out of timestamp order; the cap must still follow timestamps
+        let ids: Vec<Uuid> = (0..100).map(|_| Uuid::new_v4()).collect();
+        for i in (0..100).rev() {
             graph
                 .add_event(Event {
-                    id: Uuid::new_v4(),
+                    id: ids[i],
                     trace_id,
                     parent_id: Some(root_id),
-                    timestamp: base + ChronoDuration::milliseconds(offset_ms),
-                    kind: EventKind::LockAcquire {
-                        lock_id: "balance-lock".into(),
-                        lock_type: "Mutex".into(),
-                        location: "tests.rs:12".into(),
+                    timestamp: base + ChronoDuration::milliseconds(i as i64 + 1),
+                    kind: EventKind::StateChange {
+                        variable: "counter".into(),
+                        old_value: None,
+                        new_value: serde_json::json!(i),
+                        location: "tests.rs:30".into(),
+                        access_type: AccessType::Write,
                     },
-                    metadata: metadata(thread, 1),
+                    metadata: metadata(&format!("worker-{}", i % 2), 5),
                     causality_vector: Vec::new(),
                     lock_set: Vec::new(),
                 })
                 .unwrap();
         }
 
-        for (thread, offset_ms) in [("worker-a", 2), ("worker-b", 3)] {
-            graph
-                .add_event(Event {
-                    id: Uuid::new_v4(),
-                    trace_id,
-                    parent_id: Some(root_id),
-                    timestamp: base + ChronoDuration::milliseconds(offset_ms),
-                    kind: EventKind::StateChange {
-                        variable: "balance".into(),
-                        old_value: Some(serde_json::json!(10)),
+        let (pairs, truncated) = graph
+            .find_concurrent_events_with_truncation(trace_id)
+            .unwrap();
+        assert!(truncated);
+        let pair_ids: Vec<(Uuid, Uuid)> = pairs.iter().map(|(a, b)| (a.id, b.id)).collect();
+        // The first write races with every later write on the other thread
+        let expected: Vec<(Uuid, Uuid)> = (1..20).step_by(2).map(|j| (ids[0], ids[j])).collect();
+        assert_eq!(pair_ids, expected);
+
+        graph.invalidate_trace_caches(trace_id);
+        let (again, _) = graph
+            .find_concurrent_events_with_truncation(trace_id)
+            .unwrap();
+        let again_ids: Vec<(Uuid, Uuid)> = again.iter().map(|(a, b)| (a.id, b.id)).collect();
+        assert_eq!(again_ids, expected);
+    }
+
+    #[test]
+    fn lock_protected_events_do_not_race() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        // Both threads acquire the same lock before writing.
+        for (thread, offset_ms) in [("worker-a", 1), ("worker-b", 1)] {
+            graph
+                .add_event(Event {
+                    id: Uuid::new_v4(),
+                    trace_id,
+                    parent_id: Some(root_id),
+                    timestamp: base + ChronoDuration::milliseconds(offset_ms),
+                    kind: EventKind::LockAcquire {
+                        lock_id: "balance-lock".into(),
+                        lock_type: "Mutex".into(),
+                        location: "tests.rs:12".into(),
+                    },
+                    metadata: metadata(thread, 1),
+                    causality_vector: Vec::new(),
+                    lock_set: Vec::new(),
+                })
+                .unwrap();
+        }
+
+        for (thread, offset_ms) in [("worker-a", 2), ("worker-b", 3)] {
+            graph
+                .add_event(Event {
+                    id: Uuid::new_v4(),
+                    trace_id,
+                    parent_id: Some(root_id),
+                    timestamp: base + ChronoDuration::milliseconds(offset_ms),
+                    kind: EventKind::StateChange {
+                        variable: "balance".into(),
+                        old_value: Some(serde_json::json!(10)),
                         new_value: serde_json::json!(15),
                         location: "tests.rs:22".into(),
                         access_type: AccessType::Write,
@@ -1725,6 +3982,96 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn database_result_pairs_with_its_query() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let query_id = Uuid::new_v4();
+        let result_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+        graph
+            .add_event(Event {
+                id: query_id,
+                trace_id,
+                parent_id: Some(root_id),
+                timestamp: base + ChronoDuration::milliseconds(1),
+                kind: EventKind::DatabaseQuery {
+                    query: "SELECT * FROM orders".into(),
+                    database: "orders".into(),
+                    duration_ms: 0,
+                },
+                metadata: metadata("main", 0),
+                causality_vector: Vec::new(),
+                lock_set: Vec::new(),
+            })
+            .unwrap();
+        graph
+            .add_event(Event {
+                id: result_id,
+                trace_id,
+                parent_id: Some(query_id),
+                timestamp: base + ChronoDuration::milliseconds(13),
+                kind: EventKind::DatabaseResult { rows_affected: 3 },
+                metadata: metadata("main", 12),
+                causality_vector: Vec::new(),
+                lock_set: Vec::new(),
+            })
+            .unwrap();
+
+        let query_idx = graph.nodes.get(&query_id).unwrap().value().0;
+        let result_idx = graph.nodes.get(&result_id).unwrap().value().0;
+        {
+            let petgraph = graph.graph.lock().unwrap();
+            let edge = petgraph.find_edge(query_idx, result_idx).unwrap();
+            assert!(matches!(petgraph[edge], CausalEdge::DatabaseQueryResult));
+        }
+
+        // The query's latency, carried by its result, is on the critical path
+        let path = graph.get_critical_path(trace_id).unwrap();
+        assert!(path.path.iter().any(|event| event.id == result_id));
+    }
+
+    #[test]
+    fn find_cycles_reports_the_events_of_each_cycle() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        let mut chain = vec![root_id];
+        for step in 1..=2 {
+            let id = Uuid::new_v4();
+            let mut event = make_root(id, trace_id, base, "step");
+            event.parent_id = chain.last().copied();
+            event.timestamp = base + ChronoDuration::milliseconds(step);
+            graph.add_event(event).unwrap();
+            chain.push(id);
+        }
+        assert!(graph.find_cycles().is_empty());
+        assert!(!graph.has_cycles());
+
+        // Corrupt the graph: the last step points back to the first one
+        {
+            let index = |id: &Uuid| graph.nodes.get(id).unwrap().value().0;
+            let mut petgraph = graph.graph.lock().unwrap();
+            petgraph.add_edge(index(&chain[2]), index(&chain[1]), CausalEdge::DirectCall);
+        }
+
+        let cycles = graph.find_cycles();
+        assert_eq!(cycles.len(), 1);
+        let cycle: HashSet<Uuid> = cycles[0].iter().copied().collect();
+        assert_eq!(cycle, HashSet::from([chain[1], chain[2]]));
+        assert!(graph.has_cycles());
+    }
+
     #[test]
     fn anomalies_ignore_baseline_duration() {
         let graph = CausalGraph::new();
@@ -1824,6 +4171,70 @@ mod tests {
         assert_eq!(anomalies.len(), 1);
     }
 
+    /// Detect anomalies on a fresh trace holding one `HttpResponse(200)` of `duration_ms`,
+    /// which also adds it to the baseline
+    fn detect_on_response(graph: &CausalGraph, duration_ms: u64) -> Vec<Anomaly> {
+        let trace_id = Uuid::new_v4();
+        graph
+            .add_event(Event {
+                id: Uuid::new_v4(),
+                trace_id,
+                parent_id: None,
+                timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+                kind: EventKind::HttpResponse {
+                    status: 200,
+                    headers: HashMap::new(),
+                    body: None,
+                    duration_ms: 0,
+                },
+                metadata: metadata("http", duration_ms),
+                causality_vector: Vec::new(),
+                lock_set: Vec::new(),
+            })
+            .unwrap();
+        graph.detect_anomalies(trace_id).unwrap()
+    }
+
+    #[test]
+    fn min_baseline_samples_holds_back_anomalies_until_enough_samples() {
+        let default_graph = CausalGraph::new();
+        let graph = CausalGraph::new().with_min_baseline_samples(20, HashMap::new());
+        for i in 0..10 {
+            detect_on_response(&default_graph, 15 + i % 5);
+            detect_on_response(&graph, 15 + i % 5);
+        }
+
+        // 10 samples are enough for the default of 5, not for 20
+        assert_eq!(detect_on_response(&default_graph, 100).len(), 1);
+        assert!(detect_on_response(&graph, 100).is_empty());
+
+        for i in 0..10 {
+            assert!(detect_on_response(&graph, 15 + i % 5).is_empty());
+        }
+        assert_eq!(detect_on_response(&graph, 100).len(), 1);
+    }
+
+    #[test]
+    fn min_baseline_samples_per_kind_override_the_global_minimum() {
+        let by_variant = HashMap::from([("HttpResponse".to_string(), 5)]);
+        let graph = CausalGraph::new().with_min_baseline_samples(20, by_variant);
+        for i in 0..10 {
+            detect_on_response(&graph, 15 + i % 5);
+        }
+        assert_eq!(detect_on_response(&graph, 100).len(), 1);
+
+        // An exact kind wins over its variant
+        let by_kind = HashMap::from([
+            ("HttpResponse".to_string(), 5),
+            ("HttpResponse(200)".to_string(), 20),
+        ]);
+        let graph = CausalGraph::new().with_min_baseline_samples(5, by_kind);
+        for i in 0..10 {
+            detect_on_response(&graph, 15 + i % 5);
+        }
+        assert!(detect_on_response(&graph, 100).is_empty());
+    }
+
     #[test]
     fn audit_trail_flags_race_access() {
         let graph = CausalGraph::new();
@@ -1861,6 +4272,88 @@ mod tests {
         assert!(trail.accesses.iter().any(|access| access.is_race));
     }
 
+    #[test]
+    fn audit_trail_flags_lost_update_from_banking_transfers() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        // Two transfers out of alice's account both read 1000 before either
+        // writes, then store 1000 - 100 and 1000 - 200
+        let accesses = [
+            ("transfer-1", 1_i64, None, 1000, AccessType::Read),
+            ("transfer-2", 2, None, 1000, AccessType::Read),
+            ("transfer-1", 3, Some(1000), 900, AccessType::Write),
+            ("transfer-2", 4, Some(1000), 800, AccessType::Write),
+        ];
+        for (thread, offset_ms, old_value, new_value, access_type) in accesses {
+            graph
+                .add_event(Event {
+                    id: Uuid::new_v4(),
+                    trace_id,
+                    parent_id: Some(root_id),
+                    timestamp: base + ChronoDuration::milliseconds(offset_ms),
+                    kind: EventKind::StateChange {
+                        variable: "alice.balance".into(),
+                        old_value: old_value.map(|v: i64| serde_json::json!(v)),
+                        new_value: serde_json::json!(new_value),
+                        location: format!("main.rs:{}", 190 + offset_ms),
+                        access_type,
+                    },
+                    metadata: metadata(thread, 5),
+                    causality_vector: Vec::new(),
+                    lock_set: Vec::new(),
+                })
+                .unwrap();
+        }
+
+        let trail = graph.get_audit_trail(trace_id, "alice.balance").unwrap();
+        let lost: Vec<bool> = trail.accesses.iter().map(|a| a.is_lost_update).collect();
+        assert_eq!(lost, vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn audit_trail_ignores_concurrent_writes_of_the_same_value() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        for (thread, offset_ms) in [("worker-a", 1_i64), ("worker-b", 2)] {
+            graph
+                .add_event(Event {
+                    id: Uuid::new_v4(),
+                    trace_id,
+                    parent_id: Some(root_id),
+                    timestamp: base + ChronoDuration::milliseconds(offset_ms),
+                    kind: EventKind::StateChange {
+                        variable: "status".into(),
+                        old_value: Some(serde_json::json!("pending")),
+                        new_value: serde_json::json!("done"),
+                        location: format!("tests.rs:{}", offset_ms),
+                        access_type: AccessType::Write,
+                    },
+                    metadata: metadata(thread, 5),
+                    causality_vector: Vec::new(),
+                    lock_set: Vec::new(),
+                })
+                .unwrap();
+        }
+
+        let trail = graph.get_audit_trail(trace_id, "status").unwrap();
+        assert!(trail.accesses.iter().any(|access| access.is_race));
+        assert!(!trail.accesses.iter().any(|access| access.is_lost_update));
+    }
+
     #[test]
     fn service_dependencies_capture_cross_service_calls() {
         let graph = CausalGraph::new();
@@ -1907,7 +4400,7 @@ mod tests {
             })
             .unwrap();
 
-        let deps = graph.get_service_dependencies(trace_id).unwrap();
+        let deps = graph.get_service_dependencies(trace_id, false).unwrap();
         assert_eq!(deps.services.len(), 2);
         assert!(deps
             .dependencies
@@ -1915,6 +4408,63 @@ mod tests {
             .any(|dep| dep.from == "svc-a" && dep.to == "svc-b" && dep.call_count == 1));
     }
 
+    #[test]
+    fn service_dependencies_by_instance_split_instances_of_a_service() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let mut root = make_root(Uuid::new_v4(), trace_id, base, "checkout");
+        root.metadata = metadata_with_service("main", "gateway", 5);
+        graph.add_event(root.clone()).unwrap();
+        for (offset, instance) in [(1, "cart-1"), (2, "cart-2"), (3, "cart-2")] {
+            let mut child = make_root(
+                Uuid::new_v4(),
+                trace_id,
+                base + ChronoDuration::milliseconds(offset),
+                "reserve",
+            );
+            child.parent_id = Some(root.id);
+            child.metadata = metadata_with_service("worker", "cart", 1);
+            child.metadata.instance_id = Some(instance.into());
+            graph.add_event(child).unwrap();
+        }
+
+        let call_counts = |deps: &ServiceDependencies| {
+            let mut calls: Vec<(String, String, usize)> = deps
+                .dependencies
+                .iter()
+                .map(|dep| (dep.from.clone(), dep.to.clone(), dep.call_count))
+                .collect();
+            calls.sort();
+            calls
+        };
+
+        let by_service = graph.get_service_dependencies(trace_id, false).unwrap();
+        assert_eq!(by_service.services.len(), 2);
+        assert_eq!(
+            call_counts(&by_service),
+            [("gateway".to_string(), "cart".to_string(), 3)]
+        );
+
+        // The gateway has no instance id and keeps its bare name
+        let by_instance = graph.get_service_dependencies(trace_id, true).unwrap();
+        let mut names: Vec<&str> = by_instance
+            .services
+            .iter()
+            .map(|service| service.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["cart#cart-1", "cart#cart-2", "gateway"]);
+        assert_eq!(
+            call_counts(&by_instance),
+            [
+                ("gateway".to_string(), "cart#cart-1".to_string(), 1),
+                ("gateway".to_string(), "cart#cart-2".to_string(), 2),
+            ]
+        );
+    }
+
     #[test]
     fn global_concurrency_detects_cross_trace_races() {
         let graph = CausalGraph::new();
@@ -2044,6 +4594,9 @@ mod tests {
             instance_id: None,
             distributed_span_id: None,
             upstream_span_id: None,
+            monotonic_ns: None,
+            retry_of: None,
+            attempt: None,
         }
     }
 
@@ -2752,6 +5305,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reapplying_distributed_edge_is_idempotent() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let mut metadata_a = metadata("service-a-thread", 5);
+        metadata_a.distributed_span_id = Some("span-a".into());
+        let mut metadata_b = metadata("service-b-thread", 5);
+        metadata_b.distributed_span_id = Some("span-b".into());
+
+        let make_event = |name: &str, offset_ms: i64, metadata: EventMetadata| Event {
+            id: Uuid::new_v4(),
+            trace_id,
+            parent_id: None,
+            timestamp: base + ChronoDuration::milliseconds(offset_ms),
+            kind: EventKind::FunctionCall {
+                function_name: name.into(),
+                module: name.into(),
+                args: serde_json::json!({}),
+                file: format!("{}.rs", name),
+                line: 1,
+            },
+            metadata,
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        };
+        let event_a = make_event("a", 0, metadata_a);
+        let event_b = make_event("b", 10, metadata_b);
+
+        graph.add_event(event_a.clone()).unwrap();
+        graph.add_event(event_b.clone()).unwrap();
+
+        let dist_edge = crate::event::DistributedEdge {
+            from_span: "span-a".into(),
+            to_span: "span-b".into(),
+            link_type: crate::event::EdgeLinkType::HttpCall,
+            metadata: serde_json::json!({}),
+        };
+
+        // Same edge twice in one batch, then replayed again (e.g. warmup after SDK retry)
+        graph.add_distributed_edges(vec![dist_edge.clone(), dist_edge.clone()]);
+        graph.add_distributed_edges(vec![dist_edge]);
+
+        let upstreams = graph.distributed_edges.get(&event_b.id).unwrap();
+        assert_eq!(upstreams.as_slice(), &[event_a.id]);
+        drop(upstreams);
+
+        assert!(graph.is_ancestor(event_a.id, event_b.id));
+        assert!(!graph.is_ancestor(event_b.id, event_a.id));
+    }
+
     #[test]
     fn is_ancestor_traverses_multi_hop_distributed_chain() {
         let graph = CausalGraph::new();
@@ -2965,4 +5570,1397 @@ mod tests {
             "should not create edge for missing span"
         );
     }
+
+    #[test]
+    fn causality_vector_is_pruned_to_component_limit() {
+        let graph = CausalGraph::new().with_clock_component_limit(8);
+        let trace_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        // A chain hopping across 20 instances accumulates one component per hop
+        let mut parent_id = None;
+        for hop in 0..20 {
+            let mut event = make_root(Uuid::new_v4(), trace_id, base, "hop");
+            event.parent_id = parent_id;
+            event.timestamp = base + ChronoDuration::milliseconds(hop);
+            event.metadata.instance_id = Some(format!("instance-{}", hop));
+            parent_id = Some(event.id);
+            graph.add_event(event).unwrap();
+        }
+
+        let last = graph
+            .nodes
+            .get(&parent_id.unwrap())
+            .unwrap()
+            .value()
+            .1
+            .event
+            .clone();
+        let components: Vec<&str> = last
+            .causality_vector
+            .iter()
+            .map(|(component, _)| component.as_str())
+            .collect();
+        let expected: Vec<String> = (12..20)
+            .map(|hop| format!("test-service#instance-{}", hop))
+            .collect();
+
+        assert_eq!(components, expected);
+        assert!(last
+            .causality_vector
+            .windows(2)
+            .all(|pair| pair[0].1 < pair[1].1));
+    }
+
+    // ─── Lock Recommendation Tests ──────────────────────────────────────────
+
+    fn write_event(trace_id: Uuid, thread: &str, service: &str, variable: &str) -> Event {
+        Event {
+            id: Uuid::new_v4(),
+            trace_id,
+            parent_id: None,
+            timestamp: Utc::now(),
+            kind: EventKind::StateChange {
+                variable: variable.into(),
+                old_value: None,
+                new_value: serde_json::json!(1),
+                location: "bank.rs:10".into(),
+                access_type: AccessType::Write,
+            },
+            metadata: metadata_with_service(thread, service, 1),
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
+    fn lock_event(trace_id: Uuid, thread: &str, lock_id: &str, acquire: bool) -> Event {
+        let kind = if acquire {
+            EventKind::LockAcquire {
+                lock_id: lock_id.into(),
+                lock_type: "Mutex".into(),
+                location: "bank.rs:9".into(),
+            }
+        } else {
+            EventKind::LockRelease {
+                lock_id: lock_id.into(),
+                lock_type: "Mutex".into(),
+                location: "bank.rs:11".into(),
+            }
+        };
+        Event {
+            id: Uuid::new_v4(),
+            trace_id,
+            parent_id: None,
+            timestamp: Utc::now(),
+            kind,
+            metadata: metadata(thread, 1),
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn lock_recommendation_for_consistently_unlocked_variable() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+
+        for (thread, service) in [("t1", "api"), ("t2", "api"), ("t3", "worker")] {
+            graph
+                .add_event(write_event(trace_id, thread, service, "alice.balance"))
+                .unwrap();
+        }
+
+        assert_eq!(
+            graph
+                .lock_recommendation("alice.balance", &HashSet::from([trace_id]))
+                .as_deref(),
+            Some(
+                "guard `alice.balance` with a mutex; it is accessed by 3 threads across 2 services with no common lock"
+            )
+        );
+    }
+
+    #[test]
+    fn lock_recommendation_for_inconsistently_locked_variable() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+
+        // Three accesses hold balance-lock, two do not
+        for thread in ["t1", "t2", "t3"] {
+            graph
+                .add_event(lock_event(trace_id, thread, "balance-lock", true))
+                .unwrap();
+            graph
+                .add_event(write_event(trace_id, thread, "api", "alice.balance"))
+                .unwrap();
+            graph
+                .add_event(lock_event(trace_id, thread, "balance-lock", false))
+                .unwrap();
+        }
+        for thread in ["t4", "t5"] {
+            graph
+                .add_event(write_event(trace_id, thread, "api", "alice.balance"))
+                .unwrap();
+        }
+
+        let traces = HashSet::from([trace_id]);
+        assert_eq!(
+            graph
+                .lock_recommendation("alice.balance", &traces)
+                .as_deref(),
+            Some("use lock `balance-lock` consistently — 2 of 5 accesses were unlocked")
+        );
+        assert!(graph.lock_recommendation("unknown", &traces).is_none());
+    }
+
+    #[test]
+    fn lock_recommendation_counts_only_the_races_traces() {
+        let graph = CausalGraph::new();
+        let (locked_trace, unlocked_trace) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // One trace takes balance-lock on every access but one...
+        for thread in ["t1", "t2", "t3"] {
+            graph
+                .add_event(lock_event(locked_trace, thread, "balance-lock", true))
+                .unwrap();
+            graph
+                .add_event(write_event(locked_trace, thread, "api", "alice.balance"))
+                .unwrap();
+            graph
+                .add_event(lock_event(locked_trace, thread, "balance-lock", false))
+                .unwrap();
+        }
+        graph
+            .add_event(write_event(locked_trace, "t4", "api", "alice.balance"))
+            .unwrap();
+        // ...the other never takes it
+        for thread in ["w1", "w2"] {
+            graph
+                .add_event(write_event(
+                    unlocked_trace,
+                    thread,
+                    "worker",
+                    "alice.balance",
+                ))
+                .unwrap();
+        }
+
+        assert_eq!(
+            graph
+                .lock_recommendation("alice.balance", &HashSet::from([locked_trace]))
+                .as_deref(),
+            Some("use lock `balance-lock` consistently — 1 of 4 accesses were unlocked")
+        );
+        assert_eq!(
+            graph
+                .lock_recommendation("alice.balance", &HashSet::from([unlocked_trace]))
+                .as_deref(),
+            Some(
+                "guard `alice.balance` with a mutex; it is accessed by 2 threads across 1 service with no common lock"
+            )
+        );
+    }
+
+    // ─── Double-Checked Locking Tests ───────────────────────────────────────
+
+    fn read_event(trace_id: Uuid, thread: &str, variable: &str, access_type: AccessType) -> Event {
+        let mut event = write_event(trace_id, thread, "api", variable);
+        if let EventKind::StateChange {
+            access_type: access,
+            ..
+        } = &mut event.kind
+        {
+            *access = access_type;
+        }
+        event
+    }
+
+    fn add_double_checked_init(graph: &CausalGraph, trace_id: Uuid, flag_access: AccessType) {
+        graph
+            .add_event(read_event(trace_id, "t1", "initialized", flag_access))
+            .unwrap();
+        graph
+            .add_event(lock_event(trace_id, "t1", "init-lock", true))
+            .unwrap();
+        graph
+            .add_event(read_event(trace_id, "t1", "initialized", flag_access))
+            .unwrap();
+        graph
+            .add_event(write_event(trace_id, "t1", "api", "initialized"))
+            .unwrap();
+        graph
+            .add_event(lock_event(trace_id, "t1", "init-lock", false))
+            .unwrap();
+    }
+
+    #[test]
+    fn double_checked_locking_on_plain_flag_is_a_warning() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        add_double_checked_init(&graph, trace_id, AccessType::Read);
+
+        let findings = graph.find_double_checked_locking(trace_id).unwrap();
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.variable, "initialized");
+        assert_eq!(finding.thread_id, "t1");
+        assert_eq!(finding.lock_id, "init-lock");
+        assert_eq!(finding.severity, AnomalySeverity::Warning);
+        assert_ne!(finding.unlocked_read, finding.locked_read);
+    }
+
+    #[test]
+    fn double_checked_locking_on_atomic_flag_is_not_flagged() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        add_double_checked_init(&graph, trace_id, AccessType::AtomicRead);
+
+        assert!(graph
+            .find_double_checked_locking(trace_id)
+            .unwrap()
+            .is_empty());
+    }
+
+    // ─── Unreleased Lock Tests ──────────────────────────────────────────────
+
+    #[test]
+    fn unreleased_lock_does_not_protect_accesses_after_its_section() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let at = |mut event: Event, offset_ms: i64| {
+            event.timestamp = base + ChronoDuration::milliseconds(offset_ms);
+            event
+        };
+
+        // t1 takes balance-lock, writes inside the section and returns without
+        // releasing it. t2 holds the lock for 2ms around its own write, so by
+        // the time t1 writes again its section has long ended.
+        let events = [
+            at(lock_event(trace_id, "t1", "balance-lock", true), 0),
+            at(write_event(trace_id, "t1", "api", "alice.balance"), 1),
+            at(lock_event(trace_id, "t2", "balance-lock", true), 2),
+            at(write_event(trace_id, "t2", "api", "alice.balance"), 3),
+            at(lock_event(trace_id, "t2", "balance-lock", false), 4),
+            at(write_event(trace_id, "t1", "api", "alice.balance"), 10),
+        ];
+        let orphaned_id = events[0].id;
+        let locked_write = events[3].id;
+        let unprotected_write = events[5].id;
+        for event in events {
+            graph.add_event(event).unwrap();
+        }
+
+        let unreleased = graph.find_unreleased_locks(trace_id).unwrap();
+        assert_eq!(unreleased.len(), 1);
+        assert_eq!(unreleased[0].imbalance, LockImbalance::UnreleasedAcquire);
+        assert_eq!(unreleased[0].thread_id, "t1");
+        assert_eq!(unreleased[0].lock_id, "balance-lock");
+        assert_eq!(unreleased[0].acquires, 1);
+        assert_eq!(unreleased[0].releases, 0);
+        assert_eq!(unreleased[0].orphaned_acquire, Some(orphaned_id));
+        assert_eq!(unreleased[0].location.as_deref(), Some("bank.rs:9"));
+        let windows = &unreleased[0].stale_windows;
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].from, base + ChronoDuration::milliseconds(2));
+        assert_eq!(windows[0].until, None);
+
+        // The write inside t1's section stays protected; the one after it races
+        let races = graph.find_concurrent_events(trace_id).unwrap();
+        assert_eq!(races.len(), 1);
+        let (a, b) = &races[0];
+        let mut ids = [a.id, b.id];
+        ids.sort();
+        let mut expected = [locked_write, unprotected_write];
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn release_without_acquire_is_reported_with_its_location() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+
+        let release = lock_event(trace_id, "t1", "balance-lock", false);
+        let release_id = release.id;
+        graph.add_event(release).unwrap();
+
+        let unreleased = graph.find_unreleased_locks(trace_id).unwrap();
+        assert_eq!(unreleased.len(), 1);
+        assert_eq!(unreleased[0].imbalance, LockImbalance::UnmatchedRelease);
+        assert_eq!(unreleased[0].releases, 1);
+        assert_eq!(unreleased[0].unmatched_release, Some(release_id));
+        assert_eq!(unreleased[0].orphaned_acquire, None);
+        assert_eq!(unreleased[0].location.as_deref(), Some("bank.rs:11"));
+    }
+
+    #[test]
+    fn orphaned_lock_does_not_mask_races_after_its_section() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let at = |mut event: Event, offset_ms: i64| {
+            event.timestamp = base + ChronoDuration::milliseconds(offset_ms);
+            event
+        };
+
+        // t1 fails inside balance-lock, nested in request-lock, without
+        // tracking the release; it writes after leaving request-lock, then
+        // later takes balance-lock properly
+        let events = [
+            at(lock_event(trace_id, "t1", "request-lock", true), 0),
+            at(lock_event(trace_id, "t1", "balance-lock", true), 1),
+            at(lock_event(trace_id, "t1", "request-lock", false), 2),
+            at(write_event(trace_id, "t1", "api", "alice.balance"), 3),
+            at(lock_event(trace_id, "t2", "balance-lock", true), 4),
+            at(write_event(trace_id, "t2", "api", "alice.balance"), 5),
+            at(lock_event(trace_id, "t2", "balance-lock", false), 6),
+            at(lock_event(trace_id, "t1", "balance-lock", true), 7),
+            at(write_event(trace_id, "t1", "api", "alice.balance"), 8),
+            at(lock_event(trace_id, "t1", "balance-lock", false), 9),
+        ];
+        let unprotected_write = events[3].id;
+        let retaken_at = events[7].timestamp;
+        for event in events {
+            graph.add_event(event).unwrap();
+        }
+
+        let unreleased = graph.find_unreleased_locks(trace_id).unwrap();
+        assert_eq!(unreleased.len(), 1);
+        let windows = &unreleased[0].stale_windows;
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].from, base + ChronoDuration::milliseconds(2));
+        assert_eq!(windows[0].until, Some(retaken_at));
+
+        // Only the write outside any section races; the retaken section is
+        // protected again
+        let races = graph.find_concurrent_events(trace_id).unwrap();
+        assert_eq!(races.len(), 1);
+        let (a, b) = &races[0];
+        assert!(a.id == unprotected_write || b.id == unprotected_write);
+        assert!(a.lock_set.contains(&"balance-lock".to_string()));
+        assert!(b.lock_set.contains(&"balance-lock".to_string()));
+    }
+
+    #[test]
+    fn balanced_locks_are_not_reported() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+
+        for thread in ["t1", "t2"] {
+            graph
+                .add_event(lock_event(trace_id, thread, "balance-lock", true))
+                .unwrap();
+            graph
+                .add_event(write_event(trace_id, thread, "api", "alice.balance"))
+                .unwrap();
+            graph
+                .add_event(lock_event(trace_id, thread, "balance-lock", false))
+                .unwrap();
+        }
+
+        assert!(graph.find_unreleased_locks(trace_id).unwrap().is_empty());
+        assert!(graph.find_concurrent_events(trace_id).unwrap().is_empty());
+    }
+
+    // ─── Lock Order Tests ───────────────────────────────────────────────────
+
+    /// Take `first` then `second` on `thread`, returning the event id of the
+    /// nested acquire
+    fn add_nested_locks(
+        graph: &CausalGraph,
+        trace_id: Uuid,
+        thread: &str,
+        first: &str,
+        second: &str,
+    ) -> Uuid {
+        let nested = lock_event(trace_id, thread, second, true);
+        let nested_id = nested.id;
+        for event in [
+            lock_event(trace_id, thread, first, true),
+            nested,
+            lock_event(trace_id, thread, second, false),
+            lock_event(trace_id, thread, first, false),
+        ] {
+            graph.add_event(event).unwrap();
+        }
+        nested_id
+    }
+
+    #[test]
+    fn opposite_lock_orders_are_reported_as_inversion() {
+        let graph = CausalGraph::new();
+        let a_then_b = add_nested_locks(&graph, Uuid::new_v4(), "t1", "lock-a", "lock-b");
+        let b_then_a = add_nested_locks(&graph, Uuid::new_v4(), "t2", "lock-b", "lock-a");
+
+        let violations = graph.detect_lock_order_inversions();
+        assert_eq!(violations.len(), 1);
+        let violation = &violations[0];
+        assert_eq!(violation.lock_a, "lock-a");
+        assert_eq!(violation.lock_b, "lock-b");
+        assert_eq!(violation.a_then_b.thread_id, "t1");
+        assert_eq!(violation.a_then_b.event_id, a_then_b);
+        assert_eq!(violation.b_then_a.len(), 1);
+        assert_eq!(violation.b_then_a[0].held, "lock-b");
+        assert_eq!(violation.b_then_a[0].acquired, "lock-a");
+        assert_eq!(violation.b_then_a[0].thread_id, "t2");
+        assert_eq!(violation.b_then_a[0].event_id, b_then_a);
+    }
+
+    #[test]
+    fn consistent_lock_order_is_not_reported() {
+        let graph = CausalGraph::new();
+        add_nested_locks(&graph, Uuid::new_v4(), "t1", "lock-a", "lock-b");
+        add_nested_locks(&graph, Uuid::new_v4(), "t2", "lock-a", "lock-b");
+
+        assert!(graph.detect_lock_order_inversions().is_empty());
+    }
+
+    // ─── Lock Contention Tests ──────────────────────────────────────────────
+
+    /// `lock_event` at `offset_ms` past a fixed start
+    fn lock_event_at(
+        trace_id: Uuid,
+        thread: &str,
+        lock_id: &str,
+        acquire: bool,
+        offset_ms: i64,
+    ) -> Event {
+        let mut event = lock_event(trace_id, thread, lock_id, acquire);
+        event.timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+            + ChronoDuration::milliseconds(offset_ms);
+        event
+    }
+
+    #[test]
+    fn lock_contention_stats_count_threads_and_hold_times() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        // t1 and t2 take turns on the same lock; t2 also takes an unrelated lock
+        for (thread, lock_id, acquire, offset_ms) in [
+            ("t1", "accounts", true, 0),
+            ("t1", "accounts", false, 10),
+            ("t2", "accounts", true, 12),
+            ("t2", "audit", true, 14),
+            ("t2", "audit", false, 15),
+            ("t2", "accounts", false, 42),
+            ("t1", "accounts", true, 45),
+            ("t1", "accounts", false, 50),
+        ] {
+            graph
+                .add_event(lock_event_at(trace_id, thread, lock_id, acquire, offset_ms))
+                .unwrap();
+        }
+
+        let stats = graph.lock_contention_stats();
+        assert_eq!(stats.len(), 2);
+
+        let accounts = &stats[0];
+        assert_eq!(accounts.lock_id, "accounts");
+        assert_eq!(accounts.acquisitions, 3);
+        assert_eq!(accounts.thread_count, 2);
+        assert_eq!(accounts.trace_count, 1);
+        assert!((accounts.total_hold_ms - 45.0).abs() < 1e-9);
+        assert!((accounts.mean_hold_ms - 15.0).abs() < 1e-9);
+        assert!((accounts.max_hold_ms - 30.0).abs() < 1e-9);
+
+        let audit = &stats[1];
+        assert_eq!(audit.lock_id, "audit");
+        assert_eq!(audit.acquisitions, 1);
+        assert_eq!(audit.thread_count, 1);
+        assert!((audit.total_hold_ms - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lock_hold_anomalies_flag_holds_far_above_the_lock_baseline() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        // Six 2ms holds of "accounts", one 20ms hold, and a short-lived "audit" lock
+        let mut offset_ms = 0;
+        for held_ms in [2, 2, 2, 20, 2, 2, 2] {
+            for (acquire, at) in [(true, offset_ms), (false, offset_ms + held_ms)] {
+                graph
+                    .add_event(lock_event_at(trace_id, "t1", "accounts", acquire, at))
+                    .unwrap();
+            }
+            offset_ms += held_ms + 1;
+        }
+        for (acquire, at) in [(true, offset_ms), (false, offset_ms + 50)] {
+            graph
+                .add_event(lock_event_at(trace_id, "t2", "audit", acquire, at))
+                .unwrap();
+        }
+
+        let anomalies = graph.detect_lock_hold_anomalies(trace_id).unwrap();
+        assert_eq!(anomalies.len(), 1);
+
+        let anomaly = &anomalies[0];
+        assert_eq!(anomaly.event_kind, "accounts");
+        assert!((anomaly.actual_duration_ms - 20.0).abs() < 1e-9);
+        assert!((anomaly.expected_duration_ms - 2.0).abs() < 1e-9);
+        assert_eq!(anomaly.severity, AnomalySeverity::Critical);
+
+        assert!(graph.detect_lock_hold_anomalies(Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn unreleased_acquires_count_without_hold_time() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        for (thread, acquire, offset_ms) in [("t1", true, 0), ("t1", false, 4), ("t2", true, 6)] {
+            let event = lock_event_at(trace_id, thread, "accounts", acquire, offset_ms);
+            graph.add_event(event).unwrap();
+        }
+
+        let stats = graph.lock_contention_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].acquisitions, 2);
+        assert_eq!(stats[0].thread_count, 2);
+        assert!((stats[0].total_hold_ms - 4.0).abs() < 1e-9);
+        assert!((stats[0].mean_hold_ms - 4.0).abs() < 1e-9);
+    }
+
+    // ─── Variable Hotspot Tests ─────────────────────────────────────────────
+
+    #[test]
+    fn variable_hotspots_rank_by_access_count() {
+        let graph = CausalGraph::new();
+        let (trace1, trace2) = (Uuid::new_v4(), Uuid::new_v4());
+        // "balance" has the most accesses; "cache" spans more traces but has fewer
+        for (trace_id, thread, service, variable) in [
+            (trace1, "t1", "api", "balance"),
+            (trace1, "t2", "api", "balance"),
+            (trace1, "t1", "worker", "balance"),
+            (trace1, "t1", "api", "cache"),
+            (trace2, "t1", "api", "cache"),
+            (trace2, "t1", "api", "audit"),
+        ] {
+            graph
+                .add_event(write_event(trace_id, thread, service, variable))
+                .unwrap();
+        }
+
+        let hotspots = graph.variable_hotspots(2, None);
+        let ranked: Vec<&str> = hotspots.iter().map(|h| h.variable.as_str()).collect();
+        assert_eq!(ranked, vec!["balance", "cache"]);
+
+        let balance = &hotspots[0];
+        assert_eq!(balance.access_count, 3);
+        assert_eq!(balance.trace_count, 1);
+        assert_eq!(balance.services, vec!["api", "worker"]);
+        assert_eq!(balance.accesses_by_service["api"], 2);
+        assert_eq!(balance.accesses_by_service["worker"], 1);
+        assert_eq!(hotspots[1].trace_count, 2);
+
+        assert!(graph.variable_hotspots(10, Some("production")).is_empty());
+    }
+
+    // ─── Monotonic Ordering Tests ───────────────────────────────────────────
+
+    #[test]
+    fn monotonic_offset_orders_sub_millisecond_same_thread_events() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        // The second write's wall clock reads earlier (skew), but its monotonic
+        // offset is 400µs after the first write.
+        let mut first = write_event(trace_id, "t1", "api", "counter");
+        first.timestamp = base + ChronoDuration::microseconds(500);
+        first.metadata.monotonic_ns = Some(1_000_000);
+
+        let mut second = write_event(trace_id, "t1", "api", "counter");
+        second.timestamp = base + ChronoDuration::microseconds(300);
+        second.metadata.monotonic_ns = Some(1_400_000);
+
+        let first_id = first.id.to_string();
+        let second_id = second.id.to_string();
+        graph.ingest_events(vec![second, first]).unwrap();
+
+        let trail = graph.get_audit_trail(trace_id, "counter").unwrap();
+        let order: Vec<_> = trail.accesses.iter().map(|a| a.event_id.clone()).collect();
+        assert_eq!(order, vec![first_id, second_id]);
+    }
+
+    // ─── Orphan Buffer Tests ────────────────────────────────────────────────
+
+    #[test]
+    fn child_in_earlier_batch_links_when_parent_arrives() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let mut child = write_event(trace_id, "worker-a", "api", "balance");
+        child.parent_id = Some(root_id);
+        child.timestamp = base + ChronoDuration::milliseconds(1);
+        let child_id = child.id;
+
+        graph.add_events_reconciling(vec![child]).unwrap();
+        assert!(graph.is_orphan(child_id));
+        assert!(!graph.has_trace(trace_id));
+
+        graph
+            .add_events_reconciling(vec![make_root(root_id, trace_id, base, "root")])
+            .unwrap();
+        assert!(!graph.is_orphan(child_id));
+
+        let tree = graph.get_trace_tree(trace_id).unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].id, root_id.to_string());
+        assert_eq!(tree[0].children.len(), 1);
+        assert_eq!(tree[0].children[0].id, child_id.to_string());
+    }
+
+    #[test]
+    fn full_orphan_buffer_adds_oldest_as_root() {
+        let graph = CausalGraph::new().with_orphan_buffer(1, std::time::Duration::from_secs(60));
+        let trace_id = Uuid::new_v4();
+
+        let mut first = write_event(trace_id, "worker-a", "api", "balance");
+        first.parent_id = Some(Uuid::new_v4());
+        let first_id = first.id;
+        let mut second = write_event(trace_id, "worker-b", "api", "balance");
+        second.parent_id = Some(Uuid::new_v4());
+        let second_id = second.id;
+
+        graph.add_events_reconciling(vec![first]).unwrap();
+        graph.add_events_reconciling(vec![second]).unwrap();
+
+        assert!(graph.contains_event(first_id));
+        assert!(graph.is_orphan(second_id));
+        let roots: Vec<String> = graph
+            .get_trace_tree(trace_id)
+            .unwrap()
+            .into_iter()
+            .map(|node| node.id)
+            .collect();
+        assert_eq!(roots, vec![first_id.to_string()]);
+    }
+
+    // ─── Retention Tests ────────────────────────────────────────────────────
+
+    /// A root with one `balance` write under it, `minutes` after a fixed base time
+    fn add_trace_at(graph: &CausalGraph, minutes: i64) -> Uuid {
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let start =
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap() + ChronoDuration::minutes(minutes);
+
+        graph
+            .add_event(make_root(root_id, trace_id, start, "root"))
+            .unwrap();
+        let mut write = write_event(trace_id, "worker", "api", "balance");
+        write.parent_id = Some(root_id);
+        write.timestamp = start + ChronoDuration::milliseconds(1);
+        graph.add_event(write).unwrap();
+        trace_id
+    }
+
+    #[test]
+    fn retain_recent_evicts_oldest_traces_and_reclaims_their_nodes() {
+        let graph = CausalGraph::new();
+        // Added out of order so eviction can't just follow node indices
+        let middle = add_trace_at(&graph, 1);
+        let oldest = add_trace_at(&graph, 0);
+        let newest = add_trace_at(&graph, 2);
+        assert_eq!(graph.stats().total_events, 6);
+
+        assert_eq!(graph.retain_recent(2), HashSet::from([oldest]));
+
+        let mut remaining = graph.get_all_trace_ids();
+        remaining.sort();
+        let mut expected = vec![middle, newest];
+        expected.sort();
+        assert_eq!(remaining, expected);
+
+        let stats = graph.stats();
+        assert_eq!(stats.total_events, 4);
+        assert_eq!(stats.total_edges, 2);
+        assert_eq!(graph.graph.lock().unwrap().node_count(), 4);
+        assert_eq!(graph.variable_index.get("balance").unwrap().len(), 2);
+        assert!(!graph.vector_clocks.contains_key(&oldest));
+
+        // Surviving node indices still point at their own events
+        for trace_id in [middle, newest] {
+            let tree = graph.get_trace_tree(trace_id).unwrap();
+            assert_eq!(tree.len(), 1);
+            assert_eq!(tree[0].children.len(), 1);
+        }
+
+        assert!(graph.retain_recent(2).is_empty());
+    }
+
+    #[test]
+    fn evicted_trace_can_be_ingested_again() {
+        let graph = CausalGraph::new();
+        let oldest = add_trace_at(&graph, 0);
+        add_trace_at(&graph, 1);
+        let events = graph.get_causal_order(oldest).unwrap();
+
+        graph.retain_recent(1);
+        assert!(!graph.has_trace(oldest));
+
+        graph.ingest_events(events).unwrap();
+        assert!(graph.has_trace(oldest));
+        assert_eq!(graph.get_causal_order(oldest).unwrap().len(), 2);
+    }
+
+    // ─── Safe Pattern Tests ─────────────────────────────────────────────────
+
+    #[test]
+    fn declared_safe_pattern_suppresses_only_matching_variables() {
+        let graph = CausalGraph::new().with_safe_patterns(vec![SafePattern {
+            variable_glob: "counter.*".into(),
+            access_a: AccessType::Write,
+            access_b: AccessType::Write,
+        }]);
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        for variable in ["counter.hits", "balance"] {
+            for (thread, offset_ms) in [("worker-a", 1), ("worker-b", 2)] {
+                let mut event = write_event(trace_id, thread, "api", variable);
+                event.parent_id = Some(root_id);
+                event.timestamp = base + ChronoDuration::milliseconds(offset_ms);
+                graph.add_event(event).unwrap();
+            }
+        }
+
+        let races = graph.find_concurrent_events(trace_id).unwrap();
+        assert_eq!(races.len(), 1);
+        assert!(matches!(
+            &races[0].0.kind,
+            EventKind::StateChange { variable, .. } if variable == "balance"
+        ));
+
+        // The variable is still tracked even though its races are suppressed
+        assert!(graph.variable_index.contains_key("counter.hits"));
+    }
+
+    // ─── Lock Family Tests ──────────────────────────────────────────────────
+
+    #[test]
+    fn accesses_under_different_stripes_of_a_lock_family_do_not_race() {
+        let graph = CausalGraph::new().with_lock_families(vec![LockFamily {
+            lock_glob: "balance-lock-*".into(),
+            variable_glob: "*.balance".into(),
+        }]);
+        let trace_id = Uuid::new_v4();
+
+        // Both variables are written under stripe 3 on one thread and stripe 7
+        // on the other, but only alice.balance is covered by the family
+        for (thread, stripe) in [("t1", "balance-lock-3"), ("t2", "balance-lock-7")] {
+            graph
+                .add_event(lock_event(trace_id, thread, stripe, true))
+                .unwrap();
+            graph
+                .add_event(write_event(trace_id, thread, "api", "alice.balance"))
+                .unwrap();
+            graph
+                .add_event(write_event(trace_id, thread, "api", "bob.total"))
+                .unwrap();
+            graph
+                .add_event(lock_event(trace_id, thread, stripe, false))
+                .unwrap();
+        }
+
+        let races = graph.find_concurrent_events(trace_id).unwrap();
+        assert_eq!(races.len(), 1);
+        assert!(matches!(
+            &races[0].0.kind,
+            EventKind::StateChange { variable, .. } if variable == "bob.total"
+        ));
+
+        let alice: Vec<Uuid> = graph
+            .variable_index
+            .get("alice.balance")
+            .unwrap()
+            .value()
+            .clone();
+        let explanation = graph.explain_pair(trace_id, alice[0], alice[1]).unwrap();
+        assert!(!explanation.race);
+        assert_eq!(explanation.shared_locks, vec!["balance-lock-*".to_string()]);
+    }
+
+    // ─── Race Policy Tests ──────────────────────────────────────────────────
+
+    /// One `access` to `variable` on each of two threads
+    fn add_unordered_accesses(graph: &CausalGraph, variable: &str, access: AccessType) -> Uuid {
+        add_unordered_access_pair(graph, variable, access, access)
+    }
+
+    /// `first` on one thread and `second` on another, both to `variable`
+    fn add_unordered_access_pair(
+        graph: &CausalGraph,
+        variable: &str,
+        first: AccessType,
+        second: AccessType,
+    ) -> Uuid {
+        let trace_id = Uuid::new_v4();
+        for (thread, access) in [("t1", first), ("t2", second)] {
+            graph
+                .add_event(read_event(trace_id, thread, variable, access))
+                .unwrap();
+        }
+        trace_id
+    }
+
+    #[test]
+    fn default_race_policy_allows_atomic_pairs_and_reads() {
+        let graph = CausalGraph::new();
+        let rmw_trace = add_unordered_accesses(&graph, "hits", AccessType::AtomicRMW);
+        let read_trace = add_unordered_accesses(&graph, "config", AccessType::Read);
+
+        assert!(graph.find_concurrent_events(rmw_trace).unwrap().is_empty());
+        assert!(graph.find_concurrent_events(read_trace).unwrap().is_empty());
+        assert!(graph.find_global_concurrent_events().unwrap().is_empty());
+    }
+
+    #[test]
+    fn atomic_accesses_never_race_each_other() {
+        use AccessType::*;
+
+        for (first, second) in [
+            (AtomicRead, AtomicWrite),
+            (AtomicWrite, AtomicWrite),
+            (AtomicWrite, AtomicRMW),
+            (AtomicRead, AtomicRMW),
+        ] {
+            let graph = CausalGraph::new();
+            let trace_id = add_unordered_access_pair(&graph, "flag", first, second);
+            assert!(
+                graph.find_concurrent_events(trace_id).unwrap().is_empty(),
+                "{:?}/{:?} should not be a race",
+                first,
+                second
+            );
+        }
+    }
+
+    #[test]
+    fn atomic_access_mixed_with_plain_access_is_a_race() {
+        use AccessType::*;
+
+        for (first, second) in [
+            (Read, AtomicWrite),
+            (Write, AtomicRead),
+            (Write, AtomicWrite),
+            (AtomicRMW, Write),
+        ] {
+            let graph = CausalGraph::new();
+            let trace_id = add_unordered_access_pair(&graph, "flag", first, second);
+            assert_eq!(
+                graph.find_concurrent_events(trace_id).unwrap().len(),
+                1,
+                "{:?}/{:?} should be a race",
+                first,
+                second
+            );
+        }
+    }
+
+    #[test]
+    fn custom_race_policy_changes_which_pairs_are_flagged() {
+        let graph = CausalGraph::new().with_race_policy(RaceDetectionPolicy {
+            read_read_safe: false,
+            atomic_rmw_rmw_safe: false,
+            ..RaceDetectionPolicy::default()
+        });
+        let rmw_trace = add_unordered_accesses(&graph, "hits", AccessType::AtomicRMW);
+        let read_trace = add_unordered_accesses(&graph, "config", AccessType::Read);
+
+        assert_eq!(graph.find_concurrent_events(rmw_trace).unwrap().len(), 1);
+        assert_eq!(graph.find_concurrent_events(read_trace).unwrap().len(), 1);
+        assert_eq!(graph.find_global_concurrent_events().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn read_write_safe_only_covers_plain_read_write_pairs() {
+        let graph = CausalGraph::new().with_race_policy(RaceDetectionPolicy {
+            read_write_safe: true,
+            ..RaceDetectionPolicy::default()
+        });
+        let read_write =
+            add_unordered_access_pair(&graph, "config", AccessType::Read, AccessType::Write);
+        let write_write = add_unordered_accesses(&graph, "balance", AccessType::Write);
+
+        assert!(graph.find_concurrent_events(read_write).unwrap().is_empty());
+        assert_eq!(graph.find_concurrent_events(write_write).unwrap().len(), 1);
+    }
+
+    // ─── Variable-Scoped Global Race Tests ──────────────────────────────────
+
+    #[test]
+    fn global_races_for_variable_only_pair_that_variable() {
+        let graph = CausalGraph::new();
+        add_unordered_accesses(&graph, "balance", AccessType::Write);
+        add_unordered_accesses(&graph, "balance", AccessType::Write);
+        add_unordered_accesses(&graph, "hits", AccessType::Write);
+
+        let all = graph.find_global_concurrent_events().unwrap();
+        let scoped = graph.find_global_concurrent_events_for("balance");
+        assert!(all.len() > scoped.len());
+        assert!(!scoped.is_empty());
+        for (a, b) in &scoped {
+            for event in [a, b] {
+                assert!(matches!(
+                    &event.kind,
+                    EventKind::StateChange { variable, .. } if variable == "balance"
+                ));
+            }
+        }
+
+        let expected = all
+            .iter()
+            .filter(|(a, _)| {
+                matches!(&a.kind, EventKind::StateChange { variable, .. } if variable == "balance")
+            })
+            .count();
+        assert_eq!(scoped.len(), expected);
+
+        let missing = graph.find_global_concurrent_events_for("missing");
+        assert!(missing.is_empty());
+    }
+
+    // ─── Race Severity Tests ────────────────────────────────────────────────
+
+    fn write_value(trace_id: Uuid, thread: &str, variable: &str, value: i64) -> Event {
+        let mut event = write_event(trace_id, thread, "api", variable);
+        if let EventKind::StateChange { new_value, .. } = &mut event.kind {
+            *new_value = serde_json::json!(value);
+        }
+        event
+    }
+
+    #[test]
+    fn divergent_write_write_race_is_critical() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        graph
+            .add_event(write_value(trace_id, "t1", "balance", 50))
+            .unwrap();
+        graph
+            .add_event(write_value(trace_id, "t2", "balance", 75))
+            .unwrap();
+
+        let races = graph.find_races_with_severity(trace_id).unwrap();
+        assert_eq!(races.len(), 1);
+        assert_eq!(races[0].severity, RaceSeverity::Critical);
+        assert!(races[0].values_diverge);
+        assert_eq!(races[0].concurrent_writers, 2);
+    }
+
+    #[test]
+    fn single_read_write_race_is_warning() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        graph
+            .add_event(read_event(trace_id, "t1", "balance", AccessType::Read))
+            .unwrap();
+        graph
+            .add_event(write_value(trace_id, "t2", "balance", 75))
+            .unwrap();
+
+        let races = graph.find_races_with_severity(trace_id).unwrap();
+        assert_eq!(races.len(), 1);
+        assert_eq!(races[0].severity, RaceSeverity::Warning);
+        assert!(!races[0].values_diverge);
+        assert_eq!(races[0].concurrent_writers, 1);
+    }
+
+    #[test]
+    fn race_severity_weighs_values_and_writer_count() {
+        use AccessType::{Read, Write};
+        use RaceSeverity::{Critical, Info, Warning};
+
+        assert_eq!(race_severity(Write, Write, true, 2), Critical);
+        assert_eq!(race_severity(Write, Write, false, 2), Warning);
+        assert_eq!(race_severity(Write, Write, false, 3), Critical);
+        assert_eq!(race_severity(Read, Write, false, 2), Critical);
+        assert_eq!(race_severity(Read, Read, false, 0), Info);
+    }
+
+    // ─── Temporal Overlap Tests ─────────────────────────────────────────────
+
+    /// Two unordered writes to `balance` on different threads, `offset_ms` apart,
+    /// each lasting `duration_ms`
+    fn distant_writes_graph(graph: &CausalGraph, offset_ms: i64, duration_ms: u64) -> Uuid {
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        for (thread, start_ms) in [("worker-a", 1), ("worker-b", 1 + offset_ms)] {
+            let mut event = write_event(trace_id, thread, "api", "balance");
+            event.parent_id = Some(root_id);
+            event.timestamp = base + ChronoDuration::milliseconds(start_ms);
+            event.metadata.duration_ns = Some(duration_ms * 1_000_000);
+            graph.add_event(event).unwrap();
+        }
+
+        trace_id
+    }
+
+    #[test]
+    fn temporal_overlap_mode_ignores_distant_accesses() {
+        // Without the mode, unordered writes a second apart are still a race
+        let graph = CausalGraph::new();
+        let trace_id = distant_writes_graph(&graph, 1_000, 5);
+        assert_eq!(graph.find_concurrent_events(trace_id).unwrap().len(), 1);
+
+        let graph = CausalGraph::new().with_temporal_overlap(10);
+        let trace_id = distant_writes_graph(&graph, 1_000, 5);
+        assert!(graph.find_concurrent_events(trace_id).unwrap().is_empty());
+        assert!(graph.find_global_concurrent_events().unwrap().is_empty());
+    }
+
+    #[test]
+    fn temporal_overlap_mode_keeps_overlapping_and_near_accesses() {
+        // [1, 11] and [6, 16] overlap
+        let graph = CausalGraph::new().with_temporal_overlap(0);
+        let trace_id = distant_writes_graph(&graph, 5, 10);
+        assert_eq!(graph.find_concurrent_events(trace_id).unwrap().len(), 1);
+
+        // [1, 3] and [8, 10] are 5ms apart: within a 10ms gap, outside a 2ms gap
+        let graph = CausalGraph::new().with_temporal_overlap(10);
+        let trace_id = distant_writes_graph(&graph, 7, 2);
+        assert_eq!(graph.find_concurrent_events(trace_id).unwrap().len(), 1);
+
+        let graph = CausalGraph::new().with_temporal_overlap(2);
+        let trace_id = distant_writes_graph(&graph, 7, 2);
+        assert!(graph.find_concurrent_events(trace_id).unwrap().is_empty());
+    }
+
+    // ─── Race Explanation Tests ─────────────────────────────────────────────
+
+    #[test]
+    fn explain_pair_reports_unlocked_concurrent_writes() {
+        let graph = CausalGraph::new();
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        let mut ids = Vec::new();
+        for (thread, offset_ms) in [("worker-a", 1), ("worker-b", 2)] {
+            let mut event = write_event(trace_id, thread, "api", "balance");
+            event.parent_id = Some(root_id);
+            event.timestamp = base + ChronoDuration::milliseconds(offset_ms);
+            ids.push(event.id);
+            graph.add_event(event).unwrap();
+        }
+        assert_eq!(graph.find_concurrent_events(trace_id).unwrap().len(), 1);
+
+        let explanation = graph.explain_pair(trace_id, ids[0], ids[1]).unwrap();
+        assert!(explanation.race);
+        assert!(explanation.same_variable);
+        assert!(explanation.different_thread);
+        assert!(explanation.concurrent);
+        assert!(explanation.shared_locks.is_empty());
+        assert!(!explanation.vector_clocks.is_empty());
+        assert_eq!(
+            explanation.reason,
+            "No shared lock and concurrent vector clocks"
+        );
+    }
+
+    #[test]
+    fn explain_pair_rejects_events_from_other_traces() {
+        let graph = CausalGraph::new();
+        let event = write_event(Uuid::new_v4(), "worker-a", "api", "balance");
+        let event_id = event.id;
+        graph.add_event(event).unwrap();
+
+        assert!(graph
+            .explain_pair(Uuid::new_v4(), event_id, event_id)
+            .is_err());
+        assert!(graph
+            .explain_pair(Uuid::new_v4(), Uuid::new_v4(), event_id)
+            .is_err());
+    }
+
+    #[test]
+    fn event_ordering_across_traces_follows_distributed_edges() {
+        let graph = CausalGraph::new();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let mut upstream = make_root(Uuid::new_v4(), Uuid::new_v4(), base, "checkout");
+        upstream.metadata.distributed_span_id = Some("span-checkout".into());
+        let mut downstream = make_root(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            base + ChronoDuration::milliseconds(5),
+            "charge",
+        );
+        downstream.metadata.distributed_span_id = Some("span-payments".into());
+        let unrelated = make_root(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            base + ChronoDuration::milliseconds(1),
+            "audit",
+        );
+        for event in [&upstream, &downstream, &unrelated] {
+            graph.add_event(event.clone()).unwrap();
+        }
+
+        let concurrent = EventOrdering {
+            a_before_b: false,
+            b_before_a: false,
+            concurrent: true,
+        };
+        assert_eq!(
+            graph.event_ordering(upstream.id, downstream.id).unwrap(),
+            concurrent
+        );
+
+        graph.add_distributed_edges(vec![crate::event::DistributedEdge {
+            from_span: "span-checkout".into(),
+            to_span: "span-payments".into(),
+            link_type: crate::event::EdgeLinkType::HttpCall,
+            metadata: serde_json::json!({}),
+        }]);
+        let ordering = graph.event_ordering(downstream.id, upstream.id).unwrap();
+        assert!(ordering.b_before_a && !ordering.a_before_b && !ordering.concurrent);
+        assert_eq!(
+            graph.event_ordering(unrelated.id, downstream.id).unwrap(),
+            concurrent
+        );
+        assert!(graph.event_ordering(upstream.id, Uuid::new_v4()).is_err());
+    }
+
+    // ─── Anomaly Sampling Tests ─────────────────────────────────────────────
+
+    #[test]
+    fn anomaly_sampling_still_finds_slowest_outlier() {
+        let graph = CausalGraph::new().with_anomaly_scan_limit(10);
+        let trace_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        graph.set_baseline(
+            "HttpResponse(200)",
+            DurationStats {
+                count: 10,
+                total_duration_us: 170_000,
+                min_duration_us: 15_000,
+                max_duration_us: 19_000,
+                mean_duration_us: 17_000.0,
+                variance: (2_000.0_f64).powi(2),
+                std_dev: 2_000.0,
+            },
+        );
+
+        // 101 events scanned with a stride of 11; the outlier at index 37 is off-stride
+        let mut outlier_id = Uuid::nil();
+        for i in 0..101 {
+            let duration_ms = if i == 37 { 80 } else { 17 };
+            let event = Event {
+                id: Uuid::new_v4(),
+                trace_id,
+                parent_id: None,
+                timestamp: base + ChronoDuration::milliseconds(i),
+                kind: EventKind::HttpResponse {
+                    status: 200,
+                    headers: HashMap::new(),
+                    body: None,
+                    duration_ms: 0,
+                },
+                metadata: metadata("http", duration_ms),
+                causality_vector: Vec::new(),
+                lock_set: Vec::new(),
+            };
+            if i == 37 {
+                outlier_id = event.id;
+            }
+            graph.add_event(event).unwrap();
+        }
+
+        let anomalies = graph.detect_anomalies(trace_id).unwrap();
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].event_id, outlier_id.to_string());
+    }
+
+    // ─── Percentile Anomaly Tests ───────────────────────────────────────────
+
+    fn timed_response(trace_id: Uuid, duration_ms: u64) -> Event {
+        Event {
+            id: Uuid::new_v4(),
+            trace_id,
+            parent_id: None,
+            timestamp: Utc::now(),
+            kind: EventKind::HttpResponse {
+                status: 200,
+                headers: HashMap::new(),
+                body: None,
+                duration_ms: 0,
+            },
+            metadata: metadata("http", duration_ms),
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
+    /// Durations of probe events flagged against a heavy-tailed baseline
+    /// (10ms growing 5% per sample: mean ≈ 30ms, σ ≈ 16.5ms, p95 = 64ms)
+    fn flagged_durations(graph: CausalGraph, probes: &[u64]) -> Vec<f64> {
+        let baseline_trace = Uuid::new_v4();
+        for i in 0..40 {
+            let duration_ms = (10.0 * 1.05_f64.powi(i)).round() as u64;
+            graph
+                .add_event(timed_response(baseline_trace, duration_ms))
+                .unwrap();
+        }
+        graph.update_baselines(baseline_trace).unwrap();
+
+        let probe_trace = Uuid::new_v4();
+        for &duration_ms in probes {
+            graph
+                .add_event(timed_response(probe_trace, duration_ms))
+                .unwrap();
+        }
+        let mut flagged: Vec<f64> = graph
+            .detect_anomalies(probe_trace)
+            .unwrap()
+            .iter()
+            .map(|anomaly| anomaly.actual_duration_ms)
+            .collect();
+        flagged.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        flagged
+    }
+
+    #[test]
+    fn percentile_mode_ignores_tail_latency_that_sigma_flags() {
+        // 70ms is ~2.4σ above the mean but only ~1.1× the p95
+        assert_eq!(
+            flagged_durations(CausalGraph::new(), &[70, 300]),
+            vec![70.0, 300.0]
+        );
+        assert_eq!(
+            flagged_durations(
+                CausalGraph::new().with_percentile_anomalies(1.5),
+                &[70, 300]
+            ),
+            vec![300.0]
+        );
+    }
+
+    #[test]
+    fn percentile_severity_scales_with_distance_above_threshold() {
+        assert!(percentile_severity(95.0, 64.0, 1.5).is_none());
+        assert!(matches!(
+            percentile_severity(150.0, 64.0, 1.5),
+            Some(AnomalySeverity::Minor)
+        ));
+        assert!(matches!(
+            percentile_severity(250.0, 64.0, 1.5),
+            Some(AnomalySeverity::Warning)
+        ));
+        assert!(matches!(
+            percentile_severity(300.0, 64.0, 1.5),
+            Some(AnomalySeverity::Critical)
+        ));
+    }
+
+    // ─── Environment Isolation Tests ────────────────────────────────────────
+
+    #[test]
+    fn dev_events_do_not_affect_prod_baselines() {
+        let graph = CausalGraph::new().with_environment_isolation();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let add_trace = |environment: &str, duration_ms: u64| {
+            let trace_id = Uuid::new_v4();
+            for i in 0..5 {
+                let mut event_metadata = metadata("http", duration_ms);
+                event_metadata.environment = environment.to_string();
+                graph
+                    .add_event(Event {
+                        id: Uuid::new_v4(),
+                        trace_id,
+                        parent_id: None,
+                        timestamp: base + ChronoDuration::milliseconds(i),
+                        kind: EventKind::HttpResponse {
+                            status: 200,
+                            headers: HashMap::new(),
+                            body: None,
+                            duration_ms: 0,
+                        },
+                        metadata: event_metadata,
+                        causality_vector: Vec::new(),
+                        lock_set: Vec::new(),
+                    })
+                    .unwrap();
+            }
+            graph.update_baselines(trace_id).unwrap();
+        };
+
+        add_trace("prod", 17);
+        add_trace("dev", 500);
+
+        let baselines = graph.get_all_baselines();
+        let prod = &baselines["HttpResponse(200)@prod"];
+        assert_eq!(prod.count, 5);
+        assert_eq!(prod.max_duration_us, 17_000);
+        assert_eq!(baselines["HttpResponse(200)@dev"].count, 5);
+        assert!(!baselines.contains_key("HttpResponse(200)"));
+    }
+
+    // ─── Declared Sync Tests ────────────────────────────────────────────────
+
+    /// Writes to `balance` on two threads; with `declare_sync`, worker-b first
+    /// declares that it synchronizes with worker-a's write (e.g. via a file lock)
+    fn out_of_band_writes(graph: &CausalGraph, declare_sync: bool) -> Uuid {
+        let trace_id = Uuid::new_v4();
+        let root_id = Uuid::new_v4();
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        graph
+            .add_event(make_root(root_id, trace_id, base, "root"))
+            .unwrap();
+
+        let mut write_a = write_event(trace_id, "worker-a", "api", "balance");
+        write_a.parent_id = Some(root_id);
+        write_a.timestamp = base + ChronoDuration::milliseconds(1);
+        graph.add_event(write_a.clone()).unwrap();
+
+        let mut write_b = write_event(trace_id, "worker-b", "api", "balance");
+        write_b.parent_id = Some(root_id);
+        write_b.timestamp = base + ChronoDuration::milliseconds(3);
+
+        if declare_sync {
+            let mut sync = write_event(trace_id, "worker-b", "api", "balance");
+            sync.kind = EventKind::Custom {
+                name: SYNC_EVENT_NAME.into(),
+                data: serde_json::json!({ "synchronizes_with": write_a.id.to_string() }),
+            };
+            sync.parent_id = Some(root_id);
+            sync.timestamp = base + ChronoDuration::milliseconds(2);
+            write_b.parent_id = Some(sync.id);
+            graph.add_event(sync).unwrap();
+        }
+
+        graph.add_event(write_b).unwrap();
+        trace_id
+    }
+
+    #[test]
+    fn declared_sync_edge_suppresses_race() {
+        let graph = CausalGraph::new();
+        let trace_id = out_of_band_writes(&graph, false);
+        assert_eq!(graph.find_concurrent_events(trace_id).unwrap().len(), 1);
+
+        let graph = CausalGraph::new();
+        let trace_id = out_of_band_writes(&graph, true);
+        assert!(graph.find_concurrent_events(trace_id).unwrap().is_empty());
+    }
 }
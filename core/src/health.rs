@@ -0,0 +1,309 @@
+//! Trace health score: one 0–100 triage number per trace.
+//!
+//! The score starts at 100 and subtracts four capped penalties:
+//!
+//! | Component      | Penalty                                                      | Cap |
+//! |----------------|--------------------------------------------------------------|-----|
+//! | Races          | 25 per critical, 10 per warning, 2 per info                  | 60  |
+//! | Anomalies      | 8 per critical, 4 per warning, 1 per minor                   | 20  |
+//! | Critical path  | linear from 0 at 80% of trace duration to 10 at 100%         | 10  |
+//! | Coverage       | 10 × fraction of events without a causality vector           | 10  |
+//!
+//! Race severities come from `graph::race_severity`, the same scoring the
+//! trace analysis reports per race.
+//!
+//! Races dominate on purpose: a single write/write race costs more than any
+//! amount of slowness. Critical-path dominance penalises traces where nearly
+//! all time is spent on one serial chain, and low coverage penalises traces
+//! whose events can't be causally ordered (and so can't be race-checked).
+
+use crate::event::Event;
+use crate::graph::{AnomalySeverity, RaceSeverity};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+const RACE_PENALTY_CAP: f64 = 60.0;
+const ANOMALY_PENALTY_CAP: f64 = 20.0;
+const CRITICAL_PATH_PENALTY_MAX: f64 = 10.0;
+const CRITICAL_PATH_DOMINANCE_THRESHOLD: f64 = 80.0;
+const COVERAGE_PENALTY_MAX: f64 = 10.0;
+
+fn race_penalty(severity: RaceSeverity) -> f64 {
+    match severity {
+        RaceSeverity::Critical => 25.0,
+        RaceSeverity::Warning => 10.0,
+        RaceSeverity::Info => 2.0,
+    }
+}
+
+fn anomaly_penalty(severity: AnomalySeverity) -> f64 {
+    match severity {
+        AnomalySeverity::Critical => 8.0,
+        AnomalySeverity::Warning => 4.0,
+        AnomalySeverity::Minor => 1.0,
+    }
+}
+
+/// Analysis outputs the health score is computed from
+#[derive(Debug, Clone)]
+pub struct HealthInputs {
+    pub races: Vec<RaceSeverity>,
+    pub anomalies: Vec<AnomalySeverity>,
+    /// Critical path duration as a percentage of the whole trace, if known
+    pub critical_path_percentage: Option<f64>,
+    /// Fraction (0.0–1.0) of events that carry a causality vector
+    pub coverage: f64,
+}
+
+impl Default for HealthInputs {
+    fn default() -> Self {
+        Self {
+            races: Vec::new(),
+            anomalies: Vec::new(),
+            critical_path_percentage: None,
+            coverage: 1.0,
+        }
+    }
+}
+
+/// Health score with the penalties that produced it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceHealth {
+    pub score: f64,
+    pub race_penalty: f64,
+    pub anomaly_penalty: f64,
+    pub critical_path_penalty: f64,
+    pub coverage_penalty: f64,
+}
+
+/// Compute the health score (see module docs for the formula)
+pub fn health_score(inputs: &HealthInputs) -> TraceHealth {
+    let race_penalty = inputs
+        .races
+        .iter()
+        .map(|severity| race_penalty(*severity))
+        .sum::<f64>()
+        .min(RACE_PENALTY_CAP);
+
+    let anomaly_penalty = inputs
+        .anomalies
+        .iter()
+        .map(|severity| anomaly_penalty(*severity))
+        .sum::<f64>()
+        .min(ANOMALY_PENALTY_CAP);
+
+    let critical_path_penalty = inputs
+        .critical_path_percentage
+        .map(|percentage| {
+            let excess = (percentage.min(100.0) - CRITICAL_PATH_DOMINANCE_THRESHOLD).max(0.0);
+            CRITICAL_PATH_PENALTY_MAX * excess / (100.0 - CRITICAL_PATH_DOMINANCE_THRESHOLD)
+        })
+        .unwrap_or(0.0);
+
+    let coverage_penalty = COVERAGE_PENALTY_MAX * (1.0 - inputs.coverage.clamp(0.0, 1.0));
+
+    let score = (100.0 - race_penalty - anomaly_penalty - critical_path_penalty - coverage_penalty)
+        .clamp(0.0, 100.0);
+
+    TraceHealth {
+        score,
+        race_penalty,
+        anomaly_penalty,
+        critical_path_penalty,
+        coverage_penalty,
+    }
+}
+
+/// Fraction of events that carry a causality vector and can be causally ordered
+pub fn event_coverage(events: &[Event]) -> f64 {
+    if events.is_empty() {
+        return 1.0;
+    }
+    let covered = events
+        .iter()
+        .filter(|event| !event.causality_vector.is_empty())
+        .count();
+    covered as f64 / events.len() as f64
+}
+
+/// Health scores of many traces kept in worst-first order, so listings can
+/// page through them without rescoring every trace
+#[derive(Debug, Default)]
+pub struct HealthIndex {
+    scores: HashMap<Uuid, f64>,
+    ranked: BTreeSet<(u64, Uuid)>,
+    /// trace -> traces whose scores include its events (merged distributed traces)
+    dependents: HashMap<Uuid, HashSet<Uuid>>,
+    /// Traces whose scores were dropped and still need recomputing
+    pending: HashSet<Uuid>,
+}
+
+/// One page of traces from a `HealthIndex`, worst score first
+#[derive(Debug, Clone, Default)]
+pub struct HealthPage {
+    pub traces: Vec<(Uuid, f64)>,
+    /// Scored traces the page was drawn from
+    pub total: usize,
+    pub has_more: bool,
+}
+
+/// Ordering key for a score. Scores are clamped to 0–100, and the bits of
+/// non-negative floats sort like their values (`+ 0.0` turns -0.0 into 0.0).
+fn rank_key(score: f64) -> u64 {
+    (score + 0.0).to_bits()
+}
+
+impl HealthIndex {
+    /// Record `trace_id`'s score, which also counted events from `sources`
+    pub fn insert(&mut self, trace_id: Uuid, score: f64, sources: impl IntoIterator<Item = Uuid>) {
+        self.remove(trace_id);
+        self.scores.insert(trace_id, score);
+        self.ranked.insert((rank_key(score), trace_id));
+        for source in sources {
+            if source != trace_id {
+                self.dependents.entry(source).or_default().insert(trace_id);
+            }
+        }
+    }
+
+    /// Forget `trace_id` entirely (it left the graph)
+    pub fn remove(&mut self, trace_id: Uuid) {
+        if let Some(score) = self.scores.remove(&trace_id) {
+            self.ranked.remove(&(rank_key(score), trace_id));
+        }
+        self.dependents.remove(&trace_id);
+        self.pending.remove(&trace_id);
+    }
+
+    /// Drop the scores of `trace_ids`, and of every trace that merged their
+    /// events, and queue them all for recomputing
+    pub fn invalidate(&mut self, trace_ids: impl IntoIterator<Item = Uuid>) {
+        for trace_id in trace_ids {
+            let dependents = self.dependents.remove(&trace_id).unwrap_or_default();
+            for stale in std::iter::once(trace_id).chain(dependents) {
+                if let Some(score) = self.scores.remove(&stale) {
+                    self.ranked.remove(&(rank_key(score), stale));
+                }
+                self.pending.insert(stale);
+            }
+        }
+    }
+
+    /// Traces queued by `invalidate` since the last call
+    pub fn take_pending(&mut self) -> HashSet<Uuid> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Up to `limit` traces, worst first, skipping `offset` of them and starting
+    /// after the trace `after` when given. `only` restricts the ranking to those
+    /// traces. None if `after` has no score.
+    pub fn page(
+        &self,
+        offset: usize,
+        after: Option<Uuid>,
+        limit: usize,
+        only: Option<&HashSet<Uuid>>,
+    ) -> Option<HealthPage> {
+        let start = match after {
+            Some(cursor) => {
+                std::ops::Bound::Excluded((rank_key(*self.scores.get(&cursor)?), cursor))
+            }
+            None => std::ops::Bound::Unbounded,
+        };
+        let mut ranked = self
+            .ranked
+            .range((start, std::ops::Bound::Unbounded))
+            .filter(|(_, trace_id)| only.is_none_or(|only| only.contains(trace_id)))
+            .skip(offset);
+        let traces: Vec<(Uuid, f64)> = ranked
+            .by_ref()
+            .take(limit)
+            .map(|(_, trace_id)| (*trace_id, self.scores[trace_id]))
+            .collect();
+
+        Some(HealthPage {
+            traces,
+            total: match only {
+                Some(only) => only
+                    .iter()
+                    .filter(|id| self.scores.contains_key(id))
+                    .count(),
+                None => self.scores.len(),
+            },
+            has_more: ranked.next().is_some(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_trace_scores_near_100() {
+        let health = health_score(&HealthInputs {
+            critical_path_percentage: Some(50.0),
+            ..HealthInputs::default()
+        });
+
+        assert!(health.score >= 99.0, "score was {}", health.score);
+    }
+
+    #[test]
+    fn test_multiple_critical_races_score_low() {
+        let health = health_score(&HealthInputs {
+            races: vec![RaceSeverity::Critical; 3],
+            anomalies: vec![AnomalySeverity::Warning],
+            ..HealthInputs::default()
+        });
+
+        assert!(health.score <= 40.0, "score was {}", health.score);
+    }
+
+    #[test]
+    fn test_score_is_monotonic_in_race_severity() {
+        let score_for = |races: Vec<RaceSeverity>| {
+            health_score(&HealthInputs {
+                races,
+                ..HealthInputs::default()
+            })
+            .score
+        };
+
+        let none = score_for(vec![]);
+        let info = score_for(vec![RaceSeverity::Info]);
+        let warning = score_for(vec![RaceSeverity::Warning]);
+        let critical = score_for(vec![RaceSeverity::Critical]);
+
+        assert!(none > info);
+        assert!(info > warning);
+        assert!(warning > critical);
+        // More races never improve the score
+        assert!(score_for(vec![RaceSeverity::Critical; 2]) <= critical);
+    }
+
+    #[test]
+    fn test_health_index_pages_worst_first_and_requeues_dependents() {
+        let [healthy, racy, merged] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let mut index = HealthIndex::default();
+        index.insert(healthy, 100.0, []);
+        index.insert(racy, 35.0, []);
+        // `merged` is a distributed trace whose score counted `racy`'s events
+        index.insert(merged, 70.0, [racy]);
+
+        let page = index.page(0, None, 2, None).unwrap();
+        assert_eq!(page.traces, vec![(racy, 35.0), (merged, 70.0)]);
+        assert_eq!(page.total, 3);
+        assert!(page.has_more);
+        let rest = index.page(0, Some(merged), 2, None).unwrap();
+        assert_eq!(rest.traces, vec![(healthy, 100.0)]);
+        assert!(!rest.has_more);
+
+        index.invalidate([racy]);
+        assert_eq!(index.take_pending(), HashSet::from([racy, merged]));
+        let page = index.page(0, None, 10, None).unwrap();
+        assert_eq!(page.traces, vec![(healthy, 100.0)]);
+        assert!(index.page(0, Some(racy), 10, None).is_none());
+    }
+}
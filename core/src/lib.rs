@@ -1,15 +1,19 @@
 pub mod analysis;
+pub mod anomaly_feed;
+pub mod bundle;
 pub mod cache;
 pub mod capture;
 pub mod config;
 pub mod engine;
 pub mod event;
 pub mod graph;
+pub mod health;
 pub mod storage;
+pub mod timing;
 
 pub use analysis::AnalysisService;
 pub use cache::QueryCache;
-pub use capture::EventCapture;
+pub use capture::{Backpressure, EventCapture};
 pub use config::Config;
 pub use engine::RacewayEngine;
 pub use event::{Event, EventKind, EventMetadata};
@@ -1,15 +1,41 @@
 use super::storage_trait::StorageBackend;
-use super::types::{DurationStats, TraceSummary};
+use super::types::{
+    DurationStats, MaintenanceReport, MetricsScope, ThroughputBucket, TimeWindow, TraceSummary,
+};
 use crate::config::StorageConfig;
 use crate::event::{AccessType, DistributedEdge, DistributedSpan, Event, EventKind};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// Bumped whenever the snapshot layout changes incompatibly
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything a `MemoryBackend` holds, as written by `snapshot_to_path`
+#[derive(Serialize, Deserialize)]
+struct MemorySnapshot {
+    version: u32,
+    /// Grouped by trace, each trace's events in the order they were stored
+    events: Vec<Event>,
+    baselines: HashMap<String, DurationStats>,
+    distributed_spans: Vec<DistributedSpan>,
+    distributed_edges: HashMap<Uuid, Vec<DistributedEdge>>,
+    pending_edges: HashMap<String, Vec<DistributedEdge>>,
+    pending_edges_targets: HashMap<String, Vec<DistributedEdge>>,
+    /// Absent from snapshots written before trace tags existed
+    #[serde(default)]
+    trace_tags: HashMap<Uuid, HashMap<String, String>>,
+}
+
 /// Pure in-memory storage backend using DashMaps
 /// This is now a proper storage layer without computation logic
 pub struct MemoryBackend {
@@ -21,11 +47,15 @@ pub struct MemoryBackend {
     distributed_edges: DashMap<Uuid, RwLock<Vec<DistributedEdge>>>, // trace_id -> edges
     pending_edges: DashMap<String, Vec<DistributedEdge>>, // from_span_id -> edges awaiting upstream span
     pending_edges_targets: DashMap<String, Vec<DistributedEdge>>, // to_span_id -> edges awaiting downstream span
+    /// trace_id -> labels attached with `add_trace_tags`
+    trace_tags: DashMap<Uuid, HashMap<String, String>>,
+    /// `storage.memory_snapshot_path`: loaded on start, written on `close`
+    snapshot_path: Option<PathBuf>,
 }
 
 impl MemoryBackend {
-    pub fn new(_config: &StorageConfig) -> Result<Self> {
-        Ok(Self {
+    pub fn new(config: &StorageConfig) -> Result<Self> {
+        let backend = Self {
             events: DashMap::new(),
             trace_events: DashMap::new(),
             baselines: DashMap::new(),
@@ -33,7 +63,125 @@ impl MemoryBackend {
             distributed_edges: DashMap::new(),
             pending_edges: DashMap::new(),
             pending_edges_targets: DashMap::new(),
-        })
+            trace_tags: DashMap::new(),
+            snapshot_path: config.memory_snapshot_path.clone(),
+        };
+
+        if let Some(path) = backend
+            .snapshot_path
+            .as_deref()
+            .filter(|path| path.exists())
+        {
+            backend.load_from_path(path)?;
+            tracing::info!(
+                "Loaded {} events from memory snapshot {}",
+                backend.events.len(),
+                path.display()
+            );
+        }
+
+        Ok(backend)
+    }
+
+    /// Write every event, baseline, distributed span/edge and trace tag to
+    /// `path` as JSON. The file is replaced atomically, so a crash mid-write leaves the
+    /// previous snapshot intact.
+    pub fn snapshot_to_path(&self, path: &Path) -> Result<()> {
+        let events = self
+            .trace_events
+            .iter()
+            .flat_map(|entry| entry.value().read().unwrap().clone())
+            .filter_map(|event_id| self.events.get(&event_id).map(|event| event.clone()))
+            .collect();
+        let snapshot = MemorySnapshot {
+            version: SNAPSHOT_VERSION,
+            events,
+            baselines: clone_map(&self.baselines),
+            distributed_spans: self
+                .distributed_spans
+                .iter()
+                .map(|entry| entry.value().clone())
+                .collect(),
+            distributed_edges: self
+                .distributed_edges
+                .iter()
+                .map(|entry| (*entry.key(), entry.value().read().unwrap().clone()))
+                .collect(),
+            pending_edges: clone_map(&self.pending_edges),
+            pending_edges_targets: clone_map(&self.pending_edges_targets),
+            trace_tags: self
+                .trace_tags
+                .iter()
+                .map(|entry| (*entry.key(), entry.value().clone()))
+                .collect(),
+        };
+
+        let partial = path.with_extension("partial");
+        let file = File::create(&partial)
+            .with_context(|| format!("Failed to create snapshot {}", partial.display()))?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, &snapshot)?;
+        writer.flush()?;
+        drop(writer);
+        std::fs::rename(&partial, path)
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Replace everything stored with the contents of a snapshot written by
+    /// `snapshot_to_path`
+    pub fn load_from_path(&self, path: &Path) -> Result<()> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open snapshot {}", path.display()))?;
+        let snapshot: MemorySnapshot = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("Failed to parse snapshot {}", path.display()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            anyhow::bail!(
+                "Snapshot {} has version {}, expected {}",
+                path.display(),
+                snapshot.version,
+                SNAPSHOT_VERSION
+            );
+        }
+
+        self.events.clear();
+        self.trace_events.clear();
+        for event in snapshot.events {
+            self.trace_events
+                .entry(event.trace_id)
+                .or_insert_with(|| RwLock::new(Vec::new()))
+                .write()
+                .unwrap()
+                .push(event.id);
+            self.events.insert(event.id, event);
+        }
+
+        // `DashMap::extend` needs `&mut self`, so insert entry by entry
+        self.baselines.clear();
+        for (kind, stats) in snapshot.baselines {
+            self.baselines.insert(kind, stats);
+        }
+        self.distributed_spans.clear();
+        for span in snapshot.distributed_spans {
+            self.distributed_spans.insert(span.span_id.clone(), span);
+        }
+        self.distributed_edges.clear();
+        for (trace_id, edges) in snapshot.distributed_edges {
+            self.distributed_edges.insert(trace_id, RwLock::new(edges));
+        }
+        self.pending_edges.clear();
+        for (span_id, edges) in snapshot.pending_edges {
+            self.pending_edges.insert(span_id, edges);
+        }
+        self.pending_edges_targets.clear();
+        for (span_id, edges) in snapshot.pending_edges_targets {
+            self.pending_edges_targets.insert(span_id, edges);
+        }
+        self.trace_tags.clear();
+        for (trace_id, tags) in snapshot.trace_tags {
+            self.trace_tags.insert(trace_id, tags);
+        }
+        Ok(())
     }
 
     fn store_distributed_edge(&self, trace_id: Uuid, edge: DistributedEdge) {
@@ -53,6 +201,58 @@ impl MemoryBackend {
             edges.push(edge);
         }
     }
+
+    /// Delete a trace's events and labels; false if the trace wasn't stored
+    fn remove_trace(&self, trace_id: Uuid) -> bool {
+        let Some((_, event_ids)) = self.trace_events.remove(&trace_id) else {
+            return false;
+        };
+        self.trace_tags.remove(&trace_id);
+        for event_id in event_ids.read().unwrap().iter() {
+            self.events.remove(event_id);
+        }
+        true
+    }
+
+    /// True when any event of the trace was captured in `environment`
+    fn trace_in_environment(&self, trace_id: Uuid, environment: &str) -> bool {
+        self.trace_events.get(&trace_id).is_some_and(|event_ids| {
+            event_ids.read().unwrap().iter().any(|event_id| {
+                self.events
+                    .get(event_id)
+                    .is_some_and(|event| event.metadata.environment == environment)
+            })
+        })
+    }
+
+    /// Per-trace summaries and events restricted to `scope`, newest trace first
+    fn scoped_traces(&self, limit: usize, scope: &MetricsScope) -> Vec<(TraceSummary, Vec<Event>)> {
+        let cutoff = scope.cutoff();
+        let mut traces = Vec::new();
+
+        for trace_entry in self.trace_events.iter() {
+            let event_ids = trace_entry.value().read().unwrap();
+            let events: Vec<Event> = event_ids
+                .iter()
+                .filter_map(|event_id| self.events.get(event_id).map(|e| e.clone()))
+                .filter(|event| scope.includes(event, cutoff))
+                .collect();
+
+            if let Some(summary) = TraceSummary::from_events(*trace_entry.key(), &events) {
+                traces.push((summary, events));
+            }
+        }
+
+        traces.sort_by_key(|t| std::cmp::Reverse(t.0.last_timestamp));
+        traces.truncate(limit);
+        traces
+    }
+}
+
+fn clone_map<V: Clone>(map: &DashMap<String, V>) -> HashMap<String, V> {
+    map.iter()
+        .map(|entry| (entry.key().clone(), entry.value().clone()))
+        .collect()
 }
 
 /// Helper function to calculate percentile from a sorted vector
@@ -94,14 +294,6 @@ fn access_type_to_string(access: AccessType) -> &'static str {
     }
 }
 
-fn is_write_access(access: &str) -> bool {
-    matches!(access, "Write" | "AtomicWrite" | "AtomicRMW")
-}
-
-fn is_read_access(access: &str) -> bool {
-    matches!(access, "Read" | "AtomicRead")
-}
-
 #[async_trait]
 impl StorageBackend for MemoryBackend {
     async fn add_event(&self, event: Event) -> Result<()> {
@@ -205,6 +397,7 @@ impl StorageBackend for MemoryBackend {
         page: usize,
         page_size: usize,
         min_events: Option<usize>,
+        window: TimeWindow,
     ) -> Result<(Vec<TraceSummary>, usize)> {
         let mut summaries: Vec<TraceSummary> = Vec::new();
         let min_event_count = min_events.unwrap_or(1);
@@ -235,6 +428,9 @@ impl StorageBackend for MemoryBackend {
 
                 let first_timestamp = events.iter().map(|e| e.timestamp).min().unwrap();
                 let last_timestamp = events.iter().map(|e| e.timestamp).max().unwrap();
+                if !window.overlaps(first_timestamp, last_timestamp) {
+                    continue;
+                }
 
                 // Extract unique service names from event metadata
                 let mut service_set = std::collections::HashSet::new();
@@ -257,7 +453,7 @@ impl StorageBackend for MemoryBackend {
         }
 
         // Sort by last_timestamp DESC (newest first)
-        summaries.sort_by(|a, b| b.last_timestamp.cmp(&a.last_timestamp));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.last_timestamp));
 
         let total_count = summaries.len();
 
@@ -326,8 +522,33 @@ impl StorageBackend for MemoryBackend {
         }
 
         // Sort by last_timestamp DESC (newest first)
-        summaries.sort_by(|a, b| b.last_timestamp.cmp(&a.last_timestamp));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.last_timestamp));
+
+        let total_count = summaries.len();
+
+        // Apply pagination
+        let offset = (page.saturating_sub(1)) * page_size;
+        let paginated = summaries.into_iter().skip(offset).take(page_size).collect();
+
+        Ok((paginated, total_count))
+    }
+
+    async fn get_trace_summaries_by_environment(
+        &self,
+        environment: &str,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        let (summaries, _) = self
+            .get_trace_summaries(1, self.trace_events.len().max(1), min_events, window)
+            .await?;
 
+        let summaries: Vec<TraceSummary> = summaries
+            .into_iter()
+            .filter(|summary| self.trace_in_environment(summary.trace_id, environment))
+            .collect();
         let total_count = summaries.len();
 
         // Apply pagination
@@ -337,6 +558,20 @@ impl StorageBackend for MemoryBackend {
         Ok((paginated, total_count))
     }
 
+    async fn get_trace_ids_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        // Tags aren't indexed, so this scans every stored event
+        Ok(self
+            .events
+            .iter()
+            .filter(|entry| entry.value().metadata.tags.get(key).map(String::as_str) == Some(value))
+            .map(|entry| entry.value().trace_id)
+            .collect())
+    }
+
     async fn get_trace_roots(&self, trace_id: Uuid) -> Result<Vec<Uuid>> {
         let events = self.get_trace_events(trace_id).await?;
 
@@ -350,6 +585,28 @@ impl StorageBackend for MemoryBackend {
         Ok(roots)
     }
 
+    async fn add_trace_tags(&self, trace_id: Uuid, tags: HashMap<String, String>) -> Result<()> {
+        self.trace_tags.entry(trace_id).or_default().extend(tags);
+        Ok(())
+    }
+
+    async fn get_trace_tags(&self, trace_id: Uuid) -> Result<HashMap<String, String>> {
+        Ok(self
+            .trace_tags
+            .get(&trace_id)
+            .map(|tags| tags.clone())
+            .unwrap_or_default())
+    }
+
+    async fn get_trace_ids_by_trace_tag(&self, key: &str, value: &str) -> Result<HashSet<Uuid>> {
+        Ok(self
+            .trace_tags
+            .iter()
+            .filter(|entry| entry.value().get(key).map(String::as_str) == Some(value))
+            .map(|entry| *entry.key())
+            .collect())
+    }
+
     async fn save_baseline(&self, operation: &str, stats: DurationStats) -> Result<()> {
         self.baselines.insert(operation.to_string(), stats);
         Ok(())
@@ -447,6 +704,20 @@ impl StorageBackend for MemoryBackend {
         Ok(edges)
     }
 
+    async fn list_distributed_edges(&self) -> Result<Vec<DistributedEdge>> {
+        // An edge spanning two traces is stored under both
+        let mut seen = HashSet::new();
+        let mut edges = Vec::new();
+        for entry in self.distributed_edges.iter() {
+            for edge in entry.value().read().unwrap().iter() {
+                if seen.insert((edge.from_span.clone(), edge.to_span.clone())) {
+                    edges.push(edge.clone());
+                }
+            }
+        }
+        Ok(edges)
+    }
+
     async fn cleanup_old_traces(&self, retention_hours: u64) -> Result<usize> {
         let cutoff_time = chrono::Utc::now() - chrono::Duration::hours(retention_hours as i64);
         let mut deleted_count = 0;
@@ -475,11 +746,7 @@ impl StorageBackend for MemoryBackend {
 
         // Delete traces and their events
         for trace_id in traces_to_delete {
-            if let Some((_, event_ids)) = self.trace_events.remove(&trace_id) {
-                let event_ids = event_ids.read().unwrap();
-                for event_id in event_ids.iter() {
-                    self.events.remove(event_id);
-                }
+            if self.remove_trace(trace_id) {
                 deleted_count += 1;
             }
         }
@@ -487,6 +754,20 @@ impl StorageBackend for MemoryBackend {
         Ok(deleted_count)
     }
 
+    async fn evict_traces(&self, trace_ids: &[Uuid]) -> Result<usize> {
+        let evicted: HashSet<Uuid> = trace_ids.iter().copied().collect();
+        let deleted = evicted
+            .iter()
+            .filter(|trace_id| self.remove_trace(**trace_id))
+            .count();
+        self.distributed_spans
+            .retain(|_, span| !evicted.contains(&span.trace_id));
+        for trace_id in &evicted {
+            self.distributed_edges.remove(trace_id);
+        }
+        Ok(deleted)
+    }
+
     async fn get_all_services(&self) -> Result<Vec<(String, usize, usize)>> {
         use std::collections::{HashMap, HashSet};
 
@@ -529,6 +810,37 @@ impl StorageBackend for MemoryBackend {
         Ok(services)
     }
 
+    async fn get_service_tag_values(&self, tag: &str) -> Result<HashMap<String, String>> {
+        let mut latest: HashMap<String, (chrono::DateTime<chrono::Utc>, String)> = HashMap::new();
+
+        for entry in self.events.iter() {
+            let event = entry.value();
+            let Some(value) = event.metadata.tags.get(tag) else {
+                continue;
+            };
+            let current = latest
+                .entry(event.metadata.service_name.clone())
+                .or_insert_with(|| (event.timestamp, value.clone()));
+            if event.timestamp > current.0 {
+                *current = (event.timestamp, value.clone());
+            }
+        }
+
+        Ok(latest
+            .into_iter()
+            .map(|(service, (_, value))| (service, value))
+            .collect())
+    }
+
+    async fn get_service_event_durations(&self, service: &str) -> Result<Vec<u64>> {
+        Ok(self
+            .events
+            .iter()
+            .filter(|entry| entry.value().metadata.service_name == service)
+            .filter_map(|entry| entry.value().metadata.duration_ns)
+            .collect())
+    }
+
     async fn get_service_dependencies_global(
         &self,
         service_name: &str,
@@ -577,13 +889,13 @@ impl StorageBackend for MemoryBackend {
             .into_iter()
             .map(|(name, (total_calls, traces))| (name, total_calls, traces.len()))
             .collect();
-        calls_to.sort_by(|a, b| b.1.cmp(&a.1));
+        calls_to.sort_by_key(|e| std::cmp::Reverse(e.1));
 
         let mut called_by: Vec<(String, usize, usize)> = called_by_map
             .into_iter()
             .map(|(name, (total_calls, traces))| (name, total_calls, traces.len()))
             .collect();
-        called_by.sort_by(|a, b| b.1.cmp(&a.1));
+        called_by.sort_by_key(|e| std::cmp::Reverse(e.1));
 
         Ok((calls_to, called_by))
     }
@@ -625,7 +937,7 @@ impl StorageBackend for MemoryBackend {
         }
 
         let mut entries: Vec<((String, String, String), usize)> = counts.into_iter().collect();
-        entries.sort_by(|a, b| b.1.cmp(&a.1));
+        entries.sort_by_key(|e| std::cmp::Reverse(e.1));
 
         let edges = entries
             .into_iter()
@@ -642,13 +954,18 @@ impl StorageBackend for MemoryBackend {
         Ok(edges)
     }
 
-    async fn get_global_race_candidates(&self) -> Result<Vec<serde_json::Value>> {
+    async fn get_global_race_candidates(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
         #[derive(Default)]
         struct VariableStats {
             trace_ids: HashSet<Uuid>,
             thread_ids: HashSet<String>,
             services: HashSet<String>,
             access_types: HashSet<String>,
+            has_write: bool,
+            has_read: bool,
             access_count: usize,
         }
 
@@ -656,6 +973,9 @@ impl StorageBackend for MemoryBackend {
 
         for entry in self.events.iter() {
             let event = entry.value();
+            if environment.is_some_and(|environment| event.metadata.environment != environment) {
+                continue;
+            }
             if let EventKind::StateChange {
                 variable,
                 access_type,
@@ -668,6 +988,11 @@ impl StorageBackend for MemoryBackend {
                 stat.services.insert(event.metadata.service_name.clone());
                 stat.access_types
                     .insert(access_type_to_string(*access_type).to_string());
+                if access_type.is_write() {
+                    stat.has_write = true;
+                } else {
+                    stat.has_read = true;
+                }
                 stat.access_count += 1;
             }
         }
@@ -682,20 +1007,11 @@ impl StorageBackend for MemoryBackend {
                 continue;
             }
 
-            let has_write = stat
-                .access_types
-                .iter()
-                .any(|access| is_write_access(access.as_str()));
-            let has_read = stat
-                .access_types
-                .iter()
-                .any(|access| is_read_access(access.as_str()));
-
-            let severity = if has_write && has_read {
+            let severity = if stat.has_write && stat.has_read {
                 "WARNING"
-            } else if has_write && thread_count > 1 {
+            } else if stat.has_write && thread_count > 1 {
                 "CRITICAL"
-            } else if has_write {
+            } else if stat.has_write {
                 "WARNING"
             } else {
                 "INFO"
@@ -728,54 +1044,17 @@ impl StorageBackend for MemoryBackend {
         Ok(results.into_iter().map(|(_, _, value)| value).collect())
     }
 
-    async fn get_system_hotspots(
+    async fn get_top_service_calls(
         &self,
-    ) -> Result<(Vec<serde_json::Value>, Vec<serde_json::Value>)> {
-        #[derive(Default)]
-        struct VariableStats {
-            trace_ids: HashSet<Uuid>,
-            services: HashSet<String>,
-            access_count: usize,
-        }
-
-        let mut variable_stats: HashMap<String, VariableStats> = HashMap::new();
-
-        for entry in self.events.iter() {
-            let event = entry.value();
-            if let EventKind::StateChange { variable, .. } = &event.kind {
-                let stat = variable_stats.entry(variable.clone()).or_default();
-                stat.trace_ids.insert(event.trace_id);
-                stat.services.insert(event.metadata.service_name.clone());
-                stat.access_count += 1;
-            }
-        }
-
-        let mut variables: Vec<(usize, serde_json::Value)> = variable_stats
-            .into_iter()
-            .map(|(variable, stat)| {
-                let mut services: Vec<String> = stat.services.into_iter().collect();
-                services.sort();
-                (
-                    stat.access_count,
-                    json!({
-                        "variable": variable,
-                        "access_count": stat.access_count,
-                        "trace_count": stat.trace_ids.len(),
-                        "services": services,
-                    }),
-                )
-            })
-            .collect();
-
-        variables.sort_by(|a, b| b.0.cmp(&a.0));
-        let top_variables: Vec<serde_json::Value> = variables
-            .into_iter()
-            .take(10)
-            .map(|(_, value)| value)
-            .collect();
-
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
         let mut call_counts: HashMap<(String, String), usize> = HashMap::new();
         for entry in self.distributed_edges.iter() {
+            if environment
+                .is_some_and(|environment| !self.trace_in_environment(*entry.key(), environment))
+            {
+                continue;
+            }
             let edges = entry.value().read().unwrap();
             for edge in edges.iter() {
                 let Some(from_span) = self.distributed_spans.get(&edge.from_span) else {
@@ -808,14 +1087,12 @@ impl StorageBackend for MemoryBackend {
             })
             .collect();
 
-        service_calls.sort_by(|a, b| b.0.cmp(&a.0));
-        let top_service_calls: Vec<serde_json::Value> = service_calls
+        service_calls.sort_by_key(|e| std::cmp::Reverse(e.0));
+        Ok(service_calls
             .into_iter()
             .take(10)
             .map(|(_, value)| value)
-            .collect();
-
-        Ok((top_variables, top_service_calls))
+            .collect())
     }
 
     async fn get_service_health(&self, time_window_minutes: u64) -> Result<Vec<serde_json::Value>> {
@@ -902,11 +1179,16 @@ impl StorageBackend for MemoryBackend {
         Ok(results)
     }
 
-    async fn get_performance_metrics(&self, limit: usize) -> Result<serde_json::Value> {
+    async fn get_performance_metrics(
+        &self,
+        limit: usize,
+        scope: &MetricsScope,
+    ) -> Result<serde_json::Value> {
         use std::collections::HashMap;
 
-        // Collect trace summaries (limited)
-        let (summaries, _) = self.get_trace_summaries(1, limit, None).await?;
+        // Collect trace summaries (limited), counting only events inside the scope
+        let (summaries, mut scoped_events): (Vec<TraceSummary>, Vec<Vec<Event>>) =
+            self.scoped_traces(limit, scope).into_iter().unzip();
 
         if summaries.is_empty() {
             return Ok(serde_json::json!({
@@ -979,12 +1261,8 @@ impl StorageBackend for MemoryBackend {
             .collect();
 
         // Collect all events from sample traces (first 5 for performance)
-        let sample_size = 5.min(summaries.len());
-        let mut all_events = Vec::new();
-        for summary in summaries.iter().take(sample_size) {
-            let events = self.get_trace_events(summary.trace_id).await?;
-            all_events.extend(events);
-        }
+        scoped_events.truncate(5);
+        let all_events: Vec<Event> = scoped_events.into_iter().flatten().collect();
 
         // Event type aggregations
         let mut event_type_stats: HashMap<String, (usize, f64)> = HashMap::new(); // (count, total_duration_ms)
@@ -1109,12 +1387,114 @@ impl StorageBackend for MemoryBackend {
         }))
     }
 
+    async fn get_throughput_buckets(
+        &self,
+        minutes: u64,
+        service: Option<&str>,
+    ) -> Result<Vec<ThroughputBucket>> {
+        let start = ThroughputBucket::window_start(minutes);
+        let mut buckets: Vec<(usize, HashSet<Uuid>)> =
+            (0..minutes).map(|_| (0, HashSet::new())).collect();
+
+        for entry in self.events.iter() {
+            let event = entry.value();
+            if event.timestamp < start
+                || service.is_some_and(|service| event.metadata.service_name != service)
+            {
+                continue;
+            }
+            if let Some((events, traces)) =
+                buckets.get_mut((event.timestamp - start).num_minutes() as usize)
+            {
+                *events += 1;
+                traces.insert(event.trace_id);
+            }
+        }
+
+        Ok(buckets
+            .into_iter()
+            .enumerate()
+            .map(|(i, (events, traces))| {
+                ThroughputBucket::new(
+                    start + chrono::Duration::minutes(i as i64),
+                    events,
+                    traces.len(),
+                )
+            })
+            .collect())
+    }
+
+    async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        let _ = self.trace_events.len();
+        Ok(start.elapsed())
+    }
+
+    async fn maintenance(&self) -> Result<MaintenanceReport> {
+        let mut rows_removed = 0;
+
+        // Drop event ids whose events are gone, then traces left with none
+        let mut emptied = Vec::new();
+        for trace_entry in self.trace_events.iter() {
+            let mut event_ids = trace_entry.value().write().unwrap();
+            let before = event_ids.len();
+            event_ids.retain(|event_id| self.events.contains_key(event_id));
+            event_ids.shrink_to_fit();
+            rows_removed += before - event_ids.len();
+            if event_ids.is_empty() {
+                emptied.push(*trace_entry.key());
+            }
+        }
+        for trace_id in emptied {
+            self.trace_events.remove(&trace_id);
+            self.trace_tags.remove(&trace_id);
+            rows_removed += 1;
+        }
+
+        let before = self.distributed_edges.len();
+        self.distributed_edges
+            .retain(|_, edges| !edges.get_mut().unwrap().is_empty());
+        rows_removed += before - self.distributed_edges.len();
+
+        self.events.shrink_to_fit();
+        self.trace_events.shrink_to_fit();
+        self.baselines.shrink_to_fit();
+        self.distributed_spans.shrink_to_fit();
+        self.distributed_edges.shrink_to_fit();
+        self.pending_edges.shrink_to_fit();
+        self.pending_edges_targets.shrink_to_fit();
+        self.trace_tags.shrink_to_fit();
+
+        Ok(MaintenanceReport {
+            steps: vec![
+                "prune dangling trace index entries".to_string(),
+                "shrink maps".to_string(),
+            ],
+            rows_removed,
+            reclaimed_bytes: None,
+            trace_count: self.trace_events.len(),
+        })
+    }
+
     async fn clear(&self) -> Result<()> {
         self.events.clear();
         self.trace_events.clear();
         self.baselines.clear();
         self.distributed_spans.clear();
         self.distributed_edges.clear();
+        self.trace_tags.clear();
+        Ok(())
+    }
+
+    async fn close(&self) -> Result<()> {
+        if let Some(path) = &self.snapshot_path {
+            self.snapshot_to_path(path)?;
+            tracing::info!(
+                "Saved {} events to memory snapshot {}",
+                self.events.len(),
+                path.display()
+            );
+        }
         Ok(())
     }
 }
@@ -1156,6 +1536,9 @@ mod tests {
                 instance_id: None,
                 distributed_span_id: None,
                 upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
             },
             causality_vector: Vec::new(),
             lock_set: Vec::new(),
@@ -1227,20 +1610,357 @@ mod tests {
         assert_eq!(edges[0]["to_service"], json!("service-b"));
         assert_eq!(edges[0]["call_count"].as_u64().unwrap(), 1);
 
-        let races = backend.get_global_race_candidates().await?;
+        let races = backend.get_global_race_candidates(None).await?;
         assert_eq!(races.len(), 1);
         let race = &races[0];
         assert_eq!(race["variable"], json!("account.balance"));
         assert_eq!(race["severity"], json!("WARNING"));
         assert_eq!(race["trace_count"].as_u64().unwrap(), 2);
 
-        let (top_variables, top_service_calls) = backend.get_system_hotspots().await?;
-        assert!(!top_variables.is_empty());
-        assert_eq!(top_variables[0]["variable"], json!("account.balance"));
+        let top_service_calls = backend.get_top_service_calls(None).await?;
         assert!(top_service_calls.iter().any(|entry| {
             entry["from_service"] == json!("service-a") && entry["to_service"] == json!("service-b")
         }));
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn performance_metrics_respect_window_and_service() -> Result<()> {
+        let backend = MemoryBackend::new(&StorageConfig::default())?;
+        let now = Utc::now();
+
+        // Old, slow trace (10 minutes long, two hours ago)
+        let old_trace = Uuid::new_v4();
+        let old_start = now - chrono::Duration::hours(2);
+        for offset in [0, 10] {
+            backend
+                .add_event(make_state_change_event(
+                    old_trace,
+                    "t1",
+                    "service-a",
+                    AccessType::Write,
+                    "x",
+                    old_start + chrono::Duration::minutes(offset),
+                ))
+                .await?;
+        }
+
+        // Recent, fast trace (2 seconds long, five minutes ago)
+        let recent_trace = Uuid::new_v4();
+        let recent_start = now - chrono::Duration::minutes(5);
+        for offset in [0, 2] {
+            backend
+                .add_event(make_state_change_event(
+                    recent_trace,
+                    "t1",
+                    "service-b",
+                    AccessType::Write,
+                    "x",
+                    recent_start + chrono::Duration::seconds(offset),
+                ))
+                .await?;
+        }
+
+        let unscoped = backend
+            .get_performance_metrics(50, &MetricsScope::default())
+            .await?;
+        assert_eq!(
+            unscoped["trace_latency"]["p99_ms"].as_f64(),
+            Some(600_000.0)
+        );
+
+        let windowed = backend
+            .get_performance_metrics(
+                50,
+                &MetricsScope {
+                    window_minutes: Some(60),
+                    service: None,
+                },
+            )
+            .await?;
+        assert_eq!(windowed["trace_latency"]["p99_ms"].as_f64(), Some(2_000.0));
+        let slowest = windowed["trace_latency"]["slowest_traces"]
+            .as_array()
+            .unwrap();
+        assert_eq!(slowest.len(), 1);
+        assert_eq!(slowest[0]["trace_id"], json!(recent_trace.to_string()));
+
+        let by_service = backend
+            .get_performance_metrics(
+                50,
+                &MetricsScope {
+                    window_minutes: None,
+                    service: Some("service-a".to_string()),
+                },
+            )
+            .await?;
+        assert_eq!(
+            by_service["trace_latency"]["p99_ms"].as_f64(),
+            Some(600_000.0)
+        );
+        let slowest = by_service["trace_latency"]["slowest_traces"]
+            .as_array()
+            .unwrap();
+        assert_eq!(slowest.len(), 1);
+        assert_eq!(slowest[0]["trace_id"], json!(old_trace.to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn maintenance_compacts_without_losing_traces() -> Result<()> {
+        let backend = MemoryBackend::new(&StorageConfig::default())?;
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let trace_a = Uuid::new_v4();
+        let trace_b = Uuid::new_v4();
+        let events = vec![
+            make_state_change_event(trace_a, "t1", "svc", AccessType::Write, "x", now),
+            make_state_change_event(trace_a, "t2", "svc", AccessType::Read, "x", now),
+            make_state_change_event(trace_b, "t1", "svc", AccessType::Write, "y", now),
+        ];
+        // Leave a dangling index entry behind, as a partial purge would
+        let purged = events[1].id;
+        backend.add_events_batch(events).await?;
+        backend.events.remove(&purged);
+
+        let traces_before = backend.count_traces().await?;
+        let report = backend.maintenance().await?;
+
+        assert_eq!(report.rows_removed, 1);
+        assert_eq!(report.trace_count, traces_before);
+        assert_eq!(backend.count_traces().await?, traces_before);
+        assert_eq!(backend.get_trace_events(trace_a).await?.len(), 1);
+        assert_eq!(backend.get_trace_events(trace_b).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn trace_summaries_respect_time_window() -> Result<()> {
+        let backend = MemoryBackend::new(&StorageConfig::default())?;
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let minutes = chrono::Duration::minutes;
+
+        let inside = Uuid::new_v4();
+        let outside = Uuid::new_v4();
+        let straddling = Uuid::new_v4();
+        for (trace_id, offsets) in [
+            (inside, [10, 20]),
+            (outside, [-60, -50]),
+            (straddling, [-5, 5]),
+        ] {
+            for offset in offsets {
+                backend
+                    .add_event(make_state_change_event(
+                        trace_id,
+                        "thread-1",
+                        "service-a",
+                        AccessType::Write,
+                        "counter",
+                        base + minutes(offset),
+                    ))
+                    .await?;
+            }
+        }
+
+        let window = TimeWindow {
+            since: Some(base),
+            until: Some(base + minutes(30)),
+        };
+        let (summaries, total) = backend.get_trace_summaries(1, 10, None, window).await?;
+        assert_eq!(total, 2);
+        let ids: HashSet<Uuid> = summaries.iter().map(|s| s.trace_id).collect();
+        assert!(ids.contains(&inside));
+        assert!(ids.contains(&straddling));
+        assert!(!ids.contains(&outside));
+
+        let (_, total) = backend
+            .get_trace_summaries(1, 10, None, TimeWindow::default())
+            .await?;
+        assert_eq!(total, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn trace_tags_merge_and_are_searchable() -> Result<()> {
+        let backend = MemoryBackend::new(&StorageConfig::default())?;
+        let (tagged, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let labels = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        backend
+            .add_trace_tags(tagged, labels(&[("bug", "checkout"), ("owner", "alice")]))
+            .await?;
+        backend
+            .add_trace_tags(tagged, labels(&[("owner", "bob")]))
+            .await?;
+        backend
+            .add_trace_tags(other, labels(&[("bug", "login")]))
+            .await?;
+
+        assert_eq!(
+            backend.get_trace_tags(tagged).await?,
+            labels(&[("bug", "checkout"), ("owner", "bob")])
+        );
+        assert!(backend.get_trace_tags(Uuid::new_v4()).await?.is_empty());
+        let found = backend
+            .get_trace_ids_by_trace_tag("bug", "checkout")
+            .await?;
+        assert_eq!(found, HashSet::from([tagged]));
+        assert!(backend
+            .get_trace_ids_by_trace_tag("owner", "alice")
+            .await?
+            .is_empty());
+
+        backend.clear().await?;
+        assert!(backend.get_trace_tags(tagged).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn snapshot_round_trips_traces_baselines_and_edges() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("raceway-snapshot-{}.json", Uuid::new_v4()));
+        let config = StorageConfig {
+            memory_snapshot_path: Some(path.clone()),
+            ..StorageConfig::default()
+        };
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let backend = MemoryBackend::new(&config)?;
+        let traces = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        for (i, trace_id) in traces.iter().enumerate() {
+            for step in 0..=i {
+                backend
+                    .add_event(make_state_change_event(
+                        *trace_id,
+                        &format!("thread-{}", step),
+                        "service-a",
+                        AccessType::Write,
+                        "counter",
+                        now + chrono::Duration::milliseconds(step as i64),
+                    ))
+                    .await?;
+            }
+        }
+        let baseline = DurationStats {
+            count: 3,
+            total_duration_us: 300,
+            min_duration_us: 50,
+            max_duration_us: 150,
+            mean_duration_us: 100.0,
+            variance: 1666.7,
+            std_dev: 40.8,
+        };
+        backend.save_baseline("FunctionCall", baseline).await?;
+        backend
+            .save_distributed_span(DistributedSpan {
+                trace_id: traces[0],
+                span_id: "span-a".to_string(),
+                service: "service-a".to_string(),
+                instance: "inst-a".to_string(),
+                first_event: now,
+                last_event: Some(now),
+            })
+            .await?;
+        backend
+            .add_distributed_edge(DistributedEdge {
+                from_span: "span-a".to_string(),
+                to_span: "span-b".to_string(),
+                link_type: crate::event::EdgeLinkType::HttpCall,
+                metadata: json!({}),
+            })
+            .await?;
+        backend
+            .add_distributed_edge(DistributedEdge {
+                from_span: "span-upstream".to_string(),
+                to_span: "span-a".to_string(),
+                link_type: crate::event::EdgeLinkType::HttpCall,
+                metadata: json!({}),
+            })
+            .await?;
+
+        backend
+            .add_trace_tags(
+                traces[1],
+                HashMap::from([("bug".to_string(), "checkout".to_string())]),
+            )
+            .await?;
+
+        // Graceful shutdown writes the snapshot; a new backend loads it on start
+        backend.close().await?;
+        let restored = MemoryBackend::new(&config)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(restored.count_traces().await?, 3);
+        for trace_id in traces {
+            let ids = |events: Vec<Event>| events.iter().map(|e| e.id).collect::<Vec<_>>();
+            assert_eq!(
+                ids(restored.get_trace_events(trace_id).await?),
+                ids(backend.get_trace_events(trace_id).await?)
+            );
+        }
+        let restored_baseline = restored.get_baseline_metric("FunctionCall").await?.unwrap();
+        assert_eq!(restored_baseline.count, 3);
+        assert_eq!(restored_baseline.max_duration_us, 150);
+        assert!(restored.get_distributed_span("span-a").await?.is_some());
+        let spans = |edges: Vec<DistributedEdge>| {
+            edges
+                .into_iter()
+                .map(|edge| (edge.from_span, edge.to_span))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            spans(restored.get_distributed_edges(traces[0]).await?),
+            spans(backend.get_distributed_edges(traces[0]).await?)
+        );
+        assert_eq!(restored.get_distributed_edges(traces[0]).await?.len(), 2);
+        // Each edge's missing span never arrived; both are still waiting for it
+        assert!(restored.pending_edges_targets.contains_key("span-b"));
+        assert!(restored.pending_edges.contains_key("span-upstream"));
+        assert_eq!(
+            restored.get_trace_tags(traces[1]).await?,
+            backend.get_trace_tags(traces[1]).await?
+        );
+        let found = restored
+            .get_trace_ids_by_trace_tag("bug", "checkout")
+            .await?;
+        assert_eq!(found, HashSet::from([traces[1]]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_from_path_replaces_existing_contents() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("raceway-snapshot-{}.json", Uuid::new_v4()));
+        let empty = MemoryBackend::new(&StorageConfig::default())?;
+        empty.snapshot_to_path(&path)?;
+
+        let backend = MemoryBackend::new(&StorageConfig::default())?;
+        backend
+            .add_event(make_state_change_event(
+                Uuid::new_v4(),
+                "thread-1",
+                "service-a",
+                AccessType::Write,
+                "counter",
+                Utc::now(),
+            ))
+            .await?;
+
+        backend.load_from_path(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(backend.count_events().await?, 0);
+        assert_eq!(backend.count_traces().await?, 0);
+
+        assert!(backend.load_from_path(&path).is_err());
+        Ok(())
+    }
 }
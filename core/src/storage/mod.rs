@@ -1,10 +1,18 @@
 mod memory;
+#[cfg(feature = "mysql")]
+mod mysql;
 mod postgres;
+#[cfg(feature = "redis")]
+mod redis;
 mod storage_trait;
 mod types;
 
 pub use memory::MemoryBackend;
+#[cfg(feature = "mysql")]
+pub use mysql::MySqlBackend;
 pub use postgres::PostgresBackend;
+#[cfg(feature = "redis")]
+pub use redis::RedisBackend;
 pub use storage_trait::StorageBackend;
 pub use types::*;
 
@@ -16,7 +24,15 @@ use std::sync::Arc;
 pub async fn create_storage_backend(config: &StorageConfig) -> Result<Arc<dyn StorageBackend>> {
     match config.backend.as_str() {
         "memory" => {
-            tracing::info!("💾 Storage Backend: In-Memory (data will not persist across restarts)");
+            match &config.memory_snapshot_path {
+                Some(path) => tracing::info!(
+                    "💾 Storage Backend: In-Memory (snapshot to {} on shutdown)",
+                    path.display()
+                ),
+                None => tracing::info!(
+                    "💾 Storage Backend: In-Memory (data will not persist across restarts)"
+                ),
+            }
             let backend = MemoryBackend::new(config)?;
             Ok(Arc::new(backend))
         }
@@ -31,9 +47,31 @@ pub async fn create_storage_backend(config: &StorageConfig) -> Result<Arc<dyn St
             tracing::info!("✅ Database connection established");
             Ok(Arc::new(backend))
         }
+        #[cfg(feature = "redis")]
+        "redis" => {
+            tracing::info!("💾 Storage Backend: Redis (shared across instances)");
+            let backend = RedisBackend::new(config).await?;
+            backend.ping().await?;
+            tracing::info!("✅ Redis connection established");
+            Ok(Arc::new(backend))
+        }
+        #[cfg(not(feature = "redis"))]
+        "redis" => {
+            anyhow::bail!(
+                "Redis backend requires building with the `redis` feature (cargo build --features redis)."
+            )
+        }
+        #[cfg(feature = "mysql")]
+        "mysql" => {
+            tracing::info!("💾 Storage Backend: MySQL (persistent)");
+            let backend = MySqlBackend::new(config).await?;
+            tracing::info!("✅ Database connection established");
+            Ok(Arc::new(backend))
+        }
+        #[cfg(not(feature = "mysql"))]
         "mysql" => {
             anyhow::bail!(
-                "MySQL backend not yet implemented. Use 'memory' or 'postgres' backend for now."
+                "MySQL backend requires building with the `mysql` feature (cargo build --features mysql)."
             )
         }
         "sqlite" => {
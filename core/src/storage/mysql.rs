@@ -0,0 +1,1664 @@
+use super::storage_trait::StorageBackend;
+use super::types::{
+    DurationStats, MaintenanceReport, MetricsScope, ThroughputBucket, TimeWindow, TraceSummary,
+};
+use crate::config::StorageConfig;
+use crate::event::{DistributedEdge, DistributedSpan, Event, EventKind};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use sqlx::mysql::{MySql, MySqlArguments, MySqlPool, MySqlPoolOptions, MySqlRow};
+use sqlx::query::Query;
+use sqlx::{QueryBuilder, Row};
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+use uuid::fmt::Hyphenated;
+use uuid::Uuid;
+
+/// Rows per multi-row INSERT, keeping batches well under MySQL's 65535
+/// placeholder limit
+const INSERT_CHUNK_ROWS: usize = 1000;
+
+/// Distinct services per trace as a JSON array. MySQL's JSON_ARRAYAGG has no
+/// DISTINCT, so the spans are deduplicated first.
+const TRACE_SERVICES: &str = r#"
+    SELECT trace_id, JSON_ARRAYAGG(service) AS services, COUNT(*) AS service_count
+    FROM (SELECT DISTINCT trace_id, service FROM distributed_spans) spans
+    GROUP BY trace_id
+"#;
+
+/// Restricts `events e` to a `MetricsScope`; bind with `bind_scope`
+const SCOPE_FILTER: &str = "(? IS NULL OR e.timestamp >= ?) AND (? IS NULL OR e.service_name = ?)";
+
+/// Keeps trace groups of `events e` overlapping a `TimeWindow`; bind with
+/// `bind_window`
+const WINDOW_FILTER: &str =
+    "(? IS NULL OR MAX(e.timestamp) >= ?) AND (? IS NULL OR MIN(e.timestamp) <= ?)";
+
+/// `metadata.duration_ns` of `events e`, NULL when absent or null
+const DURATION_NS: &str =
+    "CASE WHEN JSON_TYPE(e.metadata->'$.duration_ns') IN ('INTEGER', 'UNSIGNED INTEGER') \
+     THEN CAST(e.metadata->'$.duration_ns' AS SIGNED) END";
+
+/// MySQL storage backend (CRUD operations only), mirroring the PostgreSQL
+/// schema with JSON columns in place of JSONB
+pub struct MySqlBackend {
+    pool: MySqlPool,
+}
+
+impl MySqlBackend {
+    pub async fn new(config: &StorageConfig) -> Result<Self> {
+        let mysql_config = &config.mysql;
+
+        let connection_string = mysql_config
+            .connection_string
+            .as_ref()
+            .ok_or_else(|| anyhow!("MySQL connection string is required"))?;
+
+        let pool = MySqlPoolOptions::new()
+            .max_connections(mysql_config.max_connections)
+            .min_connections(mysql_config.min_connections)
+            .acquire_timeout(Duration::from_secs(
+                mysql_config.connection_timeout_seconds as u64,
+            ))
+            .connect(connection_string)
+            .await?;
+
+        if mysql_config.auto_migrate {
+            tracing::info!("Running MySQL migrations...");
+
+            let migration_001 = include_str!("../../migrations/mysql/001_initial_schema.sql");
+            sqlx::raw_sql(migration_001).execute(&pool).await?;
+            tracing::info!("✓ Migration 001 (initial schema) completed");
+
+            let migration_002 = include_str!("../../migrations/mysql/002_distributed_tracing.sql");
+            sqlx::raw_sql(migration_002).execute(&pool).await?;
+            tracing::info!("✓ Migration 002 (distributed tracing) completed");
+
+            tracing::info!("All migrations completed successfully");
+        }
+
+        Ok(Self { pool })
+    }
+
+    /// Page of trace summaries, newest first, for traces with at least
+    /// `min_events` events that overlap `window`. `filter` is an extra
+    /// condition on `e.trace_id` with a single placeholder, bound to its argument.
+    async fn query_trace_summaries(
+        &self,
+        filter: Option<(&str, &str)>,
+        min_events: i64,
+        window: TimeWindow,
+        page: usize,
+        page_size: usize,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        // Without a filter the placeholder is bound to NULL and ignored
+        let (condition, argument) = match filter {
+            Some((condition, argument)) => (condition, Some(argument)),
+            None => ("? IS NULL", None),
+        };
+
+        let count_sql = format!(
+            r#"
+            SELECT COUNT(*) AS total
+            FROM (
+                SELECT e.trace_id
+                FROM events e
+                WHERE {condition}
+                GROUP BY e.trace_id
+                HAVING COUNT(*) >= ? AND {WINDOW_FILTER}
+            ) AS filtered
+            "#
+        );
+        let count_row = bind_window(
+            sqlx::query(&count_sql).bind(argument).bind(min_events),
+            window,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        let total_count: i64 = count_row.try_get("total")?;
+
+        let offset = (page.saturating_sub(1)) * page_size;
+        let summaries_sql = format!(
+            r#"
+            SELECT
+                e.trace_id,
+                COUNT(*) AS event_count,
+                MIN(e.timestamp) AS first_timestamp,
+                MAX(e.timestamp) AS last_timestamp,
+                ANY_VALUE(ds.services) AS services,
+                COALESCE(ANY_VALUE(ds.service_count), 0) AS service_count
+            FROM events e
+            LEFT JOIN ({TRACE_SERVICES}) ds ON e.trace_id = ds.trace_id
+            WHERE {condition}
+            GROUP BY e.trace_id
+            HAVING COUNT(*) >= ? AND {WINDOW_FILTER}
+            ORDER BY MAX(e.timestamp) DESC
+            LIMIT ? OFFSET ?
+            "#
+        );
+        let rows = bind_window(
+            sqlx::query(&summaries_sql).bind(argument).bind(min_events),
+            window,
+        )
+        .bind(page_size as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let summaries = rows
+            .iter()
+            .map(|row| {
+                let mut services: Vec<String> = json_or_default(row, "services")?;
+                services.sort();
+                Ok(TraceSummary {
+                    trace_id: uuid_column(row, "trace_id")?,
+                    event_count: row.try_get("event_count")?,
+                    first_timestamp: row.try_get("first_timestamp")?,
+                    last_timestamp: row.try_get("last_timestamp")?,
+                    services,
+                    service_count: row.try_get::<i64, _>("service_count")? as usize,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok((summaries, total_count as usize))
+    }
+}
+
+/// Ids are stored as hyphenated strings in CHAR(36) columns; sqlx's native
+/// MySQL encoding of `Uuid` is BINARY(16)
+fn uuid_column(row: &MySqlRow, column: &str) -> Result<Uuid> {
+    Ok(row.try_get::<Hyphenated, _>(column)?.into_uuid())
+}
+
+/// Deserialize a nullable JSON column, using `T::default()` for NULL
+fn json_or_default<T: DeserializeOwned + Default>(row: &MySqlRow, column: &str) -> Result<T> {
+    match row.try_get::<Option<serde_json::Value>, _>(column)? {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(T::default()),
+    }
+}
+
+fn row_to_event(row: &MySqlRow) -> Result<Event> {
+    Ok(Event {
+        id: uuid_column(row, "id")?,
+        trace_id: uuid_column(row, "trace_id")?,
+        parent_id: row
+            .try_get::<Option<Hyphenated>, _>("parent_id")?
+            .map(Hyphenated::into_uuid),
+        timestamp: row.try_get("timestamp")?,
+        kind: serde_json::from_value(row.try_get("kind")?)?,
+        metadata: serde_json::from_value(row.try_get("metadata")?)?,
+        causality_vector: json_or_default(row, "causality_vector")?,
+        lock_set: json_or_default(row, "lock_set")?,
+    })
+}
+
+fn edge_type(kind: &EventKind) -> &'static str {
+    match kind {
+        EventKind::AsyncSpawn { .. } => "AsyncSpawn",
+        EventKind::AsyncAwait { .. } => "AsyncAwait",
+        EventKind::HttpResponse { .. } => "HttpRequestResponse",
+        EventKind::DatabaseResult { .. } => "DatabaseQueryResult",
+        EventKind::StateChange { .. } => "DataDependency",
+        _ => "DirectCall",
+    }
+}
+
+fn bind_scope<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    cutoff: Option<DateTime<Utc>>,
+    service: Option<&'q str>,
+) -> Query<'q, MySql, MySqlArguments> {
+    query.bind(cutoff).bind(cutoff).bind(service).bind(service)
+}
+
+fn bind_window<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    window: TimeWindow,
+) -> Query<'q, MySql, MySqlArguments> {
+    query
+        .bind(window.since)
+        .bind(window.since)
+        .bind(window.until)
+        .bind(window.until)
+}
+
+/// Linearly interpolated percentile of ascending `sorted`, matching
+/// PostgreSQL's PERCENTILE_CONT (MySQL has no equivalent)
+fn percentile_cont(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let rank = (sorted.len() - 1) as f64 * p;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// An event serialized for the `events` table
+struct EventRow {
+    id: Hyphenated,
+    trace_id: Hyphenated,
+    parent_id: Option<Hyphenated>,
+    timestamp: DateTime<Utc>,
+    kind: serde_json::Value,
+    metadata: serde_json::Value,
+    causality_vector: serde_json::Value,
+    lock_set: serde_json::Value,
+}
+
+impl EventRow {
+    fn new(event: &Event) -> Result<Self> {
+        Ok(Self {
+            id: event.id.hyphenated(),
+            trace_id: event.trace_id.hyphenated(),
+            parent_id: event.parent_id.map(Uuid::hyphenated),
+            timestamp: event.timestamp,
+            kind: serde_json::to_value(&event.kind)?,
+            metadata: serde_json::to_value(&event.metadata)?,
+            causality_vector: serde_json::to_value(&event.causality_vector)?,
+            lock_set: serde_json::to_value(&event.lock_set)?,
+        })
+    }
+}
+
+/// A StateChange entry for the `cross_trace_index` table
+struct CrossTraceRow {
+    variable: String,
+    event_id: Hyphenated,
+    trace_id: Hyphenated,
+    timestamp: DateTime<Utc>,
+    thread_id: String,
+    access_type: String,
+    value: serde_json::Value,
+    location: String,
+}
+
+#[async_trait]
+impl StorageBackend for MySqlBackend {
+    async fn add_event(&self, event: Event) -> Result<()> {
+        self.add_events_batch(vec![event]).await?;
+        Ok(())
+    }
+
+    async fn add_events_batch(&self, events: Vec<Event>) -> Result<usize> {
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let event_count = events.len();
+
+        let mut event_rows = Vec::with_capacity(event_count);
+        let mut causal_edges: Vec<(Hyphenated, Hyphenated, &str)> = Vec::new();
+        let mut trace_roots: Vec<(Hyphenated, Hyphenated)> = Vec::new();
+        let mut cross_trace_entries = Vec::new();
+
+        for event in &events {
+            event_rows.push(EventRow::new(event)?);
+
+            if let Some(parent_id) = event.parent_id {
+                causal_edges.push((
+                    parent_id.hyphenated(),
+                    event.id.hyphenated(),
+                    edge_type(&event.kind),
+                ));
+            } else {
+                trace_roots.push((event.trace_id.hyphenated(), event.id.hyphenated()));
+            }
+
+            if let EventKind::StateChange {
+                variable,
+                new_value,
+                location,
+                access_type,
+                ..
+            } = &event.kind
+            {
+                cross_trace_entries.push(CrossTraceRow {
+                    variable: variable.clone(),
+                    event_id: event.id.hyphenated(),
+                    trace_id: event.trace_id.hyphenated(),
+                    timestamp: event.timestamp,
+                    thread_id: event.metadata.thread_id.clone(),
+                    access_type: format!("{:?}", access_type),
+                    value: new_value.clone(),
+                    location: location.clone(),
+                });
+            }
+        }
+
+        // Multi-row inserts in one transaction; duplicate keys are left as they are
+        let mut tx = self.pool.begin().await?;
+
+        for chunk in event_rows.chunks(INSERT_CHUNK_ROWS) {
+            let mut builder = QueryBuilder::<MySql>::new(
+                "INSERT INTO events (id, trace_id, parent_id, timestamp, kind, metadata, causality_vector, lock_set) ",
+            );
+            builder.push_values(chunk, |mut row, event| {
+                row.push_bind(event.id)
+                    .push_bind(event.trace_id)
+                    .push_bind(event.parent_id)
+                    .push_bind(event.timestamp)
+                    .push_bind(event.kind.clone())
+                    .push_bind(event.metadata.clone())
+                    .push_bind(event.causality_vector.clone())
+                    .push_bind(event.lock_set.clone());
+            });
+            builder.push(" ON DUPLICATE KEY UPDATE id = id");
+            builder.build().execute(&mut *tx).await?;
+        }
+
+        for chunk in causal_edges.chunks(INSERT_CHUNK_ROWS) {
+            let mut builder = QueryBuilder::<MySql>::new(
+                "INSERT INTO causal_edges (from_event_id, to_event_id, edge_type) ",
+            );
+            builder.push_values(chunk, |mut row, (from, to, edge_type)| {
+                row.push_bind(*from).push_bind(*to).push_bind(*edge_type);
+            });
+            builder.push(" ON DUPLICATE KEY UPDATE edge_type = edge_type");
+            builder.build().execute(&mut *tx).await?;
+        }
+
+        for chunk in trace_roots.chunks(INSERT_CHUNK_ROWS) {
+            let mut builder =
+                QueryBuilder::<MySql>::new("INSERT INTO trace_roots (trace_id, root_event_id) ");
+            builder.push_values(chunk, |mut row, (trace_id, root_event_id)| {
+                row.push_bind(*trace_id).push_bind(*root_event_id);
+            });
+            builder.push(" ON DUPLICATE KEY UPDATE trace_id = trace_id");
+            builder.build().execute(&mut *tx).await?;
+        }
+
+        for chunk in cross_trace_entries.chunks(INSERT_CHUNK_ROWS) {
+            let mut builder = QueryBuilder::<MySql>::new(
+                "INSERT INTO cross_trace_index (variable, event_id, trace_id, timestamp, thread_id, access_type, value, location) ",
+            );
+            builder.push_values(chunk, |mut row, entry: &CrossTraceRow| {
+                row.push_bind(entry.variable.clone())
+                    .push_bind(entry.event_id)
+                    .push_bind(entry.trace_id)
+                    .push_bind(entry.timestamp)
+                    .push_bind(entry.thread_id.clone())
+                    .push_bind(entry.access_type.clone())
+                    .push_bind(entry.value.clone())
+                    .push_bind(entry.location.clone());
+            });
+            builder.push(" ON DUPLICATE KEY UPDATE variable = variable");
+            builder.build().execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+        Ok(event_count)
+    }
+
+    async fn get_event(&self, id: Uuid) -> Result<Option<Event>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, trace_id, parent_id, timestamp, kind, metadata, causality_vector, lock_set
+            FROM events
+            WHERE id = ?
+            "#,
+        )
+        .bind(id.hyphenated())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(row_to_event).transpose()
+    }
+
+    async fn get_trace_events(&self, trace_id: Uuid) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, trace_id, parent_id, timestamp, kind, metadata, causality_vector, lock_set
+            FROM events
+            WHERE trace_id = ?
+            ORDER BY timestamp ASC,
+                     JSON_LENGTH(causality_vector) ASC,
+                     id ASC
+            "#,
+        )
+        .bind(trace_id.hyphenated())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_event).collect()
+    }
+
+    async fn get_all_events(&self) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, trace_id, parent_id, timestamp, kind, metadata, causality_vector, lock_set
+            FROM events
+            ORDER BY timestamp ASC,
+                     JSON_LENGTH(causality_vector) ASC,
+                     id ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_event).collect()
+    }
+
+    async fn count_events(&self) -> Result<usize> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count as usize)
+    }
+
+    async fn count_traces(&self) -> Result<usize> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(DISTINCT trace_id) FROM events")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count as usize)
+    }
+
+    async fn get_all_trace_ids(&self) -> Result<Vec<Uuid>> {
+        let rows = sqlx::query("SELECT DISTINCT trace_id FROM events ORDER BY trace_id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| uuid_column(row, "trace_id"))
+            .collect()
+    }
+
+    async fn get_trace_summaries(
+        &self,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.query_trace_summaries(
+            None,
+            min_events.unwrap_or(1) as i64,
+            window,
+            page,
+            page_size,
+        )
+        .await
+    }
+
+    async fn get_trace_summaries_by_service(
+        &self,
+        service_name: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.query_trace_summaries(
+            Some((
+                "e.trace_id IN (SELECT trace_id FROM distributed_spans WHERE service = ?)",
+                service_name,
+            )),
+            1,
+            TimeWindow::default(),
+            page,
+            page_size,
+        )
+        .await
+    }
+
+    async fn get_trace_summaries_by_environment(
+        &self,
+        environment: &str,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.query_trace_summaries(
+            Some((
+                "e.trace_id IN (SELECT trace_id FROM events WHERE environment = ?)",
+                environment,
+            )),
+            min_events.unwrap_or(1) as i64,
+            window,
+            page,
+            page_size,
+        )
+        .await
+    }
+
+    async fn get_trace_ids_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT trace_id
+            FROM events
+            WHERE JSON_UNQUOTE(JSON_EXTRACT(metadata, CONCAT('$.tags.', JSON_QUOTE(?)))) = ?
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| uuid_column(row, "trace_id"))
+            .collect()
+    }
+
+    async fn get_trace_roots(&self, trace_id: Uuid) -> Result<Vec<Uuid>> {
+        let rows = sqlx::query("SELECT root_event_id FROM trace_roots WHERE trace_id = ?")
+            .bind(trace_id.hyphenated())
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| uuid_column(row, "root_event_id"))
+            .collect()
+    }
+
+    async fn save_baseline(&self, operation: &str, stats: DurationStats) -> Result<()> {
+        self.save_baselines_batch(HashMap::from([(operation.to_string(), stats)]))
+            .await
+    }
+
+    async fn save_baselines_batch(&self, baselines: HashMap<String, DurationStats>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for (operation, stats) in baselines {
+            sqlx::query(
+                r#"
+                INSERT INTO baseline_metrics (
+                    operation, count, total_duration_micros, min_duration_micros,
+                    max_duration_micros, mean_duration_micros, variance, std_dev
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ON DUPLICATE KEY UPDATE
+                    count = VALUES(count),
+                    total_duration_micros = VALUES(total_duration_micros),
+                    min_duration_micros = VALUES(min_duration_micros),
+                    max_duration_micros = VALUES(max_duration_micros),
+                    mean_duration_micros = VALUES(mean_duration_micros),
+                    variance = VALUES(variance),
+                    std_dev = VALUES(std_dev)
+                "#,
+            )
+            .bind(&operation)
+            .bind(stats.count as i64)
+            .bind(stats.total_duration_us as i64)
+            .bind(stats.min_duration_us as i64)
+            .bind(stats.max_duration_us as i64)
+            .bind(stats.mean_duration_us)
+            .bind(stats.variance)
+            .bind(stats.std_dev)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_baseline_metric(&self, operation: &str) -> Result<Option<DurationStats>> {
+        let row = sqlx::query(
+            r#"
+            SELECT count, total_duration_micros, min_duration_micros, max_duration_micros,
+                   mean_duration_micros, variance, std_dev
+            FROM baseline_metrics
+            WHERE operation = ?
+            "#,
+        )
+        .bind(operation)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| DurationStats {
+            count: r.try_get::<i64, _>("count").unwrap_or(0) as usize,
+            total_duration_us: r.try_get::<i64, _>("total_duration_micros").unwrap_or(0) as u64,
+            min_duration_us: r.try_get::<i64, _>("min_duration_micros").unwrap_or(0) as u64,
+            max_duration_us: r.try_get::<i64, _>("max_duration_micros").unwrap_or(0) as u64,
+            mean_duration_us: r.try_get("mean_duration_micros").unwrap_or(0.0),
+            variance: r.try_get("variance").unwrap_or(0.0),
+            std_dev: r.try_get("std_dev").unwrap_or(0.0),
+        }))
+    }
+
+    async fn get_all_baseline_operations(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT operation FROM baseline_metrics ORDER BY operation")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get("operation")).collect())
+    }
+
+    async fn save_distributed_span(&self, span: DistributedSpan) -> Result<()> {
+        let span_json = serde_json::to_value(&span)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO distributed_spans (trace_id, span_id, service, instance, first_event, last_event, span_data)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                last_event = VALUES(last_event),
+                span_data = VALUES(span_data)
+            "#,
+        )
+        .bind(span.trace_id.hyphenated())
+        .bind(&span.span_id)
+        .bind(&span.service)
+        .bind(&span.instance)
+        .bind(span.first_event)
+        .bind(span.last_event)
+        .bind(span_json)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_distributed_span(&self, span_id: &str) -> Result<Option<DistributedSpan>> {
+        let row = sqlx::query("SELECT span_data FROM distributed_spans WHERE span_id = ?")
+            .bind(span_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(serde_json::from_value(row.try_get("span_data")?)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_distributed_spans(&self, trace_id: Uuid) -> Result<Vec<DistributedSpan>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT span_data
+            FROM distributed_spans
+            WHERE trace_id = ?
+            ORDER BY first_event
+            "#,
+        )
+        .bind(trace_id.hyphenated())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.try_get("span_data")?)?))
+            .collect()
+    }
+
+    async fn add_distributed_edge(&self, edge: DistributedEdge) -> Result<()> {
+        let edge_json = serde_json::to_value(&edge)?;
+        let link_type_str = format!("{:?}", edge.link_type);
+
+        sqlx::query(
+            r#"
+            INSERT INTO distributed_edges (from_span, to_span, link_type, edge_data)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE from_span = from_span
+            "#,
+        )
+        .bind(&edge.from_span)
+        .bind(&edge.to_span)
+        .bind(link_type_str)
+        .bind(edge_json)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_distributed_edges(&self, trace_id: Uuid) -> Result<Vec<DistributedEdge>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT de.edge_data
+            FROM distributed_edges de
+            LEFT JOIN distributed_spans ds_from ON de.from_span = ds_from.span_id
+            LEFT JOIN distributed_spans ds_to ON de.to_span = ds_to.span_id
+            WHERE ds_from.trace_id = ? OR ds_to.trace_id = ?
+            "#,
+        )
+        .bind(trace_id.hyphenated())
+        .bind(trace_id.hyphenated())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.try_get("edge_data")?)?))
+            .collect()
+    }
+
+    async fn list_distributed_edges(&self) -> Result<Vec<DistributedEdge>> {
+        let rows = sqlx::query("SELECT edge_data FROM distributed_edges")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.try_get("edge_data")?)?))
+            .collect()
+    }
+
+    async fn cleanup_old_traces(&self, retention_hours: u64) -> Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::hours(retention_hours as i64);
+
+        // No DELETE ... RETURNING in MySQL: count the affected traces first
+        let mut tx = self.pool.begin().await?;
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(DISTINCT trace_id) FROM events WHERE timestamp < ?")
+                .bind(cutoff)
+                .fetch_one(&mut *tx)
+                .await?;
+        sqlx::query("DELETE FROM events WHERE timestamp < ?")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(count as usize)
+    }
+
+    async fn maintenance(&self) -> Result<MaintenanceReport> {
+        const TABLES: [&str; 8] = [
+            "events",
+            "causal_edges",
+            "trace_roots",
+            "cross_trace_index",
+            "anomalies",
+            "baseline_metrics",
+            "distributed_spans",
+            "distributed_edges",
+        ];
+
+        let database_size = || async {
+            let size: i64 = sqlx::query_scalar(
+                r#"
+                SELECT CAST(COALESCE(SUM(data_length + index_length), 0) AS SIGNED)
+                FROM information_schema.tables
+                WHERE table_schema = DATABASE()
+                "#,
+            )
+            .fetch_one(&self.pool)
+            .await?;
+            Ok::<i64, anyhow::Error>(size)
+        };
+        let size_before = database_size().await?;
+        let mut steps = Vec::new();
+
+        // Edges whose spans were purged can no longer be resolved to a trace
+        let result = sqlx::query(
+            r#"
+            DELETE de FROM distributed_edges de
+            WHERE NOT EXISTS (SELECT 1 FROM distributed_spans WHERE span_id = de.from_span)
+               OR NOT EXISTS (SELECT 1 FROM distributed_spans WHERE span_id = de.to_span)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        let rows_removed = result.rows_affected() as usize;
+        steps.push("DELETE orphaned distributed_edges".to_string());
+
+        // InnoDB maps OPTIMIZE TABLE to a rebuild plus ANALYZE
+        for table in TABLES {
+            sqlx::raw_sql(&format!("OPTIMIZE TABLE {}", table))
+                .execute(&self.pool)
+                .await?;
+            steps.push(format!("OPTIMIZE TABLE {}", table));
+        }
+
+        let size_after = database_size().await?;
+
+        Ok(MaintenanceReport {
+            steps,
+            rows_removed,
+            reclaimed_bytes: Some(size_before - size_after),
+            trace_count: self.count_traces().await?,
+        })
+    }
+
+    async fn get_all_services(&self) -> Result<Vec<(String, usize, usize)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                ds.service,
+                COUNT(e.id) AS event_count,
+                COUNT(DISTINCT ds.trace_id) AS trace_count
+            FROM distributed_spans ds
+            LEFT JOIN events e ON e.trace_id = ds.trace_id
+                AND e.service_name = ds.service
+            GROUP BY ds.service
+            ORDER BY ds.service
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut services = Vec::new();
+        for row in rows {
+            let service_name: String = row.try_get("service")?;
+            let event_count: i64 = row.try_get("event_count").unwrap_or(0);
+            let trace_count: i64 = row.try_get("trace_count").unwrap_or(0);
+            services.push((service_name, event_count as usize, trace_count as usize));
+        }
+
+        Ok(services)
+    }
+
+    async fn get_service_tag_values(&self, tag: &str) -> Result<HashMap<String, String>> {
+        // Latest value per service; the tag is quoted into the JSON path so any
+        // key is safe
+        let rows = sqlx::query(
+            r#"
+            SELECT service, value
+            FROM (
+                SELECT
+                    service_name AS service,
+                    JSON_UNQUOTE(JSON_EXTRACT(metadata, CONCAT('$.tags.', JSON_QUOTE(?)))) AS value,
+                    ROW_NUMBER() OVER (PARTITION BY service_name ORDER BY timestamp DESC) AS rn
+                FROM events
+                WHERE JSON_CONTAINS_PATH(metadata, 'one', CONCAT('$.tags.', JSON_QUOTE(?)))
+            ) latest
+            WHERE rn = 1
+            "#,
+        )
+        .bind(tag)
+        .bind(tag)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut values = HashMap::new();
+        for row in rows {
+            values.insert(row.try_get("service")?, row.try_get("value")?);
+        }
+        Ok(values)
+    }
+
+    async fn get_service_event_durations(&self, service: &str) -> Result<Vec<u64>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {DURATION_NS} AS duration_ns
+            FROM events e
+            WHERE e.service_name = ? AND {DURATION_NS} IS NOT NULL
+            "#
+        ))
+        .bind(service)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(row.try_get::<i64, _>("duration_ns")? as u64))
+            .collect()
+    }
+
+    async fn get_service_dependencies_global(
+        &self,
+        service_name: &str,
+    ) -> Result<(Vec<(String, usize, usize)>, Vec<(String, usize, usize)>)> {
+        let calls_to_rows = sqlx::query(
+            r#"
+            SELECT
+                ds_to.service AS to_service,
+                COUNT(de.from_span) AS total_calls,
+                COUNT(DISTINCT ds_from.trace_id) AS trace_count
+            FROM distributed_edges de
+            JOIN distributed_spans ds_from ON de.from_span = ds_from.span_id
+            JOIN distributed_spans ds_to ON de.to_span = ds_to.span_id
+            WHERE ds_from.service = ?
+                AND ds_from.service != ds_to.service
+            GROUP BY ds_to.service
+            ORDER BY total_calls DESC
+            "#,
+        )
+        .bind(service_name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut calls_to = Vec::new();
+        for row in calls_to_rows {
+            let to_service: String = row.try_get("to_service")?;
+            let total_calls: i64 = row.try_get("total_calls").unwrap_or(0);
+            let trace_count: i64 = row.try_get("trace_count").unwrap_or(0);
+            calls_to.push((to_service, total_calls as usize, trace_count as usize));
+        }
+
+        let called_by_rows = sqlx::query(
+            r#"
+            SELECT
+                ds_from.service AS from_service,
+                COUNT(de.from_span) AS total_calls,
+                COUNT(DISTINCT ds_from.trace_id) AS trace_count
+            FROM distributed_edges de
+            JOIN distributed_spans ds_from ON de.from_span = ds_from.span_id
+            JOIN distributed_spans ds_to ON de.to_span = ds_to.span_id
+            WHERE ds_to.service = ?
+                AND ds_from.service != ds_to.service
+            GROUP BY ds_from.service
+            ORDER BY total_calls DESC
+            "#,
+        )
+        .bind(service_name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut called_by = Vec::new();
+        for row in called_by_rows {
+            let from_service: String = row.try_get("from_service")?;
+            let total_calls: i64 = row.try_get("total_calls").unwrap_or(0);
+            let trace_count: i64 = row.try_get("trace_count").unwrap_or(0);
+            called_by.push((from_service, total_calls as usize, trace_count as usize));
+        }
+
+        Ok((calls_to, called_by))
+    }
+
+    async fn get_all_distributed_edges(&self) -> Result<Vec<serde_json::Value>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+              ds_from.service AS from_service,
+              ds_to.service AS to_service,
+              de.link_type,
+              COUNT(*) AS call_count
+            FROM distributed_edges de
+            JOIN distributed_spans ds_from ON de.from_span = ds_from.span_id
+            JOIN distributed_spans ds_to ON de.to_span = ds_to.span_id
+            GROUP BY ds_from.service, ds_to.service, de.link_type
+            ORDER BY call_count DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut edges = Vec::new();
+        for row in rows {
+            edges.push(serde_json::json!({
+                "from_service": row.try_get::<String, _>("from_service")?,
+                "to_service": row.try_get::<String, _>("to_service")?,
+                "link_type": row.try_get::<String, _>("link_type")?,
+                "call_count": row.try_get::<i64, _>("call_count")? as usize,
+            }));
+        }
+
+        Ok(edges)
+    }
+
+    async fn get_global_race_candidates(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        // JSON_ARRAYAGG has no DISTINCT, so access types and trace ids are
+        // deduplicated below
+        let rows = sqlx::query(
+            r#"
+            SELECT
+              cti.variable,
+              COUNT(DISTINCT cti.trace_id) AS trace_count,
+              COUNT(*) AS access_count,
+              JSON_ARRAYAGG(cti.access_type) AS access_types,
+              COUNT(DISTINCT cti.thread_id) AS thread_count,
+              JSON_ARRAYAGG(cti.trace_id) AS trace_ids
+            FROM cross_trace_index cti
+            JOIN events e ON cti.event_id = e.id
+            WHERE ? IS NULL OR e.environment = ?
+            GROUP BY cti.variable
+            HAVING COUNT(DISTINCT cti.trace_id) > 1 OR COUNT(DISTINCT cti.thread_id) > 1
+            ORDER BY trace_count DESC, access_count DESC
+            "#,
+        )
+        .bind(environment)
+        .bind(environment)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut races = Vec::new();
+        for row in rows {
+            let access_types: BTreeSet<String> = json_or_default(&row, "access_types")?;
+            let trace_ids: BTreeSet<String> = json_or_default(&row, "trace_ids")?;
+            let thread_count: i64 = row.try_get("thread_count")?;
+
+            // Check for any write-type operations (Write, AtomicWrite, AtomicRMW)
+            let has_write = access_types
+                .iter()
+                .any(|t| t == "Write" || t == "AtomicWrite" || t == "AtomicRMW");
+            let has_read = access_types
+                .iter()
+                .any(|t| t == "Read" || t == "AtomicRead");
+
+            // Determine severity based on access patterns
+            let severity = if has_write && has_read {
+                // Mix of reads and writes = read-write race
+                "WARNING"
+            } else if has_write && thread_count > 1 {
+                // Multiple threads writing (no reads) = write-write race
+                "CRITICAL"
+            } else if has_write {
+                // Single thread writing
+                "WARNING"
+            } else {
+                // Only reads
+                "INFO"
+            };
+
+            races.push(serde_json::json!({
+                "variable": row.try_get::<String, _>("variable")?,
+                "trace_count": row.try_get::<i64, _>("trace_count")? as usize,
+                "access_count": row.try_get::<i64, _>("access_count")? as usize,
+                "access_types": access_types,
+                "thread_count": thread_count as usize,
+                "severity": severity,
+                "trace_ids": trace_ids,
+            }));
+        }
+
+        Ok(races)
+    }
+
+    async fn get_top_service_calls(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let service_rows = sqlx::query(
+            r#"
+            SELECT
+              ds_from.service AS from_service,
+              ds_to.service AS to_service,
+              COUNT(*) AS call_count
+            FROM distributed_edges de
+            JOIN distributed_spans ds_from ON de.from_span = ds_from.span_id
+            JOIN distributed_spans ds_to ON de.to_span = ds_to.span_id
+            WHERE ? IS NULL OR ds_from.trace_id IN (
+                SELECT trace_id FROM events WHERE environment = ?
+            )
+            GROUP BY ds_from.service, ds_to.service
+            ORDER BY call_count DESC
+            LIMIT 10
+            "#,
+        )
+        .bind(environment)
+        .bind(environment)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut top_service_calls = Vec::new();
+        for row in service_rows {
+            top_service_calls.push(serde_json::json!({
+                "from_service": row.try_get::<String, _>("from_service")?,
+                "to_service": row.try_get::<String, _>("to_service")?,
+                "call_count": row.try_get::<i64, _>("call_count")? as usize,
+            }));
+        }
+
+        Ok(top_service_calls)
+    }
+
+    async fn get_service_health(&self, time_window_minutes: u64) -> Result<Vec<serde_json::Value>> {
+        let window_start = Utc::now() - chrono::Duration::minutes(time_window_minutes as i64);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                s.service AS service_name,
+                COUNT(DISTINCT CASE WHEN e.timestamp > ? THEN e.trace_id END) AS trace_count,
+                MAX(e.timestamp) AS last_activity,
+                CAST(COALESCE(AVG(trace_events.event_count), 0) AS DOUBLE) AS avg_events_per_trace,
+                CAST(TIMESTAMPDIFF(MICROSECOND, COALESCE(MAX(e.timestamp), UTC_TIMESTAMP(6)), UTC_TIMESTAMP(6)) / 60000000.0 AS DOUBLE) AS minutes_since_last_activity
+            FROM distributed_spans s
+            LEFT JOIN events e ON s.trace_id = e.trace_id
+            LEFT JOIN (
+                SELECT trace_id, COUNT(*) AS event_count
+                FROM events
+                WHERE timestamp > ?
+                GROUP BY trace_id
+            ) trace_events ON e.trace_id = trace_events.trace_id AND e.timestamp > ?
+            GROUP BY s.service
+            ORDER BY s.service
+            "#,
+        )
+        .bind(window_start)
+        .bind(window_start)
+        .bind(window_start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut services = Vec::new();
+        for row in rows {
+            let minutes_since: f64 = row.try_get("minutes_since_last_activity")?;
+            let status = if minutes_since < 5.0 {
+                "healthy"
+            } else if minutes_since < 30.0 {
+                "warning"
+            } else {
+                "critical"
+            };
+
+            let last_activity = row.try_get::<Option<DateTime<Utc>>, _>("last_activity")?;
+
+            services.push(serde_json::json!({
+                "name": row.try_get::<String, _>("service_name")?,
+                "status": status,
+                "trace_count": row.try_get::<i64, _>("trace_count")? as usize,
+                "last_activity": last_activity.unwrap_or_else(Utc::now),
+                "avg_events_per_trace": row.try_get::<f64, _>("avg_events_per_trace")?,
+                "minutes_since_last_activity": minutes_since,
+            }));
+        }
+
+        Ok(services)
+    }
+
+    async fn get_performance_metrics(
+        &self,
+        limit: usize,
+        scope: &MetricsScope,
+    ) -> Result<serde_json::Value> {
+        let cutoff = scope.cutoff();
+        let service = scope.service.as_deref();
+
+        // Every trace's duration, for the latency percentiles
+        let mut trace_durations = bind_scope(
+            sqlx::query(&format!(
+                r#"
+                SELECT CAST(TIMESTAMPDIFF(MICROSECOND, MIN(e.timestamp), MAX(e.timestamp)) / 1000.0 AS DOUBLE) AS duration_ms
+                FROM events e
+                WHERE {SCOPE_FILTER}
+                GROUP BY e.trace_id
+                "#
+            )),
+            cutoff,
+            service,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| row.try_get("duration_ms"))
+        .collect::<std::result::Result<Vec<f64>, _>>()?;
+        trace_durations.sort_by(|a, b| a.total_cmp(b));
+
+        let avg_duration = if trace_durations.is_empty() {
+            0.0
+        } else {
+            trace_durations.iter().sum::<f64>() / trace_durations.len() as f64
+        };
+        let p50 = percentile_cont(&trace_durations, 0.5);
+        let p95 = percentile_cont(&trace_durations, 0.95);
+        let p99 = percentile_cont(&trace_durations, 0.99);
+
+        let trace_rows = bind_scope(
+            sqlx::query(&format!(
+                r#"
+                SELECT
+                    e.trace_id,
+                    CAST(TIMESTAMPDIFF(MICROSECOND, MIN(e.timestamp), MAX(e.timestamp)) / 1000.0 AS DOUBLE) AS duration_ms,
+                    ANY_VALUE(ds.services) AS services
+                FROM events e
+                LEFT JOIN ({TRACE_SERVICES}) ds ON e.trace_id = ds.trace_id
+                WHERE {SCOPE_FILTER}
+                GROUP BY e.trace_id
+                ORDER BY duration_ms DESC
+                LIMIT ?
+                "#
+            )),
+            cutoff,
+            service,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut slowest_traces = Vec::new();
+        for row in trace_rows {
+            let mut services: Vec<String> = json_or_default(&row, "services")?;
+            services.sort();
+            slowest_traces.push(serde_json::json!({
+                "trace_id": uuid_column(&row, "trace_id")?.to_string(),
+                "duration_ms": row.try_get::<f64, _>("duration_ms")?,
+                "services": services,
+            }));
+        }
+
+        // Event type performance; every EventKind serializes as a one-key object
+        let event_type_rows = bind_scope(
+            sqlx::query(&format!(
+                r#"
+                SELECT
+                    event_type,
+                    COUNT(*) AS count,
+                    CAST(AVG(duration_ns / 1000000.0) AS DOUBLE) AS avg_duration_ms
+                FROM (
+                    SELECT
+                        JSON_UNQUOTE(JSON_EXTRACT(JSON_KEYS(e.kind), '$[0]')) AS event_type,
+                        {DURATION_NS} AS duration_ns
+                    FROM events e
+                    WHERE {SCOPE_FILTER}
+                ) event_types
+                WHERE event_type IS NOT NULL AND duration_ns > 0
+                GROUP BY event_type
+                ORDER BY avg_duration_ms DESC
+                LIMIT 20
+                "#
+            )),
+            cutoff,
+            service,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut event_types = Vec::new();
+        for row in event_type_rows {
+            event_types.push(serde_json::json!({
+                "type": row.try_get::<String, _>("event_type")?,
+                "count": row.try_get::<i64, _>("count")? as usize,
+                "avg_duration_ms": row.try_get::<f64, _>("avg_duration_ms").unwrap_or(0.0),
+            }));
+        }
+
+        // Service latency
+        let service_rows = bind_scope(
+            sqlx::query(&format!(
+                r#"
+                SELECT
+                    s.service,
+                    COUNT(DISTINCT e.id) AS event_count,
+                    CAST(AVG({DURATION_NS} / 1000000.0) AS DOUBLE) AS avg_duration_ms
+                FROM distributed_spans s
+                JOIN events e ON s.trace_id = e.trace_id
+                WHERE {SCOPE_FILTER} AND {DURATION_NS} IS NOT NULL
+                GROUP BY s.service
+                ORDER BY avg_duration_ms DESC
+                "#
+            )),
+            cutoff,
+            service,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut service_latency = Vec::new();
+        for row in service_rows {
+            service_latency.push(serde_json::json!({
+                "service": row.try_get::<String, _>("service")?,
+                "event_count": row.try_get::<i64, _>("event_count")? as usize,
+                "avg_duration_ms": row.try_get::<f64, _>("avg_duration_ms").unwrap_or(0.0),
+            }));
+        }
+
+        // Throughput metrics
+        let throughput_row = bind_scope(
+            sqlx::query(&format!(
+                r#"
+                SELECT
+                    COUNT(*) AS total_events,
+                    COUNT(DISTINCT e.trace_id) AS total_traces,
+                    CAST(TIMESTAMPDIFF(MICROSECOND, MIN(e.timestamp), MAX(e.timestamp)) / 1000000.0 AS DOUBLE) AS time_range_seconds
+                FROM events e
+                WHERE {SCOPE_FILTER}
+                "#
+            )),
+            cutoff,
+            service,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let total_events: i64 = throughput_row.try_get("total_events").unwrap_or(0);
+        let total_traces: i64 = throughput_row.try_get("total_traces").unwrap_or(0);
+        let time_range: f64 = throughput_row.try_get("time_range_seconds").unwrap_or(1.0);
+
+        let events_per_second = if time_range > 0.0 {
+            total_events as f64 / time_range
+        } else {
+            0.0
+        };
+
+        let traces_per_second = if time_range > 0.0 {
+            total_traces as f64 / time_range
+        } else {
+            0.0
+        };
+
+        Ok(serde_json::json!({
+            "trace_latency": {
+                "avg_ms": avg_duration,
+                "p50_ms": p50,
+                "p95_ms": p95,
+                "p99_ms": p99,
+                "slowest_traces": slowest_traces,
+            },
+            "event_performance": {
+                "by_type": event_types,
+            },
+            "service_latency": service_latency,
+            "throughput": {
+                "events_per_second": events_per_second,
+                "traces_per_second": traces_per_second,
+                "time_range_seconds": time_range,
+            },
+        }))
+    }
+
+    async fn get_throughput_buckets(
+        &self,
+        minutes: u64,
+        service: Option<&str>,
+    ) -> Result<Vec<ThroughputBucket>> {
+        let start = ThroughputBucket::window_start(minutes);
+        let end = start + chrono::Duration::minutes(minutes as i64);
+
+        // Only minutes with events come back; the quiet ones are filled in below
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                CAST(FLOOR(TIMESTAMPDIFF(SECOND, ?, timestamp) / 60) AS SIGNED) AS minute_index,
+                COUNT(*) AS events,
+                COUNT(DISTINCT trace_id) AS traces
+            FROM events
+            WHERE timestamp >= ? AND timestamp < ?
+              AND (? IS NULL OR service_name = ?)
+            GROUP BY minute_index
+            "#,
+        )
+        .bind(start)
+        .bind(start)
+        .bind(end)
+        .bind(service)
+        .bind(service)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut counts: HashMap<i64, (usize, usize)> = HashMap::new();
+        for row in rows {
+            counts.insert(
+                row.try_get("minute_index")?,
+                (
+                    row.try_get::<i64, _>("events")? as usize,
+                    row.try_get::<i64, _>("traces")? as usize,
+                ),
+            );
+        }
+
+        Ok((0..minutes as i64)
+            .map(|index| {
+                let (events, traces) = counts.get(&index).copied().unwrap_or_default();
+                ThroughputBucket::new(start + chrono::Duration::minutes(index), events, traces)
+            })
+            .collect())
+    }
+
+    async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(start.elapsed())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        // TRUNCATE is rejected for tables referenced by foreign keys, so delete
+        // children before the events they point at
+        const STATEMENTS: [&str; 8] = [
+            "DELETE FROM causal_edges",
+            "DELETE FROM trace_roots",
+            "DELETE FROM cross_trace_index",
+            "DELETE FROM anomalies",
+            "DELETE FROM events",
+            "DELETE FROM baseline_metrics",
+            "DELETE FROM distributed_edges",
+            "DELETE FROM distributed_spans",
+        ];
+
+        let mut tx = self.pool.begin().await?;
+        for statement in STATEMENTS {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MySqlConfig;
+    use crate::event::{AccessType, EventMetadata};
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn make_state_change_event(
+        trace_id: Uuid,
+        thread_id: &str,
+        service: &str,
+        access_type: AccessType,
+        timestamp: DateTime<Utc>,
+    ) -> Event {
+        Event {
+            id: Uuid::new_v4(),
+            trace_id,
+            parent_id: None,
+            timestamp,
+            kind: EventKind::StateChange {
+                variable: "account.balance".to_string(),
+                old_value: Some(json!(123)),
+                new_value: json!(456),
+                location: "test.rs:1".to_string(),
+                access_type,
+            },
+            metadata: EventMetadata {
+                thread_id: thread_id.to_string(),
+                process_id: 1,
+                service_name: service.to_string(),
+                environment: "test".to_string(),
+                tags: HashMap::new(),
+                duration_ns: Some(1_000_000),
+                instance_id: None,
+                distributed_span_id: None,
+                upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
+            },
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
+    /// Backend with empty tables, or `None` when RACEWAY_TEST_MYSQL_URL is unset
+    async fn test_backend(test_name: &str) -> Result<Option<MySqlBackend>> {
+        let url = match std::env::var("RACEWAY_TEST_MYSQL_URL") {
+            Ok(url) => url,
+            Err(_) => {
+                eprintln!("Skipping {} (set RACEWAY_TEST_MYSQL_URL to run)", test_name);
+                return Ok(None);
+            }
+        };
+
+        let storage_config = StorageConfig {
+            backend: "mysql".to_string(),
+            mysql: MySqlConfig {
+                connection_string: Some(url),
+                auto_migrate: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let backend = MySqlBackend::new(&storage_config).await?;
+        backend.clear().await?;
+        Ok(Some(backend))
+    }
+
+    #[test]
+    fn percentile_cont_interpolates_between_ranks() {
+        assert_eq!(percentile_cont(&[], 0.5), 0.0);
+        assert_eq!(percentile_cont(&[4.0], 0.99), 4.0);
+        assert_eq!(percentile_cont(&[1.0, 2.0, 3.0, 4.0], 0.5), 2.5);
+        assert!((percentile_cont(&[0.0, 10.0], 0.95) - 9.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn mysql_backend_round_trips_events() -> Result<()> {
+        let Some(backend) = test_backend("mysql_backend_round_trips_events").await? else {
+            return Ok(());
+        };
+
+        let trace_id = Uuid::new_v4();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let root = make_state_change_event(trace_id, "t1", "api", AccessType::Write, start);
+        let mut child = make_state_change_event(
+            trace_id,
+            "t2",
+            "api",
+            AccessType::Read,
+            start + chrono::Duration::milliseconds(5),
+        );
+        child.parent_id = Some(root.id);
+        child.causality_vector = vec![
+            (trace_id.to_string(), 2),
+            ("api#instance-1".to_string(), u64::MAX),
+        ];
+        child.lock_set = vec!["accounts".to_string(), "audit".to_string()];
+
+        assert_eq!(
+            backend
+                .add_events_batch(vec![root.clone(), child.clone()])
+                .await?,
+            2
+        );
+        // Re-ingesting the same events is a no-op
+        backend.add_event(child.clone()).await?;
+
+        let stored = backend.get_trace_events(trace_id).await?;
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].id, root.id);
+        assert_eq!(stored[1].parent_id, Some(root.id));
+        assert_eq!(stored[1].causality_vector, child.causality_vector);
+        assert_eq!(stored[1].lock_set, child.lock_set);
+        assert_eq!(stored[1].timestamp, child.timestamp);
+
+        assert_eq!(backend.get_trace_roots(trace_id).await?, vec![root.id]);
+        assert_eq!(backend.count_events().await?, 2);
+        assert_eq!(backend.count_traces().await?, 1);
+
+        let (summaries, total) = backend
+            .get_trace_summaries(1, 10, None, TimeWindow::default())
+            .await?;
+        assert_eq!(total, 1);
+        assert_eq!(summaries[0].trace_id, trace_id);
+        assert_eq!(summaries[0].event_count, 2);
+
+        let (_, total) = backend
+            .get_trace_summaries_by_environment("test", 1, 10, Some(3), TimeWindow::default())
+            .await?;
+        assert_eq!(total, 0);
+
+        let stats = DurationStats {
+            count: 3,
+            total_duration_us: 30,
+            min_duration_us: 5,
+            max_duration_us: 15,
+            mean_duration_us: 10.0,
+            variance: 16.7,
+            std_dev: 4.08,
+        };
+        backend.save_baseline("StateChange", stats.clone()).await?;
+        backend.save_baseline("StateChange", stats).await?;
+        let baseline = backend.get_baseline_metric("StateChange").await?.unwrap();
+        assert_eq!(baseline.count, 3);
+        assert_eq!(
+            backend.get_all_baseline_operations().await?,
+            vec!["StateChange"]
+        );
+
+        backend.clear().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mysql_backend_distributed_insights() -> Result<()> {
+        let Some(backend) = test_backend("mysql_backend_distributed_insights").await? else {
+            return Ok(());
+        };
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let trace_a = Uuid::new_v4();
+        let trace_b = Uuid::new_v4();
+
+        for (span_id, service) in [("span-a", "service-a"), ("span-b", "service-b")] {
+            backend
+                .save_distributed_span(DistributedSpan {
+                    trace_id: trace_a,
+                    span_id: span_id.to_string(),
+                    service: service.to_string(),
+                    instance: format!("{}-1", service),
+                    first_event: now,
+                    last_event: Some(now),
+                })
+                .await?;
+        }
+        backend
+            .add_distributed_edge(DistributedEdge {
+                from_span: "span-a".to_string(),
+                to_span: "span-b".to_string(),
+                link_type: crate::event::EdgeLinkType::HttpCall,
+                metadata: json!({}),
+            })
+            .await?;
+
+        backend
+            .add_event(make_state_change_event(
+                trace_a,
+                "thread-1",
+                "service-a",
+                AccessType::Write,
+                now,
+            ))
+            .await?;
+        backend
+            .add_event(make_state_change_event(
+                trace_b,
+                "thread-2",
+                "service-b",
+                AccessType::Read,
+                now + chrono::Duration::milliseconds(1),
+            ))
+            .await?;
+
+        assert_eq!(backend.get_distributed_spans(trace_a).await?.len(), 2);
+        assert_eq!(backend.get_distributed_edges(trace_a).await?.len(), 1);
+
+        let edges = backend.get_all_distributed_edges().await?;
+        assert_eq!(edges[0]["from_service"], json!("service-a"));
+        assert_eq!(edges[0]["to_service"], json!("service-b"));
+        assert_eq!(edges[0]["call_count"].as_u64().unwrap(), 1);
+
+        let races = backend.get_global_race_candidates(Some("test")).await?;
+        assert_eq!(races[0]["variable"], json!("account.balance"));
+        assert_eq!(races[0]["severity"], json!("WARNING"));
+        assert_eq!(races[0]["trace_ids"].as_array().unwrap().len(), 2);
+
+        let (summaries, _) = backend
+            .get_trace_summaries(1, 10, None, TimeWindow::default())
+            .await?;
+        let summary = summaries
+            .iter()
+            .find(|summary| summary.trace_id == trace_a)
+            .unwrap();
+        assert_eq!(summary.services, vec!["service-a", "service-b"]);
+
+        let metrics = backend
+            .get_performance_metrics(10, &MetricsScope::default())
+            .await?;
+        assert_eq!(
+            metrics["trace_latency"]["slowest_traces"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            metrics["event_performance"]["by_type"][0]["type"],
+            json!("StateChange")
+        );
+
+        assert_eq!(backend.get_top_service_calls(None).await?.len(), 1);
+
+        backend.clear().await?;
+        Ok(())
+    }
+}
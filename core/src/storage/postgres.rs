@@ -1,5 +1,7 @@
 use super::storage_trait::StorageBackend;
-use super::types::{DurationStats, TraceSummary};
+use super::types::{
+    DurationStats, MaintenanceReport, MetricsScope, ThroughputBucket, TimeWindow, TraceSummary,
+};
 use crate::config::StorageConfig;
 use crate::event::{DistributedEdge, DistributedSpan, Event, EventKind};
 use anyhow::{anyhow, Result};
@@ -7,6 +9,7 @@ use async_trait::async_trait;
 use itertools::Itertools;
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use sqlx::Row;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Pure PostgreSQL storage backend (CRUD operations only)
@@ -57,6 +60,16 @@ impl PostgresBackend {
             sqlx::raw_sql(migration_003).execute(&pool).await?;
             tracing::info!("✓ Migration 003 (distributed tracing) completed");
 
+            // Migration 004: Environment index
+            let migration_004 = include_str!("../../migrations/postgres/004_environment_index.sql");
+            sqlx::raw_sql(migration_004).execute(&pool).await?;
+            tracing::info!("✓ Migration 004 (environment index) completed");
+
+            // Migration 005: Trace tags
+            let migration_005 = include_str!("../../migrations/postgres/005_trace_tags.sql");
+            sqlx::raw_sql(migration_005).execute(&pool).await?;
+            tracing::info!("✓ Migration 005 (trace tags) completed");
+
             tracing::info!("All migrations completed successfully");
         }
 
@@ -461,34 +474,33 @@ impl StorageBackend for PostgresBackend {
         page: usize,
         page_size: usize,
         min_events: Option<usize>,
+        window: TimeWindow,
     ) -> Result<(Vec<TraceSummary>, usize)> {
-        // Build count query with optional min_events filter
-        let count_query = if let Some(min) = min_events {
-            format!(
+        // Count traces; filters need the per-trace aggregates
+        let total_count: i64 = if min_events.is_none() && window.is_unbounded() {
+            sqlx::query_scalar("SELECT COUNT(DISTINCT trace_id) FROM events")
+                .fetch_one(&self.pool)
+                .await?
+        } else {
+            sqlx::query_scalar(
                 r#"
-                SELECT COUNT(DISTINCT trace_id) as total
+                SELECT COUNT(*)
                 FROM (
-                    SELECT trace_id, COUNT(*) as cnt
+                    SELECT trace_id
                     FROM events
                     GROUP BY trace_id
-                    HAVING COUNT(*) >= {}
+                    HAVING COUNT(*) >= $1
+                       AND ($2::TIMESTAMPTZ IS NULL OR MAX(timestamp) >= $2)
+                       AND ($3::TIMESTAMPTZ IS NULL OR MIN(timestamp) <= $3)
                 ) AS filtered
                 "#,
-                min
             )
-        } else {
-            r#"
-            SELECT COUNT(DISTINCT trace_id) as total
-            FROM events
-            "#
-            .to_string()
-        };
-
-        let count_row = sqlx::query(&count_query)
+            .bind(min_events.unwrap_or(1) as i64)
+            .bind(window.since)
+            .bind(window.until)
             .fetch_one(&self.pool)
-            .await?;
-
-        let total_count: i64 = count_row.get("total");
+            .await?
+        };
 
         // Get paginated summaries with optimized query
         // Pre-aggregate services in a subquery to avoid expensive ARRAY_AGG in main query
@@ -514,6 +526,8 @@ impl StorageBackend for PostgresBackend {
             ) ds ON e.trace_id = ds.trace_id
             GROUP BY e.trace_id, ds.services, ds.service_count
             HAVING COUNT(DISTINCT e.id) >= $1
+               AND ($4::TIMESTAMPTZ IS NULL OR MAX(e.timestamp) >= $4)
+               AND ($5::TIMESTAMPTZ IS NULL OR MIN(e.timestamp) <= $5)
             ORDER BY MAX(e.timestamp) DESC
             LIMIT $2 OFFSET $3
             "#,
@@ -521,6 +535,8 @@ impl StorageBackend for PostgresBackend {
         .bind(min_events.unwrap_or(1) as i64)
         .bind(page_size as i64)
         .bind(offset as i64)
+        .bind(window.since)
+        .bind(window.until)
         .fetch_all(&self.pool)
         .await?;
 
@@ -618,6 +634,123 @@ impl StorageBackend for PostgresBackend {
         Ok((summaries, total_count as usize))
     }
 
+    async fn get_trace_summaries_by_environment(
+        &self,
+        environment: &str,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        let min_events = min_events.unwrap_or(1) as i64;
+
+        // Count traces with at least one event in this environment
+        let count_row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as total
+            FROM (
+                SELECT trace_id
+                FROM events
+                WHERE trace_id IN (
+                    SELECT trace_id FROM events WHERE metadata->>'environment' = $1
+                )
+                GROUP BY trace_id
+                HAVING COUNT(*) >= $2
+                   AND ($3::TIMESTAMPTZ IS NULL OR MAX(timestamp) >= $3)
+                   AND ($4::TIMESTAMPTZ IS NULL OR MIN(timestamp) <= $4)
+            ) AS filtered
+            "#,
+        )
+        .bind(environment)
+        .bind(min_events)
+        .bind(window.since)
+        .bind(window.until)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let total_count: i64 = count_row.get("total");
+
+        let offset = (page.saturating_sub(1)) * page_size;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                e.trace_id,
+                COUNT(DISTINCT e.id) as event_count,
+                MIN(e.timestamp) as first_timestamp,
+                MAX(e.timestamp) as last_timestamp,
+                COALESCE(ds.services, ARRAY[]::TEXT[]) as services,
+                COALESCE(ds.service_count, 0) as service_count
+            FROM events e
+            LEFT JOIN (
+                SELECT
+                    trace_id,
+                    ARRAY_AGG(DISTINCT service ORDER BY service) as services,
+                    COUNT(DISTINCT service) as service_count
+                FROM distributed_spans
+                GROUP BY trace_id
+            ) ds ON e.trace_id = ds.trace_id
+            WHERE e.trace_id IN (
+                SELECT trace_id FROM events WHERE metadata->>'environment' = $1
+            )
+            GROUP BY e.trace_id, ds.services, ds.service_count
+            HAVING COUNT(DISTINCT e.id) >= $2
+               AND ($5::TIMESTAMPTZ IS NULL OR MAX(e.timestamp) >= $5)
+               AND ($6::TIMESTAMPTZ IS NULL OR MIN(e.timestamp) <= $6)
+            ORDER BY MAX(e.timestamp) DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(environment)
+        .bind(min_events)
+        .bind(page_size as i64)
+        .bind(offset as i64)
+        .bind(window.since)
+        .bind(window.until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let summaries = rows
+            .into_iter()
+            .map(|row| {
+                let services: Vec<String> = row.get("services");
+                let service_count: i64 = row.get("service_count");
+                TraceSummary {
+                    trace_id: row.get("trace_id"),
+                    event_count: row.get("event_count"),
+                    first_timestamp: row.get("first_timestamp"),
+                    last_timestamp: row.get("last_timestamp"),
+                    services,
+                    service_count: service_count as usize,
+                }
+            })
+            .collect();
+
+        Ok((summaries, total_count as usize))
+    }
+
+    async fn get_trace_ids_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT trace_id
+            FROM events
+            WHERE metadata->'tags'->>$1 = $2
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(row.try_get("trace_id")?))
+            .collect()
+    }
+
     async fn get_trace_roots(&self, trace_id: Uuid) -> Result<Vec<Uuid>> {
         let rows = sqlx::query(
             r#"
@@ -636,6 +769,63 @@ impl StorageBackend for PostgresBackend {
             .collect())
     }
 
+    async fn add_trace_tags(
+        &self,
+        trace_id: Uuid,
+        tags: std::collections::HashMap<String, String>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for (key, value) in tags {
+            sqlx::query(
+                r#"
+                INSERT INTO trace_tags (trace_id, key, value)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (trace_id, key) DO UPDATE SET value = EXCLUDED.value
+                "#,
+            )
+            .bind(trace_id)
+            .bind(&key)
+            .bind(&value)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_trace_tags(
+        &self,
+        trace_id: Uuid,
+    ) -> Result<std::collections::HashMap<String, String>> {
+        let rows = sqlx::query("SELECT key, value FROM trace_tags WHERE trace_id = $1")
+            .bind(trace_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect())
+    }
+
+    async fn get_trace_ids_by_trace_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        let rows = sqlx::query("SELECT trace_id FROM trace_tags WHERE key = $1 AND value = $2")
+            .bind(key)
+            .bind(value)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| Ok(row.try_get("trace_id")?))
+            .collect()
+    }
+
     async fn save_baseline(&self, operation: &str, stats: DurationStats) -> Result<()> {
         sqlx::query(
             r#"
@@ -863,6 +1053,19 @@ impl StorageBackend for PostgresBackend {
         Ok(edges)
     }
 
+    async fn list_distributed_edges(&self) -> Result<Vec<DistributedEdge>> {
+        let rows = sqlx::query("SELECT edge_data FROM distributed_edges")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let edge_json: serde_json::Value = row.get("edge_data");
+                Ok(serde_json::from_value(edge_json)?)
+            })
+            .collect()
+    }
+
     async fn cleanup_old_traces(&self, retention_hours: u64) -> Result<usize> {
         let result = sqlx::query(
             r#"
@@ -882,6 +1085,77 @@ impl StorageBackend for PostgresBackend {
         Ok(count as usize)
     }
 
+    async fn maintenance(&self) -> Result<MaintenanceReport> {
+        const TABLES: [&str; 9] = [
+            "events",
+            "causal_edges",
+            "trace_roots",
+            "cross_trace_index",
+            "anomalies",
+            "baseline_metrics",
+            "distributed_spans",
+            "distributed_edges",
+            "trace_tags",
+        ];
+
+        let database_size = || async {
+            let row = sqlx::query("SELECT pg_database_size(current_database()) as size")
+                .fetch_one(&self.pool)
+                .await?;
+            Ok::<i64, anyhow::Error>(row.get("size"))
+        };
+        let size_before = database_size().await?;
+        let mut steps = Vec::new();
+
+        // Edges whose spans were purged can no longer be resolved to a trace
+        let result = sqlx::query(
+            r#"
+            DELETE FROM distributed_edges de
+            WHERE NOT EXISTS (SELECT 1 FROM distributed_spans WHERE span_id = de.from_span)
+               OR NOT EXISTS (SELECT 1 FROM distributed_spans WHERE span_id = de.to_span)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        let mut rows_removed = result.rows_affected() as usize;
+        steps.push("DELETE orphaned distributed_edges".to_string());
+
+        // Tags outlive their trace once retention has deleted its events
+        let result = sqlx::query(
+            r#"
+            DELETE FROM trace_tags tt
+            WHERE NOT EXISTS (SELECT 1 FROM events WHERE trace_id = tt.trace_id)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        rows_removed += result.rows_affected() as usize;
+        steps.push("DELETE orphaned trace_tags".to_string());
+
+        // VACUUM and REINDEX can't run inside a transaction, so issue them one by one
+        for table in TABLES {
+            sqlx::query(&format!("REINDEX TABLE {}", table))
+                .execute(&self.pool)
+                .await?;
+            steps.push(format!("REINDEX TABLE {}", table));
+        }
+        for table in TABLES {
+            sqlx::query(&format!("VACUUM ANALYZE {}", table))
+                .execute(&self.pool)
+                .await?;
+            steps.push(format!("VACUUM ANALYZE {}", table));
+        }
+
+        let size_after = database_size().await?;
+
+        Ok(MaintenanceReport {
+            steps,
+            rows_removed,
+            reclaimed_bytes: Some(size_before - size_after),
+            trace_count: self.count_traces().await?,
+        })
+    }
+
     async fn get_all_services(&self) -> Result<Vec<(String, usize, usize)>> {
         // Optimized query using distributed_spans table
         // Counts total events and distinct traces per service
@@ -912,6 +1186,49 @@ impl StorageBackend for PostgresBackend {
         Ok(services)
     }
 
+    async fn get_service_tag_values(
+        &self,
+        tag: &str,
+    ) -> Result<std::collections::HashMap<String, String>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (metadata->>'service_name')
+                metadata->>'service_name' as service,
+                metadata->'tags'->>$1 as value
+            FROM events
+            WHERE metadata->'tags' ? $1
+            ORDER BY metadata->>'service_name', timestamp DESC
+            "#,
+        )
+        .bind(tag)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut values = std::collections::HashMap::new();
+        for row in rows {
+            values.insert(row.try_get("service")?, row.try_get("value")?);
+        }
+        Ok(values)
+    }
+
+    async fn get_service_event_durations(&self, service: &str) -> Result<Vec<u64>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT (metadata->>'duration_ns')::BIGINT AS duration_ns
+            FROM events
+            WHERE metadata->>'service_name' = $1
+              AND jsonb_typeof(metadata->'duration_ns') = 'number'
+            "#,
+        )
+        .bind(service)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(row.try_get::<i64, _>("duration_ns")? as u64))
+            .collect()
+    }
+
     async fn get_service_dependencies_global(
         &self,
         service_name: &str,
@@ -1006,22 +1323,28 @@ impl StorageBackend for PostgresBackend {
         Ok(edges)
     }
 
-    async fn get_global_race_candidates(&self) -> Result<Vec<serde_json::Value>> {
+    async fn get_global_race_candidates(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
         let rows = sqlx::query(
             r#"
             SELECT
-              variable,
-              COUNT(DISTINCT trace_id) as trace_count,
+              cti.variable,
+              COUNT(DISTINCT cti.trace_id) as trace_count,
               COUNT(*) as access_count,
-              array_agg(DISTINCT access_type) as access_types,
-              COUNT(DISTINCT thread_id) as thread_count,
-              array_agg(DISTINCT trace_id::text) as trace_ids
-            FROM cross_trace_index
-            GROUP BY variable
-            HAVING COUNT(DISTINCT trace_id) > 1 OR COUNT(DISTINCT thread_id) > 1
+              array_agg(DISTINCT cti.access_type) as access_types,
+              COUNT(DISTINCT cti.thread_id) as thread_count,
+              array_agg(DISTINCT cti.trace_id::text) as trace_ids
+            FROM cross_trace_index cti
+            JOIN events e ON cti.event_id = e.id
+            WHERE $1::TEXT IS NULL OR e.metadata->>'environment' = $1
+            GROUP BY cti.variable
+            HAVING COUNT(DISTINCT cti.trace_id) > 1 OR COUNT(DISTINCT cti.thread_id) > 1
             ORDER BY trace_count DESC, access_count DESC
             "#,
         )
+        .bind(environment)
         .fetch_all(&self.pool)
         .await?;
 
@@ -1068,39 +1391,10 @@ impl StorageBackend for PostgresBackend {
         Ok(races)
     }
 
-    async fn get_system_hotspots(
+    async fn get_top_service_calls(
         &self,
-    ) -> Result<(Vec<serde_json::Value>, Vec<serde_json::Value>)> {
-        // Top variables by access count
-        let variable_rows = sqlx::query(
-            r#"
-            SELECT
-              cti.variable,
-              COUNT(*) as access_count,
-              COUNT(DISTINCT cti.trace_id) as trace_count,
-              array_agg(DISTINCT e.metadata->>'service_name') as services
-            FROM cross_trace_index cti
-            JOIN events e ON cti.event_id = e.id
-            WHERE e.metadata->>'service_name' IS NOT NULL
-            GROUP BY cti.variable
-            ORDER BY access_count DESC
-            LIMIT 10
-            "#,
-        )
-        .fetch_all(&self.pool)
-        .await?;
-
-        let mut top_variables = Vec::new();
-        for row in variable_rows {
-            let services: Vec<String> = row.try_get("services")?;
-            top_variables.push(serde_json::json!({
-                "variable": row.try_get::<String, _>("variable")?,
-                "access_count": row.try_get::<i64, _>("access_count")? as usize,
-                "trace_count": row.try_get::<i64, _>("trace_count")? as usize,
-                "services": services,
-            }));
-        }
-
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
         // Top service calls by frequency
         let service_rows = sqlx::query(
             r#"
@@ -1111,11 +1405,15 @@ impl StorageBackend for PostgresBackend {
             FROM distributed_edges de
             JOIN distributed_spans ds_from ON de.from_span = ds_from.span_id
             JOIN distributed_spans ds_to ON de.to_span = ds_to.span_id
+            WHERE $1::TEXT IS NULL OR ds_from.trace_id IN (
+                SELECT trace_id FROM events WHERE metadata->>'environment' = $1
+            )
             GROUP BY ds_from.service, ds_to.service
             ORDER BY call_count DESC
             LIMIT 10
             "#,
         )
+        .bind(environment)
         .fetch_all(&self.pool)
         .await?;
 
@@ -1128,7 +1426,7 @@ impl StorageBackend for PostgresBackend {
             }));
         }
 
-        Ok((top_variables, top_service_calls))
+        Ok(top_service_calls)
     }
 
     async fn get_service_health(&self, time_window_minutes: u64) -> Result<Vec<serde_json::Value>> {
@@ -1184,21 +1482,38 @@ impl StorageBackend for PostgresBackend {
         Ok(services)
     }
 
-    async fn get_performance_metrics(&self, limit: usize) -> Result<serde_json::Value> {
+    async fn get_performance_metrics(
+        &self,
+        limit: usize,
+        scope: &MetricsScope,
+    ) -> Result<serde_json::Value> {
+        // Every query below aggregates over the `scoped` CTE, binding the window
+        // cutoff and service filter as nullable parameters ($1 and $2)
+        const SCOPED_EVENTS: &str = r#"
+            WITH scoped AS (
+                SELECT * FROM events
+                WHERE ($1::timestamptz IS NULL OR timestamp >= $1)
+                  AND ($2::text IS NULL OR metadata->>'service_name' = $2)
+            )
+        "#;
+        let cutoff = scope.cutoff();
+
         // Get trace durations and calculate percentiles
-        let trace_rows = sqlx::query(
-            r#"
+        let trace_rows = sqlx::query(&format!(
+            r#"{SCOPED_EVENTS}
             SELECT
                 e.trace_id,
                 CAST(EXTRACT(EPOCH FROM (MAX(e.timestamp) - MIN(e.timestamp))) * 1000.0 AS DOUBLE PRECISION) as duration_ms,
                 array_agg(DISTINCT s.service) FILTER (WHERE s.service IS NOT NULL) as services
-            FROM events e
+            FROM scoped e
             LEFT JOIN distributed_spans s ON e.trace_id = s.trace_id
             GROUP BY e.trace_id
             ORDER BY duration_ms DESC
-            LIMIT $1
-            "#,
-        )
+            LIMIT $3
+            "#
+        ))
+        .bind(cutoff)
+        .bind(scope.service.as_deref())
         .bind(limit as i64)
         .fetch_all(&self.pool)
         .await?;
@@ -1220,8 +1535,8 @@ impl StorageBackend for PostgresBackend {
         }
 
         // Calculate percentiles using PostgreSQL
-        let percentile_row = sqlx::query(
-            r#"
+        let percentile_row = sqlx::query(&format!(
+            r#"{SCOPED_EVENTS}
             SELECT
                 CAST(AVG(duration_ms) AS DOUBLE PRECISION) as avg_duration,
                 CAST(PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY duration_ms) AS DOUBLE PRECISION) as p50,
@@ -1230,11 +1545,13 @@ impl StorageBackend for PostgresBackend {
             FROM (
                 SELECT
                     CAST(EXTRACT(EPOCH FROM (MAX(timestamp) - MIN(timestamp))) * 1000.0 AS DOUBLE PRECISION) as duration_ms
-                FROM events
+                FROM scoped
                 GROUP BY trace_id
             ) durations
-            "#,
-        )
+            "#
+        ))
+        .bind(cutoff)
+        .bind(scope.service.as_deref())
         .fetch_one(&self.pool)
         .await?;
 
@@ -1244,13 +1561,13 @@ impl StorageBackend for PostgresBackend {
         let p99: f64 = percentile_row.try_get("p99").unwrap_or(0.0);
 
         // Event type performance
-        let event_type_rows = sqlx::query(
-            r#"
-            WITH event_types AS (
+        let event_type_rows = sqlx::query(&format!(
+            r#"{SCOPED_EVENTS}
+            , event_types AS (
                 SELECT
                     jsonb_object_keys(kind) as event_type,
                     CAST(metadata->>'duration_ns' AS BIGINT) as duration_ns
-                FROM events
+                FROM scoped
                 WHERE metadata->>'duration_ns' IS NOT NULL
                   AND CAST(metadata->>'duration_ns' AS BIGINT) > 0
             )
@@ -1262,8 +1579,10 @@ impl StorageBackend for PostgresBackend {
             GROUP BY event_type
             ORDER BY avg_duration_ms DESC
             LIMIT 20
-            "#,
-        )
+            "#
+        ))
+        .bind(cutoff)
+        .bind(scope.service.as_deref())
         .fetch_all(&self.pool)
         .await?;
 
@@ -1277,19 +1596,21 @@ impl StorageBackend for PostgresBackend {
         }
 
         // Service latency
-        let service_rows = sqlx::query(
-            r#"
+        let service_rows = sqlx::query(&format!(
+            r#"{SCOPED_EVENTS}
             SELECT
                 s.service,
                 COUNT(DISTINCT e.id) as event_count,
                 CAST(AVG(CAST(e.metadata->>'duration_ns' AS BIGINT) / 1000000.0) AS DOUBLE PRECISION) as avg_duration_ms
             FROM distributed_spans s
-            JOIN events e ON s.trace_id = e.trace_id
+            JOIN scoped e ON s.trace_id = e.trace_id
             WHERE e.metadata->>'duration_ns' IS NOT NULL
             GROUP BY s.service
             ORDER BY avg_duration_ms DESC
-            "#,
-        )
+            "#
+        ))
+        .bind(cutoff)
+        .bind(scope.service.as_deref())
         .fetch_all(&self.pool)
         .await?;
 
@@ -1303,15 +1624,17 @@ impl StorageBackend for PostgresBackend {
         }
 
         // Throughput metrics
-        let throughput_row = sqlx::query(
-            r#"
+        let throughput_row = sqlx::query(&format!(
+            r#"{SCOPED_EVENTS}
             SELECT
                 COUNT(DISTINCT id) as total_events,
                 COUNT(DISTINCT trace_id) as total_traces,
                 CAST(EXTRACT(EPOCH FROM (MAX(timestamp) - MIN(timestamp))) AS DOUBLE PRECISION) as time_range_seconds
-            FROM events
-            "#,
-        )
+            FROM scoped
+            "#
+        ))
+        .bind(cutoff)
+        .bind(scope.service.as_deref())
         .fetch_one(&self.pool)
         .await?;
 
@@ -1351,8 +1674,58 @@ impl StorageBackend for PostgresBackend {
         }))
     }
 
+    async fn get_throughput_buckets(
+        &self,
+        minutes: u64,
+        service: Option<&str>,
+    ) -> Result<Vec<ThroughputBucket>> {
+        let start = ThroughputBucket::window_start(minutes);
+
+        // Minutes come from generate_series so quiet minutes still get a bucket
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                b.minute,
+                COUNT(e.id) as events,
+                COUNT(DISTINCT e.trace_id) as traces
+            FROM generate_series(
+                $1::timestamptz,
+                $1::timestamptz + INTERVAL '1 minute' * ($2 - 1),
+                INTERVAL '1 minute'
+            ) AS b(minute)
+            LEFT JOIN events e
+                ON e.timestamp >= b.minute
+               AND e.timestamp < b.minute + INTERVAL '1 minute'
+               AND ($3::text IS NULL OR e.metadata->>'service_name' = $3)
+            GROUP BY b.minute
+            ORDER BY b.minute
+            "#,
+        )
+        .bind(start)
+        .bind(minutes as i64)
+        .bind(service)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(ThroughputBucket::new(
+                    row.try_get("minute")?,
+                    row.try_get::<i64, _>("events")? as usize,
+                    row.try_get::<i64, _>("traces")? as usize,
+                ))
+            })
+            .collect()
+    }
+
+    async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(start.elapsed())
+    }
+
     async fn clear(&self) -> Result<()> {
-        sqlx::query("TRUNCATE events, causal_edges, trace_roots, baseline_metrics, cross_trace_index, distributed_spans, distributed_edges CASCADE")
+        sqlx::query("TRUNCATE events, causal_edges, trace_roots, baseline_metrics, cross_trace_index, distributed_spans, distributed_edges, trace_tags CASCADE")
             .execute(&self.pool)
             .await?;
 
@@ -1399,6 +1772,9 @@ mod tests {
                 instance_id: None,
                 distributed_span_id: None,
                 upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
             },
             causality_vector: Vec::new(),
             lock_set: Vec::new(),
@@ -1422,6 +1798,7 @@ mod tests {
                 auto_migrate: true,
                 ..Default::default()
             },
+            ..Default::default()
         };
 
         let backend = PostgresBackend::new(&storage_config).await?;
@@ -1489,14 +1866,13 @@ mod tests {
         assert_eq!(edges[0]["to_service"], json!("service-b"));
         assert_eq!(edges[0]["call_count"].as_u64().unwrap(), 1);
 
-        let races = backend.get_global_race_candidates().await?;
+        let races = backend.get_global_race_candidates(None).await?;
         assert!(!races.is_empty());
         let race = &races[0];
         assert_eq!(race["variable"], json!("account.balance"));
         assert_eq!(race["severity"], json!("WARNING"));
 
-        let (top_variables, top_service_calls) = backend.get_system_hotspots().await?;
-        assert!(!top_variables.is_empty());
+        let top_service_calls = backend.get_top_service_calls(None).await?;
         assert!(!top_service_calls.is_empty());
 
         backend.clear().await?;
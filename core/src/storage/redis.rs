@@ -0,0 +1,822 @@
+use super::memory::MemoryBackend;
+use super::storage_trait::StorageBackend;
+use super::types::{
+    DurationStats, MaintenanceReport, MetricsScope, ThroughputBucket, TimeWindow, TraceSummary,
+};
+use crate::config::StorageConfig;
+use crate::event::{DistributedEdge, DistributedSpan, Event};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Redis storage backend, shared by every server instance pointed at it
+///
+/// Layout (all keys live under `key_prefix`):
+/// - `{prefix}:traces`: set of trace ids
+/// - `{prefix}:trace_index`: sorted set of trace ids scored by their latest
+///   event timestamp (ms), used to page trace summaries (needs Redis 6.2+)
+/// - `{prefix}:trace:{trace_id}`: list of the trace's events (JSON)
+/// - `{prefix}:event_traces`: hash of event id -> trace id
+/// - `{prefix}:baselines`, `{prefix}:spans`: hashes of JSON values
+/// - `{prefix}:edges`: set of distributed edges (JSON)
+///
+/// Trace summaries are paged from `trace_index`. Other cross-trace
+/// aggregations are computed by loading the data into a transient
+/// `MemoryBackend`, so results match the in-memory backend exactly.
+pub struct RedisBackend {
+    conn: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisBackend {
+    pub async fn new(config: &StorageConfig) -> Result<Self> {
+        let url = config
+            .redis
+            .url
+            .as_ref()
+            .context("Redis url is required when backend = \"redis\"")?;
+
+        let client = redis::Client::open(url.as_str())?;
+        let conn = ConnectionManager::new(client).await?;
+
+        Ok(Self {
+            conn,
+            prefix: config.redis.key_prefix.clone(),
+        })
+    }
+
+    fn traces_key(&self) -> String {
+        format!("{}:traces", self.prefix)
+    }
+
+    fn trace_index_key(&self) -> String {
+        format!("{}:trace_index", self.prefix)
+    }
+
+    fn trace_key(&self, trace_id: Uuid) -> String {
+        format!("{}:trace:{}", self.prefix, trace_id)
+    }
+
+    fn event_traces_key(&self) -> String {
+        format!("{}:event_traces", self.prefix)
+    }
+
+    fn baselines_key(&self) -> String {
+        format!("{}:baselines", self.prefix)
+    }
+
+    fn spans_key(&self) -> String {
+        format!("{}:spans", self.prefix)
+    }
+
+    fn edges_key(&self) -> String {
+        format!("{}:edges", self.prefix)
+    }
+
+    async fn all_spans(&self) -> Result<Vec<DistributedSpan>> {
+        let mut conn = self.conn.clone();
+        let values: Vec<String> = conn.hvals(self.spans_key()).await?;
+        values
+            .iter()
+            .map(|value| Ok(serde_json::from_str(value)?))
+            .collect()
+    }
+
+    async fn all_edges(&self) -> Result<Vec<DistributedEdge>> {
+        let mut conn = self.conn.clone();
+        let values: Vec<String> = conn.smembers(self.edges_key()).await?;
+        values
+            .iter()
+            .map(|value| Ok(serde_json::from_str(value)?))
+            .collect()
+    }
+
+    /// Whether every stored trace is in the trace index. Traces written before
+    /// the index existed are only added by `maintenance`.
+    async fn trace_index_complete(&self) -> Result<bool> {
+        let mut conn = self.conn.clone();
+        let (indexed, traces): (usize, usize) = redis::pipe()
+            .zcard(self.trace_index_key())
+            .scard(self.traces_key())
+            .query_async(&mut conn)
+            .await?;
+        Ok(indexed == traces)
+    }
+
+    /// Load everything into a `MemoryBackend` to answer cross-trace queries
+    async fn snapshot(&self) -> Result<MemoryBackend> {
+        let memory = MemoryBackend::new(&StorageConfig::default())?;
+        memory
+            .add_events_batch(self.get_all_events().await?)
+            .await?;
+        // Spans first so edges resolve to their traces immediately
+        for span in self.all_spans().await? {
+            memory.save_distributed_span(span).await?;
+        }
+        for edge in self.all_edges().await? {
+            memory.add_distributed_edge(edge).await?;
+        }
+        Ok(memory)
+    }
+}
+
+fn sort_events(events: &mut [Event]) {
+    // Sort by timestamp (primary), causality depth (secondary), id (tertiary)
+    events.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then(a.causality_vector.len().cmp(&b.causality_vector.len()))
+            .then(a.id.cmp(&b.id))
+    });
+}
+
+fn parse_events(values: &[String]) -> Result<Vec<Event>> {
+    values
+        .iter()
+        .map(|value| Ok(serde_json::from_str(value)?))
+        .collect()
+}
+
+fn parse_trace_ids(ids: &[String]) -> Result<Vec<Uuid>> {
+    ids.iter().map(|id| Ok(Uuid::parse_str(id)?)).collect()
+}
+
+#[async_trait]
+impl StorageBackend for RedisBackend {
+    async fn add_event(&self, event: Event) -> Result<()> {
+        self.add_events_batch(vec![event]).await?;
+        Ok(())
+    }
+
+    async fn add_events_batch(&self, events: Vec<Event>) -> Result<usize> {
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let mut latest: HashMap<Uuid, i64> = HashMap::new();
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for event in &events {
+            let timestamp_ms = event.timestamp.timestamp_millis();
+            latest
+                .entry(event.trace_id)
+                .and_modify(|ts| *ts = (*ts).max(timestamp_ms))
+                .or_insert(timestamp_ms);
+
+            let trace_id = event.trace_id.to_string();
+            pipe.rpush(
+                self.trace_key(event.trace_id),
+                serde_json::to_string(event)?,
+            )
+            .ignore()
+            .sadd(self.traces_key(), &trace_id)
+            .ignore()
+            .hset(self.event_traces_key(), event.id.to_string(), &trace_id)
+            .ignore();
+        }
+        for (trace_id, timestamp_ms) in latest {
+            // GT only ever moves a trace's score forward, whatever order batches land in
+            pipe.cmd("ZADD")
+                .arg(self.trace_index_key())
+                .arg("GT")
+                .arg(timestamp_ms)
+                .arg(trace_id.to_string())
+                .ignore();
+        }
+
+        let mut conn = self.conn.clone();
+        let _: () = pipe.query_async(&mut conn).await?;
+        Ok(events.len())
+    }
+
+    async fn get_event(&self, id: Uuid) -> Result<Option<Event>> {
+        let mut conn = self.conn.clone();
+        let trace_id: Option<String> = conn.hget(self.event_traces_key(), id.to_string()).await?;
+        let Some(trace_id) = trace_id else {
+            return Ok(None);
+        };
+
+        let events = self.get_trace_events(Uuid::parse_str(&trace_id)?).await?;
+        Ok(events.into_iter().find(|event| event.id == id))
+    }
+
+    async fn get_trace_events(&self, trace_id: Uuid) -> Result<Vec<Event>> {
+        let mut conn = self.conn.clone();
+        let values: Vec<String> = conn.lrange(self.trace_key(trace_id), 0, -1).await?;
+        let mut events = parse_events(&values)?;
+        sort_events(&mut events);
+        Ok(events)
+    }
+
+    async fn get_all_events(&self) -> Result<Vec<Event>> {
+        let trace_ids = self.get_all_trace_ids().await?;
+        if trace_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for trace_id in &trace_ids {
+            pipe.lrange(self.trace_key(*trace_id), 0, -1);
+        }
+
+        let mut conn = self.conn.clone();
+        let lists: Vec<Vec<String>> = pipe.query_async(&mut conn).await?;
+        let mut events = Vec::new();
+        for values in &lists {
+            events.extend(parse_events(values)?);
+        }
+        sort_events(&mut events);
+        Ok(events)
+    }
+
+    async fn count_events(&self) -> Result<usize> {
+        let mut conn = self.conn.clone();
+        Ok(conn.hlen(self.event_traces_key()).await?)
+    }
+
+    async fn count_traces(&self) -> Result<usize> {
+        let mut conn = self.conn.clone();
+        Ok(conn.scard(self.traces_key()).await?)
+    }
+
+    async fn get_all_trace_ids(&self) -> Result<Vec<Uuid>> {
+        let mut conn = self.conn.clone();
+        let ids: Vec<String> = conn.smembers(self.traces_key()).await?;
+        parse_trace_ids(&ids)
+    }
+
+    async fn get_trace_summaries(
+        &self,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        // The index only scores traces by their latest event, so a time window
+        // (which also bounds the first event) is served from a snapshot
+        if !window.is_unbounded() || !self.trace_index_complete().await? {
+            return self
+                .snapshot()
+                .await?
+                .get_trace_summaries(page, page_size, min_events, window)
+                .await;
+        }
+
+        let mut conn = self.conn.clone();
+        let offset = page.saturating_sub(1) * page_size;
+        let min_event_count = min_events.unwrap_or(1);
+
+        // Newest first; every indexed trace has at least one event
+        let (page_ids, total) = if min_event_count <= 1 {
+            let total: usize = conn.zcard(self.trace_index_key()).await?;
+            if page_size == 0 || offset >= total {
+                return Ok((Vec::new(), total));
+            }
+            let stop = (offset + page_size - 1) as isize;
+            let ids: Vec<String> = conn
+                .zrevrange(self.trace_index_key(), offset as isize, stop)
+                .await?;
+            (parse_trace_ids(&ids)?, total)
+        } else {
+            let ids: Vec<String> = conn.zrevrange(self.trace_index_key(), 0, -1).await?;
+            let ids = parse_trace_ids(&ids)?;
+            let mut pipe = redis::pipe();
+            for trace_id in &ids {
+                pipe.llen(self.trace_key(*trace_id));
+            }
+            let lengths: Vec<usize> = pipe.query_async(&mut conn).await?;
+            let matching: Vec<Uuid> = ids
+                .into_iter()
+                .zip(lengths)
+                .filter(|(_, length)| *length >= min_event_count)
+                .map(|(id, _)| id)
+                .collect();
+            let total = matching.len();
+            let ids = matching.into_iter().skip(offset).take(page_size).collect();
+            (ids, total)
+        };
+
+        let mut summaries = Vec::with_capacity(page_ids.len());
+        for trace_id in page_ids {
+            let events = self.get_trace_events(trace_id).await?;
+            summaries.extend(TraceSummary::from_events(trace_id, &events));
+        }
+
+        Ok((summaries, total))
+    }
+
+    async fn get_trace_summaries_by_service(
+        &self,
+        service_name: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.snapshot()
+            .await?
+            .get_trace_summaries_by_service(service_name, page, page_size)
+            .await
+    }
+
+    async fn get_trace_summaries_by_environment(
+        &self,
+        environment: &str,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.snapshot()
+            .await?
+            .get_trace_summaries_by_environment(environment, page, page_size, min_events, window)
+            .await
+    }
+
+    async fn get_trace_ids_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        self.snapshot()
+            .await?
+            .get_trace_ids_by_tag(key, value)
+            .await
+    }
+
+    async fn get_trace_roots(&self, trace_id: Uuid) -> Result<Vec<Uuid>> {
+        let events = self.get_trace_events(trace_id).await?;
+
+        // Find events with no parent_id
+        Ok(events
+            .into_iter()
+            .filter(|e| e.parent_id.is_none())
+            .map(|e| e.id)
+            .collect())
+    }
+
+    async fn save_baseline(&self, operation: &str, stats: DurationStats) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let _: () = conn
+            .hset(
+                self.baselines_key(),
+                operation,
+                serde_json::to_string(&stats)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn save_baselines_batch(&self, baselines: HashMap<String, DurationStats>) -> Result<()> {
+        if baselines.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        for (operation, stats) in &baselines {
+            pipe.hset(
+                self.baselines_key(),
+                operation,
+                serde_json::to_string(stats)?,
+            )
+            .ignore();
+        }
+
+        let mut conn = self.conn.clone();
+        let _: () = pipe.query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    async fn get_baseline_metric(&self, operation: &str) -> Result<Option<DurationStats>> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = conn.hget(self.baselines_key(), operation).await?;
+        value
+            .map(|value| Ok(serde_json::from_str(&value)?))
+            .transpose()
+    }
+
+    async fn get_all_baseline_operations(&self) -> Result<Vec<String>> {
+        let mut conn = self.conn.clone();
+        Ok(conn.hkeys(self.baselines_key()).await?)
+    }
+
+    async fn save_distributed_span(&self, span: DistributedSpan) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let _: () = conn
+            .hset(
+                self.spans_key(),
+                &span.span_id,
+                serde_json::to_string(&span)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_distributed_span(&self, span_id: &str) -> Result<Option<DistributedSpan>> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = conn.hget(self.spans_key(), span_id).await?;
+        value
+            .map(|value| Ok(serde_json::from_str(&value)?))
+            .transpose()
+    }
+
+    async fn get_distributed_spans(&self, trace_id: Uuid) -> Result<Vec<DistributedSpan>> {
+        Ok(self
+            .all_spans()
+            .await?
+            .into_iter()
+            .filter(|span| span.trace_id == trace_id)
+            .collect())
+    }
+
+    async fn add_distributed_edge(&self, edge: DistributedEdge) -> Result<()> {
+        // Stored globally; edges are matched to traces through their spans on
+        // read, so an edge may arrive before either of its spans
+        let mut conn = self.conn.clone();
+        let _: () = conn
+            .sadd(self.edges_key(), serde_json::to_string(&edge)?)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_distributed_edges(&self, trace_id: Uuid) -> Result<Vec<DistributedEdge>> {
+        let trace_spans: HashSet<String> = self
+            .get_distributed_spans(trace_id)
+            .await?
+            .into_iter()
+            .map(|span| span.span_id)
+            .collect();
+
+        Ok(self
+            .all_edges()
+            .await?
+            .into_iter()
+            .filter(|edge| {
+                trace_spans.contains(&edge.from_span) || trace_spans.contains(&edge.to_span)
+            })
+            .collect())
+    }
+
+    async fn list_distributed_edges(&self) -> Result<Vec<DistributedEdge>> {
+        self.all_edges().await
+    }
+
+    async fn get_all_services(&self) -> Result<Vec<(String, usize, usize)>> {
+        self.snapshot().await?.get_all_services().await
+    }
+
+    async fn get_service_tag_values(&self, tag: &str) -> Result<HashMap<String, String>> {
+        self.snapshot().await?.get_service_tag_values(tag).await
+    }
+
+    async fn get_service_event_durations(&self, service: &str) -> Result<Vec<u64>> {
+        self.snapshot()
+            .await?
+            .get_service_event_durations(service)
+            .await
+    }
+
+    async fn get_service_dependencies_global(
+        &self,
+        service_name: &str,
+    ) -> Result<(Vec<(String, usize, usize)>, Vec<(String, usize, usize)>)> {
+        self.snapshot()
+            .await?
+            .get_service_dependencies_global(service_name)
+            .await
+    }
+
+    async fn get_all_distributed_edges(&self) -> Result<Vec<serde_json::Value>> {
+        self.snapshot().await?.get_all_distributed_edges().await
+    }
+
+    async fn get_global_race_candidates(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.snapshot()
+            .await?
+            .get_global_race_candidates(environment)
+            .await
+    }
+
+    async fn get_top_service_calls(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.snapshot()
+            .await?
+            .get_top_service_calls(environment)
+            .await
+    }
+
+    async fn get_service_health(&self, time_window_minutes: u64) -> Result<Vec<serde_json::Value>> {
+        self.snapshot()
+            .await?
+            .get_service_health(time_window_minutes)
+            .await
+    }
+
+    async fn get_performance_metrics(
+        &self,
+        limit: usize,
+        scope: &MetricsScope,
+    ) -> Result<serde_json::Value> {
+        self.snapshot()
+            .await?
+            .get_performance_metrics(limit, scope)
+            .await
+    }
+
+    async fn get_throughput_buckets(
+        &self,
+        minutes: u64,
+        service: Option<&str>,
+    ) -> Result<Vec<ThroughputBucket>> {
+        self.snapshot()
+            .await?
+            .get_throughput_buckets(minutes, service)
+            .await
+    }
+
+    async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        let mut conn = self.conn.clone();
+        let _: String = redis::cmd("PING").query_async(&mut conn).await?;
+        Ok(start.elapsed())
+    }
+
+    async fn cleanup_old_traces(&self, retention_hours: u64) -> Result<usize> {
+        let cutoff_time = chrono::Utc::now() - chrono::Duration::hours(retention_hours as i64);
+        let mut deleted_count = 0;
+
+        for trace_id in self.get_all_trace_ids().await? {
+            let events = self.get_trace_events(trace_id).await?;
+            if events.is_empty() || events.iter().any(|e| e.timestamp > cutoff_time) {
+                continue;
+            }
+
+            let event_ids: Vec<String> = events.iter().map(|e| e.id.to_string()).collect();
+            let mut pipe = redis::pipe();
+            pipe.atomic()
+                .del(self.trace_key(trace_id))
+                .ignore()
+                .srem(self.traces_key(), trace_id.to_string())
+                .ignore()
+                .zrem(self.trace_index_key(), trace_id.to_string())
+                .ignore()
+                .hdel(self.event_traces_key(), event_ids)
+                .ignore();
+
+            let mut conn = self.conn.clone();
+            let _: () = pipe.query_async(&mut conn).await?;
+            deleted_count += 1;
+        }
+
+        Ok(deleted_count)
+    }
+
+    async fn maintenance(&self) -> Result<MaintenanceReport> {
+        let mut conn = self.conn.clone();
+        let mut rows_removed = 0;
+
+        // Trace ids whose event list is gone
+        let mut live_traces = HashSet::new();
+        for trace_id in self.get_all_trace_ids().await? {
+            let exists: bool = conn.exists(self.trace_key(trace_id)).await?;
+            if exists {
+                live_traces.insert(trace_id.to_string());
+            } else {
+                let _: () = conn.srem(self.traces_key(), trace_id.to_string()).await?;
+                rows_removed += 1;
+            }
+        }
+
+        // Index entries for removed traces, and live traces written before the index existed
+        let indexed: Vec<String> = conn.zrange(self.trace_index_key(), 0, -1).await?;
+        let indexed: HashSet<String> = indexed.into_iter().collect();
+        let stale_index: Vec<&String> = indexed
+            .iter()
+            .filter(|trace_id| !live_traces.contains(*trace_id))
+            .collect();
+        if !stale_index.is_empty() {
+            rows_removed += stale_index.len();
+            let _: () = conn.zrem(self.trace_index_key(), stale_index).await?;
+        }
+        for trace_id in live_traces.iter().filter(|id| !indexed.contains(*id)) {
+            let events = self.get_trace_events(Uuid::parse_str(trace_id)?).await?;
+            if let Some(latest) = events.iter().map(|e| e.timestamp).max() {
+                let _: () = conn
+                    .zadd(self.trace_index_key(), trace_id, latest.timestamp_millis())
+                    .await?;
+            }
+        }
+
+        // Event -> trace entries pointing at traces that no longer exist
+        let event_traces: HashMap<String, String> = conn.hgetall(self.event_traces_key()).await?;
+        let stale: Vec<String> = event_traces
+            .into_iter()
+            .filter(|(_, trace_id)| !live_traces.contains(trace_id))
+            .map(|(event_id, _)| event_id)
+            .collect();
+        if !stale.is_empty() {
+            rows_removed += stale.len();
+            let _: () = conn.hdel(self.event_traces_key(), stale).await?;
+        }
+
+        Ok(MaintenanceReport {
+            steps: vec![
+                "prune stale trace ids".to_string(),
+                "rebuild trace index".to_string(),
+                "prune stale event index entries".to_string(),
+            ],
+            rows_removed,
+            reclaimed_bytes: None,
+            trace_count: live_traces.len(),
+        })
+    }
+
+    async fn clear(&self) -> Result<()> {
+        let mut keys: Vec<String> = self
+            .get_all_trace_ids()
+            .await?
+            .into_iter()
+            .map(|trace_id| self.trace_key(trace_id))
+            .collect();
+        keys.extend([
+            self.traces_key(),
+            self.trace_index_key(),
+            self.event_traces_key(),
+            self.baselines_key(),
+            self.spans_key(),
+            self.edges_key(),
+        ]);
+
+        let mut conn = self.conn.clone();
+        let _: () = conn.del(keys).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedisConfig;
+    use crate::event::{AccessType, EventKind, EventMetadata};
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn make_event(trace_id: Uuid, offset_ms: i64) -> Event {
+        Event {
+            id: Uuid::new_v4(),
+            trace_id,
+            parent_id: None,
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+                + chrono::Duration::milliseconds(offset_ms),
+            kind: EventKind::StateChange {
+                variable: "account.balance".to_string(),
+                old_value: Some(json!(100)),
+                new_value: json!(50),
+                location: "test.rs:1".to_string(),
+                access_type: AccessType::Write,
+            },
+            metadata: EventMetadata {
+                thread_id: "thread-1".to_string(),
+                process_id: 1,
+                service_name: "api".to_string(),
+                environment: "test".to_string(),
+                tags: HashMap::new(),
+                duration_ns: Some(1),
+                instance_id: None,
+                distributed_span_id: None,
+                upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
+            },
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
+    /// Backend under a fresh key prefix, or `None` when REDIS_TEST_URL is unset
+    async fn test_backend(test_name: &str) -> Result<Option<RedisBackend>> {
+        let url = match std::env::var("REDIS_TEST_URL") {
+            Ok(url) => url,
+            Err(_) => {
+                eprintln!("Skipping {} (set REDIS_TEST_URL to run)", test_name);
+                return Ok(None);
+            }
+        };
+
+        let storage_config = StorageConfig {
+            backend: "redis".to_string(),
+            redis: RedisConfig {
+                url: Some(url),
+                key_prefix: format!("raceway-test-{}", Uuid::new_v4()),
+            },
+            ..Default::default()
+        };
+
+        let backend = RedisBackend::new(&storage_config).await?;
+        backend.ping().await?;
+        Ok(Some(backend))
+    }
+
+    #[tokio::test]
+    async fn redis_backend_round_trips_events_and_trace_ids() -> Result<()> {
+        let Some(backend) = test_backend("redis_backend_round_trips_events_and_trace_ids").await?
+        else {
+            return Ok(());
+        };
+
+        let trace_a = Uuid::new_v4();
+        let trace_b = Uuid::new_v4();
+        let later = make_event(trace_a, 10);
+        let earlier = make_event(trace_a, 0);
+        let other = make_event(trace_b, 5);
+
+        backend.add_event(later.clone()).await?;
+        backend
+            .add_events_batch(vec![earlier.clone(), other.clone()])
+            .await?;
+
+        let mut trace_ids = backend.get_all_trace_ids().await?;
+        trace_ids.sort();
+        let mut expected = vec![trace_a, trace_b];
+        expected.sort();
+        assert_eq!(trace_ids, expected);
+        assert_eq!(backend.count_traces().await?, 2);
+        assert_eq!(backend.count_events().await?, 3);
+
+        let events = backend.get_trace_events(trace_a).await?;
+        let ids: Vec<Uuid> = events.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![earlier.id, later.id]);
+        assert_eq!(events[1].timestamp, later.timestamp);
+
+        let fetched = backend.get_event(other.id).await?.expect("event stored");
+        assert_eq!(fetched.trace_id, trace_b);
+
+        let (summaries, total) = backend
+            .get_trace_summaries(1, 10, None, TimeWindow::default())
+            .await?;
+        assert_eq!(total, 2);
+        assert_eq!(summaries.len(), 2);
+
+        backend.clear().await?;
+        assert_eq!(backend.count_traces().await?, 0);
+        assert!(backend.get_trace_events(trace_a).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redis_backend_pages_trace_summaries_newest_first() -> Result<()> {
+        let Some(backend) =
+            test_backend("redis_backend_pages_trace_summaries_newest_first").await?
+        else {
+            return Ok(());
+        };
+
+        let oldest = Uuid::new_v4();
+        let middle = Uuid::new_v4();
+        let newest = Uuid::new_v4();
+        backend
+            .add_events_batch(vec![
+                make_event(oldest, 0),
+                make_event(newest, 30),
+                make_event(middle, 20),
+                make_event(middle, 5),
+            ])
+            .await?;
+        // A late event for the oldest trace must not move it backwards
+        backend.add_event(make_event(oldest, 1)).await?;
+
+        let (first, total) = backend
+            .get_trace_summaries(1, 2, None, TimeWindow::default())
+            .await?;
+        assert_eq!(total, 3);
+        let ids: Vec<Uuid> = first.iter().map(|s| s.trace_id).collect();
+        assert_eq!(ids, vec![newest, middle]);
+        assert_eq!(first[1].event_count, 2);
+
+        let (second, _) = backend
+            .get_trace_summaries(2, 2, None, TimeWindow::default())
+            .await?;
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].trace_id, oldest);
+        assert_eq!(second[0].event_count, 2);
+
+        let (filtered, total) = backend
+            .get_trace_summaries(1, 10, Some(2), TimeWindow::default())
+            .await?;
+        assert_eq!(total, 2);
+        let ids: Vec<Uuid> = filtered.iter().map(|s| s.trace_id).collect();
+        assert_eq!(ids, vec![middle, oldest]);
+
+        backend.clear().await?;
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
-use super::types::{DurationStats, TraceSummary};
+use super::types::{
+    DurationStats, MaintenanceReport, MetricsScope, ThroughputBucket, TimeWindow, TraceSummary,
+};
 use crate::event::{DistributedEdge, DistributedSpan, Event};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Pure storage backend trait for CRUD operations only
@@ -47,12 +50,13 @@ pub trait StorageBackend: Send + Sync {
     /// Get all trace IDs in the system
     async fn get_all_trace_ids(&self) -> Result<Vec<Uuid>>;
 
-    /// Get paginated trace summaries with metadata
+    /// Get paginated trace summaries with metadata, for traces overlapping `window`
     async fn get_trace_summaries(
         &self,
         page: usize,
         page_size: usize,
         min_events: Option<usize>,
+        window: TimeWindow,
     ) -> Result<(Vec<TraceSummary>, usize)>;
 
     /// Get paginated trace summaries filtered by service name
@@ -63,9 +67,54 @@ pub trait StorageBackend: Send + Sync {
         page_size: usize,
     ) -> Result<(Vec<TraceSummary>, usize)>;
 
+    /// Get paginated trace summaries for traces with events in `environment`
+    /// that overlap `window`
+    async fn get_trace_summaries_by_environment(
+        &self,
+        environment: &str,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)>;
+
+    /// Get the IDs of traces with at least one event tagged `key=value`
+    async fn get_trace_ids_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>>;
+
     /// Get trace roots (entry point events) for a specific trace
     async fn get_trace_roots(&self, trace_id: Uuid) -> Result<Vec<Uuid>>;
 
+    /// Attach labels to a trace, replacing the value of any key it already has.
+    /// Backends that don't store trace tags reject this and report none on reads.
+    async fn add_trace_tags(
+        &self,
+        _trace_id: Uuid,
+        _tags: std::collections::HashMap<String, String>,
+    ) -> Result<()> {
+        anyhow::bail!("Trace tags are not supported by this storage backend")
+    }
+
+    /// Get the labels attached to a trace (empty when it has none)
+    async fn get_trace_tags(
+        &self,
+        _trace_id: Uuid,
+    ) -> Result<std::collections::HashMap<String, String>> {
+        Ok(std::collections::HashMap::new())
+    }
+
+    /// Get the IDs of traces labelled `key=value`
+    async fn get_trace_ids_by_trace_tag(
+        &self,
+        _key: &str,
+        _value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        Ok(std::collections::HashSet::new())
+    }
+
     // ========================================================================
     // Baseline Metrics (CRUD only)
     // ========================================================================
@@ -104,6 +153,9 @@ pub trait StorageBackend: Send + Sync {
     /// Get all distributed edges for a trace
     async fn get_distributed_edges(&self, trace_id: Uuid) -> Result<Vec<DistributedEdge>>;
 
+    /// Get every stored distributed edge, for rebuilding the graph on startup
+    async fn list_distributed_edges(&self) -> Result<Vec<DistributedEdge>>;
+
     // ========================================================================
     // Service Catalog & Dependencies (Phase 3 - Optimized Queries)
     // ========================================================================
@@ -112,6 +164,16 @@ pub trait StorageBackend: Send + Sync {
     /// Returns (service_name, event_count, trace_count) tuples
     async fn get_all_services(&self) -> Result<Vec<(String, usize, usize)>>;
 
+    /// Get the most recent value of metadata tag `tag` for each service
+    /// Services that never set the tag are omitted
+    async fn get_service_tag_values(
+        &self,
+        tag: &str,
+    ) -> Result<std::collections::HashMap<String, String>>;
+
+    /// Get the `duration_ns` of every event emitted by `service` that reports one
+    async fn get_service_event_durations(&self, service: &str) -> Result<Vec<u64>>;
+
     /// Get service dependencies for a specific service across all traces
     /// Returns (calls_to, called_by) where each is a Vec of (service_name, total_calls, trace_count)
     async fn get_service_dependencies_global(
@@ -128,29 +190,76 @@ pub trait StorageBackend: Send + Sync {
     async fn get_all_distributed_edges(&self) -> Result<Vec<serde_json::Value>>;
 
     /// Get global race candidates from cross_trace_index
-    /// Returns variables with concurrent access across traces
-    async fn get_global_race_candidates(&self) -> Result<Vec<serde_json::Value>>;
+    /// Returns variables with concurrent access across traces,
+    /// counting only events from `environment` when given
+    async fn get_global_race_candidates(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>>;
 
-    /// Get system hotspots - most accessed variables and busiest service calls
-    /// Returns (top_variables, top_service_calls)
-    async fn get_system_hotspots(&self)
-        -> Result<(Vec<serde_json::Value>, Vec<serde_json::Value>)>;
+    /// Get the busiest cross-service calls, counting only traces from
+    /// `environment` when given. The variable side of the hotspots comes from
+    /// the causal graph's variable index (`CausalGraph::variable_hotspots`).
+    async fn get_top_service_calls(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>>;
 
     /// Get service health metrics
     /// Returns health status for all services including last activity and trace counts
     async fn get_service_health(&self, time_window_minutes: u64) -> Result<Vec<serde_json::Value>>;
 
     /// Get performance metrics across the system
-    /// Returns aggregated performance data including latencies, throughput, and event metrics
-    async fn get_performance_metrics(&self, limit: usize) -> Result<serde_json::Value>;
+    /// Returns aggregated performance data including latencies, throughput, and event metrics,
+    /// computed only over events inside `scope`
+    async fn get_performance_metrics(
+        &self,
+        limit: usize,
+        scope: &MetricsScope,
+    ) -> Result<serde_json::Value>;
+
+    /// Event and distinct trace counts per minute over the last `minutes` minutes,
+    /// oldest first and including the current minute, optionally for one service.
+    /// Minutes without events are returned as empty buckets.
+    async fn get_throughput_buckets(
+        &self,
+        minutes: u64,
+        service: Option<&str>,
+    ) -> Result<Vec<ThroughputBucket>>;
 
     // ========================================================================
     // Maintenance
     // ========================================================================
 
+    /// Verify the backend is reachable and return the round trip of a trivial
+    /// query (used by health and readiness probes)
+    async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        self.count_traces().await?;
+        Ok(start.elapsed())
+    }
+
     /// Cleanup old traces beyond retention period
     async fn cleanup_old_traces(&self, retention_hours: u64) -> Result<usize>;
 
+    /// Drop traces the engine evicted from its causal graph (`max_graph_traces`).
+    /// Durable backends keep them, to be reloaded when queried; a backend that
+    /// only holds traces in memory deletes them so it doesn't grow without bound.
+    /// Returns how many traces were deleted.
+    async fn evict_traces(&self, _trace_ids: &[Uuid]) -> Result<usize> {
+        Ok(0)
+    }
+
+    /// Run backend-specific maintenance (vacuum, reindex, compaction)
+    /// Removes only stale bookkeeping, never events, so trace counts are unchanged
+    async fn maintenance(&self) -> Result<MaintenanceReport>;
+
     /// Clear all data (useful for testing)
     async fn clear(&self) -> Result<()>;
+
+    /// Persist anything held only in this process before it exits. Called
+    /// once on graceful shutdown, after baselines are saved.
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
 }
@@ -1,8 +1,8 @@
 use crate::event::Event;
 use crate::graph::{Anomaly, CriticalPath, ServiceDependencies, VariableAccess};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, DurationRound, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// Statistics for duration measurements
@@ -59,6 +59,8 @@ pub struct VariableAccessData {
     pub location: String,
     pub has_causal_link_to_previous: bool,
     pub is_race: bool,
+    #[serde(default)]
+    pub is_lost_update: bool,
 }
 
 /// Batch data fetch for trace analysis (includes ALL computed data in single query)
@@ -81,3 +83,121 @@ pub struct TraceSummary {
     pub services: Vec<String>,
     pub service_count: usize,
 }
+
+impl TraceSummary {
+    /// Summarize `trace_id` from its events (None when there are none)
+    pub fn from_events(trace_id: Uuid, events: &[Event]) -> Option<Self> {
+        let first_timestamp = events.iter().map(|e| e.timestamp).min()?;
+        let last_timestamp = events.iter().map(|e| e.timestamp).max()?;
+
+        let mut services: Vec<String> = events
+            .iter()
+            .map(|e| e.metadata.service_name.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        services.sort();
+
+        Some(Self {
+            trace_id,
+            event_count: events.len() as i64,
+            first_timestamp,
+            last_timestamp,
+            service_count: services.len(),
+            services,
+        })
+    }
+}
+
+/// Outcome of a `StorageBackend::maintenance()` pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    /// Maintenance steps that ran, in order (e.g. "VACUUM ANALYZE events")
+    pub steps: Vec<String>,
+    /// Stale bookkeeping rows/entries removed (never events)
+    pub rows_removed: usize,
+    /// Storage size reclaimed, when the backend can measure it
+    pub reclaimed_bytes: Option<i64>,
+    /// Traces stored after maintenance
+    pub trace_count: usize,
+}
+
+/// Events and traces seen during one minute of throughput history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThroughputBucket {
+    /// Start of the minute (RFC 3339, UTC)
+    pub minute: String,
+    pub events: usize,
+    /// Distinct traces with at least one event in this minute
+    pub traces: usize,
+}
+
+impl ThroughputBucket {
+    /// Start of the oldest of `minutes` one-minute buckets ending with the current minute
+    pub fn window_start(minutes: u64) -> DateTime<Utc> {
+        let now = Utc::now();
+        let current_minute = now
+            .duration_trunc(chrono::Duration::minutes(1))
+            .unwrap_or(now);
+        current_minute - chrono::Duration::minutes(minutes.saturating_sub(1) as i64)
+    }
+
+    pub fn new(minute: DateTime<Utc>, events: usize, traces: usize) -> Self {
+        Self {
+            minute: minute.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            events,
+            traces,
+        }
+    }
+}
+
+/// Time window a trace listing is restricted to. A trace matches when its
+/// span of events overlaps the window; an unset bound leaves that side open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeWindow {
+    /// True when neither bound is set (every trace matches)
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    /// Whether a trace spanning `first..=last` overlaps the window
+    pub fn overlaps(&self, first: DateTime<Utc>, last: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| last >= since)
+            && self.until.is_none_or(|until| first <= until)
+    }
+}
+
+/// Restricts which events feed the performance metrics aggregation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsScope {
+    /// Only include events newer than this many minutes
+    pub window_minutes: Option<u64>,
+    /// Only include events emitted by this service
+    pub service: Option<String>,
+}
+
+impl MetricsScope {
+    /// True when no filter is applied (the whole store is aggregated)
+    pub fn is_unscoped(&self) -> bool {
+        self.window_minutes.is_none() && self.service.is_none()
+    }
+
+    /// Earliest timestamp included by the window, if any
+    pub fn cutoff(&self) -> Option<DateTime<Utc>> {
+        self.window_minutes
+            .map(|minutes| Utc::now() - chrono::Duration::minutes(minutes as i64))
+    }
+
+    pub fn includes(&self, event: &Event, cutoff: Option<DateTime<Utc>>) -> bool {
+        cutoff.is_none_or(|cutoff| event.timestamp >= cutoff)
+            && self
+                .service
+                .as_deref()
+                .is_none_or(|service| event.metadata.service_name == service)
+    }
+}
@@ -0,0 +1,218 @@
+//! Per-request timing breakdown: where a trace's wall-clock time went.
+//!
+//! Non-root events are bucketed by kind:
+//!
+//! | Category   | Event kinds                                    |
+//! |------------|------------------------------------------------|
+//! | Database   | `DatabaseQuery`, `DatabaseResult`              |
+//! | Downstream | `HttpRequest`, `HttpResponse` (outbound calls) |
+//! | Locks      | `LockAcquire` (time spent waiting)             |
+//! | Compute    | everything else: the unaccounted remainder     |
+//!
+//! Root events are skipped because they span the whole request (an inbound
+//! `HttpRequest` root would otherwise count the entire trace as downstream).
+//! Compute is derived rather than summed so nested function calls are not
+//! double counted. If I/O overlaps (parallel calls) and exceeds the wall-clock
+//! duration, the total grows to the I/O sum so percentages still add to 100.
+
+use crate::event::{Event, EventKind};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeCategory {
+    Database,
+    Downstream,
+    Locks,
+    Compute,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryTiming {
+    pub category: TimeCategory,
+    pub duration_ms: f64,
+    pub percentage: f64,
+}
+
+/// Trace duration split by category, always listing all four categories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimingBreakdown {
+    pub total_duration_ms: f64,
+    pub categories: Vec<CategoryTiming>,
+}
+
+impl TimingBreakdown {
+    pub fn get(&self, category: TimeCategory) -> Option<&CategoryTiming> {
+        self.categories
+            .iter()
+            .find(|timing| timing.category == category)
+    }
+}
+
+/// Bucket a trace's duration by event-kind category (see module docs)
+pub fn timing_breakdown(events: &[Event]) -> TimingBreakdown {
+    let wall_clock_ms = wall_clock_ms(events);
+
+    let (mut database_ms, mut downstream_ms, mut locks_ms) = (0.0, 0.0, 0.0);
+    for event in events.iter().filter(|event| event.parent_id.is_some()) {
+        let duration = event_duration_ms(event);
+        match &event.kind {
+            EventKind::DatabaseQuery { .. } | EventKind::DatabaseResult { .. } => {
+                database_ms += duration
+            }
+            EventKind::HttpRequest { .. } | EventKind::HttpResponse { .. } => {
+                downstream_ms += duration
+            }
+            EventKind::LockAcquire { .. } => locks_ms += duration,
+            _ => {}
+        }
+    }
+
+    let io_ms = database_ms + downstream_ms + locks_ms;
+    let total_duration_ms = wall_clock_ms.max(io_ms);
+    let compute_ms = total_duration_ms - io_ms;
+
+    let categories = [
+        (TimeCategory::Database, database_ms),
+        (TimeCategory::Downstream, downstream_ms),
+        (TimeCategory::Locks, locks_ms),
+        (TimeCategory::Compute, compute_ms),
+    ]
+    .into_iter()
+    .map(|(category, duration_ms)| CategoryTiming {
+        category,
+        duration_ms,
+        percentage: if total_duration_ms > 0.0 {
+            duration_ms / total_duration_ms * 100.0
+        } else {
+            0.0
+        },
+    })
+    .collect();
+
+    TimingBreakdown {
+        total_duration_ms,
+        categories,
+    }
+}
+
+/// Duration from metadata, falling back to the kind's own duration field
+fn event_duration_ms(event: &Event) -> f64 {
+    if let Some(duration_ns) = event.metadata.duration_ns {
+        return duration_ns as f64 / 1_000_000.0;
+    }
+    match &event.kind {
+        EventKind::DatabaseQuery { duration_ms, .. }
+        | EventKind::HttpResponse { duration_ms, .. } => *duration_ms as f64,
+        _ => 0.0,
+    }
+}
+
+/// First event start to last event end
+fn wall_clock_ms(events: &[Event]) -> f64 {
+    let Some(start) = events.iter().map(|event| event.timestamp).min() else {
+        return 0.0;
+    };
+    let end = events
+        .iter()
+        .map(|event| {
+            let duration_ns = event.metadata.duration_ns.unwrap_or(0);
+            event.timestamp + Duration::nanoseconds(duration_ns.min(i64::MAX as u64) as i64)
+        })
+        .max()
+        .unwrap_or(start);
+
+    (end - start).num_microseconds().unwrap_or(0) as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventMetadata;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn event(parent_id: Option<Uuid>, offset_ms: i64, duration_ms: u64, kind: EventKind) -> Event {
+        Event {
+            id: Uuid::new_v4(),
+            trace_id: Uuid::nil(),
+            parent_id,
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+                + Duration::milliseconds(offset_ms),
+            kind,
+            metadata: EventMetadata {
+                thread_id: "main".into(),
+                process_id: 1,
+                service_name: "api".into(),
+                environment: "test".into(),
+                tags: HashMap::new(),
+                duration_ns: Some(duration_ms * 1_000_000),
+                instance_id: None,
+                distributed_span_id: None,
+                upstream_span_id: None,
+                monotonic_ns: None,
+                retry_of: None,
+                attempt: None,
+            },
+            causality_vector: Vec::new(),
+            lock_set: Vec::new(),
+        }
+    }
+
+    fn http_request(url: &str) -> EventKind {
+        EventKind::HttpRequest {
+            method: "GET".into(),
+            url: url.into(),
+            headers: HashMap::new(),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_breakdown_splits_db_downstream_and_compute() {
+        // 100ms inbound request: 30ms in the database, 50ms waiting on a
+        // downstream service, and the remaining 20ms in compute
+        let root = event(None, 0, 100, http_request("/checkout"));
+        let events = vec![
+            event(
+                Some(root.id),
+                5,
+                30,
+                EventKind::DatabaseQuery {
+                    query: "SELECT 1".into(),
+                    database: "orders".into(),
+                    duration_ms: 30,
+                },
+            ),
+            event(
+                Some(root.id),
+                40,
+                50,
+                http_request("http://payments/charge"),
+            ),
+            event(
+                Some(root.id),
+                92,
+                5,
+                EventKind::FunctionCall {
+                    function_name: "render".into(),
+                    module: "api".into(),
+                    args: serde_json::json!({}),
+                    file: "api.rs".into(),
+                    line: 1,
+                },
+            ),
+            root,
+        ];
+
+        let breakdown = timing_breakdown(&events);
+        let percentage = |category| breakdown.get(category).unwrap().percentage;
+
+        assert_eq!(breakdown.total_duration_ms, 100.0);
+        assert_eq!(percentage(TimeCategory::Database), 30.0);
+        assert_eq!(percentage(TimeCategory::Downstream), 50.0);
+        assert_eq!(percentage(TimeCategory::Locks), 0.0);
+        assert_eq!(percentage(TimeCategory::Compute), 20.0);
+    }
+}
@@ -139,24 +139,19 @@ async fn process_handler(
 
     // Call downstream service if specified
     if let Some(downstream_url) = &req.downstream {
-        if let Ok(prop_headers) = client.propagation_headers(None) {
-            let http_client = reqwest::Client::new();
-            let payload = serde_json::json!({
-                "payload": format!("{} → {}", SERVICE_NAME, req.payload),
-                "downstream": req.next_downstream
-            });
-
-            if let Ok(resp) = http_client
-                .post(downstream_url)
-                .json(&payload)
-                .header("traceparent", prop_headers.get("traceparent").unwrap())
-                .header("raceway-clock", prop_headers.get("raceway-clock").unwrap())
-                .send()
-                .await
-            {
-                if let Ok(json) = resp.json::<serde_json::Value>().await {
-                    downstream_response = Some(json);
-                }
+        let http_client = reqwest::Client::new();
+        let payload = serde_json::json!({
+            "payload": format!("{} → {}", SERVICE_NAME, req.payload),
+            "downstream": req.next_downstream
+        });
+
+        if let Ok(resp) = client
+            .traced_request(http_client.post(downstream_url).json(&payload))
+            .send()
+            .await
+        {
+            if let Ok(json) = resp.json::<serde_json::Value>().await {
+                downstream_response = Some(json);
             }
         }
     }
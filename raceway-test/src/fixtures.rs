@@ -30,6 +30,9 @@ pub fn sample_trace_fixture() -> TraceFixture {
         instance_id: None,
         distributed_span_id: None,
         upstream_span_id: None,
+        monotonic_ns: None,
+        retry_of: None,
+        attempt: None,
     };
 
     let metadata_a = EventMetadata {
@@ -42,6 +45,9 @@ pub fn sample_trace_fixture() -> TraceFixture {
         instance_id: None,
         distributed_span_id: None,
         upstream_span_id: None,
+        monotonic_ns: None,
+        retry_of: None,
+        attempt: None,
     };
 
     let metadata_b = EventMetadata {
@@ -54,6 +60,9 @@ pub fn sample_trace_fixture() -> TraceFixture {
         instance_id: None,
         distributed_span_id: None,
         upstream_span_id: None,
+        monotonic_ns: None,
+        retry_of: None,
+        attempt: None,
     };
 
     let metadata_finish = EventMetadata {
@@ -66,6 +75,9 @@ pub fn sample_trace_fixture() -> TraceFixture {
         instance_id: None,
         distributed_span_id: None,
         upstream_span_id: None,
+        monotonic_ns: None,
+        retry_of: None,
+        attempt: None,
     };
 
     let root = Event {
@@ -143,3 +155,9 @@ pub fn sample_trace_fixture() -> TraceFixture {
         expected_critical_path_nodes: 1,
     }
 }
+
+/// A three-span Jaeger UI export: a frontend request with one same-service
+/// child and one child in the payments service.
+pub fn jaeger_export_fixture() -> &'static str {
+    include_str!("../fixtures/jaeger_trace.json")
+}
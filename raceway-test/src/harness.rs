@@ -1,30 +1,64 @@
 use anyhow::{Context, Result};
-use axum::{body::Body, http::Request, Router};
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, Request, StatusCode},
+    Router,
+};
 use http_body_util::BodyExt;
-use raceway::server::{build_router, init_engine};
-use raceway_core::{Config, RacewayEngine};
+use raceway::server::{build_router, init_engine, init_engine_with_storage};
+use raceway_core::{Config, RacewayEngine, StorageBackend};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower::ServiceExt;
 
+fn test_config(mut config: Config) -> Config {
+    config.server.cors_enabled = false;
+    config.server.verbose = false;
+    config.engine.flush_interval_ms = 10;
+    config
+}
+
 pub struct TestApp {
     router: Router,
-    _engine: Arc<RacewayEngine>,
+    engine: Arc<RacewayEngine>,
 }
 
 impl TestApp {
     pub async fn new(mut config: Config) -> Result<Self> {
-        config.server.cors_enabled = false;
-        config.server.verbose = false;
         config.storage.backend = "memory".into();
-        config.engine.flush_interval_ms = 10;
+        let config = test_config(config);
 
         let engine = init_engine(&config).await?;
         let router = build_router(&config, Arc::clone(&engine));
 
-        Ok(Self {
-            router,
-            _engine: engine,
-        })
+        Ok(Self { router, engine })
+    }
+
+    /// Like `new`, but serving from the given storage backend
+    pub async fn with_storage(config: Config, storage: Arc<dyn StorageBackend>) -> Result<Self> {
+        let config = test_config(config);
+
+        let engine = init_engine_with_storage(&config, storage).await?;
+        let router = build_router(&config, Arc::clone(&engine));
+
+        Ok(Self { router, engine })
+    }
+
+    pub fn engine(&self) -> Arc<RacewayEngine> {
+        Arc::clone(&self.engine)
+    }
+
+    /// Serve the router on an ephemeral localhost port, for clients such as
+    /// WebSockets that need a real connection. The server runs until the test ends.
+    pub async fn serve(&self) -> Result<SocketAddr> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let service = self
+            .router
+            .clone()
+            .into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, service).await });
+        Ok(addr)
     }
 
     pub async fn post_json(
@@ -42,6 +76,80 @@ impl TestApp {
         self.execute(request).await
     }
 
+    /// POST a raw body with the given content type and return the status code
+    /// alongside the JSON response.
+    pub async fn post_raw(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: impl Into<Body>,
+    ) -> Result<(StatusCode, serde_json::Value)> {
+        let request = Request::builder()
+            .method("POST")
+            .uri(path)
+            .header("content-type", content_type)
+            .body(body.into())?;
+
+        self.execute_with_status(request).await
+    }
+
+    /// POST a JSON body sent with `Content-Encoding: content_encoding` (already
+    /// encoded by the caller) and return the status code with the raw response
+    pub async fn post_encoded(
+        &self,
+        path: &str,
+        content_encoding: &str,
+        body: impl Into<Body>,
+    ) -> Result<(StatusCode, Bytes)> {
+        let request = Request::builder()
+            .method("POST")
+            .uri(path)
+            .header("content-type", "application/json")
+            .header("content-encoding", content_encoding)
+            .body(body.into())?;
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .context("router execution failed")?;
+
+        let status = response.status();
+        Ok((status, response.into_body().collect().await?.to_bytes()))
+    }
+
+    /// POST a raw body asking for an NDJSON response, and return the status
+    /// code with the response split into one JSON value per line
+    pub async fn post_raw_ndjson(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: impl Into<Body>,
+    ) -> Result<(StatusCode, Vec<serde_json::Value>)> {
+        let request = Request::builder()
+            .method("POST")
+            .uri(path)
+            .header("content-type", content_type)
+            .header("accept", "application/x-ndjson")
+            .body(body.into())?;
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .context("router execution failed")?;
+
+        let status = response.status();
+        let bytes = response.into_body().collect().await?.to_bytes();
+        let lines = std::str::from_utf8(&bytes)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        Ok((status, lines))
+    }
+
     pub async fn get_json(&self, path: &str) -> Result<serde_json::Value> {
         let request = Request::builder()
             .method("GET")
@@ -51,6 +159,83 @@ impl TestApp {
         self.execute(request).await
     }
 
+    /// GET a path and return the status code alongside the body, without
+    /// treating non-2xx responses as errors.
+    pub async fn get_with_status(&self, path: &str) -> Result<(StatusCode, serde_json::Value)> {
+        let request = Request::builder()
+            .method("GET")
+            .uri(path)
+            .body(Body::empty())?;
+
+        self.execute_with_status(request).await
+    }
+
+    /// GET a path and return the status code and headers alongside the body,
+    /// without treating non-2xx responses as errors.
+    pub async fn get_with_headers(
+        &self,
+        path: &str,
+    ) -> Result<(StatusCode, HeaderMap, serde_json::Value)> {
+        let request = Request::builder()
+            .method("GET")
+            .uri(path)
+            .body(Body::empty())?;
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .context("router execution failed")?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.into_body().collect().await?.to_bytes();
+        Ok((status, headers, serde_json::from_slice(&bytes)?))
+    }
+
+    /// GET a path and return the status code with the body as text, for
+    /// endpoints that don't respond with JSON
+    pub async fn get_text(&self, path: &str) -> Result<(StatusCode, String)> {
+        let (status, body) = self.get_stream(path).await?;
+        let bytes = body.collect().await?.to_bytes();
+        Ok((status, String::from_utf8(bytes.to_vec())?))
+    }
+
+    /// GET a path and return the status code with the body left unread, for
+    /// streaming responses that only end when the client hangs up
+    pub async fn get_stream(&self, path: &str) -> Result<(StatusCode, Body)> {
+        let request = Request::builder()
+            .method("GET")
+            .uri(path)
+            .body(Body::empty())?;
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .context("router execution failed")?;
+
+        Ok((response.status(), response.into_body()))
+    }
+
+    async fn execute_with_status(
+        &self,
+        request: Request<Body>,
+    ) -> Result<(StatusCode, serde_json::Value)> {
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .context("router execution failed")?;
+
+        let status = response.status();
+        let bytes = response.into_body().collect().await?.to_bytes();
+        Ok((status, serde_json::from_slice(&bytes)?))
+    }
+
     async fn execute(&self, request: Request<Body>) -> Result<serde_json::Value> {
         let response = self
             .router
@@ -1,8 +1,27 @@
-use anyhow::Result;
-use raceway_core::Config;
-use raceway_test::{fixtures::sample_trace_fixture, harness::TestApp};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use axum::body::{Body, Bytes};
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use raceway::import::{parse_events, ImportFormat};
+use raceway::offline;
+use raceway_core::bundle::BundleAnnotations;
+use raceway_core::event::{DistributedEdge, DistributedSpan};
+use raceway_core::storage::{
+    DurationStats, MaintenanceReport, MemoryBackend, MetricsScope, ThroughputBucket, TimeWindow,
+    TraceSummary,
+};
+use raceway_core::{Config, Event, StorageBackend};
+use raceway_test::{
+    fixtures::{jaeger_export_fixture, sample_trace_fixture, TraceFixture},
+    harness::TestApp,
+};
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::time::{sleep, Duration};
+use tower::ServiceExt;
+use uuid::Uuid;
 
 async fn wait_for_trace(app: &TestApp, trace_id: String, expected_events: usize) -> Result<()> {
     for _ in 0..40 {
@@ -19,6 +38,25 @@ async fn wait_for_trace(app: &TestApp, trace_id: String, expected_events: usize)
     Err(anyhow::anyhow!("trace did not reach expected event count"))
 }
 
+/// Wait until storage holds `expected_events` for a trace, whether or not the
+/// graph has linked them yet
+async fn wait_for_stored(
+    app: &TestApp,
+    trace_id: uuid::Uuid,
+    expected_events: usize,
+) -> Result<()> {
+    for _ in 0..40 {
+        let events = app.engine().storage().get_trace_events(trace_id).await?;
+        if events.len() == expected_events {
+            return Ok(());
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    Err(anyhow::anyhow!(
+        "trace did not reach expected stored event count"
+    ))
+}
+
 // ─── GET /api/traces Tests ──────────────────────────────────────────────────
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
@@ -178,6 +216,762 @@ async fn test_api_trace_get_with_analysis() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_trace_get_filters_and_pages_events() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, fixture.trace_id.to_string(), 4).await?;
+
+    let trace = app
+        .get_json(&format!(
+            "/api/traces/{}?event_kind=StateChange",
+            fixture.trace_id
+        ))
+        .await?;
+    let data = &trace["data"];
+
+    let events = data["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events
+        .iter()
+        .all(|event| event["kind"].get("StateChange").is_some()));
+
+    // Analysis still covers the whole trace
+    assert_eq!(data["total_events"], 4);
+    assert_eq!(
+        data["analysis"]["race_details"].as_array().unwrap().len(),
+        fixture.expected_races
+    );
+
+    let page = app
+        .get_json(&format!(
+            "/api/traces/{}?event_kind=StateChange&event_offset=1&event_limit=5",
+            fixture.trace_id
+        ))
+        .await?;
+    let paged = page["data"]["events"].as_array().unwrap();
+    assert_eq!(paged.len(), 1);
+    assert_eq!(paged[0]["id"], events[1]["id"]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_trace_lineage_orders_attempts() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let original = sample_trace_fixture();
+
+    // The retry replays the same work as a new trace linked to the original
+    let retry_trace_id = uuid::Uuid::new_v4();
+    let mut retry_events = original.events.clone();
+    let id_map: std::collections::HashMap<_, _> = retry_events
+        .iter()
+        .map(|event| (event.id, uuid::Uuid::new_v4()))
+        .collect();
+    for event in &mut retry_events {
+        event.id = id_map[&event.id];
+        event.parent_id = event.parent_id.map(|parent| id_map[&parent]);
+        event.trace_id = retry_trace_id;
+        event.metadata.retry_of = Some(original.trace_id);
+        event.metadata.attempt = Some(2);
+    }
+
+    app.post_json("/events", json!({ "events": original.events }))
+        .await?;
+    app.post_json("/events", json!({ "events": retry_events }))
+        .await?;
+    wait_for_trace(&app, original.trace_id.to_string(), 4).await?;
+    wait_for_trace(&app, retry_trace_id.to_string(), 4).await?;
+
+    for trace_id in [original.trace_id, retry_trace_id] {
+        let lineage = app
+            .get_json(&format!("/api/traces/{}/lineage", trace_id))
+            .await?;
+        let data = &lineage["data"];
+        let attempts = data["attempts"].as_array().unwrap();
+
+        assert_eq!(data["original_trace_id"], original.trace_id.to_string());
+        assert_eq!(data["attempt_count"], 2);
+        assert_eq!(attempts[0]["trace_id"], original.trace_id.to_string());
+        assert_eq!(attempts[0]["attempt"], 1);
+        assert_eq!(attempts[1]["trace_id"], retry_trace_id.to_string());
+        assert_eq!(attempts[1]["attempt"], 2);
+        assert_eq!(attempts[1]["retry_of"], original.trace_id.to_string());
+        assert_eq!(attempts[0]["event_count"], 4);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_explain_reports_race_reasoning() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let trace_id = fixture.trace_id.to_string();
+    let (write_a, write_b) = (fixture.events[1].id, fixture.events[2].id);
+
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, trace_id.clone(), 4).await?;
+
+    let explanation = app
+        .get_json(&format!(
+            "/api/traces/{}/explain?a={}&b={}",
+            trace_id, write_a, write_b
+        ))
+        .await?;
+    let data = &explanation["data"];
+    assert_eq!(data["race"], true);
+    assert_eq!(data["concurrent"], true);
+    assert_eq!(data["shared_locks"].as_array().unwrap().len(), 0);
+    assert_eq!(
+        data["reason"],
+        "No shared lock and concurrent vector clocks"
+    );
+
+    let (status, _) = app
+        .get_with_status(&format!("/api/traces/{}/explain?a={}", trace_id, write_a))
+        .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_happens_before_orders_event_pairs() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let trace_id = fixture.trace_id.to_string();
+    let root = fixture.events[0].id;
+    let (write_a, write_b) = (fixture.events[1].id, fixture.events[2].id);
+
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, trace_id.clone(), 4).await?;
+
+    let ordering = |a: Uuid, b: Uuid| {
+        let app = &app;
+        let trace_id = &trace_id;
+        async move {
+            app.get_json(&format!(
+                "/api/traces/{}/happens-before?a={}&b={}",
+                trace_id, a, b
+            ))
+            .await
+            .map(|response| response["data"].clone())
+        }
+    };
+
+    // The root is an ancestor of both writes
+    let data = ordering(root, write_a).await?;
+    assert_eq!(
+        data,
+        json!({ "a_before_b": true, "b_before_a": false, "concurrent": false })
+    );
+
+    let data = ordering(write_a, root).await?;
+    assert_eq!(
+        data,
+        json!({ "a_before_b": false, "b_before_a": true, "concurrent": false })
+    );
+
+    // Sibling writes on different threads
+    let data = ordering(write_a, write_b).await?;
+    assert_eq!(
+        data,
+        json!({ "a_before_b": false, "b_before_a": false, "concurrent": true })
+    );
+
+    let (status, _) = app
+        .get_with_status(&format!(
+            "/api/traces/{}/happens-before?a={}",
+            trace_id, write_a
+        ))
+        .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app
+        .get_with_status(&format!(
+            "/api/traces/{}/happens-before?a={}&b={}",
+            trace_id,
+            write_a,
+            Uuid::new_v4()
+        ))
+        .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_dependencies_by_instance_split_service_instances() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let mut fixture = sample_trace_fixture();
+    let trace_id = fixture.trace_id.to_string();
+
+    // Both writes are children of the root and run on two instances of one service
+    for (event, instance) in fixture.events[1..3]
+        .iter_mut()
+        .zip(["ledger-1", "ledger-2"])
+    {
+        event.metadata.service_name = "ledger".into();
+        event.metadata.instance_id = Some(instance.into());
+    }
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, trace_id.clone(), 4).await?;
+
+    let service_names = |deps: &serde_json::Value| {
+        let mut names: Vec<String> = deps["data"]["services"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|service| service["name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    let deps = app
+        .get_json(&format!("/api/traces/{}/dependencies", trace_id))
+        .await?;
+    assert_eq!(service_names(&deps), ["ledger", "web"]);
+    let calls = deps["data"]["dependencies"].as_array().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0]["call_count"], 2);
+
+    let deps = app
+        .get_json(&format!(
+            "/api/traces/{}/dependencies?by_instance=true",
+            trace_id
+        ))
+        .await?;
+    assert_eq!(
+        service_names(&deps),
+        ["ledger#ledger-1", "ledger#ledger-2", "web"]
+    );
+    let calls = deps["data"]["dependencies"].as_array().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert!(calls
+        .iter()
+        .all(|call| call["from"] == "web" && call["call_count"] == 1));
+
+    Ok(())
+}
+
+/// The sample trace reparented into a single chain: root → a → b → finish
+fn linear_trace_fixture() -> TraceFixture {
+    let mut fixture = sample_trace_fixture();
+    for i in 2..fixture.events.len() {
+        fixture.events[i].parent_id = Some(fixture.events[i - 1].id);
+    }
+    fixture
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_causal_path_follows_linear_trace() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = linear_trace_fixture();
+    let trace_id = fixture.trace_id.to_string();
+    let ids: Vec<String> = fixture.events.iter().map(|e| e.id.to_string()).collect();
+
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, trace_id.clone(), 4).await?;
+
+    let path = app
+        .get_json(&format!(
+            "/api/traces/{}/path?from={}&to={}",
+            trace_id, ids[0], ids[3]
+        ))
+        .await?;
+    let chain: Vec<&str> = path["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(chain, ids);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_causal_path_not_found_against_causality() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = linear_trace_fixture();
+    let trace_id = fixture.trace_id.to_string();
+    let (first, last) = (fixture.events[0].id, fixture.events[3].id);
+
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, trace_id.clone(), 4).await?;
+
+    let (status, body) = app
+        .get_with_status(&format!(
+            "/api/traces/{}/path?from={}&to={}",
+            trace_id, last, first
+        ))
+        .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        body["error"],
+        format!("No causal path found between {} and {}", last, first)
+    );
+
+    let (status, _) = app
+        .get_with_status(&format!(
+            "/api/traces/{}/path?from={}&to={}",
+            trace_id,
+            first,
+            Uuid::new_v4()
+        ))
+        .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+/// Give a fixture fresh trace and event IDs so several copies can coexist
+fn relabeled_fixture(mut fixture: TraceFixture) -> TraceFixture {
+    let ids: HashMap<Uuid, Uuid> = fixture
+        .events
+        .iter()
+        .map(|event| (event.id, Uuid::new_v4()))
+        .collect();
+    fixture.trace_id = Uuid::new_v4();
+    for event in &mut fixture.events {
+        event.id = ids[&event.id];
+        event.trace_id = fixture.trace_id;
+        event.parent_id = event.parent_id.map(|parent| ids[&parent]);
+    }
+    fixture
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_analyze_batch_summarizes_each_trace() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixtures = [
+        sample_trace_fixture(),
+        relabeled_fixture(sample_trace_fixture()),
+        relabeled_fixture(linear_trace_fixture()),
+    ];
+
+    for fixture in &fixtures {
+        app.post_json("/events", json!({ "events": fixture.events }))
+            .await?;
+        wait_for_trace(&app, fixture.trace_id.to_string(), 4).await?;
+    }
+
+    let trace_ids: Vec<String> = fixtures.iter().map(|f| f.trace_id.to_string()).collect();
+    let batch = app
+        .post_json(
+            "/api/traces/analyze-batch",
+            json!({ "trace_ids": trace_ids }),
+        )
+        .await?;
+    let summaries = batch["data"].as_object().unwrap();
+    assert_eq!(summaries.len(), 3);
+
+    for (trace_id, expected_races) in trace_ids.iter().zip([1, 1, 0]) {
+        let summary = &summaries[trace_id];
+        assert_eq!(summary["potential_races"], expected_races, "{}", trace_id);
+        assert_eq!(summary["concurrent_events"], expected_races, "{}", trace_id);
+
+        let anomalies = app
+            .get_json(&format!("/api/traces/{}/anomalies", trace_id))
+            .await?;
+        assert_eq!(
+            summary["anomaly_count"], anomalies["data"]["anomaly_count"],
+            "{}",
+            trace_id
+        );
+    }
+
+    let (status, _) = app
+        .post_raw(
+            "/api/traces/analyze-batch",
+            "application/json",
+            json!({ "trace_ids": ["not-a-uuid"] }).to_string(),
+        )
+        .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_cursor_sync_returns_only_new_items() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let trace_id = fixture.trace_id.to_string();
+
+    // First half of the trace
+    app.post_json("/events", json!({ "events": fixture.events[..2] }))
+        .await?;
+    wait_for_trace(&app, trace_id.clone(), 2).await?;
+
+    let traces = app.get_json("/api/traces?after=").await?;
+    let listed = traces["data"]["traces"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["trace_id"], trace_id);
+    let trace_cursor = traces["data"]["next_cursor"].as_str().unwrap().to_string();
+
+    let page = app
+        .get_json(&format!("/api/traces/{}/events?after=", trace_id))
+        .await?;
+    assert_eq!(page["data"]["events"].as_array().unwrap().len(), 2);
+    let event_cursor = page["data"]["next_cursor"].as_str().unwrap().to_string();
+
+    // Rest of the trace, plus a second trace starting an hour later
+    let mut later = fixture.events.clone();
+    let later_trace_id = uuid::Uuid::new_v4();
+    for event in &mut later {
+        event.id = uuid::Uuid::new_v4();
+        event.parent_id = None;
+        event.trace_id = later_trace_id;
+        event.timestamp += chrono::Duration::hours(1);
+    }
+    app.post_json("/events", json!({ "events": fixture.events[2..] }))
+        .await?;
+    app.post_json("/events", json!({ "events": later })).await?;
+    wait_for_trace(&app, trace_id.clone(), 4).await?;
+    wait_for_trace(&app, later_trace_id.to_string(), 4).await?;
+
+    let traces = app
+        .get_json(&format!("/api/traces?after={}", trace_cursor))
+        .await?;
+    let listed = traces["data"]["traces"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["trace_id"], later_trace_id.to_string());
+
+    let page = app
+        .get_json(&format!(
+            "/api/traces/{}/events?after={}",
+            trace_id, event_cursor
+        ))
+        .await?;
+    let ids: Vec<String> = page["data"]["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["id"].as_str().unwrap().to_string())
+        .collect();
+    let expected: Vec<String> = fixture.events[2..]
+        .iter()
+        .map(|event| event.id.to_string())
+        .collect();
+    assert_eq!(ids, expected);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_environment_filter_scopes_trace_listing() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let prod_trace_id = fixture.trace_id.to_string();
+
+    let mut prod = fixture.events.clone();
+    for event in &mut prod {
+        event.metadata.environment = "prod".into();
+    }
+    let mut dev = fixture.events.clone();
+    let dev_trace_id = uuid::Uuid::new_v4();
+    for event in &mut dev {
+        event.id = uuid::Uuid::new_v4();
+        event.parent_id = None;
+        event.trace_id = dev_trace_id;
+        event.metadata.environment = "dev".into();
+    }
+    app.post_json("/events", json!({ "events": prod })).await?;
+    app.post_json("/events", json!({ "events": dev })).await?;
+    wait_for_trace(&app, prod_trace_id.clone(), 4).await?;
+    wait_for_trace(&app, dev_trace_id.to_string(), 4).await?;
+
+    let traces = app.get_json("/api/traces?environment=prod").await?;
+    assert_eq!(traces["data"]["total_traces"], 1);
+    let listed = traces["data"]["traces"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["trace_id"], prod_trace_id);
+
+    let traces = app.get_json("/api/traces?environment=all").await?;
+    assert_eq!(traces["data"]["total_traces"], 2);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_tag_filter_scopes_trace_listing() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let tagged_trace_id = fixture.trace_id.to_string();
+
+    // Only one event of the tagged trace carries the tag
+    let mut tagged = fixture.events.clone();
+    tagged[1]
+        .metadata
+        .tags
+        .insert("region".into(), "eu-west:1".into());
+    let mut untagged = fixture.events.clone();
+    let untagged_trace_id = uuid::Uuid::new_v4();
+    for event in &mut untagged {
+        event.id = uuid::Uuid::new_v4();
+        event.parent_id = None;
+        event.trace_id = untagged_trace_id;
+    }
+    untagged[0]
+        .metadata
+        .tags
+        .insert("region".into(), "us-east:1".into());
+    app.post_json("/events", json!({ "events": tagged }))
+        .await?;
+    app.post_json("/events", json!({ "events": untagged }))
+        .await?;
+    wait_for_trace(&app, tagged_trace_id.clone(), 4).await?;
+    wait_for_trace(&app, untagged_trace_id.to_string(), 4).await?;
+
+    let traces = app.get_json("/api/traces?tag=region:eu-west%3A1").await?;
+    assert_eq!(traces["data"]["total_traces"], 1);
+    let listed = traces["data"]["traces"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["trace_id"], tagged_trace_id);
+
+    let traces = app.get_json("/api/traces?tag=user_id:42").await?;
+    assert_eq!(traces["data"]["total_traces"], 0);
+
+    let traces = app.get_json("/api/traces").await?;
+    assert_eq!(traces["data"]["total_traces"], 2);
+
+    let (status, _) = app.get_with_status("/api/traces?tag=region").await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_trace_tags_label_and_find_traces() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let trace_id = fixture.trace_id.to_string();
+
+    let mut other = fixture.events.clone();
+    let other_trace_id = uuid::Uuid::new_v4();
+    for event in &mut other {
+        event.id = uuid::Uuid::new_v4();
+        event.parent_id = None;
+        event.trace_id = other_trace_id;
+    }
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    app.post_json("/events", json!({ "events": other })).await?;
+    wait_for_trace(&app, trace_id.clone(), 4).await?;
+    wait_for_trace(&app, other_trace_id.to_string(), 4).await?;
+
+    let tagged = app
+        .post_json(
+            &format!("/api/traces/{}/tags", trace_id),
+            json!({ "tags": { "bug": "checkout-repro", "owner": "alice" } }),
+        )
+        .await?;
+    assert_eq!(tagged["success"], true);
+    assert_eq!(tagged["data"]["tags"]["bug"], "checkout-repro");
+
+    // Tagging again overwrites the key and keeps the others
+    let tagged = app
+        .post_json(
+            &format!("/api/traces/{}/tags", trace_id),
+            json!({ "tags": { "owner": "bob" } }),
+        )
+        .await?;
+    assert_eq!(tagged["data"]["tags"]["owner"], "bob");
+    assert_eq!(tagged["data"]["tags"]["bug"], "checkout-repro");
+
+    let traces = app
+        .get_json("/api/traces?trace_tag=bug:checkout-repro")
+        .await?;
+    assert_eq!(traces["data"]["total_traces"], 1);
+    let listed = traces["data"]["traces"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["trace_id"], trace_id);
+    assert_eq!(listed[0]["trace_tags"]["owner"], "bob");
+
+    let traces = app.get_json("/api/traces?trace_tag=owner:alice").await?;
+    assert_eq!(traces["data"]["total_traces"], 0);
+
+    // Untagged traces list without a `trace_tags` field
+    let traces = app.get_json("/api/traces").await?;
+    assert_eq!(traces["data"]["total_traces"], 2);
+    let untagged = traces["data"]["traces"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|trace| trace["trace_id"] == other_trace_id.to_string())
+        .unwrap();
+    assert!(untagged.get("trace_tags").is_none());
+
+    let (status, _) = app
+        .post_raw(
+            &format!("/api/traces/{}/tags", uuid::Uuid::new_v4()),
+            "application/json",
+            r#"{"tags":{"bug":"x"}}"#,
+        )
+        .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .post_raw(
+            &format!("/api/traces/{}/tags", trace_id),
+            "application/json",
+            r#"{"tags":{"bug:id":"x"}}"#,
+        )
+        .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_time_window_scopes_trace_listing() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    // The fixture starts at 2024-01-01T12:00:00Z; shift copies of it around
+    // a 12:00-12:30 window.
+    let shifted = |offset_minutes: i64| {
+        let trace_id = Uuid::new_v4();
+        let mut events = fixture.events.clone();
+        for event in &mut events {
+            event.id = Uuid::new_v4();
+            event.parent_id = None;
+            event.trace_id = trace_id;
+            event.timestamp += chrono::Duration::minutes(offset_minutes);
+        }
+        (trace_id, events)
+    };
+    let (inside_id, inside) = shifted(10);
+    let (outside_id, outside) = shifted(-60);
+    // Starts before the window and finishes inside it
+    let (straddling_id, mut straddling) = shifted(0);
+    straddling[0].timestamp -= chrono::Duration::minutes(5);
+
+    for (trace_id, events) in [
+        (inside_id, inside),
+        (outside_id, outside),
+        (straddling_id, straddling),
+    ] {
+        app.post_json("/events", json!({ "events": events }))
+            .await?;
+        wait_for_trace(&app, trace_id.to_string(), 4).await?;
+    }
+
+    let traces = app
+        .get_json("/api/traces?since=2024-01-01T12:00:00Z&until=2024-01-01T12:30:00Z")
+        .await?;
+    assert_eq!(traces["data"]["total_traces"], 2);
+    let listed: Vec<String> = traces["data"]["traces"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|trace| trace["trace_id"].as_str().unwrap().to_string())
+        .collect();
+    assert!(listed.contains(&inside_id.to_string()));
+    assert!(listed.contains(&straddling_id.to_string()));
+    assert!(!listed.contains(&outside_id.to_string()));
+
+    let traces = app
+        .get_json("/api/traces?until=2024-01-01T11:30:00Z")
+        .await?;
+    assert_eq!(traces["data"]["total_traces"], 1);
+    assert_eq!(
+        traces["data"]["traces"][0]["trace_id"],
+        outside_id.to_string()
+    );
+
+    let (status, _) = app.get_with_status("/api/traces?since=yesterday").await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app
+        .get_with_status("/api/traces?since=2024-01-02T00:00:00Z&until=2024-01-01T00:00:00Z")
+        .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_health_sort_pages_worst_first() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let racy = sample_trace_fixture();
+    let racy_id = racy.trace_id.to_string();
+
+    // Just the racy trace's root, in a trace of its own
+    let clean_id = uuid::Uuid::new_v4();
+    let mut clean = racy.events[0].clone();
+    clean.id = uuid::Uuid::new_v4();
+    clean.trace_id = clean_id;
+    clean.metadata.environment = "prod".into();
+    app.post_json("/events", json!({ "events": racy.events }))
+        .await?;
+    app.post_json("/events", json!({ "events": [clean] }))
+        .await?;
+    wait_for_trace(&app, racy_id.clone(), 4).await?;
+    wait_for_trace(&app, clean_id.to_string(), 1).await?;
+
+    let first = app.get_json("/api/traces?sort=health&page_size=1").await?;
+    assert_eq!(first["data"]["total_traces"], 2);
+    assert_eq!(first["data"]["total_pages"], 2);
+    let listed = first["data"]["traces"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["trace_id"], racy_id);
+    assert_eq!(listed[0]["event_count"], 4);
+    let worst = listed[0]["health_score"].as_f64().unwrap();
+
+    let second = app
+        .get_json("/api/traces?sort=health&page=2&page_size=1")
+        .await?;
+    assert_eq!(
+        second["data"]["traces"][0]["trace_id"],
+        clean_id.to_string()
+    );
+    assert!(
+        second["data"]["traces"][0]["health_score"]
+            .as_f64()
+            .unwrap()
+            > worst
+    );
+
+    let after = app
+        .get_json(&format!(
+            "/api/traces?sort=health&page_size=1&after={}",
+            racy_id
+        ))
+        .await?;
+    assert_eq!(after["data"]["traces"][0]["trace_id"], clean_id.to_string());
+    assert_eq!(after["data"]["next_cursor"], clean_id.to_string());
+    assert_eq!(after["data"]["has_more"], false);
+
+    let filtered = app
+        .get_json("/api/traces?sort=health&environment=prod")
+        .await?;
+    assert_eq!(filtered["data"]["total_traces"], 1);
+    assert_eq!(
+        filtered["data"]["traces"][0]["trace_id"],
+        clean_id.to_string()
+    );
+
+    let (status, _) = app
+        .get_with_status(&format!(
+            "/api/traces?sort=health&after={}",
+            uuid::Uuid::new_v4()
+        ))
+        .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_api_trace_get_nonexistent() -> Result<()> {
     let app = TestApp::new(Config::default()).await?;
@@ -269,6 +1063,109 @@ async fn test_api_events_post_invalid_structure() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_post_reports_malformed_events_by_index() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let malformed_id = Uuid::new_v4().to_string();
+
+    let (status, body) = app
+        .post_raw(
+            "/events",
+            "application/json",
+            serde_json::to_vec(&json!({
+                "events": [fixture.events[0], { "id": malformed_id, "trace_id": fixture.trace_id }],
+            }))?,
+        )
+        .await?;
+    assert_eq!(status, StatusCode::MULTI_STATUS);
+    assert_eq!(body["data"]["ingested"], 1);
+    let failed = body["data"]["failed"].as_array().unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0]["index"], 1);
+    assert_eq!(failed[0]["id"], malformed_id);
+    assert!(failed[0]["error"]
+        .as_str()
+        .unwrap()
+        .contains("missing field"));
+
+    // The valid event was captured despite its malformed neighbour
+    wait_for_stored(&app, fixture.trace_id, 1).await?;
+
+    // With no valid event left, the whole batch is a bad request
+    let (status, body) = app
+        .post_raw(
+            "/events",
+            "application/json",
+            serde_json::to_vec(&json!({ "events": [{ "id": malformed_id }] }))?,
+        )
+        .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+    assert_eq!(body["data"]["failed"][0]["index"], 0);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_backpressure_retry_stores_each_event_once() -> Result<()> {
+    let mut config = Config::default();
+    config.engine.buffer_size = 2;
+    let app = TestApp::new(config).await?;
+    let fixture = sample_trace_fixture();
+    let events: Vec<_> = (0..6)
+        .map(|_| {
+            let mut event = fixture.events[0].clone();
+            event.id = Uuid::new_v4();
+            event.parent_id = None;
+            event
+        })
+        .collect();
+
+    // Resend only the indices a 429 lists as rejected, as the SDK does
+    let mut pending = events;
+    let mut backpressured = false;
+    for _ in 0..40 {
+        let (status, body) = app
+            .post_raw(
+                "/events",
+                "application/json",
+                serde_json::to_vec(&json!({ "events": pending }))?,
+            )
+            .await?;
+        if status != StatusCode::TOO_MANY_REQUESTS {
+            assert_eq!(status, StatusCode::OK);
+            pending.clear();
+            break;
+        }
+        backpressured = true;
+        pending = body["data"]["failed"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|failed| pending[failed["index"].as_u64().unwrap() as usize].clone())
+            .collect();
+        sleep(Duration::from_millis(20)).await;
+    }
+    assert!(
+        backpressured,
+        "six events should overflow a two-event buffer"
+    );
+    assert!(pending.is_empty());
+
+    // The events accepted before the buffer filled were not stored again
+    wait_for_stored(&app, fixture.trace_id, 6).await?;
+    sleep(Duration::from_millis(50)).await;
+    let stored = app
+        .engine()
+        .storage()
+        .get_trace_events(fixture.trace_id)
+        .await?;
+    assert_eq!(stored.len(), 6);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_api_events_post_large_batch() -> Result<()> {
     let app = TestApp::new(Config::default()).await?;
@@ -305,6 +1202,62 @@ async fn test_api_events_post_large_batch() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_past_trace_cap_are_rejected_and_trace_truncated() -> Result<()> {
+    let mut config = Config::default();
+    config.capture.max_events_per_trace = Some(4);
+    let app = TestApp::new(config).await?;
+    let fixture = sample_trace_fixture();
+
+    let events: Vec<_> = (0..6)
+        .map(|i| {
+            let mut event = fixture.events[0].clone();
+            event.id = uuid::Uuid::new_v4();
+            event.timestamp += chrono::Duration::milliseconds(i);
+            event
+        })
+        .collect();
+    let (status, body) = app
+        .post_raw(
+            "/events",
+            "application/json",
+            serde_json::to_vec(&json!({ "events": events }))?,
+        )
+        .await?;
+    assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(body["data"], "Ingested 4 events, 2 errors");
+
+    wait_for_stored(&app, fixture.trace_id, 4).await?;
+
+    let list = app.get_json("/api/traces").await?;
+    let trace = &list["data"]["traces"][0];
+    assert_eq!(trace["trace_id"], fixture.trace_id.to_string());
+    assert_eq!(trace["event_count"], 4);
+    assert_eq!(trace["truncated"], true);
+
+    // Later events for the trace are still turned away
+    let mut late = fixture.events[0].clone();
+    late.id = uuid::Uuid::new_v4();
+    let (status, _) = app
+        .post_raw(
+            "/events",
+            "application/json",
+            serde_json::to_vec(&json!({ "events": [late] }))?,
+        )
+        .await?;
+    assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        app.engine()
+            .storage()
+            .get_trace_events(fixture.trace_id)
+            .await?
+            .len(),
+        4
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_api_events_post_malformed_json() -> Result<()> {
     let app = TestApp::new(Config::default()).await?;
@@ -324,15 +1277,1024 @@ async fn test_api_events_post_malformed_json() -> Result<()> {
     Ok(())
 }
 
-// ─── Integration Tests ──────────────────────────────────────────────────────
-
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
-async fn test_api_full_workflow() -> Result<()> {
+async fn test_api_events_post_gzip_batch() -> Result<()> {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
     let app = TestApp::new(Config::default()).await?;
     let fixture = sample_trace_fixture();
 
-    // 1. List should be empty
-    let list_before = app.get_json("/api/traces?page=1&page_size=10").await?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(&json!({ "events": fixture.events }))?)?;
+    let (status, body) = app
+        .post_encoded("/events", "gzip", encoder.finish()?)
+        .await?;
+
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    wait_for_trace(&app, fixture.trace_id.to_string(), fixture.events.len()).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_post_unsupported_encoding() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+
+    let (status, _) = app
+        .post_encoded("/events", "compress", r#"{"events": []}"#)
+        .await?;
+
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_post_ndjson_truncated_final_line() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    let mut body = String::new();
+    for event in &fixture.events {
+        body.push_str(&serde_json::to_string(event)?);
+        body.push('\n');
+    }
+    // Simulate a connection that dropped partway through the next event
+    let extra = serde_json::to_string(&fixture.events[0])?;
+    body.push_str(&extra[..extra.len() / 2]);
+
+    let (status, response) = app
+        .post_raw("/events", "application/x-ndjson", body)
+        .await?;
+
+    assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response["data"]["ingested"], fixture.events.len());
+    assert_eq!(response["data"]["malformed_lines"], 1);
+
+    wait_for_trace(&app, fixture.trace_id.to_string(), fixture.events.len()).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_post_ndjson_oversized_line_is_malformed() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    // A 3 MiB line with no newline in sight, streamed in small chunks, then a
+    // valid event on the next line
+    let mut chunks = vec![Bytes::from(
+        serde_json::to_string(&fixture.events[0])? + "\n",
+    )];
+    chunks.extend((0..48).map(|_| Bytes::from(vec![b'x'; 64 * 1024])));
+    chunks.push(Bytes::from(format!(
+        "\n{}\n",
+        serde_json::to_string(&fixture.events[1])?
+    )));
+    let body = Body::from_stream(futures::stream::iter(
+        chunks.into_iter().map(Ok::<_, std::convert::Infallible>),
+    ));
+
+    let (status, results) = app
+        .post_raw_ndjson("/events", "application/x-ndjson", body)
+        .await?;
+
+    assert_eq!(status, StatusCode::OK);
+    let statuses: Vec<&str> = results
+        .iter()
+        .map(|result| result["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["ingested", "malformed", "ingested"]);
+    assert_eq!(results[1]["line"], 2);
+    assert!(results[1]["error"].as_str().unwrap().contains("exceeds"));
+
+    wait_for_trace(&app, fixture.trace_id.to_string(), 2).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_post_ndjson_streams_per_event_results() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    let mut malformed = serde_json::to_value(&fixture.events[2])?;
+    malformed["kind"] = json!("NotAKind");
+    let lines = [
+        serde_json::to_string(&fixture.events[0])?,
+        serde_json::to_string(&fixture.events[1])?,
+        malformed.to_string(),
+        "{not json".to_string(),
+        serde_json::to_string(&fixture.events[3])?,
+    ];
+
+    let (status, results) = app
+        .post_raw_ndjson("/events", "application/x-ndjson", lines.join("\n"))
+        .await?;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results.len(), 5);
+    let statuses: Vec<&str> = results
+        .iter()
+        .map(|result| result["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        statuses,
+        ["ingested", "ingested", "malformed", "malformed", "ingested"]
+    );
+    for (index, result) in results.iter().enumerate() {
+        assert_eq!(result["line"], index + 1);
+    }
+
+    // The malformed event is still identified by id so clients can reconcile it
+    assert_eq!(results[2]["id"], fixture.events[2].id.to_string());
+    assert!(results[2]["error"].is_string());
+    assert!(results[3]["id"].is_null());
+    assert!(results[0].get("error").is_none());
+
+    wait_for_trace(&app, fixture.trace_id.to_string(), 3).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_child_before_parent_across_posts() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let (root, child) = (&fixture.events[0], &fixture.events[1]);
+
+    app.post_json("/events", json!({ "events": [child] }))
+        .await?;
+    wait_for_stored(&app, fixture.trace_id, 1).await?;
+    app.post_json("/events", json!({ "events": [root] }))
+        .await?;
+    wait_for_trace(&app, fixture.trace_id.to_string(), 2).await?;
+
+    let tree = app
+        .engine()
+        .analysis()
+        .get_trace_tree(fixture.trace_id)
+        .await?;
+    assert_eq!(tree.len(), 1, "the child must not become a second root");
+    assert_eq!(tree[0].id, root.id.to_string());
+    assert_eq!(tree[0].children.len(), 1);
+    assert_eq!(tree[0].children[0].id, child.id.to_string());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_events_post_imported_jaeger_trace() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+
+    let events = parse_events(jaeger_export_fixture(), ImportFormat::Jaeger)?;
+    assert_eq!(events.len(), 3);
+    let trace_id = events[0].trace_id;
+
+    app.post_json("/events", json!({ "events": events }))
+        .await?;
+    wait_for_trace(&app, trace_id.to_string(), 3).await?;
+
+    let trace = app.get_json(&format!("/api/traces/{}", trace_id)).await?;
+    let stored = trace["data"]["events"].as_array().unwrap();
+    let find = |service: &str, span: &str| {
+        stored
+            .iter()
+            .find(|event| {
+                event["metadata"]["service_name"] == service
+                    && event["metadata"]["distributed_span_id"] == span
+            })
+            .unwrap_or_else(|| panic!("missing span {}", span))
+    };
+
+    let root = find("frontend", "00f067aa0ba902b7");
+    let render = find("frontend", "00f067aa0ba902b8");
+    let charge = find("payments", "00f067aa0ba902b9");
+
+    assert!(root["parent_id"].is_null());
+    assert_eq!(render["parent_id"], root["id"]);
+    assert_eq!(charge["parent_id"], root["id"]);
+    assert!(charge["kind"]["DatabaseQuery"].is_object());
+
+    // Only the cross-service child is linked as a distributed call
+    assert!(render["metadata"]["upstream_span_id"].is_null());
+    assert_eq!(charge["metadata"]["upstream_span_id"], "00f067aa0ba902b7");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_services_group_by_sums_member_stats() -> Result<()> {
+    let mut config = Config::default();
+    config.distributed_tracing.enabled = true;
+    config.service_groups.insert(
+        "team".into(),
+        [
+            ("checkout".to_string(), "payments".to_string()),
+            ("billing".to_string(), "payments".to_string()),
+        ]
+        .into_iter()
+        .collect(),
+    );
+    let app = TestApp::new(config).await?;
+
+    // checkout and billing are mapped in config; search declares its team via a tag
+    let fixture = sample_trace_fixture();
+    let mut events = fixture.events.clone();
+    for (event, service) in events
+        .iter_mut()
+        .zip(["checkout", "checkout", "billing", "search"])
+    {
+        event.metadata.service_name = service.into();
+        event.metadata.distributed_span_id = Some(format!("span-{}", service));
+        if service == "search" {
+            event
+                .metadata
+                .tags
+                .insert("team".into(), "discovery".into());
+        }
+    }
+    app.post_json("/events", json!({ "events": events }))
+        .await?;
+    wait_for_trace(&app, fixture.trace_id.to_string(), 4).await?;
+
+    let services = app.get_json("/api/services").await?;
+    let services = services["data"]["services"].as_array().unwrap();
+    let member_events = |name: &str| {
+        services
+            .iter()
+            .find(|service| service["name"] == name)
+            .map(|service| service["event_count"].as_u64().unwrap())
+            .unwrap_or_else(|| panic!("missing service {}", name))
+    };
+    let payments_events = member_events("checkout") + member_events("billing");
+
+    let grouped = app.get_json("/api/services?group_by=team").await?;
+    assert_eq!(grouped["data"]["group_by"], "team");
+    assert_eq!(grouped["data"]["total_groups"], 2);
+    let groups = grouped["data"]["groups"].as_array().unwrap();
+
+    assert_eq!(groups[0]["name"], "discovery");
+    assert_eq!(groups[0]["services"], json!(["search"]));
+    assert_eq!(groups[0]["event_count"], member_events("search"));
+
+    assert_eq!(groups[1]["name"], "payments");
+    assert_eq!(groups[1]["event_count"], payments_events);
+    assert_eq!(groups[1]["trace_count"], 2);
+    let mut members: Vec<&str> = groups[1]["services"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|service| service.as_str().unwrap())
+        .collect();
+    members.sort();
+    assert_eq!(members, ["billing", "checkout"]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_service_overview_reports_latency_percentiles() -> Result<()> {
+    let mut config = Config::default();
+    config.distributed_tracing.enabled = true;
+    let app = TestApp::new(config).await?;
+    let fixture = sample_trace_fixture();
+    let template = fixture.events[0].clone();
+
+    // ledger events take 1ms..=100ms; one more event reports no duration
+    let mut events: Vec<_> = (1..=100u64)
+        .map(|ms| {
+            let mut event = template.clone();
+            event.id = uuid::Uuid::new_v4();
+            event.parent_id = None;
+            event.metadata.service_name = "ledger".into();
+            event.metadata.distributed_span_id = Some("span-ledger".into());
+            event.metadata.duration_ns = Some(ms * 1_000_000);
+            event
+        })
+        .collect();
+    let mut untimed = events[0].clone();
+    untimed.id = uuid::Uuid::new_v4();
+    untimed.metadata.duration_ns = None;
+    events.push(untimed);
+    app.post_json("/events", json!({ "events": events }))
+        .await?;
+    wait_for_trace(&app, fixture.trace_id.to_string(), 101).await?;
+
+    let overview = app.get_json("/api/services/ledger/overview").await?;
+    let data = &overview["data"];
+    assert_eq!(data["event_count"], 101);
+    assert_eq!(data["trace_count"], 1);
+    assert_eq!(data["latency"]["samples"], 100);
+    let percentile = |key: &str| data["latency"][key].as_f64().unwrap();
+    assert!((percentile("p50_ms") - 50.5).abs() < 1e-9);
+    assert!((percentile("p95_ms") - 95.05).abs() < 1e-9);
+    assert!((percentile("p99_ms") - 99.01).abs() < 1e-9);
+
+    let (status, _) = app
+        .get_with_status("/api/services/unknown/overview")
+        .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_topology_marks_racing_edge() -> Result<()> {
+    let mut config = Config::default();
+    config.distributed_tracing.enabled = true;
+    let app = TestApp::new(config).await?;
+
+    // gateway calls accounts and ledger; accounts races with a gateway worker on `balance`
+    let fixture = sample_trace_fixture();
+    let mut events = fixture.events.clone();
+    for (event, (service, span, upstream)) in events.iter_mut().zip([
+        ("gateway", "span-gateway", None),
+        ("accounts", "span-accounts", Some("span-gateway")),
+        ("gateway", "span-gateway-worker", None),
+        ("ledger", "span-ledger", Some("span-gateway")),
+    ]) {
+        event.metadata.service_name = service.into();
+        event.metadata.distributed_span_id = Some(span.into());
+        event.metadata.upstream_span_id = upstream.map(Into::into);
+    }
+    app.post_json("/events", json!({ "events": events }))
+        .await?;
+    wait_for_trace(&app, fixture.trace_id.to_string(), 4).await?;
+
+    let topology = app.get_json("/api/topology").await?;
+    let data = &topology["data"];
+    let mut services: Vec<&str> = data["services"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|service| service["name"].as_str().unwrap())
+        .collect();
+    services.sort();
+    assert_eq!(services, ["accounts", "gateway", "ledger"]);
+
+    let edges = data["edges"].as_array().unwrap();
+    let edge_to = |service: &str| {
+        edges
+            .iter()
+            .find(|edge| edge["from_service"] == "gateway" && edge["to_service"] == service)
+            .unwrap_or_else(|| panic!("missing edge gateway -> {}", service))
+    };
+    assert_eq!(data["total_edges"], 2);
+    assert_eq!(data["racing_edges"], 1);
+    assert_eq!(edge_to("accounts")["has_race"], true);
+    assert_eq!(edge_to("accounts")["race_count"], 1);
+    assert_eq!(edge_to("ledger")["has_race"], false);
+
+    Ok(())
+}
+
+// ─── GET /api/traces/:id/stream Tests ───────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_trace_stream_delivers_events_captured_after_subscribing() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    let event = fixture.events[0].clone();
+
+    let (status, mut body) = app
+        .get_stream(&format!("/api/traces/{}/stream", fixture.trace_id))
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+
+    // The other trace's event is captured first but filtered out of the stream
+    let mut other = event.clone();
+    other.id = uuid::Uuid::new_v4();
+    other.trace_id = uuid::Uuid::new_v4();
+    app.post_json("/events", json!({ "events": [other, event] }))
+        .await?;
+
+    let mut received = String::new();
+    while !received.contains("\n\n") {
+        let frame = tokio::time::timeout(Duration::from_secs(5), body.frame())
+            .await?
+            .context("stream ended")??;
+        if let Ok(data) = frame.into_data() {
+            received.push_str(std::str::from_utf8(&data)?);
+        }
+    }
+
+    let message = received.split("\n\n").next().unwrap_or_default();
+    assert!(message.lines().any(|line| line == "event: event"));
+    let data = message
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .context("message has no data")?;
+    let streamed: serde_json::Value = serde_json::from_str(data)?;
+    assert_eq!(streamed["id"], fixture.events[0].id.to_string());
+    assert_eq!(streamed["trace_id"], fixture.trace_id.to_string());
+
+    Ok(())
+}
+
+// ─── GET /api/ws/races Tests ────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_race_alerts_websocket_pushes_new_races() -> Result<()> {
+    use futures::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let app = TestApp::new(Config::default()).await?;
+    let addr = app.serve().await?;
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("ws://{}/api/ws/races", addr)).await?;
+
+    // The root and both concurrent writes to `balance`
+    let fixture = sample_trace_fixture();
+    app.post_json("/events", json!({ "events": fixture.events[..3] }))
+        .await?;
+
+    let alert = loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await?
+            .context("socket closed")??;
+        if let Message::Text(text) = message {
+            break serde_json::from_str::<serde_json::Value>(&text)?;
+        }
+    };
+    assert_eq!(alert["trace_id"], fixture.trace_id.to_string());
+    assert_eq!(alert["variable"], "balance");
+    assert_eq!(alert["severity"], "Critical");
+    let mut pair = [alert["event1"].clone(), alert["event2"].clone()];
+    pair.sort_by_key(|id| id.to_string());
+    assert_eq!(
+        pair,
+        [
+            json!(fixture.events[1].id.to_string()),
+            json!(fixture.events[2].id.to_string())
+        ]
+    );
+
+    // More events in the trace don't repeat a race that was already alerted
+    app.post_json("/events", json!({ "events": [&fixture.events[3]] }))
+        .await?;
+    let repeat = tokio::time::timeout(Duration::from_millis(750), socket.next()).await;
+    assert!(repeat.is_err(), "unexpected message: {:?}", repeat);
+
+    Ok(())
+}
+
+// ─── Trace Bundle Tests ─────────────────────────────────────────────────────
+
+/// Races and critical path for a trace, in the form bundles annotate them
+async fn analysis_results(app: &TestApp, trace_id: uuid::Uuid) -> Result<BundleAnnotations> {
+    let analysis = app.engine().analysis();
+    let races = analysis.find_concurrent_events(trace_id).await?;
+    let critical_path = analysis.get_critical_path(trace_id).await.ok();
+    Ok(BundleAnnotations::from_results(
+        &races,
+        critical_path.as_ref(),
+    ))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_bundle_round_trip_reproduces_analysis() -> Result<()> {
+    let source = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    source
+        .post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&source, fixture.trace_id.to_string(), 4).await?;
+
+    let exported = source
+        .get_json(&format!("/api/traces/{}/bundle", fixture.trace_id))
+        .await?;
+    let bundle = exported["data"].clone();
+    assert_eq!(bundle["events"].as_array().unwrap().len(), 4);
+
+    let target = TestApp::new(Config::default()).await?;
+    let imported = target.post_json("/api/bundles", bundle).await?;
+    assert_eq!(imported["data"]["events_imported"], 4);
+    assert_eq!(imported["data"]["reproduced"], true);
+
+    let expected = analysis_results(&source, fixture.trace_id).await?;
+    let actual = analysis_results(&target, fixture.trace_id).await?;
+    assert!(!expected.races.is_empty());
+    assert!(!expected.critical_path.is_empty());
+    assert_eq!(actual, expected);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_offline_engine_lists_traces_from_bundle_directory() -> Result<()> {
+    let source = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+    source
+        .post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&source, fixture.trace_id.to_string(), 4).await?;
+    let bundle = source
+        .engine()
+        .analysis()
+        .export_bundle(fixture.trace_id)
+        .await?;
+
+    let dir = std::env::temp_dir().join(format!("raceway-offline-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("trace.json"), serde_json::to_vec(&bundle)?)?;
+    std::fs::write(dir.join("notes.txt"), "not a bundle")?;
+
+    let engine = offline::init_offline_engine(&offline::offline_config(), &dir).await;
+    std::fs::remove_dir_all(&dir)?;
+    let engine = engine?;
+
+    let (traces, total) = engine
+        .storage()
+        .get_trace_summaries(1, 20, None, TimeWindow::default())
+        .await?;
+    assert_eq!(total, 1);
+    assert_eq!(traces[0].trace_id, fixture.trace_id);
+    assert_eq!(traces[0].event_count, 4);
+    assert_eq!(
+        engine
+            .analysis()
+            .find_concurrent_events(fixture.trace_id)
+            .await?
+            .len(),
+        fixture.expected_races
+    );
+
+    Ok(())
+}
+
+// ─── Rate Limit Tests ───────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_rate_limited_request_carries_retry_after() -> Result<()> {
+    let mut config = Config::default();
+    config.server.rate_limit_enabled = true;
+    config.server.rate_limit_rpm = 2;
+    let app = TestApp::new(config).await?;
+
+    let (status, headers, _) = app.get_with_headers("/api/traces").await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["x-ratelimit-limit"], "2");
+    assert_eq!(headers["x-ratelimit-remaining"], "1");
+
+    app.get_with_headers("/api/traces").await?;
+    let (status, headers, body) = app.get_with_headers("/api/traces").await?;
+
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["success"], false);
+    assert_eq!(headers["x-ratelimit-remaining"], "0");
+    let retry_after: u64 = headers["retry-after"].to_str()?.parse()?;
+    assert!(
+        (1..=30).contains(&retry_after),
+        "retry-after was {}",
+        retry_after
+    );
+
+    Ok(())
+}
+
+// ─── Probe Tests ────────────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_livez_ok_during_warmup_readyz_unavailable() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let analysis = app.engine().analysis();
+    analysis.begin_warmup(3).await;
+
+    let (live_status, _) = app.get_with_status("/livez").await?;
+    assert_eq!(live_status, StatusCode::OK);
+
+    let (ready_status, body) = app.get_with_status("/readyz").await?;
+    assert_eq!(ready_status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["data"]["storage_reachable"], true);
+    assert_eq!(body["data"]["warmup"]["phase"], "replaying");
+
+    analysis.complete_warmup().await;
+
+    let (ready_status, body) = app.get_with_status("/readyz").await?;
+    assert_eq!(ready_status, StatusCode::OK);
+    assert_eq!(body["data"]["ready"], true);
+
+    let (live_status, _) = app.get_with_status("/livez").await?;
+    assert_eq!(live_status, StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_readyz_unavailable_after_failed_warmup() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    app.engine()
+        .analysis()
+        .fail_warmup("replay aborted".to_string())
+        .await;
+
+    let (ready_status, body) = app.get_with_status("/readyz").await?;
+    assert_eq!(ready_status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["data"]["warmup"]["last_error"], "replay aborted");
+
+    let (live_status, _) = app.get_with_status("/livez").await?;
+    assert_eq!(live_status, StatusCode::OK);
+
+    Ok(())
+}
+
+/// Memory storage whose pings fail, as if the database had gone away
+struct UnreachableStorage {
+    inner: MemoryBackend,
+}
+
+#[async_trait]
+impl StorageBackend for UnreachableStorage {
+    async fn add_event(&self, event: Event) -> Result<()> {
+        self.inner.add_event(event).await
+    }
+
+    async fn add_events_batch(&self, events: Vec<Event>) -> Result<usize> {
+        self.inner.add_events_batch(events).await
+    }
+
+    async fn get_event(&self, id: Uuid) -> Result<Option<Event>> {
+        self.inner.get_event(id).await
+    }
+
+    async fn get_trace_events(&self, trace_id: Uuid) -> Result<Vec<Event>> {
+        self.inner.get_trace_events(trace_id).await
+    }
+
+    async fn get_all_events(&self) -> Result<Vec<Event>> {
+        self.inner.get_all_events().await
+    }
+
+    async fn count_events(&self) -> Result<usize> {
+        self.inner.count_events().await
+    }
+
+    async fn count_traces(&self) -> Result<usize> {
+        self.inner.count_traces().await
+    }
+
+    async fn get_all_trace_ids(&self) -> Result<Vec<Uuid>> {
+        self.inner.get_all_trace_ids().await
+    }
+
+    async fn get_trace_summaries(
+        &self,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.inner
+            .get_trace_summaries(page, page_size, min_events, window)
+            .await
+    }
+
+    async fn get_trace_summaries_by_service(
+        &self,
+        service_name: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.inner
+            .get_trace_summaries_by_service(service_name, page, page_size)
+            .await
+    }
+
+    async fn get_trace_summaries_by_environment(
+        &self,
+        environment: &str,
+        page: usize,
+        page_size: usize,
+        min_events: Option<usize>,
+        window: TimeWindow,
+    ) -> Result<(Vec<TraceSummary>, usize)> {
+        self.inner
+            .get_trace_summaries_by_environment(environment, page, page_size, min_events, window)
+            .await
+    }
+
+    async fn get_trace_ids_by_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        self.inner.get_trace_ids_by_tag(key, value).await
+    }
+
+    async fn get_trace_roots(&self, trace_id: Uuid) -> Result<Vec<Uuid>> {
+        self.inner.get_trace_roots(trace_id).await
+    }
+
+    async fn add_trace_tags(&self, trace_id: Uuid, tags: HashMap<String, String>) -> Result<()> {
+        self.inner.add_trace_tags(trace_id, tags).await
+    }
+
+    async fn get_trace_tags(&self, trace_id: Uuid) -> Result<HashMap<String, String>> {
+        self.inner.get_trace_tags(trace_id).await
+    }
+
+    async fn get_trace_ids_by_trace_tag(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<std::collections::HashSet<Uuid>> {
+        self.inner.get_trace_ids_by_trace_tag(key, value).await
+    }
+
+    async fn save_baseline(&self, operation: &str, stats: DurationStats) -> Result<()> {
+        self.inner.save_baseline(operation, stats).await
+    }
+
+    async fn save_baselines_batch(&self, baselines: HashMap<String, DurationStats>) -> Result<()> {
+        self.inner.save_baselines_batch(baselines).await
+    }
+
+    async fn get_baseline_metric(&self, operation: &str) -> Result<Option<DurationStats>> {
+        self.inner.get_baseline_metric(operation).await
+    }
+
+    async fn get_all_baseline_operations(&self) -> Result<Vec<String>> {
+        self.inner.get_all_baseline_operations().await
+    }
+
+    async fn save_distributed_span(&self, span: DistributedSpan) -> Result<()> {
+        self.inner.save_distributed_span(span).await
+    }
+
+    async fn get_distributed_span(&self, span_id: &str) -> Result<Option<DistributedSpan>> {
+        self.inner.get_distributed_span(span_id).await
+    }
+
+    async fn get_distributed_spans(&self, trace_id: Uuid) -> Result<Vec<DistributedSpan>> {
+        self.inner.get_distributed_spans(trace_id).await
+    }
+
+    async fn add_distributed_edge(&self, edge: DistributedEdge) -> Result<()> {
+        self.inner.add_distributed_edge(edge).await
+    }
+
+    async fn get_distributed_edges(&self, trace_id: Uuid) -> Result<Vec<DistributedEdge>> {
+        self.inner.get_distributed_edges(trace_id).await
+    }
+
+    async fn list_distributed_edges(&self) -> Result<Vec<DistributedEdge>> {
+        self.inner.list_distributed_edges().await
+    }
+
+    async fn get_all_services(&self) -> Result<Vec<(String, usize, usize)>> {
+        self.inner.get_all_services().await
+    }
+
+    async fn get_service_tag_values(&self, tag: &str) -> Result<HashMap<String, String>> {
+        self.inner.get_service_tag_values(tag).await
+    }
+
+    async fn get_service_event_durations(&self, service: &str) -> Result<Vec<u64>> {
+        self.inner.get_service_event_durations(service).await
+    }
+
+    async fn get_service_dependencies_global(
+        &self,
+        service_name: &str,
+    ) -> Result<(Vec<(String, usize, usize)>, Vec<(String, usize, usize)>)> {
+        self.inner
+            .get_service_dependencies_global(service_name)
+            .await
+    }
+
+    async fn get_all_distributed_edges(&self) -> Result<Vec<serde_json::Value>> {
+        self.inner.get_all_distributed_edges().await
+    }
+
+    async fn get_global_race_candidates(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.inner.get_global_race_candidates(environment).await
+    }
+
+    async fn get_top_service_calls(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.inner.get_top_service_calls(environment).await
+    }
+
+    async fn get_service_health(&self, time_window_minutes: u64) -> Result<Vec<serde_json::Value>> {
+        self.inner.get_service_health(time_window_minutes).await
+    }
+
+    async fn get_performance_metrics(
+        &self,
+        limit: usize,
+        scope: &MetricsScope,
+    ) -> Result<serde_json::Value> {
+        self.inner.get_performance_metrics(limit, scope).await
+    }
+
+    async fn get_throughput_buckets(
+        &self,
+        minutes: u64,
+        service: Option<&str>,
+    ) -> Result<Vec<ThroughputBucket>> {
+        self.inner.get_throughput_buckets(minutes, service).await
+    }
+
+    async fn ping(&self) -> Result<std::time::Duration> {
+        Err(anyhow::anyhow!("connection refused"))
+    }
+
+    async fn cleanup_old_traces(&self, retention_hours: u64) -> Result<usize> {
+        self.inner.cleanup_old_traces(retention_hours).await
+    }
+
+    async fn maintenance(&self) -> Result<MaintenanceReport> {
+        self.inner.maintenance().await
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.inner.clear().await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_health_reports_storage_latency() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    app.engine().analysis().complete_warmup().await;
+
+    let (status, body) = app.get_with_status("/health").await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["storage_healthy"], true);
+    assert!(body["data"]["storage_latency_ms"].as_f64().is_some());
+    assert_eq!(body["data"]["phase"], "completed");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_health_unavailable_when_storage_ping_fails() -> Result<()> {
+    let config = Config::default();
+    let storage = UnreachableStorage {
+        inner: MemoryBackend::new(&config.storage)?,
+    };
+    let app = TestApp::with_storage(config, Arc::new(storage)).await?;
+    app.engine().analysis().complete_warmup().await;
+
+    let (status, body) = app.get_with_status("/health").await?;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["success"], false);
+    assert_eq!(body["error"], "connection refused");
+    assert_eq!(body["data"]["storage_healthy"], false);
+    assert!(body["data"]["storage_latency_ms"].is_null());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_metrics_exposes_prometheus_counters() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, fixture.trace_id.to_string(), 4).await?;
+
+    let (status, body) = app.get_text("/metrics").await?;
+    assert_eq!(status, StatusCode::OK);
+    for name in [
+        "raceway_events_ingested_total",
+        "raceway_traces_active",
+        "raceway_races_detected_total",
+        "raceway_anomalies_detected_total",
+        "raceway_ingest_request_duration_seconds_bucket",
+        "raceway_storage_ping_seconds",
+    ] {
+        assert!(body.contains(name), "missing {} in:\n{}", name, body);
+    }
+    assert!(body.contains("raceway_events_ingested_total 4"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_metrics_public_skips_api_key() -> Result<()> {
+    let mut config = Config::default();
+    config.server.auth_enabled = true;
+    config.server.api_keys = vec!["secret".to_string()];
+
+    let app = TestApp::new(config.clone()).await?;
+    let (status, _) = app.get_text("/metrics").await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    config.server.metrics_public = true;
+    let app = TestApp::new(config).await?;
+    let (status, _) = app.get_text("/metrics").await?;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.get_text("/status").await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+// ─── Global Analysis Job Tests ──────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_performance_metrics_throughput_buckets() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let window_start = ThroughputBucket::window_start(5);
+    let minute = |offset: i64| window_start + chrono::Duration::minutes(offset);
+
+    // One trace spread over minutes 1-3, a second one entirely in minute 2
+    let mut spread = sample_trace_fixture();
+    for (event, offset) in spread.events.iter_mut().zip([1, 1, 2, 3]) {
+        event.timestamp = minute(offset) + chrono::Duration::seconds(10);
+    }
+    let mut burst = relabeled_fixture(sample_trace_fixture());
+    for (i, event) in burst.events.iter_mut().enumerate() {
+        event.timestamp = minute(2) + chrono::Duration::seconds(20 + i as i64);
+    }
+    for fixture in [&spread, &burst] {
+        app.post_json("/events", json!({ "events": fixture.events }))
+            .await?;
+        wait_for_stored(&app, fixture.trace_id, 4).await?;
+    }
+
+    let metrics = app.get_json("/api/performance/metrics").await?;
+    assert!(metrics["data"]["throughput"].get("buckets").is_none());
+
+    let metrics = app.get_json("/api/performance/metrics?buckets=5").await?;
+    let buckets = metrics["data"]["throughput"]["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 5);
+
+    // Look buckets up by minute, so the test holds if the clock ticks over mid-test
+    let counts = |offset: i64| {
+        let minute = ThroughputBucket::new(minute(offset), 0, 0).minute;
+        buckets
+            .iter()
+            .find(|bucket| bucket["minute"] == minute)
+            .map(|bucket| (bucket["events"].clone(), bucket["traces"].clone()))
+            .unwrap()
+    };
+    assert_eq!(counts(1), (json!(2), json!(1)));
+    assert_eq!(counts(2), (json!(5), json!(2)));
+    assert_eq!(counts(3), (json!(1), json!(1)));
+
+    let total: u64 = buckets
+        .iter()
+        .map(|bucket| bucket["events"].as_u64().unwrap())
+        .sum();
+    assert_eq!(total, 8);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_global_analysis_job_completes() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    app.post_json("/events", json!({ "events": fixture.events }))
+        .await?;
+    wait_for_trace(&app, fixture.trace_id.to_string(), 4).await?;
+
+    let started = app.post_json("/api/analyze/global", json!({})).await?;
+    let job_id = started["data"]["job_id"].as_str().unwrap().to_string();
+
+    let mut status = serde_json::Value::Null;
+    for _ in 0..40 {
+        status = app
+            .get_json(&format!("/api/analyze/global/{}", job_id))
+            .await?;
+        if status["data"]["status"] != "Running" {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(status["data"]["status"], "Completed");
+    assert_eq!(status["data"]["progress_percent"], 100.0);
+    assert_eq!(status["data"]["result"]["total_traces"], 1);
+    assert!(status["data"]["result"]["race_details"].is_array());
+
+    let (missing, _) = app
+        .get_with_status(&format!("/api/analyze/global/{}", uuid::Uuid::new_v4()))
+        .await?;
+    assert_eq!(missing, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+// ─── Integration Tests ──────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_api_full_workflow() -> Result<()> {
+    let app = TestApp::new(Config::default()).await?;
+    let fixture = sample_trace_fixture();
+
+    // 1. List should be empty
+    let list_before = app.get_json("/api/traces?page=1&page_size=10").await?;
     assert_eq!(list_before["data"]["traces"].as_array().unwrap().len(), 0);
 
     // 2. Submit events
@@ -389,3 +2351,41 @@ async fn test_api_concurrent_submissions() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_cors_allow_list_rejects_unlisted_origins() -> Result<()> {
+    let mut config = Config::default();
+    config.storage.backend = "memory".into();
+    config.server.cors_origins = vec![
+        "http://localhost:3000".into(),
+        "https://app.example.com".into(),
+        "not\na valid origin".into(),
+    ];
+
+    let engine = raceway::server::init_engine(&config).await?;
+    let router = raceway::server::build_router(&config, engine);
+
+    for (origin, allowed) in [
+        ("http://localhost:3000", true),
+        ("https://app.example.com", true),
+        ("https://evil.example.com", false),
+    ] {
+        let request = Request::builder()
+            .uri("/health")
+            .header("origin", origin)
+            .body(Body::empty())?;
+        let response = router.clone().oneshot(request).await?;
+        let allow_origin = response.headers().get("access-control-allow-origin");
+
+        if allowed {
+            assert_eq!(
+                allow_origin.and_then(|value| value.to_str().ok()),
+                Some(origin)
+            );
+        } else {
+            assert!(allow_origin.is_none(), "{} should be rejected", origin);
+        }
+    }
+
+    Ok(())
+}
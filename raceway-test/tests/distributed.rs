@@ -24,6 +24,9 @@ fn create_metadata(
         instance_id: Some(instance.into()),
         distributed_span_id: span_id,
         upstream_span_id: parent_span,
+        monotonic_ns: None,
+        retry_of: None,
+        attempt: None,
     }
 }
 
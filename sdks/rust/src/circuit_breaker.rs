@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+
+/// Consecutive failed flushes before the breaker opens
+pub(crate) const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+/// How long an open breaker holds sends back before probing the server again
+pub(crate) const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+/// Unsent events kept while sends fail; the oldest are dropped beyond this
+pub(crate) const DEFAULT_MAX_BUFFERED_EVENTS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BreakerState {
+    /// Sending normally, counting consecutive failures
+    Closed { failures: u32 },
+    /// Not sending until the cooldown ends
+    Open { until: Instant },
+    /// Cooldown over: the next send is a probe that closes or reopens the breaker
+    HalfOpen,
+}
+
+/// Circuit breaker guarding the SDK's sends to `/events`, so an unreachable
+/// server costs one failed request per cooldown instead of one per flush.
+#[derive(Debug, Clone)]
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: BreakerState,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: BreakerState::Closed { failures: 0 },
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn state(&self) -> BreakerState {
+        self.state
+    }
+
+    /// Whether a send may go out at `now`; an open breaker half-opens once its cooldown ends
+    pub(crate) fn allow(&mut self, now: Instant) -> bool {
+        match self.state {
+            BreakerState::Closed { .. } | BreakerState::HalfOpen => true,
+            BreakerState::Open { until } if now >= until => {
+                self.state = BreakerState::HalfOpen;
+                true
+            }
+            BreakerState::Open { .. } => false,
+        }
+    }
+
+    /// Time left before an open breaker lets a probe through
+    pub(crate) fn remaining(&self, now: Instant) -> Option<Duration> {
+        match self.state {
+            BreakerState::Open { until } if until > now => Some(until - now),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn record_success(&mut self) {
+        self.state = BreakerState::Closed { failures: 0 };
+    }
+
+    pub(crate) fn record_failure(&mut self, now: Instant) {
+        self.state = match self.state {
+            BreakerState::Closed { failures } if failures + 1 < self.failure_threshold => {
+                BreakerState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            _ => BreakerState::Open {
+                until: now + self.cooldown,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let mut breaker = CircuitBreaker::new(3, Duration::from_secs(10));
+        let now = Instant::now();
+
+        breaker.record_failure(now);
+        breaker.record_failure(now);
+        assert_eq!(breaker.state(), BreakerState::Closed { failures: 2 });
+        assert!(breaker.allow(now));
+
+        breaker.record_failure(now);
+        assert!(!breaker.allow(now));
+        assert_eq!(breaker.remaining(now), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let mut breaker = CircuitBreaker::new(2, Duration::from_secs(10));
+        let now = Instant::now();
+
+        breaker.record_failure(now);
+        breaker.record_success();
+        breaker.record_failure(now);
+        assert_eq!(breaker.state(), BreakerState::Closed { failures: 1 });
+    }
+
+    #[test]
+    fn test_half_open_probe_closes_or_reopens() {
+        let mut breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+        let now = Instant::now();
+        let later = now + Duration::from_secs(10);
+
+        breaker.record_failure(now);
+        assert!(!breaker.allow(now + Duration::from_secs(9)));
+        assert!(breaker.allow(later));
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        // A failed probe reopens for a full cooldown
+        breaker.record_failure(later);
+        assert_eq!(
+            breaker.state(),
+            BreakerState::Open {
+                until: later + Duration::from_secs(10)
+            }
+        );
+
+        assert!(breaker.allow(later + Duration::from_secs(10)));
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed { failures: 0 });
+    }
+}
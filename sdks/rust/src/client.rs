@@ -1,15 +1,46 @@
+use crate::circuit_breaker::CircuitBreaker;
 use crate::context::{RacewayContext, RACEWAY_CONTEXT};
+use crate::redaction::Redactor;
 use crate::trace_context::{
     build_propagation_headers, increment_clock_vector, parse_incoming_headers,
 };
 use crate::types::*;
-use axum::{extract::Request, http::HeaderMap, middleware::Next, response::Response};
+use crate::variable_filter::VariableFilter;
+use axum::{
+    extract::Request,
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use parking_lot::RwLock;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
 use std::process;
-use std::sync::Arc;
+use std::sync::{Arc, Once, OnceLock};
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+/// Returned by [`RacewayClient::track_db_query`]; pass it to
+/// [`RacewayClient::track_db_result`] to link the result to its query.
+#[derive(Debug, Clone)]
+#[must_use = "pass the token to track_db_result to record the query's result"]
+pub struct DbQueryToken {
+    /// None when the query wasn't recorded (no trace context, or not sampled)
+    query_event_id: Option<String>,
+}
+
+impl DbQueryToken {
+    /// ID of the recorded query event
+    pub fn event_id(&self) -> Option<&str> {
+        self.query_event_id.as_deref()
+    }
+}
 
 #[derive(Clone)]
 pub struct RacewayClient {
@@ -17,11 +48,75 @@ pub struct RacewayClient {
     service_name: String,
     module_name: String,
     instance_id: String,
+    monotonic_timing: bool,
+    variable_filter: Option<Arc<VariableFilter>>,
+    /// Scrubs event payloads before they are buffered, see `RacewayClientBuilder::redact_fields`
+    redactor: Option<Arc<Redactor>>,
+    /// Batches with a larger JSON body are sent gzipped
+    gzip_threshold: Option<usize>,
+    /// Fraction of traces recorded, see `RacewayClientBuilder::sample_rate`
+    sample_rate: f64,
     traces: Arc<RwLock<HashMap<String, TraceContext>>>,
     event_buffer: Arc<RwLock<Vec<Event>>>,
+    /// Set from `Retry-After` when the server rate limits us; flushes wait until then
+    retry_at: Arc<RwLock<Option<Instant>>>,
+    /// Opens after repeated failed sends so a down server isn't hit on every flush
+    breaker: Arc<parking_lot::Mutex<CircuitBreaker>>,
+    /// Unsent events kept while sends fail; the oldest are dropped beyond this
+    max_buffered_events: usize,
+    send_lock: Arc<tokio::sync::Mutex<()>>,
     http_client: reqwest::Client,
 }
 
+/// How long `shutdown` waits on its final flush before dropping what's left
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+thread_local! {
+    /// Where the last panic on this thread happened, set by the hook from `install_panic_hook`
+    static LAST_PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Chain a panic hook that remembers the panic location, since the payload
+/// caught by the middleware only carries the message
+fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if let Some(location) = info.location() {
+                LAST_PANIC_LOCATION.with(|cell| *cell.borrow_mut() = Some(location.to_string()));
+            }
+            previous(info);
+        }));
+    });
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "handler panicked".to_string()
+    }
+}
+
+/// Resolves to the panic payload if polling the inner future panics
+struct CatchPanic<F>(Pin<Box<F>>);
+
+impl<F: Future> Future for CatchPanic<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let inner = self.0.as_mut();
+        match std::panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
 impl RacewayClient {
     pub fn new(endpoint: &str, service_name: &str) -> Self {
         Self::new_with_api_key(endpoint, service_name, None)
@@ -37,8 +132,45 @@ impl RacewayClient {
         module_name: &str,
         api_key: Option<&str>,
     ) -> Self {
+        Self::from_config(
+            ClientConfig::new(endpoint, service_name)
+                .module(module_name)
+                .with_api_key(api_key.map(str::to_string)),
+        )
+    }
+
+    /// Start building a client with optional settings (module, API key, variable allowlist, ...).
+    pub fn builder(endpoint: &str, service_name: &str) -> RacewayClientBuilder {
+        RacewayClientBuilder {
+            config: ClientConfig::new(endpoint, service_name),
+        }
+    }
+
+    pub fn from_config(config: ClientConfig) -> Self {
+        let client = Self::build(config);
+
+        // Start auto-flush background task
+        let client_clone = client.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
+            loop {
+                interval.tick().await;
+                client_clone.background_flush().await;
+            }
+        });
+
+        client
+    }
+
+    /// A client with no auto-flush task, so tests decide when (and whether) events are sent
+    #[cfg(test)]
+    pub(crate) fn without_auto_flush(endpoint: &str, service_name: &str) -> Self {
+        Self::build(ClientConfig::new(endpoint, service_name))
+    }
+
+    fn build(config: ClientConfig) -> Self {
         let mut headers = reqwest::header::HeaderMap::new();
-        if let Some(key) = api_key {
+        if let Some(key) = config.api_key.as_deref() {
             let bearer = format!("Bearer {}", key.trim());
             if let Ok(value) = reqwest::header::HeaderValue::from_str(&bearer) {
                 headers.insert(reqwest::header::AUTHORIZATION, value);
@@ -48,42 +180,58 @@ impl RacewayClient {
             }
         }
 
-        let instance_id = resolve_instance_id();
+        let instance_id = config.instance_id.unwrap_or_else(resolve_instance_id);
 
-        let client = Self {
-            endpoint: endpoint.to_string(),
-            service_name: service_name.to_string(),
-            module_name: module_name.to_string(),
+        Self {
+            endpoint: config.endpoint,
+            service_name: config.service_name,
+            module_name: config.module_name,
             instance_id,
+            monotonic_timing: config.monotonic_timing,
+            variable_filter: config
+                .track_only
+                .map(|patterns| Arc::new(VariableFilter::new(patterns))),
+            redactor: Redactor::new(config.redact_fields, config.redact_fn).map(Arc::new),
+            gzip_threshold: config.gzip_threshold,
+            sample_rate: if config.sample_rate.is_nan() {
+                1.0
+            } else {
+                config.sample_rate.clamp(0.0, 1.0)
+            },
             traces: Arc::new(RwLock::new(HashMap::new())),
             event_buffer: Arc::new(RwLock::new(Vec::new())),
+            retry_at: Arc::new(RwLock::new(None)),
+            breaker: Arc::new(parking_lot::Mutex::new(CircuitBreaker::new(
+                config.breaker_failure_threshold,
+                config.breaker_cooldown,
+            ))),
+            max_buffered_events: config.max_buffered_events,
+            send_lock: Arc::new(tokio::sync::Mutex::new(())),
             http_client: reqwest::Client::builder()
                 .default_headers(headers)
                 .build()
                 .unwrap_or_else(|_| reqwest::Client::new()),
-        };
-
-        // Start auto-flush background task
-        let client_clone = client.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
-            loop {
-                interval.tick().await;
-                client_clone.flush().await;
-            }
-        });
-
-        client
+        }
     }
 
-    // Middleware to initialize trace context from headers
+    // Middleware to initialize trace context from headers.
+    // A panicking handler is recorded as an Error event in the request's trace
+    // and answered with a 500 instead of dropping the connection.
     pub async fn middleware(
         client: Arc<RacewayClient>,
         headers: HeaderMap,
         request: Request,
         next: Next,
     ) -> Response {
+        install_panic_hook();
+
         let parsed = parse_incoming_headers(&headers, &client.service_name, &client.instance_id);
+        if let (Some(error), false) = (&parsed.traceparent_error, parsed.distributed) {
+            eprintln!(
+                "[Raceway] Ignoring invalid traceparent header ({}); starting trace {}",
+                error, parsed.trace_id
+            );
+        }
 
         let mut ctx = RacewayContext::new(
             parsed.trace_id.clone(),
@@ -104,7 +252,18 @@ impl RacewayClient {
                 let uri = request.uri().to_string();
                 client.track_http_request(&method, &uri);
 
-                next.run(request).await
+                match CatchPanic(Box::pin(next.run(request))).await {
+                    Ok(response) => response,
+                    Err(payload) => {
+                        let location = LAST_PANIC_LOCATION.with(|cell| cell.borrow_mut().take());
+                        client.track_error(
+                            "panic",
+                            &panic_message(payload.as_ref()),
+                            location.into_iter().collect(),
+                        );
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
+                }
             })
             .await
     }
@@ -117,8 +276,16 @@ impl RacewayClient {
         new_value: T,
         access_type: &str,
     ) {
+        // Checked before anything is serialized so filtered variables cost nothing
+        if !self.is_tracked_variable(variable) {
+            return;
+        }
+
         RACEWAY_CONTEXT
             .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
                 let ctx = ctx_cell.borrow().clone();
                 let location = format!("{}:{}", file!(), line!());
 
@@ -162,6 +329,24 @@ impl RacewayClient {
             .ok();
     }
 
+    fn is_tracked_variable(&self, variable: &str) -> bool {
+        self.variable_filter
+            .as_ref()
+            .is_none_or(|filter| filter.matches(variable))
+    }
+
+    /// Whether events for `trace_id` are recorded. The decision depends only on
+    /// the trace id, so a trace is kept or dropped as a whole, and by every
+    /// service sampling at the same rate.
+    fn is_sampled(&self, trace_id: &str) -> bool {
+        if self.sample_rate >= 1.0 {
+            return true;
+        }
+        // Top 53 bits of the hash as a uniform fraction in [0, 1)
+        let fraction = (fnv1a(trace_id.as_bytes()) >> 11) as f64 / (1u64 << 53) as f64;
+        fraction < self.sample_rate
+    }
+
     /// Track a function call with automatic causality tracking.
     ///
     /// This method is **synchronous** - do not use `.await`.
@@ -206,6 +391,9 @@ impl RacewayClient {
     ) {
         RACEWAY_CONTEXT
             .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
                 let ctx = ctx_cell.borrow().clone();
 
                 let updated_vector =
@@ -275,6 +463,9 @@ impl RacewayClient {
     fn track_http_request(&self, method: &str, url: &str) {
         RACEWAY_CONTEXT
             .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
                 let ctx = ctx_cell.borrow().clone();
 
                 let updated_vector =
@@ -340,6 +531,9 @@ impl RacewayClient {
     pub fn track_http_response(&self, status: u16, duration_ms: u64) {
         RACEWAY_CONTEXT
             .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
                 let ctx = ctx_cell.borrow().clone();
 
                 // Convert duration from ms to ns for metadata
@@ -379,11 +573,232 @@ impl RacewayClient {
             .ok();
     }
 
+    /// Track a database query about to be sent, returning a token for its result.
+    ///
+    /// This method is **synchronous** - do not use `.await`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use raceway::RacewayClient;
+    /// use std::time::Instant;
+    ///
+    /// let client = RacewayClient::new("http://localhost:8080", "my-service");
+    /// let start = Instant::now();
+    /// let query = client.track_db_query("orders", "SELECT * FROM orders WHERE id = $1");
+    ///
+    /// // ... run the query ...
+    ///
+    /// client.track_db_result(query, 1, start.elapsed().as_millis() as u64);
+    /// ```
+    pub fn track_db_query(&self, database: &str, statement: &str) -> DbQueryToken {
+        let query_event_id = RACEWAY_CONTEXT
+            .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return None;
+                }
+                let ctx = ctx_cell.borrow().clone();
+
+                let updated_vector =
+                    increment_clock_vector(&ctx.clock_vector, &ctx.service_name, &ctx.instance_id);
+
+                let distributed_metadata = Some((
+                    ctx.instance_id.clone(),
+                    ctx.span_id.clone(),
+                    ctx.parent_span_id.clone(),
+                ));
+
+                // The duration is only known once the result arrives
+                let event_id = self.capture_event(
+                    &ctx.trace_id,
+                    ctx.parent_id.clone(),
+                    updated_vector.clone(),
+                    EventKind::DatabaseQuery(DatabaseQueryData {
+                        query: statement.to_string(),
+                        database: database.to_string(),
+                        duration_ms: 0,
+                    }),
+                    None,
+                    distributed_metadata,
+                );
+
+                // Update context
+                let mut ctx_mut = ctx_cell.borrow_mut();
+                if ctx_mut.root_id.is_none() {
+                    ctx_mut.root_id = Some(event_id.clone());
+                }
+                ctx_mut.parent_id = Some(event_id.clone());
+                ctx_mut.clock += 1;
+                ctx_mut.clock_vector = updated_vector;
+                Some(event_id)
+            })
+            .ok()
+            .flatten();
+
+        DbQueryToken { query_event_id }
+    }
+
+    /// Track the result of a query started with [`track_db_query`](Self::track_db_query).
+    ///
+    /// The result is recorded as the query's child even if other events were
+    /// tracked in between, so the server pairs them as a query-result edge and
+    /// the query's latency shows up on the critical path.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token `track_db_query` returned
+    /// * `row_count` - Rows returned or affected
+    /// * `duration_ms` - Query duration in milliseconds
+    pub fn track_db_result(&self, token: DbQueryToken, row_count: usize, duration_ms: u64) {
+        let Some(query_event_id) = token.query_event_id else {
+            return;
+        };
+
+        RACEWAY_CONTEXT
+            .try_with(|ctx_cell| {
+                let ctx = ctx_cell.borrow().clone();
+
+                let updated_vector =
+                    increment_clock_vector(&ctx.clock_vector, &ctx.service_name, &ctx.instance_id);
+
+                let distributed_metadata = Some((
+                    ctx.instance_id.clone(),
+                    ctx.span_id.clone(),
+                    ctx.parent_span_id.clone(),
+                ));
+
+                let event_id = self.capture_event(
+                    &ctx.trace_id,
+                    Some(query_event_id),
+                    updated_vector.clone(),
+                    EventKind::DatabaseResult(DatabaseResultData {
+                        rows_affected: row_count,
+                    }),
+                    Some(duration_ms * 1_000_000),
+                    distributed_metadata,
+                );
+
+                // Update context
+                let mut ctx_mut = ctx_cell.borrow_mut();
+                ctx_mut.parent_id = Some(event_id);
+                ctx_mut.clock += 1;
+                ctx_mut.clock_vector = updated_vector;
+            })
+            .ok();
+    }
+
+    /// Track an error in the current trace, e.g. a failure the handler recovered from.
+    /// The middleware uses it for handler panics, with the panic location as the
+    /// only stack frame.
+    pub fn track_error(&self, error_type: &str, message: &str, stack_trace: Vec<String>) {
+        RACEWAY_CONTEXT
+            .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
+                let ctx = ctx_cell.borrow().clone();
+
+                let updated_vector =
+                    increment_clock_vector(&ctx.clock_vector, &ctx.service_name, &ctx.instance_id);
+
+                let distributed_metadata = Some((
+                    ctx.instance_id.clone(),
+                    ctx.span_id.clone(),
+                    ctx.parent_span_id.clone(),
+                ));
+
+                let event_id = self.capture_event(
+                    &ctx.trace_id,
+                    ctx.parent_id.clone(),
+                    updated_vector.clone(),
+                    EventKind::Error(ErrorData {
+                        error_type: error_type.to_string(),
+                        message: message.to_string(),
+                        stack_trace,
+                    }),
+                    None,
+                    distributed_metadata,
+                );
+
+                // Update context
+                let mut ctx_mut = ctx_cell.borrow_mut();
+                ctx_mut.parent_id = Some(event_id);
+                ctx_mut.clock += 1;
+                ctx_mut.clock_vector = updated_vector;
+            })
+            .ok();
+    }
+
+    /// Declare that the current point synchronizes with `other_event_id` through a
+    /// mechanism Raceway can't observe (a file lock, an external queue). The server
+    /// orders that event before everything tracked after this call, suppressing
+    /// races between them.
+    pub fn track_synchronizes_with(&self, other_event_id: &str) {
+        RACEWAY_CONTEXT
+            .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
+                let ctx = ctx_cell.borrow().clone();
+
+                let updated_vector =
+                    increment_clock_vector(&ctx.clock_vector, &ctx.service_name, &ctx.instance_id);
+
+                let distributed_metadata = Some((
+                    ctx.instance_id.clone(),
+                    ctx.span_id.clone(),
+                    ctx.parent_span_id.clone(),
+                ));
+
+                let event_id = self.capture_event(
+                    &ctx.trace_id,
+                    ctx.parent_id.clone(),
+                    updated_vector.clone(),
+                    EventKind::Custom(crate::types::CustomData {
+                        name: "raceway.sync".to_string(),
+                        data: serde_json::json!({ "synchronizes_with": other_event_id }),
+                    }),
+                    None,
+                    distributed_metadata,
+                );
+
+                // Update context
+                let mut ctx_mut = ctx_cell.borrow_mut();
+                ctx_mut.parent_id = Some(event_id);
+                ctx_mut.clock += 1;
+                ctx_mut.clock_vector = updated_vector;
+            })
+            .ok();
+    }
+
+    /// Mark the current trace as a retry of `retry_of` (the original trace is attempt 1).
+    /// Events captured from now on carry the link, so the server can group attempts
+    /// under `GET /api/traces/:id/lineage`. Call it before tracking the retry's work.
+    pub fn mark_retry(&self, retry_of: &str, attempt: u32) {
+        RACEWAY_CONTEXT
+            .try_with(|ctx_cell| {
+                let trace_id = ctx_cell.borrow().trace_id.clone();
+                let mut traces = self.traces.write();
+                traces
+                    .entry(trace_id.clone())
+                    .or_insert_with(|| TraceContext {
+                        trace_id,
+                        events: Vec::new(),
+                        lineage: None,
+                    })
+                    .lineage = Some((retry_of.to_string(), attempt));
+            })
+            .ok();
+    }
+
     /// Track acquiring a lock.
     /// Location is automatically captured from the call site.
     pub fn track_lock_acquire(&self, lock_id: &str, lock_type: &str) {
         RACEWAY_CONTEXT
             .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
                 let ctx = ctx_cell.borrow().clone();
                 let location = format!("{}:{}", file!(), line!());
 
@@ -426,6 +841,9 @@ impl RacewayClient {
     pub fn track_lock_release(&self, lock_id: &str, lock_type: &str) {
         RACEWAY_CONTEXT
             .try_with(|ctx_cell| {
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return;
+                }
                 let ctx = ctx_cell.borrow().clone();
                 let location = format!("{}:{}", file!(), line!());
 
@@ -460,25 +878,106 @@ impl RacewayClient {
             .ok();
     }
 
-    pub fn propagation_headers(
+    /// Spawn a tokio task that inherits the current Raceway context.
+    ///
+    /// `task_local!` context does not cross `tokio::spawn`, so events recorded in a
+    /// plain spawned task are lost. This records an `AsyncSpawn` event (labelled with
+    /// the caller's location) and runs the future with a child context whose parent
+    /// is that event. Outside a traced request it behaves like `tokio::spawn`.
+    #[track_caller]
+    pub fn spawn<F>(&self, future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let caller = std::panic::Location::caller();
+        self.spawn_with_label(format!("{}:{}", caller.file(), caller.line()), future)
+    }
+
+    /// Like [`spawn`](Self::spawn), but labels the `AsyncSpawn` event with `task_name`.
+    pub fn spawn_tracked<F>(&self, task_name: &str, future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.spawn_with_label(task_name.to_string(), future)
+    }
+
+    fn spawn_with_label<F>(
         &self,
-        extra: Option<HashMap<String, String>>,
-    ) -> Result<HashMap<String, String>, String> {
-        RACEWAY_CONTEXT
+        spawned_by: String,
+        future: F,
+    ) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let child_ctx = RACEWAY_CONTEXT
             .try_with(|ctx_cell| {
-                let mut ctx = ctx_cell.borrow_mut();
-                let result = build_propagation_headers(
-                    &ctx.trace_id,
-                    &ctx.span_id,
-                    ctx.tracestate.as_deref(),
-                    &ctx.clock_vector,
-                    &ctx.service_name,
-                    &ctx.instance_id,
-                );
+                // Unsampled traces still propagate their context to the child
+                if !self.is_sampled(&ctx_cell.borrow().trace_id) {
+                    return ctx_cell.borrow().clone();
+                }
+                let ctx = ctx_cell.borrow().clone();
 
-                ctx.clock_vector = result.clock_vector.clone();
-                ctx.distributed = true;
-                ctx.parent_span_id = Some(ctx.span_id.clone());
+                let updated_vector =
+                    increment_clock_vector(&ctx.clock_vector, &ctx.service_name, &ctx.instance_id);
+
+                let distributed_metadata = Some((
+                    ctx.instance_id.clone(),
+                    ctx.span_id.clone(),
+                    ctx.parent_span_id.clone(),
+                ));
+
+                let event_id = self.capture_event(
+                    &ctx.trace_id,
+                    ctx.parent_id.clone(),
+                    updated_vector.clone(),
+                    EventKind::AsyncSpawn(AsyncSpawnData {
+                        task_id: uuid::Uuid::new_v4().to_string(),
+                        spawned_by,
+                    }),
+                    None,
+                    distributed_metadata,
+                );
+
+                // Both the spawner and the child continue from the spawn event
+                let mut ctx_mut = ctx_cell.borrow_mut();
+                if ctx_mut.root_id.is_none() {
+                    ctx_mut.root_id = Some(event_id.clone());
+                }
+                ctx_mut.parent_id = Some(event_id);
+                ctx_mut.clock += 1;
+                ctx_mut.clock_vector = updated_vector;
+                ctx_mut.clone()
+            })
+            .ok();
+
+        match child_ctx {
+            Some(ctx) => tokio::spawn(RACEWAY_CONTEXT.scope(RefCell::new(ctx), future)),
+            None => tokio::spawn(future),
+        }
+    }
+
+    pub fn propagation_headers(
+        &self,
+        extra: Option<HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>, String> {
+        RACEWAY_CONTEXT
+            .try_with(|ctx_cell| {
+                let mut ctx = ctx_cell.borrow_mut();
+                let result = build_propagation_headers(
+                    &ctx.trace_id,
+                    &ctx.span_id,
+                    ctx.tracestate.as_deref(),
+                    &ctx.clock_vector,
+                    &ctx.service_name,
+                    &ctx.instance_id,
+                );
+
+                ctx.clock_vector = result.clock_vector.clone();
+                ctx.distributed = true;
+                ctx.parent_span_id = Some(ctx.span_id.clone());
                 ctx.span_id = result.child_span_id.clone();
 
                 let mut headers_map = HashMap::new();
@@ -499,15 +998,30 @@ impl RacewayClient {
             .map_err(|_| "Raceway context is not active".to_string())?
     }
 
+    /// Attach propagation headers for the active trace to an outbound request.
+    /// Outside a Raceway context the request is returned unchanged.
+    pub fn traced_request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.propagation_headers(None) {
+            Ok(headers) => headers
+                .into_iter()
+                .fold(request, |request, (key, value)| request.header(key, value)),
+            Err(_) => request,
+        }
+    }
+
     fn capture_event(
         &self,
         trace_id: &str,
         parent_id: Option<String>,
         clock_vector: Vec<(String, u64)>,
-        kind: EventKind,
+        mut kind: EventKind,
         duration_ns: Option<u64>,
         distributed_metadata: Option<(String, String, Option<String>)>, // (instance_id, span_id, upstream_span_id)
     ) -> String {
+        if let Some(redactor) = &self.redactor {
+            redactor.redact_event(&mut kind);
+        }
+
         // Get or create trace
         let mut traces = self.traces.write();
 
@@ -518,6 +1032,7 @@ impl RacewayClient {
                 TraceContext {
                     trace_id: trace_id.to_string(),
                     events: Vec::new(),
+                    lineage: None,
                 },
             );
         }
@@ -530,6 +1045,10 @@ impl RacewayClient {
             } else {
                 (None, None, None)
             };
+        let (retry_of, attempt) = match &trace.lineage {
+            Some((retry_of, attempt)) => (Some(retry_of.clone()), Some(*attempt)),
+            None => (None, None),
+        };
 
         let event = Event {
             id: uuid::Uuid::new_v4().to_string(),
@@ -552,6 +1071,9 @@ impl RacewayClient {
                 instance_id,
                 distributed_span_id,
                 upstream_span_id,
+                monotonic_ns: self.monotonic_timing.then(monotonic_now_ns),
+                retry_of,
+                attempt,
             },
             causality_vector: clock_vector,
             lock_set: vec![],
@@ -561,62 +1083,333 @@ impl RacewayClient {
         event_id
     }
 
-    async fn flush(&self) {
-        // First, move events from all active traces to the buffer
-        {
-            let mut traces = self.traces.write();
-            let mut buffer = self.event_buffer.write();
+    /// Every captured event not yet flushed, across all traces
+    #[cfg(test)]
+    pub(crate) fn captured_events(&self) -> Vec<Event> {
+        self.traces
+            .read()
+            .values()
+            .flat_map(|trace| trace.events.iter().cloned())
+            .collect()
+    }
 
-            for (_trace_id, trace) in traces.iter_mut() {
-                if !trace.events.is_empty() {
-                    buffer.extend(trace.events.drain(..));
-                }
-            }
+    /// Send every buffered event now and resolve once the server has accepted
+    /// them (a 2xx from `/events`), with the number of events delivered.
+    ///
+    /// Waits for a send already in flight and for any `Retry-After` the server
+    /// set. Call it before a short-lived process or serverless handler returns.
+    /// A batch the server rejects with a 4xx is dropped. When the server is
+    /// unreachable, answers with a 5xx or rate limits, the events stay buffered
+    /// for the next flush, and while the circuit breaker is open this returns
+    /// an error right away without contacting the server.
+    pub async fn flush(&self) -> Result<usize, String> {
+        if let Some(delay) = self.retry_delay() {
+            tokio::time::sleep(delay).await;
         }
+        self.send_buffered(false).await
+    }
 
-        // Now flush the buffer
-        let events: Vec<Event> = self.event_buffer.write().drain(..).collect();
-        if events.is_empty() {
+    /// One auto-flush tick: sends unless the server asked us to back off or the breaker is open
+    async fn background_flush(&self) {
+        if self.retry_delay().is_some() || self.breaker_delay().is_some() {
+            self.buffer_trace_events();
             return;
         }
+        if let Err(e) = self.send_buffered(false).await {
+            eprintln!("[Raceway] {}", e);
+        }
+    }
+
+    /// Move events from all active traces to the send buffer
+    fn buffer_trace_events(&self) {
+        let mut traces = self.traces.write();
+        let mut buffer = self.event_buffer.write();
+
+        for (_trace_id, trace) in traces.iter_mut() {
+            if !trace.events.is_empty() {
+                buffer.extend(trace.events.drain(..));
+            }
+        }
+        self.enforce_buffer_cap(&mut buffer);
+    }
 
-        let payload = serde_json::json!({ "events": events });
-        match self
+    /// Drop the oldest buffered events beyond `max_buffered_events`
+    fn enforce_buffer_cap(&self, buffer: &mut Vec<Event>) {
+        if buffer.len() > self.max_buffered_events {
+            let excess = buffer.len() - self.max_buffered_events;
+            buffer.drain(..excess);
+            eprintln!(
+                "[Raceway] Buffer full; dropped {} oldest unsent events",
+                excess
+            );
+        }
+    }
+
+    /// Put an unsent batch back ahead of anything buffered since
+    fn requeue(&self, events: Vec<Event>) -> usize {
+        let mut buffer = self.event_buffer.write();
+        let newer = std::mem::replace(&mut *buffer, events);
+        buffer.extend(newer);
+        self.enforce_buffer_cap(&mut buffer);
+        buffer.len()
+    }
+
+    /// Record a failed send with the breaker and keep its batch for a later flush
+    fn send_failed(&self, events: Vec<Event>, error: String) -> Result<usize, String> {
+        self.breaker.lock().record_failure(Instant::now());
+        let buffered = self.requeue(events);
+        Err(format!("{}; {} events stay buffered", error, buffered))
+    }
+
+    /// `force` skips the circuit breaker check, for the final flush on shutdown
+    async fn send_buffered(&self, force: bool) -> Result<usize, String> {
+        // One send at a time, so a flush can't resolve while an earlier batch is in flight
+        let _sending = self.send_lock.lock().await;
+
+        self.buffer_trace_events();
+        if self.event_buffer.read().is_empty() {
+            return Ok(0);
+        }
+        if !force && !self.breaker.lock().allow(Instant::now()) {
+            return Err(format!(
+                "Circuit breaker open; holding {} buffered events for {:?}",
+                self.event_buffer.read().len(),
+                self.breaker_delay().unwrap_or_default()
+            ));
+        }
+
+        let events: Vec<Event> = self.event_buffer.write().drain(..).collect();
+        let count = events.len();
+
+        let payload = serde_json::to_vec(&serde_json::json!({ "events": events }))
+            .map_err(|e| format!("Error serializing events: {}", e))?;
+        let mut request = self
             .http_client
             .post(format!("{}/events", self.endpoint))
-            .json(&payload)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let status = response.status();
-                if !status.is_success() {
-                    let body = response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| "No body".to_string());
-                    eprintln!("[Raceway] Server returned {}: {}", status, body);
-                }
-            }
-            Err(e) => {
-                eprintln!("[Raceway] Error sending events: {}", e);
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        let body = match self.gzip_threshold {
+            Some(threshold) if payload.len() > threshold => {
+                request = request.header(reqwest::header::CONTENT_ENCODING, "gzip");
+                gzip(&payload).map_err(|e| format!("Error compressing events: {}", e))?
             }
+            _ => payload,
+        };
+        let response = match request.body(body).send().await {
+            Ok(response) => response,
+            Err(e) => return self.send_failed(events, format!("Error sending events: {}", e)),
+        };
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let delay = retry_after(response.headers());
+            *self.retry_at.write() = Some(Instant::now() + delay);
+
+            // A full capture buffer accepts the head of the batch and lists the
+            // rest under `failed`; resend only those so nothing is stored twice
+            let body = response.bytes().await.unwrap_or_default();
+            let events = match rejected_indices(&body) {
+                Some(rejected) => events
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| rejected.contains(index))
+                    .map(|(_, event)| event)
+                    .collect(),
+                None => events,
+            };
+            let buffered = self.requeue(events);
+            return Err(format!(
+                "Rate limited; retrying {} buffered events in {:?}",
+                buffered, delay
+            ));
+        }
+        if status.is_server_error() {
+            return self.send_failed(events, format!("Server returned {}", status));
+        }
+        // The server answered, so it's up even if it rejected this batch
+        self.breaker.lock().record_success();
+        if !status.is_success() {
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "No body".to_string());
+            return Err(format!("Server returned {}: {}", status, body));
         }
+
+        Ok(count)
+    }
+
+    /// Time left before the server's `Retry-After` allows another send
+    fn retry_delay(&self) -> Option<Duration> {
+        let retry_at = (*self.retry_at.read())?;
+        retry_at.checked_duration_since(Instant::now())
+    }
+
+    /// Time left before an open circuit breaker lets a probe send through
+    fn breaker_delay(&self) -> Option<Duration> {
+        self.breaker.lock().remaining(Instant::now())
     }
 
     /// Shutdown the client and flush all buffered events synchronously.
     /// This should be called before the application exits.
+    ///
+    /// Makes one final flush, even with the circuit breaker open, and gives up
+    /// after `SHUTDOWN_FLUSH_TIMEOUT` so a down server can't block exit.
     pub fn shutdown(&self) {
         // Use tokio's block_in_place to allow blocking in async context
         tokio::task::block_in_place(|| {
             let rt = tokio::runtime::Handle::current();
             rt.block_on(async {
-                self.flush().await;
+                let final_flush = async {
+                    if let Some(delay) = self.retry_delay() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    self.send_buffered(true).await
+                };
+                match tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, final_flush).await {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => eprintln!("[Raceway] {}", e),
+                    Err(_) => eprintln!(
+                        "[Raceway] Final flush timed out after {:?}; dropping buffered events",
+                        SHUTDOWN_FLUSH_TIMEOUT
+                    ),
+                }
             });
         });
     }
 }
 
+/// Builder for [`RacewayClient`], created with [`RacewayClient::builder`].
+pub struct RacewayClientBuilder {
+    config: ClientConfig,
+}
+
+impl RacewayClientBuilder {
+    pub fn module(mut self, module: &str) -> Self {
+        self.config = self.config.module(module);
+        self
+    }
+
+    pub fn api_key(mut self, key: &str) -> Self {
+        self.config = self.config.with_api_key(Some(key.to_string()));
+        self
+    }
+
+    pub fn instance_id(mut self, instance: &str) -> Self {
+        self.config = self.config.with_instance_id(instance);
+        self
+    }
+
+    pub fn monotonic_timing(mut self, enabled: bool) -> Self {
+        self.config = self.config.monotonic_timing(enabled);
+        self
+    }
+
+    /// Only record `track_state_change` calls for variables matching these patterns.
+    ///
+    /// Patterns support `*` (any run of characters) and `?` (one character), e.g.
+    /// `&["alice.balance", "inventory.*"]`. Non-matching variables are a cheap no-op.
+    pub fn track_only(mut self, patterns: &[&str]) -> Self {
+        self.config = self.config.track_only(patterns);
+        self
+    }
+
+    /// Gzip event batches whose JSON body is larger than `bytes`. The server
+    /// inflates them before ingesting; leave unset to always send plain JSON.
+    pub fn gzip_above(mut self, bytes: usize) -> Self {
+        self.config = self.config.gzip_above(bytes);
+        self
+    }
+
+    /// Record only this fraction of traces, from `0.0` (none) to `1.0` (all, the
+    /// default). Whether a trace is kept depends only on its trace id, so a kept
+    /// trace is recorded in full; track calls in dropped traces are no-ops.
+    pub fn sample_rate(mut self, rate: f64) -> Self {
+        self.config = self.config.sample_rate(rate);
+        self
+    }
+
+    /// Open the circuit breaker after `failures` consecutive failed flushes
+    /// (default 5). While open, flushes fail fast and events stay buffered;
+    /// after `cooldown` (default 30s) one probe send closes it again on success
+    /// or reopens it for another cooldown.
+    pub fn circuit_breaker(mut self, failures: u32, cooldown: Duration) -> Self {
+        self.config = self.config.circuit_breaker(failures, cooldown);
+        self
+    }
+
+    /// Keep at most `events` unsent events (default 10,000) while the server is
+    /// unreachable, dropping the oldest first.
+    pub fn max_buffered_events(mut self, events: usize) -> Self {
+        self.config = self.config.max_buffered_events(events);
+        self
+    }
+
+    /// Replace the value under any of these JSON keys with `"[REDACTED]"` before an
+    /// event is buffered. Keys match case-insensitively at any depth, through nested
+    /// objects and arrays, and also redact HTTP headers of the same name.
+    pub fn redact_fields(mut self, fields: &[&str]) -> Self {
+        self.config = self.config.redact_fields(fields);
+        self
+    }
+
+    /// Run `f` over each event's JSON payloads (function args, state values, HTTP
+    /// bodies, custom data) before it is buffered, after `redact_fields` is applied.
+    pub fn redact_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut serde_json::Value) + Send + Sync + 'static,
+    {
+        self.config = self.config.redact_fn(f);
+        self
+    }
+
+    pub fn build(self) -> RacewayClient {
+        RacewayClient::from_config(self.config)
+    }
+}
+
+/// Nanoseconds elapsed since this process's monotonic epoch (first call).
+/// Delay requested by a 429's `Retry-After` (delta-seconds form), defaulting to one second
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Duration {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(1))
+}
+
+/// Indices under `data.failed` in a 429 body, when the server accepted part of
+/// the batch. `None` means the whole batch was turned away (e.g. a rate limit).
+fn rejected_indices(body: &[u8]) -> Option<HashSet<usize>> {
+    let body: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let failed = body.get("data")?.get("failed")?.as_array()?;
+    failed
+        .iter()
+        .map(|entry| entry.get("index")?.as_u64().map(|index| index as usize))
+        .collect()
+}
+
+/// 64-bit FNV-1a, stable across processes and Rust versions (unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn gzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+fn monotonic_now_ns() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
 fn resolve_instance_id() -> String {
     if let Ok(explicit) = env::var("RACEWAY_INSTANCE_ID") {
         return explicit;
@@ -629,3 +1422,678 @@ fn resolve_instance_id() -> String {
     }
     format!("instance-{}", process::id())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded_variables(client: &RacewayClient) -> Vec<String> {
+        client
+            .traces
+            .read()
+            .values()
+            .flat_map(|trace| trace.events.iter())
+            .filter_map(|event| match &event.kind {
+                EventKind::StateChange(data) => Some(data.variable.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_track_only_filters_state_changes() {
+        let client = RacewayClient::builder("http://127.0.0.1:1", "test-service")
+            .track_only(&["alice.balance", "inventory.*"])
+            .build();
+
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client.track_state_change("bob.balance", Some(1), 2, "Write");
+                client.track_state_change("alice.balance", Some(100), 50, "Write");
+                client.track_state_change("inventory.widgets", None::<i32>, 3, "Read");
+            })
+            .await;
+
+        assert_eq!(
+            recorded_variables(&client),
+            vec!["alice.balance".to_string(), "inventory.widgets".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_traced_request_injects_headers_inside_context() {
+        let client = RacewayClient::without_auto_flush("http://127.0.0.1:1", "test-service");
+        let http = reqwest::Client::new();
+
+        let ctx = RacewayContext::new(
+            "0af76519-16cd-43dd-8448-eb211c80319c".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        let request = RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client
+                    .traced_request(http.get("http://downstream/reserve"))
+                    .build()
+                    .unwrap()
+            })
+            .await;
+
+        let traceparent = request.headers()["traceparent"].to_str().unwrap();
+        assert!(traceparent.contains("0af7651916cd43dd8448eb211c80319c"));
+        assert!(request.headers().contains_key("raceway-clock"));
+    }
+
+    #[tokio::test]
+    async fn test_traced_request_without_context_sends_no_headers() {
+        let client = RacewayClient::without_auto_flush("http://127.0.0.1:1", "test-service");
+        let http = reqwest::Client::new();
+
+        let request = client
+            .traced_request(http.get("http://downstream/reserve"))
+            .build()
+            .unwrap();
+
+        assert!(request.headers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_links_child_events_to_spawner() {
+        let client = RacewayClient::without_auto_flush("http://127.0.0.1:1", "test-service");
+
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                let child_client = client.clone();
+                client
+                    .spawn_tracked("worker", async move {
+                        child_client.track_state_change("counter", Some(0), 1, "Write");
+                    })
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        let traces = client.traces.read();
+        let events = &traces["trace-1"].events;
+        assert_eq!(events.len(), 2);
+
+        let spawn = &events[0];
+        assert!(matches!(
+            &spawn.kind,
+            EventKind::AsyncSpawn(data) if data.spawned_by == "worker"
+        ));
+
+        let child = &events[1];
+        assert!(matches!(child.kind, EventKind::StateChange(_)));
+        assert_eq!(child.parent_id.as_deref(), Some(spawn.id.as_str()));
+
+        // The child's clock continues from the spawn event's clock
+        let clock = |event: &Event| event.causality_vector[0].1;
+        assert!(clock(child) > clock(spawn));
+    }
+
+    #[tokio::test]
+    async fn test_db_result_links_to_its_query() {
+        let client = RacewayClient::without_auto_flush("http://127.0.0.1:1", "test-service");
+
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                let query = client.track_db_query("orders", "SELECT * FROM orders");
+                // Work tracked while the query runs doesn't break the pairing
+                client.track_state_change("cache.hits", Some(0), 1, "Write");
+                client.track_db_result(query, 3, 12);
+            })
+            .await;
+
+        let traces = client.traces.read();
+        let events = &traces["trace-1"].events;
+        assert_eq!(events.len(), 3);
+
+        let (query, result) = (&events[0], &events[2]);
+        assert!(matches!(
+            &query.kind,
+            EventKind::DatabaseQuery(data)
+                if data.database == "orders" && data.query == "SELECT * FROM orders"
+        ));
+        assert_eq!(result.parent_id.as_deref(), Some(query.id.as_str()));
+        assert_eq!(result.metadata.duration_ns, Some(12_000_000));
+
+        // The server infers a DatabaseQueryResult edge from a `DatabaseResult` child
+        let wire = serde_json::to_value(result).unwrap();
+        assert_eq!(wire["kind"]["DatabaseResult"]["rows_affected"], 3);
+        let wire = serde_json::to_value(query).unwrap();
+        assert_eq!(wire["kind"]["DatabaseQuery"]["database"], "orders");
+    }
+
+    #[tokio::test]
+    async fn test_db_query_without_context_records_nothing() {
+        let client = RacewayClient::without_auto_flush("http://127.0.0.1:1", "test-service");
+
+        let query = client.track_db_query("orders", "SELECT 1");
+        assert!(query.event_id().is_none());
+        client.track_db_result(query, 1, 1);
+
+        assert!(client.captured_events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_redact_fields_scrubs_nested_keys_before_buffering() {
+        let client = RacewayClient::builder("http://127.0.0.1:1", "test-service")
+            .redact_fields(&["password", "ssn"])
+            .build();
+
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client.track_function_call(
+                    "login",
+                    serde_json::json!({
+                        "user": {"name": "alice", "password": "hunter2"},
+                        "attempts": [{"ssn": "123-45-6789", "ok": false}],
+                    }),
+                );
+            })
+            .await;
+
+        let events = client.captured_events();
+        assert_eq!(events.len(), 1);
+        let EventKind::FunctionCall(data) = &events[0].kind else {
+            panic!("expected a function call, got {:?}", events[0].kind);
+        };
+        assert_eq!(
+            data.args,
+            serde_json::json!({
+                "user": {"name": "alice", "password": "[REDACTED]"},
+                "attempts": [{"ssn": "[REDACTED]", "ok": false}],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spawned_task_inherits_trace_and_causality() {
+        let client = RacewayClient::without_auto_flush("http://127.0.0.1:1", "test-service");
+
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client.track_state_change("balance", None::<i64>, 100, "Read");
+                let child_client = client.clone();
+                client
+                    .spawn_tracked("transfer", async move {
+                        child_client.track_state_change("balance", Some(100), 90, "Write");
+                    })
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        let traces = client.traces.read();
+        let events = &traces["trace-1"].events;
+        assert_eq!(events.len(), 3);
+        let (read, spawn, write) = (&events[0], &events[1], &events[2]);
+
+        assert!(events.iter().all(|event| event.trace_id == "trace-1"));
+        assert_eq!(spawn.parent_id.as_deref(), Some(read.id.as_str()));
+        assert_eq!(write.parent_id.as_deref(), Some(spawn.id.as_str()));
+
+        // Every component the spawner had seen is carried into the task, so the
+        // read happens-before the task's write
+        for (component, clock) in &read.causality_vector {
+            let inherited = write
+                .causality_vector
+                .iter()
+                .find(|(c, _)| c == component)
+                .map(|(_, v)| *v);
+            assert!(inherited.is_some_and(|v| v > *clock));
+        }
+    }
+
+    #[test]
+    fn test_retry_after_header_sets_backoff() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after(&headers), Duration::from_secs(1));
+
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            reqwest::header::HeaderValue::from_static("7"),
+        );
+        assert_eq!(retry_after(&headers), Duration::from_secs(7));
+    }
+
+    #[tokio::test]
+    async fn test_flush_resolves_after_server_received_events() {
+        let received = Arc::new(parking_lot::Mutex::new(Vec::<serde_json::Value>::new()));
+        let sink = received.clone();
+        let app = axum::Router::new().route(
+            "/events",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                let sink = sink.clone();
+                async move {
+                    sink.lock()
+                        .extend(body["events"].as_array().cloned().unwrap_or_default());
+                    axum::http::StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = RacewayClient::without_auto_flush(&format!("http://{}", addr), "test-service");
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client.track_state_change("balance", Some(100), 50, "Write");
+                client.track_state_change("balance", Some(50), 25, "Write");
+            })
+            .await;
+
+        let result = client.flush().await;
+
+        // Delivered before flush resolved (possibly partly by the auto-flush tick)
+        assert!(result.is_ok(), "flush failed: {:?}", result);
+        assert_eq!(received.lock().len(), 2);
+        assert_eq!(client.flush().await, Ok(0));
+    }
+
+    #[tokio::test]
+    async fn test_flush_gzips_batches_above_threshold() {
+        use std::io::Read;
+
+        let encodings = Arc::new(parking_lot::Mutex::new(Vec::<Option<String>>::new()));
+        let received = Arc::new(parking_lot::Mutex::new(0usize));
+        let (encodings_sink, received_sink) = (encodings.clone(), received.clone());
+        let app = axum::Router::new().route(
+            "/events",
+            axum::routing::post(
+                move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+                    let encoding = headers
+                        .get("content-encoding")
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string);
+                    let mut json = Vec::new();
+                    if encoding.as_deref() == Some("gzip") {
+                        flate2::read::GzDecoder::new(&body[..])
+                            .read_to_end(&mut json)
+                            .unwrap();
+                    } else {
+                        json = body.to_vec();
+                    }
+                    let batch: serde_json::Value = serde_json::from_slice(&json).unwrap();
+                    *received_sink.lock() += batch["events"].as_array().map_or(0, Vec::len);
+                    encodings_sink.lock().push(encoding);
+                    async { axum::http::StatusCode::OK }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = RacewayClient::builder(&format!("http://{}", addr), "test-service")
+            .gzip_above(1)
+            .build();
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client.track_state_change("balance", Some(100), 50, "Write");
+            })
+            .await;
+
+        assert_eq!(client.flush().await, Ok(1));
+        assert_eq!(*received.lock(), 1);
+        assert_eq!(encodings.lock().as_slice(), [Some("gzip".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_flush_reports_unreachable_server() {
+        let client = RacewayClient::without_auto_flush("http://127.0.0.1:1", "test-service");
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client.track_state_change("balance", Some(100), 50, "Write");
+            })
+            .await;
+
+        assert!(client.flush().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_resends_only_rejected_events() {
+        // First batch: the "buffer" takes two events and turns the rest away
+        let received = Arc::new(parking_lot::Mutex::new(Vec::<String>::new()));
+        let sink = received.clone();
+        let app = axum::Router::new().route(
+            "/events",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                let sink = sink.clone();
+                async move {
+                    let events = body["events"].as_array().cloned().unwrap_or_default();
+                    let mut sink = sink.lock();
+                    let capacity = if sink.is_empty() { 2 } else { events.len() };
+                    let failed: Vec<_> = (capacity..events.len())
+                        .map(|index| serde_json::json!({ "index": index }))
+                        .collect();
+                    sink.extend(
+                        events[..capacity]
+                            .iter()
+                            .map(|event| event["id"].as_str().unwrap().to_string()),
+                    );
+                    let status = if failed.is_empty() {
+                        axum::http::StatusCode::OK
+                    } else {
+                        axum::http::StatusCode::TOO_MANY_REQUESTS
+                    };
+                    let report = serde_json::json!({
+                        "success": failed.is_empty(),
+                        "data": { "ingested": capacity, "failed": failed },
+                    });
+                    (status, [("retry-after", "0")], axum::Json(report))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = RacewayClient::without_auto_flush(&format!("http://{}", addr), "test-service");
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                for balance in 0..5 {
+                    client.track_state_change("balance", None::<i32>, balance, "Write");
+                }
+            })
+            .await;
+
+        assert!(client.flush().await.is_err());
+        assert_eq!(client.flush().await, Ok(3));
+
+        let mut ids = received.lock().clone();
+        assert_eq!(ids.len(), 5);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 5);
+    }
+
+    async fn panicking_handler() -> &'static str {
+        panic!("ledger out of balance")
+    }
+
+    #[tokio::test]
+    async fn test_middleware_records_handler_panic_in_current_trace() {
+        let client = Arc::new(RacewayClient::without_auto_flush(
+            "http://127.0.0.1:1",
+            "test-service",
+        ));
+        let layer_client = client.clone();
+        let app = axum::Router::new()
+            .route("/boom", axum::routing::get(panicking_handler))
+            .layer(axum::middleware::from_fn(move |headers, request, next| {
+                RacewayClient::middleware(layer_client.clone(), headers, request, next)
+            }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let response = reqwest::Client::new()
+            .get(format!("http://{}/boom", addr))
+            .header(
+                "traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            )
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let errors: Vec<_> = client
+            .captured_events()
+            .into_iter()
+            .filter_map(|event| match event.kind {
+                EventKind::Error(data) => Some((event.trace_id, data)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(errors.len(), 1);
+        let (trace_id, error) = &errors[0];
+        assert_eq!(trace_id, "0af76519-16cd-43dd-8448-eb211c80319c");
+        assert_eq!(error.error_type, "panic");
+        assert_eq!(error.message, "ledger out of balance");
+        assert!(error.stack_trace[0].contains("client.rs"));
+    }
+
+    /// Track a call, a write and a lock round trip in `trace_id`
+    async fn track_some_events(client: &RacewayClient, trace_id: &str) {
+        let ctx = RacewayContext::new(
+            trace_id.to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                client.track_function_call("transfer", "alice");
+                client.track_state_change("balance", Some(100), 50, "Write");
+                client.track_lock_acquire("accounts", "Mutex");
+                client.track_lock_release("accounts", "Mutex");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_sample_rate_bounds_send_nothing_or_everything() {
+        let received = Arc::new(parking_lot::Mutex::new(0usize));
+        let received_sink = received.clone();
+        let app = axum::Router::new().route(
+            "/events",
+            axum::routing::post(move |axum::Json(batch): axum::Json<serde_json::Value>| {
+                *received_sink.lock() += batch["events"].as_array().map_or(0, Vec::len);
+                async { axum::http::StatusCode::OK }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let none = RacewayClient::builder(&endpoint, "test-service")
+            .sample_rate(0.0)
+            .build();
+        for i in 0..10 {
+            track_some_events(&none, &format!("trace-{}", i)).await;
+        }
+        assert_eq!(none.flush().await, Ok(0));
+        assert_eq!(*received.lock(), 0);
+
+        let all = RacewayClient::builder(&endpoint, "test-service")
+            .sample_rate(1.0)
+            .build();
+        for i in 0..10 {
+            track_some_events(&all, &format!("trace-{}", i)).await;
+        }
+        assert_eq!(all.flush().await, Ok(40));
+        assert_eq!(*received.lock(), 40);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_keeps_or_drops_whole_traces() {
+        let client = RacewayClient::builder("http://127.0.0.1:1", "test-service")
+            .sample_rate(0.5)
+            .build();
+        let trace_ids: Vec<String> = (0..50).map(|i| format!("trace-{}", i)).collect();
+        for trace_id in &trace_ids {
+            track_some_events(&client, trace_id).await;
+        }
+        // A second request in an already sampled trace is kept too
+        for trace_id in &trace_ids {
+            track_some_events(&client, trace_id).await;
+        }
+
+        let mut per_trace: HashMap<String, usize> = HashMap::new();
+        for event in client.captured_events() {
+            *per_trace.entry(event.trace_id).or_default() += 1;
+        }
+        assert!(per_trace.values().all(|&count| count == 8));
+        assert!(!per_trace.is_empty() && per_trace.len() < trace_ids.len());
+
+        // The decision is a function of the trace id alone
+        let other = RacewayClient::builder("http://127.0.0.1:1", "other-service")
+            .sample_rate(0.5)
+            .build();
+        for trace_id in &trace_ids {
+            assert_eq!(
+                other.is_sampled(trace_id),
+                per_trace.contains_key(trace_id),
+                "{}",
+                trace_id
+            );
+        }
+    }
+
+    async fn track_balance_writes(client: &RacewayClient, count: i64) {
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT
+            .scope(RefCell::new(ctx), async {
+                for i in 0..count {
+                    client.track_state_change(&format!("balance.{}", i), None::<i64>, i, "Write");
+                }
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_on_failing_endpoint_and_recovers() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let healthy = Arc::new(AtomicBool::new(false));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let received = Arc::new(AtomicUsize::new(0));
+        let (healthy_flag, request_count, received_count) =
+            (healthy.clone(), requests.clone(), received.clone());
+        let app = axum::Router::new().route(
+            "/events",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                request_count.fetch_add(1, Ordering::SeqCst);
+                let status = if healthy_flag.load(Ordering::SeqCst) {
+                    let events = body["events"].as_array().map_or(0, Vec::len);
+                    received_count.fetch_add(events, Ordering::SeqCst);
+                    axum::http::StatusCode::OK
+                } else {
+                    axum::http::StatusCode::SERVICE_UNAVAILABLE
+                };
+                async move { status }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = RacewayClient::builder(&format!("http://{}", addr), "test-service")
+            .circuit_breaker(2, Duration::from_millis(200))
+            .build();
+        track_balance_writes(&client, 3).await;
+
+        assert!(client.flush().await.is_err());
+        assert!(client.flush().await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Open: fails fast without contacting the server, keeping the events
+        let open = client.flush().await.unwrap_err();
+        assert!(open.contains("Circuit breaker open"), "{}", open);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(client.event_buffer.read().len(), 3);
+
+        // After the cooldown a probe goes out; the server is back, so everything is delivered
+        healthy.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(client.flush().await, Ok(3));
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            client.breaker.lock().state(),
+            crate::circuit_breaker::BreakerState::Closed { failures: 0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_drops_oldest_events_beyond_cap() {
+        let client = RacewayClient::builder("http://127.0.0.1:1", "test-service")
+            .circuit_breaker(1, Duration::from_secs(60))
+            .max_buffered_events(2)
+            .build();
+        track_balance_writes(&client, 4).await;
+
+        assert!(client.flush().await.is_err());
+        assert!(client.breaker_delay().is_some());
+
+        let variables: Vec<String> = client
+            .event_buffer
+            .read()
+            .iter()
+            .filter_map(|event| match &event.kind {
+                EventKind::StateChange(data) => Some(data.variable.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(variables, ["balance.2", "balance.3"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shutdown_gives_up_on_unresponsive_server() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = RacewayClient::without_auto_flush(&format!("http://{}", addr), "test-service");
+        track_balance_writes(&client, 1).await;
+
+        let started = Instant::now();
+        client.shutdown();
+        assert!(started.elapsed() < SHUTDOWN_FLUSH_TIMEOUT + Duration::from_secs(1));
+        drop(listener);
+    }
+}
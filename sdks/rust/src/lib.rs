@@ -10,6 +10,7 @@ Lightweight SDK for race condition detection in Rust applications with automatic
 - **Simplified Tracking API**: No .await needed for tracking methods
 - **Proper Causality Tracking**: Root event ID + logical clock vector for accurate race detection
 - **RAII Lock Tracking**: Automatic lock tracking with TrackedMutex and TrackedRwLock
+- **Sync Primitive Tracking**: Initialization and barrier ordering with TrackedOnce and TrackedBarrier
 
 ## Example
 
@@ -39,13 +40,21 @@ client.track_state_change("balance", Some(100), 50, "Write");
 ```
 */
 
+mod circuit_breaker;
 mod client;
 mod context;
 mod lock_helpers;
+mod redaction;
 mod trace_context;
 mod types;
+mod variable_filter;
 
-pub use client::RacewayClient;
+pub use client::{DbQueryToken, RacewayClient, RacewayClientBuilder};
 pub use context::{RacewayContext, RACEWAY_CONTEXT};
-pub use lock_helpers::{TrackedMutex, TrackedMutexGuard, TrackedRwLock, TrackedRwLockReadGuard, TrackedRwLockWriteGuard};
+pub use lock_helpers::{
+    TrackedBarrier, TrackedMutex, TrackedMutexGuard, TrackedOnce, TrackedRwLock,
+    TrackedRwLockReadGuard, TrackedRwLockWriteGuard, TrackedSemaphore, TrackedSemaphorePermit,
+};
+pub use redaction::RedactFn;
+pub use trace_context::{parse_traceparent, TraceContext};
 pub use types::*;
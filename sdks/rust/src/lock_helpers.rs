@@ -42,11 +42,55 @@ let rwlock = TrackedRwLock::new(100, raceway.clone(), "account_balance");
     *balance -= 50;
 }
 ```
+
+## Semaphore
+
+```rust,no_run
+use raceway::{RacewayClient, TrackedSemaphore};
+use std::sync::Arc;
+
+# async fn example() {
+let raceway = Arc::new(RacewayClient::new("http://localhost:8080", "my-service"));
+let pool = TrackedSemaphore::new(1, raceway.clone(), "db_pool");
+
+// Acquiring a permit is tracked as a lock acquire, dropping it as the release
+let permit = pool.acquire().await.unwrap();
+drop(permit);
+# }
+```
+
+## Once
+
+```rust,no_run
+use raceway::{RacewayClient, TrackedOnce};
+use std::sync::Arc;
+
+let raceway = Arc::new(RacewayClient::new("http://localhost:8080", "my-service"));
+let config = TrackedOnce::new(raceway.clone(), "app_config");
+
+// The first caller runs the initializer; every caller is tracked under the same lock
+let value = config.get_or_init(|| 42);
+```
+
+## Barrier
+
+```rust,no_run
+use raceway::{RacewayClient, TrackedBarrier};
+use std::sync::Arc;
+
+let raceway = Arc::new(RacewayClient::new("http://localhost:8080", "my-service"));
+let barrier = Arc::new(TrackedBarrier::new(2, raceway.clone(), "phase_1"));
+
+// Events after `wait` are ordered after every participant's arrival
+barrier.wait();
+```
 */
 
+use crate::context::RACEWAY_CONTEXT;
 use crate::RacewayClient;
 use std::ops::{Deref, DerefMut};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Barrier, BarrierWaitResult, Mutex, OnceLock, RwLock};
+use tokio::sync::{AcquireError, Semaphore, SemaphorePermit, TryAcquireError};
 
 /// A wrapper around `std::sync::Mutex` that automatically tracks lock operations.
 ///
@@ -215,3 +259,418 @@ impl<'a, T> DerefMut for TrackedRwLockWriteGuard<'a, T> {
         &mut *self.guard
     }
 }
+
+/// A wrapper around `tokio::sync::Semaphore` that automatically tracks permits.
+///
+/// Holding a permit is tracked as holding the lock `lock_id` with lock type
+/// `"Semaphore"`, so accesses made under a permit share that lock. With more
+/// than one permit, holders can run concurrently, so only a single-permit
+/// semaphore actually excludes the races this suppresses.
+pub struct TrackedSemaphore {
+    inner: Semaphore,
+    client: Arc<RacewayClient>,
+    lock_id: String,
+}
+
+impl TrackedSemaphore {
+    /// Create a new tracked semaphore.
+    ///
+    /// # Arguments
+    ///
+    /// * `permits` - Number of permits initially available
+    /// * `client` - The Raceway client for tracking
+    /// * `lock_id` - Unique identifier for this semaphore
+    pub fn new(permits: usize, client: Arc<RacewayClient>, lock_id: impl Into<String>) -> Self {
+        Self {
+            inner: Semaphore::new(permits),
+            client,
+            lock_id: lock_id.into(),
+        }
+    }
+
+    /// Wait for a permit, tracking the acquire once it is granted and the
+    /// release when the returned permit is dropped.
+    pub async fn acquire(&self) -> Result<TrackedSemaphorePermit<'_>, AcquireError> {
+        let permit = self.inner.acquire().await?;
+        Ok(self.track(permit))
+    }
+
+    /// Take a permit if one is available right now, tracking it like `acquire`.
+    pub fn try_acquire(&self) -> Result<TrackedSemaphorePermit<'_>, TryAcquireError> {
+        let permit = self.inner.try_acquire()?;
+        Ok(self.track(permit))
+    }
+
+    /// Number of permits currently available.
+    pub fn available_permits(&self) -> usize {
+        self.inner.available_permits()
+    }
+
+    fn track<'a>(&'a self, permit: SemaphorePermit<'a>) -> TrackedSemaphorePermit<'a> {
+        self.client.track_lock_acquire(&self.lock_id, "Semaphore");
+        TrackedSemaphorePermit {
+            _permit: permit,
+            client: self.client.clone(),
+            lock_id: self.lock_id.clone(),
+        }
+    }
+}
+
+/// RAII permit for a tracked semaphore.
+///
+/// When this permit is dropped, the lock release is automatically tracked
+/// and the permit is returned to the semaphore.
+pub struct TrackedSemaphorePermit<'a> {
+    _permit: SemaphorePermit<'a>,
+    client: Arc<RacewayClient>,
+    lock_id: String,
+}
+
+impl<'a> Drop for TrackedSemaphorePermit<'a> {
+    fn drop(&mut self) {
+        self.client.track_lock_release(&self.lock_id, "Semaphore");
+    }
+}
+
+/// A wrapper around `std::sync::OnceLock` that tracks initialization.
+///
+/// Every `get_or_init` call is bracketed by lock events on the once id, with a
+/// `Write` of the once id from the caller that ran the initializer and a `Read`
+/// from every other caller. Concurrent callers therefore share a lock and are
+/// not reported as racing, while unsynchronized access around the once still is.
+pub struct TrackedOnce<T> {
+    inner: OnceLock<T>,
+    client: Arc<RacewayClient>,
+    once_id: String,
+}
+
+impl<T> TrackedOnce<T> {
+    /// Create a new, uninitialized tracked once.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The Raceway client for tracking
+    /// * `once_id` - Unique identifier for this once
+    pub fn new(client: Arc<RacewayClient>, once_id: impl Into<String>) -> Self {
+        Self {
+            inner: OnceLock::new(),
+            client,
+            once_id: once_id.into(),
+        }
+    }
+
+    /// Get the value, running `init` if no caller has yet.
+    pub fn get_or_init<F>(&self, init: F) -> &T
+    where
+        F: FnOnce() -> T,
+    {
+        self.client.track_lock_acquire(&self.once_id, "Once");
+        let mut initialized = false;
+        let value = self.inner.get_or_init(|| {
+            initialized = true;
+            init()
+        });
+        if initialized {
+            self.client
+                .track_state_change(&self.once_id, None::<bool>, true, "Write");
+        } else {
+            self.client
+                .track_state_change(&self.once_id, Some(true), true, "Read");
+        }
+        self.client.track_lock_release(&self.once_id, "Once");
+        value
+    }
+
+    /// Get the value if initialized, without tracking.
+    pub fn get(&self) -> Option<&T> {
+        self.inner.get()
+    }
+}
+
+impl TrackedOnce<()> {
+    /// Run `f` exactly once across all callers, like `std::sync::Once::call_once`.
+    pub fn call_once<F>(&self, f: F)
+    where
+        F: FnOnce(),
+    {
+        self.get_or_init(f);
+    }
+}
+
+/// A wrapper around `std::sync::Barrier` that tracks synchronization.
+///
+/// Each participant records its arrival as a lock acquire on the barrier id.
+/// Once the barrier opens, it declares that it synchronizes with every other
+/// participant's arrival and then records the release, so everything tracked
+/// after `wait` is ordered after everything tracked before it on any thread.
+pub struct TrackedBarrier {
+    inner: Barrier,
+    participants: usize,
+    client: Arc<RacewayClient>,
+    barrier_id: String,
+    generations: Mutex<BarrierGenerations>,
+}
+
+/// Arrival event ids for the generation being filled and the last one to open.
+/// A generation can only be overwritten once every participant has arrived at
+/// the next one, by which time all of them have read it.
+#[derive(Default)]
+struct BarrierGenerations {
+    current: u64,
+    arrived: usize,
+    arrivals: Vec<String>,
+    opened: (u64, Vec<String>),
+}
+
+impl TrackedBarrier {
+    /// Create a new tracked barrier.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of participants that must call `wait`
+    /// * `client` - The Raceway client for tracking
+    /// * `barrier_id` - Unique identifier for this barrier
+    pub fn new(n: usize, client: Arc<RacewayClient>, barrier_id: impl Into<String>) -> Self {
+        Self {
+            inner: Barrier::new(n),
+            participants: n.max(1),
+            client,
+            barrier_id: barrier_id.into(),
+            generations: Mutex::new(BarrierGenerations::default()),
+        }
+    }
+
+    /// Block until all participants have called `wait`, tracking the arrival
+    /// and the happens-before edges the barrier establishes.
+    pub fn wait(&self) -> BarrierWaitResult {
+        self.client.track_lock_acquire(&self.barrier_id, "Barrier");
+        let arrival = current_event_id();
+
+        let generation = {
+            let mut generations = self.generations.lock().unwrap();
+            let generation = generations.current;
+            generations.arrived += 1;
+            if let Some(arrival) = arrival.clone() {
+                generations.arrivals.push(arrival);
+            }
+            if generations.arrived == self.participants {
+                let arrivals = std::mem::take(&mut generations.arrivals);
+                generations.opened = (generation, arrivals);
+                generations.arrived = 0;
+                generations.current += 1;
+            }
+            generation
+        };
+
+        let result = self.inner.wait();
+
+        let others: Vec<String> = {
+            let generations = self.generations.lock().unwrap();
+            debug_assert_eq!(generations.opened.0, generation);
+            generations
+                .opened
+                .1
+                .iter()
+                .filter(|id| Some(*id) != arrival.as_ref())
+                .cloned()
+                .collect()
+        };
+        for other in &others {
+            self.client.track_synchronizes_with(other);
+        }
+        self.client.track_lock_release(&self.barrier_id, "Barrier");
+        result
+    }
+}
+
+/// Id of the last event tracked in the current Raceway context
+fn current_event_id() -> Option<String> {
+    RACEWAY_CONTEXT
+        .try_with(|ctx_cell| ctx_cell.borrow().parent_id.clone())
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventKind, RacewayContext};
+    use std::cell::RefCell;
+
+    fn scoped<F: std::future::Future>(future: F) -> impl std::future::Future<Output = F::Output> {
+        let ctx = RacewayContext::new(
+            "trace-1".to_string(),
+            "test-service".to_string(),
+            "instance-1".to_string(),
+        );
+        RACEWAY_CONTEXT.scope(RefCell::new(ctx), future)
+    }
+
+    #[tokio::test]
+    async fn test_once_initialization_emits_events() {
+        let client = Arc::new(RacewayClient::without_auto_flush(
+            "http://127.0.0.1:1",
+            "test-service",
+        ));
+        let once = TrackedOnce::new(client.clone(), "config");
+
+        let value = scoped(async { *once.get_or_init(|| 42) }).await;
+        assert_eq!(value, 42);
+
+        let events = client.captured_events();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[0].kind,
+            EventKind::LockAcquire(data) if data.lock_id == "config" && data.lock_type == "Once"
+        ));
+        assert!(matches!(
+            &events[1].kind,
+            EventKind::StateChange(data) if data.variable == "config" && data.access_type == "Write"
+        ));
+        assert!(matches!(
+            &events[2].kind,
+            EventKind::LockRelease(data) if data.lock_id == "config"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_semaphore_permit_emits_acquire_and_release() {
+        let client = Arc::new(RacewayClient::without_auto_flush(
+            "http://127.0.0.1:1",
+            "test-service",
+        ));
+        let pool = TrackedSemaphore::new(1, client.clone(), "db_pool");
+
+        scoped(async {
+            let permit = pool.acquire().await.unwrap();
+            assert_eq!(pool.available_permits(), 0);
+            assert!(pool.try_acquire().is_err());
+            drop(permit);
+        })
+        .await;
+        assert_eq!(pool.available_permits(), 1);
+
+        let events = client.captured_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0].kind,
+            EventKind::LockAcquire(data) if data.lock_id == "db_pool" && data.lock_type == "Semaphore"
+        ));
+        assert!(matches!(
+            &events[1].kind,
+            EventKind::LockRelease(data) if data.lock_id == "db_pool" && data.lock_type == "Semaphore"
+        ));
+        assert_eq!(events[1].parent_id.as_deref(), Some(events[0].id.as_str()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_get_or_init_callers_share_the_once_lock() {
+        let client = Arc::new(RacewayClient::without_auto_flush(
+            "http://127.0.0.1:1",
+            "test-service",
+        ));
+        let once = Arc::new(TrackedOnce::new(client.clone(), "config"));
+
+        let callers: Vec<_> = (0..4)
+            .map(|_| {
+                let once = once.clone();
+                tokio::spawn(scoped(async move {
+                    *once.get_or_init(|| {
+                        std::thread::sleep(std::time::Duration::from_millis(10));
+                        42
+                    })
+                }))
+            })
+            .collect();
+        for caller in callers {
+            assert_eq!(caller.await.unwrap(), 42);
+        }
+
+        let events = client.captured_events();
+        let by_id = |id: &str| events.iter().find(|event| event.id == id);
+        let accesses: Vec<_> = events
+            .iter()
+            .filter_map(|event| match &event.kind {
+                EventKind::StateChange(data) => Some((event, data.access_type.as_str())),
+                _ => None,
+            })
+            .collect();
+
+        // Exactly one caller initialized; the rest read the initialized value
+        assert_eq!(accesses.len(), 4);
+        assert_eq!(
+            accesses
+                .iter()
+                .filter(|(_, access)| *access == "Write")
+                .count(),
+            1
+        );
+
+        // Every access happens while holding the once lock, so the server sees a
+        // common lock and does not report the callers as racing
+        for (access, _) in accesses {
+            let acquire = by_id(access.parent_id.as_deref().unwrap()).unwrap();
+            assert!(matches!(
+                &acquire.kind,
+                EventKind::LockAcquire(data) if data.lock_id == "config"
+            ));
+            let release = events
+                .iter()
+                .find(|event| event.parent_id.as_deref() == Some(access.id.as_str()))
+                .unwrap();
+            assert!(matches!(
+                &release.kind,
+                EventKind::LockRelease(data) if data.lock_id == "config"
+            ));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_barrier_orders_each_side_after_all_arrivals() {
+        let client = Arc::new(RacewayClient::without_auto_flush(
+            "http://127.0.0.1:1",
+            "test-service",
+        ));
+        let barrier = Arc::new(TrackedBarrier::new(2, client.clone(), "phase"));
+
+        let waiters: Vec<_> = (0..2)
+            .map(|_| {
+                let barrier = barrier.clone();
+                tokio::task::spawn_blocking(move || {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .build()
+                        .unwrap();
+                    runtime.block_on(scoped(async move {
+                        barrier.wait();
+                    }));
+                })
+            })
+            .collect();
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+
+        let events = client.captured_events();
+        let arrivals: Vec<&str> = events
+            .iter()
+            .filter(|event| {
+                matches!(&event.kind, EventKind::LockAcquire(data) if data.lock_type == "Barrier")
+            })
+            .map(|event| event.id.as_str())
+            .collect();
+        assert_eq!(arrivals.len(), 2);
+
+        // Each participant declares it synchronizes with the other's arrival
+        let synced: Vec<&str> = events
+            .iter()
+            .filter_map(|event| match &event.kind {
+                EventKind::Custom(data) if data.name == "raceway.sync" => {
+                    data.data["synchronizes_with"].as_str()
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(synced.len(), 2);
+        assert!(arrivals.iter().all(|arrival| synced.contains(arrival)));
+    }
+}
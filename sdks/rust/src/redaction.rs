@@ -0,0 +1,141 @@
+use crate::types::EventKind;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Written in place of every redacted value.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Caller-supplied hook that rewrites an event's JSON payloads before they are buffered.
+#[derive(Clone)]
+pub struct RedactFn(Arc<dyn Fn(&mut Value) + Send + Sync>);
+
+impl RedactFn {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&mut Value) + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for RedactFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RedactFn(..)")
+    }
+}
+
+/// Scrubs event payloads: values under any of `fields` (case-insensitive, at any depth)
+/// become `"[REDACTED]"`, then the custom hook, if any, runs over what's left.
+#[derive(Debug, Clone)]
+pub(crate) struct Redactor {
+    fields: Vec<String>,
+    custom: Option<RedactFn>,
+}
+
+impl Redactor {
+    /// `None` when there is nothing to redact, so capture skips the walk entirely
+    pub(crate) fn new(fields: Vec<String>, custom: Option<RedactFn>) -> Option<Self> {
+        if fields.is_empty() && custom.is_none() {
+            return None;
+        }
+        Some(Self { fields, custom })
+    }
+
+    pub(crate) fn redact_event(&self, kind: &mut EventKind) {
+        match kind {
+            EventKind::StateChange(data) => {
+                self.redact(&mut data.old_value);
+                self.redact(&mut data.new_value);
+            }
+            EventKind::FunctionCall(data) => self.redact(&mut data.args),
+            EventKind::HttpRequest(data) => {
+                self.redact_headers(&mut data.headers);
+                if let Some(body) = data.body.as_mut() {
+                    self.redact(body);
+                }
+            }
+            EventKind::HttpResponse(data) => {
+                self.redact_headers(&mut data.headers);
+                if let Some(body) = data.body.as_mut() {
+                    self.redact(body);
+                }
+            }
+            EventKind::Custom(data) => self.redact(&mut data.data),
+            _ => {}
+        }
+    }
+
+    fn redact(&self, value: &mut Value) {
+        if !self.fields.is_empty() {
+            redact_keys(value, &self.fields);
+        }
+        if let Some(custom) = &self.custom {
+            (custom.0)(value);
+        }
+    }
+
+    fn redact_headers(&self, headers: &mut HashMap<String, String>) {
+        for (name, value) in headers.iter_mut() {
+            if is_redacted(name, &self.fields) {
+                *value = REDACTED.to_string();
+            }
+        }
+    }
+}
+
+fn is_redacted(key: &str, fields: &[String]) -> bool {
+    fields.iter().any(|field| field.eq_ignore_ascii_case(key))
+}
+
+fn redact_keys(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_redacted(key, fields) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_keys(value, fields);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_keys(item, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redacts_matching_keys_at_any_depth() {
+        let mut value = json!({
+            "user": {"name": "alice", "Password": "hunter2"},
+            "cards": [{"ssn": "123-45-6789", "last4": "4242"}],
+            "password": {"nested": "whole subtree goes"},
+        });
+
+        redact_keys(&mut value, &["password".to_string(), "ssn".to_string()]);
+
+        assert_eq!(
+            value,
+            json!({
+                "user": {"name": "alice", "Password": "[REDACTED]"},
+                "cards": [{"ssn": "[REDACTED]", "last4": "4242"}],
+                "password": "[REDACTED]",
+            })
+        );
+    }
+
+    #[test]
+    fn test_no_fields_and_no_hook_disables_redaction() {
+        assert!(Redactor::new(Vec::new(), None).is_none());
+    }
+}
@@ -20,6 +20,18 @@ pub struct ParsedTraceContext {
     pub tracestate: Option<String>,
     pub clock_vector: Vec<(String, u64)>,
     pub distributed: bool,
+    /// Why an incoming `traceparent` header was rejected, if one was sent
+    pub traceparent_error: Option<String>,
+}
+
+/// A validated W3C `traceparent` header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// Trace id in UUID form, as Raceway stores it
+    pub trace_id: String,
+    /// Span id of the caller (`parent-id` in the W3C spec)
+    pub span_id: String,
+    pub flags: u8,
 }
 
 #[derive(Debug, Clone)]
@@ -38,15 +50,20 @@ pub fn parse_incoming_headers(
     let mut span_id: Option<String> = None;
     let mut parent_span_id: Option<String> = None;
     let mut distributed = false;
-
-    if let Some(raw) = headers
-        .get(TRACEPARENT_HEADER)
-        .and_then(|v| v.to_str().ok())
-    {
-        if let Some(parsed) = parse_traceparent(raw) {
-            trace_id = parsed.trace_id;
-            span_id = parsed.parent_span_id; // This is the span ID for THIS service
-            distributed = true;
+    let mut traceparent_error = None;
+
+    if let Some(raw) = headers.get(TRACEPARENT_HEADER) {
+        let parsed = raw
+            .to_str()
+            .map_err(|_| "traceparent is not valid ASCII".to_string())
+            .and_then(parse_traceparent);
+        match parsed {
+            Ok(parsed) => {
+                trace_id = parsed.trace_id;
+                span_id = Some(parsed.span_id); // This is the span ID for THIS service
+                distributed = true;
+            }
+            Err(e) => traceparent_error = Some(e),
         }
     }
 
@@ -88,6 +105,7 @@ pub fn parse_incoming_headers(
         tracestate,
         clock_vector,
         distributed,
+        traceparent_error,
     }
 }
 
@@ -162,30 +180,56 @@ pub fn increment_clock_vector(
     next
 }
 
-struct ParsedTraceparent {
-    trace_id: String,
-    parent_span_id: Option<String>,
-}
-
-fn parse_traceparent(value: &str) -> Option<ParsedTraceparent> {
+/// Parse and validate a W3C `traceparent` header of the form
+/// `00-<32 hex trace id>-<16 hex span id>-<2 hex flags>`.
+///
+/// Only version `00` is accepted. Hex must be lowercase, and all-zero trace
+/// or span ids are rejected, as the spec requires.
+pub fn parse_traceparent(value: &str) -> Result<TraceContext, String> {
     let parts: Vec<&str> = value.trim().split('-').collect();
-    if parts.len() != 4 {
-        return None;
+    let version = parts[0];
+    if !is_lower_hex(version, 2) {
+        return Err(format!("malformed traceparent version '{}'", version));
     }
-    let trace_id_hex = parts[1];
-    let span_id_hex = parts[2];
-    if trace_id_hex.len() != 32 || span_id_hex.len() != 16 {
-        return None;
+    if version != TRACEPARENT_VERSION {
+        return Err(format!("unsupported traceparent version '{}'", version));
     }
-    if hex::decode(trace_id_hex).is_err() || hex::decode(span_id_hex).is_err() {
-        return None;
+    let [_, trace_id_hex, span_id_hex, flags_hex] = parts[..] else {
+        return Err(format!(
+            "traceparent has {} fields, expected 4",
+            parts.len()
+        ));
+    };
+    if !is_lower_hex(trace_id_hex, 32) {
+        return Err("traceparent trace id must be 32 lowercase hex digits".to_string());
     }
-    Some(ParsedTraceparent {
+    if !is_lower_hex(span_id_hex, 16) {
+        return Err("traceparent span id must be 16 lowercase hex digits".to_string());
+    }
+    if !is_lower_hex(flags_hex, 2) {
+        return Err("traceparent flags must be 2 lowercase hex digits".to_string());
+    }
+    if trace_id_hex.bytes().all(|b| b == b'0') {
+        return Err("traceparent trace id is all zeros".to_string());
+    }
+    if span_id_hex.bytes().all(|b| b == b'0') {
+        return Err("traceparent span id is all zeros".to_string());
+    }
+
+    Ok(TraceContext {
         trace_id: traceparent_to_uuid(trace_id_hex),
-        parent_span_id: Some(span_id_hex.to_string()),
+        span_id: span_id_hex.to_string(),
+        flags: u8::from_str_radix(flags_hex, 16).unwrap_or_default(),
     })
 }
 
+fn is_lower_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
 struct ParsedClock {
     trace_id: Option<String>,
     span_id: Option<String>,
@@ -412,6 +456,55 @@ mod tests {
 
         assert!(!result.distributed);
         assert!(result.parent_span_id.is_none());
+        assert!(result.traceparent_error.is_some());
+    }
+
+    #[test]
+    fn test_parse_traceparent_accepts_valid_header() {
+        let parsed = parse_traceparent(VALID_TRACEPARENT).unwrap();
+
+        assert_eq!(parsed.trace_id, VALID_TRACE_ID);
+        assert_eq!(parsed.span_id, VALID_SPAN_ID);
+        assert_eq!(parsed.flags, 1);
+    }
+
+    #[test]
+    fn test_parse_traceparent_rejects_truncated_header() {
+        // Missing flags
+        assert!(parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331").is_err());
+        // Trace id one digit short
+        assert!(
+            parse_traceparent("00-0af7651916cd43dd8448eb211c80319-b7ad6b7169203331-01").is_err()
+        );
+        // Span id one digit short
+        assert!(
+            parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b716920333-01").is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_traceparent_rejects_wrong_version() {
+        for header in [
+            "01-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "0-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "zz-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+        ] {
+            assert!(parse_traceparent(header).is_err(), "accepted {}", header);
+        }
+    }
+
+    #[test]
+    fn test_parse_traceparent_rejects_uppercase_and_zero_ids() {
+        assert!(
+            parse_traceparent("00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01").is_err()
+        );
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-b7ad6b7169203331-01").is_err()
+        );
+        assert!(
+            parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01").is_err()
+        );
     }
 
     #[test]
@@ -1,5 +1,10 @@
+use crate::circuit_breaker::{
+    DEFAULT_COOLDOWN, DEFAULT_FAILURE_THRESHOLD, DEFAULT_MAX_BUFFERED_EVENTS,
+};
+use crate::redaction::RedactFn;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Metadata {
@@ -16,6 +21,14 @@ pub struct Metadata {
     pub distributed_span_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub upstream_span_id: Option<String>,
+    /// Nanoseconds since a per-process monotonic epoch (opt-in, see `ClientConfig::monotonic_timing`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monotonic_ns: Option<u64>,
+    /// Trace this one retries (see `RacewayClient::mark_retry`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempt: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,8 +37,13 @@ pub enum EventKind {
     FunctionCall(FunctionCallData),
     HttpRequest(HttpRequestData),
     HttpResponse(HttpResponseData),
+    DatabaseQuery(DatabaseQueryData),
+    DatabaseResult(DatabaseResultData),
     LockAcquire(LockAcquireData),
     LockRelease(LockReleaseData),
+    AsyncSpawn(AsyncSpawnData),
+    Error(ErrorData),
+    Custom(CustomData),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +80,37 @@ pub struct HttpResponseData {
     pub duration_ms: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseQueryData {
+    pub query: String,
+    pub database: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseResultData {
+    pub rows_affected: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsyncSpawnData {
+    pub task_id: String,
+    pub spawned_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomData {
+    pub name: String,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorData {
+    pub error_type: String,
+    pub message: String,
+    pub stack_trace: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockAcquireData {
     pub lock_id: String,
@@ -91,6 +140,8 @@ pub struct Event {
 pub struct TraceContext {
     pub trace_id: String,
     pub events: Vec<Event>,
+    /// (retry_of, attempt) when this trace retries an earlier one
+    pub lineage: Option<(String, u32)>,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +151,15 @@ pub struct ClientConfig {
     pub module_name: String,
     pub api_key: Option<String>,
     pub instance_id: Option<String>,
+    pub monotonic_timing: bool,
+    pub track_only: Option<Vec<String>>,
+    pub gzip_threshold: Option<usize>,
+    pub sample_rate: f64,
+    pub breaker_failure_threshold: u32,
+    pub breaker_cooldown: Duration,
+    pub max_buffered_events: usize,
+    pub redact_fields: Vec<String>,
+    pub redact_fn: Option<RedactFn>,
 }
 
 impl ClientConfig {
@@ -110,6 +170,15 @@ impl ClientConfig {
             module_name: "app".to_string(),
             api_key: None,
             instance_id: None,
+            monotonic_timing: false,
+            track_only: None,
+            gzip_threshold: None,
+            sample_rate: 1.0,
+            breaker_failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            breaker_cooldown: DEFAULT_COOLDOWN,
+            max_buffered_events: DEFAULT_MAX_BUFFERED_EVENTS,
+            redact_fields: Vec::new(),
+            redact_fn: None,
         }
     }
 
@@ -127,4 +196,57 @@ impl ClientConfig {
         self.instance_id = Some(instance.to_string());
         self
     }
+
+    /// Attach a high-resolution monotonic offset to every event so the server can
+    /// order tight in-process sequences without relying on wall-clock time.
+    pub fn monotonic_timing(mut self, enabled: bool) -> Self {
+        self.monotonic_timing = enabled;
+        self
+    }
+
+    /// Only record state changes for variables matching these patterns (`*` and `?` globs).
+    pub fn track_only(mut self, patterns: &[&str]) -> Self {
+        self.track_only = Some(patterns.iter().map(|p| p.to_string()).collect());
+        self
+    }
+
+    /// Gzip event batches whose JSON body is larger than `bytes`.
+    pub fn gzip_above(mut self, bytes: usize) -> Self {
+        self.gzip_threshold = Some(bytes);
+        self
+    }
+
+    /// Record only this fraction of traces (`0.0` to `1.0`), chosen per trace id.
+    pub fn sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = rate;
+        self
+    }
+
+    /// Stop sending for `cooldown` after `failures` consecutive failed flushes.
+    pub fn circuit_breaker(mut self, failures: u32, cooldown: Duration) -> Self {
+        self.breaker_failure_threshold = failures;
+        self.breaker_cooldown = cooldown;
+        self
+    }
+
+    /// Keep at most this many unsent events while sends fail, dropping the oldest.
+    pub fn max_buffered_events(mut self, events: usize) -> Self {
+        self.max_buffered_events = events;
+        self
+    }
+
+    /// Replace values under these JSON keys (case-insensitive, at any depth) with `"[REDACTED]"`.
+    pub fn redact_fields(mut self, fields: &[&str]) -> Self {
+        self.redact_fields = fields.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    /// Run `f` over every event's JSON payloads before it is buffered.
+    pub fn redact_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut serde_json::Value) + Send + Sync + 'static,
+    {
+        self.redact_fn = Some(RedactFn::new(f));
+        self
+    }
 }
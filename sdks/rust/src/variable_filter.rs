@@ -0,0 +1,70 @@
+/// Allowlist of variable names (with `*` / `?` globs) that `track_state_change` records.
+#[derive(Debug, Clone)]
+pub(crate) struct VariableFilter {
+    patterns: Vec<String>,
+}
+
+impl VariableFilter {
+    pub(crate) fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub(crate) fn matches(&self, variable: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), variable.as_bytes()))
+    }
+}
+
+/// Iterative glob matcher: `*` matches any run of characters, `?` matches exactly one.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Backtrack: let the last `*` absorb one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    while p < pattern.len() && pattern[p] == b'*' {
+        p += 1;
+    }
+    p == pattern.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_and_glob_patterns() {
+        let filter = VariableFilter::new(["alice.balance", "inventory.*", "user?.id"]);
+
+        assert!(filter.matches("alice.balance"));
+        assert!(!filter.matches("alice.balance.history"));
+        assert!(filter.matches("inventory.widgets"));
+        assert!(filter.matches("inventory."));
+        assert!(!filter.matches("inventory"));
+        assert!(filter.matches("user1.id"));
+        assert!(!filter.matches("user12.id"));
+        assert!(!filter.matches("bob.balance"));
+    }
+}
@@ -17,6 +17,7 @@ export interface TraceMetadata {
   last_timestamp: string;
   service_count: number;
   services: string[];
+  truncated?: boolean;
 }
 
 export interface TraceResponse {
@@ -63,6 +64,8 @@ export interface FullTraceAnalysisData {
     potential_races: number;
     anomalies: string[];
     race_details: RaceDetail[];
+    double_checked_locking?: DoubleCheckedLocking[];
+    unreleased_locks?: UnreleasedLock[];
   };
   critical_path: CriticalPathData | null;
   anomalies: DetectedAnomaly[];
@@ -101,6 +104,35 @@ export interface RaceDetail {
   description: string;
 }
 
+export interface DoubleCheckedLocking {
+  variable: string;
+  thread_id: string;
+  lock_id: string;
+  unlocked_read: string;
+  locked_read: string;
+  location: string;
+  severity: 'Minor' | 'Warning' | 'Critical';
+  description: string;
+}
+
+export interface UnreleasedLock {
+  imbalance: 'unreleased_acquire' | 'unmatched_release';
+  thread_id: string;
+  lock_id: string;
+  acquires: number;
+  releases: number;
+  orphaned_acquire: string | null;
+  orphaned_at: string | null;
+  unmatched_release: string | null;
+  location: string | null;
+  stale_windows: StaleLockWindow[];
+}
+
+export interface StaleLockWindow {
+  from: string;
+  until: string | null;
+}
+
 export interface CriticalPathData {
   trace_id: string;
   path_events: number;
@@ -134,6 +166,7 @@ export interface DetectedAnomaly {
   description: string;
   location: string;
   timestamp: string;
+  service_name?: string;
 }
 
 // Dependencies response types
@@ -182,6 +215,7 @@ export interface VariableAccess {
   location: string;
   has_causal_link_to_previous: boolean;
   is_race: boolean;
+  is_lost_update?: boolean;
 }
 
 // Global analysis response types (extends AnalysisResponse)